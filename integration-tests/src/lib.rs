@@ -0,0 +1,12 @@
+//! # Integration tests
+//!
+//! The scenarios run the payment, dispute resolution, resolvers and identities pallets together
+//! on a shared runtime, so the regressions spanning several pallets are caught end to end rather
+//! than by the narrow mock of each pallet. The property tests run random sequences of extrinsics
+//! against the payment and dispute state machines and check their invariants after every step.
+
+#![cfg(test)]
+
+mod mock;
+mod properties;
+mod scenarios;
@@ -0,0 +1,296 @@
+//! Property tests running random sequences of extrinsics against the payment and dispute state
+//! machines. The failed calls are rolled back, and the invariants are checked after every step:
+//!
+//! - The reserved balance of each party equals the fees it escrowed for the disputes in flight.
+//! - The escrow account holds the amounts of the payments in flight.
+//! - No value is created or lost, the fees are only moved between the accounts of the runtime.
+//! - No payment is left pending or fulfilled past its deadline once the queues are evaluated.
+
+use crate::{
+	mock::{
+		AccountId, Balance, Currencies, CurrencyId, DisputeResolution, ExistentialDeposit,
+		ExtBuilder, Hash, Moment, Origin, RandomnessCollectiveFlip, ResolversNetwork, System,
+		Timestamp, TreasuryAccount, ALICE, BOB, FULFILLED_WAITING_TIME, LRP,
+		PENDING_PAYMENT_WAITING_TIME, RESOLVER_1, RESOLVER_2, RESOLVER_3, RESOLVER_4,
+	},
+	scenarios::{join_resolvers_network, INIT_TIMESTAMP},
+};
+use dispute_resolution::{DisputeStatus, Judgment};
+use frame_support::{
+	assert_ok,
+	storage::{with_transaction, TransactionOutcome},
+	traits::{Get, Hooks},
+};
+use orml_traits::{MultiCurrency, MultiReservableCurrency};
+use pallet_lrp::{PaymentDescription, PaymentStatus};
+use proptest::prelude::*;
+use sp_runtime::DispatchResult;
+
+const ACCOUNTS: [AccountId; 6] = [ALICE, BOB, RESOLVER_1, RESOLVER_2, RESOLVER_3, RESOLVER_4];
+
+#[derive(Clone, Debug)]
+enum Action {
+	CreatePayment(Balance),
+	AcceptPayment(usize),
+	FulfillPayment(usize),
+	CompletePayment(usize),
+	CancelPayment(usize),
+	CreateDispute(usize),
+	FightDispute(usize),
+	EscalateDispute(usize),
+	ProposeOutcome(usize, Judgment),
+	ExpireAssignments(usize),
+	FinalizeDispute(usize),
+	AdvanceTime(Moment),
+}
+
+fn judgment() -> impl Strategy<Value = Judgment> {
+	prop_oneof![Just(Judgment::ReleaseFundToPayer), Just(Judgment::ReleaseFundToPayee)]
+}
+
+fn action() -> impl Strategy<Value = Action> {
+	prop_oneof![
+		(1..=200u128).prop_map(Action::CreatePayment),
+		any::<usize>().prop_map(Action::AcceptPayment),
+		any::<usize>().prop_map(Action::FulfillPayment),
+		any::<usize>().prop_map(Action::CompletePayment),
+		any::<usize>().prop_map(Action::CancelPayment),
+		any::<usize>().prop_map(Action::CreateDispute),
+		any::<usize>().prop_map(Action::FightDispute),
+		any::<usize>().prop_map(Action::EscalateDispute),
+		(any::<usize>(), judgment())
+			.prop_map(|(index, judgment)| Action::ProposeOutcome(index, judgment)),
+		any::<usize>().prop_map(Action::ExpireAssignments),
+		any::<usize>().prop_map(Action::FinalizeDispute),
+		prop_oneof![
+			1_000..20_000u64,
+			Just(PENDING_PAYMENT_WAITING_TIME),
+			Just(FULFILLED_WAITING_TIME)
+		]
+		.prop_map(Action::AdvanceTime),
+	]
+}
+
+// Dispatch the call in a storage transaction, so a failed call leaves no partial change.
+fn dispatch(call: impl FnOnce() -> DispatchResult) {
+	let _ = with_transaction(|| {
+		let result = call();
+		if result.is_ok() {
+			TransactionOutcome::Commit(result)
+		} else {
+			TransactionOutcome::Rollback(result)
+		}
+	});
+}
+
+fn payment_at(index: usize) -> Option<Hash> {
+	let payments = LRP::payments_owned(&ALICE);
+	if payments.is_empty() {
+		return None
+	}
+	Some(payments[index % payments.len()])
+}
+
+fn advance_time(duration: Moment) {
+	let block_number = System::block_number() + 1;
+	System::set_block_number(block_number);
+	Timestamp::set_timestamp(Timestamp::get() + duration);
+	RandomnessCollectiveFlip::on_initialize(block_number);
+	DisputeResolution::on_initialize(block_number);
+	LRP::offchain_worker(block_number);
+	ResolversNetwork::offchain_worker(block_number);
+	DisputeResolution::offchain_worker(block_number);
+}
+
+fn execute(action: Action) {
+	if let Action::CreatePayment(amount) = action {
+		return dispatch(|| {
+			LRP::create_payment(
+				Origin::signed(ALICE),
+				BOB,
+				amount,
+				CurrencyId::Native,
+				PaymentDescription::Plain("".into()),
+				"".into(),
+				None,
+			)
+		})
+	}
+	if let Action::AdvanceTime(duration) = action {
+		return advance_time(duration)
+	}
+
+	let index = match action {
+		Action::AcceptPayment(index) |
+		Action::FulfillPayment(index) |
+		Action::CompletePayment(index) |
+		Action::CancelPayment(index) |
+		Action::CreateDispute(index) |
+		Action::FightDispute(index) |
+		Action::EscalateDispute(index) |
+		Action::ProposeOutcome(index, _) |
+		Action::ExpireAssignments(index) |
+		Action::FinalizeDispute(index) => index,
+		_ => return,
+	};
+	let payment_hash = match payment_at(index) {
+		Some(payment_hash) => payment_hash,
+		None => return,
+	};
+
+	match action {
+		Action::AcceptPayment(_) =>
+			dispatch(|| LRP::accept_payment(Origin::signed(BOB), payment_hash)),
+		Action::FulfillPayment(_) =>
+			dispatch(|| LRP::fulfill_payment(Origin::signed(BOB), payment_hash)),
+		Action::CompletePayment(_) =>
+			dispatch(|| LRP::complete_payment(Origin::signed(ALICE), payment_hash)),
+		Action::CancelPayment(_) =>
+			for party in [ALICE, BOB] {
+				dispatch(|| LRP::cancel_payment(Origin::signed(party), payment_hash));
+			},
+		Action::CreateDispute(_) => dispatch(|| {
+			DisputeResolution::create_dispute(Origin::signed(ALICE), payment_hash, "".into())
+		}),
+		Action::FightDispute(_) => dispatch(|| {
+			DisputeResolution::fight_dispute(Origin::signed(BOB), payment_hash, "".into())
+		}),
+		Action::EscalateDispute(_) =>
+			for party in [ALICE, BOB] {
+				dispatch(|| {
+					DisputeResolution::escalate_dispute(Origin::signed(party), payment_hash)
+				});
+			},
+		Action::ProposeOutcome(_, judgment) => {
+			let dispute = match DisputeResolution::disputes(&payment_hash) {
+				Some(dispute) => dispute,
+				None => return,
+			};
+			// The first resolver of the panel yet to judge accepts the assignment and judges.
+			let resolver = dispute
+				.resolvers
+				.iter()
+				.find(|resolver| !dispute.judgments.iter().any(|(judged, _)| judged == *resolver));
+			if let Some(resolver) = resolver {
+				dispatch(|| {
+					DisputeResolution::accept_assignment(Origin::signed(*resolver), payment_hash)
+				});
+				dispatch(|| {
+					DisputeResolution::propose_outcome(
+						Origin::signed(*resolver),
+						payment_hash,
+						judgment,
+						None,
+					)
+				});
+			}
+		},
+		Action::ExpireAssignments(_) => dispatch(|| {
+			DisputeResolution::expire_assignments(Origin::signed(ALICE), payment_hash)
+				.map(|_| ())
+				.map_err(|err| err.error)
+		}),
+		Action::FinalizeDispute(_) => dispatch(|| {
+			DisputeResolution::finalize_dispute(Origin::signed(BOB), payment_hash)
+				.map(|_| ())
+				.map_err(|err| err.error)
+		}),
+		_ => (),
+	}
+}
+
+fn total_balance(account: &AccountId) -> Balance {
+	Currencies::total_balance(CurrencyId::Native, account)
+}
+
+fn holders() -> Vec<AccountId> {
+	let mut holders = ACCOUNTS.to_vec();
+	holders.extend([
+		LRP::escrow_account(),
+		DisputeResolution::insurance_fund_account(),
+		TreasuryAccount::get(),
+	]);
+	holders
+}
+
+fn check_invariants(total_issuance: Balance, time_advanced: bool) {
+	let now = Timestamp::get();
+	let mut payer_fees = 0;
+	let mut payee_fees = 0;
+	let mut in_flight = 0;
+
+	for payment_hash in LRP::payments_owned(&ALICE) {
+		let payment = LRP::payments(&payment_hash).unwrap();
+		if matches!(
+			payment.status,
+			PaymentStatus::Pending |
+				PaymentStatus::Accepted |
+				PaymentStatus::Fulfilled |
+				PaymentStatus::PartiallyFulfilled(_) |
+				PaymentStatus::Disputed
+		) {
+			in_flight += payment.amount;
+		}
+
+		if let Some(dispute) = DisputeResolution::disputes(&payment_hash) {
+			if dispute.status != DisputeStatus::Resolved {
+				payer_fees += dispute.payer_fee;
+				payee_fees += dispute.payee_fee;
+			}
+		}
+
+		// The queues are evaluated once the time is advanced.
+		if time_advanced {
+			let deadline = match payment.status {
+				PaymentStatus::Pending => Some(payment.updated_at + PENDING_PAYMENT_WAITING_TIME),
+				PaymentStatus::Fulfilled | PaymentStatus::PartiallyFulfilled(_) => Some(
+					LRP::payment_holds(&payment_hash)
+						.unwrap_or_default()
+						.max(payment.updated_at + FULFILLED_WAITING_TIME),
+				),
+				_ => None,
+			};
+			if let Some(deadline) = deadline {
+				assert!(
+					deadline > now,
+					"payment {:?} is stuck in {:?}",
+					payment_hash,
+					payment.status
+				);
+			}
+		}
+	}
+
+	assert_eq!(Currencies::reserved_balance(CurrencyId::Native, &ALICE), payer_fees);
+	assert_eq!(Currencies::reserved_balance(CurrencyId::Native, &BOB), payee_fees);
+	assert_eq!(
+		Currencies::free_balance(CurrencyId::Native, &LRP::escrow_account()),
+		ExistentialDeposit::get() + in_flight
+	);
+	assert_eq!(holders().iter().map(total_balance).sum::<Balance>(), total_issuance);
+}
+
+proptest! {
+	#![proptest_config(ProptestConfig::with_cases(32))]
+
+	#[test]
+	fn payment_and_dispute_invariants_hold(actions in prop::collection::vec(action(), 1..40)) {
+		ExtBuilder::default().build().execute_with(|| {
+			System::set_block_number(1);
+			Timestamp::set_timestamp(INIT_TIMESTAMP);
+			for account in ACCOUNTS {
+				assert_ok!(Currencies::deposit(CurrencyId::Native, &account, 1_000_000));
+			}
+			join_resolvers_network(&[RESOLVER_1, RESOLVER_2, RESOLVER_3, RESOLVER_4]);
+
+			let total_issuance = holders().iter().map(total_balance).sum::<Balance>();
+			check_invariants(total_issuance, false);
+
+			for action in actions {
+				let time_advanced = matches!(action, Action::AdvanceTime(_));
+				execute(action);
+				check_invariants(total_issuance, time_advanced);
+			}
+		});
+	}
+}
@@ -0,0 +1,223 @@
+use crate::mock::{
+	AccountId, Currencies, CurrencyId, DisputeResolution, Event, ExtBuilder, Hash, Identities,
+	Origin, ResolversNetwork, Runtime, System, Timestamp, ALICE, ASSIGNMENT_JUDGING_TIME, BOB,
+	DISPUTE_FEE, LRP, RESOLVER_1, RESOLVER_2, RESOLVER_3, RESOLVER_4, RESOLVER_BOND,
+};
+use dispute_resolution::{DisputeStatus, Event as DisputeEvent, Judgment};
+use frame_support::{assert_noop, assert_ok};
+use orml_traits::{MultiCurrency, MultiReservableCurrency};
+use pallet_identities::{DisputeRecord, IdentityType};
+use pallet_lrp::{PaymentDescription, PaymentStatus};
+use sp_runtime::Permill;
+
+pub const INIT_TIMESTAMP: u64 = 1_000;
+
+pub(crate) fn join_resolvers_network(resolvers: &[AccountId]) {
+	for resolver in resolvers {
+		assert_ok!(Identities::create_identity(
+			Origin::signed(*resolver),
+			"Resolver".into(),
+			IdentityType::Individual,
+			[].into(),
+		));
+		assert_ok!(ResolversNetwork::join_resolvers_network(
+			Origin::signed(*resolver),
+			"".into(),
+			1100,
+			[0; 32],
+		));
+	}
+}
+
+// Create a payment from Alice to Bob which is accepted and fulfilled by Bob.
+fn fulfilled_payment(amount: u128) -> Hash {
+	assert_ok!(LRP::create_payment(
+		Origin::signed(ALICE),
+		BOB,
+		amount,
+		CurrencyId::Native,
+		PaymentDescription::Plain("".into()),
+		"".into(),
+		None,
+	));
+	let payment_hash = *LRP::payments_owned(&ALICE).last().unwrap();
+	assert_ok!(LRP::accept_payment(Origin::signed(BOB), payment_hash));
+	assert_ok!(LRP::add_delivery_proof(Origin::signed(BOB), payment_hash, "proof".into()));
+	assert_ok!(LRP::fulfill_payment(Origin::signed(BOB), payment_hash));
+	assert_eq!(LRP::payments(payment_hash).unwrap().status, PaymentStatus::Fulfilled);
+	payment_hash
+}
+
+fn has_event(event: DisputeEvent<Runtime>) -> bool {
+	System::events()
+		.iter()
+		.any(|record| record.event == Event::DisputeResolution(event.clone()))
+}
+
+#[test]
+fn fulfilled_payment_is_completed_without_dispute() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		Timestamp::set_timestamp(INIT_TIMESTAMP);
+
+		let payment_hash = fulfilled_payment(100);
+		assert_eq!(Currencies::free_balance(CurrencyId::Native, &ALICE), 900);
+
+		assert_ok!(LRP::complete_payment(Origin::signed(ALICE), payment_hash));
+		assert_eq!(LRP::payments(payment_hash).unwrap().status, PaymentStatus::Completed);
+		assert_eq!(Currencies::free_balance(CurrencyId::Native, &BOB), 1100);
+
+		// The completed payment can no longer be disputed.
+		assert_noop!(
+			DisputeResolution::create_dispute(Origin::signed(ALICE), payment_hash, "".into()),
+			dispute_resolution::Error::<Runtime>::DisputeNotAccepted,
+		);
+	});
+}
+
+#[test]
+fn escalated_dispute_with_slashed_resolver_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		Timestamp::set_timestamp(INIT_TIMESTAMP);
+		join_resolvers_network(&[RESOLVER_1, RESOLVER_2, RESOLVER_3, RESOLVER_4]);
+
+		// The payer disputes a fulfilled payment and the payee fights it.
+		let payment_hash = fulfilled_payment(100);
+		assert_ok!(DisputeResolution::create_dispute(
+			Origin::signed(ALICE),
+			payment_hash,
+			"".into(),
+		));
+		assert_ok!(DisputeResolution::fight_dispute(Origin::signed(BOB), payment_hash, "".into()));
+		assert_eq!(LRP::payments(payment_hash).unwrap().status, PaymentStatus::Disputed);
+
+		let dispute = DisputeResolution::disputes(&payment_hash).unwrap();
+		assert_eq!(dispute.resolvers.len(), 1);
+		let original = dispute.resolvers[0];
+		assert_ok!(DisputeResolution::accept_assignment(Origin::signed(original), payment_hash));
+		assert_ok!(DisputeResolution::propose_outcome(
+			Origin::signed(original),
+			payment_hash,
+			Judgment::ReleaseFundToPayee,
+			None
+		));
+
+		// The payer escalates the dispute to a larger panel and the payee fights it again.
+		assert_ok!(DisputeResolution::escalate_dispute(Origin::signed(ALICE), payment_hash));
+		assert_ok!(DisputeResolution::fight_dispute(Origin::signed(BOB), payment_hash, "".into()));
+
+		let dispute = DisputeResolution::disputes(&payment_hash).unwrap();
+		assert_eq!(dispute.status, DisputeStatus::Evaluating);
+		assert_eq!(dispute.resolvers.len(), 3);
+		assert_eq!(dispute.payer_fee, 3 * DISPUTE_FEE);
+		assert_eq!(dispute.payee_fee, 3 * DISPUTE_FEE);
+
+		let drawn: Vec<AccountId> = dispute
+			.resolvers
+			.iter()
+			.filter(|resolver| **resolver != original)
+			.cloned()
+			.collect();
+		let (diligent, slacker) = (drawn[0], drawn[1]);
+		for resolver in [diligent, slacker] {
+			assert_ok!(DisputeResolution::accept_assignment(
+				Origin::signed(resolver),
+				payment_hash
+			));
+		}
+		assert_ok!(DisputeResolution::propose_outcome(
+			Origin::signed(diligent),
+			payment_hash,
+			Judgment::ReleaseFundToPayee,
+			None
+		));
+
+		// The slacker does not propose the judgment in time, its bond is slashed and the seat is
+		// given to the last resolver.
+		let reserved_balance = Currencies::reserved_balance(CurrencyId::Native, &slacker);
+		Timestamp::set_timestamp(INIT_TIMESTAMP + ASSIGNMENT_JUDGING_TIME);
+		assert_ok!(DisputeResolution::expire_assignments(Origin::signed(BOB), payment_hash));
+		assert_eq!(
+			Currencies::reserved_balance(CurrencyId::Native, &slacker),
+			reserved_balance - RESOLVER_BOND
+		);
+		assert!(has_event(DisputeEvent::SlashClaimRecorded {
+			payment_hash,
+			resolver: slacker,
+			amount: RESOLVER_BOND / 2,
+		}));
+
+		let dispute = DisputeResolution::disputes(&payment_hash).unwrap();
+		assert!(!dispute.resolvers.contains(&slacker));
+		let replacement = *dispute
+			.resolvers
+			.iter()
+			.find(|resolver| ![original, diligent].contains(resolver))
+			.unwrap();
+		assert_ok!(DisputeResolution::accept_assignment(Origin::signed(replacement), payment_hash));
+		assert_ok!(DisputeResolution::propose_outcome(
+			Origin::signed(replacement),
+			payment_hash,
+			Judgment::ReleaseFundToPayee,
+			None
+		));
+
+		let dispute = DisputeResolution::disputes(&payment_hash).unwrap();
+		assert_eq!(dispute.status, DisputeStatus::Finalizing);
+		assert_eq!(dispute.outcome, Judgment::ReleaseFundToPayee);
+
+		Timestamp::set_timestamp(dispute.expired_at);
+		assert_ok!(DisputeResolution::finalize_dispute(Origin::signed(BOB), payment_hash));
+
+		// The funds are released to the payee, who is refunded and paid the slash claim, while
+		// the resolvers of the final panel are paid from the escrow of the payer.
+		assert_eq!(LRP::payments(payment_hash).unwrap().status, PaymentStatus::Resolved);
+		assert_eq!(
+			DisputeResolution::disputes(&payment_hash).unwrap().status,
+			DisputeStatus::Resolved
+		);
+		assert_eq!(Currencies::reserved_balance(CurrencyId::Native, &ALICE), 0);
+		assert_eq!(Currencies::reserved_balance(CurrencyId::Native, &BOB), 0);
+		assert_eq!(
+			Currencies::free_balance(CurrencyId::Native, &ALICE),
+			1000 - 100 - 3 * DISPUTE_FEE
+		);
+		assert_eq!(
+			Currencies::free_balance(CurrencyId::Native, &BOB),
+			1000 + 100 + RESOLVER_BOND / 2
+		);
+		assert_eq!(DisputeResolution::slash_claims(&payment_hash), 0);
+		assert!(has_event(DisputeEvent::SlashClaimPaid {
+			payment_hash,
+			beneficiary: BOB,
+			amount: RESOLVER_BOND / 2,
+		}));
+		assert!(has_event(DisputeEvent::DisputeFeeRefunded {
+			payment_hash,
+			account: BOB,
+			amount: 3 * DISPUTE_FEE,
+		}));
+
+		let resolver_fee = DISPUTE_FEE - Permill::from_percent(10) * DISPUTE_FEE;
+		for resolver in [original, diligent, replacement] {
+			assert!(has_event(DisputeEvent::ResolverFeePaid {
+				payment_hash,
+				resolver,
+				amount: resolver_fee,
+			}));
+		}
+		assert!(!has_event(DisputeEvent::ResolverFeePaid {
+			payment_hash,
+			resolver: slacker,
+			amount: resolver_fee,
+		}));
+
+		// The dispute is recorded as won by the payee and lost by the payer.
+		assert_eq!(Identities::dispute_records(&BOB), DisputeRecord { opened: 1, won: 1, lost: 0 });
+		assert_eq!(
+			Identities::dispute_records(&ALICE),
+			DisputeRecord { opened: 0, won: 0, lost: 1 }
+		);
+	});
+}
@@ -1,12 +1,13 @@
 use libra_runtime::{
-	AccountId, AuraConfig, BalancesConfig, GenesisConfig, GrandpaConfig, Signature, SudoConfig,
-	SystemConfig, WASM_BINARY,
+	pallet_identities::IdentityType, AccountId, ActivationStakeAmount, AuraConfig, BalancesConfig,
+	CurrenciesRegistryConfig, ExistentialDeposit, GenesisConfig, GrandpaConfig, IdentitiesConfig,
+	LrpPalletId, ResolversNetworkConfig, Signature, SudoConfig, SystemConfig, WASM_BINARY,
 };
 use sc_service::ChainType;
 use sp_consensus_aura::sr25519::AuthorityId as AuraId;
 use sp_core::{sr25519, Pair, Public};
 use sp_finality_grandpa::AuthorityId as GrandpaId;
-use sp_runtime::traits::{IdentifyAccount, Verify};
+use sp_runtime::traits::{AccountIdConversion, IdentifyAccount, Verify};
 
 // The URL for the telemetry server.
 // const STAGING_TELEMETRY_URL: &str = "wss://telemetry.polkadot.io/submit/";
@@ -36,6 +37,11 @@ pub fn authority_keys_from_seed(s: &str) -> (AuraId, GrandpaId) {
 	(get_from_seed::<AuraId>(s), get_from_seed::<GrandpaId>(s))
 }
 
+/// Generate an initial resolver with its name.
+pub fn resolver_from_seed(s: &str) -> (AccountId, Vec<u8>) {
+	(get_account_id_from_seed::<sr25519::Public>(s), s.as_bytes().to_vec())
+}
+
 pub fn development_config() -> Result<ChainSpec, String> {
 	let wasm_binary = WASM_BINARY.ok_or_else(|| "Development wasm not available".to_string())?;
 
@@ -59,6 +65,8 @@ pub fn development_config() -> Result<ChainSpec, String> {
 					get_account_id_from_seed::<sr25519::Public>("Alice//stash"),
 					get_account_id_from_seed::<sr25519::Public>("Bob//stash"),
 				],
+				// Initial resolvers
+				vec![resolver_from_seed("Alice")],
 				true,
 			)
 		},
@@ -107,6 +115,12 @@ pub fn local_testnet_config() -> Result<ChainSpec, String> {
 					get_account_id_from_seed::<sr25519::Public>("Eve//stash"),
 					get_account_id_from_seed::<sr25519::Public>("Ferdie//stash"),
 				],
+				// Initial resolvers
+				vec![
+					resolver_from_seed("Alice"),
+					resolver_from_seed("Bob"),
+					resolver_from_seed("Charlie"),
+				],
 				true,
 			)
 		},
@@ -130,6 +144,7 @@ fn testnet_genesis(
 	initial_authorities: Vec<(AuraId, GrandpaId)>,
 	root_key: AccountId,
 	endowed_accounts: Vec<AccountId>,
+	initial_resolvers: Vec<(AccountId, Vec<u8>)>,
 	_enable_println: bool,
 ) -> GenesisConfig {
 	GenesisConfig {
@@ -138,8 +153,17 @@ fn testnet_genesis(
 			code: wasm_binary.to_vec(),
 		},
 		balances: BalancesConfig {
-			// Configure endowed accounts with initial balance of 1 << 60.
-			balances: endowed_accounts.iter().cloned().map(|k| (k, 1 << 60)).collect(),
+			// Configure endowed accounts with initial balance of 1 << 60, and keep the escrow
+			// account of the payments alive with the existential deposit.
+			balances: endowed_accounts
+				.iter()
+				.cloned()
+				.map(|k| (k, 1 << 60))
+				.chain(std::iter::once((
+					LrpPalletId::get().into_account(),
+					ExistentialDeposit::get(),
+				)))
+				.collect(),
 		},
 		aura: AuraConfig {
 			authorities: initial_authorities.iter().map(|x| (x.0.clone())).collect(),
@@ -152,5 +176,24 @@ fn testnet_genesis(
 			key: Some(root_key),
 		},
 		tokens: Default::default(),
+		currencies_registry: CurrenciesRegistryConfig { currencies: vec![] },
+		identities: IdentitiesConfig {
+			// The resolvers need an identity to be credible.
+			identities: initial_resolvers
+				.iter()
+				.map(|(account, name)| {
+					(account.clone(), name.clone(), IdentityType::Individual, vec![])
+				})
+				.collect(),
+			evaluators: vec![],
+		},
+		resolvers_network: ResolversNetworkConfig {
+			resolvers: initial_resolvers
+				.iter()
+				.map(|(account, name)| {
+					(account.clone(), name.clone(), ActivationStakeAmount::get())
+				})
+				.collect(),
+		},
 	}
 }
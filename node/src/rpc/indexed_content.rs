@@ -0,0 +1,69 @@
+//! RPC to retrieve the contents written to the off-chain indexing storage.
+//!
+//! The pallets only keep the digests of the large contents (receipts, delivery proofs, arguments,
+//! applications, reviews...) on chain, while the contents are written to the off-chain indexing
+//! storage under `primitives::indexed_content_key`. The node needs to run with
+//! `--enable-offchain-indexing true` to keep them.
+
+use jsonrpc_core::{Error as RpcError, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+use libra_runtime::Hash;
+use primitives::indexed_content_key;
+use sp_core::{
+	offchain::{OffchainStorage, STORAGE_PREFIX},
+	Bytes,
+};
+use sp_runtime::traits::{BlakeTwo256, Hash as HashT};
+
+/// The content does not match its digest.
+const CORRUPTED_CONTENT_ERROR: i64 = 1;
+/// The off-chain indexing is not enabled on the node.
+const OFFCHAIN_INDEXING_DISABLED_ERROR: i64 = 2;
+
+#[rpc]
+pub trait IndexedContentApi {
+	/// Get the original content of a digest, `None` if the content is not indexed by the node.
+	#[rpc(name = "lrp_getIndexedContent")]
+	fn get_indexed_content(&self, digest: Hash) -> Result<Option<Bytes>>;
+}
+
+/// Retrieves the indexed contents from the off-chain storage of the node.
+pub struct IndexedContent<S> {
+	storage: Option<S>,
+}
+
+impl<S> IndexedContent<S> {
+	/// Create new `IndexedContent` with the given off-chain storage.
+	pub fn new(storage: Option<S>) -> Self {
+		Self { storage }
+	}
+}
+
+impl<S> IndexedContentApi for IndexedContent<S>
+where
+	S: OffchainStorage + 'static,
+{
+	fn get_indexed_content(&self, digest: Hash) -> Result<Option<Bytes>> {
+		let storage = self.storage.as_ref().ok_or_else(|| RpcError {
+			code: ErrorCode::ServerError(OFFCHAIN_INDEXING_DISABLED_ERROR),
+			message: "Offchain indexing is not enabled".into(),
+			data: None,
+		})?;
+
+		let content = match storage.get(STORAGE_PREFIX, &indexed_content_key(&digest)) {
+			Some(content) => content,
+			None => return Ok(None),
+		};
+
+		// The pallets digest the encoded content.
+		if BlakeTwo256::hash_of(&content) != digest {
+			return Err(RpcError {
+				code: ErrorCode::ServerError(CORRUPTED_CONTENT_ERROR),
+				message: "Indexed content does not match the digest".into(),
+				data: None,
+			})
+		}
+
+		Ok(Some(content.into()))
+	}
+}
@@ -0,0 +1,118 @@
+//! Benchmarking setup for currencies registry.
+
+use super::*;
+
+#[allow(unused)]
+use crate::Pallet as CurrenciesRegistry;
+use frame_benchmarking::{account, benchmarks, whitelisted_caller};
+use frame_support::{sp_runtime::traits::Hash, traits::Get};
+use frame_system::RawOrigin;
+use orml_traits::MultiCurrency;
+use primitives::CurrencyId;
+
+type BalanceOf<T> =
+	<<T as Config>::Currency as MultiCurrency<<T as frame_system::Config>::AccountId>>::Balance;
+
+const SEED: u32 = 0;
+
+fn assert_last_event<T: Config>(generic_event: <T as Config>::Event) {
+	frame_system::Pallet::<T>::assert_last_event(generic_event.into());
+}
+
+fn funded_account<T: Config>(name: &'static str, index: u32) -> T::AccountId {
+	let who: T::AccountId = account(name, index, SEED);
+	let amount = (T::VerifiedListingBond::get() + T::VerifiedListingFee::get()) * 100u32.into();
+	T::Currency::deposit(CurrencyId::Native, &who, amount).unwrap();
+	who
+}
+
+fn create_currency<T: Config>(issuer: T::AccountId, index: u32) -> T::Hash {
+	let metadata = CurrencyMetadata::<T> {
+		name: b"Libra Dollar".to_vec(),
+		symbol: b"LUSD".to_vec(),
+		decimals: (index % 256) as u8,
+		issuer: issuer.clone(),
+	};
+	CurrenciesRegistry::<T>::create_currency(
+		RawOrigin::Signed(issuer).into(),
+		metadata.name.clone(),
+		metadata.symbol.clone(),
+		metadata.decimals,
+	)
+	.unwrap();
+	T::Hashing::hash_of(&metadata)
+}
+
+benchmarks! {
+	create_currency {
+		let caller: T::AccountId = whitelisted_caller();
+		let amount: BalanceOf<T> = T::CommunityListingBond::get() * 10u32.into();
+		T::Currency::deposit(CurrencyId::Native, &caller, amount).unwrap();
+		let metadata = CurrencyMetadata::<T> {
+			name: b"Libra Dollar".to_vec(),
+			symbol: b"LUSD".to_vec(),
+			decimals: 18,
+			issuer: caller.clone(),
+		};
+		let currency_hash = T::Hashing::hash_of(&metadata);
+	}: _(
+		RawOrigin::Signed(caller.clone()),
+		metadata.name.clone(),
+		metadata.symbol.clone(),
+		metadata.decimals
+	)
+	verify {
+		assert_last_event::<T>(
+			Event::CurrencyCreated {
+				currency_hash,
+				name: metadata.name,
+				symbol: metadata.symbol,
+				decimals: metadata.decimals,
+				created_by: caller,
+				bond: T::CommunityListingBond::get(),
+			}
+			.into(),
+		);
+	}
+
+	remove_currency {
+		let issuer = funded_account::<T>("issuer", 0);
+		let currency_hash = create_currency::<T>(issuer.clone(), 0);
+	}: _(RawOrigin::Signed(issuer), currency_hash)
+	verify {
+		assert!(CurrenciesRegistry::<T>::currencies(currency_hash).is_none());
+	}
+
+	accept_currency {
+		let issuer = funded_account::<T>("issuer", 0);
+		let merchant: T::AccountId = whitelisted_caller();
+		let currency_hash = create_currency::<T>(issuer, 0);
+	}: _(RawOrigin::Signed(merchant.clone()), currency_hash)
+	verify {
+		assert_last_event::<T>(
+			Event::CurrencyAccepted { currency_hash, accepted_by: merchant }.into()
+		);
+	}
+
+	upgrade_listing_tier {
+		let issuer = funded_account::<T>("issuer", 0);
+		let currency_hash = create_currency::<T>(issuer.clone(), 0);
+	}: _(RawOrigin::Signed(issuer), currency_hash)
+	verify {
+		assert_last_event::<T>(
+			Event::ListingTierUpgraded {
+				currency_hash,
+				tier: ListingTier::Verified,
+				bond: T::VerifiedListingBond::get(),
+				fee: T::VerifiedListingFee::get(),
+			}
+			.into(),
+		);
+	}
+
+	impl_benchmark_test_suite!(
+		CurrenciesRegistry,
+		crate::mock::ExtBuilder::default().build(),
+		crate::mock::Runtime
+	);
+}
@@ -5,33 +5,56 @@
 //! merchants need to accept the currencies before people create payments with these currencies in
 //! the LRP protocol.
 //!
+//! ## Listing tiers
+//!
+//! A currency is created as a community listing with `CommunityListingBond`. The issuer can
+//! upgrade it to a verified listing by bonding up to `VerifiedListingBond` and paying the
+//! non-refundable `VerifiedListingFee` to the treasury, which funds the curation of the registry.
+//!
+//! The bonds are reserved under the `LISTING_BOND_RESERVE` identifier and tracked in
+//! `NamedReserves`, the pallet never unreserves more than it reserved for the listings.
+//!
 //! ## Traits
 //!
 //! CurrenciesManager - Including function `is_currency_accepted` to evaluate
-//! the currency is accepted by merchant or not.
+//! the currency is accepted by merchant or not. The native token and the foreign assets (e.g.
+//! bridged USDC) are accepted by every merchant, only the registered currencies need to be
+//! accepted.
 //!
 //! ## Usage
 //!
 //! - `create_currency` - Create a new currency with metadata( name, symbol, decimals).
 //! - `remove_currency` - Remove a existing currency.
 //! - `accept_currency` - Accept a currency before accepting payment with the currency.
-//! - `accept_currency` - Accept a currency before accepting payment with the currency.
+//! - `upgrade_listing_tier` - Upgrade a community listing to a verified listing.
+//!
+//! ## Genesis config
+//!
+//! The currencies can be pre-registered at genesis. The bonding amount is reserved from the
+//! issuers like the currencies created by `create_currency`.
 //!
 //! ## Events
 //!
-//! - CurrencyCreated - A new currency is created by a registrar.
-//! - CurrencyRemoved - A currency is removed by the issuer.
+//! - CurrencyCreated - A new currency is created by a registrar, the bond is reserved.
+//! - CurrencyRemoved - A currency is removed by the issuer, the bond is unreserved.
 //! - CurrencyAccepted - A currency is accepted by a merchant.
+//! - ListingTierUpgraded - A listing is upgraded by the issuer, the additional bond is reserved and
+//!   the fee is paid to the treasury.
 //!
 //! ## Errors
 //!
 //! - CurrencyExisted - The currency with the same metadata has existed for the registrar.
 //! - CurrencyNotFound - Cannot find the currency match with the currency hash.
 //! - NotCurrencyIssuer - Cannot remove a currency if not is the currency issuer.
+//! - ListingAlreadyVerified - The listing is already at the verified tier.
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
 pub use pallet::*;
+pub use weights::WeightInfo;
+
+pub mod migrations;
+pub mod weights;
 
 #[cfg(test)]
 mod mock;
@@ -44,12 +67,17 @@ mod benchmarking;
 
 #[frame_support::pallet]
 pub mod pallet {
+	use crate::WeightInfo;
 	use frame_support::{
-		dispatch::DispatchResult, pallet_prelude::*, sp_runtime::traits::Hash, sp_std::vec::Vec,
+		dispatch::DispatchResult,
+		pallet_prelude::*,
+		sp_runtime::traits::{Hash, Saturating, Zero},
+		sp_std::vec::Vec,
+		transactional,
 	};
 	use frame_system::pallet_prelude::*;
 	use orml_traits::{MultiCurrency, MultiReservableCurrency};
-	use primitives::CurrencyId;
+	use primitives::{CurrencyId, ReserveIdentifier, LISTING_BOND_RESERVE};
 	use scale_info::TypeInfo;
 
 	pub trait CurrenciesManager<AccountId, Hash> {
@@ -65,8 +93,20 @@ pub mod pallet {
 	pub trait Config: frame_system::Config {
 		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
 		type Currency: MultiReservableCurrency<Self::AccountId, CurrencyId = CurrencyId<Self::Hash>>;
+		/// The bond reserved from the issuer for a community listing.
+		#[pallet::constant]
+		type CommunityListingBond: Get<BalanceOf<Self>>;
+		/// The bond reserved from the issuer for a verified listing.
+		#[pallet::constant]
+		type VerifiedListingBond: Get<BalanceOf<Self>>;
+		/// The non-refundable fee paid to the treasury to upgrade to a verified listing.
+		#[pallet::constant]
+		type VerifiedListingFee: Get<BalanceOf<Self>>;
+		/// The account of the treasury which receives the listing fees.
 		#[pallet::constant]
-		type BondingAmount: Get<BalanceOf<Self>>;
+		type TreasuryAccount: Get<AccountOf<Self>>;
+		/// Weight information for extrinsics in this pallet.
+		type WeightInfo: WeightInfo;
 	}
 
 	#[derive(Clone, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo)]
@@ -78,7 +118,24 @@ pub mod pallet {
 		pub issuer: AccountOf<T>,
 	}
 
+	#[derive(Clone, Copy, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo)]
+	pub enum ListingTier {
+		Community,
+		Verified,
+	}
+
+	#[derive(Clone, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo)]
+	pub struct Listing<Balance> {
+		pub tier: ListingTier,
+		/// The bond reserved from the issuer, it is unreserved when the currency is removed.
+		pub bond: Balance,
+	}
+
+	/// The current storage version, see [`crate::migrations`].
+	pub(crate) const STORAGE_VERSION: StorageVersion = StorageVersion::new(3);
+
 	#[pallet::pallet]
+	#[pallet::storage_version(STORAGE_VERSION)]
 	#[pallet::without_storage_info]
 	pub struct Pallet<T>(_);
 
@@ -87,17 +144,91 @@ pub mod pallet {
 	pub(super) type Currencies<T: Config> =
 		StorageMap<_, Twox64Concat, T::Hash, CurrencyMetadata<T>>;
 
+	/// The listing tier and the bond of the currencies. The currencies created before the listing
+	/// tiers have no listing, they are community listings bonded with `CommunityListingBond`.
+	#[pallet::storage]
+	#[pallet::getter(fn listings)]
+	pub(super) type Listings<T: Config> =
+		StorageMap<_, Twox64Concat, T::Hash, Listing<BalanceOf<T>>>;
+
+	/// The registered currencies accepted by the merchants.
 	#[pallet::storage]
-	#[pallet::getter(fn accepted_currencies)]
-	pub(super) type AcceptedCurrencies<T: Config> =
-		StorageMap<_, Twox64Concat, T::AccountId, Vec<CurrencyHashOf<T>>, ValueQuery>;
+	pub(super) type AcceptedCurrencies<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		T::AccountId,
+		Twox64Concat,
+		CurrencyHashOf<T>,
+		(),
+		OptionQuery,
+	>;
+
+	/// The funds reserved by the pallet by account, reserve identifier and currency. Only the
+	/// tracked funds are unreserved, so the reserves of the other pallets are never consumed.
+	#[pallet::storage]
+	#[pallet::getter(fn named_reserves)]
+	pub(super) type NamedReserves<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		AccountOf<T>,
+		Twox64Concat,
+		(ReserveIdentifier, CurrencyId<T::Hash>),
+		BalanceOf<T>,
+		ValueQuery,
+	>;
+
+	#[pallet::genesis_config]
+	pub struct GenesisConfig<T: Config> {
+		/// The pre-registered currencies with the issuer, name, symbol and decimals.
+		pub currencies: Vec<(AccountOf<T>, Vec<u8>, Vec<u8>, u8)>,
+	}
+
+	#[cfg(feature = "std")]
+	impl<T: Config> Default for GenesisConfig<T> {
+		fn default() -> Self {
+			Self { currencies: Default::default() }
+		}
+	}
+
+	#[pallet::genesis_build]
+	impl<T: Config> GenesisBuild<T> for GenesisConfig<T> {
+		fn build(&self) {
+			for (issuer, name, symbol, decimals) in self.currencies.iter() {
+				let metadata = CurrencyMetadata::<T> {
+					name: name.clone(),
+					symbol: symbol.clone(),
+					decimals: *decimals,
+					issuer: issuer.clone(),
+				};
+				let currency_hash = T::Hashing::hash_of(&metadata);
+
+				assert!(
+					!<Currencies<T>>::contains_key(currency_hash),
+					"Duplicate currency in genesis config"
+				);
+				let bond = T::CommunityListingBond::get();
+				Pallet::<T>::reserve_named(LISTING_BOND_RESERVE, issuer, bond)
+					.expect("Currency issuer must have enough balance to bond");
+
+				<Currencies<T>>::insert(&currency_hash, metadata);
+				<Listings<T>>::insert(
+					&currency_hash,
+					Listing { tier: ListingTier::Community, bond },
+				);
+			}
+		}
+	}
 
 	#[pallet::event]
 	#[pallet::generate_deposit(pub(super) fn deposit_event)]
 	pub enum Event<T: Config> {
 		CurrencyCreated {
 			currency_hash: CurrencyHashOf<T>,
+			name: Vec<u8>,
+			symbol: Vec<u8>,
+			decimals: u8,
 			created_by: AccountOf<T>,
+			bond: BalanceOf<T>,
 		},
 		CurrencyRemoved {
 			currency_hash: CurrencyHashOf<T>,
@@ -105,11 +236,18 @@ pub mod pallet {
 			symbol: Vec<u8>,
 			decimals: u8,
 			removed_by: AccountOf<T>,
+			bond: BalanceOf<T>,
 		},
 		CurrencyAccepted {
 			currency_hash: CurrencyHashOf<T>,
 			accepted_by: AccountOf<T>,
 		},
+		ListingTierUpgraded {
+			currency_hash: CurrencyHashOf<T>,
+			tier: ListingTier,
+			bond: BalanceOf<T>,
+			fee: BalanceOf<T>,
+		},
 	}
 
 	#[pallet::error]
@@ -117,11 +255,20 @@ pub mod pallet {
 		CurrencyExisted,
 		CurrencyNotFound,
 		NotCurrencyIssuer,
+		ListingAlreadyVerified,
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade() -> Result<(), &'static str> {
+			Self::do_try_state()
+		}
 	}
 
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {
-		#[pallet::weight(10_000 + T::DbWeight::get().writes(1))]
+		#[pallet::weight(T::WeightInfo::create_currency())]
 		pub fn create_currency(
 			origin: OriginFor<T>,
 			name: Vec<u8>,
@@ -136,15 +283,24 @@ pub mod pallet {
 
 			ensure!(!<Currencies<T>>::contains_key(currency_hash), <Error<T>>::CurrencyExisted);
 
-			<Currencies<T>>::insert(&currency_hash, metadata);
-			T::Currency::reserve(CurrencyId::Native, &issuer, T::BondingAmount::get())?;
+			let bond = T::CommunityListingBond::get();
+			Self::reserve_named(LISTING_BOND_RESERVE, &issuer, bond)?;
+			<Currencies<T>>::insert(&currency_hash, metadata.clone());
+			<Listings<T>>::insert(&currency_hash, Listing { tier: ListingTier::Community, bond });
 
-			Self::deposit_event(Event::CurrencyCreated { currency_hash, created_by: issuer });
+			Self::deposit_event(Event::CurrencyCreated {
+				currency_hash,
+				name: metadata.name,
+				symbol: metadata.symbol,
+				decimals: metadata.decimals,
+				created_by: issuer,
+				bond,
+			});
 
 			Ok(())
 		}
 
-		#[pallet::weight(10_000)]
+		#[pallet::weight(T::WeightInfo::remove_currency())]
 		pub fn remove_currency(
 			origin: OriginFor<T>,
 			currency_hash: CurrencyHashOf<T>,
@@ -155,7 +311,9 @@ pub mod pallet {
 			ensure!(who == metadata.issuer, <Error<T>>::NotCurrencyIssuer);
 
 			<Currencies<T>>::remove(&currency_hash);
-			T::Currency::unreserve(<CurrencyId<T::Hash>>::Native, &who, T::BondingAmount::get());
+			let bond = Self::listing_of(&currency_hash).bond;
+			<Listings<T>>::remove(&currency_hash);
+			Self::unreserve_named(LISTING_BOND_RESERVE, &who, bond);
 
 			Self::deposit_event(Event::CurrencyRemoved {
 				currency_hash,
@@ -163,12 +321,13 @@ pub mod pallet {
 				symbol: metadata.symbol,
 				decimals: metadata.decimals,
 				removed_by: who,
+				bond,
 			});
 
 			Ok(())
 		}
 
-		#[pallet::weight(1_000 + T::DbWeight::get().writes(1))]
+		#[pallet::weight(T::WeightInfo::accept_currency())]
 		pub fn accept_currency(
 			origin: OriginFor<T>,
 			currency_hash: CurrencyHashOf<T>,
@@ -177,14 +336,120 @@ pub mod pallet {
 
 			ensure!(<Currencies<T>>::contains_key(currency_hash), <Error<T>>::CurrencyNotFound);
 
-			<AcceptedCurrencies<T>>::mutate(&merchant, |currency_ids| {
-				currency_ids.push(currency_hash)
-			});
+			<AcceptedCurrencies<T>>::insert(&merchant, &currency_hash, ());
 
 			Self::deposit_event(Event::CurrencyAccepted { currency_hash, accepted_by: merchant });
 
 			Ok(())
 		}
+
+		#[pallet::weight(T::WeightInfo::upgrade_listing_tier())]
+		#[transactional]
+		pub fn upgrade_listing_tier(
+			origin: OriginFor<T>,
+			currency_hash: CurrencyHashOf<T>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let metadata = Self::currencies(&currency_hash).ok_or(<Error<T>>::CurrencyNotFound)?;
+
+			ensure!(who == metadata.issuer, <Error<T>>::NotCurrencyIssuer);
+
+			let mut listing = Self::listing_of(&currency_hash);
+			ensure!(listing.tier == ListingTier::Community, <Error<T>>::ListingAlreadyVerified);
+
+			// Only the difference to the verified bond is reserved, the fee is not refundable.
+			let bond = T::VerifiedListingBond::get().max(listing.bond);
+			let fee = T::VerifiedListingFee::get();
+			T::Currency::transfer(CurrencyId::Native, &who, &T::TreasuryAccount::get(), fee)?;
+			Self::reserve_named(LISTING_BOND_RESERVE, &who, bond.saturating_sub(listing.bond))?;
+
+			listing.tier = ListingTier::Verified;
+			listing.bond = bond;
+			<Listings<T>>::insert(&currency_hash, listing);
+
+			Self::deposit_event(Event::ListingTierUpgraded {
+				currency_hash,
+				tier: ListingTier::Verified,
+				bond,
+				fee,
+			});
+
+			Ok(())
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		/// The listing of a currency, a community listing if the currency is created before the
+		/// listing tiers.
+		pub fn listing_of(currency_hash: &CurrencyHashOf<T>) -> Listing<BalanceOf<T>> {
+			Self::listings(currency_hash).unwrap_or(Listing {
+				tier: ListingTier::Community,
+				bond: T::CommunityListingBond::get(),
+			})
+		}
+
+		/// Check the accounting invariants of the registry, run by `try-runtime` after the runtime
+		/// upgrades:
+		/// - The listing bonds of the currencies are covered by the listing bond reserves of the
+		///   issuers.
+		/// - The tracked reserves of an account are covered by its reserved balance.
+		#[cfg(any(feature = "try-runtime", test))]
+		pub fn do_try_state() -> Result<(), &'static str> {
+			use frame_support::sp_std::collections::btree_map::BTreeMap;
+
+			let mut bonds: BTreeMap<AccountOf<T>, BalanceOf<T>> = BTreeMap::new();
+			for (currency_hash, metadata) in <Currencies<T>>::iter() {
+				let bond = bonds.entry(metadata.issuer).or_insert_with(Zero::zero);
+				*bond += Self::listing_of(&currency_hash).bond;
+			}
+			for (issuer, bond) in bonds {
+				let reserved =
+					Self::named_reserves(&issuer, (LISTING_BOND_RESERVE, CurrencyId::Native));
+				ensure!(bond <= reserved, "The listing bonds exceed the reserves of an issuer");
+			}
+
+			for (who, (_, currency_id), amount) in <NamedReserves<T>>::iter() {
+				ensure!(
+					amount <= T::Currency::reserved_balance(currency_id, &who),
+					"The tracked reserves of an account exceed its reserved balance"
+				);
+			}
+
+			Ok(())
+		}
+
+		// Reserve the native tokens of the account under the reserve identifier.
+		fn reserve_named(
+			id: ReserveIdentifier,
+			who: &AccountOf<T>,
+			amount: BalanceOf<T>,
+		) -> DispatchResult {
+			T::Currency::reserve(CurrencyId::Native, who, amount)?;
+			<NamedReserves<T>>::mutate(who, (id, CurrencyId::Native), |reserved| {
+				*reserved = reserved.saturating_add(amount)
+			});
+			Ok(())
+		}
+
+		// Unreserve at most the native tokens tracked under the reserve identifier, returns the
+		// amount that cannot be unreserved.
+		fn unreserve_named(
+			id: ReserveIdentifier,
+			who: &AccountOf<T>,
+			amount: BalanceOf<T>,
+		) -> BalanceOf<T> {
+			let tracked = Self::named_reserves(who, (id, CurrencyId::Native));
+			let to_unreserve = amount.min(tracked);
+			let unreserved =
+				to_unreserve - T::Currency::unreserve(CurrencyId::Native, who, to_unreserve);
+
+			<NamedReserves<T>>::mutate_exists(who, (id, CurrencyId::Native), |reserved| {
+				let left = reserved.unwrap_or_default().saturating_sub(unreserved);
+				*reserved = if left.is_zero() { None } else { Some(left) };
+			});
+
+			amount - unreserved
+		}
 	}
 
 	impl<T: Config> CurrenciesManager<T::AccountId, T::Hash> for Pallet<T> {
@@ -193,11 +458,9 @@ pub mod pallet {
 			currency_id: &CurrencyId<T::Hash>,
 		) -> bool {
 			match currency_id {
-				CurrencyId::<T::Hash>::Native => true,
-				CurrencyId::<T::Hash>::Registered(hash) => {
-					let accepted_currencies = Self::accepted_currencies(merchant);
-					accepted_currencies.contains(hash)
-				},
+				CurrencyId::<T::Hash>::Native | CurrencyId::<T::Hash>::ForeignAsset(_) => true,
+				CurrencyId::<T::Hash>::Registered(hash) =>
+					<AcceptedCurrencies<T>>::contains_key(merchant, hash),
 			}
 		}
 	}
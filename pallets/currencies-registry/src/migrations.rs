@@ -0,0 +1,187 @@
+//! Storage migrations of the currencies registry pallet.
+//!
+//! Each migration upgrades the `StorageVersion` of the pallet by one and only runs if the on-chain
+//! version is the previous one. The migrations can be rehearsed against a live chain with the
+//! `try-runtime` feature, which runs the `pre_upgrade` and `post_upgrade` checks.
+
+use crate::{AcceptedCurrencies, Config, Currencies, NamedReserves, Pallet};
+#[cfg(feature = "try-runtime")]
+use frame_support::traits::OnRuntimeUpgradeHelpersExt;
+use frame_support::{pallet_prelude::*, traits::OnRuntimeUpgrade};
+
+/// Version 1 stamps the storage version. The layout of the registered currencies is unchanged since
+/// the launch, so the checks only count that they still decode.
+pub mod v1 {
+	use super::*;
+
+	pub struct MigrateToV1<T>(PhantomData<T>);
+
+	impl<T: Config> OnRuntimeUpgrade for MigrateToV1<T> {
+		fn on_runtime_upgrade() -> Weight {
+			if Pallet::<T>::on_chain_storage_version() != 0 {
+				return T::DbWeight::get().reads(1)
+			}
+
+			StorageVersion::new(1).put::<Pallet<T>>();
+			T::DbWeight::get().reads_writes(1, 1)
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<(), &'static str> {
+			Self::set_temp_storage(Currencies::<T>::iter_keys().count() as u32, "currencies");
+			Ok(())
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade() -> Result<(), &'static str> {
+			ensure!(
+				Pallet::<T>::on_chain_storage_version() >= 1,
+				"Storage version is not migrated"
+			);
+			let currencies: u32 =
+				Self::get_temp_storage("currencies").ok_or("Missing currencies count")?;
+			ensure!(
+				Currencies::<T>::iter_values().count() as u32 == currencies,
+				"Some currencies cannot be decoded with the current layout"
+			);
+			Ok(())
+		}
+	}
+}
+
+/// Version 2 moves `AcceptedCurrencies` from a list of the currencies accepted by each merchant to
+/// a double map of the merchant and the currency, so the acceptance is checked in constant time.
+pub mod v2 {
+	use super::*;
+	use frame_support::{
+		sp_std::vec::Vec, storage::migration::storage_key_iter, traits::PalletInfoAccess,
+		Twox64Concat,
+	};
+
+	type OldAcceptedCurrencies<T> =
+		Vec<(<T as frame_system::Config>::AccountId, Vec<<T as frame_system::Config>::Hash>)>;
+
+	fn old_accepted_currencies<T: Config>(drain: bool) -> OldAcceptedCurrencies<T> {
+		let iter = storage_key_iter::<T::AccountId, Vec<T::Hash>, Twox64Concat>(
+			Pallet::<T>::name().as_bytes(),
+			b"AcceptedCurrencies",
+		);
+		if drain {
+			iter.drain().collect()
+		} else {
+			iter.collect()
+		}
+	}
+
+	pub struct MigrateToV2<T>(PhantomData<T>);
+
+	impl<T: Config> OnRuntimeUpgrade for MigrateToV2<T> {
+		fn on_runtime_upgrade() -> Weight {
+			if Pallet::<T>::on_chain_storage_version() != 1 {
+				return T::DbWeight::get().reads(1)
+			}
+
+			// The old lists are drained before the new entries are written under the same prefix.
+			let merchants = old_accepted_currencies::<T>(true);
+			let mut writes = merchants.len() as Weight;
+			for (merchant, currency_hashes) in merchants.iter() {
+				for currency_hash in currency_hashes.iter() {
+					AcceptedCurrencies::<T>::insert(merchant, currency_hash, ());
+					writes += 1;
+				}
+			}
+
+			StorageVersion::new(2).put::<Pallet<T>>();
+			T::DbWeight::get().reads_writes(1 + merchants.len() as Weight, 1 + writes)
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<(), &'static str> {
+			// The old lists can contain the same currency more than once.
+			let accepted: u32 = old_accepted_currencies::<T>(false)
+				.into_iter()
+				.map(|(_, mut currency_hashes)| {
+					currency_hashes.sort();
+					currency_hashes.dedup();
+					currency_hashes.len() as u32
+				})
+				.sum();
+			Self::set_temp_storage(accepted, "accepted_currencies");
+			Ok(())
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade() -> Result<(), &'static str> {
+			ensure!(
+				Pallet::<T>::on_chain_storage_version() >= 2,
+				"Storage version is not migrated"
+			);
+			let accepted: u32 = Self::get_temp_storage("accepted_currencies")
+				.ok_or("Missing accepted currencies count")?;
+			ensure!(
+				AcceptedCurrencies::<T>::iter().count() as u32 == accepted,
+				"Some accepted currencies are not migrated"
+			);
+			Ok(())
+		}
+	}
+}
+
+/// Version 3 tracks the listing bonds in `NamedReserves`. The bonds of the existing listings are
+/// tracked as reserved from the issuers, capped by the reserved balances of the issuers.
+pub mod v3 {
+	use super::*;
+	use frame_support::{
+		sp_runtime::traits::{Saturating, Zero},
+		sp_std::collections::btree_map::BTreeMap,
+	};
+	use orml_traits::MultiReservableCurrency;
+	use primitives::{CurrencyId, LISTING_BOND_RESERVE};
+
+	pub struct MigrateToV3<T>(PhantomData<T>);
+
+	impl<T: Config> OnRuntimeUpgrade for MigrateToV3<T> {
+		fn on_runtime_upgrade() -> Weight {
+			if Pallet::<T>::on_chain_storage_version() != 2 {
+				return T::DbWeight::get().reads(1)
+			}
+
+			let mut bonds = BTreeMap::new();
+			let mut currencies: Weight = 0;
+			for (currency_hash, metadata) in Currencies::<T>::iter() {
+				currencies += 1;
+				let bond = Pallet::<T>::listing_of(&currency_hash).bond;
+				let total = bonds.entry(metadata.issuer).or_insert_with(Zero::zero);
+				*total = bond.saturating_add(*total);
+			}
+
+			let issuers = bonds.len() as Weight;
+			for (issuer, bond) in bonds {
+				let reserved = T::Currency::reserved_balance(CurrencyId::Native, &issuer);
+				NamedReserves::<T>::insert(
+					&issuer,
+					(LISTING_BOND_RESERVE, CurrencyId::Native),
+					bond.min(reserved),
+				);
+			}
+
+			StorageVersion::new(3).put::<Pallet<T>>();
+			T::DbWeight::get().reads_writes(1 + 2 * currencies + issuers, 1 + issuers)
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade() -> Result<(), &'static str> {
+			ensure!(
+				Pallet::<T>::on_chain_storage_version() == 3,
+				"Storage version is not migrated"
+			);
+			ensure!(
+				NamedReserves::<T>::iter().all(|(issuer, (_, currency_id), bond)| {
+					bond <= T::Currency::reserved_balance(currency_id, &issuer)
+				}),
+				"Some listing bonds are not reserved"
+			);
+			Ok(())
+		}
+	}
+}
@@ -1,5 +1,8 @@
 use crate as currencies_registry;
-use frame_support::{construct_runtime, parameter_types, traits::Nothing};
+use frame_support::{
+	construct_runtime, parameter_types,
+	traits::{GenesisBuild, Nothing},
+};
 use frame_system as system;
 pub use pallet_balances::Call as BalancesCall;
 use sp_runtime::{
@@ -18,7 +21,10 @@ pub type Header = generic::Header<BlockNumber, BlakeTwo256>;
 
 pub const ALICE: AccountId = 1;
 pub const BOB: AccountId = 2;
-pub const BONDING_AMOUNT: Balance = 100;
+pub const TREASURY: AccountId = 10;
+pub const COMMUNITY_LISTING_BOND: Balance = 100;
+pub const VERIFIED_LISTING_BOND: Balance = 300;
+pub const VERIFIED_LISTING_FEE: Balance = 50;
 
 parameter_types! {
 	pub const BlockHashCount: u64 = 250;
@@ -100,13 +106,20 @@ impl orml_currencies::Config for Runtime {
 }
 
 parameter_types! {
-	pub const BondingAmount: Balance = BONDING_AMOUNT;
+	pub const CommunityListingBond: Balance = COMMUNITY_LISTING_BOND;
+	pub const VerifiedListingBond: Balance = VERIFIED_LISTING_BOND;
+	pub const VerifiedListingFee: Balance = VERIFIED_LISTING_FEE;
+	pub const TreasuryAccount: AccountId = TREASURY;
 }
 
 impl currencies_registry::Config for Runtime {
 	type Event = Event;
 	type Currency = Currencies;
-	type BondingAmount = BondingAmount;
+	type CommunityListingBond = CommunityListingBond;
+	type VerifiedListingBond = VerifiedListingBond;
+	type VerifiedListingFee = VerifiedListingFee;
+	type TreasuryAccount = TreasuryAccount;
+	type WeightInfo = ();
 }
 
 type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Runtime>;
@@ -122,22 +135,34 @@ construct_runtime!(
 		Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
 		Tokens: orml_tokens::{Pallet, Storage, Event<T>, Config<T>},
 		Currencies: orml_currencies::{Pallet, Call, Event<T>},
-		CurrenciesRegistry: currencies_registry::{Pallet, Call, Storage, Event<T>},
+		CurrenciesRegistry: currencies_registry::{Pallet, Call, Storage, Config<T>, Event<T>},
 	}
 );
 
-pub struct ExtBuilder;
+#[derive(Default)]
+pub struct ExtBuilder {
+	currencies: Vec<(AccountId, Vec<u8>, Vec<u8>, u8)>,
+}
 
 impl ExtBuilder {
+	pub fn currencies(mut self, currencies: Vec<(AccountId, Vec<u8>, Vec<u8>, u8)>) -> Self {
+		self.currencies = currencies;
+		self
+	}
+
 	pub fn build(self) -> sp_io::TestExternalities {
 		let mut t = system::GenesisConfig::default().build_storage::<Runtime>().unwrap();
 
 		pallet_balances::GenesisConfig::<Runtime> {
-			balances: vec![(ALICE, 1_000), (BOB, 1_000)],
+			balances: vec![(ALICE, 1_000), (BOB, 1_000), (TREASURY, 1_000)],
 		}
 		.assimilate_storage(&mut t)
 		.unwrap();
 
+		currencies_registry::GenesisConfig::<Runtime> { currencies: self.currencies }
+			.assimilate_storage(&mut t)
+			.unwrap();
+
 		t.into()
 	}
 }
@@ -1,10 +1,20 @@
 use super::*;
 
-use frame_support::{assert_noop, assert_ok};
+use codec::Encode;
+use frame_support::{
+	assert_noop, assert_ok,
+	storage::migration::put_storage_value,
+	traits::{GetStorageVersion, OnRuntimeUpgrade, StorageVersion},
+	StorageHasher, Twox64Concat,
+};
 use frame_system as system;
 use mock::{
-	last_event, CurrenciesRegistry, Event, ExtBuilder, Origin, Runtime, System, ALICE, BOB,
+	last_event, Currencies, CurrenciesRegistry, CurrencyId, Event, ExtBuilder, Origin, Runtime,
+	System, ALICE, BOB, COMMUNITY_LISTING_BOND, TREASURY, VERIFIED_LISTING_BOND,
+	VERIFIED_LISTING_FEE,
 };
+use orml_traits::{MultiCurrency, MultiReservableCurrency};
+use primitives::LISTING_BOND_RESERVE;
 use sp_core::H256;
 use sp_runtime::traits::Hash;
 
@@ -23,7 +33,7 @@ fn expected() -> (<Runtime as system::Config>::Hash, CurrencyMetadata<Runtime>)
 
 #[test]
 fn create_currency_works() {
-	ExtBuilder.build().execute_with(|| {
+	ExtBuilder::default().build().execute_with(|| {
 		System::set_block_number(1);
 
 		let (currency_hash, metadata) = expected();
@@ -38,7 +48,11 @@ fn create_currency_works() {
 			last_event(),
 			Event::CurrenciesRegistry(crate::Event::CurrencyCreated {
 				currency_hash,
+				name: metadata.name.clone(),
+				symbol: metadata.symbol.clone(),
+				decimals: 12,
 				created_by: ALICE,
+				bond: COMMUNITY_LISTING_BOND,
 			}),
 		);
 		assert_eq!(CurrenciesRegistry::currencies(currency_hash).unwrap(), metadata);
@@ -57,7 +71,7 @@ fn create_currency_works() {
 
 #[test]
 fn remove_currency_works() {
-	ExtBuilder.build().execute_with(|| {
+	ExtBuilder::default().build().execute_with(|| {
 		System::set_block_number(1);
 
 		let (currency_hash, metadata) = expected();
@@ -87,6 +101,7 @@ fn remove_currency_works() {
 				symbol: metadata.symbol,
 				decimals: 12,
 				removed_by: ALICE,
+				bond: COMMUNITY_LISTING_BOND,
 			}),
 		);
 
@@ -99,7 +114,7 @@ fn remove_currency_works() {
 
 #[test]
 fn accept_currency_works() {
-	ExtBuilder.build().execute_with(|| {
+	ExtBuilder::default().build().execute_with(|| {
 		System::set_block_number(1);
 		let (currency_hash, _) = expected();
 
@@ -121,7 +136,235 @@ fn accept_currency_works() {
 				accepted_by: BOB,
 			}),
 		);
-		assert_eq!(CurrenciesRegistry::accepted_currencies(BOB).len(), 1);
-		assert_eq!(CurrenciesRegistry::accepted_currencies(BOB)[0], currency_hash);
+		assert!(AcceptedCurrencies::<Runtime>::contains_key(BOB, currency_hash));
+		assert!(!AcceptedCurrencies::<Runtime>::contains_key(ALICE, currency_hash));
+	});
+}
+
+#[test]
+fn upgrade_listing_tier_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+
+		let (currency_hash, _) = expected();
+
+		assert_ok!(CurrenciesRegistry::create_currency(
+			Origin::signed(ALICE),
+			"Polkadot".into(),
+			"dot".into(),
+			12,
+		));
+		assert_eq!(
+			CurrenciesRegistry::listings(currency_hash),
+			Some(Listing { tier: ListingTier::Community, bond: COMMUNITY_LISTING_BOND }),
+		);
+
+		assert_noop!(
+			CurrenciesRegistry::upgrade_listing_tier(Origin::signed(BOB), currency_hash),
+			Error::<Runtime>::NotCurrencyIssuer
+		);
+
+		assert_ok!(CurrenciesRegistry::upgrade_listing_tier(Origin::signed(ALICE), currency_hash));
+		assert_eq!(
+			last_event(),
+			Event::CurrenciesRegistry(crate::Event::ListingTierUpgraded {
+				currency_hash,
+				tier: ListingTier::Verified,
+				bond: VERIFIED_LISTING_BOND,
+				fee: VERIFIED_LISTING_FEE,
+			}),
+		);
+		assert_eq!(
+			CurrenciesRegistry::listings(currency_hash),
+			Some(Listing { tier: ListingTier::Verified, bond: VERIFIED_LISTING_BOND }),
+		);
+		assert_eq!(Currencies::reserved_balance(CurrencyId::Native, &ALICE), VERIFIED_LISTING_BOND);
+		assert_eq!(
+			Currencies::free_balance(CurrencyId::Native, &ALICE),
+			1_000 - VERIFIED_LISTING_BOND - VERIFIED_LISTING_FEE
+		);
+		assert_eq!(
+			Currencies::free_balance(CurrencyId::Native, &TREASURY),
+			1_000 + VERIFIED_LISTING_FEE
+		);
+
+		assert_noop!(
+			CurrenciesRegistry::upgrade_listing_tier(Origin::signed(ALICE), currency_hash),
+			Error::<Runtime>::ListingAlreadyVerified
+		);
+
+		// The whole verified bond is unreserved when the currency is removed, the fee is not
+		// refunded.
+		assert_ok!(CurrenciesRegistry::remove_currency(Origin::signed(ALICE), currency_hash));
+		assert_eq!(CurrenciesRegistry::listings(currency_hash), None);
+		assert_eq!(Currencies::reserved_balance(CurrencyId::Native, &ALICE), 0);
+		assert_eq!(
+			Currencies::free_balance(CurrencyId::Native, &ALICE),
+			1_000 - VERIFIED_LISTING_FEE
+		);
+	});
+}
+
+#[test]
+fn removing_currency_keeps_other_reserves() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(CurrenciesRegistry::create_currency(
+			Origin::signed(ALICE),
+			"Polkadot".into(),
+			"dot".into(),
+			12,
+		));
+		let (currency_hash, _) = expected();
+		assert_eq!(
+			CurrenciesRegistry::named_reserves(ALICE, (LISTING_BOND_RESERVE, CurrencyId::Native)),
+			COMMUNITY_LISTING_BOND
+		);
+
+		// The funds reserved by another pallet are not unreserved with the listing bond.
+		assert_ok!(Currencies::reserve(CurrencyId::Native, &ALICE, 100));
+		assert_ok!(CurrenciesRegistry::remove_currency(Origin::signed(ALICE), currency_hash));
+		assert_eq!(Currencies::reserved_balance(CurrencyId::Native, &ALICE), 100);
+		assert_eq!(
+			CurrenciesRegistry::named_reserves(ALICE, (LISTING_BOND_RESERVE, CurrencyId::Native)),
+			0
+		);
+		assert_eq!(NamedReserves::<Runtime>::iter().count(), 0);
+	});
+}
+
+#[test]
+fn currency_acceptance_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(CurrenciesRegistry::create_currency(
+			Origin::signed(ALICE),
+			"Polkadot".into(),
+			"dot".into(),
+			12,
+		));
+		let (currency_hash, _) = expected();
+		let registered = CurrencyId::Registered(currency_hash);
+
+		assert!(CurrenciesRegistry::is_currency_accepted(&BOB, &CurrencyId::Native));
+		assert!(CurrenciesRegistry::is_currency_accepted(&BOB, &CurrencyId::ForeignAsset(1)));
+		assert!(!CurrenciesRegistry::is_currency_accepted(&BOB, &registered));
+
+		assert_ok!(CurrenciesRegistry::accept_currency(Origin::signed(BOB), currency_hash));
+		assert!(CurrenciesRegistry::is_currency_accepted(&BOB, &registered));
+		assert_eq!(registered.registered_hash(), Some(&currency_hash));
+		assert_eq!(registered.foreign_asset_id(), None);
+	});
+}
+
+#[test]
+fn genesis_currencies_work() {
+	ExtBuilder::default()
+		.currencies(vec![(ALICE, "Polkadot".into(), "dot".into(), 12)])
+		.build()
+		.execute_with(|| {
+			let (currency_hash, metadata) = expected();
+
+			assert_eq!(CurrenciesRegistry::currencies(currency_hash), Some(metadata));
+			assert_eq!(
+				Currencies::reserved_balance(CurrencyId::Native, &ALICE),
+				COMMUNITY_LISTING_BOND
+			);
+		});
+}
+
+#[test]
+fn try_state_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+
+		let (currency_hash, _) = expected();
+		assert_ok!(CurrenciesRegistry::create_currency(
+			Origin::signed(ALICE),
+			"Polkadot".into(),
+			"dot".into(),
+			12,
+		));
+		assert_ok!(CurrenciesRegistry::do_try_state());
+
+		// The listing bonds must be covered by the listing bond reserves.
+		Listings::<Runtime>::mutate(currency_hash, |listing| {
+			listing.as_mut().unwrap().bond += 1;
+		});
+		assert_eq!(
+			CurrenciesRegistry::do_try_state(),
+			Err("The listing bonds exceed the reserves of an issuer")
+		);
+		Listings::<Runtime>::mutate(currency_hash, |listing| {
+			listing.as_mut().unwrap().bond -= 1;
+		});
+
+		// The tracked reserves must be covered by the reserved balances.
+		NamedReserves::<Runtime>::insert(BOB, (LISTING_BOND_RESERVE, CurrencyId::Native), 1);
+		assert_eq!(
+			CurrenciesRegistry::do_try_state(),
+			Err("The tracked reserves of an account exceed its reserved balance")
+		);
+	});
+}
+
+#[test]
+fn migrate_to_v1_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		StorageVersion::new(0).put::<CurrenciesRegistry>();
+
+		migrations::v1::MigrateToV1::<Runtime>::on_runtime_upgrade();
+		assert_eq!(CurrenciesRegistry::on_chain_storage_version(), 1);
+	});
+}
+
+#[test]
+fn migrate_to_v2_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		StorageVersion::new(1).put::<CurrenciesRegistry>();
+
+		let old_currency_hashes =
+			vec![H256::repeat_byte(1), H256::repeat_byte(2), H256::repeat_byte(1)];
+		put_storage_value(
+			b"CurrenciesRegistry",
+			b"AcceptedCurrencies",
+			&Twox64Concat::hash(&BOB.encode()),
+			old_currency_hashes,
+		);
+
+		migrations::v2::MigrateToV2::<Runtime>::on_runtime_upgrade();
+		assert_eq!(CurrenciesRegistry::on_chain_storage_version(), 2);
+		assert_eq!(AcceptedCurrencies::<Runtime>::iter().count(), 2);
+		assert!(CurrenciesRegistry::is_currency_accepted(
+			&BOB,
+			&CurrencyId::Registered(H256::repeat_byte(1))
+		));
+		assert!(CurrenciesRegistry::is_currency_accepted(
+			&BOB,
+			&CurrencyId::Registered(H256::repeat_byte(2))
+		));
 	});
 }
+
+#[test]
+fn migrate_to_v3_works() {
+	ExtBuilder::default()
+		.currencies(vec![(ALICE, "Polkadot".into(), "dot".into(), 12)])
+		.build()
+		.execute_with(|| {
+			StorageVersion::new(2).put::<CurrenciesRegistry>();
+			let _ = NamedReserves::<Runtime>::remove_all(None);
+
+			migrations::v3::MigrateToV3::<Runtime>::on_runtime_upgrade();
+			assert_eq!(CurrenciesRegistry::on_chain_storage_version(), 3);
+			assert_eq!(
+				CurrenciesRegistry::on_chain_storage_version(),
+				CurrenciesRegistry::current_storage_version()
+			);
+			assert_eq!(
+				CurrenciesRegistry::named_reserves(
+					ALICE,
+					(LISTING_BOND_RESERVE, CurrencyId::Native)
+				),
+				COMMUNITY_LISTING_BOND
+			);
+		});
+}
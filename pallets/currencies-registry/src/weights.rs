@@ -0,0 +1,69 @@
+//! Weights for currencies_registry.
+//!
+//! The weights are hand-estimated, no benchmark has been run for them yet. The database reads and
+//! writes are counted from the storage items each call accesses, and the base and per-item
+//! execution times are rough estimates. They are to be replaced with the output of the benchmarks
+//! in `benchmarking.rs` on the reference hardware before a release.
+
+use frame_support::{
+	sp_std::marker::PhantomData,
+	traits::Get,
+	weights::{constants::RocksDbWeight, Weight},
+};
+
+/// The weights of the calls of currencies_registry.
+pub trait WeightInfo {
+	fn create_currency() -> Weight;
+	fn remove_currency() -> Weight;
+	fn accept_currency() -> Weight;
+	fn upgrade_listing_tier() -> Weight;
+}
+
+/// The estimated weights for currencies_registry, priced with the database weight of the runtime.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	fn create_currency() -> Weight {
+		(37_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(2))
+			.saturating_add(T::DbWeight::get().writes(3))
+	}
+	fn remove_currency() -> Weight {
+		(36_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(3))
+			.saturating_add(T::DbWeight::get().writes(3))
+	}
+	fn accept_currency() -> Weight {
+		(24_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	fn upgrade_listing_tier() -> Weight {
+		(52_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(4))
+			.saturating_add(T::DbWeight::get().writes(3))
+	}
+}
+
+/// The estimated weights priced with the RocksDB weight, used by the tests.
+impl WeightInfo for () {
+	fn create_currency() -> Weight {
+		(37_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(2))
+			.saturating_add(RocksDbWeight::get().writes(3))
+	}
+	fn remove_currency() -> Weight {
+		(36_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(3))
+			.saturating_add(RocksDbWeight::get().writes(3))
+	}
+	fn accept_currency() -> Weight {
+		(24_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(1))
+			.saturating_add(RocksDbWeight::get().writes(1))
+	}
+	fn upgrade_listing_tier() -> Weight {
+		(52_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(4))
+			.saturating_add(RocksDbWeight::get().writes(3))
+	}
+}
@@ -0,0 +1,28 @@
+//! # Dispute Resolution Runtime API
+//! The runtime API to query the dispute resolution from the clients, e.g. the SLA metrics and the
+//! cost estimates of the disputes through the `state_call` RPC.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+pub use primitives::{DisputeCostEstimate, DisputeSlaMetrics};
+
+sp_api::decl_runtime_apis! {
+	#[api_version(2)]
+	pub trait DisputeResolutionApi<Moment, Balance, Hash> where
+		Moment: Codec,
+		Balance: Codec,
+		Hash: Codec,
+	{
+		/// The counters of the resolved disputes and the timed out assignments, with the median
+		/// time to panel, to verdict and to payout of the latest resolved disputes.
+		fn sla_metrics() -> DisputeSlaMetrics<Moment>;
+
+		/// The fees to create, fight and escalate a dispute over the payment `rounds` times with
+		/// the current parameters. Returns `None` if there is no such payment.
+		fn estimate_dispute_cost(
+			payment_hash: Hash,
+			rounds: u32,
+		) -> Option<DisputeCostEstimate<Balance>>;
+	}
+}
@@ -0,0 +1,486 @@
+//! Benchmarking setup for dispute resolution.
+
+use super::*;
+
+use frame_benchmarking::{account, benchmarks, whitelisted_caller};
+use frame_support::{
+	sp_runtime::traits::{Hash, Zero},
+	sp_std::vec::Vec,
+	traits::{EnsureOrigin, Get},
+	BoundedVec,
+};
+use frame_system::RawOrigin;
+use orml_traits::{MultiCurrency, MultiReservableCurrency};
+use pallet_identities::IdentityType;
+use pallet_lrp::PaymentDescription;
+use primitives::{CurrencyId, ReserveIdentifier, DISPUTE_FEE_RESERVE, RESOLVER_BOND_RESERVE};
+
+type BalanceOf<T> =
+	<<T as Config>::Currency as MultiCurrency<<T as frame_system::Config>::AccountId>>::Balance;
+type MomentOf<T> = <T as pallet_timestamp::Config>::Moment;
+
+const SEED: u32 = 0;
+
+fn assert_last_event<T: Config>(generic_event: <T as Config>::Event) {
+	frame_system::Pallet::<T>::assert_last_event(generic_event.into());
+}
+
+fn funded_account<T: Config>(name: &'static str, index: u32) -> T::AccountId {
+	let who: T::AccountId = account(name, index, SEED);
+	let amount =
+		(T::DisputeFee::get() + T::ResolverBond::get() + T::ChallengeBond::get()) * 1_000u32.into();
+	<T as Config>::Currency::deposit(<T as Config>::FeeCurrency::get(), &who, amount).unwrap();
+	who
+}
+
+// Reserve the fee currency under the reserve identifier of the pallet.
+fn reserve_named<T: Config>(id: ReserveIdentifier, who: &T::AccountId, amount: BalanceOf<T>) {
+	let currency_id = <T as Config>::FeeCurrency::get();
+	<T as Config>::Currency::reserve(currency_id, who, amount).unwrap();
+	<NamedReserves<T>>::mutate(who, (id, currency_id), |reserved| *reserved += amount);
+}
+
+fn create_identity<T: pallet_identities::Config>(who: &T::AccountId) {
+	pallet_identities::Pallet::<T>::create_identity(
+		RawOrigin::Signed(who.clone()).into(),
+		b"Libra".to_vec(),
+		IdentityType::Individual,
+		[].to_vec(),
+	)
+	.unwrap();
+}
+
+// Create an active resolver.
+fn create_resolver<T: Config + pallet_resolvers::Config + pallet_identities::Config>(
+	index: u32,
+) -> T::AccountId {
+	let who = funded_account::<T>("resolver", index);
+	let stake = <T as pallet_resolvers::Config>::ActivationStakeAmount::get();
+	<T as pallet_resolvers::Config>::Currency::deposit(
+		<T as pallet_resolvers::Config>::FeeCurrency::get(),
+		&who,
+		stake * 10u32.into(),
+	)
+	.unwrap();
+	create_identity::<T>(&who);
+	pallet_resolvers::Pallet::<T>::join_resolvers_network(
+		RawOrigin::Signed(who.clone()).into(),
+		b"Resolver application".to_vec(),
+		stake,
+		[0; 32],
+	)
+	.unwrap();
+	who
+}
+
+// Create a review about an identity, returns the reviewed identity and the reviewer.
+fn create_review<T: Config + pallet_identities::Config>() -> (T::AccountId, T::AccountId) {
+	let reviewed = funded_account::<T>("reviewed", 0);
+	create_identity::<T>(&reviewed);
+	let reviewer: T::AccountId = account("reviewer", 0, SEED);
+	pallet_identities::Pallet::<T>::review_identity(
+		RawOrigin::Signed(reviewer.clone()).into(),
+		reviewed.clone(),
+		b"Bad seller".to_vec(),
+	)
+	.unwrap();
+	(reviewed, reviewer)
+}
+
+// Create an accepted payment from the payer to the payee.
+fn create_payment<T: Config + pallet_lrp::Config>(
+	payer: &T::AccountId,
+	payee: &T::AccountId,
+) -> T::Hash {
+	<T as pallet_lrp::Config>::Currency::deposit(CurrencyId::Native, payer, 1_000_000u32.into())
+		.unwrap();
+	pallet_lrp::Pallet::<T>::create_payment(
+		RawOrigin::Signed(payer.clone()).into(),
+		payee.clone(),
+		1_000u32.into(),
+		CurrencyId::Native,
+		PaymentDescription::Plain(b"Payment".to_vec()),
+		b"Receipt".to_vec(),
+		None,
+	)
+	.unwrap();
+	let payment_hash = *pallet_lrp::Pallet::<T>::payments_owned(payer).last().unwrap();
+	pallet_lrp::Pallet::<T>::accept_payment(RawOrigin::Signed(payee.clone()).into(), payment_hash)
+		.unwrap();
+	payment_hash
+}
+
+// Create a dispute over an accepted payment, returns the payer, the payee and the payment hash.
+fn create_dispute<T: Config + pallet_lrp::Config>() -> (T::AccountId, T::AccountId, T::Hash) {
+	let payer = funded_account::<T>("payer", 0);
+	let payee = funded_account::<T>("payee", 0);
+	let payment_hash = create_payment::<T>(&payer, &payee);
+	Pallet::<T>::create_dispute(
+		RawOrigin::Signed(payer.clone()).into(),
+		payment_hash,
+		b"Argument".to_vec(),
+	)
+	.unwrap();
+	(payer, payee, payment_hash)
+}
+
+// Fill the dispute history of the account.
+fn fill_disputes_owned<T: Config>(who: &T::AccountId) {
+	let payment_hashes: Vec<T::Hash> =
+		(0..T::MaxDisputesOwned::get()).map(|i| T::Hashing::hash_of(&i)).collect();
+	let payment_hashes: BoundedVec<T::Hash, T::MaxDisputesOwned> =
+		payment_hashes.try_into().unwrap();
+	<DisputesOwned<T>>::insert(who, payment_hashes);
+}
+
+// Assign the panel to the dispute, the dispute is evaluating by the panel.
+fn assign_panel<T: Config>(
+	payment_hash: &T::Hash,
+	panel: &[T::AccountId],
+	accepted: bool,
+	deadline: MomentOf<T>,
+) {
+	<Disputes<T>>::mutate(payment_hash, |dispute| {
+		if let Some(dispute) = dispute {
+			dispute.status = DisputeStatus::Evaluating;
+			dispute.resolvers = panel.to_vec();
+			dispute.assignments = panel
+				.iter()
+				.map(|resolver| Assignment::<T> { resolver: resolver.clone(), accepted, deadline })
+				.collect();
+		}
+	});
+	<FinalizingDisputes<T>>::kill();
+}
+
+// Resolve the dispute by the judgments of the panel.
+fn resolve_dispute<T: Config>(payment_hash: &T::Hash, panel: &[T::AccountId], outcome: Judgment) {
+	<Disputes<T>>::mutate(payment_hash, |dispute| {
+		if let Some(dispute) = dispute {
+			dispute.status = DisputeStatus::Resolved;
+			dispute.resolvers = panel.to_vec();
+			dispute.judgments =
+				panel.iter().map(|resolver| (resolver.clone(), outcome.clone())).collect();
+			dispute.outcome = outcome;
+			dispute.payer_fee = Zero::zero();
+			dispute.resolved_at = Some(<pallet_timestamp::Pallet<T>>::get());
+		}
+	});
+	<FinalizingDisputes<T>>::kill();
+}
+
+benchmarks! {
+	where_clause {
+		where T: pallet_lrp::Config + pallet_resolvers::Config + pallet_identities::Config
+	}
+
+	create_dispute {
+		let payer = funded_account::<T>("payer", 0);
+		let payee = funded_account::<T>("payee", 0);
+		let payment_hash = create_payment::<T>(&payer, &payee);
+		fill_disputes_owned::<T>(&payer);
+		fill_disputes_owned::<T>(&payee);
+	}: _(RawOrigin::Signed(payer.clone()), payment_hash, b"Argument".to_vec())
+	verify {
+		let fee = T::DisputeFee::get();
+		assert_last_event::<T>(
+			Event::DisputeCreated {
+				payer: payer.clone(),
+				payee,
+				payment_hash,
+				issuer: payer,
+				fee,
+				payer_handle: None,
+				payee_handle: None,
+			}
+			.into(),
+		);
+	}
+
+	fight_dispute {
+		create_resolver::<T>(0);
+		let (payer, payee, payment_hash) = create_dispute::<T>();
+	}: _(RawOrigin::Signed(payee.clone()), payment_hash, b"Argument".to_vec())
+	verify {
+		let fee = T::DisputeFee::get();
+		assert_last_event::<T>(
+			Event::DisputeFought { payer, payee: payee.clone(), payment_hash, fighter: payee, fee }
+				.into(),
+		);
+	}
+
+	escalate_dispute {
+		let (payer, payee, payment_hash) = create_dispute::<T>();
+	}: _(RawOrigin::Signed(payee.clone()), payment_hash)
+	verify {
+		assert_last_event::<T>(
+			Event::DisputeEscalated {
+				payer,
+				payee: payee.clone(),
+				payment_hash,
+				escalator: payee,
+				fee: T::DisputeFee::get(),
+				escalations: 1,
+			}
+			.into(),
+		);
+	}
+
+	// The payee escalated the dispute, so the fees of both parties are refunded.
+	withdraw_dispute {
+		let (payer, payee, payment_hash) = create_dispute::<T>();
+		Pallet::<T>::escalate_dispute(RawOrigin::Signed(payee).into(), payment_hash)?;
+	}: _(RawOrigin::Signed(payer.clone()), payment_hash)
+	verify {
+		assert_last_event::<T>(
+			Event::DisputeWithdrawn { payment_hash, issuer: payer, fee: T::DisputeFee::get() }.into(),
+		);
+	}
+
+	// The last judgment of the panel concludes the dispute.
+	propose_outcome {
+		let r in 1 .. Pallet::<T>::max_panel_size();
+		let (_, _, payment_hash) = create_dispute::<T>();
+		let panel: Vec<T::AccountId> = (0..r).map(|i| funded_account::<T>("panel", i)).collect();
+		assign_panel::<T>(&payment_hash, &panel, true, T::AssignmentJudgingTime::get());
+		let caller = panel[r as usize - 1].clone();
+		<Disputes<T>>::mutate(&payment_hash, |dispute| {
+			if let Some(dispute) = dispute {
+				dispute.assignments = dispute.assignments.split_off(r as usize - 1);
+				dispute.judgments = panel[..r as usize - 1]
+					.iter()
+					.map(|resolver| (resolver.clone(), Judgment::ReleaseFundToPayee))
+					.collect();
+			}
+		});
+		reserve_named::<T>(RESOLVER_BOND_RESERVE, &caller, T::ResolverBond::get());
+		let judgment = Judgment::ReleaseFundToPayee;
+		let rationale = T::Hashing::hash(b"rationale");
+	}: _(RawOrigin::Signed(caller.clone()), payment_hash, judgment, Some(rationale))
+	verify {
+		let dispute = Pallet::<T>::disputes(&payment_hash).unwrap();
+		assert_eq!(dispute.status, DisputeStatus::Finalizing);
+		assert_eq!(dispute.judgments.len() as u32, r);
+		assert_eq!(Pallet::<T>::judgment_rationales(&payment_hash, &caller), Some(rationale));
+	}
+
+	// The challenge of the largest panel before the last round.
+	challenge_outcome {
+		let max_panel_size = Pallet::<T>::max_panel_size();
+		for i in 0 .. max_panel_size {
+			create_resolver::<T>(i);
+		}
+		let (payer, _, payment_hash) = create_dispute::<T>();
+		let panel: Vec<T::AccountId> =
+			(0..(max_panel_size - 1) / 2).map(|i| account("panel", i, SEED)).collect();
+		resolve_dispute::<T>(&payment_hash, &panel, Judgment::ReleaseFundToPayee);
+	}: _(RawOrigin::Signed(payer.clone()), payment_hash, b"Argument".to_vec())
+	verify {
+		let bond = T::ChallengeBond::get();
+		let fee = T::DisputeFee::get() * max_panel_size.into();
+		assert_last_event::<T>(
+			Event::DisputeChallenged { payment_hash, challenger: payer, bond, fee }.into(),
+		);
+	}
+
+	finalize_dispute {
+		let r in 1 .. Pallet::<T>::max_panel_size();
+		let panel: Vec<T::AccountId> = (0..r).map(|i| create_resolver::<T>(i)).collect();
+		let (payer, payee, payment_hash) = create_dispute::<T>();
+		create_identity::<T>(&payer);
+		create_identity::<T>(&payee);
+		let fee = T::DisputeFee::get() * r.into();
+		reserve_named::<T>(DISPUTE_FEE_RESERVE, &payer, fee);
+		<Disputes<T>>::mutate(&payment_hash, |dispute| {
+			if let Some(dispute) = dispute {
+				dispute.resolvers = panel.clone();
+				dispute.judgments = panel
+					.iter()
+					.map(|resolver| (resolver.clone(), Judgment::ReleaseFundToPayee))
+					.collect();
+				dispute.outcome = Judgment::ReleaseFundToPayee;
+				dispute.payer_fee += fee;
+				dispute.expired_at = <pallet_timestamp::Pallet<T>>::get();
+			}
+		});
+		let caller: T::AccountId = whitelisted_caller();
+	}: _(RawOrigin::Signed(caller), payment_hash)
+	verify {
+		let dispute = Pallet::<T>::disputes(&payment_hash).unwrap();
+		assert_eq!(dispute.status, DisputeStatus::Resolved);
+	}
+
+	// The payer concedes the dispute judged by the panel.
+	accept_outcome {
+		let r in 1 .. Pallet::<T>::max_panel_size();
+		let panel: Vec<T::AccountId> = (0..r).map(|i| create_resolver::<T>(i)).collect();
+		let (payer, payee, payment_hash) = create_dispute::<T>();
+		create_identity::<T>(&payer);
+		create_identity::<T>(&payee);
+		let fee = T::DisputeFee::get() * r.into();
+		reserve_named::<T>(DISPUTE_FEE_RESERVE, &payer, fee);
+		<Disputes<T>>::mutate(&payment_hash, |dispute| {
+			if let Some(dispute) = dispute {
+				dispute.resolvers = panel.clone();
+				dispute.judgments = panel
+					.iter()
+					.map(|resolver| (resolver.clone(), Judgment::ReleaseFundToPayee))
+					.collect();
+				dispute.outcome = Judgment::ReleaseFundToPayee;
+				dispute.payer_fee += fee;
+			}
+		});
+	}: _(RawOrigin::Signed(payer), payment_hash)
+	verify {
+		let dispute = Pallet::<T>::disputes(&payment_hash).unwrap();
+		assert_eq!(dispute.status, DisputeStatus::Resolved);
+	}
+
+	accept_assignment {
+		let r in 1 .. Pallet::<T>::max_panel_size();
+		let (_, _, payment_hash) = create_dispute::<T>();
+		let panel: Vec<T::AccountId> = (0..r).map(|i| funded_account::<T>("panel", i)).collect();
+		assign_panel::<T>(&payment_hash, &panel, false, T::AssignmentAcceptanceTime::get());
+		let caller = panel[r as usize - 1].clone();
+		fill_disputes_owned::<T>(&caller);
+	}: _(RawOrigin::Signed(caller.clone()), payment_hash)
+	verify {
+		let bond = T::ResolverBond::get();
+		let deadline = <pallet_timestamp::Pallet<T>>::get() + T::AssignmentJudgingTime::get();
+		assert_last_event::<T>(
+			Event::AssignmentAccepted { payment_hash, resolver: caller, bond, deadline }.into(),
+		);
+	}
+
+	decline_assignment {
+		let r in 1 .. Pallet::<T>::max_panel_size();
+		create_resolver::<T>(0);
+		let (_, _, payment_hash) = create_dispute::<T>();
+		let panel: Vec<T::AccountId> = (0..r).map(|i| funded_account::<T>("panel", i)).collect();
+		assign_panel::<T>(&payment_hash, &panel, false, T::AssignmentAcceptanceTime::get());
+		let caller = panel[r as usize - 1].clone();
+	}: _(RawOrigin::Signed(caller.clone()), payment_hash)
+	verify {
+		assert_last_event::<T>(Event::AssignmentDeclined { payment_hash, resolver: caller }.into());
+	}
+
+	// All the accepted assignments of the panel are expired and replaced.
+	expire_assignments {
+		let r in 1 .. Pallet::<T>::max_panel_size();
+		for i in 0 .. r {
+			create_resolver::<T>(i);
+		}
+		let (_, _, payment_hash) = create_dispute::<T>();
+		let panel: Vec<T::AccountId> = (0..r).map(|i| funded_account::<T>("panel", i)).collect();
+		for resolver in panel.iter() {
+			reserve_named::<T>(RESOLVER_BOND_RESERVE, resolver, T::ResolverBond::get());
+		}
+		assign_panel::<T>(&payment_hash, &panel, true, Zero::zero());
+		let fund = Pallet::<T>::insurance_fund_account();
+		<T as Config>::Currency::deposit(
+			<T as Config>::FeeCurrency::get(),
+			&fund,
+			T::ResolverBond::get(),
+		)?;
+		let caller: T::AccountId = whitelisted_caller();
+	}: _(RawOrigin::Signed(caller), payment_hash)
+	verify {
+		let dispute = Pallet::<T>::disputes(&payment_hash).unwrap();
+		assert!(dispute.assignments.iter().all(|assignment| !panel.contains(&assignment.resolver)));
+	}
+
+	set_compensation_cap {
+		let origin = T::GovernanceOrigin::successful_origin();
+		let cap: BalanceOf<T> = 1_000u32.into();
+	}: _<T::Origin>(origin, cap)
+	verify {
+		assert_last_event::<T>(Event::CompensationCapUpdated { cap }.into());
+	}
+
+	compensate {
+		let (payer, _, payment_hash) = create_dispute::<T>();
+		resolve_dispute::<T>(&payment_hash, &[], Judgment::ReleaseFundToPayee);
+		let amount: BalanceOf<T> = T::DisputeFee::get();
+		<CompensationCap<T>>::put(amount);
+		let fund = Pallet::<T>::insurance_fund_account();
+		<T as Config>::Currency::deposit(
+			<T as Config>::FeeCurrency::get(),
+			&fund,
+			amount * 10u32.into(),
+		)?;
+		let origin = T::GovernanceOrigin::successful_origin();
+	}: _<T::Origin>(origin, payment_hash, payer.clone(), amount)
+	verify {
+		assert_last_event::<T>(
+			Event::Compensated { payment_hash, beneficiary: payer, amount }.into()
+		);
+	}
+
+	set_call_paused {
+		let origin = T::PauseOrigin::successful_origin();
+	}: _<T::Origin>(origin, PausableCall::CreateDispute, true)
+	verify {
+		assert_last_event::<T>(
+			Event::CallPauseUpdated { call: PausableCall::CreateDispute, paused: true }.into()
+		);
+	}
+
+	submit_private_evidence {
+		let e in 1 .. Pallet::<T>::max_panel_size();
+		let (_, payee, payment_hash) = create_dispute::<T>();
+		let panel: Vec<T::AccountId> = (0..e).map(|i| create_resolver::<T>(i)).collect();
+		assign_panel::<T>(&payment_hash, &panel, true, T::AssignmentJudgingTime::get());
+		let envelopes: Vec<(T::AccountId, Vec<u8>)> =
+			panel.iter().map(|resolver| (resolver.clone(), [0; 1_024].to_vec())).collect();
+	}: _(RawOrigin::Signed(payee), payment_hash, envelopes)
+	verify {
+		assert_eq!(Pallet::<T>::evidence_envelopes(&payment_hash).len(), e as usize);
+	}
+
+	dispute_review {
+		create_resolver::<T>(0);
+		let (account, reviewer) = create_review::<T>();
+	}: _(RawOrigin::Signed(account.clone()), reviewer.clone(), b"Argument".to_vec())
+	verify {
+		assert!(Pallet::<T>::review_disputes(&account, &reviewer).is_some());
+	}
+
+	resolve_review_dispute {
+		create_resolver::<T>(0);
+		let (account, reviewer) = create_review::<T>();
+		Pallet::<T>::dispute_review(
+			RawOrigin::Signed(account.clone()).into(),
+			reviewer.clone(),
+			b"Argument".to_vec(),
+		)?;
+		let resolver = Pallet::<T>::review_disputes(&account, &reviewer).unwrap().resolver;
+	}: _(RawOrigin::Signed(resolver), account.clone(), reviewer.clone(), ReviewVerdict::Remove)
+	verify {
+		let dispute = Pallet::<T>::review_disputes(&account, &reviewer).unwrap();
+		assert_eq!(dispute.verdict, Some(ReviewVerdict::Remove));
+	}
+
+	cancel_review_dispute {
+		create_resolver::<T>(0);
+		let (account, reviewer) = create_review::<T>();
+		Pallet::<T>::dispute_review(
+			RawOrigin::Signed(account.clone()).into(),
+			reviewer.clone(),
+			b"Argument".to_vec(),
+		)?;
+		<ReviewDisputes<T>>::mutate(&account, &reviewer, |dispute| {
+			if let Some(dispute) = dispute {
+				dispute.deadline = <pallet_timestamp::Pallet<T>>::get();
+			}
+		});
+	}: _(RawOrigin::Signed(account.clone()), reviewer.clone())
+	verify {
+		assert!(Pallet::<T>::review_disputes(&account, &reviewer).is_none());
+	}
+
+	impl_benchmark_test_suite!(
+		Pallet,
+		crate::mock::ExtBuilder::default().build(),
+		crate::mock::Runtime
+	);
+}
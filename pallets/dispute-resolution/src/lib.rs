@@ -11,19 +11,121 @@
 //! # Usage
 //!
 //! ## For payer or payee
-//! - `create_dispute` - Create an on-chain dispute to request refund. If the payee does not fight
-//!   against the dispute, the refund will be execute after `DisputeFinalizingTime`.
-//! - `fight_dispute` - Payee can fight against a dispute if make sure that invalid.
+//! - `create_dispute` - Create an on-chain dispute to request refund, or to request the release
+//!   of the payment by the payee, e.g. when the payer refuses to complete the payment after the
+//!   delivery. The initial outcome is in favor of the issuer. If the other party does not fight
+//!   against the dispute, the outcome will be execute after `DisputeFinalizingTime`.
+//! - `fight_dispute` - The other party can fight against a dispute if make sure that invalid. The resolvers
+//!   are drawn after `ResolverSelectionDelay` blocks, so the parties cannot grind the assignment.
+//!   A failed draw is retried in the next blocks up to `MaxResolverSelectionRetries` times, then the
+//!   dispute is back to finalizing with its outcome and the fee of the fighter is refunded.
+//!   The resolvers reading the language hint of the payment are preferred, or else the resolvers
+//!   sharing a language with both parties, or else with any of the parties. Among them, the
+//!   resolvers with the expertise in the category of the payment are preferred. The resolvers
+//!   with a conflict of interest with the parties, e.g. the members of their organizations, the
+//!   reviewers of their identities or their delegators, are not drawn.
 //! - `escalate_dispute` - If a party is unsatisfied with the dispute result, they can escalate the
-//!   dispute to more resolvers. Although there is no limit the escalate time, but the fee will
-//!   increase follow the number of resolvers that involved to dispute case.
+//!   dispute to more resolvers. The dispute can be escalated up to `MaxEscalationRounds` times and
+//!   the fee will increase follow the number of resolvers that involved to dispute case.
+//! - `withdraw_dispute` - The issuer can withdraw a dispute opened by mistake while it is finalizing
+//!   and no resolver is drawn for it. The escrowed fees are refunded and the dispute is removed
+//!   from the dispute history and the dispute records, so the payment is back to the state before
+//!   the dispute and can be released or disputed again.
+//! - `challenge_outcome` - The losing party can challenge a resolved dispute within
+//!   `ChallengeWindow` by locking `ChallengeBond` and submitting new evidence. The dispute is
+//!   re-evaluated by an enlarged panel of new resolvers. If the outcome is overturned, the funds are
+//!   clawed back where possible and the credibility of the previous resolvers is adjusted.
+//!   Otherwise, the bond is slashed to the insurance fund.
+//! - `submit_private_evidence` - Submit the evidence encrypted to the registered X25519 keys of the
+//!   assigned resolvers, e.g. the receipts which should not be exposed to the world. One envelope is
+//!   encrypted for each resolver, the ciphertexts are indexed off-chain and only the envelope hashes
+//!   are kept on-chain. The resolvers drawn later need new envelopes. The marketplace of the payment
+//!   can submit its evidence too.
+//! - `accept_outcome` - The losing party can accept the outcome of a finalizing dispute instead of
+//!   waiting for `DisputeFinalizingTime`, so the dispute is finalized immediately. The insurance
+//!   fund share of the resolver fees is refunded to the party who conceded.
+//! - `finalize_dispute` - Anyone can finalize a dispute as the outcome once it is out of the
+//!   finalizing time, so the settlement does not depend on the off-chain worker.
+//! - `expire_assignments` - Anyone can redraw the resolvers whose assignments are out of the
+//!   deadline. The bond of an accepted resolver who does not propose the judgment in time is
+//!   slashed.
+//! - `dispute_review` - The reviewed identity can dispute a defamatory review for
+//!   `ReviewDisputeFee`. A single resolver without a conflict of interest with the identity or the
+//!   reviewer is drawn at once and judges the review before `AssignmentAcceptanceTime` plus
+//!   `AssignmentJudgingTime`. A removed review is flagged `ContestedRemoved` in the identities
+//!   pallet and no longer counted. A review is judged once.
+//! - `cancel_review_dispute` - The disputing identity withdraws a review dispute not judged in
+//!   time, the fee is refunded.
+//! ## For governance
+//! - `set_compensation_cap` - Set the maximum amount of a compensation paid from the insurance
+//!   fund.
+//! - `compensate` - Pay a compensation from the insurance fund to the party harmed by a wrongly
+//!   decided dispute.
+//! - `set_call_paused` - Pause or unpause a call in an emergency, by `PauseOrigin`. The
+//!   assignment calls and the compensation are never paused, so the ongoing disputes can be
+//!   wound down.
 //! ## For selected resolvers
+//! - `accept_assignment` - Accept the assignment before `AssignmentAcceptanceTime`. The resolver
+//!   bond is locked until the judgment is proposed. The case is open in the resolvers network
+//!   until then, so the delegations to the resolver cannot be withdrawn to escape the slashing.
+//! - `decline_assignment` - Decline the assignment, another resolver will be drawn for the dispute.
 //! - `propose_outcome` - Propose the judgment after carefully evaluate the arguments and evidence
-//!   from both sides.
+//!   from both sides. The resolver can attach the content hash of its rationale to the judgment.
+//! - `resolve_review_dispute` - Keep or remove the disputed review. The resolver is paid the fee,
+//!   less the insurance fund share, whatever the verdict.
+//!
+//! # Service level
+//! The time from the creation of a dispute to the first panel, to the latest verdict and to the
+//! payout is recorded when the dispute is resolved, along with the counters of the resolved
+//! disputes and the assignments timed out. `sla_metrics` aggregates them to the medians over the
+//! latest `MaxSlaSamples` resolved disputes, also exposed by the `DisputeResolutionApi` runtime
+//! API, so the community can tune `DisputeFinalizingTime` and the assignment times from data.
+//!
+//! # Cost estimate
+//! `estimate_dispute_cost` computes the fees to create, fight and escalate a dispute over a
+//! payment for a number of rounds with the current parameters, also exposed by the
+//! `DisputeResolutionApi` runtime API, so the parties can see the cost before they commit funds.
+//!
+//! # Dispute records
+//! Each dispute is counted as opened against the other party of the issuer on creation, and as
+//! won by one party and lost by the other on resolution in the dispute records of the
+//! `IdentitiesManager`. A dispute overturned by a challenge is moved between the records of the
+//! parties, and a withdrawn dispute is not counted.
+//!
+//! # Precedents
+//! A fought dispute over a categorized payment is indexed as a precedent of the category when it
+//! is resolved, with the outcome and the rationales of the resolvers who judged as the outcome.
+//! The latest `MaxPrecedentsPerCategory` precedents are kept for each category and can be
+//! searched by outcome with `precedents_of`, so the future panels and parties can predict the
+//! outcome of the similar disputes. The precedent of a challenged dispute is replaced by the
+//! resolution of the challenge.
+//!
+//! # Marketplaces
+//! The marketplace a disputed payment was created through is an interested party of the dispute,
+//! kept in `DisputeStakeholders` with its share of the funds, the fee it is due on the completion
+//! of the payment. It can submit private evidence to the panel but cannot fight, escalate or
+//! challenge the dispute. If the funds are released to the payee, the share is paid to the
+//! marketplace out of them, while the payer is refunded in full otherwise. A share already paid
+//! is not clawed back by a challenge.
+//!
+//! # Insurance fund
+//! The insurance fund is funded by `InsuranceFundShare` of each resolver fee and the slashed
+//! resolver bonds.
+//!
+//! # Slash claims
+//! When the bond of a resolver is slashed for not proposing the judgment in time,
+//! `SlashCompensationShare` of the slashed bond is recorded as a claim of the dispute. The claim is
+//! paid from the insurance fund to the winner of the dispute when it is resolved, as the winner is
+//! the party harmed by the delayed resolution. The rest of the slashed bond stays in the insurance
+//! fund.
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
 pub use pallet::*;
+pub use weights::WeightInfo;
+
+pub mod migrations;
+pub mod weights;
 
 #[cfg(test)]
 mod mock;
@@ -36,9 +138,15 @@ mod benchmarking;
 
 #[frame_support::pallet]
 pub mod pallet {
+	use crate::WeightInfo;
 	use frame_support::{
-		dispatch::DispatchResult, log, pallet_prelude::*, sp_runtime::traits::Hash,
-		sp_std::vec::Vec,
+		dispatch::DispatchResult,
+		log,
+		pallet_prelude::*,
+		sp_runtime::traits::{Hash, One, Saturating, Zero},
+		sp_std::{mem, vec::Vec},
+		traits::BalanceStatus,
+		transactional, PalletId,
 	};
 	use frame_system::pallet_prelude::*;
 	use orml_traits::{MultiCurrency, MultiReservableCurrency};
@@ -46,10 +154,15 @@ pub mod pallet {
 	use pallet_lrp::PaymentProtocol;
 	use pallet_resolvers::ResolversNetwork;
 	use pallet_timestamp::{self as timestamp};
-	use primitives::{Credibility, CurrencyId};
+	pub use primitives::Judgment;
+	use primitives::{
+		indexed_content_key, Credibility, CurrencyId, DisputeCostEstimate, DisputeSlaMetrics,
+		EncryptionKey, LanguageCode, PaymentCategory, ReserveIdentifier, CHALLENGE_BOND_RESERVE,
+		DISPUTE_FEE_RESERVE, RESOLVER_BOND_RESERVE, REVIEW_DISPUTE_FEE_RESERVE,
+	};
 	use scale_info::TypeInfo;
 	use sp_io::offchain_index;
-	use sp_runtime::{RuntimeDebug, SaturatedConversion};
+	use sp_runtime::{traits::AccountIdConversion, Permill, RuntimeDebug, SaturatedConversion};
 
 	#[cfg(feature = "std")]
 	use serde::{Deserialize, Serialize};
@@ -58,9 +171,23 @@ pub mod pallet {
 	pub trait Config: frame_system::Config + timestamp::Config {
 		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
 		type Currency: MultiReservableCurrency<Self::AccountId, CurrencyId = CurrencyId<Self::Hash>>;
+		/// The currency of the resolver fees, the resolver and challenge bonds and the insurance
+		/// fund, independent of the payment currency.
+		#[pallet::constant]
+		type FeeCurrency: Get<CurrencyId<Self::Hash>>;
 		type PaymentProtocol: PaymentProtocol<Self::Hash, Self::AccountId, BalanceOf<Self>>;
 		type ResolversNetwork: ResolversNetwork<Self::AccountId, Self::Hash>;
 		type IdentitiesManager: IdentitiesManager<Self::AccountId>;
+		/// The origin which can manage the insurance fund.
+		type GovernanceOrigin: EnsureOrigin<Self::Origin>;
+		/// The origin which can pause and unpause the calls in an emergency.
+		type PauseOrigin: EnsureOrigin<Self::Origin>;
+		/// The pallet id, used for deriving the insurance fund account.
+		#[pallet::constant]
+		type PalletId: Get<PalletId>;
+		/// The share of each resolver fee that is paid to the insurance fund.
+		#[pallet::constant]
+		type InsuranceFundShare: Get<Permill>;
 		/// The finalizing dispute will be finalized after `DisputeFinalizingTime`. No more actions
 		/// can take with the payment after that.
 		#[pallet::constant]
@@ -76,6 +203,53 @@ pub mod pallet {
 		/// dispute is resolved
 		#[pallet::constant]
 		type CredibilityLoss: Get<Credibility>;
+		/// The number of blocks to wait before drawing resolvers for a fought dispute. The
+		/// randomness used to draw is unknown at the time the dispute is fought.
+		#[pallet::constant]
+		type ResolverSelectionDelay: Get<Self::BlockNumber>;
+		/// The number of blocks a failed draw of the resolvers is retried before the request of
+		/// the panel is cancelled.
+		#[pallet::constant]
+		type MaxResolverSelectionRetries: Get<u32>;
+		/// The time a selected resolver has to accept or decline the assignment.
+		#[pallet::constant]
+		type AssignmentAcceptanceTime: Get<MomentOf<Self>>;
+		/// The time a resolver has to propose the judgment after accepting the assignment.
+		#[pallet::constant]
+		type AssignmentJudgingTime: Get<MomentOf<Self>>;
+		/// The bond locked when a resolver accepts an assignment. It is slashed if the resolver
+		/// does not propose the judgment in time.
+		#[pallet::constant]
+		type ResolverBond: Get<BalanceOf<Self>>;
+		/// The time the losing party can challenge a resolved dispute.
+		#[pallet::constant]
+		type ChallengeWindow: Get<MomentOf<Self>>;
+		/// The bond locked to challenge a resolved dispute. It is slashed to the insurance fund if
+		/// the outcome is not overturned.
+		#[pallet::constant]
+		type ChallengeBond: Get<BalanceOf<Self>>;
+		/// The maximum number of disputes kept in the dispute history of an account.
+		#[pallet::constant]
+		type MaxDisputesOwned: Get<u32>;
+		/// The maximum number of times a dispute can be escalated. Beyond it, the losing party can
+		/// only challenge the outcome or appeal to the governance.
+		#[pallet::constant]
+		type MaxEscalationRounds: Get<u32>;
+		/// The number of the latest resolved disputes the median SLA metrics are computed over.
+		#[pallet::constant]
+		type MaxSlaSamples: Get<u32>;
+		/// The number of the latest precedents kept for each payment category.
+		#[pallet::constant]
+		type MaxPrecedentsPerCategory: Get<u32>;
+		/// The share of a slashed resolver bond paid to the winner of the dispute when it is
+		/// resolved.
+		#[pallet::constant]
+		type SlashCompensationShare: Get<Permill>;
+		/// The fee to dispute a review, paid to the single resolver of the review dispute.
+		#[pallet::constant]
+		type ReviewDisputeFee: Get<BalanceOf<Self>>;
+		/// Weight information for extrinsics in this pallet.
+		type WeightInfo: WeightInfo;
 	}
 
 	type AccountOf<T> = <T as frame_system::Config>::AccountId;
@@ -88,13 +262,6 @@ pub mod pallet {
 		fn get_dispute_resolvers(payment_hash: Hash) -> Result<Vec<AccountId>, DispatchError>;
 	}
 
-	#[derive(Clone, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo)]
-	#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
-	pub enum Judgment {
-		ReleaseFundToPayer,
-		ReleaseFundToPayee,
-	}
-
 	#[derive(Clone, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo)]
 	#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
 	pub enum DisputeStatus {
@@ -103,6 +270,19 @@ pub mod pallet {
 		Resolved,
 	}
 
+	/// The calls which can be paused in an emergency.
+	#[derive(Clone, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo)]
+	#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+	pub enum PausableCall {
+		CreateDispute,
+		FightDispute,
+		EscalateDispute,
+		ProposeOutcome,
+		ChallengeOutcome,
+		/// Both the `finalize_dispute` call and the finalization by the off-chain worker.
+		FinalizeDispute,
+	}
+
 	#[derive(Clone, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo)]
 	#[scale_info(skip_type_params(T))]
 	pub struct Argument<T: Config> {
@@ -110,6 +290,49 @@ pub mod pallet {
 		pub content_hash: HashOf<T>,
 	}
 
+	/// The evidence of a party encrypted to the key of a panel resolver, the ciphertext is indexed
+	/// off-chain by the content hash.
+	#[derive(Clone, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo)]
+	#[scale_info(skip_type_params(T))]
+	pub struct EvidenceEnvelope<T: Config> {
+		pub provider: AccountOf<T>,
+		pub resolver: AccountOf<T>,
+		/// The key of the resolver at the submission, the resolver can rotate its key later.
+		pub encryption_key: EncryptionKey,
+		pub content_hash: HashOf<T>,
+	}
+
+	#[derive(Clone, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo)]
+	#[scale_info(skip_type_params(T))]
+	pub struct Assignment<T: Config> {
+		pub resolver: AccountOf<T>,
+		pub accepted: bool,
+		/// The assignment need to be accepted, or judged if it is accepted, before the deadline.
+		pub deadline: MomentOf<T>,
+	}
+
+	#[derive(Clone, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo)]
+	#[scale_info(skip_type_params(T))]
+	pub struct Challenge<T: Config> {
+		pub challenger: AccountOf<T>,
+		pub bond: BalanceOf<T>,
+		/// The outcome, resolvers and judgments of the challenged resolution.
+		pub original_outcome: Judgment,
+		pub original_resolvers: Vec<AccountOf<T>>,
+		pub original_judgments: Vec<(AccountOf<T>, Judgment)>,
+	}
+
+	/// The party requesting a panel for the dispute, by fighting it or by challenging its
+	/// outcome, with the fee it escrowed for the panel.
+	#[derive(Clone, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo)]
+	#[scale_info(skip_type_params(T))]
+	pub struct SelectionRequest<T: Config> {
+		pub requester: AccountOf<T>,
+		pub fee: BalanceOf<T>,
+		/// The number of failed draws of the resolvers.
+		pub retries: u32,
+	}
+
 	#[derive(Clone, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo)]
 	#[scale_info(skip_type_params(T))]
 	pub struct Dispute<T: Config> {
@@ -118,9 +341,80 @@ pub mod pallet {
 		pub expired_at: MomentOf<T>,
 		pub arguments: Vec<Argument<T>>,
 		pub resolvers: Vec<AccountOf<T>>,
-		pub fee: BalanceOf<T>,
+		pub assignments: Vec<Assignment<T>>,
+		/// The fee escrowed by the payer to pay the resolvers.
+		pub payer_fee: BalanceOf<T>,
+		/// The fee escrowed by the payee to pay the resolvers.
+		pub payee_fee: BalanceOf<T>,
 		pub judgments: Vec<(AccountOf<T>, Judgment)>,
 		pub outcome: Judgment,
+		/// The number of times the dispute is escalated.
+		pub escalations: u32,
+		pub resolved_at: Option<MomentOf<T>>,
+		pub challenge: Option<Challenge<T>>,
+	}
+
+	/// The time a dispute entered its phases, the timeline is taken when the dispute is resolved.
+	#[derive(Clone, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo)]
+	#[scale_info(skip_type_params(T))]
+	pub struct DisputeTimeline<T: Config> {
+		pub created_at: MomentOf<T>,
+		/// The first panel drawn, `None` if the dispute is not fought.
+		pub panel_drawn_at: Option<MomentOf<T>>,
+		/// The latest verdict of the panels, the verdict of an escalation replaces the previous one.
+		pub verdict_at: Option<MomentOf<T>>,
+	}
+
+	/// The phase durations of a resolved dispute.
+	#[derive(Clone, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo)]
+	#[scale_info(skip_type_params(T))]
+	pub struct SlaSample<T: Config> {
+		pub time_to_panel: Option<MomentOf<T>>,
+		pub time_to_verdict: Option<MomentOf<T>>,
+		pub time_to_payout: MomentOf<T>,
+	}
+
+	/// A fought dispute resolved in a payment category, indexed for the future panels and parties
+	/// to predict the outcome of the similar disputes.
+	#[derive(Clone, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo)]
+	#[scale_info(skip_type_params(T))]
+	pub struct Precedent<T: Config> {
+		pub payment_hash: HashOf<T>,
+		pub outcome: Judgment,
+		/// The rationales of the resolvers who judged as the outcome, the contents are indexed
+		/// off-chain by the content hashes.
+		pub rationales: Vec<HashOf<T>>,
+		pub resolved_at: MomentOf<T>,
+	}
+
+	/// The dispute of an identity over a review about it, judged by a single resolver.
+	#[derive(Clone, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo)]
+	#[scale_info(skip_type_params(T))]
+	pub struct ReviewDispute<T: Config> {
+		pub resolver: AccountOf<T>,
+		/// The fee escrowed by the reviewed identity.
+		pub fee: BalanceOf<T>,
+		/// The argument of the reviewed identity, the content is indexed off-chain by the hash.
+		pub content_hash: HashOf<T>,
+		/// The review has to be judged before the deadline.
+		pub deadline: MomentOf<T>,
+		/// The verdict of the resolver, `None` until the review is judged.
+		pub verdict: Option<ReviewVerdict>,
+	}
+
+	#[derive(Clone, Copy, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo)]
+	#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+	pub enum ReviewVerdict {
+		Keep,
+		Remove,
+	}
+
+	#[derive(Clone, Encode, Decode, Default, PartialEq, RuntimeDebug, TypeInfo)]
+	pub struct SlaCounts {
+		pub resolved: u32,
+		pub fought: u32,
+		pub acceptance_timeouts: u32,
+		pub judging_timeouts: u32,
 	}
 
 	#[derive(Clone, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo)]
@@ -130,7 +424,11 @@ pub mod pallet {
 		release_to_payee: u64,
 	}
 
+	/// The current storage version, see [`crate::migrations`].
+	pub(crate) const STORAGE_VERSION: StorageVersion = StorageVersion::new(2);
+
 	#[pallet::pallet]
+	#[pallet::storage_version(STORAGE_VERSION)]
 	#[pallet::without_storage_info]
 	pub struct Pallet<T>(_);
 
@@ -142,15 +440,176 @@ pub mod pallet {
 	#[pallet::getter(fn disputes)]
 	pub(super) type Disputes<T: Config> = StorageMap<_, Twox64Concat, HashOf<T>, Dispute<T>>;
 
+	/// The funds reserved by the pallet by account, reserve identifier and currency. Only the
+	/// tracked funds are unreserved, so the reserves of the other pallets are never consumed.
+	#[pallet::storage]
+	#[pallet::getter(fn named_reserves)]
+	pub(super) type NamedReserves<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		AccountOf<T>,
+		Twox64Concat,
+		(ReserveIdentifier, CurrencyId<T::Hash>),
+		BalanceOf<T>,
+		ValueQuery,
+	>;
+
+	/// The private evidence of the disputes encrypted to the panel resolvers.
+	#[pallet::storage]
+	#[pallet::getter(fn evidence_envelopes)]
+	pub(super) type EvidenceEnvelopes<T: Config> =
+		StorageMap<_, Twox64Concat, HashOf<T>, Vec<EvidenceEnvelope<T>>, ValueQuery>;
+
+	/// The interested parties of the disputes other than the payer and the payee, e.g. the
+	/// marketplace of the payment, with their shares of the funds released to the payee.
+	#[pallet::storage]
+	#[pallet::getter(fn dispute_stakeholders)]
+	pub(super) type DisputeStakeholders<T: Config> =
+		StorageMap<_, Twox64Concat, HashOf<T>, Vec<(AccountOf<T>, BalanceOf<T>)>, ValueQuery>;
+
+	/// The disputes waiting for resolvers to be drawn at the block.
+	#[pallet::storage]
+	#[pallet::getter(fn pending_resolver_selections)]
+	pub(super) type PendingResolverSelections<T: Config> =
+		StorageMap<_, Twox64Concat, T::BlockNumber, Vec<HashOf<T>>, ValueQuery>;
+
+	/// The number of seats dropped from the panel of the disputes being evaluated. A panel which
+	/// lost seats is concluded by the super-majority of the collected judgments.
+	#[pallet::storage]
+	#[pallet::getter(fn dropped_panel_seats)]
+	pub(super) type DroppedPanelSeats<T: Config> =
+		StorageMap<_, Twox64Concat, HashOf<T>, u32, ValueQuery>;
+
+	/// The requests of the panels waiting for the resolvers to be drawn.
+	#[pallet::storage]
+	#[pallet::getter(fn selection_requests)]
+	pub(super) type SelectionRequests<T: Config> =
+		StorageMap<_, Twox64Concat, HashOf<T>, SelectionRequest<T>>;
+
+	/// The maximum amount of a compensation paid from the insurance fund.
+	#[pallet::storage]
+	#[pallet::getter(fn compensation_cap)]
+	pub(super) type CompensationCap<T: Config> = StorageValue<_, BalanceOf<T>, ValueQuery>;
+
+	/// The compensation paid for the wrongly decided disputes.
+	#[pallet::storage]
+	#[pallet::getter(fn compensations)]
+	pub(super) type Compensations<T: Config> = StorageMap<_, Twox64Concat, HashOf<T>, BalanceOf<T>>;
+
+	/// The share of the slashed resolver bonds claimed by the winner of an unresolved dispute.
+	#[pallet::storage]
+	#[pallet::getter(fn slash_claims)]
+	pub(super) type SlashClaims<T: Config> =
+		StorageMap<_, Twox64Concat, HashOf<T>, BalanceOf<T>, ValueQuery>;
+
+	/// The latest disputes an account participated in as a payment party or a resolver.
+	#[pallet::storage]
+	#[pallet::getter(fn disputes_owned)]
+	pub(super) type DisputesOwned<T: Config> = StorageMap<
+		_,
+		Twox64Concat,
+		AccountOf<T>,
+		BoundedVec<HashOf<T>, T::MaxDisputesOwned>,
+		ValueQuery,
+	>;
+
+	/// The phase timeline of the unresolved disputes.
+	#[pallet::storage]
+	#[pallet::getter(fn dispute_timelines)]
+	pub(super) type DisputeTimelines<T: Config> =
+		StorageMap<_, Twox64Concat, HashOf<T>, DisputeTimeline<T>>;
+
+	/// The counters of the resolved disputes and the timed out assignments.
+	#[pallet::storage]
+	#[pallet::getter(fn sla_counters)]
+	pub(super) type SlaCounters<T: Config> = StorageValue<_, SlaCounts, ValueQuery>;
+
+	/// The phase durations of the latest resolved disputes, the oldest sample is dropped if full.
+	#[pallet::storage]
+	#[pallet::getter(fn sla_samples)]
+	pub(super) type SlaSamples<T: Config> =
+		StorageValue<_, BoundedVec<SlaSample<T>, T::MaxSlaSamples>, ValueQuery>;
+
+	/// The rationale a resolver attached to its judgment of a dispute, by payment hash and resolver.
+	#[pallet::storage]
+	#[pallet::getter(fn judgment_rationales)]
+	pub(super) type JudgmentRationales<T: Config> =
+		StorageDoubleMap<_, Twox64Concat, HashOf<T>, Twox64Concat, AccountOf<T>, HashOf<T>>;
+
+	/// The latest precedents of each payment category, the oldest precedent is dropped if full.
+	#[pallet::storage]
+	#[pallet::getter(fn precedents)]
+	pub(super) type Precedents<T: Config> = StorageMap<
+		_,
+		Twox64Concat,
+		PaymentCategory,
+		BoundedVec<Precedent<T>, T::MaxPrecedentsPerCategory>,
+		ValueQuery,
+	>;
+
+	/// The review disputes by reviewed identity and reviewer. The judged disputes are kept, so a
+	/// review is judged once.
+	#[pallet::storage]
+	#[pallet::getter(fn review_disputes)]
+	pub(super) type ReviewDisputes<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		AccountOf<T>,
+		Twox64Concat,
+		AccountOf<T>,
+		ReviewDispute<T>,
+	>;
+
+	/// The calls paused in an emergency.
+	#[pallet::storage]
+	#[pallet::getter(fn paused_calls)]
+	pub(super) type PausedCalls<T: Config> =
+		StorageMap<_, Twox64Concat, PausableCall, bool, ValueQuery>;
+
 	#[pallet::event]
 	#[pallet::generate_deposit(pub(super) fn deposit_event)]
 	pub enum Event<T: Config> {
-		/// A dispute is issued by payer
-		DisputeCreated { payer: AccountOf<T>, payee: AccountOf<T>, payment_hash: HashOf<T> },
+		/// A dispute is issued by payer or payee, the fee is escrowed from the issuer
+		DisputeCreated {
+			payer: AccountOf<T>,
+			payee: AccountOf<T>,
+			payment_hash: HashOf<T>,
+			issuer: AccountOf<T>,
+			fee: BalanceOf<T>,
+			/// The unexpired handles of the parties in the identities pallet.
+			payer_handle: Option<Vec<u8>>,
+			payee_handle: Option<Vec<u8>>,
+		},
+		/// The marketplace of the disputed payment takes part in the dispute for its share of the
+		/// funds released to the payee
+		DisputeStakeholderAdded {
+			payment_hash: HashOf<T>,
+			stakeholder: AccountOf<T>,
+			share: BalanceOf<T>,
+		},
+		/// A dispute is withdrawn by the issuer before any resolver is drawn, the escrowed fees are
+		/// refunded
+		DisputeWithdrawn { payment_hash: HashOf<T>, issuer: AccountOf<T>, fee: BalanceOf<T> },
+		/// The losing party accepted the outcome, the dispute is finalized without waiting for the
+		/// finalizing time
+		OutcomeAccepted { payment_hash: HashOf<T>, party: AccountOf<T>, outcome: Judgment },
 		/// A dispute is fought by payer or payee to against the outcome
-		DisputeFought { payer: AccountOf<T>, payee: AccountOf<T>, payment_hash: HashOf<T> },
+		DisputeFought {
+			payer: AccountOf<T>,
+			payee: AccountOf<T>,
+			payment_hash: HashOf<T>,
+			fighter: AccountOf<T>,
+			fee: BalanceOf<T>,
+		},
 		/// A party unsatisfied with the outcome of resolver(s) and open to escalate dispute
-		DisputeEscalated { payer: AccountOf<T>, payee: AccountOf<T>, payment_hash: HashOf<T> },
+		DisputeEscalated {
+			payer: AccountOf<T>,
+			payee: AccountOf<T>,
+			payment_hash: HashOf<T>,
+			escalator: AccountOf<T>,
+			fee: BalanceOf<T>,
+			escalations: u32,
+		},
 		/// A dispute is finalized with the outcome after finalizing time
 		DisputeResolved {
 			payment_hash: HashOf<T>,
@@ -158,13 +617,102 @@ pub mod pallet {
 			payee: AccountOf<T>,
 			amount: BalanceOf<T>,
 			currency_id: CurrencyId<T::Hash>,
+			outcome: Judgment,
+		},
+		/// A selected resolver proposed the judgment, the judgment is not revealed by the event
+		JudgmentProposed {
+			payment_hash: HashOf<T>,
+			resolver: AccountOf<T>,
+			rationale: Option<HashOf<T>>,
+		},
+		/// A party submitted the argument and evidence, the content is indexed off-chain by the
+		/// content hash
+		EvidenceSubmitted {
+			payment_hash: HashOf<T>,
+			provider: AccountOf<T>,
+			content_hash: HashOf<T>,
 		},
+		/// A party submitted the evidence encrypted to the panel resolvers, the ciphertexts are
+		/// indexed off-chain by the content hashes
+		PrivateEvidenceSubmitted {
+			payment_hash: HashOf<T>,
+			provider: AccountOf<T>,
+			envelopes: Vec<(AccountOf<T>, HashOf<T>)>,
+		},
+		/// The resolvers are drawn to the panel of the dispute
+		DisputePanelDrawn { payment_hash: HashOf<T>, resolvers: Vec<AccountOf<T>> },
+		/// A selected resolver accepted the assignment, the judgment is due at `deadline`
+		AssignmentAccepted {
+			payment_hash: HashOf<T>,
+			resolver: AccountOf<T>,
+			bond: BalanceOf<T>,
+			deadline: MomentOf<T>,
+		},
+		/// A selected resolver declined the assignment
+		AssignmentDeclined { payment_hash: HashOf<T>, resolver: AccountOf<T> },
+		/// An assignment is out of the deadline and the resolver is replaced
+		AssignmentExpired { payment_hash: HashOf<T>, resolver: AccountOf<T>, slashed: BalanceOf<T> },
+		/// The maximum amount of a compensation is updated
+		CompensationCapUpdated { cap: BalanceOf<T> },
+		/// A compensation is paid from the insurance fund for a wrongly decided dispute
+		Compensated { payment_hash: HashOf<T>, beneficiary: AccountOf<T>, amount: BalanceOf<T> },
+		/// The losing party challenged the outcome of a resolved dispute
+		DisputeChallenged {
+			payment_hash: HashOf<T>,
+			challenger: AccountOf<T>,
+			bond: BalanceOf<T>,
+			fee: BalanceOf<T>,
+		},
+		/// The outcome of a challenged dispute is overturned
+		DisputeOverturned { payment_hash: HashOf<T>, outcome: Judgment, clawback: BalanceOf<T> },
+		/// No resolver could be drawn for the dispute after the retries, the dispute is back to the
+		/// state before the panel was requested and the fee is refunded to the requester
+		ResolverSelectionCancelled {
+			payment_hash: HashOf<T>,
+			requester: AccountOf<T>,
+			fee: BalanceOf<T>,
+		},
+		/// The panel size is capped at the available resolvers
+		PanelCapped { payment_hash: HashOf<T>, size: u32 },
+		/// The seat is dropped from the panel as there is no available resolver
+		PanelSeatDropped { payment_hash: HashOf<T>, resolver: AccountOf<T> },
+		/// The dispute is concluded by the collected judgments of an incomplete panel
+		IncompletePanelConcluded { payment_hash: HashOf<T>, outcome: Judgment },
+		/// A resolver is paid from the escrowed fee
+		ResolverFeePaid { payment_hash: HashOf<T>, resolver: AccountOf<T>, amount: BalanceOf<T> },
+		/// A share of the resolvers fee is paid to the insurance fund
+		InsuranceFundDeposited { payment_hash: HashOf<T>, amount: BalanceOf<T> },
+		/// The escrowed fee is refunded to the party
+		DisputeFeeRefunded { payment_hash: HashOf<T>, account: AccountOf<T>, amount: BalanceOf<T> },
+		/// A call is paused or unpaused
+		CallPauseUpdated { call: PausableCall, paused: bool },
+		/// A resolved dispute is indexed as a precedent of the payment category
+		PrecedentRecorded { payment_hash: HashOf<T>, category: PaymentCategory, outcome: Judgment },
+		/// A share of a slashed resolver bond is claimed for the winner of the dispute
+		SlashClaimRecorded { payment_hash: HashOf<T>, resolver: AccountOf<T>, amount: BalanceOf<T> },
+		/// The slash claim of a resolved dispute is paid to the winner
+		SlashClaimPaid { payment_hash: HashOf<T>, beneficiary: AccountOf<T>, amount: BalanceOf<T> },
+		/// The reviewed identity disputes a review, the resolver has to judge it before `deadline`
+		ReviewDisputed {
+			account: AccountOf<T>,
+			reviewer: AccountOf<T>,
+			resolver: AccountOf<T>,
+			fee: BalanceOf<T>,
+			deadline: MomentOf<T>,
+		},
+		/// The resolver judged the disputed review and is paid the fee
+		ReviewDisputeResolved {
+			account: AccountOf<T>,
+			reviewer: AccountOf<T>,
+			resolver: AccountOf<T>,
+			verdict: ReviewVerdict,
+		},
+		/// A review dispute not judged in time is cancelled, the fee is refunded
+		ReviewDisputeCancelled { account: AccountOf<T>, reviewer: AccountOf<T>, fee: BalanceOf<T> },
 	}
 
 	#[pallet::error]
 	pub enum Error<T> {
-		/// The origin does not have appropriate access rights
-		AccessDenied,
 		/// The state of the payment is not allow to dispute
 		DisputeNotAccepted,
 		/// Their is no dispute related with the payment hash
@@ -173,10 +721,112 @@ pub mod pallet {
 		ActionForOnlyFinalizingDispute,
 		/// The balance is not enough to pay the fee
 		InsufficientBalance,
+		/// The dispute is not finalizing or still in the finalizing time
+		DisputeNotFinalizable,
+		/// The account is not assigned to the dispute or already proposed the judgment
+		AssignmentNotFound,
+		/// The assignment is already accepted
+		AssignmentAlreadyAccepted,
+		/// The assignment need to be accepted before proposing the judgment
+		AssignmentNotAccepted,
+		/// The assignment is out of the deadline
+		AssignmentExpired,
+		/// There is no assignment out of the deadline
+		NoExpiredAssignment,
+		/// Only resolved dispute can be compensated
+		DisputeNotResolved,
+		/// The dispute is already compensated
+		DisputeAlreadyCompensated,
+		/// The compensation exceeds the compensation cap
+		CompensationExceedsCap,
+		/// The insurance fund is not enough to pay the compensation
+		InsufficientInsuranceFund,
+		/// The dispute is already challenged
+		DisputeAlreadyChallenged,
+		/// The dispute is out of the challenge window
+		ChallengeWindowClosed,
+		/// The dispute can be withdrawn only before any resolver is drawn
+		DisputeNotWithdrawable,
+		/// The dispute reached the maximum escalation rounds
+		MaxEscalationRoundsReached,
+		/// The call is paused in an emergency
+		CallPaused,
+		/// The dispute is not evaluating by a panel
+		DisputeNotEvaluating,
+		/// The recipient of an envelope is not a resolver of the panel or has more than one
+		/// envelope
+		InvalidEnvelopeRecipient,
+		/// The resolver has not registered an encryption key
+		EncryptionKeyNotFound,
+		/// The account has no published review from the reviewer
+		ReviewNotFound,
+		/// The review is already disputed, a review is judged once
+		ReviewDisputeExisted,
+		/// There is no open dispute over the review
+		ReviewDisputeNotFound,
+		/// The review dispute can still be judged by the resolver
+		ReviewDisputeNotExpired,
+		/// Only the payer or the payee of the disputed payment, or an interested party for the
+		/// private evidence, can do the action
+		NotPaymentParty,
+		/// Only the party against the current outcome can fight the dispute
+		NotAggrievedParty,
+		/// Only the party losing the dispute can accept or challenge the outcome
+		NotLosingParty,
+		/// Only the issuer of the dispute can withdraw it
+		NotDisputeIssuer,
+		/// The account is not a resolver assigned to the dispute
+		NotAssignedResolver,
+		/// The resolver already proposed the judgment
+		AlreadyJudged,
 	}
 
 	#[pallet::hooks]
 	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_initialize(block_number: T::BlockNumber) -> Weight {
+			let hashes = <PendingResolverSelections<T>>::take(block_number);
+
+			for hash in hashes.iter() {
+				let err = match Self::_draw_resolvers(hash) {
+					Ok(()) => {
+						<SelectionRequests<T>>::remove(hash);
+						continue
+					},
+					Err(err) => err,
+				};
+				log::error!(
+					target: "Dispute resolution",
+					"Fail to draw resolvers for dispute {:?}: {:?}",
+					hash,
+					err,
+				);
+
+				// A draw without a recorded request is not retried.
+				let retries = <SelectionRequests<T>>::mutate(hash, |request| {
+					request.as_mut().map_or(u32::MAX, |request| {
+						request.retries += 1;
+						request.retries
+					})
+				});
+				if retries < T::MaxResolverSelectionRetries::get() {
+					// Retry to draw resolvers in the next block.
+					<PendingResolverSelections<T>>::mutate(block_number + One::one(), |hashes| {
+						hashes.push(*hash)
+					});
+				} else if let Err(err) = Self::_cancel_resolver_selection(hash) {
+					log::error!(
+						target: "Dispute resolution",
+						"Fail to cancel the resolver selection for dispute {:?}: {:?}",
+						hash,
+						err,
+					);
+				}
+			}
+
+			T::DbWeight::get()
+				.reads_writes(1 + 4 * hashes.len() as Weight, 1 + 3 * hashes.len() as Weight)
+		}
+
 		fn offchain_worker(block_number: T::BlockNumber) {
 			if let Err(err) = Self::run_offchain_worker() {
 				log::error!(
@@ -193,231 +843,1393 @@ pub mod pallet {
 				);
 			}
 		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade() -> Result<(), &'static str> {
+			Self::do_try_state()
+		}
 	}
 
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {
-		#[pallet::weight(1_000)]
+		#[pallet::weight(T::WeightInfo::create_dispute())]
 		pub fn create_dispute(
 			origin: OriginFor<T>,
 			payment_hash: HashOf<T>,
 			argument: Vec<u8>,
 		) -> DispatchResult {
 			let issuer = ensure_signed(origin)?;
+			Self::_ensure_not_paused(PausableCall::CreateDispute)?;
 			Self::_create_dispute(issuer, payment_hash, argument)?;
 			Ok(())
 		}
 
-		#[pallet::weight(1_000)]
+		#[pallet::weight(T::WeightInfo::fight_dispute())]
 		pub fn fight_dispute(
 			origin: OriginFor<T>,
 			payment_hash: HashOf<T>,
 			argument: Vec<u8>,
 		) -> DispatchResult {
 			let issuer = ensure_signed(origin)?;
+			Self::_ensure_not_paused(PausableCall::FightDispute)?;
 			Self::_fight_dispute(issuer, payment_hash, argument)?;
 			Ok(())
 		}
 
-		#[pallet::weight(1_000)]
+		#[pallet::weight(T::WeightInfo::escalate_dispute())]
 		pub fn escalate_dispute(origin: OriginFor<T>, payment_hash: HashOf<T>) -> DispatchResult {
 			let who = ensure_signed(origin)?;
+			Self::_ensure_not_paused(PausableCall::EscalateDispute)?;
 			Self::_escalate_dispute(who, payment_hash)?;
 			Ok(())
 		}
 
-		#[pallet::weight(1_000)]
+		#[pallet::weight(T::WeightInfo::withdraw_dispute())]
+		pub fn withdraw_dispute(origin: OriginFor<T>, payment_hash: HashOf<T>) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::_withdraw_dispute(who, payment_hash)?;
+			Ok(())
+		}
+
+		#[pallet::weight(T::WeightInfo::propose_outcome(Self::max_panel_size()))]
 		pub fn propose_outcome(
 			origin: OriginFor<T>,
 			payment_hash: HashOf<T>,
 			judgement: Judgment,
+			rationale: Option<HashOf<T>>,
 		) -> DispatchResult {
 			let who = ensure_signed(origin)?;
-			Self::_propose_outcome(who, payment_hash, judgement)?;
+			Self::_ensure_not_paused(PausableCall::ProposeOutcome)?;
+			Self::_propose_outcome(who, payment_hash, judgement, rationale)?;
 			Ok(())
 		}
-	}
 
-	impl<T: Config> Pallet<T> {
-		fn run_offchain_worker() -> DispatchResult {
-			Self::_process_finalizing_disputes()?;
+		#[pallet::weight(T::WeightInfo::challenge_outcome())]
+		pub fn challenge_outcome(
+			origin: OriginFor<T>,
+			payment_hash: HashOf<T>,
+			argument: Vec<u8>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::_ensure_not_paused(PausableCall::ChallengeOutcome)?;
+			Self::_challenge_outcome(who, payment_hash, argument)?;
 			Ok(())
 		}
 
-		fn _create_dispute(
-			issuer: AccountOf<T>,
+		#[pallet::weight(T::WeightInfo::submit_private_evidence(envelopes.len() as u32))]
+		pub fn submit_private_evidence(
+			origin: OriginFor<T>,
 			payment_hash: HashOf<T>,
-			argument: Vec<u8>,
+			envelopes: Vec<(AccountOf<T>, Vec<u8>)>,
 		) -> DispatchResult {
-			ensure!(T::PaymentProtocol::can_dispute(&payment_hash), <Error<T>>::DisputeNotAccepted);
-			let (payer, payee, ..) = T::PaymentProtocol::get_payment(&payment_hash)?;
-			ensure!(issuer == payer, <Error<T>>::AccessDenied);
-
-			let fee = Self::_compute_dispute_fee(1);
-			Self::_lock_resolvers_fee(&issuer, fee)?;
-
-			let expired_at = <timestamp::Pallet<T>>::get() + T::DisputeFinalizingTime::get();
+			let who = ensure_signed(origin)?;
+			Self::_submit_private_evidence(who, payment_hash, envelopes)?;
+			Ok(())
+		}
 
-			let dispute = Dispute::<T> {
-				payment_hash,
-				expired_at,
-				arguments: [Argument::<T> {
-					provider: payer.clone(),
-					content_hash: Self::_save_large_content(argument),
-				}]
-				.to_vec(),
-				status: DisputeStatus::Finalizing,
-				resolvers: [].to_vec(),
-				judgments: [].to_vec(),
-				fee,
-				outcome: Judgment::ReleaseFundToPayer,
-			};
+		#[pallet::weight(T::WeightInfo::finalize_dispute(Self::max_panel_size()))]
+		#[transactional]
+		pub fn finalize_dispute(
+			origin: OriginFor<T>,
+			payment_hash: HashOf<T>,
+		) -> DispatchResultWithPostInfo {
+			ensure_signed(origin)?;
+			Self::_ensure_not_paused(PausableCall::FinalizeDispute)?;
+			Self::_finalize_dispute(&payment_hash, false)?;
+			Self::_remove_finalizing_dispute(&payment_hash)?;
+			Ok(Pays::No.into())
+		}
 
-			<Disputes<T>>::insert(&payment_hash, dispute);
-			Self::_add_finalizing_dispute(payment_hash)?;
+		#[pallet::weight(T::WeightInfo::accept_outcome(Self::max_panel_size()))]
+		#[transactional]
+		pub fn accept_outcome(origin: OriginFor<T>, payment_hash: HashOf<T>) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::_ensure_not_paused(PausableCall::FinalizeDispute)?;
+			Self::_accept_outcome(who, payment_hash)?;
+			Self::_remove_finalizing_dispute(&payment_hash)?;
+			Ok(())
+		}
 
-			Self::deposit_event(Event::DisputeCreated { payment_hash, payer, payee });
+		#[pallet::weight(T::WeightInfo::accept_assignment(Self::max_panel_size()))]
+		pub fn accept_assignment(origin: OriginFor<T>, payment_hash: HashOf<T>) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::_accept_assignment(who, payment_hash)?;
+			Ok(())
+		}
 
+		#[pallet::weight(T::WeightInfo::decline_assignment(Self::max_panel_size()))]
+		pub fn decline_assignment(origin: OriginFor<T>, payment_hash: HashOf<T>) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::_decline_assignment(who, payment_hash)?;
 			Ok(())
 		}
 
-		fn _fight_dispute(
-			who: AccountOf<T>,
+		#[pallet::weight(T::WeightInfo::expire_assignments(Self::max_panel_size()))]
+		#[transactional]
+		pub fn expire_assignments(
+			origin: OriginFor<T>,
 			payment_hash: HashOf<T>,
+		) -> DispatchResultWithPostInfo {
+			ensure_signed(origin)?;
+			Self::_expire_assignments(payment_hash)?;
+			Ok(Pays::No.into())
+		}
+
+		#[pallet::weight(T::WeightInfo::dispute_review())]
+		pub fn dispute_review(
+			origin: OriginFor<T>,
+			reviewer: AccountOf<T>,
 			argument: Vec<u8>,
 		) -> DispatchResult {
-			let mut dispute = Self::disputes(&payment_hash).ok_or(<Error<T>>::DisputeNotFound)?;
-			let (payer, payee, ..) = T::PaymentProtocol::get_payment(&payment_hash)?;
+			let who = ensure_signed(origin)?;
+			Self::_dispute_review(who, reviewer, argument)?;
+			Ok(())
+		}
 
-			let is_aggrieved_party =
-				dispute.outcome == Judgment::ReleaseFundToPayee && who == payer ||
-					dispute.outcome == Judgment::ReleaseFundToPayer && who == payee;
+		#[pallet::weight(T::WeightInfo::resolve_review_dispute())]
+		#[transactional]
+		pub fn resolve_review_dispute(
+			origin: OriginFor<T>,
+			account: AccountOf<T>,
+			reviewer: AccountOf<T>,
+			verdict: ReviewVerdict,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::_resolve_review_dispute(who, account, reviewer, verdict)?;
+			Ok(())
+		}
 
-			ensure!(is_aggrieved_party, <Error<T>>::AccessDenied);
+		#[pallet::weight(T::WeightInfo::cancel_review_dispute())]
+		pub fn cancel_review_dispute(
+			origin: OriginFor<T>,
+			reviewer: AccountOf<T>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let dispute =
+				Self::review_disputes(&who, &reviewer).ok_or(<Error<T>>::ReviewDisputeNotFound)?;
+			ensure!(dispute.verdict.is_none(), <Error<T>>::ReviewDisputeNotFound);
 			ensure!(
-				dispute.status == DisputeStatus::Finalizing,
-				<Error<T>>::ActionForOnlyFinalizingDispute
+				<timestamp::Pallet<T>>::get() >= dispute.deadline,
+				<Error<T>>::ReviewDisputeNotExpired
 			);
 
-			dispute.status = DisputeStatus::Evaluating;
-			dispute.arguments.push(Argument::<T> {
-				provider: who.clone(),
-				content_hash: Self::_save_large_content(argument),
+			Self::_unreserve_named(REVIEW_DISPUTE_FEE_RESERVE, &who, dispute.fee);
+			<ReviewDisputes<T>>::remove(&who, &reviewer);
+			Self::deposit_event(Event::ReviewDisputeCancelled {
+				account: who,
+				reviewer,
+				fee: dispute.fee,
 			});
+			Ok(())
+		}
 
-			// The number of resolvers will increases after each escalating round.
-			let number_of_resolver = dispute.resolvers.len() + 1;
+		#[pallet::weight(T::WeightInfo::set_compensation_cap())]
+		pub fn set_compensation_cap(origin: OriginFor<T>, cap: BalanceOf<T>) -> DispatchResult {
+			T::GovernanceOrigin::ensure_origin(origin)?;
+			<CompensationCap<T>>::set(cap);
+			Self::deposit_event(Event::CompensationCapUpdated { cap });
+			Ok(())
+		}
 
-			Self::_lock_resolvers_fee(&who, dispute.fee)?;
+		#[pallet::weight(T::WeightInfo::compensate())]
+		pub fn compensate(
+			origin: OriginFor<T>,
+			payment_hash: HashOf<T>,
+			beneficiary: AccountOf<T>,
+			amount: BalanceOf<T>,
+		) -> DispatchResult {
+			T::GovernanceOrigin::ensure_origin(origin)?;
+			Self::_compensate(payment_hash, beneficiary, amount)?;
+			Ok(())
+		}
 
-			for _i in 0..number_of_resolver {
-				let resolver =
-					T::ResolversNetwork::get_resolver(payment_hash, dispute.resolvers.clone())?;
-				dispute.resolvers.push(resolver);
+		#[pallet::weight(T::WeightInfo::set_call_paused())]
+		pub fn set_call_paused(
+			origin: OriginFor<T>,
+			call: PausableCall,
+			paused: bool,
+		) -> DispatchResult {
+			T::PauseOrigin::ensure_origin(origin)?;
+			if paused {
+				<PausedCalls<T>>::insert(&call, true);
+			} else {
+				<PausedCalls<T>>::remove(&call);
 			}
-
-			Self::_remove_finalizing_dispute(&payment_hash)?;
-			<Disputes<T>>::insert(&payment_hash, dispute);
-
-			Self::deposit_event(Event::DisputeFought { payment_hash, payer, payee });
-
+			Self::deposit_event(Event::CallPauseUpdated { call, paused });
 			Ok(())
 		}
+	}
 
-		fn _escalate_dispute(who: AccountOf<T>, payment_hash: HashOf<T>) -> DispatchResult {
-			let (payer, payee, _, _) = T::PaymentProtocol::get_payment(&payment_hash)?;
-			let mut dispute = Self::disputes(&payment_hash).ok_or(<Error<T>>::DisputeNotFound)?;
+	impl<T: Config> Pallet<T> {
+		/// The account of the insurance fund.
+		pub fn insurance_fund_account() -> AccountOf<T> {
+			T::PalletId::get().into_account()
+		}
 
-			ensure!(who == payer || who == payee, <Error<T>>::AccessDenied);
-			ensure!(
-				dispute.status == DisputeStatus::Finalizing,
-				<Error<T>>::ActionForOnlyFinalizingDispute
-			);
+		/// The maximum number of resolvers of a dispute panel. The panel grows to `2n + 1`
+		/// resolvers by each escalation round and by the challenge.
+		pub fn max_panel_size() -> u32 {
+			2u32.saturating_pow(T::MaxEscalationRounds::get() + 2) - 1
+		}
 
-			let fee = Self::_compute_dispute_fee(dispute.resolvers.len() + 1);
-			Self::_lock_resolvers_fee(&who, fee)?;
-			dispute.fee += fee;
+		/// Check the accounting invariants of the disputes, run by `try-runtime` after the runtime
+		/// upgrades:
+		/// - The fees escrowed for the disputes are covered by the dispute fee reserves of the
+		///   parties.
+		/// - The bonds of the challenges in flight are covered by the challenge bond reserves.
+		/// - The tracked reserves of an account are covered by its reserved balance.
+		#[cfg(any(feature = "try-runtime", test))]
+		pub fn do_try_state() -> Result<(), &'static str> {
+			use frame_support::sp_std::collections::btree_map::BTreeMap;
+
+			let mut escrowed: BTreeMap<(ReserveIdentifier, AccountOf<T>), BalanceOf<T>> =
+				BTreeMap::new();
+			let mut escrow = |id: ReserveIdentifier, who: AccountOf<T>, amount: BalanceOf<T>| {
+				let total = escrowed.entry((id, who)).or_insert_with(Zero::zero);
+				*total += amount;
+			};
+			for (payment_hash, dispute) in <Disputes<T>>::iter() {
+				let (payer, payee, ..) = T::PaymentProtocol::get_payment(&payment_hash)
+					.map_err(|_| "The payment of a dispute is not found")?;
+				escrow(DISPUTE_FEE_RESERVE, payer, dispute.payer_fee);
+				escrow(DISPUTE_FEE_RESERVE, payee, dispute.payee_fee);
+				if let Some(challenge) = dispute.challenge {
+					if dispute.status != DisputeStatus::Resolved {
+						escrow(CHALLENGE_BOND_RESERVE, challenge.challenger, challenge.bond);
+					}
+				}
+			}
+
+			let currency_id = T::FeeCurrency::get();
+			for ((id, who), amount) in escrowed {
+				ensure!(
+					amount <= Self::named_reserves(&who, (id, currency_id)),
+					"The funds escrowed for the disputes exceed the reserves of an account"
+				);
+			}
+
+			for (who, (_, currency_id), amount) in <NamedReserves<T>>::iter() {
+				ensure!(
+					amount <= T::Currency::reserved_balance(currency_id, &who),
+					"The tracked reserves of an account exceed its reserved balance"
+				);
+			}
+
+			Ok(())
+		}
+
+		/// The precedents of the payment category resolved with the outcome, from the oldest to the
+		/// latest one.
+		pub fn precedents_of(category: PaymentCategory, outcome: Judgment) -> Vec<Precedent<T>> {
+			Self::precedents(category)
+				.into_iter()
+				.filter(|precedent| precedent.outcome == outcome)
+				.collect()
+		}
+
+		/// The fee schedule of a dispute over the payment escalated `rounds` times, capped at
+		/// `MaxEscalationRounds`, with the current parameters. Returns `None` if there is no such
+		/// payment.
+		pub fn estimate_dispute_cost(
+			payment_hash: HashOf<T>,
+			rounds: u32,
+		) -> Option<DisputeCostEstimate<BalanceOf<T>>> {
+			let (_, _, payment_amount, _) = T::PaymentProtocol::get_payment(&payment_hash).ok()?;
+			let create_fee = Self::_compute_dispute_fee(1);
+
+			// The escalating party pays for one more resolver than the panel has, and the panel
+			// grows to `2n + 1` resolvers when the escalation is fought.
+			let mut panel_size: usize = 1;
+			let mut escalation_fees: Vec<BalanceOf<T>> = [].to_vec();
+			for _ in 0..rounds.min(T::MaxEscalationRounds::get()) {
+				escalation_fees.push(Self::_compute_dispute_fee(panel_size + 1));
+				panel_size = panel_size * 2 + 1;
+			}
+
+			let total_per_party =
+				escalation_fees.iter().fold(create_fee, |total, fee| total.saturating_add(*fee));
+
+			Some(DisputeCostEstimate {
+				payment_amount,
+				create_fee,
+				fight_fee: create_fee,
+				escalation_fees,
+				panel_size: panel_size as u32,
+				total_per_party,
+			})
+		}
+
+		/// The SLA metrics of the dispute resolution, the medians are computed over the samples of
+		/// the latest resolved disputes.
+		pub fn sla_metrics() -> DisputeSlaMetrics<MomentOf<T>> {
+			let counts = Self::sla_counters();
+			let samples = Self::sla_samples();
+
+			DisputeSlaMetrics {
+				resolved: counts.resolved,
+				fought: counts.fought,
+				acceptance_timeouts: counts.acceptance_timeouts,
+				judging_timeouts: counts.judging_timeouts,
+				median_time_to_panel: Self::_median(
+					samples.iter().filter_map(|sample| sample.time_to_panel).collect(),
+				),
+				median_time_to_verdict: Self::_median(
+					samples.iter().filter_map(|sample| sample.time_to_verdict).collect(),
+				),
+				median_time_to_payout: Self::_median(
+					samples.iter().map(|sample| sample.time_to_payout).collect(),
+				),
+			}
+		}
+
+		fn run_offchain_worker() -> DispatchResult {
+			Self::_process_finalizing_disputes()?;
+			Ok(())
+		}
+
+		fn _create_dispute(
+			issuer: AccountOf<T>,
+			payment_hash: HashOf<T>,
+			argument: Vec<u8>,
+		) -> DispatchResult {
+			ensure!(T::PaymentProtocol::can_dispute(&payment_hash), <Error<T>>::DisputeNotAccepted);
+			let (payer, payee, ..) = T::PaymentProtocol::get_payment(&payment_hash)?;
+			ensure!(issuer == payer || issuer == payee, <Error<T>>::NotPaymentParty);
+
+			let fee = Self::_compute_dispute_fee(1);
+			Self::_lock_resolvers_fee(&issuer, fee)?;
+
+			// The initial outcome is in favor of the issuer, the other party has to fight it.
+			let (payer_fee, payee_fee, outcome, respondent) = if issuer == payer {
+				(fee, Zero::zero(), Judgment::ReleaseFundToPayer, &payee)
+			} else {
+				(Zero::zero(), fee, Judgment::ReleaseFundToPayee, &payer)
+			};
+
+			let expired_at = <timestamp::Pallet<T>>::get() + T::DisputeFinalizingTime::get();
+
+			let mut dispute = Dispute::<T> {
+				payment_hash,
+				expired_at,
+				arguments: [].to_vec(),
+				status: DisputeStatus::Finalizing,
+				resolvers: [].to_vec(),
+				assignments: [].to_vec(),
+				judgments: [].to_vec(),
+				payer_fee,
+				payee_fee,
+				outcome,
+				escalations: 0,
+				resolved_at: None,
+				challenge: None,
+			};
+			Self::_submit_evidence(&payment_hash, &mut dispute, issuer.clone(), argument);
+
+			<Disputes<T>>::insert(&payment_hash, dispute);
+			<DisputeTimelines<T>>::insert(
+				&payment_hash,
+				DisputeTimeline::<T> {
+					created_at: <timestamp::Pallet<T>>::get(),
+					panel_drawn_at: None,
+					verdict_at: None,
+				},
+			);
+			Self::_add_finalizing_dispute(payment_hash)?;
+			Self::_record_dispute(&payer, &payment_hash);
+			Self::_record_dispute(&payee, &payment_hash);
+			T::IdentitiesManager::note_dispute_opened(respondent);
+
+			Self::deposit_event(Event::DisputeCreated {
+				payment_hash,
+				payer_handle: T::IdentitiesManager::get_handle(&payer),
+				payee_handle: T::IdentitiesManager::get_handle(&payee),
+				payer,
+				payee,
+				issuer,
+				fee,
+			});
+
+			// The marketplace of the payment is an interested party of the dispute for its fee.
+			if let Some((marketplace, share)) = T::PaymentProtocol::get_marketplace(&payment_hash) {
+				<DisputeStakeholders<T>>::insert(
+					&payment_hash,
+					[(marketplace.clone(), share)].to_vec(),
+				);
+				Self::deposit_event(Event::DisputeStakeholderAdded {
+					payment_hash,
+					stakeholder: marketplace,
+					share,
+				});
+			}
+
+			Ok(())
+		}
+
+		fn _fight_dispute(
+			who: AccountOf<T>,
+			payment_hash: HashOf<T>,
+			argument: Vec<u8>,
+		) -> DispatchResult {
+			let mut dispute = Self::disputes(&payment_hash).ok_or(<Error<T>>::DisputeNotFound)?;
+			let (payer, payee, ..) = T::PaymentProtocol::get_payment(&payment_hash)?;
+
+			let is_aggrieved_party =
+				dispute.outcome == Judgment::ReleaseFundToPayee && who == payer ||
+					dispute.outcome == Judgment::ReleaseFundToPayer && who == payee;
+
+			ensure!(is_aggrieved_party, <Error<T>>::NotAggrievedParty);
+			ensure!(
+				dispute.status == DisputeStatus::Finalizing,
+				<Error<T>>::ActionForOnlyFinalizingDispute
+			);
+			// The outcome of the challenge panel is final.
+			ensure!(dispute.challenge.is_none(), <Error<T>>::DisputeAlreadyChallenged);
+
+			dispute.status = DisputeStatus::Evaluating;
+			Self::_submit_evidence(&payment_hash, &mut dispute, who.clone(), argument);
+
+			// Escrow the fee to match the escrowed fee of the other party.
+			let fee = if who == payer {
+				dispute.payee_fee.saturating_sub(dispute.payer_fee)
+			} else {
+				dispute.payer_fee.saturating_sub(dispute.payee_fee)
+			};
+			Self::_escrow_resolvers_fee(&mut dispute, &who, &payer, fee)?;
+			Self::_schedule_resolver_selection(&payment_hash, &mut dispute, &who, fee)?;
+
+			Self::_remove_finalizing_dispute(&payment_hash)?;
+			<Disputes<T>>::insert(&payment_hash, dispute);
+
+			Self::deposit_event(Event::DisputeFought {
+				payment_hash,
+				payer,
+				payee,
+				fighter: who,
+				fee,
+			});
+
+			Ok(())
+		}
+
+		fn _escalate_dispute(who: AccountOf<T>, payment_hash: HashOf<T>) -> DispatchResult {
+			let (payer, payee, _, _) = T::PaymentProtocol::get_payment(&payment_hash)?;
+			let mut dispute = Self::disputes(&payment_hash).ok_or(<Error<T>>::DisputeNotFound)?;
+
+			ensure!(who == payer || who == payee, <Error<T>>::NotPaymentParty);
+			ensure!(
+				dispute.status == DisputeStatus::Finalizing,
+				<Error<T>>::ActionForOnlyFinalizingDispute
+			);
+			// The outcome of the challenge panel is final.
+			ensure!(dispute.challenge.is_none(), <Error<T>>::DisputeAlreadyChallenged);
+			ensure!(
+				dispute.escalations < T::MaxEscalationRounds::get(),
+				<Error<T>>::MaxEscalationRoundsReached
+			);
+
+			let fee = Self::_compute_dispute_fee(dispute.resolvers.len() + 1);
+			Self::_escrow_resolvers_fee(&mut dispute, &who, &payer, fee)?;
+			dispute.escalations += 1;
+
+			if who == payer {
+				dispute.outcome = Judgment::ReleaseFundToPayer;
+			} else {
+				dispute.outcome = Judgment::ReleaseFundToPayee;
+			}
+
+			dispute.status = DisputeStatus::Finalizing;
+			let escalations = dispute.escalations;
+			<Disputes<T>>::insert(&payment_hash, dispute);
+
+			Self::deposit_event(Event::DisputeEscalated {
+				payment_hash,
+				payer,
+				payee,
+				escalator: who,
+				fee,
+				escalations,
+			});
+
+			Ok(())
+		}
+
+		// The issuer withdraws the dispute before any resolver is drawn for it.
+		fn _withdraw_dispute(who: AccountOf<T>, payment_hash: HashOf<T>) -> DispatchResult {
+			let (payer, payee, _, _) = T::PaymentProtocol::get_payment(&payment_hash)?;
+			let dispute = Self::disputes(&payment_hash).ok_or(<Error<T>>::DisputeNotFound)?;
+
+			// The issuer submitted the first argument of the dispute.
+			let is_issuer =
+				dispute.arguments.first().map_or(false, |argument| argument.provider == who);
+			ensure!(is_issuer, <Error<T>>::NotDisputeIssuer);
+			ensure!(
+				dispute.status == DisputeStatus::Finalizing && dispute.resolvers.is_empty(),
+				<Error<T>>::DisputeNotWithdrawable
+			);
+
+			// The other party may have escrowed its fee by escalating the dispute before the
+			// withdrawal.
+			Self::_refund_resolvers_fee(&payment_hash, &payer, dispute.payer_fee);
+			Self::_refund_resolvers_fee(&payment_hash, &payee, dispute.payee_fee);
+
+			<Disputes<T>>::remove(&payment_hash);
+			<DisputeTimelines<T>>::remove(&payment_hash);
+			<DisputeStakeholders<T>>::remove(&payment_hash);
+			Self::_remove_finalizing_dispute(&payment_hash)?;
+			Self::_forget_dispute(&payer, &payment_hash);
+			Self::_forget_dispute(&payee, &payment_hash);
+			let (fee, respondent) =
+				if who == payer { (dispute.payer_fee, payee) } else { (dispute.payee_fee, payer) };
+			T::IdentitiesManager::note_dispute_withdrawn(&respondent);
+
+			Self::deposit_event(Event::DisputeWithdrawn { payment_hash, issuer: who, fee });
+
+			Ok(())
+		}
+
+		// Assigned resolvers propose their judgement after evaluation.
+		fn _propose_outcome(
+			who: AccountOf<T>,
+			payment_hash: HashOf<T>,
+			judgment: Judgment,
+			rationale: Option<HashOf<T>>,
+		) -> DispatchResult {
+			let mut dispute = Self::disputes(&payment_hash).ok_or(<Error<T>>::DisputeNotFound)?;
+
+			// Ensure only selected resolver can propose for the outcome.
+			ensure!(dispute.resolvers.contains(&who), <Error<T>>::NotAssignedResolver);
+			// Ensure selected resolver can give decision once.
+			ensure!(!dispute.judgments.iter().any(|i| i.0 == who), <Error<T>>::AlreadyJudged);
+			// Ensure the resolver accepted the assignment.
+			let position = dispute
+				.assignments
+				.iter()
+				.position(|assignment| assignment.resolver == who && assignment.accepted)
+				.ok_or(<Error<T>>::AssignmentNotAccepted)?;
+
+			dispute.assignments.remove(position);
+			Self::_unreserve_named(RESOLVER_BOND_RESERVE, &who, T::ResolverBond::get());
+			T::ResolversNetwork::close_case(&who);
+
+			dispute.judgments.push((who.clone(), judgment));
+			if let Some(rationale) = rationale {
+				<JudgmentRationales<T>>::insert(&payment_hash, &who, rationale);
+			}
+			Self::deposit_event(Event::JudgmentProposed { payment_hash, resolver: who, rationale });
+
+			// The dispute will be concluded if get enough judgments from resolvers. A panel which
+			// lost seats is concluded as an incomplete panel, whether the last seat was dropped or
+			// judged.
+			if dispute.resolvers.len() == dispute.judgments.len() {
+				if Self::dropped_panel_seats(&payment_hash) > 0 {
+					Self::_conclude_incomplete_panel(&payment_hash, &mut dispute)?;
+				} else {
+					let transcript = Self::_count_judgments(&dispute);
+
+					dispute.outcome = if transcript.release_to_payee > transcript.release_to_payer {
+						Judgment::ReleaseFundToPayee
+					} else {
+						Judgment::ReleaseFundToPayer
+					};
+
+					Self::_conclude_dispute(payment_hash, &mut dispute)?;
+				}
+			}
+
+			<Disputes<T>>::insert(&payment_hash, dispute);
+
+			Ok(())
+		}
+
+		fn _count_judgments(dispute: &Dispute<T>) -> Transcript {
+			let mut transcript = Transcript { release_to_payer: 0, release_to_payee: 0 };
+
+			for (_who, judgement) in dispute.judgments.iter() {
+				match judgement {
+					Judgment::ReleaseFundToPayee => transcript.release_to_payee += 1,
+					Judgment::ReleaseFundToPayer => transcript.release_to_payer += 1,
+				}
+			}
+
+			transcript
+		}
+
+		fn _conclude_dispute(payment_hash: HashOf<T>, dispute: &mut Dispute<T>) -> DispatchResult {
+			<DroppedPanelSeats<T>>::remove(&payment_hash);
+			dispute.status = DisputeStatus::Finalizing;
+			dispute.expired_at = Self::_get_expired_time();
+			<DisputeTimelines<T>>::mutate(&payment_hash, |timeline| {
+				if let Some(timeline) = timeline {
+					timeline.verdict_at = Some(<timestamp::Pallet<T>>::get());
+				}
+			});
+			Self::_add_finalizing_dispute(payment_hash)
+		}
+
+		// The losing party concedes the outcome, the dispute is finalized immediately.
+		fn _accept_outcome(who: AccountOf<T>, payment_hash: HashOf<T>) -> DispatchResult {
+			let dispute = Self::disputes(&payment_hash).ok_or(<Error<T>>::DisputeNotFound)?;
+			let (payer, payee, ..) = T::PaymentProtocol::get_payment(&payment_hash)?;
+
+			ensure!(
+				dispute.status == DisputeStatus::Finalizing,
+				<Error<T>>::ActionForOnlyFinalizingDispute
+			);
+			// The resolution of a challenge is settled as the challenge.
+			ensure!(dispute.challenge.is_none(), <Error<T>>::DisputeAlreadyChallenged);
+
+			let is_losing_party =
+				dispute.outcome == Judgment::ReleaseFundToPayee && who == payer ||
+					dispute.outcome == Judgment::ReleaseFundToPayer && who == payee;
+			ensure!(is_losing_party, <Error<T>>::NotLosingParty);
+
+			Self::deposit_event(Event::OutcomeAccepted {
+				payment_hash,
+				party: who,
+				outcome: dispute.outcome.clone(),
+			});
+
+			Self::_finalize_dispute(&payment_hash, true)
+		}
+
+		fn _challenge_outcome(
+			who: AccountOf<T>,
+			payment_hash: HashOf<T>,
+			argument: Vec<u8>,
+		) -> DispatchResult {
+			let mut dispute = Self::disputes(&payment_hash).ok_or(<Error<T>>::DisputeNotFound)?;
+			let (payer, payee, ..) = T::PaymentProtocol::get_payment(&payment_hash)?;
+
+			ensure!(dispute.status == DisputeStatus::Resolved, <Error<T>>::DisputeNotResolved);
+			ensure!(dispute.challenge.is_none(), <Error<T>>::DisputeAlreadyChallenged);
+
+			let resolved_at = dispute.resolved_at.ok_or(<Error<T>>::DisputeNotResolved)?;
+			ensure!(
+				<timestamp::Pallet<T>>::get() < resolved_at + T::ChallengeWindow::get(),
+				<Error<T>>::ChallengeWindowClosed
+			);
+
+			let is_losing_party =
+				dispute.outcome == Judgment::ReleaseFundToPayee && who == payer ||
+					dispute.outcome == Judgment::ReleaseFundToPayer && who == payee;
+			ensure!(is_losing_party, <Error<T>>::NotLosingParty);
+
+			// The challenge is evaluated by an enlarged panel of new resolvers.
+			let fee = Self::_compute_dispute_fee(dispute.resolvers.len() * 2 + 1);
+			let bond = T::ChallengeBond::get();
+			ensure!(
+				T::Currency::free_balance(T::FeeCurrency::get(), &who) >= fee + bond,
+				<Error<T>>::InsufficientBalance,
+			);
+			Self::_escrow_resolvers_fee(&mut dispute, &who, &payer, fee)?;
+			Self::_reserve_named(CHALLENGE_BOND_RESERVE, &who, bond)?;
+
+			Self::_submit_evidence(&payment_hash, &mut dispute, who.clone(), argument);
+			dispute.challenge = Some(Challenge::<T> {
+				challenger: who.clone(),
+				bond,
+				original_outcome: dispute.outcome.clone(),
+				original_resolvers: mem::take(&mut dispute.resolvers),
+				original_judgments: mem::take(&mut dispute.judgments),
+			});
+			dispute.status = DisputeStatus::Evaluating;
+
+			Self::_schedule_resolver_selection(&payment_hash, &mut dispute, &who, fee)?;
+			<Disputes<T>>::insert(&payment_hash, dispute);
+
+			Self::deposit_event(Event::DisputeChallenged {
+				payment_hash,
+				challenger: who,
+				bond,
+				fee,
+			});
+
+			Ok(())
+		}
+
+		// Select the resolvers now or after `ResolverSelectionDelay` blocks.
+		fn _schedule_resolver_selection(
+			payment_hash: &HashOf<T>,
+			dispute: &mut Dispute<T>,
+			requester: &AccountOf<T>,
+			fee: BalanceOf<T>,
+		) -> DispatchResult {
+			let delay = T::ResolverSelectionDelay::get();
+			if delay.is_zero() {
+				Self::_select_resolvers(payment_hash, dispute)?;
+			} else {
+				let draw_at = <frame_system::Pallet<T>>::block_number() + delay;
+				<PendingResolverSelections<T>>::mutate(draw_at, |hashes| {
+					hashes.push(*payment_hash)
+				});
+				<SelectionRequests<T>>::insert(
+					payment_hash,
+					SelectionRequest::<T> { requester: requester.clone(), fee, retries: 0 },
+				);
+			}
+			Ok(())
+		}
+
+		// No resolver could be drawn for the requested panel. A fought dispute is back to
+		// finalizing with its outcome, a challenged dispute is back to its resolution, and the
+		// fee of the requester and the challenge bond are refunded.
+		fn _cancel_resolver_selection(payment_hash: &HashOf<T>) -> DispatchResult {
+			let request =
+				<SelectionRequests<T>>::take(payment_hash).ok_or(<Error<T>>::DisputeNotFound)?;
+			let mut dispute = Self::disputes(payment_hash).ok_or(<Error<T>>::DisputeNotFound)?;
+			let (payer, ..) = T::PaymentProtocol::get_payment(payment_hash)?;
+
+			Self::_refund_resolvers_fee(payment_hash, &request.requester, request.fee);
+			if request.requester == payer {
+				dispute.payer_fee = dispute.payer_fee.saturating_sub(request.fee);
+			} else {
+				dispute.payee_fee = dispute.payee_fee.saturating_sub(request.fee);
+			}
+
+			match dispute.challenge.take() {
+				Some(challenge) => {
+					Self::_unreserve_named(
+						CHALLENGE_BOND_RESERVE,
+						&challenge.challenger,
+						challenge.bond,
+					);
+					dispute.outcome = challenge.original_outcome;
+					dispute.resolvers = challenge.original_resolvers;
+					dispute.judgments = challenge.original_judgments;
+					dispute.status = DisputeStatus::Resolved;
+				},
+				None => {
+					// The parties have a new finalizing time to act on the outcome.
+					dispute.status = DisputeStatus::Finalizing;
+					dispute.expired_at = Self::_get_expired_time();
+					Self::_add_finalizing_dispute(*payment_hash)?;
+				},
+			}
+			<Disputes<T>>::insert(payment_hash, dispute);
+
+			Self::deposit_event(Event::ResolverSelectionCancelled {
+				payment_hash: *payment_hash,
+				requester: request.requester,
+				fee: request.fee,
+			});
+
+			Ok(())
+		}
+
+		// The resolvers of the dispute and the challenged resolution cannot be drawn again.
+		fn _excluded_resolvers(dispute: &Dispute<T>) -> Vec<AccountOf<T>> {
+			let mut excluded = dispute.resolvers.clone();
+			if let Some(challenge) = &dispute.challenge {
+				excluded.extend(challenge.original_resolvers.iter().cloned());
+			}
+			excluded
+		}
+
+		// The resolvers with a conflict of interest with the parties are not drawn.
+		fn _dispute_parties(payment_hash: &HashOf<T>) -> Result<Vec<AccountOf<T>>, DispatchError> {
+			let (payer, payee, ..) = T::PaymentProtocol::get_payment(payment_hash)?;
+			Ok([payer, payee].to_vec())
+		}
+
+		// The language hint of the payment is preferred, or else the common languages of the
+		// parties, or else any language of the parties.
+		fn _preferred_languages(
+			payment_hash: &HashOf<T>,
+		) -> Result<Vec<LanguageCode>, DispatchError> {
+			if let Some(locale) = T::PaymentProtocol::get_locale(payment_hash) {
+				return Ok([locale].to_vec())
+			}
+
+			let (payer, payee, ..) = T::PaymentProtocol::get_payment(payment_hash)?;
+			Ok(Self::_party_languages(&payer, &payee))
+		}
+
+		// The common languages of the parties, or else any language of the parties.
+		fn _party_languages(party: &AccountOf<T>, other: &AccountOf<T>) -> Vec<LanguageCode> {
+			let party_languages = T::IdentitiesManager::get_languages(party);
+			let other_languages = T::IdentitiesManager::get_languages(other);
+			let common_languages: Vec<LanguageCode> = party_languages
+				.iter()
+				.filter(|language| other_languages.contains(language))
+				.cloned()
+				.collect();
+			if !common_languages.is_empty() {
+				return common_languages
+			}
+
+			party_languages.into_iter().chain(other_languages).collect()
+		}
+
+		fn _select_resolvers(payment_hash: &HashOf<T>, dispute: &mut Dispute<T>) -> DispatchResult {
+			// The number of resolvers will increases after each escalating round. A challenge is
+			// evaluated by an enlarged panel.
+			let number_of_resolver = match &dispute.challenge {
+				Some(challenge) => challenge.original_resolvers.len() * 2 + 1,
+				None => dispute.resolvers.len() + 1,
+			};
+			let now = <timestamp::Pallet<T>>::get();
+			let deadline = now + T::AssignmentAcceptanceTime::get();
+			let languages = Self::_preferred_languages(payment_hash)?;
+			let parties = Self::_dispute_parties(payment_hash)?;
+			let category = T::PaymentProtocol::get_category(payment_hash);
+			let mut drawn: Vec<AccountOf<T>> = [].to_vec();
+
+			for i in 0..number_of_resolver {
+				let resolver = match T::ResolversNetwork::get_resolver(
+					*payment_hash,
+					Self::_excluded_resolvers(dispute),
+					languages.clone(),
+					parties.clone(),
+					category,
+				) {
+					Ok(resolver) => resolver,
+					// At least one new resolver is required for the panel.
+					Err(err) if i == 0 => return Err(err),
+					// The panel size is capped at the available resolvers.
+					Err(_) => {
+						Self::deposit_event(Event::PanelCapped {
+							payment_hash: *payment_hash,
+							size: i as u32,
+						});
+						break
+					},
+				};
+				dispute.resolvers.push(resolver.clone());
+				drawn.push(resolver.clone());
+				dispute
+					.assignments
+					.push(Assignment::<T> { resolver, accepted: false, deadline });
+			}
+
+			<DisputeTimelines<T>>::mutate(payment_hash, |timeline| {
+				if let Some(timeline) = timeline {
+					timeline.panel_drawn_at.get_or_insert(now);
+				}
+			});
+
+			Self::deposit_event(Event::DisputePanelDrawn {
+				payment_hash: *payment_hash,
+				resolvers: drawn,
+			});
+
+			Ok(())
+		}
+
+		// Replace the resolver of the assignment at the position by a new drawn resolver.
+		fn _replace_resolver(
+			payment_hash: &HashOf<T>,
+			dispute: &mut Dispute<T>,
+			position: usize,
+		) -> DispatchResult {
+			// The replaced resolver is still excluded from the draw.
+			let selected = Self::_excluded_resolvers(dispute);
+			let languages = Self::_preferred_languages(payment_hash)?;
+			let parties = Self::_dispute_parties(payment_hash)?;
+			let category = T::PaymentProtocol::get_category(payment_hash);
+			let assignment = dispute.assignments.remove(position);
+			dispute.resolvers.retain(|resolver| *resolver != assignment.resolver);
+
+			// The seat is dropped from the panel if there is no available resolver.
+			let resolver = match T::ResolversNetwork::get_resolver(
+				*payment_hash,
+				selected,
+				languages,
+				parties,
+				category,
+			) {
+				Ok(resolver) => resolver,
+				Err(_) => {
+					<DroppedPanelSeats<T>>::mutate(payment_hash, |seats| *seats += 1);
+					Self::deposit_event(Event::PanelSeatDropped {
+						payment_hash: *payment_hash,
+						resolver: assignment.resolver,
+					});
+					return Ok(())
+				},
+			};
+			let deadline = <timestamp::Pallet<T>>::get() + T::AssignmentAcceptanceTime::get();
+
+			dispute.resolvers.push(resolver.clone());
+			dispute.assignments.push(Assignment::<T> {
+				resolver: resolver.clone(),
+				accepted: false,
+				deadline,
+			});
+
+			Self::deposit_event(Event::DisputePanelDrawn {
+				payment_hash: *payment_hash,
+				resolvers: [resolver].to_vec(),
+			});
+
+			Ok(())
+		}
+
+		// The panel cannot be completed if the seats are dropped, the dispute is concluded by the
+		// collected judgments once there is no pending assignment.
+		fn _conclude_incomplete_panel(
+			payment_hash: &HashOf<T>,
+			dispute: &mut Dispute<T>,
+		) -> DispatchResult {
+			if dispute.status != DisputeStatus::Evaluating || !dispute.assignments.is_empty() {
+				return Ok(())
+			}
+
+			// The fund is only released to the payee with a super-majority (two thirds) of the
+			// collected judgments.
+			let transcript = Self::_count_judgments(dispute);
+			let collected = transcript.release_to_payee + transcript.release_to_payer;
+			dispute.outcome = if collected > 0 && transcript.release_to_payee * 3 >= collected * 2 {
+				Judgment::ReleaseFundToPayee
+			} else {
+				Judgment::ReleaseFundToPayer
+			};
+
+			Self::_conclude_dispute(*payment_hash, dispute)?;
+
+			Self::deposit_event(Event::IncompletePanelConcluded {
+				payment_hash: *payment_hash,
+				outcome: dispute.outcome.clone(),
+			});
+
+			Ok(())
+		}
+
+		// Add the dispute to the history of the account, the oldest dispute is dropped if the
+		// history is full.
+		fn _record_dispute(who: &AccountOf<T>, payment_hash: &HashOf<T>) {
+			<DisputesOwned<T>>::mutate(who, |payment_hashes| {
+				if payment_hashes.contains(payment_hash) {
+					return
+				}
+				if payment_hashes.try_push(*payment_hash).is_err() && !payment_hashes.is_empty() {
+					payment_hashes.remove(0);
+					let _ = payment_hashes.try_push(*payment_hash);
+				}
+			});
+		}
+
+		fn _forget_dispute(who: &AccountOf<T>, payment_hash: &HashOf<T>) {
+			<DisputesOwned<T>>::mutate(who, |payment_hashes| {
+				payment_hashes.retain(|hash| hash != payment_hash)
+			});
+		}
 
-			if who == payer {
-				dispute.outcome = Judgment::ReleaseFundToPayer;
-			} else {
-				dispute.outcome = Judgment::ReleaseFundToPayee;
-			}
+		fn _accept_assignment(who: AccountOf<T>, payment_hash: HashOf<T>) -> DispatchResult {
+			let mut dispute = Self::disputes(&payment_hash).ok_or(<Error<T>>::DisputeNotFound)?;
+			let now = <timestamp::Pallet<T>>::get();
+
+			let assignment = dispute
+				.assignments
+				.iter_mut()
+				.find(|assignment| assignment.resolver == who)
+				.ok_or(<Error<T>>::AssignmentNotFound)?;
+
+			ensure!(!assignment.accepted, <Error<T>>::AssignmentAlreadyAccepted);
+			ensure!(now < assignment.deadline, <Error<T>>::AssignmentExpired);
+
+			let bond = T::ResolverBond::get();
+			ensure!(
+				T::Currency::free_balance(T::FeeCurrency::get(), &who) >= bond,
+				<Error<T>>::InsufficientBalance,
+			);
+			Self::_reserve_named(RESOLVER_BOND_RESERVE, &who, bond)?;
+			T::ResolversNetwork::open_case(&who);
+
+			let deadline = now + T::AssignmentJudgingTime::get();
+			assignment.accepted = true;
+			assignment.deadline = deadline;
 
-			dispute.status = DisputeStatus::Finalizing;
 			<Disputes<T>>::insert(&payment_hash, dispute);
+			Self::_record_dispute(&who, &payment_hash);
 
-			Self::deposit_event(Event::DisputeEscalated { payment_hash, payer, payee });
+			Self::deposit_event(Event::AssignmentAccepted {
+				payment_hash,
+				resolver: who,
+				bond,
+				deadline,
+			});
 
 			Ok(())
 		}
 
-		// Assigned resolvers propose their judgement after evaluation.
-		fn _propose_outcome(
-			who: AccountOf<T>,
-			payment_hash: HashOf<T>,
-			judgment: Judgment,
-		) -> DispatchResult {
+		fn _decline_assignment(who: AccountOf<T>, payment_hash: HashOf<T>) -> DispatchResult {
 			let mut dispute = Self::disputes(&payment_hash).ok_or(<Error<T>>::DisputeNotFound)?;
 
-			// Ensure only selected resolver can propose for the outcome.
-			ensure!(dispute.resolvers.contains(&who), <Error<T>>::AccessDenied);
-			// Ensure selected resolver can give decision once.
-			ensure!(!dispute.judgments.iter().any(|i| i.0 == who), <Error<T>>::AccessDenied);
+			let position = dispute
+				.assignments
+				.iter()
+				.position(|assignment| assignment.resolver == who)
+				.ok_or(<Error<T>>::AssignmentNotFound)?;
 
-			dispute.judgments.push((who, judgment));
+			ensure!(!dispute.assignments[position].accepted, <Error<T>>::AssignmentAlreadyAccepted);
 
-			// The dispute will be concluded if get enough judgments from resolvers.
-			if dispute.resolvers.len() == dispute.judgments.len() {
-				let mut transcript = Transcript { release_to_payer: 0, release_to_payee: 0 };
+			Self::_replace_resolver(&payment_hash, &mut dispute, position)?;
+			Self::_conclude_incomplete_panel(&payment_hash, &mut dispute)?;
+			<Disputes<T>>::insert(&payment_hash, dispute);
 
-				for (_who, judgement) in dispute.judgments.iter() {
-					match judgement {
-						Judgment::ReleaseFundToPayee => transcript.release_to_payee += 1,
-						Judgment::ReleaseFundToPayer => transcript.release_to_payer += 1,
-					}
-				}
+			Self::deposit_event(Event::AssignmentDeclined { payment_hash, resolver: who });
+
+			Ok(())
+		}
 
-				dispute.outcome = if transcript.release_to_payee > transcript.release_to_payer {
-					Judgment::ReleaseFundToPayee
+		fn _expire_assignments(payment_hash: HashOf<T>) -> DispatchResult {
+			let mut dispute = Self::disputes(&payment_hash).ok_or(<Error<T>>::DisputeNotFound)?;
+			let now = <timestamp::Pallet<T>>::get();
+
+			let expired: Vec<Assignment<T>> = dispute
+				.assignments
+				.iter()
+				.filter(|assignment| now >= assignment.deadline)
+				.cloned()
+				.collect();
+
+			ensure!(!expired.is_empty(), <Error<T>>::NoExpiredAssignment);
+
+			for assignment in expired {
+				let position = dispute
+					.assignments
+					.iter()
+					.position(|i| i.resolver == assignment.resolver)
+					.ok_or(<Error<T>>::AssignmentNotFound)?;
+
+				Self::_replace_resolver(&payment_hash, &mut dispute, position)?;
+
+				// The resolver accepted the assignment but does not propose the judgment in time,
+				// the bond is slashed to the insurance fund.
+				let slashed = if assignment.accepted {
+					T::ResolversNetwork::close_case(&assignment.resolver);
+					let bond = T::ResolverBond::get();
+					bond - Self::_repatriate_reserved_named(
+						RESOLVER_BOND_RESERVE,
+						&assignment.resolver,
+						&Self::insurance_fund_account(),
+						bond,
+					)?
 				} else {
-					Judgment::ReleaseFundToPayer
+					Zero::zero()
 				};
 
-				dispute.status = DisputeStatus::Finalizing;
-				Self::_add_finalizing_dispute(payment_hash)?;
+				let claim = T::SlashCompensationShare::get() * slashed;
+				if !claim.is_zero() {
+					<SlashClaims<T>>::mutate(&payment_hash, |amount| {
+						*amount = amount.saturating_add(claim)
+					});
+					Self::deposit_event(Event::SlashClaimRecorded {
+						payment_hash,
+						resolver: assignment.resolver.clone(),
+						amount: claim,
+					});
+				}
+
+				<SlaCounters<T>>::mutate(|counts| {
+					if assignment.accepted {
+						counts.judging_timeouts += 1;
+					} else {
+						counts.acceptance_timeouts += 1;
+					}
+				});
+
+				Self::deposit_event(Event::AssignmentExpired {
+					payment_hash,
+					resolver: assignment.resolver,
+					slashed,
+				});
 			}
 
+			Self::_conclude_incomplete_panel(&payment_hash, &mut dispute)?;
 			<Disputes<T>>::insert(&payment_hash, dispute);
 
 			Ok(())
 		}
 
+		fn _dispute_review(
+			account: AccountOf<T>,
+			reviewer: AccountOf<T>,
+			argument: Vec<u8>,
+		) -> DispatchResult {
+			ensure!(
+				T::IdentitiesManager::has_published_review(&account, &reviewer),
+				<Error<T>>::ReviewNotFound
+			);
+			ensure!(
+				!<ReviewDisputes<T>>::contains_key(&account, &reviewer),
+				<Error<T>>::ReviewDisputeExisted
+			);
+
+			let resolver = T::ResolversNetwork::get_resolver(
+				T::Hashing::hash_of(&(&account, &reviewer)),
+				[].to_vec(),
+				Self::_party_languages(&account, &reviewer),
+				[account.clone(), reviewer.clone()].to_vec(),
+				None,
+			)?;
+
+			let fee = T::ReviewDisputeFee::get();
+			ensure!(
+				T::Currency::free_balance(T::FeeCurrency::get(), &account) >= fee,
+				<Error<T>>::InsufficientBalance,
+			);
+			Self::_reserve_named(REVIEW_DISPUTE_FEE_RESERVE, &account, fee)?;
+
+			let content_hash = T::Hashing::hash_of(&argument);
+			offchain_index::set(&indexed_content_key(&content_hash), &argument);
+
+			let deadline = <timestamp::Pallet<T>>::get() +
+				T::AssignmentAcceptanceTime::get() +
+				T::AssignmentJudgingTime::get();
+			<ReviewDisputes<T>>::insert(
+				&account,
+				&reviewer,
+				ReviewDispute {
+					resolver: resolver.clone(),
+					fee,
+					content_hash,
+					deadline,
+					verdict: None,
+				},
+			);
+
+			Self::deposit_event(Event::ReviewDisputed {
+				account,
+				reviewer,
+				resolver,
+				fee,
+				deadline,
+			});
+
+			Ok(())
+		}
+
+		fn _resolve_review_dispute(
+			who: AccountOf<T>,
+			account: AccountOf<T>,
+			reviewer: AccountOf<T>,
+			verdict: ReviewVerdict,
+		) -> DispatchResult {
+			let mut dispute = Self::review_disputes(&account, &reviewer)
+				.ok_or(<Error<T>>::ReviewDisputeNotFound)?;
+			ensure!(dispute.verdict.is_none(), <Error<T>>::ReviewDisputeNotFound);
+			ensure!(dispute.resolver == who, <Error<T>>::NotAssignedResolver);
+			ensure!(
+				<timestamp::Pallet<T>>::get() < dispute.deadline,
+				<Error<T>>::AssignmentExpired
+			);
+
+			if verdict == ReviewVerdict::Remove {
+				T::IdentitiesManager::remove_contested_review(&account, &reviewer)?;
+			}
+
+			// The resolver is paid whatever the verdict, the insurance fund takes its share.
+			let insurance = T::InsuranceFundShare::get() * dispute.fee;
+			Self::_unreserve_named(REVIEW_DISPUTE_FEE_RESERVE, &account, dispute.fee);
+			T::Currency::transfer(T::FeeCurrency::get(), &account, &who, dispute.fee - insurance)?;
+			T::Currency::transfer(
+				T::FeeCurrency::get(),
+				&account,
+				&Self::insurance_fund_account(),
+				insurance,
+			)?;
+			dispute.verdict = Some(verdict);
+			<ReviewDisputes<T>>::insert(&account, &reviewer, dispute);
+
+			Self::deposit_event(Event::ReviewDisputeResolved {
+				account,
+				reviewer,
+				resolver: who,
+				verdict,
+			});
+
+			Ok(())
+		}
+
+		fn _draw_resolvers(payment_hash: &HashOf<T>) -> DispatchResult {
+			let mut dispute = Self::disputes(payment_hash).ok_or(<Error<T>>::DisputeNotFound)?;
+			Self::_select_resolvers(payment_hash, &mut dispute)?;
+			<Disputes<T>>::insert(payment_hash, dispute);
+			Ok(())
+		}
+
 		fn _compute_dispute_fee(number_of_resolvers: usize) -> BalanceOf<T> {
 			T::DisputeFee::get() * number_of_resolvers.saturated_into::<BalanceOf<T>>()
 		}
 
 		fn _lock_resolvers_fee(requestor: &AccountOf<T>, fee: BalanceOf<T>) -> DispatchResult {
 			ensure!(
-				T::Currency::free_balance(CurrencyId::Native, requestor) >= fee,
+				T::Currency::free_balance(T::FeeCurrency::get(), requestor) >= fee,
 				<Error<T>>::InsufficientBalance,
 			);
-			T::Currency::reserve(CurrencyId::Native, requestor, fee)?;
+			Self::_reserve_named(DISPUTE_FEE_RESERVE, requestor, fee)?;
+			Ok(())
+		}
+
+		fn _escrow_resolvers_fee(
+			dispute: &mut Dispute<T>,
+			who: &AccountOf<T>,
+			payer: &AccountOf<T>,
+			fee: BalanceOf<T>,
+		) -> DispatchResult {
+			Self::_lock_resolvers_fee(who, fee)?;
+			if who == payer {
+				dispute.payer_fee += fee;
+			} else {
+				dispute.payee_fee += fee;
+			}
 			Ok(())
 		}
 
-		fn _release_resolvers_fee(who: &AccountOf<T>, fee: BalanceOf<T>) {
-			T::Currency::unreserve(CurrencyId::Native, who, fee);
+		fn _refund_resolvers_fee(payment_hash: &HashOf<T>, who: &AccountOf<T>, fee: BalanceOf<T>) {
+			if fee.is_zero() {
+				return
+			}
+			Self::_unreserve_named(DISPUTE_FEE_RESERVE, who, fee);
+			Self::deposit_event(Event::DisputeFeeRefunded {
+				payment_hash: *payment_hash,
+				account: who.clone(),
+				amount: fee,
+			});
 		}
 
+		// Pay the resolvers from the escrowed fee of the account, returns the rest of the fee.
 		fn _distribute_resolvers_fee(
+			payment_hash: &HashOf<T>,
 			who: &AccountOf<T>,
-			resolvers: Vec<AccountOf<T>>,
-		) -> DispatchResult {
+			escrowed_fee: BalanceOf<T>,
+			resolvers: &[AccountOf<T>],
+			conceded: bool,
+		) -> Result<BalanceOf<T>, DispatchError> {
+			let fee = T::DisputeFee::get();
+			let insurance = T::InsuranceFundShare::get() * fee;
+			// The insurance fund share is left in the rest fee of the party who conceded.
+			let charged = if conceded { fee - insurance } else { fee };
+			let mut rest = escrowed_fee;
+			let mut total_insurance: BalanceOf<T> = Zero::zero();
+
 			for resolver in resolvers {
-				T::Currency::unreserve(CurrencyId::Native, who, T::DisputeFee::get());
-				T::Currency::transfer(CurrencyId::Native, who, &resolver, T::DisputeFee::get())?;
+				Self::_unreserve_named(DISPUTE_FEE_RESERVE, who, charged);
+				T::Currency::transfer(T::FeeCurrency::get(), who, resolver, fee - insurance)?;
+				if !conceded {
+					T::Currency::transfer(
+						T::FeeCurrency::get(),
+						who,
+						&Self::insurance_fund_account(),
+						insurance,
+					)?;
+					total_insurance += insurance;
+				}
+				rest = rest.saturating_sub(charged);
+
+				Self::deposit_event(Event::ResolverFeePaid {
+					payment_hash: *payment_hash,
+					resolver: resolver.clone(),
+					amount: fee - insurance,
+				});
+			}
+
+			if !total_insurance.is_zero() {
+				Self::deposit_event(Event::InsuranceFundDeposited {
+					payment_hash: *payment_hash,
+					amount: total_insurance,
+				});
+			}
+
+			Ok(rest)
+		}
+
+		// The resolvers are paid from the escrowed fee of the losing party, or the challenger if
+		// the dispute is challenged. The rest escrowed fee is refunded.
+		fn _settle_resolvers_fee(
+			payment_hash: &HashOf<T>,
+			dispute: &mut Dispute<T>,
+			payer: &AccountOf<T>,
+			payee: &AccountOf<T>,
+			conceded: bool,
+		) -> DispatchResult {
+			let payer_pays = match &dispute.challenge {
+				Some(challenge) => challenge.challenger == *payer,
+				None => dispute.outcome == Judgment::ReleaseFundToPayee,
+			};
+
+			let (paying_party, paying_fee, other_party, other_fee) = if payer_pays {
+				(payer, dispute.payer_fee, payee, dispute.payee_fee)
+			} else {
+				(payee, dispute.payee_fee, payer, dispute.payer_fee)
+			};
+
+			Self::_refund_resolvers_fee(payment_hash, other_party, other_fee);
+			let rest = Self::_distribute_resolvers_fee(
+				payment_hash,
+				paying_party,
+				paying_fee,
+				&dispute.resolvers,
+				conceded,
+			)?;
+			Self::_refund_resolvers_fee(payment_hash, paying_party, rest);
+
+			dispute.payer_fee = Zero::zero();
+			dispute.payee_fee = Zero::zero();
+
+			Ok(())
+		}
+
+		fn _compensate(
+			payment_hash: HashOf<T>,
+			beneficiary: AccountOf<T>,
+			amount: BalanceOf<T>,
+		) -> DispatchResult {
+			let dispute = Self::disputes(&payment_hash).ok_or(<Error<T>>::DisputeNotFound)?;
+
+			ensure!(dispute.status == DisputeStatus::Resolved, <Error<T>>::DisputeNotResolved);
+			ensure!(
+				!<Compensations<T>>::contains_key(&payment_hash),
+				<Error<T>>::DisputeAlreadyCompensated
+			);
+			ensure!(amount <= Self::compensation_cap(), <Error<T>>::CompensationExceedsCap);
+
+			let fund = Self::insurance_fund_account();
+			ensure!(
+				T::Currency::free_balance(T::FeeCurrency::get(), &fund) >= amount,
+				<Error<T>>::InsufficientInsuranceFund
+			);
+
+			T::Currency::transfer(T::FeeCurrency::get(), &fund, &beneficiary, amount)?;
+			<Compensations<T>>::insert(&payment_hash, amount);
+
+			Self::deposit_event(Event::Compensated { payment_hash, beneficiary, amount });
+
+			Ok(())
+		}
+
+		fn _pay_slash_claim(
+			payment_hash: &HashOf<T>,
+			dispute: &Dispute<T>,
+			payer: &AccountOf<T>,
+			payee: &AccountOf<T>,
+		) -> DispatchResult {
+			let claim = <SlashClaims<T>>::take(payment_hash);
+			if claim.is_zero() {
+				return Ok(())
+			}
+
+			let beneficiary = match dispute.outcome {
+				Judgment::ReleaseFundToPayee => payee.clone(),
+				Judgment::ReleaseFundToPayer => payer.clone(),
+			};
+
+			// The fund may be drawn by the compensations since the bond was slashed.
+			let fund = Self::insurance_fund_account();
+			let amount = claim.min(T::Currency::free_balance(T::FeeCurrency::get(), &fund));
+			if amount.is_zero() {
+				return Ok(())
 			}
+
+			T::Currency::transfer(T::FeeCurrency::get(), &fund, &beneficiary, amount)?;
+
+			Self::deposit_event(Event::SlashClaimPaid {
+				payment_hash: *payment_hash,
+				beneficiary,
+				amount,
+			});
+
 			Ok(())
 		}
 
@@ -430,115 +2242,404 @@ pub mod pallet {
 			Ok(())
 		}
 
+		fn _ensure_not_paused(call: PausableCall) -> DispatchResult {
+			ensure!(!Self::paused_calls(call), <Error<T>>::CallPaused);
+			Ok(())
+		}
+
 		fn _remove_finalizing_dispute(hash: &HashOf<T>) -> DispatchResult {
 			<FinalizingDisputes<T>>::mutate(|hashes| hashes.retain(|&h| h != *hash));
 			Ok(())
 		}
 
 		fn _process_finalizing_disputes() -> DispatchResult {
+			// The disputes stay in the queue until the finalization is unpaused.
+			if Self::paused_calls(PausableCall::FinalizeDispute) {
+				return Ok(())
+			}
+
 			let hashes = <FinalizingDisputes<T>>::get();
+			let now = <timestamp::Pallet<T>>::get();
 
 			let mut resolved_disputes: Vec<T::Hash> = Vec::new();
 
 			for hash in hashes.iter() {
-				let mut dispute = Self::disputes(&hash).ok_or(<Error<T>>::DisputeNotFound)?;
-				let now = <timestamp::Pallet<T>>::get();
-				let (payer, payee, amount, currency_id) = T::PaymentProtocol::get_payment(hash)?;
+				let dispute = Self::disputes(&hash).ok_or(<Error<T>>::DisputeNotFound)?;
+
+				// The queue is sorted by time. If a dispute is in the waiting time, the rest of
+				// the queue after the dispute is still in the waiting time.
+				if now < dispute.expired_at {
+					break
+				}
 
 				// If dispute is out of finalizing time, finalize it as the outcome.
-				if now >= dispute.expired_at {
-					match dispute.outcome {
-						Judgment::ReleaseFundToPayee => {
-							T::Currency::unreserve(currency_id, &payer, amount);
-							T::Currency::transfer(currency_id, &payer, &payee, amount)?;
-							Self::_release_resolvers_fee(&payee, dispute.fee);
-							Self::_distribute_resolvers_fee(&payer, dispute.resolvers.clone())?;
-
-							if T::IdentitiesManager::has_identity(&payee) {
-								T::IdentitiesManager::increase_credibility(
-									&payee,
-									T::CredibilityGain::get(),
-								)?;
-							}
-
-							if T::IdentitiesManager::has_identity(&payer) {
-								T::IdentitiesManager::decrease_credibility(
-									&payer,
-									T::CredibilityLoss::get(),
-								)?;
-							}
-						},
-						Judgment::ReleaseFundToPayer => {
-							T::Currency::unreserve(currency_id, &payer, amount);
-							Self::_release_resolvers_fee(&payer, dispute.fee);
-							Self::_distribute_resolvers_fee(&payee, dispute.resolvers.clone())?;
-
-							// Only decrease credibility of payee and increase credibility if the
-							// payee fight dispute and lose.
-							if !dispute.resolvers.is_empty() {
-								if T::IdentitiesManager::has_identity(&payee) {
-									T::IdentitiesManager::decrease_credibility(
-										&payee,
-										T::CredibilityLoss::get(),
-									)?;
-								}
-								if T::IdentitiesManager::has_identity(&payer) {
-									T::IdentitiesManager::increase_credibility(
-										&payer,
-										T::CredibilityGain::get(),
-									)?;
-								}
-							}
-						},
-					}
+				Self::_finalize_dispute(hash, false)?;
+				resolved_disputes.push(*hash);
+			}
 
-					for (resolver, judgment) in dispute.judgments.clone() {
-						// Increase credibility for the resolver who make the correct judgment.
-						if judgment == dispute.outcome {
-							T::ResolversNetwork::increase_credibility(
-								&resolver,
-								T::CredibilityGain::get(),
-							)?;
-						} else {
-							// Decrease credibility for the resolver who make the correct judgment.
-							T::ResolversNetwork::decrease_credibility(
-								resolver.clone(),
-								T::CredibilityLoss::get(),
-							)?;
-						}
-					}
+			// Remove resolved dispute from the queue.
+			for hash in resolved_disputes.iter() {
+				Self::_remove_finalizing_dispute(hash)?;
+			}
 
-					dispute.status = DisputeStatus::Resolved;
+			Ok(())
+		}
 
-					<Disputes<T>>::insert(&hash, dispute);
-					Self::deposit_event(Event::DisputeResolved {
-						payment_hash: *hash,
-						payer,
-						payee,
-						currency_id,
-						amount,
-					});
+		// A dispute conceded by the losing party is finalized without waiting for the finalizing
+		// time.
+		fn _finalize_dispute(hash: &HashOf<T>, conceded: bool) -> DispatchResult {
+			let mut dispute = Self::disputes(&hash).ok_or(<Error<T>>::DisputeNotFound)?;
+			let now = <timestamp::Pallet<T>>::get();
+
+			ensure!(
+				dispute.status == DisputeStatus::Finalizing &&
+					(conceded || now >= dispute.expired_at),
+				<Error<T>>::DisputeNotFinalizable
+			);
+
+			let (payer, payee, amount, currency_id) = T::PaymentProtocol::get_payment(hash)?;
+
+			Self::_settle_resolvers_fee(hash, &mut dispute, &payer, &payee, conceded)?;
+
+			// The payment was settled by the challenged resolution.
+			if dispute.challenge.is_some() {
+				Self::_settle_challenge(hash, &dispute, &payer, &payee, amount, currency_id)?;
+			} else {
+				let shares = Self::dispute_stakeholders(hash);
+				T::PaymentProtocol::resolve(hash, dispute.outcome.clone(), &shares)?;
+			}
+
+			Self::_update_credibility(&dispute, &payer, &payee)?;
+			Self::_pay_slash_claim(hash, &dispute, &payer, &payee)?;
+
+			// The resolution of a challenge is not sampled again.
+			if dispute.resolved_at.is_none() {
+				Self::_record_sla(hash, &dispute, now);
+			}
+			Self::_record_precedent(hash, &dispute, now);
+
+			dispute.status = DisputeStatus::Resolved;
+			dispute.resolved_at = Some(now);
+			let outcome = dispute.outcome.clone();
+
+			<Disputes<T>>::insert(&hash, dispute);
+			Self::deposit_event(Event::DisputeResolved {
+				payment_hash: *hash,
+				payer,
+				payee,
+				currency_id,
+				amount,
+				outcome,
+			});
+
+			Ok(())
+		}
+
+		// Record the phase durations of a resolved dispute.
+		fn _record_sla(hash: &HashOf<T>, dispute: &Dispute<T>, now: MomentOf<T>) {
+			<SlaCounters<T>>::mutate(|counts| {
+				counts.resolved += 1;
+				if !dispute.resolvers.is_empty() {
+					counts.fought += 1;
+				}
+			});
+
+			// The disputes created before the timelines were tracked are not sampled.
+			let timeline = match <DisputeTimelines<T>>::take(hash) {
+				Some(timeline) => timeline,
+				None => return,
+			};
+			let since_creation = |at: MomentOf<T>| at.saturating_sub(timeline.created_at);
+			let sample = SlaSample::<T> {
+				time_to_panel: timeline.panel_drawn_at.map(since_creation),
+				time_to_verdict: timeline.verdict_at.map(since_creation),
+				time_to_payout: since_creation(now),
+			};
+
+			<SlaSamples<T>>::mutate(|samples| {
+				if samples.try_push(sample.clone()).is_err() && !samples.is_empty() {
+					samples.remove(0);
+					let _ = samples.try_push(sample);
+				}
+			});
+		}
+
+		// Index a fought dispute as a precedent of the category of the payment. The precedent of a
+		// challenged dispute is replaced by the resolution of the challenge.
+		fn _record_precedent(hash: &HashOf<T>, dispute: &Dispute<T>, now: MomentOf<T>) {
+			let category = match T::PaymentProtocol::get_category(hash) {
+				Some(category) if !dispute.resolvers.is_empty() => category,
+				_ => return,
+			};
+			let precedent = Precedent::<T> {
+				payment_hash: *hash,
+				outcome: dispute.outcome.clone(),
+				rationales: dispute
+					.judgments
+					.iter()
+					.filter(|(_, judgment)| *judgment == dispute.outcome)
+					.filter_map(|(resolver, _)| Self::judgment_rationales(hash, resolver))
+					.collect(),
+				resolved_at: now,
+			};
+
+			<Precedents<T>>::mutate(category, |precedents| {
+				precedents.retain(|precedent| precedent.payment_hash != *hash);
+				if precedents.try_push(precedent.clone()).is_err() && !precedents.is_empty() {
+					precedents.remove(0);
+					let _ = precedents.try_push(precedent);
+				}
+			});
+			Self::deposit_event(Event::PrecedentRecorded {
+				payment_hash: *hash,
+				category,
+				outcome: dispute.outcome.clone(),
+			});
+		}
+
+		// The upper median of the values.
+		fn _median(mut values: Vec<MomentOf<T>>) -> Option<MomentOf<T>> {
+			values.sort();
+			values.get(values.len() / 2).copied()
+		}
 
-					resolved_disputes.push(*hash);
+		// Update the credibility of the payment parties and the resolvers depending on whether
+		// their position matched the outcome of the dispute.
+		fn _update_credibility(
+			dispute: &Dispute<T>,
+			payer: &AccountOf<T>,
+			payee: &AccountOf<T>,
+		) -> DispatchResult {
+			let (winner, loser) = match dispute.outcome {
+				Judgment::ReleaseFundToPayee => (payee, payer),
+				Judgment::ReleaseFundToPayer => (payer, payee),
+			};
+
+			// A challenged dispute was counted on its first resolution and is only moved between
+			// the records of the parties if the challenge overturns it.
+			if dispute.challenge.is_none() {
+				T::IdentitiesManager::note_dispute_resolved(winner, loser);
+			}
+
+			// The credibility of the payment parties is only updated if the dispute is fought,
+			// a challenged dispute reverts the credibility of the parties on settlement instead.
+			if !dispute.resolvers.is_empty() && dispute.challenge.is_none() {
+				Self::_increase_party_credibility(winner, T::CredibilityGain::get())?;
+				Self::_decrease_party_credibility(loser, T::CredibilityLoss::get())?;
+			}
+
+			for (resolver, judgment) in dispute.judgments.iter() {
+				// Increase credibility for the resolver who make the correct judgment.
+				if *judgment == dispute.outcome {
+					T::ResolversNetwork::increase_credibility(resolver, T::CredibilityGain::get())?;
 				} else {
-					// The queue is sorted by time. If a dispute is in the waiting time, the rest of
-					// the queue after the dispute is still in the waiting time.
-					break
+					// Decrease credibility for the resolver who make the wrong judgment.
+					T::ResolversNetwork::decrease_credibility(
+						resolver.clone(),
+						T::CredibilityLoss::get(),
+					)?;
 				}
 			}
 
-			// Remove resolved dispute from the queue.
-			for hash in resolved_disputes.iter() {
-				Self::_remove_finalizing_dispute(hash)?;
+			Ok(())
+		}
+
+		fn _increase_party_credibility(who: &AccountOf<T>, amount: Credibility) -> DispatchResult {
+			if T::IdentitiesManager::has_identity(who) {
+				T::IdentitiesManager::increase_credibility(who, amount)?;
+			}
+			Ok(())
+		}
+
+		fn _decrease_party_credibility(who: &AccountOf<T>, amount: Credibility) -> DispatchResult {
+			if T::IdentitiesManager::has_identity(who) {
+				T::IdentitiesManager::decrease_credibility(who, amount)?;
+			}
+			Ok(())
+		}
+
+		fn _reserve_named(
+			id: ReserveIdentifier,
+			who: &AccountOf<T>,
+			amount: BalanceOf<T>,
+		) -> DispatchResult {
+			let currency_id = T::FeeCurrency::get();
+			T::Currency::reserve(currency_id, who, amount)?;
+			<NamedReserves<T>>::mutate(who, (id, currency_id), |reserved| {
+				*reserved = reserved.saturating_add(amount)
+			});
+			Ok(())
+		}
+
+		// Unreserve at most the fee currency tracked under the reserve identifier, returns the
+		// amount that cannot be unreserved.
+		fn _unreserve_named(
+			id: ReserveIdentifier,
+			who: &AccountOf<T>,
+			amount: BalanceOf<T>,
+		) -> BalanceOf<T> {
+			let currency_id = T::FeeCurrency::get();
+			let to_unreserve = amount.min(Self::named_reserves(who, (id, currency_id)));
+			let unreserved = to_unreserve - T::Currency::unreserve(currency_id, who, to_unreserve);
+			Self::_untrack_reserve(id, who, unreserved);
+
+			amount - unreserved
+		}
+
+		// Move at most the fee currency tracked under the reserve identifier to the free balance
+		// of the beneficiary, returns the amount that cannot be moved.
+		fn _repatriate_reserved_named(
+			id: ReserveIdentifier,
+			slashed: &AccountOf<T>,
+			beneficiary: &AccountOf<T>,
+			amount: BalanceOf<T>,
+		) -> Result<BalanceOf<T>, DispatchError> {
+			let currency_id = T::FeeCurrency::get();
+			let to_move = amount.min(Self::named_reserves(slashed, (id, currency_id)));
+			let moved = to_move -
+				T::Currency::repatriate_reserved(
+					currency_id,
+					slashed,
+					beneficiary,
+					to_move,
+					BalanceStatus::Free,
+				)?;
+			Self::_untrack_reserve(id, slashed, moved);
+
+			Ok(amount - moved)
+		}
+
+		fn _untrack_reserve(id: ReserveIdentifier, who: &AccountOf<T>, amount: BalanceOf<T>) {
+			<NamedReserves<T>>::mutate_exists(who, (id, T::FeeCurrency::get()), |reserved| {
+				let left = reserved.unwrap_or_default().saturating_sub(amount);
+				*reserved = if left.is_zero() { None } else { Some(left) };
+			});
+		}
+
+		fn _settle_challenge(
+			hash: &HashOf<T>,
+			dispute: &Dispute<T>,
+			payer: &AccountOf<T>,
+			payee: &AccountOf<T>,
+			amount: BalanceOf<T>,
+			currency_id: CurrencyId<T::Hash>,
+		) -> DispatchResult {
+			let challenge = dispute.challenge.clone().ok_or(<Error<T>>::DisputeNotFinalizable)?;
+
+			if dispute.outcome == challenge.original_outcome {
+				Self::_repatriate_reserved_named(
+					CHALLENGE_BOND_RESERVE,
+					&challenge.challenger,
+					&Self::insurance_fund_account(),
+					challenge.bond,
+				)?;
+				return Ok(())
+			}
+
+			Self::_unreserve_named(CHALLENGE_BOND_RESERVE, &challenge.challenger, challenge.bond);
+
+			// Claw back the funds from the party who wrongly received them, as much as possible.
+			let (from, to) = match dispute.outcome {
+				Judgment::ReleaseFundToPayer => (payee, payer),
+				Judgment::ReleaseFundToPayee => (payer, payee),
+			};
+			let clawback = amount.min(T::Currency::free_balance(currency_id, from));
+			let recipient = match dispute.outcome {
+				Judgment::ReleaseFundToPayer => T::PaymentProtocol::get_refund_address(hash)?,
+				Judgment::ReleaseFundToPayee => payee.clone(),
+			};
+			T::Currency::transfer(currency_id, from, &recipient, clawback)?;
+
+			T::IdentitiesManager::note_dispute_overturned(to, from);
+
+			// Revert the credibility the parties and the previous resolvers gained or lost.
+			let adjustment = T::CredibilityGain::get() + T::CredibilityLoss::get();
+			Self::_increase_party_credibility(to, adjustment)?;
+			Self::_decrease_party_credibility(from, adjustment)?;
+			for (resolver, judgment) in challenge.original_judgments {
+				if judgment == challenge.original_outcome {
+					T::ResolversNetwork::decrease_credibility(resolver, adjustment)?;
+				} else {
+					T::ResolversNetwork::increase_credibility(&resolver, adjustment)?;
+				}
+			}
+
+			Self::deposit_event(Event::DisputeOverturned {
+				payment_hash: *hash,
+				outcome: dispute.outcome.clone(),
+				clawback,
+			});
+
+			Ok(())
+		}
+
+		// Add the argument of the party to the dispute, the content is stored off-chain.
+		fn _submit_evidence(
+			payment_hash: &HashOf<T>,
+			dispute: &mut Dispute<T>,
+			provider: AccountOf<T>,
+			argument: Vec<u8>,
+		) {
+			let content_hash = Self::_save_large_content(argument);
+			dispute
+				.arguments
+				.push(Argument::<T> { provider: provider.clone(), content_hash });
+			Self::deposit_event(Event::EvidenceSubmitted {
+				payment_hash: *payment_hash,
+				provider,
+				content_hash,
+			});
+		}
+
+		fn _submit_private_evidence(
+			who: AccountOf<T>,
+			payment_hash: HashOf<T>,
+			envelopes: Vec<(AccountOf<T>, Vec<u8>)>,
+		) -> DispatchResult {
+			let dispute = Self::disputes(&payment_hash).ok_or(<Error<T>>::DisputeNotFound)?;
+			let (payer, payee, ..) = T::PaymentProtocol::get_payment(&payment_hash)?;
+
+			let is_stakeholder = Self::dispute_stakeholders(&payment_hash)
+				.iter()
+				.any(|(stakeholder, _)| *stakeholder == who);
+			ensure!(who == payer || who == payee || is_stakeholder, <Error<T>>::NotPaymentParty);
+			ensure!(dispute.status == DisputeStatus::Evaluating, <Error<T>>::DisputeNotEvaluating);
+
+			let mut submitted: Vec<EvidenceEnvelope<T>> = [].to_vec();
+			for (resolver, ciphertext) in envelopes {
+				ensure!(
+					dispute.resolvers.contains(&resolver) &&
+						!submitted.iter().any(|envelope| envelope.resolver == resolver),
+					<Error<T>>::InvalidEnvelopeRecipient
+				);
+				let encryption_key = T::ResolversNetwork::get_encryption_key(&resolver)
+					.ok_or(<Error<T>>::EncryptionKeyNotFound)?;
+				submitted.push(EvidenceEnvelope::<T> {
+					provider: who.clone(),
+					resolver,
+					encryption_key,
+					content_hash: Self::_save_large_content(ciphertext),
+				});
 			}
 
+			let envelope_hashes = submitted
+				.iter()
+				.map(|envelope| (envelope.resolver.clone(), envelope.content_hash))
+				.collect();
+			<EvidenceEnvelopes<T>>::mutate(&payment_hash, |envelopes| envelopes.extend(submitted));
+			Self::deposit_event(Event::PrivateEvidenceSubmitted {
+				payment_hash,
+				provider: who,
+				envelopes: envelope_hashes,
+			});
+
 			Ok(())
 		}
 
 		// Use offchain indexing to store large content in the offchain worker.
 		fn _save_large_content(content: Vec<u8>) -> T::Hash {
 			let content_hash = T::Hashing::hash_of(&content);
-			offchain_index::set(&content_hash.encode(), &content);
+			offchain_index::set(&indexed_content_key(&content_hash), &content);
 			content_hash
 		}
 	}
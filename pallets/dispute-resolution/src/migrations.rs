@@ -0,0 +1,208 @@
+//! Storage migrations of the dispute resolution pallet.
+//!
+//! Each migration upgrades the `StorageVersion` of the pallet by one and only runs if the on-chain
+//! version is the previous one. The migrations can be rehearsed against a live chain with the
+//! `try-runtime` feature, which runs the `pre_upgrade` and `post_upgrade` checks.
+
+use crate::{Config, DisputeStatus, Disputes, NamedReserves, Pallet};
+#[cfg(feature = "try-runtime")]
+use frame_support::traits::OnRuntimeUpgradeHelpersExt;
+use frame_support::{pallet_prelude::*, traits::OnRuntimeUpgrade};
+
+/// Version 1 translates the disputes created before the storage versioning, which escrowed the same
+/// `fee` for each party who took part. The fee is split into the fees of the payer and of the
+/// payee, none for the payee if the dispute was never fought. The resolvers of the evaluating
+/// disputes yet to judge get an assignment to accept from the upgrade, the escalations are counted
+/// from the size of the panel, which grew to twice plus one on each escalation, and the resolved
+/// disputes are stamped resolved at their finalizing deadline.
+pub mod v1 {
+	use super::*;
+	use crate::{Argument, Assignment, Dispute};
+	use frame_support::{sp_runtime::traits::Zero, sp_std::vec::Vec};
+	use orml_traits::MultiCurrency;
+	use primitives::Judgment;
+
+	type BalanceOf<T> =
+		<<T as Config>::Currency as MultiCurrency<<T as frame_system::Config>::AccountId>>::Balance;
+	type MomentOf<T> = <T as pallet_timestamp::Config>::Moment;
+
+	#[derive(Encode, Decode)]
+	struct OldDispute<T: Config> {
+		status: DisputeStatus,
+		payment_hash: T::Hash,
+		expired_at: MomentOf<T>,
+		arguments: Vec<Argument<T>>,
+		resolvers: Vec<T::AccountId>,
+		fee: BalanceOf<T>,
+		judgments: Vec<(T::AccountId, Judgment)>,
+		outcome: Judgment,
+	}
+
+	pub struct MigrateToV1<T>(PhantomData<T>);
+
+	impl<T: Config> OnRuntimeUpgrade for MigrateToV1<T> {
+		fn on_runtime_upgrade() -> Weight {
+			if Pallet::<T>::on_chain_storage_version() != 0 {
+				return T::DbWeight::get().reads(1)
+			}
+
+			let deadline =
+				<pallet_timestamp::Pallet<T>>::get() + T::AssignmentAcceptanceTime::get();
+			let mut disputes: Weight = 0;
+			Disputes::<T>::translate::<OldDispute<T>, _>(|_, old| {
+				disputes += 1;
+				let fought = !old.resolvers.is_empty();
+				let assignments = if old.status == DisputeStatus::Evaluating {
+					old.resolvers
+						.iter()
+						.filter(|resolver| {
+							!old.judgments.iter().any(|(judged, _)| judged == *resolver)
+						})
+						.map(|resolver| Assignment::<T> {
+							resolver: resolver.clone(),
+							accepted: false,
+							deadline,
+						})
+						.collect()
+				} else {
+					[].to_vec()
+				};
+				let escalations = (old.resolvers.len() as u32 + 1)
+					.checked_next_power_of_two()
+					.map_or(0, |panels| panels.trailing_zeros().saturating_sub(1));
+				let resolved_at =
+					if old.status == DisputeStatus::Resolved { Some(old.expired_at) } else { None };
+				Some(Dispute::<T> {
+					status: old.status,
+					payment_hash: old.payment_hash,
+					expired_at: old.expired_at,
+					arguments: old.arguments,
+					resolvers: old.resolvers,
+					assignments,
+					payer_fee: old.fee,
+					payee_fee: if fought { old.fee } else { Zero::zero() },
+					judgments: old.judgments,
+					outcome: old.outcome,
+					escalations,
+					resolved_at,
+					challenge: None,
+				})
+			});
+
+			StorageVersion::new(1).put::<Pallet<T>>();
+			T::DbWeight::get().reads_writes(2 + disputes, 1 + disputes)
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<(), &'static str> {
+			Self::set_temp_storage(Disputes::<T>::iter_keys().count() as u32, "disputes");
+			Ok(())
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade() -> Result<(), &'static str> {
+			ensure!(
+				Pallet::<T>::on_chain_storage_version() >= 1,
+				"Storage version is not migrated"
+			);
+			let disputes: u32 =
+				Self::get_temp_storage("disputes").ok_or("Missing disputes count")?;
+			ensure!(
+				Disputes::<T>::iter_values().count() as u32 == disputes,
+				"Some disputes are not migrated"
+			);
+			Ok(())
+		}
+	}
+}
+
+/// Version 2 tracks the escrowed fees, the resolver bonds and the challenge bonds of the open
+/// disputes in `NamedReserves`. The funds are tracked as reserved, capped by the reserved balances
+/// of the accounts.
+pub mod v2 {
+	use super::*;
+	use frame_support::{
+		sp_runtime::traits::{Saturating, Zero},
+		sp_std::collections::btree_map::BTreeMap,
+	};
+	use orml_traits::{MultiCurrency, MultiReservableCurrency};
+	use pallet_lrp::PaymentProtocol;
+	use primitives::{
+		ReserveIdentifier, CHALLENGE_BOND_RESERVE, DISPUTE_FEE_RESERVE, RESOLVER_BOND_RESERVE,
+	};
+
+	type BalanceOf<T> =
+		<<T as Config>::Currency as MultiCurrency<<T as frame_system::Config>::AccountId>>::Balance;
+
+	pub struct MigrateToV2<T>(PhantomData<T>);
+
+	impl<T: Config> OnRuntimeUpgrade for MigrateToV2<T> {
+		fn on_runtime_upgrade() -> Weight {
+			if Pallet::<T>::on_chain_storage_version() != 1 {
+				return T::DbWeight::get().reads(1)
+			}
+
+			let mut reserves: BTreeMap<(T::AccountId, ReserveIdentifier), BalanceOf<T>> =
+				BTreeMap::new();
+			let mut add_reserve =
+				|who: T::AccountId, id: ReserveIdentifier, amount: BalanceOf<T>| {
+					let total = reserves.entry((who, id)).or_insert_with(Zero::zero);
+					*total = total.saturating_add(amount);
+				};
+
+			let mut disputes: Weight = 0;
+			for (payment_hash, dispute) in Disputes::<T>::iter() {
+				disputes += 1;
+				// The fees and the bonds of the resolved disputes are already settled.
+				if dispute.status == DisputeStatus::Resolved {
+					continue
+				}
+
+				if let Ok((payer, payee, ..)) = T::PaymentProtocol::get_payment(&payment_hash) {
+					add_reserve(payer, DISPUTE_FEE_RESERVE, dispute.payer_fee);
+					add_reserve(payee, DISPUTE_FEE_RESERVE, dispute.payee_fee);
+				}
+				for assignment in dispute.assignments.into_iter().filter(|i| i.accepted) {
+					add_reserve(assignment.resolver, RESOLVER_BOND_RESERVE, T::ResolverBond::get());
+				}
+				if let Some(challenge) = dispute.challenge {
+					add_reserve(challenge.challenger, CHALLENGE_BOND_RESERVE, challenge.bond);
+				}
+			}
+
+			// The reserved balance of an account is shared by all of its tracked reserves.
+			let currency_id = T::FeeCurrency::get();
+			let mut available: BTreeMap<T::AccountId, BalanceOf<T>> = BTreeMap::new();
+			let mut writes: Weight = 0;
+			for ((who, id), amount) in reserves {
+				let left = available
+					.entry(who.clone())
+					.or_insert_with(|| T::Currency::reserved_balance(currency_id, &who));
+				let tracked = amount.min(*left);
+				if !tracked.is_zero() {
+					*left -= tracked;
+					NamedReserves::<T>::insert(&who, (id, currency_id), tracked);
+					writes += 1;
+				}
+			}
+
+			StorageVersion::new(2).put::<Pallet<T>>();
+			T::DbWeight::get().reads_writes(2 + disputes * 2 + writes, 1 + writes)
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade() -> Result<(), &'static str> {
+			ensure!(
+				Pallet::<T>::on_chain_storage_version() == 2,
+				"Storage version is not migrated"
+			);
+			ensure!(
+				NamedReserves::<T>::iter().all(|(who, (_, currency_id), amount)| {
+					amount <= T::Currency::reserved_balance(currency_id, &who)
+				}),
+				"Some tracked funds are not reserved"
+			);
+			Ok(())
+		}
+	}
+}
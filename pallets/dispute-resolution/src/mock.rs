@@ -5,8 +5,10 @@ use crate as dispute_resolution;
 use frame_support::{
 	construct_runtime, parameter_types,
 	traits::{GenesisBuild, Nothing},
+	PalletId,
 };
 use frame_system as system;
+use frame_system::EnsureRoot;
 use orml_currencies::BasicCurrencyAdapter;
 use orml_traits::parameter_type_with_key;
 pub use pallet_balances::Call as BalancesCall;
@@ -18,6 +20,7 @@ pub use primitives::{Credibility, CurrencyId, Hash};
 use sp_runtime::{
 	generic,
 	traits::{BlakeTwo256, IdentityLookup},
+	Permill,
 };
 
 pub type BlockNumber = u64;
@@ -33,6 +36,7 @@ pub const BOB: AccountId = 2;
 pub const RESOLVER_1: AccountId = 4;
 pub const RESOLVER_2: AccountId = 5;
 pub const RESOLVER_3: AccountId = 6;
+pub const RESOLVER_4: AccountId = 7;
 
 pub const PENDING_PAYMENT_WAITING_TIME: Moment = 172800000;
 pub const FULFILLED_WAITING_TIME: Moment = 2592000000;
@@ -45,15 +49,30 @@ pub const MAX_CREDIBILITY: Credibility = 100;
 // Resolvers network config
 pub const PENALTY_TOKEN_LOCK_TIME: Moment = 172800000;
 pub const UNDELEGATE_TIME: Moment = 172800000;
+pub const CASE_EXPOSURE_TIME: Moment = 345600000;
 pub const MINIMUM_SELF_STAKE: Balance = 100;
 pub const ACTIVATION_STAKE_AMOUNT: Balance = 1000;
 pub const REQUIRED_CREDIBILITY: Credibility = 30;
+pub const ERA_DURATION: BlockNumber = 10;
 
 // pub const DISPUTE_FINALIZING_TIME: Moment = 2592000000;
 pub const DISPUTE_FINALIZING_TIME: Moment = 10_000;
 pub const DISPUTE_FEE: Balance = 100;
+pub const RISK_MATURE_ACCOUNT_AGE: Moment = 2592000000;
+pub const RISK_HIGH_AMOUNT: Balance = 1_000;
+pub const DAILY_SPENDING_PERIOD: Moment = 86_400_000;
+pub const WEEKLY_SPENDING_PERIOD: Moment = 604_800_000;
+pub const PAYEE_CONFIRMATION_THRESHOLD: Balance = 500;
+pub const PAYEE_CONFIRMATION_COOLDOWN: Moment = 86_400_000;
 pub const CREDIBILITY_GAIN: Credibility = 1;
 pub const CREDIBILITY_LOSS: Credibility = 10;
+pub const ASSIGNMENT_ACCEPTANCE_TIME: Moment = 6_000;
+pub const ASSIGNMENT_JUDGING_TIME: Moment = 12_000;
+pub const RESOLVER_BOND: Balance = 100;
+pub const INSURANCE_FUND: Balance = 1_000;
+pub const CHALLENGE_WINDOW: Moment = 10_000;
+pub const CHALLENGE_BOND: Balance = 200;
+pub const REVIEW_DISPUTE_FEE: Balance = 20;
 
 parameter_types! {
 	pub const BlockHashCount: u64 = 250;
@@ -148,69 +167,198 @@ impl orml_currencies::Config for Runtime {
 }
 
 parameter_types! {
-	pub const BondingAmount: Balance = 100_000_000_000_000;
+	pub const CommunityListingBond: Balance = 100_000_000_000_000;
+	pub const VerifiedListingBond: Balance = 300_000_000_000_000;
+	pub const VerifiedListingFee: Balance = 50_000_000_000_000;
+	pub const TreasuryAccount: AccountId = 99;
 }
 
 impl currencies_registry::Config for Runtime {
 	type Event = Event;
 	type Currency = Currencies;
-	type BondingAmount = BondingAmount;
+	type CommunityListingBond = CommunityListingBond;
+	type VerifiedListingBond = VerifiedListingBond;
+	type VerifiedListingFee = VerifiedListingFee;
+	type TreasuryAccount = TreasuryAccount;
+	type WeightInfo = ();
 }
 
 parameter_types! {
 	pub const PendingPaymentWaitingTime: Moment = PENDING_PAYMENT_WAITING_TIME;
 	pub const FulfilledPaymentWaitingTime: Moment = FULFILLED_WAITING_TIME;
+	pub const QueueBucketDuration: Moment = 6_000;
+	pub const MaxPaymentsPerBucket: u32 = 100;
+	pub const QueueBatchSize: u32 = 100;
+	pub const PaymentIndexBucketDuration: Moment = 86_400_000;
+	pub const RiskMatureAccountAge: Moment = RISK_MATURE_ACCOUNT_AGE;
+	pub const RiskHighAmount: Balance = RISK_HIGH_AMOUNT;
+	pub const DailySpendingPeriod: Moment = DAILY_SPENDING_PERIOD;
+	pub const WeeklySpendingPeriod: Moment = WEEKLY_SPENDING_PERIOD;
+	pub const PayeeConfirmationThreshold: Balance = PAYEE_CONFIRMATION_THRESHOLD;
+	pub const PayeeConfirmationCooldown: Moment = PAYEE_CONFIRMATION_COOLDOWN;
+	pub const FulfillmentTime: Moment = 604_800_000;
+	pub const MaxDeliveryProofs: u32 = 10;
+	pub const MaxPolicyCurrencies: u32 = 10;
+	pub const MaxReferralFeeShare: Permill = Permill::from_percent(50);
+	pub const MaxPayoutRoutes: u32 = 3;
+	pub const LrpPalletId: PalletId = PalletId(*b"lb/lrpes");
+	pub const StatementEraDuration: BlockNumber = 10;
+	pub const InheritanceInactivityPeriod: Moment = 15_552_000_000;
+	pub const InheritanceChallengeWindow: Moment = 1_209_600_000;
+	pub const VelocityPolicy: Option<pallet_lrp::VelocityPolicy<Moment>> = None;
+	pub const DefaultIdentityRequirement: pallet_lrp::IdentityRequirement<Balance> =
+		pallet_lrp::IdentityRequirement {
+			payer: false,
+			payee: false,
+			min_credibility: 0,
+			threshold: 0,
+		};
 }
 
 impl pallet_lrp::Config for Runtime {
 	type Event = Event;
 	type Currency = Currencies;
 	type CurrenciesManager = CurrenciesRegistry;
+	type IdentitiesManager = Identities;
+	type PaymentFee = ();
+	type ComplianceCheck = ();
+	type TreasuryAccount = TreasuryAccount;
 	type PendingPaymentWaitingTime = PendingPaymentWaitingTime;
 	type FulfilledPaymentWaitingTime = FulfilledPaymentWaitingTime;
+	type QueueBucketDuration = QueueBucketDuration;
+	type MaxPaymentsPerBucket = MaxPaymentsPerBucket;
+	type QueueBatchSize = QueueBatchSize;
+	type PaymentIndexBucketDuration = PaymentIndexBucketDuration;
+	type DefaultIdentityRequirement = DefaultIdentityRequirement;
+	type RiskMatureAccountAge = RiskMatureAccountAge;
+	type RiskHighAmount = RiskHighAmount;
+	type DailySpendingPeriod = DailySpendingPeriod;
+	type WeeklySpendingPeriod = WeeklySpendingPeriod;
+	type PayeeConfirmationThreshold = PayeeConfirmationThreshold;
+	type PayeeConfirmationCooldown = PayeeConfirmationCooldown;
+	type FulfillmentTime = FulfillmentTime;
+	type MaxDeliveryProofs = MaxDeliveryProofs;
+	type MaxPolicyCurrencies = MaxPolicyCurrencies;
+	type MaxReferralFeeShare = MaxReferralFeeShare;
+	type MaxPayoutRoutes = MaxPayoutRoutes;
+	type PauseOrigin = EnsureRoot<AccountId>;
+	type PalletId = LrpPalletId;
+	type StatementEraDuration = StatementEraDuration;
+	type InheritanceInactivityPeriod = InheritanceInactivityPeriod;
+	type InheritanceChallengeWindow = InheritanceChallengeWindow;
+	type VelocityPolicy = VelocityPolicy;
 }
 
 parameter_types! {
 	pub const PenaltyTokenLockTime: Moment = PENALTY_TOKEN_LOCK_TIME;
 	pub const UndelegateTime: Moment = UNDELEGATE_TIME;
+	pub const CaseExposureTime: Moment = CASE_EXPOSURE_TIME;
 	pub const MinimumSelfStake: Balance = MINIMUM_SELF_STAKE;
 	pub const ActivationStakeAmount: Balance = ACTIVATION_STAKE_AMOUNT;
 	pub const RequiredCredibility: Credibility = REQUIRED_CREDIBILITY;
+	pub RequiredVerifiedFields: Vec<Vec<u8>> = Vec::new();
+	pub const MaxActiveResolvers: u32 = 100;
+	pub const EraDuration: BlockNumber = ERA_DURATION;
+	pub const UnwindBatchSize: u32 = 100;
+	pub const ReleaseBatchSize: u32 = 100;
+	pub const ConflictDelegationThreshold: Balance = 100;
+	pub const MaxExpertiseCategories: u32 = 4;
+	pub const ResolversPalletId: PalletId = PalletId(*b"lb/rslvr");
+	pub const MaxPoolTargets: u32 = 2;
+	pub const MaxPoolCommission: Permill = Permill::from_percent(10);
 }
 
 impl pallet_resolvers::Config for Runtime {
 	type Event = Event;
 	type Currency = Currencies;
+	type FeeCurrency = GetNativeCurrencyId;
 	type IdentitiesManager = Identities;
 	type Randomness = RandomnessCollectiveFlip;
+	type SelectionStrategy = pallet_resolvers::selection::UniformSelection;
 	type PenaltyTokenLockTime = PenaltyTokenLockTime;
 	type MinimumSelfStake = MinimumSelfStake;
 	type ActivationStakeAmount = ActivationStakeAmount;
 	type UndelegateTime = UndelegateTime;
+	type CaseExposureTime = CaseExposureTime;
 	type RequiredCredibility = RequiredCredibility;
+	type RequiredVerifiedFields = RequiredVerifiedFields;
+	type MaxActiveResolvers = MaxActiveResolvers;
+	type EraDuration = EraDuration;
+	type UnwindBatchSize = UnwindBatchSize;
+	type ReleaseBatchSize = ReleaseBatchSize;
+	type ConflictDelegationThreshold = ConflictDelegationThreshold;
+	type MaxExpertiseCategories = MaxExpertiseCategories;
+	type PalletId = ResolversPalletId;
+	type MaxPoolTargets = MaxPoolTargets;
+	type MaxPoolCommission = MaxPoolCommission;
+	type WeightInfo = ();
 }
 
 parameter_types! {
 	pub const DisputeFinalizingTime: Moment = DISPUTE_FINALIZING_TIME;
 	pub const DisputeFee: Balance = DISPUTE_FEE;
+	pub const DisputeResolutionPalletId: PalletId = PalletId(*b"lb/dsptr");
+	pub const InsuranceFundShare: Permill = Permill::from_percent(10);
 	pub const CredibilityGain: Credibility = CREDIBILITY_GAIN;
 	pub const CredibilityLoss: Credibility = CREDIBILITY_LOSS;
+	pub static ResolverSelectionDelay: BlockNumber = 0;
+	pub const MaxResolverSelectionRetries: u32 = 3;
+	pub const AssignmentAcceptanceTime: Moment = ASSIGNMENT_ACCEPTANCE_TIME;
+	pub const AssignmentJudgingTime: Moment = ASSIGNMENT_JUDGING_TIME;
+	pub const ResolverBond: Balance = RESOLVER_BOND;
+	pub const ChallengeWindow: Moment = CHALLENGE_WINDOW;
+	pub const ChallengeBond: Balance = CHALLENGE_BOND;
+	pub static MaxDisputesOwned: u32 = 100;
+	pub static MaxEscalationRounds: u32 = 3;
+	pub const MaxSlaSamples: u32 = 5;
+	pub const MaxPrecedentsPerCategory: u32 = 2;
+	pub const SlashCompensationShare: Permill = Permill::from_percent(50);
+	pub const ReviewDisputeFee: Balance = REVIEW_DISPUTE_FEE;
 }
 
 impl dispute_resolution::Config for Runtime {
 	type Event = Event;
 	type Currency = Currencies;
+	type FeeCurrency = GetNativeCurrencyId;
 	type PaymentProtocol = LRP;
 	type ResolversNetwork = ResolversNetwork;
 	type IdentitiesManager = Identities;
+	type GovernanceOrigin = EnsureRoot<AccountId>;
+	type PauseOrigin = EnsureRoot<AccountId>;
+	type PalletId = DisputeResolutionPalletId;
+	type InsuranceFundShare = InsuranceFundShare;
 	type DisputeFinalizingTime = DisputeFinalizingTime;
 	type DisputeFee = DisputeFee;
 	type CredibilityGain = CredibilityGain;
 	type CredibilityLoss = CredibilityLoss;
+	type ResolverSelectionDelay = ResolverSelectionDelay;
+	type MaxResolverSelectionRetries = MaxResolverSelectionRetries;
+	type AssignmentAcceptanceTime = AssignmentAcceptanceTime;
+	type AssignmentJudgingTime = AssignmentJudgingTime;
+	type ResolverBond = ResolverBond;
+	type ChallengeWindow = ChallengeWindow;
+	type ChallengeBond = ChallengeBond;
+	type MaxDisputesOwned = MaxDisputesOwned;
+	type MaxEscalationRounds = MaxEscalationRounds;
+	type MaxSlaSamples = MaxSlaSamples;
+	type MaxPrecedentsPerCategory = MaxPrecedentsPerCategory;
+	type SlashCompensationShare = SlashCompensationShare;
+	type ReviewDisputeFee = ReviewDisputeFee;
+	type WeightInfo = ();
 }
 
 parameter_types! {
 	pub const EvaluatorBonding: Balance = EVALUATOR_BONDING;
+	pub const VerifyRequestTimeout: BlockNumber = 10;
+	pub const VerifyRequestPenalty: Balance = 5;
+	pub const MaxFieldPrices: u32 = 3;
+	pub const EvaluatorRateBand: Balance = 10;
+	pub const MaxLanguages: u32 = 3;
+	pub const MaxEncryptionKeys: u32 = 3;
+	pub const MaxAttestations: u32 = 3;
+	pub const HandleDeposit: Balance = 10;
+	pub const HandlePeriod: BlockNumber = 100;
+	pub const MaxHandleLength: u32 = 16;
 	pub const InitialCredibility: Credibility = INITIAL_CREDIBILITY;
 	pub const MaxCredibility: Credibility = MAX_CREDIBILITY;
 }
@@ -219,8 +367,21 @@ impl pallet_identities::Config for Runtime {
 	type Event = Event;
 	type Currency = Currencies;
 	type EvaluatorBonding = EvaluatorBonding;
+	type GovernanceOrigin = EnsureRoot<AccountId>;
+	type Resolvers = ResolversNetwork;
+	type VerifyRequestTimeout = VerifyRequestTimeout;
+	type VerifyRequestPenalty = VerifyRequestPenalty;
+	type MaxFieldPrices = MaxFieldPrices;
+	type EvaluatorRateBand = EvaluatorRateBand;
+	type MaxLanguages = MaxLanguages;
+	type MaxEncryptionKeys = MaxEncryptionKeys;
+	type MaxAttestations = MaxAttestations;
+	type HandleDeposit = HandleDeposit;
+	type HandlePeriod = HandlePeriod;
+	type MaxHandleLength = MaxHandleLength;
 	type InitialCredibility = InitialCredibility;
 	type MaxCredibility = MaxCredibility;
+	type WeightInfo = ();
 }
 
 type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Runtime>;
@@ -269,6 +430,9 @@ impl ExtBuilder {
 				(RESOLVER_1, 2_000),
 				(RESOLVER_2, 2_000),
 				(RESOLVER_3, 2_000),
+				(RESOLVER_4, 2_000),
+				(DisputeResolution::insurance_fund_account(), INSURANCE_FUND),
+				(LRP::escrow_account(), ExistentialDeposit::get()),
 			],
 		}
 		.assimilate_storage(&mut t)
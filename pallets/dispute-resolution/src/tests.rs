@@ -1,15 +1,27 @@
 #![cfg(test)]
 
 use super::*;
-use frame_support::{assert_noop, assert_ok, traits::Hooks};
+use codec::Encode;
+use frame_support::{
+	assert_noop, assert_ok,
+	traits::{GetStorageVersion, Hooks, OnRuntimeUpgrade, StorageVersion},
+};
+use frame_system as system;
 use mock::{
-	Currencies, CurrencyId, DisputeResolution, ExtBuilder, Identities, Origin,
-	RandomnessCollectiveFlip, ResolversNetwork, Runtime, System, Timestamp, ALICE, BOB,
-	DISPUTE_FINALIZING_TIME, LRP, RESOLVER_1, RESOLVER_2, RESOLVER_3,
+	AccountId, Currencies, CurrencyId, DisputeResolution, ExistentialDeposit, ExtBuilder,
+	Identities, InsuranceFundShare, MaxDisputesOwned, MaxEscalationRounds,
+	MaxPrecedentsPerCategory, Origin, RandomnessCollectiveFlip, ResolverSelectionDelay,
+	ResolversNetwork, Runtime, System, Timestamp, ALICE, ASSIGNMENT_ACCEPTANCE_TIME,
+	ASSIGNMENT_JUDGING_TIME, BOB, CHALLENGE_WINDOW, DISPUTE_FEE, DISPUTE_FINALIZING_TIME,
+	INSURANCE_FUND, LRP, RESOLVER_1, RESOLVER_2, RESOLVER_3, RESOLVER_4, RESOLVER_BOND,
+	REVIEW_DISPUTE_FEE,
 };
 use orml_traits::{MultiCurrency, MultiReservableCurrency};
-use pallet_identities::{IdentitiesManager, IdentityType};
-use sp_runtime::traits::Header;
+use pallet_identities::{DisputeRecord, IdentitiesManager, IdentityType};
+use pallet_lrp::{PaymentDescription, PaymentStatus};
+use primitives::{DisputeCostEstimate, PaymentCategory, DISPUTE_FEE_RESERVE};
+use sp_runtime::traits::{Hash, Header};
+use sp_runtime::{DispatchError, Permill};
 
 pub const INIT_TIMESTAMP: u64 = 1_000;
 pub const BLOCK_TIME: u64 = 6_000;
@@ -21,6 +33,7 @@ fn run_to_block_number(block_number: u64) {
 		System::reset_events();
 		System::initialize(&i, &parent_hash, &Default::default());
 		RandomnessCollectiveFlip::on_initialize(i);
+		DisputeResolution::on_initialize(i);
 
 		let header = System::finalize();
 		parent_hash = header.hash();
@@ -37,13 +50,22 @@ fn create_dispute_works() {
 	ExtBuilder::default().build().execute_with(|| {
 		System::set_block_number(1);
 
+		assert_ok!(Identities::create_identity(
+			Origin::signed(BOB),
+			"Bob".into(),
+			IdentityType::Organization,
+			[].into(),
+		));
+		assert_ok!(Identities::register_handle(Origin::signed(BOB), "bob".into()));
+
 		assert_ok!(LRP::create_payment(
 			Origin::signed(ALICE),
 			BOB,
 			100,
 			CurrencyId::Native,
+			PaymentDescription::Plain("".into()),
 			"".into(),
-			"".into(),
+			None,
 		));
 
 		let payment_hash = LRP::payments_owned(&ALICE)[0];
@@ -61,8 +83,18 @@ fn create_dispute_works() {
 			"".into(),
 		));
 
-		assert_eq!(Currencies::reserved_balance(CurrencyId::Native, &ALICE), 200);
+		assert_eq!(Currencies::reserved_balance(CurrencyId::Native, &ALICE), 100);
 		assert_eq!(Currencies::free_balance(CurrencyId::Native, &ALICE), 800);
+		assert!(System::events().iter().any(|record| record.event ==
+			mock::Event::DisputeResolution(crate::Event::DisputeCreated {
+				payer: ALICE,
+				payee: BOB,
+				payment_hash,
+				issuer: ALICE,
+				fee: DISPUTE_FEE,
+				payer_handle: None,
+				payee_handle: Some("bob".into()),
+			})));
 
 		let dispute = DisputeResolution::disputes(&payment_hash).unwrap();
 
@@ -77,6 +109,131 @@ fn create_dispute_works() {
 	});
 }
 
+#[test]
+fn payee_can_create_dispute() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		Timestamp::set_timestamp(INIT_TIMESTAMP);
+
+		assert_ok!(LRP::create_payment(
+			Origin::signed(ALICE),
+			BOB,
+			100,
+			CurrencyId::Native,
+			PaymentDescription::Plain("".into()),
+			"".into(),
+			None,
+		));
+		let payment_hash = LRP::payments_owned(&ALICE)[0];
+		assert_ok!(LRP::accept_payment(Origin::signed(BOB), payment_hash));
+
+		// Only the payment parties can issue the dispute.
+		assert_noop!(
+			DisputeResolution::create_dispute(Origin::signed(RESOLVER_1), payment_hash, "".into()),
+			Error::<Runtime>::NotPaymentParty,
+		);
+
+		assert_ok!(DisputeResolution::create_dispute(Origin::signed(BOB), payment_hash, "".into()));
+
+		// The fee is escrowed from the payee and the initial outcome is in favor of the payee.
+		let dispute = DisputeResolution::disputes(&payment_hash).unwrap();
+		assert_eq!(dispute.outcome, Judgment::ReleaseFundToPayee);
+		assert_eq!(dispute.payer_fee, 0);
+		assert_eq!(dispute.payee_fee, DISPUTE_FEE);
+		assert_eq!(Currencies::reserved_balance(CurrencyId::Native, &BOB), DISPUTE_FEE);
+		assert_eq!(Identities::dispute_records(&ALICE).opened, 1);
+		assert!(System::events().iter().any(|record| record.event ==
+			mock::Event::DisputeResolution(crate::Event::DisputeCreated {
+				payer: ALICE,
+				payee: BOB,
+				payment_hash,
+				issuer: BOB,
+				fee: DISPUTE_FEE,
+				payer_handle: None,
+				payee_handle: None,
+			})));
+
+		// Only the payer is aggrieved by the initial outcome.
+		assert_noop!(
+			DisputeResolution::fight_dispute(Origin::signed(BOB), payment_hash, "".into()),
+			Error::<Runtime>::NotAggrievedParty,
+		);
+
+		// The payment is released to the payee if the payer does not fight the dispute.
+		Timestamp::set_timestamp(INIT_TIMESTAMP + DISPUTE_FINALIZING_TIME);
+		assert_ok!(DisputeResolution::finalize_dispute(Origin::signed(ALICE), payment_hash));
+
+		assert_eq!(Currencies::reserved_balance(CurrencyId::Native, &BOB), 0);
+		assert_eq!(Currencies::free_balance(CurrencyId::Native, &BOB), 1100);
+		assert_eq!(
+			Identities::dispute_records(&ALICE),
+			DisputeRecord { opened: 1, won: 0, lost: 1 }
+		);
+	});
+}
+
+#[test]
+fn withdraw_dispute_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+
+		assert_ok!(LRP::create_payment(
+			Origin::signed(ALICE),
+			BOB,
+			100,
+			CurrencyId::Native,
+			PaymentDescription::Plain("".into()),
+			"".into(),
+			None,
+		));
+		let payment_hash = LRP::payments_owned(&ALICE)[0];
+		assert_ok!(LRP::accept_payment(Origin::signed(BOB), payment_hash));
+		assert_ok!(DisputeResolution::create_dispute(
+			Origin::signed(ALICE),
+			payment_hash,
+			"".into(),
+		));
+		assert_ok!(DisputeResolution::escalate_dispute(Origin::signed(BOB), payment_hash));
+
+		// Only the issuer can withdraw the dispute.
+		assert_noop!(
+			DisputeResolution::withdraw_dispute(Origin::signed(BOB), payment_hash),
+			Error::<Runtime>::NotDisputeIssuer,
+		);
+
+		assert_ok!(DisputeResolution::withdraw_dispute(Origin::signed(ALICE), payment_hash));
+
+		// The escrowed fees of both parties are refunded.
+		assert_eq!(Currencies::reserved_balance(CurrencyId::Native, &ALICE), 0);
+		assert_eq!(Currencies::free_balance(CurrencyId::Native, &ALICE), 900);
+		assert_eq!(Currencies::reserved_balance(CurrencyId::Native, &BOB), 0);
+		assert!(System::events().iter().any(|record| record.event ==
+			mock::Event::DisputeResolution(crate::Event::DisputeWithdrawn {
+				payment_hash,
+				issuer: ALICE,
+				fee: DISPUTE_FEE,
+			})));
+
+		// The dispute is forgotten.
+		assert!(DisputeResolution::disputes(&payment_hash).is_none());
+		assert_eq!(DisputeResolution::finalizing_disputes(), [].to_vec());
+		assert!(DisputeResolution::disputes_owned(&ALICE).is_empty());
+		assert!(DisputeResolution::disputes_owned(&BOB).is_empty());
+		assert_eq!(Identities::dispute_records(&BOB), DisputeRecord::default());
+		assert_noop!(
+			DisputeResolution::withdraw_dispute(Origin::signed(ALICE), payment_hash),
+			Error::<Runtime>::DisputeNotFound,
+		);
+
+		// The payment can be disputed again.
+		assert_ok!(DisputeResolution::create_dispute(
+			Origin::signed(ALICE),
+			payment_hash,
+			"".into(),
+		));
+	});
+}
+
 #[test]
 fn fight_dispute_works() {
 	ExtBuilder::default().build().execute_with(|| {
@@ -93,6 +250,7 @@ fn fight_dispute_works() {
 			Origin::signed(RESOLVER_1),
 			"".into(),
 			1100,
+			[0; 32],
 		));
 
 		assert_ok!(LRP::create_payment(
@@ -100,8 +258,9 @@ fn fight_dispute_works() {
 			BOB,
 			100,
 			CurrencyId::Native,
+			PaymentDescription::Plain("".into()),
 			"".into(),
-			"".into(),
+			None,
 		));
 		let payment_hash = LRP::payments_owned(&ALICE)[0];
 		assert_ok!(LRP::accept_payment(Origin::signed(BOB), payment_hash));
@@ -120,17 +279,29 @@ fn fight_dispute_works() {
 		assert_eq!(dispute.judgments, [].to_vec());
 		assert_eq!(dispute.outcome, Judgment::ReleaseFundToPayer);
 
+		let events = System::events();
+		assert!(events.iter().any(|record| record.event ==
+			mock::Event::DisputeResolution(crate::Event::EvidenceSubmitted {
+				payment_hash,
+				provider: BOB,
+				content_hash: dispute.arguments[1].content_hash,
+			})));
+		assert!(events.iter().any(|record| record.event ==
+			mock::Event::DisputeResolution(crate::Event::DisputePanelDrawn {
+				payment_hash,
+				resolvers: [RESOLVER_1].to_vec(),
+			})));
+
 		let finalizing_disputes = DisputeResolution::finalizing_disputes();
 		assert_eq!(finalizing_disputes.len(), 0);
 	});
 }
 
 #[test]
-fn propose_judgment_works() {
+fn submit_private_evidence_works() {
 	ExtBuilder::default().build().execute_with(|| {
 		System::set_block_number(1);
 
-		// Initial resolvers network.
 		assert_ok!(Identities::create_identity(
 			Origin::signed(RESOLVER_1),
 			"Resolver 1".into(),
@@ -141,16 +312,17 @@ fn propose_judgment_works() {
 			Origin::signed(RESOLVER_1),
 			"".into(),
 			1100,
+			[1; 32],
 		));
 
-		// Create a dispute
 		assert_ok!(LRP::create_payment(
 			Origin::signed(ALICE),
 			BOB,
 			100,
 			CurrencyId::Native,
+			PaymentDescription::Plain("".into()),
 			"".into(),
-			"".into(),
+			None,
 		));
 		let payment_hash = LRP::payments_owned(&ALICE)[0];
 		assert_ok!(LRP::accept_payment(Origin::signed(BOB), payment_hash));
@@ -159,28 +331,76 @@ fn propose_judgment_works() {
 			payment_hash,
 			"".into(),
 		));
-		assert_ok!(DisputeResolution::fight_dispute(Origin::signed(BOB), payment_hash, "".into(),));
-		assert_ok!(DisputeResolution::propose_outcome(
-			Origin::signed(RESOLVER_1),
-			payment_hash,
-			Judgment::ReleaseFundToPayee
-		));
 
-		let dispute = DisputeResolution::disputes(&payment_hash).unwrap();
+		// There is no panel to read the evidence before the dispute is fought.
+		let envelopes: Vec<(AccountId, Vec<u8>)> = [(RESOLVER_1, "receipt".into())].to_vec();
+		assert_noop!(
+			DisputeResolution::submit_private_evidence(
+				Origin::signed(ALICE),
+				payment_hash,
+				envelopes.clone(),
+			),
+			Error::<Runtime>::DisputeNotEvaluating,
+		);
 
-		assert_eq!(dispute.status, DisputeStatus::Finalizing);
-		assert_eq!(dispute.resolvers, [RESOLVER_1].to_vec());
-		assert_eq!(dispute.judgments, [(RESOLVER_1, Judgment::ReleaseFundToPayee)].to_vec());
-		assert_eq!(dispute.outcome, Judgment::ReleaseFundToPayee);
+		assert_ok!(DisputeResolution::fight_dispute(Origin::signed(BOB), payment_hash, "".into()));
+
+		assert_noop!(
+			DisputeResolution::submit_private_evidence(
+				Origin::signed(RESOLVER_2),
+				payment_hash,
+				envelopes.clone(),
+			),
+			Error::<Runtime>::NotPaymentParty,
+		);
+		// The envelopes can only be encrypted to the panel resolvers, once each.
+		assert_noop!(
+			DisputeResolution::submit_private_evidence(
+				Origin::signed(ALICE),
+				payment_hash,
+				[(RESOLVER_2, "receipt".into())].to_vec(),
+			),
+			Error::<Runtime>::InvalidEnvelopeRecipient,
+		);
+		assert_noop!(
+			DisputeResolution::submit_private_evidence(
+				Origin::signed(ALICE),
+				payment_hash,
+				[(RESOLVER_1, "receipt".into()), (RESOLVER_1, "receipt".into())].to_vec(),
+			),
+			Error::<Runtime>::InvalidEnvelopeRecipient,
+		);
+
+		assert_ok!(DisputeResolution::submit_private_evidence(
+			Origin::signed(ALICE),
+			payment_hash,
+			envelopes,
+		));
+		let content_hash = <Runtime as system::Config>::Hashing::hash_of(&"receipt".as_bytes());
+		assert_eq!(
+			DisputeResolution::evidence_envelopes(&payment_hash),
+			[EvidenceEnvelope::<Runtime> {
+				provider: ALICE,
+				resolver: RESOLVER_1,
+				encryption_key: [1; 32],
+				content_hash,
+			}]
+			.to_vec()
+		);
+		assert!(System::events().iter().any(|record| record.event ==
+			mock::Event::DisputeResolution(crate::Event::PrivateEvidenceSubmitted {
+				payment_hash,
+				provider: ALICE,
+				envelopes: [(RESOLVER_1, content_hash)].to_vec(),
+			})));
 	});
 }
 
 #[test]
-fn escalate_dispute_works() {
+fn marketplace_can_take_part_in_dispute() {
 	ExtBuilder::default().build().execute_with(|| {
 		System::set_block_number(1);
 
-		// Initial resolvers network.
 		assert_ok!(Identities::create_identity(
 			Origin::signed(RESOLVER_1),
 			"Resolver 1".into(),
@@ -191,15 +411,18 @@ fn escalate_dispute_works() {
 			Origin::signed(RESOLVER_1),
 			"".into(),
 			1100,
+			[1; 32],
 		));
 
-		assert_ok!(LRP::create_payment(
+		assert_ok!(LRP::create_marketplace_payment(
 			Origin::signed(ALICE),
 			BOB,
 			100,
 			CurrencyId::Native,
+			PaymentDescription::Plain("".into()),
 			"".into(),
-			"".into(),
+			RESOLVER_4,
+			None,
 		));
 		let payment_hash = LRP::payments_owned(&ALICE)[0];
 		assert_ok!(LRP::accept_payment(Origin::signed(BOB), payment_hash));
@@ -208,27 +431,46 @@ fn escalate_dispute_works() {
 			payment_hash,
 			"".into(),
 		));
-		assert_ok!(DisputeResolution::fight_dispute(Origin::signed(BOB), payment_hash, "".into(),));
-		assert_ok!(DisputeResolution::propose_outcome(
-			Origin::signed(RESOLVER_1),
-			payment_hash,
-			Judgment::ReleaseFundToPayee
-		));
-		assert_ok!(DisputeResolution::escalate_dispute(Origin::signed(ALICE), payment_hash));
 
-		let dispute = DisputeResolution::disputes(&payment_hash).unwrap();
+		// The marketplace of the payment is registered with its fee, none in the mock.
+		assert_eq!(
+			DisputeResolution::dispute_stakeholders(&payment_hash),
+			[(RESOLVER_4, 0)].to_vec()
+		);
+		assert!(System::events().iter().any(|record| record.event ==
+			mock::Event::DisputeResolution(crate::Event::DisputeStakeholderAdded {
+				payment_hash,
+				stakeholder: RESOLVER_4,
+				share: 0,
+			})));
 
-		assert_eq!(dispute.status, DisputeStatus::Finalizing);
-		assert_eq!(dispute.outcome, Judgment::ReleaseFundToPayer);
+		assert_ok!(DisputeResolution::fight_dispute(Origin::signed(BOB), payment_hash, "".into()));
+
+		// The marketplace can submit private evidence, other accounts cannot.
+		let envelopes: Vec<(AccountId, Vec<u8>)> = [(RESOLVER_1, "receipt".into())].to_vec();
+		assert_noop!(
+			DisputeResolution::submit_private_evidence(
+				Origin::signed(RESOLVER_3),
+				payment_hash,
+				envelopes.clone(),
+			),
+			Error::<Runtime>::NotPaymentParty,
+		);
+		assert_ok!(DisputeResolution::submit_private_evidence(
+			Origin::signed(RESOLVER_4),
+			payment_hash,
+			envelopes,
+		));
+		assert_eq!(DisputeResolution::evidence_envelopes(&payment_hash)[0].provider, RESOLVER_4);
 	});
 }
 
 #[test]
-fn fight_an_escalated_dispute_works() {
+fn resolvers_are_drawn_after_selection_delay() {
 	ExtBuilder::default().build().execute_with(|| {
+		ResolverSelectionDelay::set(&5);
 		System::set_block_number(1);
 
-		// Initial resolvers network.
 		assert_ok!(Identities::create_identity(
 			Origin::signed(RESOLVER_1),
 			"Resolver 1".into(),
@@ -239,38 +481,17 @@ fn fight_an_escalated_dispute_works() {
 			Origin::signed(RESOLVER_1),
 			"".into(),
 			1100,
-		));
-		assert_ok!(Identities::create_identity(
-			Origin::signed(RESOLVER_2),
-			"Resolver 2".into(),
-			IdentityType::Individual,
-			[].into(),
-		));
-		assert_ok!(ResolversNetwork::join_resolvers_network(
-			Origin::signed(RESOLVER_2),
-			"".into(),
-			1100,
-		));
-		assert_ok!(Identities::create_identity(
-			Origin::signed(RESOLVER_3),
-			"Resolver 3".into(),
-			IdentityType::Individual,
-			[].into(),
-		));
-		assert_ok!(ResolversNetwork::join_resolvers_network(
-			Origin::signed(RESOLVER_3),
-			"".into(),
-			1100,
+			[0; 32],
 		));
 
-		// Simulate escalate dispute
 		assert_ok!(LRP::create_payment(
 			Origin::signed(ALICE),
 			BOB,
 			100,
 			CurrencyId::Native,
+			PaymentDescription::Plain("".into()),
 			"".into(),
-			"".into(),
+			None,
 		));
 		let payment_hash = LRP::payments_owned(&ALICE)[0];
 		assert_ok!(LRP::accept_payment(Origin::signed(BOB), payment_hash));
@@ -279,46 +500,26 @@ fn fight_an_escalated_dispute_works() {
 			payment_hash,
 			"".into(),
 		));
-		assert_ok!(DisputeResolution::fight_dispute(Origin::signed(BOB), payment_hash, "".into(),));
-		assert_ok!(DisputeResolution::propose_outcome(
-			Origin::signed(RESOLVER_1),
-			payment_hash,
-			Judgment::ReleaseFundToPayee
-		));
-		assert_ok!(DisputeResolution::escalate_dispute(Origin::signed(ALICE), payment_hash));
 		assert_ok!(DisputeResolution::fight_dispute(Origin::signed(BOB), payment_hash, "".into()));
 
+		// The resolvers are not drawn at the time the dispute is fought.
 		let dispute = DisputeResolution::disputes(&payment_hash).unwrap();
-
 		assert_eq!(dispute.status, DisputeStatus::Evaluating);
-		assert_eq!(dispute.resolvers.len(), 3);
-		assert_eq!(dispute.judgments, [(RESOLVER_1, Judgment::ReleaseFundToPayee)].to_vec());
-		assert_eq!(dispute.outcome, Judgment::ReleaseFundToPayer);
-
-		assert_ok!(DisputeResolution::propose_outcome(
-			Origin::signed(RESOLVER_2),
-			payment_hash,
-			Judgment::ReleaseFundToPayee
-		));
+		assert_eq!(dispute.resolvers, [].to_vec());
+		assert_eq!(DisputeResolution::pending_resolver_selections(6), [payment_hash].to_vec());
 
-		assert_ok!(DisputeResolution::propose_outcome(
-			Origin::signed(RESOLVER_3),
-			payment_hash,
-			Judgment::ReleaseFundToPayee
-		));
+		run_to_block_number(5);
 
 		let dispute = DisputeResolution::disputes(&payment_hash).unwrap();
-
-		assert_eq!(dispute.status, DisputeStatus::Finalizing);
-		assert_eq!(dispute.resolvers.len(), 3);
-		assert_eq!(dispute.judgments.len(), 3);
-		assert_eq!(dispute.outcome, Judgment::ReleaseFundToPayee);
+		assert_eq!(dispute.resolvers, [RESOLVER_1].to_vec());
+		assert_eq!(DisputeResolution::pending_resolver_selections(6).len(), 0);
 	});
 }
 
 #[test]
-fn finalize_judgment_works() {
+fn failed_resolver_selection_is_cancelled_after_retries() {
 	ExtBuilder::default().build().execute_with(|| {
+		ResolverSelectionDelay::set(&5);
 		System::set_block_number(1);
 
 		assert_ok!(LRP::create_payment(
@@ -326,8 +527,9 @@ fn finalize_judgment_works() {
 			BOB,
 			100,
 			CurrencyId::Native,
+			PaymentDescription::Plain("".into()),
 			"".into(),
-			"".into(),
+			None,
 		));
 		let payment_hash = LRP::payments_owned(&ALICE)[0];
 		assert_ok!(LRP::accept_payment(Origin::signed(BOB), payment_hash));
@@ -336,125 +538,253 @@ fn finalize_judgment_works() {
 			payment_hash,
 			"".into(),
 		));
+		assert_ok!(DisputeResolution::fight_dispute(Origin::signed(BOB), payment_hash, "".into()));
+		assert_eq!(Currencies::reserved_balance(CurrencyId::Native, &BOB), DISPUTE_FEE);
+		assert_eq!(DisputeResolution::selection_requests(&payment_hash).unwrap().fee, DISPUTE_FEE);
 
-		run_to_block_number((DISPUTE_FINALIZING_TIME / BLOCK_TIME + 1).into());
-
-		let finalizing_disputes = DisputeResolution::finalizing_disputes();
-		assert_eq!(finalizing_disputes.len(), 0);
+		// There is no resolver to draw, the draw is retried in the next blocks.
+		run_to_block_number(6);
 
 		let dispute = DisputeResolution::disputes(&payment_hash).unwrap();
-		assert_eq!(dispute.status, DisputeStatus::Resolved);
+		assert_eq!(dispute.status, DisputeStatus::Evaluating);
+		assert_eq!(DisputeResolution::selection_requests(&payment_hash).unwrap().retries, 2);
+		assert_eq!(DisputeResolution::pending_resolver_selections(8), [payment_hash].to_vec());
 
-		assert_eq!(Currencies::reserved_balance(CurrencyId::Native, &ALICE), 0);
-		assert_eq!(Currencies::free_balance(CurrencyId::Native, &ALICE), 1000);
+		run_to_block_number(1);
+
+		// The dispute is back to finalizing with its outcome and the fee of the fighter is
+		// refunded.
+		let dispute = DisputeResolution::disputes(&payment_hash).unwrap();
+		assert_eq!(dispute.status, DisputeStatus::Finalizing);
+		assert_eq!(dispute.outcome, Judgment::ReleaseFundToPayer);
+		assert_eq!(dispute.payee_fee, 0);
+		assert_eq!(DisputeResolution::finalizing_disputes(), [payment_hash].to_vec());
+		assert_eq!(DisputeResolution::selection_requests(&payment_hash), None);
+		assert_eq!(DisputeResolution::pending_resolver_selections(9).len(), 0);
+		assert_eq!(Currencies::reserved_balance(CurrencyId::Native, &BOB), 0);
 	});
 }
 
 #[test]
-fn only_selected_resolver_can_propose_judgment() {
+fn resolvers_sharing_a_language_are_preferred() {
 	ExtBuilder::default().build().execute_with(|| {
 		System::set_block_number(1);
 
-		// Create 3 test resolvers
-		assert_ok!(Identities::create_identity(
-			Origin::signed(RESOLVER_1),
-			"Resolver 1".into(),
-			IdentityType::Individual,
-			[].into(),
-		));
-		assert_ok!(ResolversNetwork::join_resolvers_network(
-			Origin::signed(RESOLVER_1),
-			"".into(),
-			1100,
-		));
-		assert_ok!(Identities::create_identity(
-			Origin::signed(RESOLVER_2),
-			"Resolver 2".into(),
-			IdentityType::Individual,
-			[].into(),
-		));
-		assert_ok!(ResolversNetwork::join_resolvers_network(
-			Origin::signed(RESOLVER_2),
-			"".into(),
-			1100,
-		));
-		assert_ok!(Identities::create_identity(
-			Origin::signed(RESOLVER_3),
-			"Resolver 3".into(),
-			IdentityType::Individual,
-			[].into(),
-		));
-		assert_ok!(ResolversNetwork::join_resolvers_network(
-			Origin::signed(RESOLVER_3),
-			"".into(),
-			1100,
-		));
+		for (resolver, languages) in [
+			(RESOLVER_1, [*b"en"].to_vec()),
+			(RESOLVER_2, [*b"en", *b"vi"].to_vec()),
+			(RESOLVER_3, [*b"ja"].to_vec()),
+		] {
+			assert_ok!(Identities::create_identity(
+				Origin::signed(resolver),
+				"Resolver".into(),
+				IdentityType::Individual,
+				[].into(),
+			));
+			assert_ok!(Identities::set_languages(Origin::signed(resolver), languages));
+			assert_ok!(ResolversNetwork::join_resolvers_network(
+				Origin::signed(resolver),
+				"".into(),
+				1100,
+				[0; 32],
+			));
+		}
+		for (party, languages) in [(ALICE, [*b"vi"].to_vec()), (BOB, [*b"en", *b"vi"].to_vec())] {
+			assert_ok!(Identities::create_identity(
+				Origin::signed(party),
+				"Party".into(),
+				IdentityType::Individual,
+				[].into(),
+			));
+			assert_ok!(Identities::set_languages(Origin::signed(party), languages));
+		}
 
-		// Simulate accepted payment
+		// The resolver sharing a language with both parties is drawn.
 		assert_ok!(LRP::create_payment(
 			Origin::signed(ALICE),
 			BOB,
-			200,
+			100,
 			CurrencyId::Native,
+			PaymentDescription::Plain("".into()),
 			"".into(),
-			"".into(),
+			None,
 		));
 		let payment_hash = LRP::payments_owned(&ALICE)[0];
 		assert_ok!(LRP::accept_payment(Origin::signed(BOB), payment_hash));
-
-		// Initial block hashes for the randomness
-		run_to_block_number(32);
-
 		assert_ok!(DisputeResolution::create_dispute(
 			Origin::signed(ALICE),
 			payment_hash,
 			"".into(),
 		));
 		assert_ok!(DisputeResolution::fight_dispute(Origin::signed(BOB), payment_hash, "".into()));
+		let dispute = DisputeResolution::disputes(&payment_hash).unwrap();
+		assert_eq!(dispute.resolvers, [RESOLVER_2].to_vec());
 
-		let resolvers = vec![RESOLVER_1, RESOLVER_2, RESOLVER_3];
+		// The language hint of the payment takes precedence over the languages of the parties.
+		assert_ok!(LRP::create_payment(
+			Origin::signed(ALICE),
+			BOB,
+			100,
+			CurrencyId::Native,
+			PaymentDescription::Plain("".into()),
+			"".into(),
+			None,
+		));
+		let payment_hash = LRP::payments_owned(&ALICE)[1];
+		assert_ok!(LRP::set_payment_locale(Origin::signed(ALICE), payment_hash, Some(*b"ja")));
+		assert_ok!(LRP::accept_payment(Origin::signed(BOB), payment_hash));
+		assert_ok!(DisputeResolution::create_dispute(
+			Origin::signed(ALICE),
+			payment_hash,
+			"".into(),
+		));
+		assert_ok!(DisputeResolution::fight_dispute(Origin::signed(BOB), payment_hash, "".into()));
+		let dispute = DisputeResolution::disputes(&payment_hash).unwrap();
+		assert_eq!(dispute.resolvers, [RESOLVER_3].to_vec());
+	});
+}
+
+#[test]
+fn propose_judgment_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+
+		// Initial resolvers network.
+		assert_ok!(Identities::create_identity(
+			Origin::signed(RESOLVER_1),
+			"Resolver 1".into(),
+			IdentityType::Individual,
+			[].into(),
+		));
+		assert_ok!(ResolversNetwork::join_resolvers_network(
+			Origin::signed(RESOLVER_1),
+			"".into(),
+			1100,
+			[0; 32],
+		));
+
+		// Create a dispute
+		assert_ok!(LRP::create_payment(
+			Origin::signed(ALICE),
+			BOB,
+			100,
+			CurrencyId::Native,
+			PaymentDescription::Plain("".into()),
+			"".into(),
+			None,
+		));
+		let payment_hash = LRP::payments_owned(&ALICE)[0];
+		assert_ok!(LRP::accept_payment(Origin::signed(BOB), payment_hash));
+		assert_ok!(DisputeResolution::create_dispute(
+			Origin::signed(ALICE),
+			payment_hash,
+			"".into(),
+		));
+		assert_ok!(DisputeResolution::fight_dispute(Origin::signed(BOB), payment_hash, "".into(),));
+		assert_ok!(DisputeResolution::accept_assignment(Origin::signed(RESOLVER_1), payment_hash));
+		assert_ok!(DisputeResolution::propose_outcome(
+			Origin::signed(RESOLVER_1),
+			payment_hash,
+			Judgment::ReleaseFundToPayee,
+			None
+		));
 
 		let dispute = DisputeResolution::disputes(&payment_hash).unwrap();
 
-		// Reject account that is not the selected resolver.
-		assert_noop!(
-			DisputeResolution::propose_outcome(
-				Origin::signed(ALICE),
+		assert_eq!(dispute.status, DisputeStatus::Finalizing);
+		assert_eq!(dispute.resolvers, [RESOLVER_1].to_vec());
+		assert_eq!(dispute.judgments, [(RESOLVER_1, Judgment::ReleaseFundToPayee)].to_vec());
+		assert_eq!(dispute.outcome, Judgment::ReleaseFundToPayee);
+		assert!(System::events().iter().any(|record| record.event ==
+			mock::Event::DisputeResolution(crate::Event::JudgmentProposed {
 				payment_hash,
-				Judgment::ReleaseFundToPayer
-			),
-			Error::<Runtime>::AccessDenied,
-		);
+				resolver: RESOLVER_1,
+				rationale: None,
+			})));
+	});
+}
 
-		for resolver in resolvers.iter() {
-			if dispute.resolvers[0] != *resolver {
-				assert_noop!(
-					DisputeResolution::propose_outcome(
-						Origin::signed(*resolver),
-						payment_hash,
-						Judgment::ReleaseFundToPayer
-					),
-					Error::<Runtime>::AccessDenied,
-				);
-			}
-		}
+#[test]
+fn escalate_dispute_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		MaxEscalationRounds::set(1);
 
-		// Only accept the selected resolver to propose judgment
+		// Initial resolvers network.
+		assert_ok!(Identities::create_identity(
+			Origin::signed(RESOLVER_1),
+			"Resolver 1".into(),
+			IdentityType::Individual,
+			[].into(),
+		));
+		assert_ok!(ResolversNetwork::join_resolvers_network(
+			Origin::signed(RESOLVER_1),
+			"".into(),
+			1100,
+			[0; 32],
+		));
+
+		assert_ok!(LRP::create_payment(
+			Origin::signed(ALICE),
+			BOB,
+			100,
+			CurrencyId::Native,
+			PaymentDescription::Plain("".into()),
+			"".into(),
+			None,
+		));
+		let payment_hash = LRP::payments_owned(&ALICE)[0];
+		assert_ok!(LRP::accept_payment(Origin::signed(BOB), payment_hash));
+		assert_ok!(DisputeResolution::create_dispute(
+			Origin::signed(ALICE),
+			payment_hash,
+			"".into(),
+		));
+		assert_ok!(DisputeResolution::fight_dispute(Origin::signed(BOB), payment_hash, "".into(),));
+		assert_ok!(DisputeResolution::accept_assignment(Origin::signed(RESOLVER_1), payment_hash));
 		assert_ok!(DisputeResolution::propose_outcome(
-			Origin::signed(dispute.resolvers[0]),
+			Origin::signed(RESOLVER_1),
 			payment_hash,
 			Judgment::ReleaseFundToPayee,
+			None
 		));
+		assert_ok!(DisputeResolution::escalate_dispute(Origin::signed(ALICE), payment_hash));
+
+		let dispute = DisputeResolution::disputes(&payment_hash).unwrap();
+
+		assert_eq!(dispute.status, DisputeStatus::Finalizing);
+		assert_eq!(dispute.outcome, Judgment::ReleaseFundToPayer);
+		assert_eq!(dispute.escalations, 1);
+		assert!(System::events().iter().any(|record| record.event ==
+			mock::Event::DisputeResolution(crate::Event::DisputeEscalated {
+				payer: ALICE,
+				payee: BOB,
+				payment_hash,
+				escalator: ALICE,
+				fee: DISPUTE_FEE * 2,
+				escalations: 1,
+			})));
+
+		// The dispute cannot be escalated beyond the maximum escalation rounds.
+		assert_noop!(
+			DisputeResolution::escalate_dispute(Origin::signed(BOB), payment_hash),
+			Error::<Runtime>::MaxEscalationRoundsReached,
+		);
+
+		// The dispute cannot be withdrawn once resolvers are drawn.
+		assert_noop!(
+			DisputeResolution::withdraw_dispute(Origin::signed(ALICE), payment_hash),
+			Error::<Runtime>::DisputeNotWithdrawable,
+		);
 	});
 }
 
 #[test]
-fn update_credibility_of_dispute_related_parties_works() {
+fn fight_an_escalated_dispute_works() {
 	ExtBuilder::default().build().execute_with(|| {
 		System::set_block_number(1);
 
-		// Simulate a dispute with 3 resolvers.
-		// Initial 3 resolver
+		// Initial resolvers network.
 		assert_ok!(Identities::create_identity(
 			Origin::signed(RESOLVER_1),
 			"Resolver 1".into(),
@@ -465,6 +795,7 @@ fn update_credibility_of_dispute_related_parties_works() {
 			Origin::signed(RESOLVER_1),
 			"".into(),
 			1100,
+			[0; 32],
 		));
 		assert_ok!(Identities::create_identity(
 			Origin::signed(RESOLVER_2),
@@ -476,6 +807,7 @@ fn update_credibility_of_dispute_related_parties_works() {
 			Origin::signed(RESOLVER_2),
 			"".into(),
 			1100,
+			[0; 32],
 		));
 		assert_ok!(Identities::create_identity(
 			Origin::signed(RESOLVER_3),
@@ -487,86 +819,2090 @@ fn update_credibility_of_dispute_related_parties_works() {
 			Origin::signed(RESOLVER_3),
 			"".into(),
 			1100,
+			[0; 32],
 		));
-		// Create identity for Alice an Bob
-		assert_ok!(Identities::create_identity(
-			Origin::signed(ALICE),
-			"Alice".into(),
-			IdentityType::Individual,
-			[].into(),
-		));
-		assert_ok!(Identities::create_identity(
-			Origin::signed(BOB),
-			"Bob".into(),
-			IdentityType::Individual,
-			[].into(),
-		));
-		// Alice create a payment and Bob accept it.
+
+		// Simulate escalate dispute
 		assert_ok!(LRP::create_payment(
 			Origin::signed(ALICE),
 			BOB,
-			200,
+			100,
 			CurrencyId::Native,
+			PaymentDescription::Plain("".into()),
 			"".into(),
-			"".into(),
+			None,
 		));
 		let payment_hash = LRP::payments_owned(&ALICE)[0];
 		assert_ok!(LRP::accept_payment(Origin::signed(BOB), payment_hash));
-
-		// Initial block hashes for the randomness
-		run_to_block_number(32);
-
-		// Alice issue a dispute
 		assert_ok!(DisputeResolution::create_dispute(
 			Origin::signed(ALICE),
 			payment_hash,
 			"".into(),
 		));
-		// Bob fight the dispute
-		assert_ok!(DisputeResolution::fight_dispute(Origin::signed(BOB), payment_hash, "".into()));
-
-		let dispute = DisputeResolution::disputes(&payment_hash).unwrap();
-		// Selected resolver propose the judgment
+		assert_ok!(DisputeResolution::fight_dispute(Origin::signed(BOB), payment_hash, "".into(),));
+		assert_ok!(DisputeResolution::accept_assignment(Origin::signed(RESOLVER_1), payment_hash));
 		assert_ok!(DisputeResolution::propose_outcome(
-			Origin::signed(dispute.resolvers[0]),
-			payment_hash,
-			Judgment::ReleaseFundToPayee
-		));
-		// Bob escalate the proposal judgment
-		assert_ok!(DisputeResolution::escalate_dispute(Origin::signed(BOB), payment_hash));
-		// Alice fight the escalation, 2 more resolvers are selected
-		assert_ok!(DisputeResolution::fight_dispute(
-			Origin::signed(ALICE),
+			Origin::signed(RESOLVER_1),
 			payment_hash,
-			"".into()
+			Judgment::ReleaseFundToPayee,
+			None
 		));
+		assert_ok!(DisputeResolution::escalate_dispute(Origin::signed(ALICE), payment_hash));
+		assert_ok!(DisputeResolution::fight_dispute(Origin::signed(BOB), payment_hash, "".into()));
 
 		let dispute = DisputeResolution::disputes(&payment_hash).unwrap();
+
+		assert_eq!(dispute.status, DisputeStatus::Evaluating);
+		assert_eq!(dispute.resolvers.len(), 3);
+		assert_eq!(dispute.judgments, [(RESOLVER_1, Judgment::ReleaseFundToPayee)].to_vec());
+		assert_eq!(dispute.outcome, Judgment::ReleaseFundToPayer);
+
+		// Both parties escrowed the fee of the three resolvers.
+		assert_eq!(dispute.payer_fee, 3 * DISPUTE_FEE);
+		assert_eq!(dispute.payee_fee, 3 * DISPUTE_FEE);
+		assert_eq!(Currencies::reserved_balance(CurrencyId::Native, &BOB), 3 * DISPUTE_FEE);
+
+		assert_ok!(DisputeResolution::accept_assignment(Origin::signed(RESOLVER_2), payment_hash));
 		assert_ok!(DisputeResolution::propose_outcome(
-			Origin::signed(dispute.resolvers[1]),
+			Origin::signed(RESOLVER_2),
 			payment_hash,
-			Judgment::ReleaseFundToPayer
+			Judgment::ReleaseFundToPayee,
+			None
 		));
+
+		assert_ok!(DisputeResolution::accept_assignment(Origin::signed(RESOLVER_3), payment_hash));
 		assert_ok!(DisputeResolution::propose_outcome(
-			Origin::signed(dispute.resolvers[2]),
+			Origin::signed(RESOLVER_3),
 			payment_hash,
-			Judgment::ReleaseFundToPayer
+			Judgment::ReleaseFundToPayee,
+			None
 		));
 
-		run_to_block_number((DISPUTE_FINALIZING_TIME / BLOCK_TIME + 1).into());
+		let dispute = DisputeResolution::disputes(&payment_hash).unwrap();
 
-		let finalizing_disputes = DisputeResolution::finalizing_disputes();
-		assert_eq!(finalizing_disputes.len(), 0);
+		assert_eq!(dispute.status, DisputeStatus::Finalizing);
+		assert_eq!(dispute.resolvers.len(), 3);
+		assert_eq!(dispute.judgments.len(), 3);
+		assert_eq!(dispute.outcome, Judgment::ReleaseFundToPayee);
 
-		let dispute = DisputeResolution::disputes(&payment_hash).unwrap();
-		assert_eq!(dispute.status, DisputeStatus::Resolved);
-		assert_eq!(dispute.outcome, Judgment::ReleaseFundToPayer);
+		let resolver_balance = Currencies::free_balance(CurrencyId::Native, &RESOLVER_1);
+		let bob_balance = Currencies::free_balance(CurrencyId::Native, &BOB);
 
-		// Expect the credibility of the parties updated.
-		assert_eq!(Identities::get_credibility(&ALICE).unwrap(), 61);
-		assert_eq!(Identities::get_credibility(&BOB).unwrap(), 50);
-		assert_eq!(Identities::get_credibility(&dispute.resolvers[0]).unwrap(), 50);
-		assert_eq!(Identities::get_credibility(&dispute.resolvers[1]).unwrap(), 61);
-		assert_eq!(Identities::get_credibility(&dispute.resolvers[2]).unwrap(), 61);
+		Timestamp::set_timestamp(dispute.expired_at);
+		assert_ok!(DisputeResolution::finalize_dispute(Origin::signed(BOB), payment_hash));
+
+		// The winner is refunded while the resolvers are paid from the escrow of the loser.
+		let resolver_fee = DISPUTE_FEE - Permill::from_percent(10) * DISPUTE_FEE;
+		assert_eq!(Currencies::reserved_balance(CurrencyId::Native, &ALICE), 0);
+		assert_eq!(Currencies::reserved_balance(CurrencyId::Native, &BOB), 0);
+		assert_eq!(
+			Currencies::free_balance(CurrencyId::Native, &BOB),
+			bob_balance + 3 * DISPUTE_FEE + 100
+		);
+		assert_eq!(
+			Currencies::free_balance(CurrencyId::Native, &RESOLVER_1),
+			resolver_balance + resolver_fee
+		);
+
+		let events = System::events();
+		assert!(events.iter().any(|record| record.event ==
+			mock::Event::DisputeResolution(crate::Event::DisputeFeeRefunded {
+				payment_hash,
+				account: BOB,
+				amount: 3 * DISPUTE_FEE,
+			})));
+		for resolver in [RESOLVER_1, RESOLVER_2, RESOLVER_3] {
+			assert!(events.iter().any(|record| record.event ==
+				mock::Event::DisputeResolution(crate::Event::ResolverFeePaid {
+					payment_hash,
+					resolver,
+					amount: resolver_fee,
+				})));
+		}
+	});
+}
+
+#[test]
+fn conflicted_resolvers_are_not_drawn() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+
+		for resolver in [RESOLVER_1, RESOLVER_2] {
+			assert_ok!(Identities::create_identity(
+				Origin::signed(resolver),
+				"Resolver".into(),
+				IdentityType::Individual,
+				[].into(),
+			));
+			assert_ok!(ResolversNetwork::join_resolvers_network(
+				Origin::signed(resolver),
+				"".into(),
+				1100,
+				[0; 32],
+			));
+		}
+		// The payer delegated to the first resolver above the threshold.
+		assert_ok!(ResolversNetwork::delegate(Origin::signed(ALICE), RESOLVER_1, 200));
+
+		for _ in 0..3 {
+			assert_ok!(LRP::create_payment(
+				Origin::signed(ALICE),
+				BOB,
+				10,
+				CurrencyId::Native,
+				PaymentDescription::Plain("".into()),
+				"".into(),
+				None,
+			));
+		}
+		for payment_hash in LRP::payments_owned(&ALICE) {
+			assert_ok!(LRP::accept_payment(Origin::signed(BOB), payment_hash));
+			assert_ok!(DisputeResolution::create_dispute(
+				Origin::signed(ALICE),
+				payment_hash,
+				"".into(),
+			));
+			assert_ok!(DisputeResolution::fight_dispute(
+				Origin::signed(BOB),
+				payment_hash,
+				"".into(),
+			));
+
+			let dispute = DisputeResolution::disputes(&payment_hash).unwrap();
+			assert_eq!(dispute.resolvers, [RESOLVER_2].to_vec());
+		}
+	});
+}
+
+#[test]
+fn category_experts_are_drawn() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+
+		for resolver in [RESOLVER_1, RESOLVER_2] {
+			assert_ok!(Identities::create_identity(
+				Origin::signed(resolver),
+				"Resolver".into(),
+				IdentityType::Individual,
+				[].into(),
+			));
+			assert_ok!(ResolversNetwork::join_resolvers_network(
+				Origin::signed(resolver),
+				"".into(),
+				1100,
+				[0; 32],
+			));
+		}
+		assert_ok!(ResolversNetwork::set_expertise(
+			Origin::signed(RESOLVER_2),
+			[PaymentCategory::DigitalGoods].into(),
+		));
+
+		for _ in 0..3 {
+			assert_ok!(LRP::create_payment(
+				Origin::signed(ALICE),
+				BOB,
+				10,
+				CurrencyId::Native,
+				PaymentDescription::Plain("".into()),
+				"".into(),
+				None,
+			));
+		}
+		for payment_hash in LRP::payments_owned(&ALICE) {
+			assert_ok!(LRP::set_payment_category(
+				Origin::signed(BOB),
+				payment_hash,
+				Some(PaymentCategory::DigitalGoods),
+			));
+			assert_ok!(LRP::accept_payment(Origin::signed(BOB), payment_hash));
+			assert_ok!(DisputeResolution::create_dispute(
+				Origin::signed(ALICE),
+				payment_hash,
+				"".into(),
+			));
+			assert_ok!(DisputeResolution::fight_dispute(
+				Origin::signed(BOB),
+				payment_hash,
+				"".into(),
+			));
+
+			let dispute = DisputeResolution::disputes(&payment_hash).unwrap();
+			assert_eq!(dispute.resolvers, [RESOLVER_2].to_vec());
+		}
+	});
+}
+
+#[test]
+fn accept_outcome_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		Timestamp::set_timestamp(INIT_TIMESTAMP);
+
+		// Initial resolvers network.
+		assert_ok!(Identities::create_identity(
+			Origin::signed(RESOLVER_1),
+			"Resolver 1".into(),
+			IdentityType::Individual,
+			[].into(),
+		));
+		assert_ok!(ResolversNetwork::join_resolvers_network(
+			Origin::signed(RESOLVER_1),
+			"".into(),
+			1100,
+			[0; 32],
+		));
+
+		assert_ok!(LRP::create_payment(
+			Origin::signed(ALICE),
+			BOB,
+			100,
+			CurrencyId::Native,
+			PaymentDescription::Plain("".into()),
+			"".into(),
+			None,
+		));
+		let payment_hash = LRP::payments_owned(&ALICE)[0];
+		assert_ok!(LRP::accept_payment(Origin::signed(BOB), payment_hash));
+		assert_ok!(DisputeResolution::create_dispute(
+			Origin::signed(ALICE),
+			payment_hash,
+			"".into(),
+		));
+		assert_ok!(DisputeResolution::fight_dispute(Origin::signed(BOB), payment_hash, "".into(),));
+
+		// The outcome can be accepted only once the panel concluded the dispute.
+		assert_noop!(
+			DisputeResolution::accept_outcome(Origin::signed(ALICE), payment_hash),
+			Error::<Runtime>::ActionForOnlyFinalizingDispute,
+		);
+
+		assert_ok!(DisputeResolution::accept_assignment(Origin::signed(RESOLVER_1), payment_hash));
+		assert_ok!(DisputeResolution::propose_outcome(
+			Origin::signed(RESOLVER_1),
+			payment_hash,
+			Judgment::ReleaseFundToPayee,
+			None
+		));
+		let resolver_balance = Currencies::free_balance(CurrencyId::Native, &RESOLVER_1);
+
+		// Only the losing party can accept the outcome.
+		assert_noop!(
+			DisputeResolution::accept_outcome(Origin::signed(BOB), payment_hash),
+			Error::<Runtime>::NotLosingParty,
+		);
+
+		// The dispute is finalized without waiting for the finalizing time.
+		assert_ok!(DisputeResolution::accept_outcome(Origin::signed(ALICE), payment_hash));
+
+		let dispute = DisputeResolution::disputes(&payment_hash).unwrap();
+		assert_eq!(dispute.status, DisputeStatus::Resolved);
+		assert_eq!(DisputeResolution::finalizing_disputes().len(), 0);
+		assert!(System::events().iter().any(|record| record.event ==
+			mock::Event::DisputeResolution(crate::Event::OutcomeAccepted {
+				payment_hash,
+				party: ALICE,
+				outcome: Judgment::ReleaseFundToPayee,
+			})));
+
+		// The resolver is paid in full and the insurance fund share is refunded to the payer.
+		let insurance = InsuranceFundShare::get() * DISPUTE_FEE;
+		assert_eq!(
+			Currencies::free_balance(CurrencyId::Native, &RESOLVER_1),
+			resolver_balance + DISPUTE_FEE - insurance
+		);
+		assert_eq!(Currencies::reserved_balance(CurrencyId::Native, &ALICE), 0);
+		assert_eq!(
+			Currencies::free_balance(CurrencyId::Native, &ALICE),
+			900 - DISPUTE_FEE + insurance
+		);
+		assert_eq!(Currencies::free_balance(CurrencyId::Native, &BOB), 1100);
+		assert_eq!(
+			Currencies::free_balance(CurrencyId::Native, &DisputeResolution::insurance_fund_account()),
+			INSURANCE_FUND
+		);
+	});
+}
+
+#[test]
+fn finalize_judgment_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+
+		assert_ok!(LRP::create_payment(
+			Origin::signed(ALICE),
+			BOB,
+			100,
+			CurrencyId::Native,
+			PaymentDescription::Plain("".into()),
+			"".into(),
+			None,
+		));
+		let payment_hash = LRP::payments_owned(&ALICE)[0];
+		assert_ok!(LRP::accept_payment(Origin::signed(BOB), payment_hash));
+		assert_ok!(DisputeResolution::create_dispute(
+			Origin::signed(ALICE),
+			payment_hash,
+			"".into(),
+		));
+
+		run_to_block_number((DISPUTE_FINALIZING_TIME / BLOCK_TIME + 1).into());
+
+		let finalizing_disputes = DisputeResolution::finalizing_disputes();
+		assert_eq!(finalizing_disputes.len(), 0);
+
+		let dispute = DisputeResolution::disputes(&payment_hash).unwrap();
+		assert_eq!(dispute.status, DisputeStatus::Resolved);
+
+		assert_eq!(Currencies::reserved_balance(CurrencyId::Native, &ALICE), 0);
+		assert_eq!(Currencies::free_balance(CurrencyId::Native, &ALICE), 1000);
+	});
+}
+
+#[test]
+fn finalize_dispute_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		Timestamp::set_timestamp(INIT_TIMESTAMP);
+
+		assert_ok!(LRP::create_payment(
+			Origin::signed(ALICE),
+			BOB,
+			100,
+			CurrencyId::Native,
+			PaymentDescription::Plain("".into()),
+			"".into(),
+			None,
+		));
+		let payment_hash = LRP::payments_owned(&ALICE)[0];
+		assert_ok!(LRP::accept_payment(Origin::signed(BOB), payment_hash));
+		assert_ok!(DisputeResolution::create_dispute(
+			Origin::signed(ALICE),
+			payment_hash,
+			"".into(),
+		));
+
+		assert_noop!(
+			DisputeResolution::finalize_dispute(Origin::signed(BOB), payment_hash),
+			Error::<Runtime>::DisputeNotFinalizable,
+		);
+
+		Timestamp::set_timestamp(INIT_TIMESTAMP + DISPUTE_FINALIZING_TIME);
+
+		// Anyone can finalize the dispute once it is out of the finalizing time.
+		assert_ok!(DisputeResolution::finalize_dispute(Origin::signed(BOB), payment_hash));
+
+		let dispute = DisputeResolution::disputes(&payment_hash).unwrap();
+		assert_eq!(dispute.status, DisputeStatus::Resolved);
+		assert_eq!(DisputeResolution::finalizing_disputes().len(), 0);
+		assert_eq!(Currencies::reserved_balance(CurrencyId::Native, &ALICE), 0);
+		assert_eq!(Currencies::free_balance(CurrencyId::Native, &ALICE), 1000);
+
+		// The dispute is counted as lost by the payee and won by the payer.
+		assert_eq!(Identities::dispute_records(&BOB), DisputeRecord { opened: 1, won: 0, lost: 1 });
+		assert_eq!(
+			Identities::dispute_records(&ALICE),
+			DisputeRecord { opened: 0, won: 1, lost: 0 }
+		);
+		assert_eq!(Identities::get_dispute_record(&BOB).loss_rate(), Permill::one());
+		assert_eq!(Identities::get_dispute_record(&BOB).refund_rate(), Permill::one());
+
+		// The loss rate of the payee raises the risk score of the payments to it.
+		for payee in [BOB, RESOLVER_1] {
+			assert_ok!(LRP::create_payment(
+				Origin::signed(ALICE),
+				payee,
+				100,
+				CurrencyId::Native,
+				PaymentDescription::Plain("".into()),
+				"".into(),
+				None,
+			));
+		}
+		let risk_score =
+			|index: usize| LRP::payments(LRP::payments_owned(&ALICE)[index]).unwrap().risk_score;
+		assert_eq!(risk_score(1), risk_score(2) + 20);
+
+		assert_noop!(
+			DisputeResolution::finalize_dispute(Origin::signed(BOB), payment_hash),
+			Error::<Runtime>::DisputeNotFinalizable,
+		);
+
+		// The resolved dispute cannot be challenged after the challenge window.
+		Timestamp::set_timestamp(INIT_TIMESTAMP + DISPUTE_FINALIZING_TIME + CHALLENGE_WINDOW);
+		assert_noop!(
+			DisputeResolution::challenge_outcome(Origin::signed(BOB), payment_hash, "".into()),
+			Error::<Runtime>::ChallengeWindowClosed,
+		);
+	});
+}
+
+#[test]
+fn finalize_dispute_keeps_other_reserves() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		Timestamp::set_timestamp(INIT_TIMESTAMP);
+
+		assert_ok!(LRP::create_payment(
+			Origin::signed(ALICE),
+			BOB,
+			100,
+			CurrencyId::Native,
+			PaymentDescription::Plain("".into()),
+			"".into(),
+			None,
+		));
+		let payment_hash = LRP::payments_owned(&ALICE)[0];
+		assert_ok!(LRP::accept_payment(Origin::signed(BOB), payment_hash));
+		assert_ok!(DisputeResolution::create_dispute(
+			Origin::signed(ALICE),
+			payment_hash,
+			"".into(),
+		));
+		assert_eq!(
+			DisputeResolution::named_reserves(ALICE, (DISPUTE_FEE_RESERVE, CurrencyId::Native)),
+			DISPUTE_FEE
+		);
+
+		// The funds reserved by another pallet are not released with the payment and the fee.
+		assert_ok!(Currencies::reserve(CurrencyId::Native, &ALICE, 50));
+		Timestamp::set_timestamp(INIT_TIMESTAMP + DISPUTE_FINALIZING_TIME);
+		assert_ok!(DisputeResolution::finalize_dispute(Origin::signed(BOB), payment_hash));
+
+		assert_eq!(Currencies::reserved_balance(CurrencyId::Native, &ALICE), 50);
+		assert_eq!(
+			DisputeResolution::named_reserves(ALICE, (DISPUTE_FEE_RESERVE, CurrencyId::Native)),
+			0
+		);
+		assert_eq!(
+			Currencies::free_balance(CurrencyId::Native, &LRP::escrow_account()),
+			ExistentialDeposit::get()
+		);
+
+		// The released payment cannot be cancelled and released again.
+		assert_eq!(LRP::payments(payment_hash).unwrap().status, PaymentStatus::Resolved);
+		assert_noop!(
+			LRP::cancel_payment(Origin::signed(BOB), payment_hash),
+			pallet_lrp::Error::<Runtime>::InvalidStatusChange
+		);
+	});
+}
+
+#[test]
+fn only_selected_resolver_can_propose_judgment() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+
+		// Create 3 test resolvers
+		assert_ok!(Identities::create_identity(
+			Origin::signed(RESOLVER_1),
+			"Resolver 1".into(),
+			IdentityType::Individual,
+			[].into(),
+		));
+		assert_ok!(ResolversNetwork::join_resolvers_network(
+			Origin::signed(RESOLVER_1),
+			"".into(),
+			1100,
+			[0; 32],
+		));
+		assert_ok!(Identities::create_identity(
+			Origin::signed(RESOLVER_2),
+			"Resolver 2".into(),
+			IdentityType::Individual,
+			[].into(),
+		));
+		assert_ok!(ResolversNetwork::join_resolvers_network(
+			Origin::signed(RESOLVER_2),
+			"".into(),
+			1100,
+			[0; 32],
+		));
+		assert_ok!(Identities::create_identity(
+			Origin::signed(RESOLVER_3),
+			"Resolver 3".into(),
+			IdentityType::Individual,
+			[].into(),
+		));
+		assert_ok!(ResolversNetwork::join_resolvers_network(
+			Origin::signed(RESOLVER_3),
+			"".into(),
+			1100,
+			[0; 32],
+		));
+
+		// Simulate accepted payment
+		assert_ok!(LRP::create_payment(
+			Origin::signed(ALICE),
+			BOB,
+			200,
+			CurrencyId::Native,
+			PaymentDescription::Plain("".into()),
+			"".into(),
+			None,
+		));
+		let payment_hash = LRP::payments_owned(&ALICE)[0];
+		assert_ok!(LRP::accept_payment(Origin::signed(BOB), payment_hash));
+
+		// Initial block hashes for the randomness
+		run_to_block_number(32);
+
+		assert_ok!(DisputeResolution::create_dispute(
+			Origin::signed(ALICE),
+			payment_hash,
+			"".into(),
+		));
+		assert_ok!(DisputeResolution::fight_dispute(Origin::signed(BOB), payment_hash, "".into()));
+
+		let resolvers = vec![RESOLVER_1, RESOLVER_2, RESOLVER_3];
+
+		let dispute = DisputeResolution::disputes(&payment_hash).unwrap();
+
+		// Reject account that is not the selected resolver.
+		assert_noop!(
+			DisputeResolution::propose_outcome(
+				Origin::signed(ALICE),
+				payment_hash,
+				Judgment::ReleaseFundToPayer,
+				None
+			),
+			Error::<Runtime>::NotAssignedResolver,
+		);
+
+		for resolver in resolvers.iter() {
+			if dispute.resolvers[0] != *resolver {
+				assert_noop!(
+					DisputeResolution::propose_outcome(
+						Origin::signed(*resolver),
+						payment_hash,
+						Judgment::ReleaseFundToPayer,
+						None
+					),
+					Error::<Runtime>::NotAssignedResolver,
+				);
+			}
+		}
+
+		// Only accept the selected resolver to propose judgment
+		assert_ok!(DisputeResolution::accept_assignment(
+			Origin::signed(dispute.resolvers[0]),
+			payment_hash
+		));
+		assert_ok!(DisputeResolution::propose_outcome(
+			Origin::signed(dispute.resolvers[0]),
+			payment_hash,
+			Judgment::ReleaseFundToPayee,
+			None,
+		));
+
+		// The resolver proposes the judgment once.
+		assert_noop!(
+			DisputeResolution::propose_outcome(
+				Origin::signed(dispute.resolvers[0]),
+				payment_hash,
+				Judgment::ReleaseFundToPayer,
+				None
+			),
+			Error::<Runtime>::AlreadyJudged,
+		);
+	});
+}
+
+#[test]
+fn update_credibility_of_dispute_related_parties_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+
+		// Simulate a dispute with 3 resolvers.
+		// Initial 3 resolver
+		assert_ok!(Identities::create_identity(
+			Origin::signed(RESOLVER_1),
+			"Resolver 1".into(),
+			IdentityType::Individual,
+			[].into(),
+		));
+		assert_ok!(ResolversNetwork::join_resolvers_network(
+			Origin::signed(RESOLVER_1),
+			"".into(),
+			1100,
+			[0; 32],
+		));
+		assert_ok!(Identities::create_identity(
+			Origin::signed(RESOLVER_2),
+			"Resolver 2".into(),
+			IdentityType::Individual,
+			[].into(),
+		));
+		assert_ok!(ResolversNetwork::join_resolvers_network(
+			Origin::signed(RESOLVER_2),
+			"".into(),
+			1100,
+			[0; 32],
+		));
+		assert_ok!(Identities::create_identity(
+			Origin::signed(RESOLVER_3),
+			"Resolver 3".into(),
+			IdentityType::Individual,
+			[].into(),
+		));
+		assert_ok!(ResolversNetwork::join_resolvers_network(
+			Origin::signed(RESOLVER_3),
+			"".into(),
+			1100,
+			[0; 32],
+		));
+		// Create identity for Alice an Bob
+		assert_ok!(Identities::create_identity(
+			Origin::signed(ALICE),
+			"Alice".into(),
+			IdentityType::Individual,
+			[].into(),
+		));
+		assert_ok!(Identities::create_identity(
+			Origin::signed(BOB),
+			"Bob".into(),
+			IdentityType::Individual,
+			[].into(),
+		));
+		// Alice create a payment and Bob accept it.
+		assert_ok!(LRP::create_payment(
+			Origin::signed(ALICE),
+			BOB,
+			200,
+			CurrencyId::Native,
+			PaymentDescription::Plain("".into()),
+			"".into(),
+			None,
+		));
+		let payment_hash = LRP::payments_owned(&ALICE)[0];
+		assert_ok!(LRP::accept_payment(Origin::signed(BOB), payment_hash));
+
+		// Initial block hashes for the randomness
+		run_to_block_number(32);
+
+		// Alice issue a dispute
+		assert_ok!(DisputeResolution::create_dispute(
+			Origin::signed(ALICE),
+			payment_hash,
+			"".into(),
+		));
+		// Bob fight the dispute
+		assert_ok!(DisputeResolution::fight_dispute(Origin::signed(BOB), payment_hash, "".into()));
+
+		let dispute = DisputeResolution::disputes(&payment_hash).unwrap();
+		// Selected resolver propose the judgment
+		assert_ok!(DisputeResolution::accept_assignment(
+			Origin::signed(dispute.resolvers[0]),
+			payment_hash
+		));
+		assert_ok!(DisputeResolution::propose_outcome(
+			Origin::signed(dispute.resolvers[0]),
+			payment_hash,
+			Judgment::ReleaseFundToPayee,
+			None
+		));
+		// Bob escalate the proposal judgment
+		assert_ok!(DisputeResolution::escalate_dispute(Origin::signed(BOB), payment_hash));
+		// Alice fight the escalation, 2 more resolvers are selected
+		assert_ok!(DisputeResolution::fight_dispute(
+			Origin::signed(ALICE),
+			payment_hash,
+			"".into()
+		));
+
+		let dispute = DisputeResolution::disputes(&payment_hash).unwrap();
+		assert_ok!(DisputeResolution::accept_assignment(
+			Origin::signed(dispute.resolvers[1]),
+			payment_hash
+		));
+		assert_ok!(DisputeResolution::propose_outcome(
+			Origin::signed(dispute.resolvers[1]),
+			payment_hash,
+			Judgment::ReleaseFundToPayer,
+			None
+		));
+		assert_ok!(DisputeResolution::accept_assignment(
+			Origin::signed(dispute.resolvers[2]),
+			payment_hash
+		));
+		assert_ok!(DisputeResolution::propose_outcome(
+			Origin::signed(dispute.resolvers[2]),
+			payment_hash,
+			Judgment::ReleaseFundToPayer,
+			None
+		));
+
+		run_to_block_number((DISPUTE_FINALIZING_TIME / BLOCK_TIME + 1).into());
+
+		let finalizing_disputes = DisputeResolution::finalizing_disputes();
+		assert_eq!(finalizing_disputes.len(), 0);
+
+		let dispute = DisputeResolution::disputes(&payment_hash).unwrap();
+		assert_eq!(dispute.status, DisputeStatus::Resolved);
+		assert_eq!(dispute.outcome, Judgment::ReleaseFundToPayer);
+
+		// Expect the credibility of the parties updated.
+		assert_eq!(Identities::get_credibility(&ALICE).unwrap(), 61);
+		assert_eq!(Identities::get_credibility(&BOB).unwrap(), 50);
+		assert_eq!(Identities::get_credibility(&dispute.resolvers[0]).unwrap(), 50);
+		assert_eq!(Identities::get_credibility(&dispute.resolvers[1]).unwrap(), 61);
+		assert_eq!(Identities::get_credibility(&dispute.resolvers[2]).unwrap(), 61);
+	});
+}
+
+#[test]
+fn accept_assignment_locks_resolver_bond() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+
+		assert_ok!(Identities::create_identity(
+			Origin::signed(RESOLVER_1),
+			"Resolver 1".into(),
+			IdentityType::Individual,
+			[].into(),
+		));
+		assert_ok!(ResolversNetwork::join_resolvers_network(
+			Origin::signed(RESOLVER_1),
+			"".into(),
+			1100,
+			[0; 32],
+		));
+
+		assert_ok!(LRP::create_payment(
+			Origin::signed(ALICE),
+			BOB,
+			100,
+			CurrencyId::Native,
+			PaymentDescription::Plain("".into()),
+			"".into(),
+			None,
+		));
+		let payment_hash = LRP::payments_owned(&ALICE)[0];
+		assert_ok!(LRP::accept_payment(Origin::signed(BOB), payment_hash));
+		assert_ok!(DisputeResolution::create_dispute(
+			Origin::signed(ALICE),
+			payment_hash,
+			"".into(),
+		));
+		assert_ok!(DisputeResolution::fight_dispute(Origin::signed(BOB), payment_hash, "".into()));
+
+		// The resolver need to accept the assignment before proposing the judgment.
+		assert_noop!(
+			DisputeResolution::propose_outcome(
+				Origin::signed(RESOLVER_1),
+				payment_hash,
+				Judgment::ReleaseFundToPayee,
+				None
+			),
+			Error::<Runtime>::AssignmentNotAccepted,
+		);
+
+		let reserved_balance = Currencies::reserved_balance(CurrencyId::Native, &RESOLVER_1);
+		assert_ok!(DisputeResolution::accept_assignment(Origin::signed(RESOLVER_1), payment_hash));
+		assert_noop!(
+			DisputeResolution::accept_assignment(Origin::signed(RESOLVER_1), payment_hash),
+			Error::<Runtime>::AssignmentAlreadyAccepted,
+		);
+		assert_noop!(
+			DisputeResolution::decline_assignment(Origin::signed(RESOLVER_1), payment_hash),
+			Error::<Runtime>::AssignmentAlreadyAccepted,
+		);
+		assert_eq!(
+			Currencies::reserved_balance(CurrencyId::Native, &RESOLVER_1),
+			reserved_balance + RESOLVER_BOND
+		);
+		assert!(System::events().iter().any(|record| record.event ==
+			mock::Event::DisputeResolution(crate::Event::AssignmentAccepted {
+				payment_hash,
+				resolver: RESOLVER_1,
+				bond: RESOLVER_BOND,
+				deadline: Timestamp::get() + ASSIGNMENT_JUDGING_TIME,
+			})));
+
+		// The bond is released after the judgment is proposed.
+		assert_ok!(DisputeResolution::propose_outcome(
+			Origin::signed(RESOLVER_1),
+			payment_hash,
+			Judgment::ReleaseFundToPayee,
+			None
+		));
+		assert_eq!(Currencies::reserved_balance(CurrencyId::Native, &RESOLVER_1), reserved_balance);
+
+		let dispute = DisputeResolution::disputes(&payment_hash).unwrap();
+		assert_eq!(dispute.assignments.len(), 0);
+		assert_eq!(dispute.status, DisputeStatus::Finalizing);
+	});
+}
+
+#[test]
+fn decline_assignment_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+
+		for (resolver, name) in [(RESOLVER_1, "Resolver 1"), (RESOLVER_2, "Resolver 2")] {
+			assert_ok!(Identities::create_identity(
+				Origin::signed(resolver),
+				name.into(),
+				IdentityType::Individual,
+				[].into(),
+			));
+			assert_ok!(ResolversNetwork::join_resolvers_network(
+				Origin::signed(resolver),
+				"".into(),
+				1100,
+				[0; 32],
+			));
+		}
+
+		assert_ok!(LRP::create_payment(
+			Origin::signed(ALICE),
+			BOB,
+			100,
+			CurrencyId::Native,
+			PaymentDescription::Plain("".into()),
+			"".into(),
+			None,
+		));
+		let payment_hash = LRP::payments_owned(&ALICE)[0];
+		assert_ok!(LRP::accept_payment(Origin::signed(BOB), payment_hash));
+		assert_ok!(DisputeResolution::create_dispute(
+			Origin::signed(ALICE),
+			payment_hash,
+			"".into(),
+		));
+		assert_ok!(DisputeResolution::fight_dispute(Origin::signed(BOB), payment_hash, "".into()));
+
+		let dispute = DisputeResolution::disputes(&payment_hash).unwrap();
+		let assigned = dispute.resolvers[0];
+		let other = if assigned == RESOLVER_1 { RESOLVER_2 } else { RESOLVER_1 };
+
+		assert_noop!(
+			DisputeResolution::decline_assignment(Origin::signed(other), payment_hash),
+			Error::<Runtime>::AssignmentNotFound,
+		);
+		assert_ok!(DisputeResolution::decline_assignment(Origin::signed(assigned), payment_hash));
+
+		// Another resolver is drawn to replace the declined one.
+		let dispute = DisputeResolution::disputes(&payment_hash).unwrap();
+		assert_eq!(dispute.resolvers, [other].to_vec());
+		assert_eq!(dispute.assignments.len(), 1);
+		assert_eq!(dispute.assignments[0].resolver, other);
+		assert!(!dispute.assignments[0].accepted);
+	});
+}
+
+#[test]
+fn expire_assignments_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		Timestamp::set_timestamp(INIT_TIMESTAMP);
+
+		for (resolver, name) in [(RESOLVER_1, "Resolver 1"), (RESOLVER_2, "Resolver 2")] {
+			assert_ok!(Identities::create_identity(
+				Origin::signed(resolver),
+				name.into(),
+				IdentityType::Individual,
+				[].into(),
+			));
+			assert_ok!(ResolversNetwork::join_resolvers_network(
+				Origin::signed(resolver),
+				"".into(),
+				1100,
+				[0; 32],
+			));
+		}
+
+		assert_ok!(LRP::create_payment(
+			Origin::signed(ALICE),
+			BOB,
+			100,
+			CurrencyId::Native,
+			PaymentDescription::Plain("".into()),
+			"".into(),
+			None,
+		));
+		let payment_hash = LRP::payments_owned(&ALICE)[0];
+		assert_ok!(LRP::accept_payment(Origin::signed(BOB), payment_hash));
+		assert_ok!(DisputeResolution::create_dispute(
+			Origin::signed(ALICE),
+			payment_hash,
+			"".into(),
+		));
+		assert_ok!(DisputeResolution::fight_dispute(Origin::signed(BOB), payment_hash, "".into()));
+
+		let dispute = DisputeResolution::disputes(&payment_hash).unwrap();
+		let assigned = dispute.resolvers[0];
+		let other = if assigned == RESOLVER_1 { RESOLVER_2 } else { RESOLVER_1 };
+
+		assert_ok!(DisputeResolution::accept_assignment(Origin::signed(assigned), payment_hash));
+		assert_noop!(
+			DisputeResolution::expire_assignments(Origin::signed(ALICE), payment_hash),
+			Error::<Runtime>::NoExpiredAssignment,
+		);
+
+		let free_balance = Currencies::free_balance(CurrencyId::Native, &assigned);
+		let reserved_balance = Currencies::reserved_balance(CurrencyId::Native, &assigned);
+
+		// The resolver does not propose the judgment in time.
+		Timestamp::set_timestamp(INIT_TIMESTAMP + ASSIGNMENT_JUDGING_TIME);
+		assert_ok!(DisputeResolution::expire_assignments(Origin::signed(ALICE), payment_hash));
+
+		// The bond is slashed to the insurance fund and another resolver is drawn.
+		assert!(System::events().iter().any(|record| record.event ==
+			mock::Event::DisputeResolution(crate::Event::AssignmentExpired {
+				payment_hash,
+				resolver: assigned,
+				slashed: RESOLVER_BOND,
+			})));
+		assert_eq!(Currencies::free_balance(CurrencyId::Native, &assigned), free_balance);
+		assert_eq!(
+			Currencies::reserved_balance(CurrencyId::Native, &assigned),
+			reserved_balance - RESOLVER_BOND
+		);
+		assert_eq!(
+			Currencies::free_balance(
+				CurrencyId::Native,
+				&DisputeResolution::insurance_fund_account()
+			),
+			INSURANCE_FUND + RESOLVER_BOND
+		);
+
+		let dispute = DisputeResolution::disputes(&payment_hash).unwrap();
+		assert_eq!(dispute.resolvers, [other].to_vec());
+		assert_eq!(dispute.assignments[0].resolver, other);
+		assert_eq!(DisputeResolution::sla_counters().judging_timeouts, 1);
+		assert_eq!(
+			dispute.assignments[0].deadline,
+			INIT_TIMESTAMP + ASSIGNMENT_JUDGING_TIME + ASSIGNMENT_ACCEPTANCE_TIME
+		);
+	});
+}
+
+#[test]
+fn slash_claim_is_paid_to_winner() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		Timestamp::set_timestamp(INIT_TIMESTAMP);
+
+		for (resolver, name) in [(RESOLVER_1, "Resolver 1"), (RESOLVER_2, "Resolver 2")] {
+			assert_ok!(Identities::create_identity(
+				Origin::signed(resolver),
+				name.into(),
+				IdentityType::Individual,
+				[].into(),
+			));
+			assert_ok!(ResolversNetwork::join_resolvers_network(
+				Origin::signed(resolver),
+				"".into(),
+				1100,
+				[0; 32],
+			));
+		}
+
+		assert_ok!(LRP::create_payment(
+			Origin::signed(ALICE),
+			BOB,
+			100,
+			CurrencyId::Native,
+			PaymentDescription::Plain("".into()),
+			"".into(),
+			None,
+		));
+		let payment_hash = LRP::payments_owned(&ALICE)[0];
+		assert_ok!(LRP::accept_payment(Origin::signed(BOB), payment_hash));
+		assert_ok!(DisputeResolution::create_dispute(
+			Origin::signed(ALICE),
+			payment_hash,
+			"".into(),
+		));
+		assert_ok!(DisputeResolution::fight_dispute(Origin::signed(BOB), payment_hash, "".into()));
+
+		let assigned = DisputeResolution::disputes(&payment_hash).unwrap().resolvers[0];
+		assert_ok!(DisputeResolution::accept_assignment(Origin::signed(assigned), payment_hash));
+
+		// The resolver does not propose the judgment in time, half of the bond is claimed.
+		Timestamp::set_timestamp(INIT_TIMESTAMP + ASSIGNMENT_JUDGING_TIME);
+		assert_ok!(DisputeResolution::expire_assignments(Origin::signed(ALICE), payment_hash));
+		assert_eq!(DisputeResolution::slash_claims(&payment_hash), RESOLVER_BOND / 2);
+		assert!(System::events().iter().any(|record| record.event ==
+			mock::Event::DisputeResolution(crate::Event::SlashClaimRecorded {
+				payment_hash,
+				resolver: assigned,
+				amount: RESOLVER_BOND / 2,
+			})));
+
+		let other = DisputeResolution::disputes(&payment_hash).unwrap().resolvers[0];
+		assert_ok!(DisputeResolution::accept_assignment(Origin::signed(other), payment_hash));
+		assert_ok!(DisputeResolution::propose_outcome(
+			Origin::signed(other),
+			payment_hash,
+			Judgment::ReleaseFundToPayer,
+			None
+		));
+
+		let dispute = DisputeResolution::disputes(&payment_hash).unwrap();
+		Timestamp::set_timestamp(dispute.expired_at);
+		assert_ok!(DisputeResolution::finalize_dispute(Origin::signed(BOB), payment_hash));
+
+		// The claim is paid to the payer who won the dispute.
+		assert_eq!(DisputeResolution::slash_claims(&payment_hash), 0);
+		assert!(System::events().iter().any(|record| record.event ==
+			mock::Event::DisputeResolution(crate::Event::SlashClaimPaid {
+				payment_hash,
+				beneficiary: ALICE,
+				amount: RESOLVER_BOND / 2,
+			})));
+	});
+}
+
+#[test]
+fn compensate_from_insurance_fund_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		Timestamp::set_timestamp(INIT_TIMESTAMP);
+
+		assert_ok!(Identities::create_identity(
+			Origin::signed(RESOLVER_1),
+			"Resolver 1".into(),
+			IdentityType::Individual,
+			[].into(),
+		));
+		assert_ok!(ResolversNetwork::join_resolvers_network(
+			Origin::signed(RESOLVER_1),
+			"".into(),
+			1100,
+			[0; 32],
+		));
+
+		assert_ok!(LRP::create_payment(
+			Origin::signed(ALICE),
+			BOB,
+			100,
+			CurrencyId::Native,
+			PaymentDescription::Plain("".into()),
+			"".into(),
+			None,
+		));
+		let payment_hash = LRP::payments_owned(&ALICE)[0];
+		assert_ok!(LRP::accept_payment(Origin::signed(BOB), payment_hash));
+		assert_ok!(DisputeResolution::create_dispute(
+			Origin::signed(ALICE),
+			payment_hash,
+			"".into(),
+		));
+		assert_ok!(DisputeResolution::fight_dispute(Origin::signed(BOB), payment_hash, "".into()));
+		assert_ok!(DisputeResolution::accept_assignment(Origin::signed(RESOLVER_1), payment_hash));
+		assert_ok!(DisputeResolution::propose_outcome(
+			Origin::signed(RESOLVER_1),
+			payment_hash,
+			Judgment::ReleaseFundToPayee,
+			None
+		));
+
+		assert_ok!(DisputeResolution::set_compensation_cap(Origin::root(), 50));
+		assert_noop!(
+			DisputeResolution::compensate(Origin::root(), payment_hash, ALICE, 50),
+			Error::<Runtime>::DisputeNotResolved,
+		);
+
+		Timestamp::set_timestamp(INIT_TIMESTAMP + DISPUTE_FINALIZING_TIME);
+		assert_ok!(DisputeResolution::finalize_dispute(Origin::signed(BOB), payment_hash));
+
+		// A share of the resolver fee is paid to the insurance fund.
+		let fund = DisputeResolution::insurance_fund_account();
+		let insurance = Permill::from_percent(10) * DISPUTE_FEE;
+		assert_eq!(Currencies::free_balance(CurrencyId::Native, &fund), INSURANCE_FUND + insurance);
+		assert_eq!(Currencies::free_balance(CurrencyId::Native, &ALICE), 800);
+
+		assert_noop!(
+			DisputeResolution::compensate(Origin::signed(ALICE), payment_hash, ALICE, 50),
+			DispatchError::BadOrigin,
+		);
+		assert_noop!(
+			DisputeResolution::compensate(Origin::root(), payment_hash, ALICE, 51),
+			Error::<Runtime>::CompensationExceedsCap,
+		);
+
+		assert_ok!(DisputeResolution::compensate(Origin::root(), payment_hash, ALICE, 50));
+		assert_eq!(DisputeResolution::compensations(&payment_hash), Some(50));
+		assert_eq!(Currencies::free_balance(CurrencyId::Native, &ALICE), 850);
+		assert_eq!(
+			Currencies::free_balance(CurrencyId::Native, &fund),
+			INSURANCE_FUND + insurance - 50
+		);
+
+		assert_noop!(
+			DisputeResolution::compensate(Origin::root(), payment_hash, ALICE, 50),
+			Error::<Runtime>::DisputeAlreadyCompensated,
+		);
+	});
+}
+
+#[test]
+fn challenge_outcome_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		Timestamp::set_timestamp(INIT_TIMESTAMP);
+
+		for resolver in [RESOLVER_1, RESOLVER_2, RESOLVER_3, RESOLVER_4] {
+			assert_ok!(Identities::create_identity(
+				Origin::signed(resolver),
+				"Resolver".into(),
+				IdentityType::Individual,
+				[].into(),
+			));
+			assert_ok!(ResolversNetwork::join_resolvers_network(
+				Origin::signed(resolver),
+				"".into(),
+				1100,
+				[0; 32],
+			));
+		}
+		for party in [ALICE, BOB] {
+			assert_ok!(Identities::create_identity(
+				Origin::signed(party),
+				"Party".into(),
+				IdentityType::Individual,
+				[].into(),
+			));
+		}
+
+		assert_ok!(LRP::create_payment(
+			Origin::signed(ALICE),
+			BOB,
+			100,
+			CurrencyId::Native,
+			PaymentDescription::Plain("".into()),
+			"".into(),
+			None,
+		));
+		let payment_hash = LRP::payments_owned(&ALICE)[0];
+		assert_ok!(LRP::accept_payment(Origin::signed(BOB), payment_hash));
+		assert_ok!(DisputeResolution::create_dispute(
+			Origin::signed(ALICE),
+			payment_hash,
+			"".into(),
+		));
+		assert_ok!(DisputeResolution::fight_dispute(Origin::signed(BOB), payment_hash, "".into()));
+
+		let dispute = DisputeResolution::disputes(&payment_hash).unwrap();
+		let original_resolver = dispute.resolvers[0];
+		assert_ok!(DisputeResolution::accept_assignment(
+			Origin::signed(original_resolver),
+			payment_hash
+		));
+		assert_ok!(DisputeResolution::propose_outcome(
+			Origin::signed(original_resolver),
+			payment_hash,
+			Judgment::ReleaseFundToPayee,
+			None
+		));
+
+		Timestamp::set_timestamp(INIT_TIMESTAMP + DISPUTE_FINALIZING_TIME);
+		assert_ok!(DisputeResolution::finalize_dispute(Origin::signed(BOB), payment_hash));
+		assert_eq!(Currencies::free_balance(CurrencyId::Native, &ALICE), 800);
+		assert_eq!(Currencies::free_balance(CurrencyId::Native, &BOB), 1100);
+		assert_eq!(Identities::get_credibility(&original_resolver).unwrap(), 61);
+		assert_eq!(Identities::get_credibility(&ALICE).unwrap(), 50);
+		assert_eq!(Identities::get_credibility(&BOB).unwrap(), 61);
+		assert_eq!(Identities::dispute_records(&BOB), DisputeRecord { opened: 1, won: 1, lost: 0 });
+		assert_eq!(
+			Identities::dispute_records(&ALICE),
+			DisputeRecord { opened: 0, won: 0, lost: 1 }
+		);
+
+		// Only the losing party can challenge the outcome.
+		assert_noop!(
+			DisputeResolution::challenge_outcome(Origin::signed(BOB), payment_hash, "".into()),
+			Error::<Runtime>::NotLosingParty,
+		);
+		assert_ok!(DisputeResolution::challenge_outcome(
+			Origin::signed(ALICE),
+			payment_hash,
+			"".into()
+		));
+
+		// The challenger locks the bond and the fee of an enlarged panel of new resolvers.
+		assert_eq!(Currencies::free_balance(CurrencyId::Native, &ALICE), 300);
+		let dispute = DisputeResolution::disputes(&payment_hash).unwrap();
+		assert_eq!(dispute.status, DisputeStatus::Evaluating);
+		assert_eq!(dispute.resolvers.len(), 3);
+		assert!(!dispute.resolvers.contains(&original_resolver));
+		assert_eq!(
+			dispute.challenge.clone().unwrap().original_resolvers,
+			[original_resolver].to_vec()
+		);
+
+		for resolver in dispute.resolvers.iter() {
+			assert_ok!(DisputeResolution::accept_assignment(
+				Origin::signed(*resolver),
+				payment_hash
+			));
+			assert_ok!(DisputeResolution::propose_outcome(
+				Origin::signed(*resolver),
+				payment_hash,
+				Judgment::ReleaseFundToPayer,
+				None
+			));
+		}
+
+		// The outcome of the challenge panel is final.
+		assert_noop!(
+			DisputeResolution::escalate_dispute(Origin::signed(BOB), payment_hash),
+			Error::<Runtime>::DisputeAlreadyChallenged,
+		);
+
+		Timestamp::set_timestamp(INIT_TIMESTAMP + DISPUTE_FINALIZING_TIME * 2);
+		assert_ok!(DisputeResolution::finalize_dispute(Origin::signed(BOB), payment_hash));
+
+		// The outcome is overturned, the funds are clawed back and the bond is returned.
+		let dispute = DisputeResolution::disputes(&payment_hash).unwrap();
+		assert_eq!(dispute.status, DisputeStatus::Resolved);
+		assert_eq!(dispute.outcome, Judgment::ReleaseFundToPayer);
+		assert_eq!(Currencies::free_balance(CurrencyId::Native, &ALICE), 600);
+		assert_eq!(Currencies::free_balance(CurrencyId::Native, &BOB), 1000);
+		assert_eq!(Currencies::reserved_balance(CurrencyId::Native, &ALICE), 0);
+
+		// The credibility of the parties and the previous resolver is adjusted retroactively.
+		assert_eq!(Identities::get_credibility(&original_resolver).unwrap(), 50);
+		assert_eq!(Identities::get_credibility(&ALICE).unwrap(), 61);
+		assert_eq!(Identities::get_credibility(&BOB).unwrap(), 50);
+		for resolver in dispute.resolvers.iter() {
+			assert_eq!(Identities::get_credibility(resolver).unwrap(), 61);
+		}
+
+		// The overturned dispute is moved between the dispute records of the parties.
+		assert_eq!(Identities::dispute_records(&BOB), DisputeRecord { opened: 1, won: 0, lost: 1 });
+		assert_eq!(
+			Identities::dispute_records(&ALICE),
+			DisputeRecord { opened: 0, won: 1, lost: 0 }
+		);
+
+		assert_noop!(
+			DisputeResolution::challenge_outcome(Origin::signed(BOB), payment_hash, "".into()),
+			Error::<Runtime>::DisputeAlreadyChallenged,
+		);
+	});
+}
+
+#[test]
+fn precedents_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		Timestamp::set_timestamp(INIT_TIMESTAMP);
+
+		assert_ok!(Identities::create_identity(
+			Origin::signed(RESOLVER_1),
+			"Resolver".into(),
+			IdentityType::Individual,
+			[].into(),
+		));
+		assert_ok!(ResolversNetwork::join_resolvers_network(
+			Origin::signed(RESOLVER_1),
+			"".into(),
+			1100,
+			[0; 32],
+		));
+
+		let rationale = <Runtime as system::Config>::Hashing::hash_of(&"rationale".as_bytes());
+		let judgments = [
+			(Judgment::ReleaseFundToPayee, Some(rationale)),
+			(Judgment::ReleaseFundToPayer, None),
+			(Judgment::ReleaseFundToPayee, Some(rationale)),
+		];
+		for _ in 0..judgments.len() {
+			assert_ok!(LRP::create_payment(
+				Origin::signed(ALICE),
+				BOB,
+				10,
+				CurrencyId::Native,
+				PaymentDescription::Plain("".into()),
+				"".into(),
+				None,
+			));
+		}
+		let payment_hashes = LRP::payments_owned(&ALICE);
+		for (payment_hash, (judgment, rationale)) in payment_hashes.iter().zip(judgments) {
+			assert_ok!(LRP::set_payment_category(
+				Origin::signed(BOB),
+				*payment_hash,
+				Some(PaymentCategory::DigitalGoods),
+			));
+			assert_ok!(LRP::accept_payment(Origin::signed(BOB), *payment_hash));
+			assert_ok!(DisputeResolution::create_dispute(
+				Origin::signed(ALICE),
+				*payment_hash,
+				"".into(),
+			));
+			assert_ok!(DisputeResolution::fight_dispute(
+				Origin::signed(BOB),
+				*payment_hash,
+				"".into(),
+			));
+			assert_ok!(DisputeResolution::accept_assignment(
+				Origin::signed(RESOLVER_1),
+				*payment_hash
+			));
+			assert_ok!(DisputeResolution::propose_outcome(
+				Origin::signed(RESOLVER_1),
+				*payment_hash,
+				judgment,
+				rationale,
+			));
+			assert_eq!(
+				DisputeResolution::judgment_rationales(payment_hash, &RESOLVER_1),
+				rationale
+			);
+		}
+
+		// The precedents are indexed when the disputes are resolved.
+		assert!(DisputeResolution::precedents(PaymentCategory::DigitalGoods).is_empty());
+		Timestamp::set_timestamp(INIT_TIMESTAMP + DISPUTE_FINALIZING_TIME);
+		for payment_hash in payment_hashes.iter() {
+			assert_ok!(DisputeResolution::finalize_dispute(Origin::signed(BOB), *payment_hash));
+		}
+		assert!(System::events().iter().any(|record| record.event ==
+			mock::Event::DisputeResolution(crate::Event::PrecedentRecorded {
+				payment_hash: payment_hashes[2],
+				category: PaymentCategory::DigitalGoods,
+				outcome: Judgment::ReleaseFundToPayee,
+			})));
+
+		// The oldest precedent is dropped once the category is full.
+		let precedents = DisputeResolution::precedents(PaymentCategory::DigitalGoods);
+		assert_eq!(precedents.len() as u32, MaxPrecedentsPerCategory::get());
+		assert_eq!(precedents[0].payment_hash, payment_hashes[1]);
+		assert_eq!(precedents[0].rationales, [].to_vec());
+
+		let precedents = DisputeResolution::precedents_of(
+			PaymentCategory::DigitalGoods,
+			Judgment::ReleaseFundToPayee,
+		);
+		assert_eq!(precedents.len(), 1);
+		assert_eq!(precedents[0].payment_hash, payment_hashes[2]);
+		assert_eq!(precedents[0].rationales, [rationale].to_vec());
+		assert_eq!(precedents[0].resolved_at, INIT_TIMESTAMP + DISPUTE_FINALIZING_TIME);
+	});
+}
+
+#[test]
+fn disputes_owned_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+
+		assert_ok!(Identities::create_identity(
+			Origin::signed(RESOLVER_1),
+			"Resolver 1".into(),
+			IdentityType::Individual,
+			[].into(),
+		));
+		assert_ok!(ResolversNetwork::join_resolvers_network(
+			Origin::signed(RESOLVER_1),
+			"".into(),
+			1100,
+			[0; 32],
+		));
+
+		for _ in 0..2 {
+			assert_ok!(LRP::create_payment(
+				Origin::signed(ALICE),
+				BOB,
+				100,
+				CurrencyId::Native,
+				PaymentDescription::Plain("".into()),
+				"".into(),
+				None,
+			));
+		}
+		let first_payment_hash = LRP::payments_owned(&ALICE)[0];
+		let second_payment_hash = LRP::payments_owned(&ALICE)[1];
+
+		assert_ok!(LRP::accept_payment(Origin::signed(BOB), first_payment_hash));
+		assert_ok!(DisputeResolution::create_dispute(
+			Origin::signed(ALICE),
+			first_payment_hash,
+			"".into(),
+		));
+		assert_ok!(DisputeResolution::fight_dispute(
+			Origin::signed(BOB),
+			first_payment_hash,
+			"".into()
+		));
+
+		// The resolver participates in the dispute once the assignment is accepted.
+		assert_eq!(DisputeResolution::disputes_owned(&RESOLVER_1).to_vec(), [].to_vec());
+		assert_ok!(DisputeResolution::accept_assignment(
+			Origin::signed(RESOLVER_1),
+			first_payment_hash
+		));
+
+		assert_eq!(
+			DisputeResolution::disputes_owned(&ALICE).to_vec(),
+			[first_payment_hash].to_vec()
+		);
+		assert_eq!(DisputeResolution::disputes_owned(&BOB).to_vec(), [first_payment_hash].to_vec());
+		assert_eq!(
+			DisputeResolution::disputes_owned(&RESOLVER_1).to_vec(),
+			[first_payment_hash].to_vec()
+		);
+
+		// The oldest dispute is dropped once the history is full.
+		MaxDisputesOwned::set(1);
+		assert_ok!(LRP::accept_payment(Origin::signed(BOB), second_payment_hash));
+		assert_ok!(DisputeResolution::create_dispute(
+			Origin::signed(ALICE),
+			second_payment_hash,
+			"".into(),
+		));
+
+		assert_eq!(
+			DisputeResolution::disputes_owned(&ALICE).to_vec(),
+			[second_payment_hash].to_vec()
+		);
+		assert_eq!(
+			DisputeResolution::disputes_owned(&RESOLVER_1).to_vec(),
+			[first_payment_hash].to_vec()
+		);
+	});
+}
+
+#[test]
+fn incomplete_panel_is_concluded_by_collected_judgments() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+
+		for resolver in [RESOLVER_1, RESOLVER_2] {
+			assert_ok!(Identities::create_identity(
+				Origin::signed(resolver),
+				"Resolver".into(),
+				IdentityType::Individual,
+				[].into(),
+			));
+			assert_ok!(ResolversNetwork::join_resolvers_network(
+				Origin::signed(resolver),
+				"".into(),
+				1100,
+				[0; 32],
+			));
+		}
+
+		assert_ok!(LRP::create_payment(
+			Origin::signed(ALICE),
+			BOB,
+			100,
+			CurrencyId::Native,
+			PaymentDescription::Plain("".into()),
+			"".into(),
+			None,
+		));
+		let payment_hash = LRP::payments_owned(&ALICE)[0];
+		assert_ok!(LRP::accept_payment(Origin::signed(BOB), payment_hash));
+		assert_ok!(DisputeResolution::create_dispute(
+			Origin::signed(ALICE),
+			payment_hash,
+			"".into(),
+		));
+		assert_ok!(DisputeResolution::fight_dispute(Origin::signed(BOB), payment_hash, "".into()));
+
+		let first_resolver = DisputeResolution::disputes(&payment_hash).unwrap().resolvers[0];
+		assert_ok!(DisputeResolution::accept_assignment(
+			Origin::signed(first_resolver),
+			payment_hash
+		));
+		assert_ok!(DisputeResolution::propose_outcome(
+			Origin::signed(first_resolver),
+			payment_hash,
+			Judgment::ReleaseFundToPayee,
+			None
+		));
+		assert_ok!(DisputeResolution::escalate_dispute(Origin::signed(ALICE), payment_hash));
+		assert_ok!(DisputeResolution::fight_dispute(Origin::signed(BOB), payment_hash, "".into()));
+
+		// Only one resolver is available, the panel size is capped.
+		let dispute = DisputeResolution::disputes(&payment_hash).unwrap();
+		assert_eq!(dispute.status, DisputeStatus::Evaluating);
+		assert_eq!(dispute.resolvers.len(), 2);
+		let second_resolver = dispute.resolvers[1];
+
+		// The declined seat cannot be refilled, the dispute is concluded by the collected
+		// judgments.
+		assert_ok!(DisputeResolution::decline_assignment(
+			Origin::signed(second_resolver),
+			payment_hash
+		));
+
+		let dispute = DisputeResolution::disputes(&payment_hash).unwrap();
+		assert_eq!(dispute.status, DisputeStatus::Finalizing);
+		assert_eq!(dispute.resolvers, [first_resolver].to_vec());
+		assert_eq!(dispute.outcome, Judgment::ReleaseFundToPayee);
+		assert_eq!(DisputeResolution::finalizing_disputes(), [payment_hash].to_vec());
+	});
+}
+
+#[test]
+fn panel_losing_seats_is_concluded_by_super_majority_when_judged_last() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+
+		for resolver in [RESOLVER_1, RESOLVER_2, RESOLVER_3] {
+			assert_ok!(Identities::create_identity(
+				Origin::signed(resolver),
+				"Resolver".into(),
+				IdentityType::Individual,
+				[].into(),
+			));
+			assert_ok!(ResolversNetwork::join_resolvers_network(
+				Origin::signed(resolver),
+				"".into(),
+				1100,
+				[0; 32],
+			));
+		}
+
+		assert_ok!(LRP::create_payment(
+			Origin::signed(ALICE),
+			BOB,
+			100,
+			CurrencyId::Native,
+			PaymentDescription::Plain("".into()),
+			"".into(),
+			None,
+		));
+		let payment_hash = LRP::payments_owned(&ALICE)[0];
+		assert_ok!(LRP::accept_payment(Origin::signed(BOB), payment_hash));
+		assert_ok!(DisputeResolution::create_dispute(
+			Origin::signed(ALICE),
+			payment_hash,
+			"".into(),
+		));
+		assert_ok!(DisputeResolution::fight_dispute(Origin::signed(BOB), payment_hash, "".into()));
+
+		let first_resolver = DisputeResolution::disputes(&payment_hash).unwrap().resolvers[0];
+		assert_ok!(DisputeResolution::accept_assignment(
+			Origin::signed(first_resolver),
+			payment_hash
+		));
+		assert_ok!(DisputeResolution::propose_outcome(
+			Origin::signed(first_resolver),
+			payment_hash,
+			Judgment::ReleaseFundToPayee,
+			None
+		));
+		assert_ok!(DisputeResolution::escalate_dispute(Origin::signed(ALICE), payment_hash));
+		assert_ok!(DisputeResolution::fight_dispute(Origin::signed(BOB), payment_hash, "".into()));
+
+		// The declined seat cannot be refilled, the last resolver is still to judge.
+		let dispute = DisputeResolution::disputes(&payment_hash).unwrap();
+		let (second_resolver, third_resolver) = (dispute.resolvers[1], dispute.resolvers[2]);
+		assert_ok!(DisputeResolution::decline_assignment(
+			Origin::signed(second_resolver),
+			payment_hash
+		));
+		assert_eq!(DisputeResolution::dropped_panel_seats(&payment_hash), 1);
+		assert_eq!(
+			DisputeResolution::disputes(&payment_hash).unwrap().status,
+			DisputeStatus::Evaluating
+		);
+
+		// The judgments of the other seats are collected, 3 of 5 for the payee once the last
+		// resolver judges for the payer.
+		Disputes::<Runtime>::mutate(&payment_hash, |dispute| {
+			let dispute = dispute.as_mut().unwrap();
+			for (resolver, judgment) in [
+				(100, Judgment::ReleaseFundToPayee),
+				(101, Judgment::ReleaseFundToPayee),
+				(102, Judgment::ReleaseFundToPayer),
+			] {
+				dispute.resolvers.push(resolver);
+				dispute.judgments.push((resolver, judgment));
+			}
+		});
+		assert_ok!(DisputeResolution::accept_assignment(
+			Origin::signed(third_resolver),
+			payment_hash
+		));
+		assert_ok!(DisputeResolution::propose_outcome(
+			Origin::signed(third_resolver),
+			payment_hash,
+			Judgment::ReleaseFundToPayer,
+			None
+		));
+
+		// A simple majority is not enough for the payee, as if the seat was dropped last.
+		let dispute = DisputeResolution::disputes(&payment_hash).unwrap();
+		assert_eq!(dispute.status, DisputeStatus::Finalizing);
+		assert_eq!(dispute.outcome, Judgment::ReleaseFundToPayer);
+		assert_eq!(DisputeResolution::dropped_panel_seats(&payment_hash), 0);
+		assert!(System::events().iter().any(|record| record.event ==
+			mock::Event::DisputeResolution(crate::Event::IncompletePanelConcluded {
+				payment_hash,
+				outcome: Judgment::ReleaseFundToPayer,
+			})));
+	});
+}
+
+#[test]
+fn paused_calls_are_rejected() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		Timestamp::set_timestamp(INIT_TIMESTAMP);
+
+		assert_ok!(LRP::create_payment(
+			Origin::signed(ALICE),
+			BOB,
+			100,
+			CurrencyId::Native,
+			PaymentDescription::Plain("".into()),
+			"".into(),
+			None,
+		));
+		let payment_hash = LRP::payments_owned(&ALICE)[0];
+		assert_ok!(LRP::accept_payment(Origin::signed(BOB), payment_hash));
+
+		assert_noop!(
+			DisputeResolution::set_call_paused(
+				Origin::signed(ALICE),
+				PausableCall::CreateDispute,
+				true
+			),
+			DispatchError::BadOrigin,
+		);
+		assert_ok!(DisputeResolution::set_call_paused(
+			Origin::root(),
+			PausableCall::CreateDispute,
+			true
+		));
+		assert!(DisputeResolution::paused_calls(PausableCall::CreateDispute));
+		assert!(System::events().iter().any(|record| record.event ==
+			mock::Event::DisputeResolution(crate::Event::CallPauseUpdated {
+				call: PausableCall::CreateDispute,
+				paused: true,
+			})));
+
+		assert_noop!(
+			DisputeResolution::create_dispute(Origin::signed(ALICE), payment_hash, "".into()),
+			Error::<Runtime>::CallPaused,
+		);
+
+		assert_ok!(DisputeResolution::set_call_paused(
+			Origin::root(),
+			PausableCall::CreateDispute,
+			false
+		));
+		assert_ok!(DisputeResolution::create_dispute(
+			Origin::signed(ALICE),
+			payment_hash,
+			"".into(),
+		));
+
+		// The paused finalization keeps the dispute in the queue.
+		assert_ok!(DisputeResolution::set_call_paused(
+			Origin::root(),
+			PausableCall::FinalizeDispute,
+			true
+		));
+		Timestamp::set_timestamp(INIT_TIMESTAMP + DISPUTE_FINALIZING_TIME);
+		assert_noop!(
+			DisputeResolution::finalize_dispute(Origin::signed(BOB), payment_hash),
+			Error::<Runtime>::CallPaused,
+		);
+		run_to_block_number(1);
+		assert_eq!(DisputeResolution::finalizing_disputes(), [payment_hash].to_vec());
+
+		assert_ok!(DisputeResolution::set_call_paused(
+			Origin::root(),
+			PausableCall::FinalizeDispute,
+			false
+		));
+		assert_ok!(DisputeResolution::finalize_dispute(Origin::signed(BOB), payment_hash));
+		assert_eq!(DisputeResolution::finalizing_disputes().len(), 0);
+	});
+}
+
+#[test]
+fn sla_metrics_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		Timestamp::set_timestamp(INIT_TIMESTAMP);
+
+		assert_ok!(Identities::create_identity(
+			Origin::signed(RESOLVER_1),
+			"Resolver 1".into(),
+			IdentityType::Individual,
+			[].into(),
+		));
+		assert_ok!(ResolversNetwork::join_resolvers_network(
+			Origin::signed(RESOLVER_1),
+			"".into(),
+			1100,
+			[0; 32],
+		));
+
+		for _ in 0..2 {
+			assert_ok!(LRP::create_payment(
+				Origin::signed(ALICE),
+				BOB,
+				100,
+				CurrencyId::Native,
+				PaymentDescription::Plain("".into()),
+				"".into(),
+				None,
+			));
+		}
+		let unfought_payment_hash = LRP::payments_owned(&ALICE)[0];
+		let fought_payment_hash = LRP::payments_owned(&ALICE)[1];
+
+		for payment_hash in [unfought_payment_hash, fought_payment_hash] {
+			assert_ok!(LRP::accept_payment(Origin::signed(BOB), payment_hash));
+			assert_ok!(DisputeResolution::create_dispute(
+				Origin::signed(ALICE),
+				payment_hash,
+				"".into(),
+			));
+		}
+
+		let metrics = DisputeResolution::sla_metrics();
+		assert_eq!(metrics.resolved, 0);
+		assert_eq!(metrics.median_time_to_payout, None);
+
+		// The panel is drawn 1 second and the verdict is proposed 3 seconds after the creation.
+		Timestamp::set_timestamp(INIT_TIMESTAMP + 1_000);
+		assert_ok!(DisputeResolution::fight_dispute(
+			Origin::signed(BOB),
+			fought_payment_hash,
+			"".into(),
+		));
+		Timestamp::set_timestamp(INIT_TIMESTAMP + 3_000);
+		assert_ok!(DisputeResolution::accept_assignment(
+			Origin::signed(RESOLVER_1),
+			fought_payment_hash,
+		));
+		assert_ok!(DisputeResolution::propose_outcome(
+			Origin::signed(RESOLVER_1),
+			fought_payment_hash,
+			Judgment::ReleaseFundToPayee,
+			None
+		));
+
+		let timeline = DisputeResolution::dispute_timelines(&fought_payment_hash).unwrap();
+		assert_eq!(timeline.created_at, INIT_TIMESTAMP);
+		assert_eq!(timeline.panel_drawn_at, Some(INIT_TIMESTAMP + 1_000));
+		assert_eq!(timeline.verdict_at, Some(INIT_TIMESTAMP + 3_000));
+
+		Timestamp::set_timestamp(INIT_TIMESTAMP + DISPUTE_FINALIZING_TIME);
+		assert_ok!(DisputeResolution::finalize_dispute(Origin::signed(BOB), unfought_payment_hash));
+		Timestamp::set_timestamp(INIT_TIMESTAMP + 3_000 + DISPUTE_FINALIZING_TIME);
+		assert_ok!(DisputeResolution::finalize_dispute(Origin::signed(BOB), fought_payment_hash));
+
+		// The timelines are taken once the disputes are resolved.
+		assert!(!DisputeTimelines::<Runtime>::contains_key(&unfought_payment_hash));
+		assert!(!DisputeTimelines::<Runtime>::contains_key(&fought_payment_hash));
+		assert_eq!(DisputeResolution::sla_samples().len(), 2);
+
+		let metrics = DisputeResolution::sla_metrics();
+		assert_eq!(metrics.resolved, 2);
+		assert_eq!(metrics.fought, 1);
+		assert_eq!(metrics.acceptance_timeouts, 0);
+		assert_eq!(metrics.judging_timeouts, 0);
+		assert_eq!(metrics.median_time_to_panel, Some(1_000));
+		assert_eq!(metrics.median_time_to_verdict, Some(3_000));
+		assert_eq!(metrics.median_time_to_payout, Some(3_000 + DISPUTE_FINALIZING_TIME));
+	});
+}
+
+#[test]
+fn estimate_dispute_cost_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		Timestamp::set_timestamp(INIT_TIMESTAMP);
+
+		assert_eq!(DisputeResolution::estimate_dispute_cost(Default::default(), 1), None);
+
+		assert_ok!(LRP::create_payment(
+			Origin::signed(ALICE),
+			BOB,
+			100,
+			CurrencyId::Native,
+			PaymentDescription::Plain("".into()),
+			"".into(),
+			None,
+		));
+		let payment_hash = LRP::payments_owned(&ALICE)[0];
+
+		let estimate = DisputeResolution::estimate_dispute_cost(payment_hash, 2).unwrap();
+		assert_eq!(
+			estimate,
+			DisputeCostEstimate {
+				payment_amount: 100,
+				create_fee: DISPUTE_FEE,
+				fight_fee: DISPUTE_FEE,
+				escalation_fees: vec![DISPUTE_FEE * 2, DISPUTE_FEE * 4],
+				panel_size: 7,
+				total_per_party: DISPUTE_FEE * 7,
+			}
+		);
+
+		// The rounds are capped at the maximum escalation rounds.
+		let estimate = DisputeResolution::estimate_dispute_cost(payment_hash, 10).unwrap();
+		assert_eq!(estimate.escalation_fees.len(), 3);
+		assert_eq!(estimate.panel_size, 15);
+	});
+}
+
+#[test]
+fn review_dispute_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		Timestamp::set_timestamp(INIT_TIMESTAMP);
+
+		assert_ok!(Identities::create_identity(
+			Origin::signed(RESOLVER_1),
+			"Resolver 1".into(),
+			IdentityType::Individual,
+			[].into(),
+		));
+		assert_ok!(ResolversNetwork::join_resolvers_network(
+			Origin::signed(RESOLVER_1),
+			"".into(),
+			1100,
+			[0; 32],
+		));
+		assert_ok!(Identities::create_identity(
+			Origin::signed(ALICE),
+			"Alice".into(),
+			IdentityType::Organization,
+			[].into(),
+		));
+		assert_ok!(Identities::review_identity(Origin::signed(BOB), ALICE, "Scam".into()));
+		assert_ok!(Identities::review_identity(Origin::signed(RESOLVER_2), ALICE, "Bad".into()));
+
+		assert_noop!(
+			DisputeResolution::dispute_review(Origin::signed(ALICE), RESOLVER_3, "".into()),
+			Error::<Runtime>::ReviewNotFound,
+		);
+		assert_ok!(DisputeResolution::dispute_review(
+			Origin::signed(ALICE),
+			BOB,
+			"Never traded with Bob".into(),
+		));
+		let deadline = INIT_TIMESTAMP + ASSIGNMENT_ACCEPTANCE_TIME + ASSIGNMENT_JUDGING_TIME;
+		assert!(System::events().iter().any(|record| record.event ==
+			mock::Event::DisputeResolution(crate::Event::ReviewDisputed {
+				account: ALICE,
+				reviewer: BOB,
+				resolver: RESOLVER_1,
+				fee: REVIEW_DISPUTE_FEE,
+				deadline,
+			})));
+		assert_eq!(Currencies::reserved_balance(CurrencyId::Native, &ALICE), REVIEW_DISPUTE_FEE);
+		assert_noop!(
+			DisputeResolution::dispute_review(Origin::signed(ALICE), BOB, "".into()),
+			Error::<Runtime>::ReviewDisputeExisted,
+		);
+
+		// Only the drawn resolver judges the review, the dispute can be cancelled after the
+		// deadline only.
+		assert_noop!(
+			DisputeResolution::resolve_review_dispute(
+				Origin::signed(BOB),
+				ALICE,
+				BOB,
+				ReviewVerdict::Keep
+			),
+			Error::<Runtime>::NotAssignedResolver,
+		);
+		assert_noop!(
+			DisputeResolution::cancel_review_dispute(Origin::signed(ALICE), BOB),
+			Error::<Runtime>::ReviewDisputeNotExpired,
+		);
+
+		// The removed review is not counted anymore and the resolver is paid.
+		let resolver_balance = Currencies::free_balance(CurrencyId::Native, &RESOLVER_1);
+		assert_ok!(DisputeResolution::resolve_review_dispute(
+			Origin::signed(RESOLVER_1),
+			ALICE,
+			BOB,
+			ReviewVerdict::Remove
+		));
+		let insurance = InsuranceFundShare::get() * REVIEW_DISPUTE_FEE;
+		assert_eq!(
+			Currencies::free_balance(CurrencyId::Native, &RESOLVER_1),
+			resolver_balance + REVIEW_DISPUTE_FEE - insurance
+		);
+		assert_eq!(Currencies::reserved_balance(CurrencyId::Native, &ALICE), 0);
+		assert!(!Identities::has_published_review(&ALICE, &BOB));
+		assert_eq!(Identities::review_counts(&ALICE), 1);
+		assert_eq!(
+			DisputeResolution::review_disputes(&ALICE, &BOB).unwrap().verdict,
+			Some(ReviewVerdict::Remove)
+		);
+		assert_noop!(
+			DisputeResolution::resolve_review_dispute(
+				Origin::signed(RESOLVER_1),
+				ALICE,
+				BOB,
+				ReviewVerdict::Keep
+			),
+			Error::<Runtime>::ReviewDisputeNotFound,
+		);
+
+		// A review dispute not judged in time is cancelled and the fee is refunded.
+		assert_ok!(DisputeResolution::dispute_review(Origin::signed(ALICE), RESOLVER_2, "".into()));
+		Timestamp::set_timestamp(INIT_TIMESTAMP + ASSIGNMENT_ACCEPTANCE_TIME + ASSIGNMENT_JUDGING_TIME);
+		assert_noop!(
+			DisputeResolution::resolve_review_dispute(
+				Origin::signed(RESOLVER_1),
+				ALICE,
+				RESOLVER_2,
+				ReviewVerdict::Remove
+			),
+			Error::<Runtime>::AssignmentExpired,
+		);
+		assert_ok!(DisputeResolution::cancel_review_dispute(Origin::signed(ALICE), RESOLVER_2));
+		assert!(System::events().iter().any(|record| record.event ==
+			mock::Event::DisputeResolution(crate::Event::ReviewDisputeCancelled {
+				account: ALICE,
+				reviewer: RESOLVER_2,
+				fee: REVIEW_DISPUTE_FEE,
+			})));
+		assert_eq!(Currencies::reserved_balance(CurrencyId::Native, &ALICE), 0);
+		assert!(Identities::has_published_review(&ALICE, &RESOLVER_2));
+	});
+}
+
+#[test]
+fn try_state_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		Timestamp::set_timestamp(INIT_TIMESTAMP);
+
+		assert_ok!(LRP::create_payment(
+			Origin::signed(ALICE),
+			BOB,
+			100,
+			CurrencyId::Native,
+			PaymentDescription::Plain("".into()),
+			"".into(),
+			None,
+		));
+		let payment_hash = LRP::payments_owned(&ALICE)[0];
+		assert_ok!(LRP::accept_payment(Origin::signed(BOB), payment_hash));
+		assert_ok!(DisputeResolution::create_dispute(
+			Origin::signed(ALICE),
+			payment_hash,
+			"".into(),
+		));
+		assert_ok!(DisputeResolution::do_try_state());
+
+		// The escrowed fees must be covered by the dispute fee reserves.
+		<Disputes<Runtime>>::mutate(&payment_hash, |dispute| {
+			dispute.as_mut().unwrap().payer_fee += 1;
+		});
+		assert_eq!(
+			DisputeResolution::do_try_state(),
+			Err("The funds escrowed for the disputes exceed the reserves of an account")
+		);
+		<Disputes<Runtime>>::mutate(&payment_hash, |dispute| {
+			dispute.as_mut().unwrap().payer_fee -= 1;
+		});
+
+		// The tracked reserves must be covered by the reserved balances.
+		NamedReserves::<Runtime>::insert(BOB, (DISPUTE_FEE_RESERVE, CurrencyId::Native), 1);
+		assert_eq!(
+			DisputeResolution::do_try_state(),
+			Err("The tracked reserves of an account exceed its reserved balance")
+		);
+	});
+}
+
+#[test]
+fn migrate_to_v1_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		Timestamp::set_timestamp(INIT_TIMESTAMP);
+
+		// Store a fought and an unfought dispute with the layout before the storage versioning.
+		let fought = <Runtime as system::Config>::Hashing::hash_of(&1u8);
+		let unfought = <Runtime as system::Config>::Hashing::hash_of(&2u8);
+		for (payment_hash, status, resolvers) in [
+			(fought, DisputeStatus::Evaluating, [RESOLVER_1].to_vec()),
+			(unfought, DisputeStatus::Finalizing, [].to_vec()),
+		] {
+			frame_support::storage::unhashed::put_raw(
+				&Disputes::<Runtime>::hashed_key_for(payment_hash),
+				&(
+					status,
+					payment_hash,
+					INIT_TIMESTAMP,
+					Vec::<Argument<Runtime>>::new(),
+					resolvers,
+					DISPUTE_FEE,
+					Vec::<(AccountId, Judgment)>::new(),
+					Judgment::ReleaseFundToPayer,
+				)
+					.encode(),
+			);
+		}
+		StorageVersion::new(0).put::<DisputeResolution>();
+
+		migrations::v1::MigrateToV1::<Runtime>::on_runtime_upgrade();
+		assert_eq!(DisputeResolution::on_chain_storage_version(), 1);
+
+		// The fee is escrowed by the payee only if the dispute was fought, and the resolvers yet
+		// to judge need to accept the assignments.
+		let dispute = DisputeResolution::disputes(&fought).unwrap();
+		assert_eq!((dispute.payer_fee, dispute.payee_fee), (DISPUTE_FEE, DISPUTE_FEE));
+		assert_eq!(
+			dispute.assignments,
+			[Assignment::<Runtime> {
+				resolver: RESOLVER_1,
+				accepted: false,
+				deadline: INIT_TIMESTAMP + ASSIGNMENT_ACCEPTANCE_TIME,
+			}]
+			.to_vec()
+		);
+		assert_eq!(dispute.escalations, 0);
+
+		let dispute = DisputeResolution::disputes(&unfought).unwrap();
+		assert_eq!((dispute.payer_fee, dispute.payee_fee), (DISPUTE_FEE, 0));
+		assert!(dispute.assignments.is_empty());
+		assert_eq!(dispute.challenge, None);
+	});
+}
+
+#[test]
+fn migrate_to_v2_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+
+		assert_ok!(LRP::create_payment(
+			Origin::signed(ALICE),
+			BOB,
+			100,
+			CurrencyId::Native,
+			PaymentDescription::Plain("".into()),
+			"".into(),
+			None,
+		));
+		let payment_hash = LRP::payments_owned(&ALICE)[0];
+		assert_ok!(LRP::accept_payment(Origin::signed(BOB), payment_hash));
+		assert_ok!(DisputeResolution::create_dispute(
+			Origin::signed(ALICE),
+			payment_hash,
+			"".into(),
+		));
+
+		StorageVersion::new(1).put::<DisputeResolution>();
+		let _ = NamedReserves::<Runtime>::remove_all(None);
+
+		migrations::v2::MigrateToV2::<Runtime>::on_runtime_upgrade();
+		assert_eq!(DisputeResolution::on_chain_storage_version(), 2);
+		assert_eq!(
+			DisputeResolution::on_chain_storage_version(),
+			DisputeResolution::current_storage_version()
+		);
+		assert_eq!(
+			DisputeResolution::named_reserves(ALICE, (DISPUTE_FEE_RESERVE, CurrencyId::Native)),
+			DISPUTE_FEE
+		);
 	});
 }
@@ -0,0 +1,243 @@
+//! Weights for dispute_resolution.
+//!
+//! The weights are hand-estimated, no benchmark has been run for them yet. The database reads and
+//! writes are counted from the storage items each call accesses, and the base and per-item
+//! execution times are rough estimates. They are to be replaced with the output of the benchmarks
+//! in `benchmarking.rs` on the reference hardware before a release.
+
+use frame_support::{
+	sp_std::marker::PhantomData,
+	traits::Get,
+	weights::{constants::RocksDbWeight, Weight},
+};
+
+/// The weights of the calls of dispute_resolution.
+pub trait WeightInfo {
+	fn create_dispute() -> Weight;
+	fn fight_dispute() -> Weight;
+	fn escalate_dispute() -> Weight;
+	fn withdraw_dispute() -> Weight;
+	fn propose_outcome(r: u32) -> Weight;
+	fn challenge_outcome() -> Weight;
+	fn finalize_dispute(r: u32) -> Weight;
+	fn accept_outcome(r: u32) -> Weight;
+	fn accept_assignment(r: u32) -> Weight;
+	fn decline_assignment(r: u32) -> Weight;
+	fn expire_assignments(r: u32) -> Weight;
+	fn set_compensation_cap() -> Weight;
+	fn compensate() -> Weight;
+	fn set_call_paused() -> Weight;
+	fn submit_private_evidence(e: u32) -> Weight;
+	fn dispute_review() -> Weight;
+	fn resolve_review_dispute() -> Weight;
+	fn cancel_review_dispute() -> Weight;
+}
+
+/// The estimated weights for dispute_resolution, priced with the database weight of the runtime.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	fn create_dispute() -> Weight {
+		(124_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(7))
+			.saturating_add(T::DbWeight::get().writes(7))
+	}
+	fn fight_dispute() -> Weight {
+		(116_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(9))
+			.saturating_add(T::DbWeight::get().writes(6))
+	}
+	fn escalate_dispute() -> Weight {
+		(70_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(4))
+			.saturating_add(T::DbWeight::get().writes(2))
+	}
+	fn withdraw_dispute() -> Weight {
+		(82_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(8))
+			.saturating_add(T::DbWeight::get().writes(8))
+	}
+	fn propose_outcome(r: u32) -> Weight {
+		(48_000_000 as Weight)
+			.saturating_add((310_000 as Weight).saturating_mul(r as Weight))
+			.saturating_add(T::DbWeight::get().reads(6))
+			.saturating_add(T::DbWeight::get().writes(5))
+	}
+	fn challenge_outcome() -> Weight {
+		(1_900_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(9))
+			.saturating_add(T::DbWeight::get().writes(5))
+	}
+	fn finalize_dispute(r: u32) -> Weight {
+		(178_000_000 as Weight)
+			.saturating_add((95_000_000 as Weight).saturating_mul(r as Weight))
+			.saturating_add(T::DbWeight::get().reads(12))
+			.saturating_add(T::DbWeight::get().reads((3 as Weight).saturating_mul(r as Weight)))
+			.saturating_add(T::DbWeight::get().writes(9))
+			.saturating_add(T::DbWeight::get().writes((3 as Weight).saturating_mul(r as Weight)))
+	}
+	fn accept_outcome(r: u32) -> Weight {
+		(180_000_000 as Weight)
+			.saturating_add((82_000_000 as Weight).saturating_mul(r as Weight))
+			.saturating_add(T::DbWeight::get().reads(12))
+			.saturating_add(T::DbWeight::get().reads((2 as Weight).saturating_mul(r as Weight)))
+			.saturating_add(T::DbWeight::get().writes(10))
+			.saturating_add(T::DbWeight::get().writes((2 as Weight).saturating_mul(r as Weight)))
+	}
+	fn accept_assignment(r: u32) -> Weight {
+		(52_000_000 as Weight)
+			.saturating_add((260_000 as Weight).saturating_mul(r as Weight))
+			.saturating_add(T::DbWeight::get().reads(4))
+			.saturating_add(T::DbWeight::get().writes(3))
+	}
+	fn decline_assignment(r: u32) -> Weight {
+		(60_000_000 as Weight)
+			.saturating_add((540_000 as Weight).saturating_mul(r as Weight))
+			.saturating_add(T::DbWeight::get().reads(5))
+			.saturating_add(T::DbWeight::get().writes(2))
+	}
+	fn expire_assignments(r: u32) -> Weight {
+		(58_000_000 as Weight)
+			.saturating_add((68_000_000 as Weight).saturating_mul(r as Weight))
+			.saturating_add(T::DbWeight::get().reads(7))
+			.saturating_add(T::DbWeight::get().reads((1 as Weight).saturating_mul(r as Weight)))
+			.saturating_add(T::DbWeight::get().writes(4))
+			.saturating_add(T::DbWeight::get().writes((2 as Weight).saturating_mul(r as Weight)))
+	}
+	fn set_compensation_cap() -> Weight {
+		(18_000_000 as Weight).saturating_add(T::DbWeight::get().writes(1))
+	}
+	fn compensate() -> Weight {
+		(62_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(5))
+			.saturating_add(T::DbWeight::get().writes(3))
+	}
+	fn set_call_paused() -> Weight {
+		(17_000_000 as Weight).saturating_add(T::DbWeight::get().writes(1))
+	}
+	fn submit_private_evidence(e: u32) -> Weight {
+		(36_000_000 as Weight)
+			.saturating_add((6_200_000 as Weight).saturating_mul(e as Weight))
+			.saturating_add(T::DbWeight::get().reads(3))
+			.saturating_add(T::DbWeight::get().reads((1 as Weight).saturating_mul(e as Weight)))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	fn dispute_review() -> Weight {
+		(92_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(9))
+			.saturating_add(T::DbWeight::get().writes(3))
+	}
+	fn resolve_review_dispute() -> Weight {
+		(78_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(8))
+			.saturating_add(T::DbWeight::get().writes(7))
+	}
+	fn cancel_review_dispute() -> Weight {
+		(40_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(4))
+			.saturating_add(T::DbWeight::get().writes(3))
+	}
+}
+
+/// The estimated weights priced with the RocksDB weight, used by the tests.
+impl WeightInfo for () {
+	fn create_dispute() -> Weight {
+		(124_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(7))
+			.saturating_add(RocksDbWeight::get().writes(7))
+	}
+	fn fight_dispute() -> Weight {
+		(116_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(9))
+			.saturating_add(RocksDbWeight::get().writes(6))
+	}
+	fn escalate_dispute() -> Weight {
+		(70_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(4))
+			.saturating_add(RocksDbWeight::get().writes(2))
+	}
+	fn withdraw_dispute() -> Weight {
+		(82_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(8))
+			.saturating_add(RocksDbWeight::get().writes(8))
+	}
+	fn propose_outcome(r: u32) -> Weight {
+		(48_000_000 as Weight)
+			.saturating_add((310_000 as Weight).saturating_mul(r as Weight))
+			.saturating_add(RocksDbWeight::get().reads(6))
+			.saturating_add(RocksDbWeight::get().writes(5))
+	}
+	fn challenge_outcome() -> Weight {
+		(1_900_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(9))
+			.saturating_add(RocksDbWeight::get().writes(5))
+	}
+	fn finalize_dispute(r: u32) -> Weight {
+		(178_000_000 as Weight)
+			.saturating_add((95_000_000 as Weight).saturating_mul(r as Weight))
+			.saturating_add(RocksDbWeight::get().reads(12))
+			.saturating_add(RocksDbWeight::get().reads((3 as Weight).saturating_mul(r as Weight)))
+			.saturating_add(RocksDbWeight::get().writes(9))
+			.saturating_add(RocksDbWeight::get().writes((3 as Weight).saturating_mul(r as Weight)))
+	}
+	fn accept_outcome(r: u32) -> Weight {
+		(180_000_000 as Weight)
+			.saturating_add((82_000_000 as Weight).saturating_mul(r as Weight))
+			.saturating_add(RocksDbWeight::get().reads(12))
+			.saturating_add(RocksDbWeight::get().reads((2 as Weight).saturating_mul(r as Weight)))
+			.saturating_add(RocksDbWeight::get().writes(10))
+			.saturating_add(RocksDbWeight::get().writes((2 as Weight).saturating_mul(r as Weight)))
+	}
+	fn accept_assignment(r: u32) -> Weight {
+		(52_000_000 as Weight)
+			.saturating_add((260_000 as Weight).saturating_mul(r as Weight))
+			.saturating_add(RocksDbWeight::get().reads(4))
+			.saturating_add(RocksDbWeight::get().writes(3))
+	}
+	fn decline_assignment(r: u32) -> Weight {
+		(60_000_000 as Weight)
+			.saturating_add((540_000 as Weight).saturating_mul(r as Weight))
+			.saturating_add(RocksDbWeight::get().reads(5))
+			.saturating_add(RocksDbWeight::get().writes(2))
+	}
+	fn expire_assignments(r: u32) -> Weight {
+		(58_000_000 as Weight)
+			.saturating_add((68_000_000 as Weight).saturating_mul(r as Weight))
+			.saturating_add(RocksDbWeight::get().reads(7))
+			.saturating_add(RocksDbWeight::get().reads((1 as Weight).saturating_mul(r as Weight)))
+			.saturating_add(RocksDbWeight::get().writes(4))
+			.saturating_add(RocksDbWeight::get().writes((2 as Weight).saturating_mul(r as Weight)))
+	}
+	fn set_compensation_cap() -> Weight {
+		(18_000_000 as Weight).saturating_add(RocksDbWeight::get().writes(1))
+	}
+	fn compensate() -> Weight {
+		(62_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(5))
+			.saturating_add(RocksDbWeight::get().writes(3))
+	}
+	fn set_call_paused() -> Weight {
+		(17_000_000 as Weight).saturating_add(RocksDbWeight::get().writes(1))
+	}
+	fn submit_private_evidence(e: u32) -> Weight {
+		(36_000_000 as Weight)
+			.saturating_add((6_200_000 as Weight).saturating_mul(e as Weight))
+			.saturating_add(RocksDbWeight::get().reads(3))
+			.saturating_add(RocksDbWeight::get().reads((1 as Weight).saturating_mul(e as Weight)))
+			.saturating_add(RocksDbWeight::get().writes(1))
+	}
+	fn dispute_review() -> Weight {
+		(92_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(9))
+			.saturating_add(RocksDbWeight::get().writes(3))
+	}
+	fn resolve_review_dispute() -> Weight {
+		(78_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(8))
+			.saturating_add(RocksDbWeight::get().writes(7))
+	}
+	fn cancel_review_dispute() -> Weight {
+		(40_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(4))
+			.saturating_add(RocksDbWeight::get().writes(3))
+	}
+}
@@ -0,0 +1,33 @@
+//! # Identities Runtime API
+//! The runtime API to query the identities from the clients, e.g. the W3C DID documents of the
+//! identities or the evaluator directory through the `state_call` RPC.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+use sp_std::vec::Vec;
+
+pub use primitives::{
+	DidDocument, DidService, DidVerificationMethod, EvaluatorFilter, EvaluatorListing, Pagination,
+};
+
+sp_api::decl_runtime_apis! {
+	#[api_version(2)]
+	pub trait IdentitiesApi<AccountId, Balance, Hash> where
+		AccountId: Codec,
+		Balance: Codec,
+		Hash: Codec,
+	{
+		/// The DID document of the identity of the account, with the registered encryption keys
+		/// as the verification methods and the verified domains and emails as the services.
+		/// Returns `None` if the account has no identity.
+		fn did_document(who: AccountId) -> Option<DidDocument>;
+
+		/// A page of the active evaluators publishing a price for the field of the filter, or
+		/// with a rate, in the price range. A page holds at most 100 evaluators.
+		fn list_evaluators(
+			filter: EvaluatorFilter<Hash, Balance>,
+			pagination: Pagination,
+		) -> Vec<EvaluatorListing<AccountId, Balance>>;
+	}
+}
@@ -0,0 +1,563 @@
+//! Benchmarking setup for identities.
+
+use super::*;
+
+use frame_benchmarking::{account, benchmarks, whitelisted_caller};
+use frame_support::{sp_runtime::traits::Hash, sp_std::vec::Vec, traits::Get};
+use frame_system::RawOrigin;
+use orml_traits::MultiCurrency;
+use primitives::{CurrencyId, LanguageCode};
+
+type BalanceOf<T> =
+	<<T as Config>::Currency as MultiCurrency<<T as frame_system::Config>::AccountId>>::Balance;
+
+const SEED: u32 = 0;
+/// The maximum number of data fields of an identity in the benchmarks.
+const MAX_FIELDS: u32 = 100;
+/// The number of reviews of an identity before the benchmarked review.
+const MAX_REVIEWS: u32 = 100;
+/// The number of verify requests expiring at the same block before the benchmarked request.
+const MAX_VERIFY_REQUESTS: u32 = 100;
+/// The price an evaluator publishes for each field in the benchmarks.
+const FIELD_PRICE: u32 = 1_000;
+
+/// A valid Ethereum signature, it recovers an address for any signed message, so the caller does
+/// not need to own the address.
+const ETHEREUM_SIGNATURE: [u8; 65] = [
+	0xde, 0x75, 0x8b, 0x0e, 0x65, 0xea, 0x1d, 0x23, 0x5f, 0x05, 0x69, 0x26, 0xeb, 0xb7, 0x0b, 0x16,
+	0xc4, 0x20, 0x77, 0xe7, 0x4f, 0xca, 0xcf, 0x99, 0x95, 0x06, 0x82, 0x5e, 0xeb, 0xb2, 0x29, 0x43,
+	0x46, 0x5f, 0xb2, 0xf5, 0xd5, 0x7f, 0xa0, 0xde, 0x18, 0x6c, 0x4c, 0x48, 0xe9, 0xd5, 0x81, 0x47,
+	0xc2, 0xe7, 0xc1, 0x27, 0x08, 0xc6, 0x00, 0x1a, 0x7e, 0xda, 0x61, 0x5b, 0x15, 0xce, 0x45, 0x80,
+	0x1b,
+];
+
+/// The handle registered in the benchmarks.
+const HANDLE: &[u8] = b"libra";
+
+fn assert_last_event<T: Config>(generic_event: <T as Config>::Event) {
+	frame_system::Pallet::<T>::assert_last_event(generic_event.into());
+}
+
+fn funded_account<T: Config>(name: &'static str, index: u32) -> T::AccountId {
+	let who: T::AccountId = account(name, index, SEED);
+	let amount = T::EvaluatorBonding::get() * 1_000u32.into();
+	T::Currency::deposit(CurrencyId::Native, &who, amount).unwrap();
+	who
+}
+
+fn handle<T: Config>() -> HandleOf<T> {
+	HANDLE.to_vec().try_into().unwrap()
+}
+
+// The domain fields are the most expensive fields to validate.
+fn data_field() -> IdentityFieldInput {
+	IdentityFieldInput {
+		name: b"website".to_vec(),
+		value: b"www.libra.atscale.xyz".to_vec(),
+		verify_method: VerifyMethod::Domain,
+	}
+}
+
+// The names of the data fields of an identity are unique.
+fn data_fields(n: u32) -> Vec<IdentityFieldInput> {
+	(0..n)
+		.map(|i| {
+			let mut field = data_field();
+			field.name.extend_from_slice(&i.to_le_bytes());
+			field
+		})
+		.collect()
+}
+
+fn create_identity<T: Config>(who: &T::AccountId, fields: u32) {
+	Pallet::<T>::create_identity(
+		RawOrigin::Signed(who.clone()).into(),
+		b"Libra".to_vec(),
+		IdentityType::Organization,
+		data_fields(fields),
+	)
+	.unwrap();
+}
+
+fn create_evaluator<T: Config>(who: &T::AccountId) {
+	Pallet::<T>::create_evaluator(
+		RawOrigin::Signed(who.clone()).into(),
+		b"Evaluator".to_vec(),
+		b"Verify the identity data".to_vec(),
+		1_000u32.into(),
+	)
+	.unwrap();
+}
+
+// The benchmarked field is priced last, so the lookup scans the whole price list.
+fn field_prices<T: Config>(count: u32) -> Vec<(T::Hash, BalanceOf<T>)> {
+	let mut prices: Vec<(T::Hash, BalanceOf<T>)> =
+		(1..count).map(|i| (T::Hashing::hash_of(&i), FIELD_PRICE.into())).collect();
+	if count > 0 {
+		prices.push((T::Hashing::hash(&data_fields(1)[0].name), FIELD_PRICE.into()));
+	}
+	prices
+}
+
+benchmarks! {
+	create_identity {
+		let d in 0 .. MAX_FIELDS;
+		let caller: T::AccountId = whitelisted_caller();
+		let data = data_fields(d);
+	}: _(RawOrigin::Signed(caller.clone()), b"Libra".to_vec(), IdentityType::Organization, data)
+	verify {
+		assert_last_event::<T>(Event::IdentityCreated { account_id: caller }.into());
+	}
+
+	update_identity {
+		let d in 0 .. MAX_FIELDS;
+		let caller: T::AccountId = whitelisted_caller();
+		create_identity::<T>(&caller, MAX_FIELDS);
+		let data = data_fields(d);
+	}: _(RawOrigin::Signed(caller.clone()), Some(b"Libra Network".to_vec()), Some(data))
+	verify {
+		let positions = (0..d as u64).collect();
+		assert_last_event::<T>(Event::IdentityUpdated { account_id: caller, positions }.into());
+	}
+
+	update_identity_data {
+		let caller: T::AccountId = whitelisted_caller();
+		create_identity::<T>(&caller, MAX_FIELDS);
+	}: _(RawOrigin::Signed(caller.clone()), (MAX_FIELDS - 1).into(), data_field())
+	verify {
+		let positions = [(MAX_FIELDS - 1).into()].to_vec();
+		assert_last_event::<T>(Event::IdentityUpdated { account_id: caller, positions }.into());
+	}
+
+	add_identity_data {
+		let caller: T::AccountId = whitelisted_caller();
+		create_identity::<T>(&caller, MAX_FIELDS);
+	}: _(RawOrigin::Signed(caller.clone()), data_field())
+	verify {
+		let positions = [MAX_FIELDS.into()].to_vec();
+		assert_last_event::<T>(Event::IdentityUpdated { account_id: caller, positions }.into());
+	}
+
+	remove_identity {
+		let caller = funded_account::<T>("caller", 0);
+		create_identity::<T>(&caller, MAX_FIELDS);
+		Pallet::<T>::register_handle(RawOrigin::Signed(caller.clone()).into(), HANDLE.to_vec())?;
+	}: _(RawOrigin::Signed(caller.clone()))
+	verify {
+		assert!(Pallet::<T>::identities(&caller).is_none());
+		assert!(Pallet::<T>::handles(&handle::<T>()).is_none());
+		assert_last_event::<T>(Event::IdentityRemoved { account_id: caller }.into());
+	}
+
+	review_identity {
+		let owner: T::AccountId = account("owner", 0, SEED);
+		create_identity::<T>(&owner, MAX_FIELDS);
+		for i in 0 .. MAX_REVIEWS {
+			let reviewer: T::AccountId = account("reviewer", i, SEED);
+			Pallet::<T>::review_identity(
+				RawOrigin::Signed(reviewer).into(),
+				owner.clone(),
+				b"Good seller".to_vec(),
+			)?;
+		}
+		let caller: T::AccountId = whitelisted_caller();
+	}: _(RawOrigin::Signed(caller.clone()), owner.clone(), b"Good seller".to_vec())
+	verify {
+		assert_eq!(Pallet::<T>::review_counts(&owner), MAX_REVIEWS + 1);
+		assert!(Pallet::<T>::identity_reviews(&owner, &caller).is_some());
+	}
+
+	create_evaluator {
+		let caller: T::AccountId = whitelisted_caller();
+		let amount = T::EvaluatorBonding::get() * 10u32.into();
+		T::Currency::deposit(CurrencyId::Native, &caller, amount)?;
+		let rate: BalanceOf<T> = 1_000u32.into();
+	}: _(RawOrigin::Signed(caller.clone()), b"Evaluator".to_vec(), b"About".to_vec(), rate)
+	verify {
+		assert!(Pallet::<T>::evaluators(&caller).is_some());
+	}
+
+	set_field_prices {
+		let p in 0 .. T::MaxFieldPrices::get();
+		let evaluator = funded_account::<T>("evaluator", 0);
+		create_evaluator::<T>(&evaluator);
+		// The previously published prices are unindexed.
+		Pallet::<T>::set_field_prices(
+			RawOrigin::Signed(evaluator.clone()).into(),
+			field_prices::<T>(T::MaxFieldPrices::get()),
+		)?;
+		let prices = field_prices::<T>(p);
+	}: _(RawOrigin::Signed(evaluator.clone()), prices.clone())
+	verify {
+		assert_last_event::<T>(Event::FieldPricesUpdated { evaluator, prices }.into());
+	}
+
+	request_to_verify {
+		let p in 1 .. MAX_FIELDS;
+		let evaluator = funded_account::<T>("evaluator", 0);
+		create_evaluator::<T>(&evaluator);
+		Pallet::<T>::set_field_prices(
+			RawOrigin::Signed(evaluator.clone()).into(),
+			field_prices::<T>(T::MaxFieldPrices::get()),
+		)?;
+		for i in 0 .. MAX_VERIFY_REQUESTS {
+			let requestor = funded_account::<T>("requestor", i);
+			create_identity::<T>(&requestor, 1);
+			Pallet::<T>::request_to_verify(
+				RawOrigin::Signed(requestor).into(),
+				[0].to_vec(),
+				evaluator.clone(),
+			)?;
+		}
+		let caller = funded_account::<T>("caller", 0);
+		create_identity::<T>(&caller, MAX_FIELDS);
+		let positions: Vec<u64> = (0..p as u64).collect();
+	}: _(RawOrigin::Signed(caller.clone()), positions.clone(), evaluator.clone())
+	verify {
+		let fee: BalanceOf<T> = (FIELD_PRICE * p).into();
+		assert_last_event::<T>(
+			Event::VerifyDataRequestCreated {
+				request_id: MAX_VERIFY_REQUESTS as VerifyRequestId,
+				requestor: caller,
+				positions,
+				evaluator,
+				fee,
+			}
+			.into()
+		);
+	}
+
+	cancel_verify_request {
+		let evaluator = funded_account::<T>("evaluator", 0);
+		create_evaluator::<T>(&evaluator);
+		let caller = funded_account::<T>("caller", 0);
+		create_identity::<T>(&caller, MAX_FIELDS);
+		let positions: Vec<u64> = (0..MAX_FIELDS as u64).collect();
+		Pallet::<T>::request_to_verify(
+			RawOrigin::Signed(caller.clone()).into(),
+			positions.clone(),
+			evaluator.clone(),
+		)?;
+	}: _(RawOrigin::Signed(caller.clone()), 0)
+	verify {
+		let refund: BalanceOf<T> = (FIELD_PRICE * MAX_FIELDS).into();
+		assert_last_event::<T>(
+			Event::VerifyDataRequestCancelled {
+				request_id: 0,
+				requestor: caller,
+				evaluator,
+				positions,
+				refund,
+			}
+			.into()
+		);
+	}
+
+	verify_data {
+		let p in 1 .. MAX_FIELDS;
+		let evaluator = funded_account::<T>("evaluator", 0);
+		create_evaluator::<T>(&evaluator);
+		let requestor = funded_account::<T>("requestor", 0);
+		create_identity::<T>(&requestor, MAX_FIELDS);
+		let positions: Vec<u64> = (0..p as u64).collect();
+		Pallet::<T>::request_to_verify(
+			RawOrigin::Signed(requestor.clone()).into(),
+			positions.clone(),
+			evaluator.clone(),
+		)?;
+		let transcript: Vec<(u64, bool)> = positions.iter().map(|p| (*p, true)).collect();
+	}: _(RawOrigin::Signed(evaluator.clone()), 0, transcript)
+	verify {
+		let identity = Pallet::<T>::identities(&requestor).unwrap();
+		assert!(identity.data.iter().take(p as usize).all(|field| field.is_verified));
+		let fee: BalanceOf<T> = (FIELD_PRICE * p).into();
+		assert_last_event::<T>(
+			Event::DataVerified { request_id: 0, account: requestor, positions, evaluator, fee }
+				.into()
+		);
+	}
+
+	review_evaluator {
+		let evaluator = funded_account::<T>("evaluator", 0);
+		create_evaluator::<T>(&evaluator);
+		let caller = funded_account::<T>("caller", 0);
+		create_identity::<T>(&caller, 1);
+		Pallet::<T>::request_to_verify(
+			RawOrigin::Signed(caller.clone()).into(),
+			[0].to_vec(),
+			evaluator.clone(),
+		)?;
+		Pallet::<T>::verify_data(
+			RawOrigin::Signed(evaluator.clone()).into(),
+			0,
+			[(0, true)].to_vec(),
+		)?;
+		let content = b"Fast and accurate verification".to_vec();
+		let content_digest = T::Hashing::hash_of(&content);
+	}: _(RawOrigin::Signed(caller.clone()), evaluator.clone(), MAX_REVIEW_STARS, content)
+	verify {
+		assert_last_event::<T>(
+			Event::EvaluatorReviewed {
+				evaluator,
+				reviewer: caller,
+				stars: MAX_REVIEW_STARS,
+				content_digest,
+			}
+			.into()
+		);
+	}
+
+	set_field_schema {
+		let origin = T::GovernanceOrigin::successful_origin();
+		let name = data_field().name;
+	}: _<T::Origin>(origin, name.clone(), VerifyMethod::Domain, b"domain".to_vec())
+	verify {
+		assert!(Pallet::<T>::field_schemas(&name).is_some());
+	}
+
+	remove_field_schema {
+		let name = data_field().name;
+		Pallet::<T>::set_field_schema(
+			T::GovernanceOrigin::successful_origin(),
+			name.clone(),
+			VerifyMethod::Domain,
+			b"domain".to_vec(),
+		)?;
+		let origin = T::GovernanceOrigin::successful_origin();
+	}: _<T::Origin>(origin, name.clone())
+	verify {
+		assert_last_event::<T>(Event::FieldSchemaRemoved { name }.into());
+	}
+
+	suspend_evaluator {
+		let evaluator = funded_account::<T>("evaluator", 0);
+		create_evaluator::<T>(&evaluator);
+		let origin = T::GovernanceOrigin::successful_origin();
+	}: _<T::Origin>(origin, evaluator.clone())
+	verify {
+		assert!(Pallet::<T>::suspended_evaluators(&evaluator));
+		assert_last_event::<T>(Event::EvaluatorSuspended { account: evaluator }.into());
+	}
+
+	reinstate_evaluator {
+		let evaluator = funded_account::<T>("evaluator", 0);
+		create_evaluator::<T>(&evaluator);
+		Pallet::<T>::suspend_evaluator(
+			T::GovernanceOrigin::successful_origin(),
+			evaluator.clone(),
+		)?;
+		let origin = T::GovernanceOrigin::successful_origin();
+	}: _<T::Origin>(origin, evaluator.clone())
+	verify {
+		assert!(!Pallet::<T>::suspended_evaluators(&evaluator));
+		assert_last_event::<T>(Event::EvaluatorReinstated { account: evaluator }.into());
+	}
+
+	set_languages {
+		let l in 0 .. T::MaxLanguages::get();
+		let caller = funded_account::<T>("caller", 0);
+		create_identity::<T>(&caller, 1);
+		let languages: Vec<LanguageCode> = (0 .. l).map(|i| [b'a', b'a' + i as u8]).collect();
+	}: _(RawOrigin::Signed(caller.clone()), languages.clone())
+	verify {
+		assert_last_event::<T>(Event::LanguagesUpdated { account_id: caller, languages }.into());
+	}
+
+	invite_member {
+		let caller = funded_account::<T>("caller", 0);
+		create_identity::<T>(&caller, 1);
+		let member: T::AccountId = account("member", 0, SEED);
+		create_identity::<T>(&member, 1);
+	}: _(RawOrigin::Signed(caller.clone()), member.clone())
+	verify {
+		assert_last_event::<T>(Event::MemberInvited { organization: caller, member }.into());
+	}
+
+	accept_membership {
+		let organization: T::AccountId = account("organization", 0, SEED);
+		create_identity::<T>(&organization, 1);
+		let caller = funded_account::<T>("caller", 0);
+		create_identity::<T>(&caller, 1);
+		Pallet::<T>::invite_member(RawOrigin::Signed(organization.clone()).into(), caller.clone())?;
+	}: _(RawOrigin::Signed(caller.clone()), organization.clone())
+	verify {
+		assert_eq!(Pallet::<T>::organizations(&caller), Some(organization));
+	}
+
+	remove_member {
+		let caller = funded_account::<T>("caller", 0);
+		create_identity::<T>(&caller, 1);
+		let member: T::AccountId = account("member", 0, SEED);
+		create_identity::<T>(&member, 1);
+		Pallet::<T>::invite_member(RawOrigin::Signed(caller.clone()).into(), member.clone())?;
+		Pallet::<T>::accept_membership(RawOrigin::Signed(member.clone()).into(), caller.clone())?;
+	}: _(RawOrigin::Signed(caller.clone()), member.clone())
+	verify {
+		assert_last_event::<T>(Event::MemberRemoved { organization: caller, member }.into());
+	}
+
+	set_encryption_key {
+		let caller = funded_account::<T>("caller", 0);
+		create_identity::<T>(&caller, 1);
+	}: _(RawOrigin::Signed(caller.clone()), EncryptionKeyType::X25519, [1u8; 32])
+	verify {
+		assert_last_event::<T>(
+			Event::EncryptionKeySet {
+				account_id: caller,
+				key_type: EncryptionKeyType::X25519,
+				public: [1u8; 32],
+			}
+			.into(),
+		);
+	}
+
+	rotate_encryption_key {
+		let caller = funded_account::<T>("caller", 0);
+		create_identity::<T>(&caller, 1);
+		Pallet::<T>::set_encryption_key(
+			RawOrigin::Signed(caller.clone()).into(),
+			EncryptionKeyType::X25519,
+			[0u8; 32],
+		)?;
+		// The worst case drops the oldest key of a full history.
+		for i in 1 .. T::MaxEncryptionKeys::get() {
+			Pallet::<T>::rotate_encryption_key(
+				RawOrigin::Signed(caller.clone()).into(),
+				EncryptionKeyType::X25519,
+				[i as u8; 32],
+			)?;
+		}
+	}: _(RawOrigin::Signed(caller.clone()), EncryptionKeyType::Sr25519, [255u8; 32])
+	verify {
+		assert_last_event::<T>(
+			Event::EncryptionKeyRotated {
+				account_id: caller,
+				key_type: EncryptionKeyType::Sr25519,
+				public: [255u8; 32],
+			}
+			.into(),
+		);
+	}
+
+	attest {
+		let caller = funded_account::<T>("caller", 0);
+		create_identity::<T>(&caller, 1);
+		let subject: T::AccountId = account("subject", 0, SEED);
+		create_identity::<T>(&subject, 1);
+		// The worst case scans a nearly full list of attestations.
+		for i in 1 .. T::MaxAttestations::get() {
+			let attester: T::AccountId = account("attester", i, SEED);
+			create_identity::<T>(&attester, 1);
+			Pallet::<T>::attest(
+				RawOrigin::Signed(attester).into(),
+				subject.clone(),
+				AttestationType::Customer,
+			)?;
+		}
+	}: _(RawOrigin::Signed(caller.clone()), subject.clone(), AttestationType::Supplier)
+	verify {
+		assert_last_event::<T>(
+			Event::AttestationIssued {
+				attester: caller,
+				subject,
+				attestation_type: AttestationType::Supplier,
+			}
+			.into(),
+		);
+	}
+
+	revoke_attestation {
+		let caller = funded_account::<T>("caller", 0);
+		create_identity::<T>(&caller, 1);
+		let subject: T::AccountId = account("subject", 0, SEED);
+		create_identity::<T>(&subject, 1);
+		for i in 1 .. T::MaxAttestations::get() {
+			let attester: T::AccountId = account("attester", i, SEED);
+			create_identity::<T>(&attester, 1);
+			Pallet::<T>::attest(
+				RawOrigin::Signed(attester).into(),
+				subject.clone(),
+				AttestationType::Customer,
+			)?;
+		}
+		Pallet::<T>::attest(
+			RawOrigin::Signed(caller.clone()).into(),
+			subject.clone(),
+			AttestationType::Supplier,
+		)?;
+	}: _(RawOrigin::Signed(caller.clone()), subject.clone(), AttestationType::Supplier)
+	verify {
+		assert_last_event::<T>(
+			Event::AttestationRevoked {
+				attester: caller,
+				subject,
+				attestation_type: AttestationType::Supplier,
+			}
+			.into(),
+		);
+	}
+
+	link_external_account {
+		let caller = funded_account::<T>("caller", 0);
+		create_identity::<T>(&caller, 1);
+	}: _(
+		RawOrigin::Signed(caller.clone()),
+		b"ethereum".to_vec(),
+		ExternalAccountProof::Ethereum(ETHEREUM_SIGNATURE)
+	)
+	verify {
+		let identity = Pallet::<T>::identities(&caller).unwrap();
+		assert_eq!(identity.data.last().unwrap().verify_method, VerifyMethod::ExternalSignature);
+	}
+
+	// The worst case claims a handle expired from another account.
+	register_handle {
+		let owner = funded_account::<T>("owner", 0);
+		create_identity::<T>(&owner, 1);
+		Pallet::<T>::register_handle(RawOrigin::Signed(owner).into(), HANDLE.to_vec())?;
+		frame_system::Pallet::<T>::set_block_number(T::HandlePeriod::get() + 1u32.into());
+
+		let caller = funded_account::<T>("caller", 0);
+		create_identity::<T>(&caller, 1);
+	}: _(RawOrigin::Signed(caller.clone()), HANDLE.to_vec())
+	verify {
+		assert_eq!(Pallet::<T>::handles(&handle::<T>()).unwrap().owner, caller);
+	}
+
+	renew_handle {
+		let caller = funded_account::<T>("caller", 0);
+		create_identity::<T>(&caller, 1);
+		Pallet::<T>::register_handle(RawOrigin::Signed(caller.clone()).into(), HANDLE.to_vec())?;
+		let expires_at = Pallet::<T>::handles(&handle::<T>()).unwrap().expires_at;
+	}: _(RawOrigin::Signed(caller.clone()))
+	verify {
+		let registration = Pallet::<T>::handles(&handle::<T>()).unwrap();
+		assert_eq!(registration.expires_at, expires_at + T::HandlePeriod::get());
+	}
+
+	transfer_handle {
+		let caller = funded_account::<T>("caller", 0);
+		create_identity::<T>(&caller, 1);
+		Pallet::<T>::register_handle(RawOrigin::Signed(caller.clone()).into(), HANDLE.to_vec())?;
+		let recipient = funded_account::<T>("recipient", 0);
+		create_identity::<T>(&recipient, 1);
+	}: _(RawOrigin::Signed(caller), recipient.clone())
+	verify {
+		assert_eq!(Pallet::<T>::handles(&handle::<T>()).unwrap().owner, recipient);
+	}
+
+	release_handle {
+		let caller = funded_account::<T>("caller", 0);
+		create_identity::<T>(&caller, 1);
+		Pallet::<T>::register_handle(RawOrigin::Signed(caller.clone()).into(), HANDLE.to_vec())?;
+	}: _(RawOrigin::Signed(caller.clone()))
+	verify {
+		assert!(Pallet::<T>::handles(&handle::<T>()).is_none());
+	}
+
+	impl_benchmark_test_suite!(
+		Pallet,
+		crate::mock::ExtBuilder::default().build(),
+		crate::mock::Runtime
+	);
+}
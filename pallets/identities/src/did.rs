@@ -0,0 +1,106 @@
+//! # DID documents
+//!
+//! The identities are rendered as W3C DID documents, so the wallets and the off-chain verifiers
+//! can consume them in a standard format. The DID of an account is `did:libra:` followed by the
+//! hex of the encoded account id.
+//!
+//! - The registered encryption keys are the verification methods `#key-<n>`, from the oldest to
+//!   the current one, and the current key is the key agreement method.
+//! - The verified domains are `LinkedDomains` services and the verified emails are `Email`
+//!   services, `#domain-<n>` and `#email-<n>` where `n` is the position of the data field.
+//! - The organization of a member is the controller of the document.
+
+use crate::{EncryptionKey, EncryptionKeyType, VerifyMethod};
+use codec::Encode;
+use frame_support::sp_std::vec::Vec;
+use primitives::{DidService, DidVerificationMethod, DID_METHOD_PREFIX};
+
+/// The multicodec prefixes of the public keys, as unsigned varints.
+const X25519_MULTICODEC: [u8; 2] = [0xec, 0x01];
+const SR25519_MULTICODEC: [u8; 2] = [0xef, 0x01];
+
+/// The multibase prefix of the lowercase base16 encoding.
+const BASE16_MULTIBASE: u8 = b'f';
+
+/// The DID of the account.
+pub fn did_of<AccountId: Encode>(account_id: &AccountId) -> Vec<u8> {
+	let mut did = DID_METHOD_PREFIX.to_vec();
+	did.extend_from_slice(b"0x");
+	did.extend(hex(&account_id.encode()));
+	did
+}
+
+/// The DID URL of a fragment of the document, e.g. `did:libra:0xd435...#key-1`.
+pub fn did_url(did: &[u8], fragment: &[u8], index: usize) -> Vec<u8> {
+	let mut url = did.to_vec();
+	url.push(b'#');
+	url.extend_from_slice(fragment);
+	url.push(b'-');
+	url.extend(decimal(index));
+	url
+}
+
+/// The verification method of the registered key at the index of the key history.
+pub fn verification_method<BlockNumber>(
+	did: &[u8],
+	index: usize,
+	key: &EncryptionKey<BlockNumber>,
+) -> DidVerificationMethod {
+	let (method_type, multicodec): (&[u8], _) = match key.key_type {
+		EncryptionKeyType::X25519 => (b"X25519KeyAgreementKey2020", X25519_MULTICODEC),
+		EncryptionKeyType::Sr25519 => (b"Sr25519VerificationKey2020", SR25519_MULTICODEC),
+	};
+	let mut public_key_multibase = [BASE16_MULTIBASE].to_vec();
+	public_key_multibase.extend(hex(&multicodec));
+	public_key_multibase.extend(hex(&key.public));
+
+	DidVerificationMethod {
+		id: did_url(did, b"key", index),
+		method_type: method_type.to_vec(),
+		controller: did.to_vec(),
+		public_key_multibase,
+	}
+}
+
+/// The service of a verified data field, `None` if the field is neither a domain nor an email.
+pub fn service(
+	did: &[u8],
+	index: usize,
+	verify_method: &VerifyMethod,
+	value: &[u8],
+) -> Option<DidService> {
+	let (fragment, service_type, scheme): (&[u8], &[u8], &[u8]) = match verify_method {
+		VerifyMethod::Domain => (b"domain", b"LinkedDomains", b"https://"),
+		VerifyMethod::Email => (b"email", b"Email", b"mailto:"),
+		_ => return None,
+	};
+	let mut service_endpoint = scheme.to_vec();
+	service_endpoint.extend_from_slice(value);
+
+	Some(DidService {
+		id: did_url(did, fragment, index),
+		service_type: service_type.to_vec(),
+		service_endpoint,
+	})
+}
+
+pub(crate) fn hex(bytes: &[u8]) -> Vec<u8> {
+	const DIGITS: &[u8; 16] = b"0123456789abcdef";
+	bytes
+		.iter()
+		.flat_map(|byte| [DIGITS[(byte >> 4) as usize], DIGITS[(byte & 0xf) as usize]])
+		.collect()
+}
+
+pub(crate) fn decimal(mut value: usize) -> Vec<u8> {
+	let mut digits = Vec::new();
+	loop {
+		digits.push(b'0' + (value % 10) as u8);
+		value /= 10;
+		if value == 0 {
+			break
+		}
+	}
+	digits.reverse();
+	digits
+}
@@ -0,0 +1,38 @@
+//! # External account proofs
+//!
+//! An identity owner proves the ownership of an external account by signing the DID of its
+//! identity, see [`crate::did`], with the external key. The signature is checked on-chain when the
+//! field is linked, and the value of the field is the external account recovered from the
+//! signature, so the wallets should check the linked value matches the expected account.
+//!
+//! - Ethereum: a `personal_sign` (EIP-191) signature, the value is the `0x` prefixed hex address.
+
+use crate::did;
+use frame_support::sp_std::vec::Vec;
+use sp_io::{crypto::secp256k1_ecdsa_recover, hashing::keccak_256};
+
+/// The prefix of the messages signed with the Ethereum `personal_sign`.
+const ETHEREUM_MESSAGE_PREFIX: &[u8] = b"\x19Ethereum Signed Message:\n";
+
+/// The hash of the message signed with the Ethereum `personal_sign`.
+pub fn ethereum_message_hash(message: &[u8]) -> [u8; 32] {
+	let mut prefixed = ETHEREUM_MESSAGE_PREFIX.to_vec();
+	prefixed.extend(did::decimal(message.len()));
+	prefixed.extend_from_slice(message);
+	keccak_256(&prefixed)
+}
+
+/// The Ethereum address which signed the message, `None` if the signature is invalid.
+pub fn recover_ethereum_address(message: &[u8], signature: &[u8; 65]) -> Option<[u8; 20]> {
+	let public = secp256k1_ecdsa_recover(signature, &ethereum_message_hash(message)).ok()?;
+	let mut address = [0u8; 20];
+	address.copy_from_slice(&keccak_256(&public)[12..]);
+	Some(address)
+}
+
+/// The value of a linked Ethereum account field.
+pub fn ethereum_address_value(address: &[u8; 20]) -> Vec<u8> {
+	let mut value = b"0x".to_vec();
+	value.extend(did::hex(address));
+	value
+}
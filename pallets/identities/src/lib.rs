@@ -21,35 +21,125 @@
 //! - `update_identity`: update existed identity. This will replace the old identity with the new
 //!   one.
 //! - `update_identity_data`: update a data field of an existed identity
-//! - `add_identity_data`: add a new data field to an existed identity
+//! - `link_external_account`: add a data field proving the ownership of an external account, e.g.
+//!   an Ethereum address, by a signature of the identity DID with the external key. The signature
+//!   is checked on submission, see [`crate::external`]. The linked fields cannot be added as plain
+//!   data.
+//! - `add_identity_data`: add a new data field to an existed identity. The names of the data fields
+//!   of an identity are unique, and a field with a registered schema name must use the expected
+//!   verify method of the schema.
 //! - `remove_identity`: remove an existed identity. The identity reviews will not be removed after
 //!   this action.
-//! - `request_to_verify`: request an evaluator to verify identity data
+//! - `review_identity`: review the identity of another account, once per reviewer. The reviews are
+//!   kept by subject and reviewer out of the identity record, and read a page at a time with
+//!   `identity_reviews_page`. The reviewed identity can dispute a review in the dispute resolution
+//!   pallet, a review removed by the resolver is flagged `ContestedRemoved` and no longer counted.
+//! - `review_evaluator`: rate an evaluator from 1 to 5 stars after it verified the requested data.
+//!   The ratings are aggregated on the evaluator record as its track record.
+//! - `request_to_verify`: request an evaluator to verify identity data. Each request gets an id
+//!   and is kept in `VerifyRequests` with its status, so a requestor can have several concurrent
+//!   requests to an evaluator. The fee is escrowed from the requestor and paid to the evaluator
+//!   once the data is verified. If the evaluator does not respond within `VerifyRequestTimeout`
+//!   blocks, the fee is refunded and the evaluator pays `VerifyRequestPenalty` from its bond to
+//!   the requestor.
+//! - `cancel_verify_request`: withdraw a pending verify data request, the escrowed fee is refunded.
+//! - `set_languages`: declare the preferred languages of the identity owner, up to `MaxLanguages`.
+//!   The dispute resolution prefers the resolvers who share a language with the dispute parties.
+//! - `set_encryption_key`: register the first encryption public key of the identity, X25519 or
+//!   sr25519, the others encrypt the private data to the identity owner with.
+//! - `rotate_encryption_key`: replace the current encryption key. The previous keys are kept up to
+//!   `MaxEncryptionKeys`, the oldest one is dropped from the history once it is full.
+//! - `invite_member`: invite an account to be a member of the organization identity.
+//! - `accept_membership`: accept the invitation of an organization. An account is a member of one
+//!   organization at most, the accepted membership replaces the previous one.
+//! - `remove_member`: end a membership, either by the member or by the organization. The
+//!   resolvers are not drawn for the disputes of the members of their organizations.
+//! - `attest`: attest a typed relationship with another identity, e.g. the subject is an employee
+//!   or a verified supplier of the attester. Each identity holds up to `MaxAttestations`
+//!   attestations, queried by the payment risk scoring and the resolver conflict checks.
+//! - `revoke_attestation`: revoke an attestation issued by the caller.
+//! ## Handles
+//! The identity names are free text, so a unique handle can be registered to tell the identities
+//! apart. The handles are lowercase and first-come, first-served. The registration reserves the
+//! `HandleDeposit` and lasts `HandlePeriod` blocks, an expired handle can be registered by anyone.
+//! The handles of the dispute parties are emitted in the payment and dispute events.
+//! - `register_handle`: register a free or expired handle, an account owns one handle at most.
+//! - `renew_handle`: extend the registration of the handle by `HandlePeriod` blocks.
+//! - `transfer_handle`: transfer the handle and its deposit to another identity.
+//! - `release_handle`: release the handle, the deposit is unreserved.
+//! ## DID documents
+//! `did_document` renders an identity as a W3C DID document with the DID `did:libra:<account>`,
+//! see [`crate::did`]. It is exposed to the clients by the `IdentitiesApi` runtime API.
+//! ## Dispute records
+//! The disputes opened against each account and the disputes it won and lost are counted in
+//! `DisputeRecords` by the payment and dispute pallets through the `IdentitiesManager`, for the
+//! accounts with or without an identity. The loss rate and the refund rate of a merchant are red
+//! flags the payers can check before paying, and the loss rate is part of the payment risk score.
 //! ## Identity Verify Services
 //! - `create_evaluator`: bond native tokens to become evaluator.
-//! - `verify_data`: verify data of a requested identity.
+//! - `set_field_prices`: publish the prices of the data fields keyed by the hash of the field name.
+//!   The fields without a published price cost the evaluator rate.
+//! - `verify_data`: verify the data of a pending verify request.
+//!
+//! The evaluators are indexed by the fields they publish a price for and by the band of their
+//! rate, `EvaluatorRateBand` wide. `list_evaluators` reads a page of the active evaluators
+//! matching a field and a price range through the indexes, it is exposed to the clients by the
+//! `IdentitiesApi` runtime API.
+//! ## Governance
+//! - `suspend_evaluator`: suspend an evaluator, e.g. when its off-chain service is compromised.
+//!   The suspended evaluator cannot receive new verify requests or verify data, and its bond stays
+//!   frozen until it is reinstated.
+//! - `reinstate_evaluator`: reinstate a suspended evaluator.
+//! - `set_field_schema`: register the expected verify method and format hint of a field name, so
+//!   the wallets can render the identities consistently.
+//! - `remove_field_schema`: remove a registered field schema.
+//! ## Reserves
+//! The evaluator bonds and the escrowed fees are reserved under the `EVALUATOR_BOND_RESERVE` and
+//! `VERIFY_FEE_RESERVE` identifiers and tracked in `NamedReserves`. The penalties and the fees are
+//! only taken from the tracked funds, so they never consume the reserves of the other pallets.
+//! ## Genesis
+//! - `identities`: bootstrap identities with the initial credibility.
+//! - `evaluators`: bootstrap evaluators, the evaluator bonding is reserved from them.
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
+pub mod did;
+pub mod external;
+pub mod migrations;
+pub mod weights;
+
 #[cfg(test)]
 mod mock;
 #[cfg(test)]
 mod tests;
 
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+
 pub use pallet::*;
+pub use weights::WeightInfo;
 
 #[frame_support::pallet]
 pub mod pallet {
+	use crate::{did, external, WeightInfo};
 	use codec::{Decode, Encode};
 	use frame_support::{
 		dispatch::DispatchResult,
 		pallet_prelude::*,
-		sp_runtime::{traits::Hash, SaturatedConversion},
+		sp_runtime::{
+			traits::{Hash, Saturating, UniqueSaturatedInto, Zero},
+			Permill,
+		},
 		sp_std::vec::Vec,
+		traits::BalanceStatus,
 	};
 	use frame_system::pallet_prelude::*;
 	use orml_traits::{MultiCurrency, MultiReservableCurrency};
-	use primitives::{Credibility, CurrencyId};
+	use primitives::{
+		indexed_content_key, Credibility, CurrencyId, DidDocument, EvaluatorFilter,
+		EvaluatorListing, LanguageCode, Pagination, ReserveIdentifier, EVALUATOR_BOND_RESERVE,
+		HANDLE_DEPOSIT_RESERVE, VERIFY_FEE_RESERVE,
+	};
 	use scale_info::TypeInfo;
 	#[cfg(feature = "std")]
 	use serde::{Deserialize, Serialize};
@@ -62,12 +152,50 @@ pub mod pallet {
 		/// The amount that an account need to bond to become an evaluator.
 		#[pallet::constant]
 		type EvaluatorBonding: Get<BalanceOf<Self>>;
+		/// The origin which can suspend and reinstate the evaluators.
+		type GovernanceOrigin: EnsureOrigin<Self::Origin>;
+		/// The resolvers network, the identity of a resolver cannot be removed.
+		type Resolvers: ResolversInspector<Self::AccountId>;
+		/// The number of blocks an evaluator has to respond to a verify data request.
+		#[pallet::constant]
+		type VerifyRequestTimeout: Get<Self::BlockNumber>;
+		/// The amount slashed from the evaluator bond to the requestor when a verify data request
+		/// expires.
+		#[pallet::constant]
+		type VerifyRequestPenalty: Get<BalanceOf<Self>>;
+		/// The maximum number of field prices an evaluator can publish.
+		#[pallet::constant]
+		type MaxFieldPrices: Get<u32>;
+		/// The width of the rate bands the evaluator directory indexes the evaluators by.
+		#[pallet::constant]
+		type EvaluatorRateBand: Get<BalanceOf<Self>>;
+		/// The maximum number of preferred languages of an identity.
+		#[pallet::constant]
+		type MaxLanguages: Get<u32>;
+		/// The maximum number of encryption keys of an identity, the current one included. The
+		/// oldest keys are dropped from the history on rotation.
+		#[pallet::constant]
+		type MaxEncryptionKeys: Get<u32>;
+		/// The maximum number of attestations about an identity.
+		#[pallet::constant]
+		type MaxAttestations: Get<u32>;
+		/// The amount reserved from the owner of a handle.
+		#[pallet::constant]
+		type HandleDeposit: Get<BalanceOf<Self>>;
+		/// The number of blocks a handle registration or renewal lasts.
+		#[pallet::constant]
+		type HandlePeriod: Get<Self::BlockNumber>;
+		/// The maximum length of a handle.
+		#[pallet::constant]
+		type MaxHandleLength: Get<u32>;
 		/// Initial credibility of an identity.
 		#[pallet::constant]
 		type InitialCredibility: Get<Credibility>;
 		/// Max credibility of an identity.
 		#[pallet::constant]
 		type MaxCredibility: Get<Credibility>;
+		/// Weight information for extrinsics in this pallet.
+		type WeightInfo: WeightInfo;
 	}
 
 	type AccountOf<T> = <T as frame_system::Config>::AccountId;
@@ -79,6 +207,54 @@ pub mod pallet {
 		fn get_credibility(account_id: &AccountId) -> Result<Credibility, DispatchError>;
 		fn increase_credibility(account_id: &AccountId, amount: Credibility) -> DispatchResult;
 		fn decrease_credibility(account_id: &AccountId, amount: Credibility) -> DispatchResult;
+		/// The value of the verified data field with the name, `None` if the identity has no such
+		/// field or the field is not verified.
+		fn get_verified_field(account_id: &AccountId, name: &[u8]) -> Option<Vec<u8>>;
+		fn is_field_verified(account_id: &AccountId, name: &[u8]) -> bool;
+		/// The preferred languages declared by the identity owner.
+		fn get_languages(account_id: &AccountId) -> Vec<LanguageCode>;
+		/// The organization identity the account is a confirmed member of.
+		fn get_organization(account_id: &AccountId) -> Option<AccountId>;
+		/// Whether the account has an organization identity.
+		fn is_organization(account_id: &AccountId) -> bool;
+		/// Whether the reviewer has reviewed the identity of the account.
+		fn is_reviewer(account_id: &AccountId, reviewer: &AccountId) -> bool;
+		/// Whether the review of the reviewer about the account is published.
+		fn has_published_review(account_id: &AccountId, reviewer: &AccountId) -> bool;
+		/// Flag the review of the reviewer about the account as removed by a review dispute.
+		fn remove_contested_review(account_id: &AccountId, reviewer: &AccountId) -> DispatchResult;
+		/// The current encryption public key of the identity, if it has registered one.
+		fn get_encryption_key(account_id: &AccountId) -> Option<Vec<u8>>;
+		/// The attesters and the types of the attestations about the account.
+		fn get_attestations(account_id: &AccountId) -> Vec<(AccountId, AttestationType)>;
+		/// Whether the attester has attested any relationship with the account.
+		fn is_attested_by(account_id: &AccountId, attester: &AccountId) -> bool;
+		/// The unexpired handle of the account.
+		fn get_handle(account_id: &AccountId) -> Option<Vec<u8>>;
+		/// Count a dispute opened against the account.
+		fn note_dispute_opened(account_id: &AccountId);
+		/// Uncount a dispute withdrawn before any resolver is drawn.
+		fn note_dispute_withdrawn(account_id: &AccountId);
+		/// Count a resolved dispute as won by the winner and lost by the loser.
+		fn note_dispute_resolved(winner: &AccountId, loser: &AccountId);
+		/// Move a resolved dispute overturned by a challenge from the record of the previous
+		/// winner to the record of the new winner.
+		fn note_dispute_overturned(winner: &AccountId, loser: &AccountId);
+		/// The dispute track record of the account.
+		fn get_dispute_record(account_id: &AccountId) -> DisputeRecord;
+	}
+
+	/// The resolvers network as seen by the identities, it depends on the identities so it is
+	/// wired by the runtime.
+	pub trait ResolversInspector<AccountId> {
+		/// Whether the account is a resolver of the network that is not terminated.
+		fn is_active_resolver(account_id: &AccountId) -> bool;
+	}
+
+	impl<AccountId> ResolversInspector<AccountId> for () {
+		fn is_active_resolver(_account_id: &AccountId) -> bool {
+			false
+		}
 	}
 
 	#[derive(Clone, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo)]
@@ -88,6 +264,16 @@ pub mod pallet {
 		Email,
 		Evaluator,
 		None,
+		/// The value is an external account proven by a signature of the account owner.
+		ExternalSignature,
+	}
+
+	/// A signature of the DID of the identity by the key of an external account.
+	#[derive(Clone, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo)]
+	#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+	pub enum ExternalAccountProof {
+		/// An Ethereum `personal_sign` signature, `r`, `s` and `v`.
+		Ethereum([u8; 65]),
 	}
 
 	#[derive(Clone, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo)]
@@ -105,6 +291,15 @@ pub mod pallet {
 		pub verify_method: VerifyMethod,
 	}
 
+	#[derive(Clone, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo)]
+	#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+	pub struct FieldSchema {
+		/// The verify method of the data fields with the schema name.
+		pub verify_method: VerifyMethod,
+		/// How the wallets should format the value, e.g. `date:YYYY-MM-DD`.
+		pub format_hint: Vec<u8>,
+	}
+
 	#[derive(Clone, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo)]
 	#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
 	#[scale_info(skip_type_params(T))]
@@ -134,6 +329,16 @@ pub mod pallet {
 	pub struct IdentityReview<T: Config> {
 		pub reviewer: AccountOf<T>,
 		pub content_digest: T::Hash,
+		pub status: ReviewStatus,
+	}
+
+	#[derive(Clone, Copy, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo)]
+	#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+	pub enum ReviewStatus {
+		Published,
+		/// The review is removed by the resolver of a review dispute, it is kept for the record
+		/// but not counted or listed anymore.
+		ContestedRemoved,
 	}
 
 	#[derive(Clone, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo)]
@@ -143,7 +348,6 @@ pub mod pallet {
 		pub identity_type: IdentityType,
 		pub credibility: Credibility,
 		pub data: Vec<IdentityField<T>>,
-		pub reviews: Vec<IdentityReview<T>>,
 	}
 
 	#[derive(Clone, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo)]
@@ -152,9 +356,155 @@ pub mod pallet {
 		pub name: Vec<u8>,
 		pub about: Vec<u8>,
 		pub rate: BalanceOf<T>,
+		/// The number of reviews of the completed verify data requests.
+		pub review_count: u32,
+		/// The sum of the stars of the reviews.
+		pub total_stars: u32,
+	}
+
+	#[derive(Clone, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo)]
+	#[scale_info(skip_type_params(T))]
+	pub struct EvaluatorReview<T: Config> {
+		pub stars: u8,
+		pub content_digest: T::Hash,
+	}
+
+	/// The maximum stars of an evaluator review.
+	pub const MAX_REVIEW_STARS: u8 = 5;
+
+	/// The last rate band of the evaluator directory, it also holds the evaluators with higher
+	/// rates.
+	pub const MAX_RATE_BAND: u32 = 63;
+
+	/// The maximum number of evaluators of a directory page.
+	pub const MAX_DIRECTORY_PAGE: u32 = 100;
+
+	pub type VerifyRequestId = u64;
+
+	#[derive(Clone, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo)]
+	#[scale_info(skip_type_params(T))]
+	pub struct VerifyRequest<T: Config> {
+		pub requestor: AccountOf<T>,
+		pub evaluator: AccountOf<T>,
+		/// The positions waiting for a response of the evaluator.
+		pub positions: Vec<u64>,
+		/// The fee reserved from the requestor for the pending positions, until they are
+		/// verified, cancelled or expired.
+		pub fee: BalanceOf<T>,
+		pub created_at: T::BlockNumber,
+		/// The block the request expires if the evaluator does not respond.
+		pub deadline: T::BlockNumber,
+		pub status: VerifyRequestStatus,
+	}
+
+	#[derive(Clone, Copy, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo)]
+	#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+	pub enum VerifyRequestStatus {
+		/// The request waits for the evaluator, the fee is escrowed.
+		Pending,
+		/// The evaluator responded to all the positions, the fee is paid to the evaluator.
+		Verified,
+		/// The requestor cancelled the request, the fee is refunded.
+		Cancelled,
+		/// The evaluator did not respond in time, the fee is refunded.
+		Expired,
+	}
+
+	#[derive(Clone, Copy, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo)]
+	#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+	pub enum EncryptionKeyType {
+		X25519,
+		Sr25519,
+	}
+
+	/// A public key the others can encrypt the data to the identity owner with, e.g. the payment
+	/// memos and the dispute evidence.
+	#[derive(Clone, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo)]
+	#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+	pub struct EncryptionKey<BlockNumber> {
+		pub key_type: EncryptionKeyType,
+		pub public: [u8; 32],
+		/// The block the key is registered at, the data encrypted before were encrypted to the
+		/// previous keys.
+		pub registered_at: BlockNumber,
+	}
+
+	/// The relationship an identity attests with another identity, the subject of the attestation.
+	#[derive(Clone, Copy, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo)]
+	#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+	pub enum AttestationType {
+		/// The subject is an employee of the attester.
+		Employee,
+		/// The subject is a verified supplier of the attester.
+		Supplier,
+		/// The subject is a customer of the attester.
+		Customer,
+		/// The subject is a business partner of the attester.
+		Partner,
+	}
+
+	#[derive(Clone, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo)]
+	#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+	pub struct Attestation<AccountId, BlockNumber> {
+		pub attester: AccountId,
+		pub attestation_type: AttestationType,
+		/// The block the attestation is issued at.
+		pub attested_at: BlockNumber,
+	}
+
+	/// The minimum length of a handle.
+	pub const MIN_HANDLE_LENGTH: usize = 3;
+
+	pub type HandleOf<T> = BoundedVec<u8, <T as Config>::MaxHandleLength>;
+
+	#[derive(Clone, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo)]
+	#[scale_info(skip_type_params(T))]
+	pub struct HandleRegistration<T: Config> {
+		pub owner: AccountOf<T>,
+		/// The amount reserved from the owner.
+		pub deposit: BalanceOf<T>,
+		/// The block the registration expires at, the handle can be registered by anyone after.
+		pub expires_at: T::BlockNumber,
+	}
+
+	/// The dispute track record of an account, the payers can check the record of a merchant
+	/// before paying it.
+	#[derive(Clone, Encode, Decode, Default, PartialEq, RuntimeDebug, TypeInfo)]
+	#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+	pub struct DisputeRecord {
+		/// The number of disputes opened against the account.
+		pub opened: u32,
+		/// The number of resolved disputes the account won.
+		pub won: u32,
+		/// The number of resolved disputes the account lost.
+		pub lost: u32,
 	}
 
+	impl DisputeRecord {
+		/// The share of the resolved disputes the account lost.
+		pub fn loss_rate(&self) -> Permill {
+			let resolved = self.won.saturating_add(self.lost);
+			if resolved.is_zero() {
+				return Permill::zero()
+			}
+			Permill::from_rational(self.lost, resolved)
+		}
+
+		/// The share of the disputes opened against the account that were refunded to the other
+		/// party.
+		pub fn refund_rate(&self) -> Permill {
+			if self.opened.is_zero() {
+				return Permill::zero()
+			}
+			Permill::from_rational(self.lost.min(self.opened), self.opened)
+		}
+	}
+
+	/// The current storage version, see [`crate::migrations`].
+	pub(crate) const STORAGE_VERSION: StorageVersion = StorageVersion::new(7);
+
 	#[pallet::pallet]
+	#[pallet::storage_version(STORAGE_VERSION)]
 	#[pallet::without_storage_info]
 	pub struct Pallet<T>(_);
 
@@ -162,28 +512,264 @@ pub mod pallet {
 	pub(super) type VerifyDomainRequests<T: Config> =
 		StorageValue<_, Vec<(Vec<u8>, AccountOf<T>)>, ValueQuery>;
 
+	/// The id of the next verify data request.
 	#[pallet::storage]
-	#[pallet::getter(fn verify_data_requests)]
-	pub(super) type VerifyDataRequests<T: Config> =
-		StorageMap<_, Twox64Concat, AccountOf<T>, Vec<(AccountOf<T>, Vec<u64>)>>;
+	pub(super) type NextVerifyRequestId<T: Config> = StorageValue<_, VerifyRequestId, ValueQuery>;
+
+	/// The verify data requests by id. The requests are kept with their final status once they
+	/// are verified, cancelled or expired.
+	#[pallet::storage]
+	#[pallet::getter(fn verify_requests)]
+	pub(super) type VerifyRequests<T: Config> =
+		StorageMap<_, Twox64Concat, VerifyRequestId, VerifyRequest<T>>;
+
+	/// The ids of the verify data requests that expire at a block.
+	#[pallet::storage]
+	pub(super) type VerifyRequestDeadlines<T: Config> =
+		StorageMap<_, Twox64Concat, T::BlockNumber, Vec<VerifyRequestId>, ValueQuery>;
 
 	#[pallet::storage]
 	#[pallet::getter(fn identities)]
 	pub(super) type Identities<T: Config> = StorageMap<_, Twox64Concat, AccountOf<T>, Identity<T>>;
 
+	/// The reviews of the identities by subject and reviewer, an account reviews an identity once.
+	#[pallet::storage]
+	#[pallet::getter(fn identity_reviews)]
+	pub(super) type IdentityReviews<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		AccountOf<T>,
+		Twox64Concat,
+		AccountOf<T>,
+		IdentityReview<T>,
+	>;
+
+	/// The number of published reviews about each identity, the contested removed reviews are not
+	/// counted.
+	#[pallet::storage]
+	#[pallet::getter(fn review_counts)]
+	pub(super) type ReviewCounts<T: Config> =
+		StorageMap<_, Twox64Concat, AccountOf<T>, u32, ValueQuery>;
+
 	#[pallet::storage]
 	#[pallet::getter(fn evaluators)]
 	pub(super) type Evaluators<T: Config> = StorageMap<_, Twox64Concat, AccountOf<T>, Evaluator<T>>;
 
+	/// The requestors who can review an evaluator, one review per completed verify data request.
+	#[pallet::storage]
+	pub(super) type CompletedVerifyRequests<T: Config> =
+		StorageDoubleMap<_, Twox64Concat, AccountOf<T>, Twox64Concat, AccountOf<T>, ()>;
+
+	/// The latest review of a requestor about an evaluator.
+	#[pallet::storage]
+	#[pallet::getter(fn evaluator_reviews)]
+	pub(super) type EvaluatorReviews<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		AccountOf<T>,
+		Twox64Concat,
+		AccountOf<T>,
+		EvaluatorReview<T>,
+	>;
+
+	/// The prices an evaluator charges to verify a data field, keyed by the hash of the field name.
+	#[pallet::storage]
+	#[pallet::getter(fn field_prices)]
+	pub(super) type FieldPrices<T: Config> = StorageMap<
+		_,
+		Twox64Concat,
+		AccountOf<T>,
+		BoundedVec<(T::Hash, BalanceOf<T>), T::MaxFieldPrices>,
+		ValueQuery,
+	>;
+
+	/// The evaluators who publish a price for a field, keyed by the hash of the field name, with
+	/// the published price.
+	#[pallet::storage]
+	pub(super) type EvaluatorsByField<T: Config> =
+		StorageDoubleMap<_, Twox64Concat, T::Hash, Twox64Concat, AccountOf<T>, BalanceOf<T>>;
+
+	/// The evaluators by the band of their rate.
+	#[pallet::storage]
+	pub(super) type EvaluatorsByRateBand<T: Config> =
+		StorageDoubleMap<_, Twox64Concat, u32, Twox64Concat, AccountOf<T>, ()>;
+
+	/// The field schemas registered by the governance, keyed by the field name.
+	#[pallet::storage]
+	#[pallet::getter(fn field_schemas)]
+	pub(super) type FieldSchemas<T: Config> = StorageMap<_, Blake2_128Concat, Vec<u8>, FieldSchema>;
+
+	/// The preferred languages of the identity owners.
+	#[pallet::storage]
+	#[pallet::getter(fn languages)]
+	pub(super) type Languages<T: Config> = StorageMap<
+		_,
+		Twox64Concat,
+		AccountOf<T>,
+		BoundedVec<LanguageCode, T::MaxLanguages>,
+		ValueQuery,
+	>;
+
+	/// The pending invitations of the organizations by member and organization.
+	#[pallet::storage]
+	#[pallet::getter(fn membership_invitations)]
+	pub(super) type MembershipInvitations<T: Config> =
+		StorageDoubleMap<_, Twox64Concat, AccountOf<T>, Twox64Concat, AccountOf<T>, ()>;
+
+	/// The organization identity of the members, confirmed by both the organization and the
+	/// member.
+	#[pallet::storage]
+	#[pallet::getter(fn organizations)]
+	pub(super) type Organizations<T: Config> =
+		StorageMap<_, Twox64Concat, AccountOf<T>, AccountOf<T>>;
+
+	/// The encryption keys of the identities, from the oldest to the current one.
+	#[pallet::storage]
+	#[pallet::getter(fn encryption_keys)]
+	pub(super) type EncryptionKeys<T: Config> = StorageMap<
+		_,
+		Twox64Concat,
+		AccountOf<T>,
+		BoundedVec<EncryptionKey<T::BlockNumber>, T::MaxEncryptionKeys>,
+		ValueQuery,
+	>;
+
+	/// The attestations about the identities, from the oldest to the latest one.
+	#[pallet::storage]
+	#[pallet::getter(fn attestations)]
+	pub(super) type Attestations<T: Config> = StorageMap<
+		_,
+		Twox64Concat,
+		AccountOf<T>,
+		BoundedVec<Attestation<AccountOf<T>, T::BlockNumber>, T::MaxAttestations>,
+		ValueQuery,
+	>;
+
+	/// The registrations of the handles.
+	#[pallet::storage]
+	#[pallet::getter(fn handles)]
+	pub(super) type Handles<T: Config> =
+		StorageMap<_, Blake2_128Concat, HandleOf<T>, HandleRegistration<T>>;
+
+	/// The handle registered by the accounts, it may be expired.
+	#[pallet::storage]
+	#[pallet::getter(fn account_handles)]
+	pub(super) type AccountHandles<T: Config> =
+		StorageMap<_, Twox64Concat, AccountOf<T>, HandleOf<T>>;
+
+	/// The dispute track records of the accounts, with or without an identity.
+	#[pallet::storage]
+	#[pallet::getter(fn dispute_records)]
+	pub(super) type DisputeRecords<T: Config> =
+		StorageMap<_, Twox64Concat, AccountOf<T>, DisputeRecord, ValueQuery>;
+
+	/// The evaluators suspended by the governance.
+	#[pallet::storage]
+	#[pallet::getter(fn suspended_evaluators)]
+	pub(super) type SuspendedEvaluators<T: Config> =
+		StorageMap<_, Twox64Concat, AccountOf<T>, bool, ValueQuery>;
+
+	/// The funds reserved by the pallet by account, reserve identifier and currency. Only the
+	/// tracked funds are unreserved or slashed, so the reserves of the other pallets are never
+	/// consumed.
+	#[pallet::storage]
+	#[pallet::getter(fn named_reserves)]
+	pub(super) type NamedReserves<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		AccountOf<T>,
+		Twox64Concat,
+		(ReserveIdentifier, CurrencyId<T::Hash>),
+		BalanceOf<T>,
+		ValueQuery,
+	>;
+
+	#[pallet::genesis_config]
+	pub struct GenesisConfig<T: Config> {
+		/// The bootstrap identities with the name, type and data fields.
+		pub identities: Vec<(AccountOf<T>, Vec<u8>, IdentityType, Vec<IdentityFieldInput>)>,
+		/// The bootstrap evaluators with the name, about and rate.
+		pub evaluators: Vec<(AccountOf<T>, Vec<u8>, Vec<u8>, BalanceOf<T>)>,
+	}
+
+	#[cfg(feature = "std")]
+	impl<T: Config> Default for GenesisConfig<T> {
+		fn default() -> Self {
+			Self { identities: Default::default(), evaluators: Default::default() }
+		}
+	}
+
+	#[pallet::genesis_build]
+	impl<T: Config> GenesisBuild<T> for GenesisConfig<T> {
+		fn build(&self) {
+			for (account, name, identity_type, data) in self.identities.iter() {
+				assert!(
+					!<Identities<T>>::contains_key(account),
+					"Duplicate identity in genesis config"
+				);
+				Pallet::<T>::_validate_data(data.clone()).expect("Invalid identity data");
+
+				let identity = Identity::<T> {
+					name: name.clone(),
+					identity_type: identity_type.clone(),
+					credibility: T::InitialCredibility::get(),
+					data: data
+						.iter()
+						.map(|input| IdentityField::from_identity_field_input(input))
+						.collect(),
+				};
+				<Identities<T>>::insert(account, identity);
+			}
+
+			for (account, name, about, rate) in self.evaluators.iter() {
+				assert!(
+					!<Evaluators<T>>::contains_key(account),
+					"Duplicate evaluator in genesis config"
+				);
+				Pallet::<T>::_reserve_named(
+					EVALUATOR_BOND_RESERVE,
+					account,
+					T::EvaluatorBonding::get(),
+				)
+				.expect("Evaluator must have enough balance to bond");
+
+				let evaluator = Evaluator::<T> {
+					name: name.clone(),
+					about: about.clone(),
+					rate: *rate,
+					review_count: 0,
+					total_stars: 0,
+				};
+				<Evaluators<T>>::insert(account, evaluator);
+				Pallet::<T>::_index_evaluator_rate(account, *rate);
+			}
+		}
+	}
+
 	#[pallet::event]
 	#[pallet::generate_deposit(pub(super) fn deposit_event)]
 	pub enum Event<T: Config> {
 		/// The identity is created for the account.
 		IdentityCreated { account_id: AccountOf<T> },
-		/// The identity is updated.
-		IdentityUpdated { account_id: AccountOf<T> },
+		/// The identity is updated, `positions` are the positions of the updated data fields.
+		IdentityUpdated { account_id: AccountOf<T>, positions: Vec<u64> },
 		/// The identity is removed.
 		IdentityRemoved { account_id: AccountOf<T> },
+		/// A data field is added to the identity. The field events of a call are deposited before
+		/// its `IdentityCreated` or `IdentityUpdated` event.
+		FieldAdded { account_id: AccountOf<T>, position: u64, name: Vec<u8> },
+		/// The data field at the position is replaced, it is not verified anymore.
+		FieldUpdated { account_id: AccountOf<T>, position: u64, name: Vec<u8> },
+		/// The data field at the position is removed from the identity.
+		FieldRemoved { account_id: AccountOf<T>, position: u64, name: Vec<u8> },
+		/// The data field at the position is verified by the method, `by` is the evaluator for
+		/// the fields verified by an evaluator.
+		FieldVerified {
+			account_id: AccountOf<T>,
+			position: u64,
+			method: VerifyMethod,
+			by: Option<AccountOf<T>>,
+		},
 		/// The ownership of the domain is verified.
 		DomainVerified { domain: Vec<u8>, owner: AccountOf<T> },
 		/// The evaluator is created.
@@ -193,20 +779,110 @@ pub mod pallet {
 			about: Vec<u8>,
 			rate: BalanceOf<T>,
 		},
+		/// A requestor reviews the evaluator who verified its data.
+		EvaluatorReviewed {
+			evaluator: AccountOf<T>,
+			reviewer: AccountOf<T>,
+			stars: u8,
+			content_digest: T::Hash,
+		},
+		/// The evaluator publishes the prices of the data fields.
+		FieldPricesUpdated { evaluator: AccountOf<T>, prices: Vec<(T::Hash, BalanceOf<T>)> },
 		/// An account requests an evaluator to verify identity data of the account.
 		VerifyDataRequestCreated {
+			request_id: VerifyRequestId,
 			requestor: AccountOf<T>,
 			positions: Vec<u64>,
 			evaluator: AccountOf<T>,
+			fee: BalanceOf<T>,
+		},
+		/// An evaluator verify identity data of an account, `positions` are the positions of the
+		/// valid data fields and `fee` is the part of the fee released to the evaluator.
+		DataVerified {
+			request_id: VerifyRequestId,
+			account: AccountOf<T>,
+			positions: Vec<u64>,
+			evaluator: AccountOf<T>,
+			fee: BalanceOf<T>,
 		},
-		/// An evaluator verify identity data of an account.
-		DataVerified { account: AccountOf<T>, positions: Vec<u64>, evaluator: AccountOf<T> },
+		/// The credibility of the identity is updated.
+		CredibilityUpdated { account_id: AccountOf<T>, credibility: Credibility },
 		/// An account create a review about another account.
 		IdentityReviewAdded {
 			account: AccountOf<T>,
 			reviewer: AccountOf<T>,
 			content_digest: T::Hash,
 		},
+		/// The review is removed by the resolver of a review dispute, it is flagged
+		/// `ContestedRemoved`.
+		IdentityReviewRemoved { account: AccountOf<T>, reviewer: AccountOf<T> },
+		/// The evaluator is suspended by the governance, its bond is frozen.
+		EvaluatorSuspended { account: AccountOf<T> },
+		/// The suspended evaluator is reinstated by the governance.
+		EvaluatorReinstated { account: AccountOf<T> },
+		/// The requestor cancels the pending verify data request, the fee is refunded.
+		VerifyDataRequestCancelled {
+			request_id: VerifyRequestId,
+			requestor: AccountOf<T>,
+			evaluator: AccountOf<T>,
+			positions: Vec<u64>,
+			refund: BalanceOf<T>,
+		},
+		/// The governance registers the schema of a field name.
+		FieldSchemaSet { name: Vec<u8>, verify_method: VerifyMethod, format_hint: Vec<u8> },
+		/// The governance removes the schema of a field name.
+		FieldSchemaRemoved { name: Vec<u8> },
+		/// The identity owner declares the preferred languages.
+		LanguagesUpdated { account_id: AccountOf<T>, languages: Vec<LanguageCode> },
+		/// The evaluator does not respond to the verify data request in time, the fee is refunded
+		/// and `penalty` is slashed from the evaluator bond to the requestor.
+		VerifyDataRequestExpired {
+			request_id: VerifyRequestId,
+			requestor: AccountOf<T>,
+			evaluator: AccountOf<T>,
+			refund: BalanceOf<T>,
+			penalty: BalanceOf<T>,
+		},
+		/// An organization invites an account to be its member.
+		MemberInvited { organization: AccountOf<T>, member: AccountOf<T> },
+		/// The invited account accepts to be a member of the organization.
+		MembershipAccepted { organization: AccountOf<T>, member: AccountOf<T> },
+		/// The member leaves or is removed from the organization.
+		MemberRemoved { organization: AccountOf<T>, member: AccountOf<T> },
+		/// The identity owner registers its first encryption key.
+		EncryptionKeySet { account_id: AccountOf<T>, key_type: EncryptionKeyType, public: [u8; 32] },
+		/// The identity owner replaces its current encryption key, the previous key is kept in the
+		/// history.
+		EncryptionKeyRotated {
+			account_id: AccountOf<T>,
+			key_type: EncryptionKeyType,
+			public: [u8; 32],
+		},
+		/// The attester attests a relationship with the subject.
+		AttestationIssued {
+			attester: AccountOf<T>,
+			subject: AccountOf<T>,
+			attestation_type: AttestationType,
+		},
+		/// The account registers the handle until `expires_at`, the previous registration of the
+		/// handle is expired.
+		HandleRegistered { account_id: AccountOf<T>, handle: Vec<u8>, expires_at: T::BlockNumber },
+		/// The owner renews the registration of the handle until `expires_at`.
+		HandleRenewed { account_id: AccountOf<T>, handle: Vec<u8>, expires_at: T::BlockNumber },
+		/// The owner transfers the handle and its deposit to another identity.
+		HandleTransferred { from: AccountOf<T>, to: AccountOf<T>, handle: Vec<u8> },
+		/// The owner releases the handle, or the expired handle is registered by another account,
+		/// the deposit is unreserved.
+		HandleReleased { account_id: AccountOf<T>, handle: Vec<u8> },
+		/// The identity owner links an external account proven by the signature, `value` is the
+		/// external account.
+		ExternalAccountLinked { account_id: AccountOf<T>, name: Vec<u8>, value: Vec<u8> },
+		/// The attester revokes its attestation about the subject.
+		AttestationRevoked {
+			attester: AccountOf<T>,
+			subject: AccountOf<T>,
+			attestation_type: AttestationType,
+		},
 	}
 
 	#[pallet::error]
@@ -219,20 +895,107 @@ pub mod pallet {
 		EvaluatorExisted,
 		/// The account is not an evaluator.
 		EvaluatorNotFound,
-		/// The account does not have appropriate access rights.
-		AccessDenied,
 		/// The submitted data is not a valid domain.
 		InvalidDomain,
 		/// The submitted data is not a valid email.
 		InvalidEmail,
 		/// There is no any data field matched with the condition.
 		DataFieldNotFound,
-		/// The evaluator is not requested to verify the identity.
+		/// There is no verify data request with the id.
 		VerifyRequestNotFound,
-		/// The transcript is not matched with the verify data request.
+		/// The verify data request is already verified, cancelled or expired.
+		VerifyRequestNotPending,
+		/// The transcript is empty, repeats a position or has a position that is not pending in
+		/// the verify data request.
 		InvalidTranscript,
 		/// An account only can review other account once.
 		CanOnlyReviewOnce,
+		/// The account has no published review from the reviewer.
+		ReviewNotFound,
+		/// The evaluator is suspended.
+		EvaluatorSuspended,
+		/// The evaluator is not suspended.
+		EvaluatorNotSuspended,
+		/// The evaluator publishes more field prices than `MaxFieldPrices`.
+		TooManyFieldPrices,
+		/// An identity already has a data field with the name.
+		DuplicateFieldName,
+		/// The verify method of the data field does not match the registered field schema.
+		FieldSchemaMismatch,
+		/// There is no registered schema of the field name.
+		FieldSchemaNotFound,
+		/// The stars of a review are not from 1 to `MAX_REVIEW_STARS`.
+		InvalidReviewStars,
+		/// The account has no completed verify data request to review.
+		NoCompletedVerifyRequest,
+		/// The identity owner declares more languages than `MaxLanguages`.
+		TooManyLanguages,
+		/// Only an organization identity can invite members.
+		NotAnOrganization,
+		/// The organization did not invite the account.
+		MembershipInvitationNotFound,
+		/// The account is not a member of an organization.
+		NotAMember,
+		/// The identity already has an encryption key, it needs to be rotated.
+		EncryptionKeyExisted,
+		/// The identity has no encryption key to rotate.
+		EncryptionKeyNotFound,
+		/// The new encryption key is the current one.
+		SameEncryptionKey,
+		/// No encryption key fits in the history, `MaxEncryptionKeys` is zero.
+		TooManyEncryptionKeys,
+		/// An identity cannot attest about itself.
+		CannotAttestSelf,
+		/// The attester already issued an attestation of the type about the subject.
+		AttestationExisted,
+		/// There is no attestation of the type issued by the attester about the subject.
+		AttestationNotFound,
+		/// The subject already has `MaxAttestations` attestations.
+		TooManyAttestations,
+		/// The external account cannot be recovered from the signature.
+		InvalidExternalProof,
+		/// The external accounts can only be linked with a proof, not added as plain data.
+		ExternalProofRequired,
+		/// The handle is shorter than `MIN_HANDLE_LENGTH`, longer than `MaxHandleLength` or has
+		/// other characters than lowercase letters, digits, `_` and `-`.
+		InvalidHandle,
+		/// The handle is registered by another account and not expired.
+		HandleTaken,
+		/// The account already owns a handle.
+		HandleExisted,
+		/// The account has no handle.
+		HandleNotFound,
+		/// The handle is expired, it needs to be renewed.
+		HandleExpired,
+		/// The identity of an evaluator cannot be removed.
+		EvaluatorIdentityInUse,
+		/// The identity of an active resolver cannot be removed.
+		ResolverIdentityInUse,
+		/// Only the requestor can cancel the verify request.
+		NotRequestor,
+		/// Only the evaluator of the verify request can verify the data.
+		NotRequestEvaluator,
+		/// Only the member or its organization can end the membership.
+		NotMemberOrOrganization,
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_initialize(block_number: T::BlockNumber) -> Weight {
+			let expired = <VerifyRequestDeadlines<T>>::take(block_number);
+
+			for request_id in expired.iter() {
+				Self::_expire_verify_data_request(*request_id);
+			}
+
+			T::DbWeight::get()
+				.reads_writes(1 + 3 * expired.len() as Weight, 1 + 3 * expired.len() as Weight)
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade() -> Result<(), &'static str> {
+			Self::do_try_state()
+		}
 	}
 
 	// #[pallet::hooks]
@@ -257,7 +1020,7 @@ pub mod pallet {
 
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {
-		#[pallet::weight(1_000)]
+		#[pallet::weight(T::WeightInfo::create_identity(data.len() as u32))]
 		pub fn create_identity(
 			origin: OriginFor<T>,
 			name: Vec<u8>,
@@ -269,55 +1032,176 @@ pub mod pallet {
 			Ok(())
 		}
 
-		#[pallet::weight(1_000)]
+		#[pallet::weight(
+			T::WeightInfo::update_identity(data.as_ref().map_or(0, |data| data.len() as u32))
+		)]
 		pub fn update_identity(
 			origin: OriginFor<T>,
 			name: Option<Vec<u8>>,
 			data: Option<Vec<IdentityFieldInput>>,
 		) -> DispatchResult {
 			let requestor = ensure_signed(origin)?;
+			let positions =
+				data.as_ref().map_or([].to_vec(), |data| (0..data.len() as u64).collect());
 			Self::_update_identity(requestor.clone(), name, data)?;
-			Self::deposit_event(Event::IdentityUpdated { account_id: requestor });
+			Self::deposit_event(Event::IdentityUpdated { account_id: requestor, positions });
 			Ok(())
 		}
 
-		#[pallet::weight(1_000)]
+		// Replace the data field at the position. The updated field is not verified anymore, even
+		// if it was verified before, and must be requested to verify again.
+		#[pallet::weight(T::WeightInfo::update_identity_data())]
 		pub fn update_identity_data(
 			origin: OriginFor<T>,
 			position: u64,
 			data_field: IdentityFieldInput,
 		) -> DispatchResult {
 			let requestor = ensure_signed(origin)?;
-			Self::_update_identity_data_field(
-				requestor.clone(),
-				position.try_into().unwrap(),
-				data_field,
-			)?;
-			Self::deposit_event(Event::IdentityUpdated { account_id: requestor });
+			Self::_update_identity_data_field(requestor.clone(), position, data_field)?;
+			Self::deposit_event(Event::IdentityUpdated {
+				account_id: requestor,
+				positions: [position].to_vec(),
+			});
 			Ok(())
 		}
 
-		#[pallet::weight(1_000)]
+		#[pallet::weight(T::WeightInfo::add_identity_data())]
 		pub fn add_identity_data(
 			origin: OriginFor<T>,
 			data_field: IdentityFieldInput,
 		) -> DispatchResult {
 			let requestor = ensure_signed(origin)?;
-			Self::_add_identity_data_field(requestor.clone(), data_field)?;
-			Self::deposit_event(Event::IdentityUpdated { account_id: requestor });
+			let position = Self::_add_identity_data_field(requestor.clone(), data_field)?;
+			Self::deposit_event(Event::IdentityUpdated {
+				account_id: requestor,
+				positions: [position as u64].to_vec(),
+			});
+			Ok(())
+		}
+
+		#[pallet::weight(T::WeightInfo::link_external_account())]
+		pub fn link_external_account(
+			origin: OriginFor<T>,
+			name: Vec<u8>,
+			proof: ExternalAccountProof,
+		) -> DispatchResult {
+			let account_id = ensure_signed(origin)?;
+			Self::_link_external_account(account_id, name, proof)?;
 			Ok(())
 		}
 
-		#[pallet::weight(1_000)]
+		#[pallet::weight(T::WeightInfo::remove_identity())]
 		pub fn remove_identity(origin: OriginFor<T>) -> DispatchResult {
 			let requestor = ensure_signed(origin)?;
-			// Only remove name and identity data
-			Self::_update_identity(requestor.clone(), Some("".into()), Some([].into()))?;
-			Self::deposit_event(Event::IdentityRemoved { account_id: requestor });
+			Self::_remove_identity(requestor)?;
+			Ok(())
+		}
+
+		#[pallet::weight(T::WeightInfo::set_languages(languages.len() as u32))]
+		pub fn set_languages(origin: OriginFor<T>, languages: Vec<LanguageCode>) -> DispatchResult {
+			let account_id = ensure_signed(origin)?;
+			Self::_set_languages(account_id, languages)?;
+			Ok(())
+		}
+
+		#[pallet::weight(T::WeightInfo::invite_member())]
+		pub fn invite_member(origin: OriginFor<T>, member: AccountOf<T>) -> DispatchResult {
+			let organization = ensure_signed(origin)?;
+			Self::_invite_member(organization, member)?;
+			Ok(())
+		}
+
+		#[pallet::weight(T::WeightInfo::accept_membership())]
+		pub fn accept_membership(
+			origin: OriginFor<T>,
+			organization: AccountOf<T>,
+		) -> DispatchResult {
+			let member = ensure_signed(origin)?;
+			Self::_accept_membership(member, organization)?;
+			Ok(())
+		}
+
+		#[pallet::weight(T::WeightInfo::remove_member())]
+		pub fn remove_member(origin: OriginFor<T>, member: AccountOf<T>) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			Self::_remove_member(sender, member)?;
+			Ok(())
+		}
+
+		#[pallet::weight(T::WeightInfo::set_encryption_key())]
+		pub fn set_encryption_key(
+			origin: OriginFor<T>,
+			key_type: EncryptionKeyType,
+			public: [u8; 32],
+		) -> DispatchResult {
+			let account_id = ensure_signed(origin)?;
+			Self::_set_encryption_key(account_id, key_type, public)?;
+			Ok(())
+		}
+
+		#[pallet::weight(T::WeightInfo::rotate_encryption_key())]
+		pub fn rotate_encryption_key(
+			origin: OriginFor<T>,
+			key_type: EncryptionKeyType,
+			public: [u8; 32],
+		) -> DispatchResult {
+			let account_id = ensure_signed(origin)?;
+			Self::_rotate_encryption_key(account_id, key_type, public)?;
+			Ok(())
+		}
+
+		#[pallet::weight(T::WeightInfo::attest())]
+		pub fn attest(
+			origin: OriginFor<T>,
+			subject: AccountOf<T>,
+			attestation_type: AttestationType,
+		) -> DispatchResult {
+			let attester = ensure_signed(origin)?;
+			Self::_attest(attester, subject, attestation_type)?;
+			Ok(())
+		}
+
+		#[pallet::weight(T::WeightInfo::revoke_attestation())]
+		pub fn revoke_attestation(
+			origin: OriginFor<T>,
+			subject: AccountOf<T>,
+			attestation_type: AttestationType,
+		) -> DispatchResult {
+			let attester = ensure_signed(origin)?;
+			Self::_revoke_attestation(attester, subject, attestation_type)?;
+			Ok(())
+		}
+
+		#[pallet::weight(T::WeightInfo::register_handle())]
+		pub fn register_handle(origin: OriginFor<T>, handle: Vec<u8>) -> DispatchResult {
+			let account_id = ensure_signed(origin)?;
+			Self::_register_handle(account_id, handle)?;
+			Ok(())
+		}
+
+		#[pallet::weight(T::WeightInfo::renew_handle())]
+		pub fn renew_handle(origin: OriginFor<T>) -> DispatchResult {
+			let account_id = ensure_signed(origin)?;
+			Self::_renew_handle(account_id)?;
+			Ok(())
+		}
+
+		#[pallet::weight(T::WeightInfo::transfer_handle())]
+		pub fn transfer_handle(origin: OriginFor<T>, to: AccountOf<T>) -> DispatchResult {
+			let account_id = ensure_signed(origin)?;
+			Self::_transfer_handle(account_id, to)?;
+			Ok(())
+		}
+
+		#[pallet::weight(T::WeightInfo::release_handle())]
+		pub fn release_handle(origin: OriginFor<T>) -> DispatchResult {
+			let account_id = ensure_signed(origin)?;
+			let handle = <AccountHandles<T>>::get(&account_id).ok_or(<Error<T>>::HandleNotFound)?;
+			Self::_release_handle(&account_id, handle);
 			Ok(())
 		}
 
-		#[pallet::weight(2_000)]
+		#[pallet::weight(T::WeightInfo::review_identity())]
 		pub fn review_identity(
 			origin: OriginFor<T>,
 			account: AccountOf<T>,
@@ -328,8 +1212,20 @@ pub mod pallet {
 			Ok(())
 		}
 
+		#[pallet::weight(T::WeightInfo::review_evaluator())]
+		pub fn review_evaluator(
+			origin: OriginFor<T>,
+			evaluator: AccountOf<T>,
+			stars: u8,
+			content: Vec<u8>,
+		) -> DispatchResult {
+			let reviewer = ensure_signed(origin)?;
+			Self::_add_evaluator_review(evaluator, reviewer, stars, content)?;
+			Ok(())
+		}
+
 		// Request evaluator to review identity data.
-		#[pallet::weight(1000)]
+		#[pallet::weight(T::WeightInfo::create_evaluator())]
 		pub fn create_evaluator(
 			origin: OriginFor<T>,
 			name: Vec<u8>,
@@ -341,8 +1237,19 @@ pub mod pallet {
 			Ok(())
 		}
 
+		// The previously published prices are unindexed, up to `MaxFieldPrices` of them.
+		#[pallet::weight(T::WeightInfo::set_field_prices(T::MaxFieldPrices::get()))]
+		pub fn set_field_prices(
+			origin: OriginFor<T>,
+			prices: Vec<(T::Hash, BalanceOf<T>)>,
+		) -> DispatchResult {
+			let evaluator = ensure_signed(origin)?;
+			Self::_set_field_prices(evaluator, prices)?;
+			Ok(())
+		}
+
 		// Request evaluator to review identity data.
-		#[pallet::weight(1000)]
+		#[pallet::weight(T::WeightInfo::request_to_verify(positions.len() as u32))]
 		pub fn request_to_verify(
 			origin: OriginFor<T>,
 			positions: Vec<u64>,
@@ -353,40 +1260,222 @@ pub mod pallet {
 			Ok(())
 		}
 
-		// Verify data for customer.
-		#[pallet::weight(1000)]
+		#[pallet::weight(T::WeightInfo::cancel_verify_request())]
+		pub fn cancel_verify_request(
+			origin: OriginFor<T>,
+			request_id: VerifyRequestId,
+		) -> DispatchResult {
+			let requestor = ensure_signed(origin)?;
+			Self::_cancel_verify_data_request(requestor, request_id)?;
+			Ok(())
+		}
+
+		// Verify data for customer, the evaluator may respond to a part of the pending positions.
+		#[pallet::weight(T::WeightInfo::verify_data(transcript.len() as u32))]
 		pub fn verify_data(
 			origin: OriginFor<T>,
-			account: AccountOf<T>,
+			request_id: VerifyRequestId,
 			transcript: Vec<(u64, bool)>,
 		) -> DispatchResult {
 			let evaluator = ensure_signed(origin)?;
-			Self::_verify_data(evaluator, account, transcript)?;
+			Self::_verify_data(evaluator, request_id, transcript)?;
 			Ok(())
 		}
-	}
 
-	impl<T: Config> Pallet<T> {
-		fn _create_identity(
-			requestor: AccountOf<T>,
+		#[pallet::weight(T::WeightInfo::suspend_evaluator())]
+		pub fn suspend_evaluator(origin: OriginFor<T>, evaluator: AccountOf<T>) -> DispatchResult {
+			T::GovernanceOrigin::ensure_origin(origin)?;
+			Self::_suspend_evaluator(evaluator)?;
+			Ok(())
+		}
+
+		#[pallet::weight(T::WeightInfo::set_field_schema())]
+		pub fn set_field_schema(
+			origin: OriginFor<T>,
 			name: Vec<u8>,
-			identity_type: IdentityType,
-			data: Vec<IdentityFieldInput>,
+			verify_method: VerifyMethod,
+			format_hint: Vec<u8>,
 		) -> DispatchResult {
-			ensure!(!<Identities<T>>::contains_key(&requestor), <Error<T>>::IdentityExisted);
-			Self::_validate_data(data.clone())?;
+			T::GovernanceOrigin::ensure_origin(origin)?;
+			Self::_set_field_schema(name, verify_method, format_hint)?;
+			Ok(())
+		}
 
-			let identity = Identity {
-				name,
-				identity_type,
-				credibility: T::InitialCredibility::get(),
-				data: data
-					.iter()
-					.map(|input| IdentityField::from_identity_field_input(input))
-					.collect(),
-				reviews: [].to_vec(),
-			};
+		#[pallet::weight(T::WeightInfo::remove_field_schema())]
+		pub fn remove_field_schema(origin: OriginFor<T>, name: Vec<u8>) -> DispatchResult {
+			T::GovernanceOrigin::ensure_origin(origin)?;
+			Self::_remove_field_schema(name)?;
+			Ok(())
+		}
 
+		#[pallet::weight(T::WeightInfo::reinstate_evaluator())]
+		pub fn reinstate_evaluator(
+			origin: OriginFor<T>,
+			evaluator: AccountOf<T>,
+		) -> DispatchResult {
+			T::GovernanceOrigin::ensure_origin(origin)?;
+			Self::_reinstate_evaluator(evaluator)?;
+			Ok(())
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		/// A page of at most `limit` published reviews about the identity, skipping the first
+		/// `offset` reviews. The order is stable while no review is added, `review_counts` gives
+		/// the total.
+		pub fn identity_reviews_page(
+			account_id: &AccountOf<T>,
+			offset: u32,
+			limit: u32,
+		) -> Vec<IdentityReview<T>> {
+			<IdentityReviews<T>>::iter_prefix_values(account_id)
+				.filter(|review| review.status == ReviewStatus::Published)
+				.skip(offset as usize)
+				.take(limit as usize)
+				.collect()
+		}
+
+		/// A page of the active evaluators matching the filter, at most `MAX_DIRECTORY_PAGE`. The
+		/// evaluators are read through the field index if the filter has a field, or else through
+		/// the rate bands covering the price range.
+		pub fn list_evaluators(
+			filter: EvaluatorFilter<T::Hash, BalanceOf<T>>,
+			pagination: Pagination,
+		) -> Vec<EvaluatorListing<AccountOf<T>, BalanceOf<T>>> {
+			let candidates: Vec<(AccountOf<T>, BalanceOf<T>)> = match filter.field {
+				Some(field) => <EvaluatorsByField<T>>::iter_prefix(field).collect(),
+				None => {
+					let lowest = filter.min_price.map_or(0, Self::_rate_band);
+					let highest = filter.max_price.map_or(MAX_RATE_BAND, Self::_rate_band);
+					(lowest..=highest)
+						.flat_map(|band| <EvaluatorsByRateBand<T>>::iter_key_prefix(band))
+						.filter_map(|account| {
+							Self::evaluators(&account).map(|evaluator| (account, evaluator.rate))
+						})
+						.collect()
+				},
+			};
+
+			candidates
+				.into_iter()
+				.filter(|(_, price)| {
+					filter.min_price.map_or(true, |min| *price >= min) &&
+						filter.max_price.map_or(true, |max| *price <= max)
+				})
+				.filter(|(account, _)| !Self::suspended_evaluators(account))
+				.filter_map(|(account, price)| {
+					let evaluator = Self::evaluators(&account)?;
+					Some(EvaluatorListing {
+						account,
+						name: evaluator.name,
+						about: evaluator.about,
+						price,
+						review_count: evaluator.review_count,
+						total_stars: evaluator.total_stars,
+					})
+				})
+				.skip(pagination.offset as usize)
+				.take(pagination.limit.min(MAX_DIRECTORY_PAGE) as usize)
+				.collect()
+		}
+
+		/// The W3C DID document of the identity, `None` if the account has no identity.
+		pub fn did_document(account_id: &AccountOf<T>) -> Option<DidDocument> {
+			let identity = Self::identities(account_id)?;
+			let id = did::did_of(account_id);
+
+			let keys = Self::encryption_keys(account_id);
+			let verification_method: Vec<_> = keys
+				.iter()
+				.enumerate()
+				.map(|(index, key)| did::verification_method(&id, index, key))
+				.collect();
+			let key_agreement =
+				verification_method.last().map(|method| method.id.clone()).into_iter().collect();
+			let service = identity
+				.data
+				.iter()
+				.enumerate()
+				.filter(|(_, field)| field.is_verified)
+				.filter_map(|(index, field)| {
+					did::service(&id, index, &field.verify_method, &field.value)
+				})
+				.collect();
+
+			Some(DidDocument {
+				controller: Self::organizations(account_id)
+					.map(|organization| did::did_of(&organization)),
+				id,
+				name: identity.name,
+				verification_method,
+				key_agreement,
+				service,
+			})
+		}
+
+		/// Check the accounting invariants of the identities, run by `try-runtime` after the
+		/// runtime upgrades:
+		/// - The fees of the pending verify data requests are covered by the verify fee reserves
+		///   of the requestors.
+		/// - The deposits of the registered handles are covered by the handle deposit reserves of
+		///   the owners.
+		/// - The tracked reserves of an account are covered by its reserved balance.
+		#[cfg(any(feature = "try-runtime", test))]
+		pub fn do_try_state() -> Result<(), &'static str> {
+			use frame_support::sp_std::collections::btree_map::BTreeMap;
+
+			let mut escrowed: BTreeMap<(ReserveIdentifier, AccountOf<T>), BalanceOf<T>> =
+				BTreeMap::new();
+			let mut escrow = |id: ReserveIdentifier, who: AccountOf<T>, amount: BalanceOf<T>| {
+				let total = escrowed.entry((id, who)).or_insert_with(Zero::zero);
+				*total += amount;
+			};
+			for (_, request) in <VerifyRequests<T>>::iter() {
+				if request.status == VerifyRequestStatus::Pending {
+					escrow(VERIFY_FEE_RESERVE, request.requestor, request.fee);
+				}
+			}
+			for (_, registration) in <Handles<T>>::iter() {
+				escrow(HANDLE_DEPOSIT_RESERVE, registration.owner, registration.deposit);
+			}
+
+			for ((id, who), amount) in escrowed {
+				ensure!(
+					amount <= Self::named_reserves(&who, (id, CurrencyId::Native)),
+					"The funds escrowed for the identities exceed the reserves of an account"
+				);
+			}
+
+			for (who, (_, currency_id), amount) in <NamedReserves<T>>::iter() {
+				ensure!(
+					amount <= T::Currency::reserved_balance(currency_id, &who),
+					"The tracked reserves of an account exceed its reserved balance"
+				);
+			}
+
+			Ok(())
+		}
+
+		fn _create_identity(
+			requestor: AccountOf<T>,
+			name: Vec<u8>,
+			identity_type: IdentityType,
+			data: Vec<IdentityFieldInput>,
+		) -> DispatchResult {
+			ensure!(!<Identities<T>>::contains_key(&requestor), <Error<T>>::IdentityExisted);
+			Self::_validate_data(data.clone())?;
+
+			let identity = Identity {
+				name,
+				identity_type,
+				credibility: T::InitialCredibility::get(),
+				data: data
+					.iter()
+					.map(|input| IdentityField::from_identity_field_input(input))
+					.collect(),
+			};
+
+			Self::_deposit_field_changes(&requestor, &[], &identity.data);
 			<Identities<T>>::insert(&requestor, identity);
 			Self::deposit_event(Event::IdentityCreated { account_id: requestor });
 
@@ -407,10 +1496,12 @@ pub mod pallet {
 
 			if let Some(data) = data {
 				Self::_validate_data(data.clone())?;
-				identity.data = data
+				let data: Vec<IdentityField<T>> = data
 					.iter()
 					.map(|input| IdentityField::from_identity_field_input(input))
 					.collect();
+				Self::_deposit_field_changes(&requestor, &identity.data, &data);
+				identity.data = data;
 			}
 
 			<Identities<T>>::insert(&requestor, identity);
@@ -418,27 +1509,156 @@ pub mod pallet {
 			Ok(())
 		}
 
+		// The reviews, the review counts, the dispute record and the encryption keys of the
+		// account are kept, they are still referred to by the other accounts and pallets.
+		fn _remove_identity(account_id: AccountOf<T>) -> DispatchResult {
+			ensure!(<Identities<T>>::contains_key(&account_id), <Error<T>>::IdentityNotFound);
+			ensure!(
+				!<Evaluators<T>>::contains_key(&account_id),
+				<Error<T>>::EvaluatorIdentityInUse
+			);
+			ensure!(
+				!T::Resolvers::is_active_resolver(&account_id),
+				<Error<T>>::ResolverIdentityInUse
+			);
+
+			if let Some(handle) = Self::account_handles(&account_id) {
+				Self::_release_handle(&account_id, handle);
+			}
+			<Identities<T>>::remove(&account_id);
+			<Languages<T>>::remove(&account_id);
+			<Attestations<T>>::remove(&account_id);
+			<Organizations<T>>::remove(&account_id);
+			Self::deposit_event(Event::IdentityRemoved { account_id });
+
+			Ok(())
+		}
+
 		fn _update_identity_data_field(
 			requestor: AccountOf<T>,
-			position: usize,
+			position: u64,
 			data_field: IdentityFieldInput,
 		) -> DispatchResult {
 			Self::_validate_data_field(data_field.clone())?;
 			let mut identity = Self::identities(&requestor).ok_or(<Error<T>>::IdentityNotFound)?;
-			identity.data[position] = IdentityField::from_identity_field_input(&data_field);
+			let index = Self::_field_index(&identity, position)?;
+			let has_name = identity
+				.data
+				.iter()
+				.enumerate()
+				.any(|(other, field)| other != index && field.name == data_field.name);
+			ensure!(!has_name, <Error<T>>::DuplicateFieldName);
+			// The new field starts unverified, the verification of the old value is reset.
+			identity.data[index] = IdentityField::from_identity_field_input(&data_field);
 			<Identities<T>>::insert(&requestor, identity);
+			Self::deposit_event(Event::FieldUpdated {
+				account_id: requestor,
+				position,
+				name: data_field.name,
+			});
 			Ok(())
 		}
 
+		// The index of the data field at the position, `DataFieldNotFound` if the position is out
+		// of the data of the identity.
+		fn _field_index(identity: &Identity<T>, position: u64) -> Result<usize, DispatchError> {
+			usize::try_from(position)
+				.ok()
+				.filter(|index| *index < identity.data.len())
+				.ok_or_else(|| <Error<T>>::DataFieldNotFound.into())
+		}
+
+		// Deposit the field events of replacing the data fields `old` with `new`, the unchanged
+		// fields are skipped.
+		fn _deposit_field_changes(
+			account_id: &AccountOf<T>,
+			old: &[IdentityField<T>],
+			new: &[IdentityField<T>],
+		) {
+			for (position, field) in new.iter().enumerate() {
+				let (account_id, name) = (account_id.clone(), field.name.clone());
+				let event = match old.get(position) {
+					Some(old_field) if old_field == field => continue,
+					Some(_) => Event::FieldUpdated { account_id, position: position as u64, name },
+					None => Event::FieldAdded { account_id, position: position as u64, name },
+				};
+				Self::deposit_event(event);
+			}
+			for (position, field) in old.iter().enumerate().skip(new.len()) {
+				Self::deposit_event(Event::FieldRemoved {
+					account_id: account_id.clone(),
+					position: position as u64,
+					name: field.name.clone(),
+				});
+			}
+		}
+
+		// Returns the position of the added data field.
 		fn _add_identity_data_field(
 			requestor: AccountOf<T>,
 			data_field: IdentityFieldInput,
-		) -> DispatchResult {
+		) -> Result<usize, DispatchError> {
 			Self::_validate_data_field(data_field.clone())?;
 			let mut identity = Self::identities(&requestor).ok_or(<Error<T>>::IdentityNotFound)?;
-			// TODO: considering not allow data field with the same name.
+			let has_name = identity.data.iter().any(|field| field.name == data_field.name);
+			ensure!(!has_name, <Error<T>>::DuplicateFieldName);
 			identity.data.push(IdentityField::from_identity_field_input(&data_field));
+			let position = identity.data.len() - 1;
 			<Identities<T>>::insert(&requestor, identity);
+			Self::deposit_event(Event::FieldAdded {
+				account_id: requestor,
+				position: position as u64,
+				name: data_field.name,
+			});
+			Ok(position)
+		}
+
+		fn _link_external_account(
+			account_id: AccountOf<T>,
+			name: Vec<u8>,
+			proof: ExternalAccountProof,
+		) -> DispatchResult {
+			let mut identity = Self::identities(&account_id).ok_or(<Error<T>>::IdentityNotFound)?;
+			if let Some(schema) = Self::field_schemas(&name) {
+				ensure!(
+					schema.verify_method == VerifyMethod::ExternalSignature,
+					<Error<T>>::FieldSchemaMismatch
+				);
+			}
+			let has_name = identity.data.iter().any(|field| field.name == name);
+			ensure!(!has_name, <Error<T>>::DuplicateFieldName);
+
+			let message = did::did_of(&account_id);
+			let value = match proof {
+				ExternalAccountProof::Ethereum(signature) => {
+					let address = external::recover_ethereum_address(&message, &signature)
+						.ok_or(<Error<T>>::InvalidExternalProof)?;
+					external::ethereum_address_value(&address)
+				},
+			};
+
+			identity.data.push(IdentityField {
+				name: name.clone(),
+				value: value.clone(),
+				verify_method: VerifyMethod::ExternalSignature,
+				is_verified: true,
+				verify_by: None,
+			});
+			let position = (identity.data.len() - 1) as u64;
+			<Identities<T>>::insert(&account_id, identity);
+			Self::deposit_event(Event::FieldAdded {
+				account_id: account_id.clone(),
+				position,
+				name: name.clone(),
+			});
+			Self::deposit_event(Event::FieldVerified {
+				account_id: account_id.clone(),
+				position,
+				method: VerifyMethod::ExternalSignature,
+				by: None,
+			});
+			Self::deposit_event(Event::ExternalAccountLinked { account_id, name, value });
+
 			Ok(())
 		}
 
@@ -447,18 +1667,19 @@ pub mod pallet {
 			reviewer: AccountOf<T>,
 			content: Vec<u8>,
 		) -> DispatchResult {
-			let mut identity = Self::identities(&account).ok_or(<Error<T>>::IdentityNotFound)?;
-
-			let has_review = identity.reviews.iter().any(|r| r.reviewer == reviewer);
-			ensure!(!has_review, <Error<T>>::CanOnlyReviewOnce);
+			ensure!(<Identities<T>>::contains_key(&account), <Error<T>>::IdentityNotFound);
+			ensure!(
+				!<IdentityReviews<T>>::contains_key(&account, &reviewer),
+				<Error<T>>::CanOnlyReviewOnce
+			);
 
 			let content_digest = T::Hashing::hash_of(&content);
-			offchain_index::set(&content_digest.encode(), &content);
+			offchain_index::set(&indexed_content_key(&content_digest), &content);
 
-			let review = IdentityReview { reviewer, content_digest };
-			identity.reviews.push(review.clone());
-
-			<Identities<T>>::insert(&account, identity);
+			let review =
+				IdentityReview { reviewer, content_digest, status: ReviewStatus::Published };
+			<IdentityReviews<T>>::insert(&account, &review.reviewer, &review);
+			<ReviewCounts<T>>::mutate(&account, |count| *count = count.saturating_add(1));
 
 			Self::deposit_event(Event::IdentityReviewAdded {
 				account,
@@ -468,6 +1689,39 @@ pub mod pallet {
 			Ok(())
 		}
 
+		fn _add_evaluator_review(
+			evaluator: AccountOf<T>,
+			reviewer: AccountOf<T>,
+			stars: u8,
+			content: Vec<u8>,
+		) -> DispatchResult {
+			ensure!((1..=MAX_REVIEW_STARS).contains(&stars), <Error<T>>::InvalidReviewStars);
+			let mut evaluator_data =
+				Self::evaluators(&evaluator).ok_or(<Error<T>>::EvaluatorNotFound)?;
+			<CompletedVerifyRequests<T>>::take(&evaluator, &reviewer)
+				.ok_or(<Error<T>>::NoCompletedVerifyRequest)?;
+
+			let content_digest = T::Hashing::hash_of(&content);
+			offchain_index::set(&indexed_content_key(&content_digest), &content);
+
+			evaluator_data.review_count = evaluator_data.review_count.saturating_add(1);
+			evaluator_data.total_stars = evaluator_data.total_stars.saturating_add(stars.into());
+			<Evaluators<T>>::insert(&evaluator, evaluator_data);
+			<EvaluatorReviews<T>>::insert(
+				&evaluator,
+				&reviewer,
+				EvaluatorReview { stars, content_digest },
+			);
+
+			Self::deposit_event(Event::EvaluatorReviewed {
+				evaluator,
+				reviewer,
+				stars,
+				content_digest,
+			});
+			Ok(())
+		}
+
 		fn _create_evaluator(
 			account: AccountOf<T>,
 			name: Vec<u8>,
@@ -475,9 +1729,10 @@ pub mod pallet {
 			rate: BalanceOf<T>,
 		) -> DispatchResult {
 			ensure!(!<Evaluators<T>>::contains_key(&account), <Error<T>>::EvaluatorExisted);
-			T::Currency::reserve(CurrencyId::Native, &account, T::EvaluatorBonding::get())?;
-			let evaluator = Evaluator::<T> { name, about, rate };
+			Self::_reserve_named(EVALUATOR_BOND_RESERVE, &account, T::EvaluatorBonding::get())?;
+			let evaluator = Evaluator::<T> { name, about, rate, review_count: 0, total_stars: 0 };
 			<Evaluators<T>>::insert(&account, evaluator.clone());
+			Self::_index_evaluator_rate(&account, rate);
 			Self::deposit_event(Event::EvaluatorCreated {
 				account,
 				name: evaluator.name,
@@ -487,6 +1742,69 @@ pub mod pallet {
 			Ok(())
 		}
 
+		fn _set_field_prices(
+			evaluator: AccountOf<T>,
+			prices: Vec<(T::Hash, BalanceOf<T>)>,
+		) -> DispatchResult {
+			ensure!(<Evaluators<T>>::contains_key(&evaluator), <Error<T>>::EvaluatorNotFound);
+			let bounded_prices: BoundedVec<(T::Hash, BalanceOf<T>), T::MaxFieldPrices> =
+				prices.clone().try_into().map_err(|_| <Error<T>>::TooManyFieldPrices)?;
+
+			for (name_hash, _) in Self::field_prices(&evaluator).iter() {
+				<EvaluatorsByField<T>>::remove(name_hash, &evaluator);
+			}
+			for (name_hash, price) in bounded_prices.iter() {
+				<EvaluatorsByField<T>>::insert(name_hash, &evaluator, price);
+			}
+
+			if bounded_prices.is_empty() {
+				<FieldPrices<T>>::remove(&evaluator);
+			} else {
+				<FieldPrices<T>>::insert(&evaluator, bounded_prices);
+			}
+			Self::deposit_event(Event::FieldPricesUpdated { evaluator, prices });
+
+			Ok(())
+		}
+
+		pub(crate) fn _index_evaluator_rate(evaluator: &AccountOf<T>, rate: BalanceOf<T>) {
+			<EvaluatorsByRateBand<T>>::insert(Self::_rate_band(rate), evaluator, ());
+		}
+
+		fn _rate_band(rate: BalanceOf<T>) -> u32 {
+			let width = T::EvaluatorRateBand::get();
+			if width.is_zero() {
+				return 0
+			}
+			let band: u32 = (rate / width).unique_saturated_into();
+			band.min(MAX_RATE_BAND)
+		}
+
+		// The data fields without a published price cost the evaluator rate.
+		fn _compute_verify_cost(
+			requestor: &AccountOf<T>,
+			evaluator_address: &AccountOf<T>,
+			evaluator: &Evaluator<T>,
+			positions: &[u64],
+		) -> Result<BalanceOf<T>, DispatchError> {
+			let identity = Self::identities(requestor).ok_or(<Error<T>>::IdentityNotFound)?;
+			let prices = Self::field_prices(evaluator_address);
+
+			let mut cost: BalanceOf<T> = Zero::zero();
+			for position in positions {
+				let field = &identity.data[Self::_field_index(&identity, *position)?];
+				let name_hash = T::Hashing::hash(&field.name);
+				let price = prices
+					.iter()
+					.find(|(hash, _)| *hash == name_hash)
+					.map(|(_, price)| *price)
+					.unwrap_or(evaluator.rate);
+				cost = cost.saturating_add(price);
+			}
+
+			Ok(cost)
+		}
+
 		fn _create_verify_data_request(
 			requestor: AccountOf<T>,
 			positions: Vec<u64>,
@@ -494,27 +1812,67 @@ pub mod pallet {
 		) -> DispatchResult {
 			let evaluator =
 				Self::evaluators(&evaluator_address).ok_or(<Error<T>>::EvaluatorNotFound)?;
-
-			let cost = evaluator.rate * positions.len().saturated_into::<BalanceOf<T>>();
-
-			T::Currency::transfer(CurrencyId::Native, &requestor, &evaluator_address, cost)?;
-
-			let verify_requests = Self::verify_data_requests(&evaluator_address);
-
-			if let Some(mut verify_requests) = verify_requests {
-				verify_requests.push((requestor.clone(), positions.clone()));
-				<VerifyDataRequests<T>>::insert(&evaluator_address, verify_requests);
-			} else {
-				<VerifyDataRequests<T>>::insert(
-					&evaluator_address,
-					[(requestor.clone(), positions.clone())].to_vec(),
-				);
-			}
+			ensure!(
+				!Self::suspended_evaluators(&evaluator_address),
+				<Error<T>>::EvaluatorSuspended
+			);
+
+			let cost =
+				Self::_compute_verify_cost(&requestor, &evaluator_address, &evaluator, &positions)?;
+
+			Self::_reserve_named(VERIFY_FEE_RESERVE, &requestor, cost)?;
+
+			let request_id = <NextVerifyRequestId<T>>::get();
+			let created_at = <frame_system::Pallet<T>>::block_number();
+			let deadline = created_at + T::VerifyRequestTimeout::get();
+			let request = VerifyRequest {
+				requestor: requestor.clone(),
+				evaluator: evaluator_address.clone(),
+				positions: positions.clone(),
+				fee: cost,
+				created_at,
+				deadline,
+				status: VerifyRequestStatus::Pending,
+			};
+			<VerifyRequests<T>>::insert(request_id, request);
+			<NextVerifyRequestId<T>>::put(request_id.saturating_add(1));
+			<VerifyRequestDeadlines<T>>::mutate(deadline, |requests| requests.push(request_id));
 
 			Self::deposit_event(Event::VerifyDataRequestCreated {
+				request_id,
 				requestor,
 				positions,
 				evaluator: evaluator_address,
+				fee: cost,
+			});
+
+			Ok(())
+		}
+
+		fn _cancel_verify_data_request(
+			requestor: AccountOf<T>,
+			request_id: VerifyRequestId,
+		) -> DispatchResult {
+			let mut request =
+				Self::verify_requests(request_id).ok_or(<Error<T>>::VerifyRequestNotFound)?;
+			ensure!(request.requestor == requestor, <Error<T>>::NotRequestor);
+			ensure!(
+				request.status == VerifyRequestStatus::Pending,
+				<Error<T>>::VerifyRequestNotPending
+			);
+
+			request.status = VerifyRequestStatus::Cancelled;
+			<VerifyRequests<T>>::insert(request_id, &request);
+
+			let refund =
+				request.fee - Self::_unreserve_named(VERIFY_FEE_RESERVE, &requestor, request.fee);
+
+			Self::deposit_event(Event::VerifyDataRequestCancelled {
+				request_id,
+				requestor,
+				evaluator: request.evaluator,
+				positions: request.positions,
+				refund,
 			});
 
 			Ok(())
@@ -522,45 +1880,456 @@ pub mod pallet {
 
 		fn _verify_data(
 			evaluator: AccountOf<T>,
-			account: AccountOf<T>,
+			request_id: VerifyRequestId,
 			transcript: Vec<(u64, bool)>,
 		) -> DispatchResult {
+			ensure!(!Self::suspended_evaluators(&evaluator), <Error<T>>::EvaluatorSuspended);
+			let mut request =
+				Self::verify_requests(request_id).ok_or(<Error<T>>::VerifyRequestNotFound)?;
+			ensure!(request.evaluator == evaluator, <Error<T>>::NotRequestEvaluator);
+			ensure!(
+				request.status == VerifyRequestStatus::Pending,
+				<Error<T>>::VerifyRequestNotPending
+			);
+			let account = request.requestor.clone();
 			let mut identity = Self::identities(&account).ok_or(<Error<T>>::IdentityNotFound)?;
-			let mut verify_requests =
-				Self::verify_data_requests(&evaluator).ok_or(<Error<T>>::VerifyRequestNotFound)?;
 
-			let request = verify_requests.iter().find(|r| r.0 == account);
+			// The evaluator may respond to a part of the pending positions, each at most once.
+			let mut responded: Vec<u64> = Vec::new();
+			for (position, _) in transcript.iter() {
+				ensure!(
+					request.positions.contains(position) && !responded.contains(position),
+					<Error<T>>::InvalidTranscript
+				);
+				responded.push(*position);
+			}
+			ensure!(!responded.is_empty(), <Error<T>>::InvalidTranscript);
+
+			let mut positions: Vec<u64> = [].to_vec();
+			let mut methods: Vec<VerifyMethod> = [].to_vec();
+			for (position, is_valid) in transcript {
+				if is_valid {
+					let index = Self::_field_index(&identity, position)?;
+					let field = &mut identity.data[index];
+					field.is_verified = true;
+					field.verify_by = Some(evaluator.clone());
+					positions.push(position);
+					methods.push(field.verify_method.clone());
+				}
+			}
 
-			if let Some(request) = request {
-				let transcript_pos: Vec<u64> = transcript.iter().map(|item| item.0).collect();
+			// The fee is released in proportion to the responded positions, the last response
+			// releases what is left of it.
+			let pending = request.positions.len() as u32;
+			request.positions.retain(|position| !responded.contains(position));
+			let fee = if request.positions.is_empty() {
+				request.fee
+			} else {
+				Permill::from_rational(responded.len() as u32, pending) * request.fee
+			};
+			Self::_repatriate_reserved_named(
+				VERIFY_FEE_RESERVE,
+				&account,
+				&evaluator,
+				fee,
+				BalanceStatus::Free,
+			)?;
 
-				ensure!(transcript_pos == request.1, <Error<T>>::InvalidTranscript);
+			request.fee = request.fee.saturating_sub(fee);
+			if request.positions.is_empty() {
+				request.status = VerifyRequestStatus::Verified;
+			}
+			<VerifyRequests<T>>::insert(request_id, request);
+			<CompletedVerifyRequests<T>>::insert(&evaluator, &account, ());
+			for (position, method) in positions.iter().zip(methods) {
+				Self::deposit_event(Event::FieldVerified {
+					account_id: account.clone(),
+					position: *position,
+					method,
+					by: Some(evaluator.clone()),
+				});
+			}
+			<Identities<T>>::insert(&account, identity);
+			Self::deposit_event(Event::DataVerified {
+				request_id,
+				account,
+				positions,
+				evaluator,
+				fee,
+			});
 
-				for (position, is_valid) in transcript {
-					if is_valid {
-						identity.data[position as usize].is_verified = true;
-						identity.data[position as usize].verify_by = Some(evaluator.clone());
-					}
+			Ok(())
+		}
+
+		// The request is skipped if it was verified or cancelled before the deadline.
+		fn _expire_verify_data_request(request_id: VerifyRequestId) {
+			let mut request = match Self::verify_requests(request_id) {
+				Some(request) if request.status == VerifyRequestStatus::Pending => request,
+				_ => return,
+			};
+			request.status = VerifyRequestStatus::Expired;
+			<VerifyRequests<T>>::insert(request_id, &request);
+
+			let requestor = &request.requestor;
+			let evaluator = &request.evaluator;
+			let refund =
+				request.fee - Self::_unreserve_named(VERIFY_FEE_RESERVE, requestor, request.fee);
+
+			// The penalty is capped by what is left of the evaluator bond. The evaluator is not
+			// penalized if the requestor removed its identity, there is no data left to verify.
+			let penalty = if <Identities<T>>::contains_key(requestor) {
+				T::VerifyRequestPenalty::get()
+			} else {
+				Zero::zero()
+			};
+			let penalty = Self::_repatriate_reserved_named(
+				EVALUATOR_BOND_RESERVE,
+				evaluator,
+				requestor,
+				penalty,
+				BalanceStatus::Free,
+			)
+			.map(|remaining| penalty - remaining)
+			.unwrap_or_else(|_| Zero::zero());
+
+			Self::deposit_event(Event::VerifyDataRequestExpired {
+				request_id,
+				requestor: requestor.clone(),
+				evaluator: evaluator.clone(),
+				refund,
+				penalty,
+			});
+		}
+
+		fn _set_languages(
+			account_id: AccountOf<T>,
+			languages: Vec<LanguageCode>,
+		) -> DispatchResult {
+			ensure!(<Identities<T>>::contains_key(&account_id), <Error<T>>::IdentityNotFound);
+			let bounded_languages: BoundedVec<LanguageCode, T::MaxLanguages> =
+				languages.clone().try_into().map_err(|_| <Error<T>>::TooManyLanguages)?;
+
+			if bounded_languages.is_empty() {
+				<Languages<T>>::remove(&account_id);
+			} else {
+				<Languages<T>>::insert(&account_id, bounded_languages);
+			}
+			Self::deposit_event(Event::LanguagesUpdated { account_id, languages });
+
+			Ok(())
+		}
+
+		fn _invite_member(organization: AccountOf<T>, member: AccountOf<T>) -> DispatchResult {
+			let identity = Self::identities(&organization).ok_or(<Error<T>>::IdentityNotFound)?;
+			ensure!(
+				identity.identity_type == IdentityType::Organization,
+				<Error<T>>::NotAnOrganization
+			);
+			ensure!(<Identities<T>>::contains_key(&member), <Error<T>>::IdentityNotFound);
+
+			<MembershipInvitations<T>>::insert(&member, &organization, ());
+			Self::deposit_event(Event::MemberInvited { organization, member });
+
+			Ok(())
+		}
+
+		// The accepted membership replaces the previous membership of the account.
+		fn _accept_membership(member: AccountOf<T>, organization: AccountOf<T>) -> DispatchResult {
+			<MembershipInvitations<T>>::take(&member, &organization)
+				.ok_or(<Error<T>>::MembershipInvitationNotFound)?;
+
+			<Organizations<T>>::insert(&member, &organization);
+			Self::deposit_event(Event::MembershipAccepted { organization, member });
+
+			Ok(())
+		}
+
+		// Either the member or the organization can end the membership.
+		fn _remove_member(sender: AccountOf<T>, member: AccountOf<T>) -> DispatchResult {
+			let organization = Self::organizations(&member).ok_or(<Error<T>>::NotAMember)?;
+			ensure!(
+				sender == member || sender == organization,
+				<Error<T>>::NotMemberOrOrganization
+			);
+
+			<Organizations<T>>::remove(&member);
+			Self::deposit_event(Event::MemberRemoved { organization, member });
+
+			Ok(())
+		}
+
+		fn _set_encryption_key(
+			account_id: AccountOf<T>,
+			key_type: EncryptionKeyType,
+			public: [u8; 32],
+		) -> DispatchResult {
+			ensure!(<Identities<T>>::contains_key(&account_id), <Error<T>>::IdentityNotFound);
+			ensure!(
+				Self::encryption_keys(&account_id).is_empty(),
+				<Error<T>>::EncryptionKeyExisted
+			);
+
+			Self::_push_encryption_key(&account_id, key_type, public)?;
+			Self::deposit_event(Event::EncryptionKeySet { account_id, key_type, public });
+
+			Ok(())
+		}
+
+		fn _rotate_encryption_key(
+			account_id: AccountOf<T>,
+			key_type: EncryptionKeyType,
+			public: [u8; 32],
+		) -> DispatchResult {
+			let keys = Self::encryption_keys(&account_id);
+			let current = keys.last().ok_or(<Error<T>>::EncryptionKeyNotFound)?;
+			ensure!(
+				current.key_type != key_type || current.public != public,
+				<Error<T>>::SameEncryptionKey
+			);
+
+			Self::_push_encryption_key(&account_id, key_type, public)?;
+			Self::deposit_event(Event::EncryptionKeyRotated { account_id, key_type, public });
+
+			Ok(())
+		}
+
+		fn _push_encryption_key(
+			account_id: &AccountOf<T>,
+			key_type: EncryptionKeyType,
+			public: [u8; 32],
+		) -> DispatchResult {
+			let key = EncryptionKey {
+				key_type,
+				public,
+				registered_at: <frame_system::Pallet<T>>::block_number(),
+			};
+			<EncryptionKeys<T>>::try_mutate(account_id, |keys| {
+				// The oldest key is dropped once the history is full.
+				if !keys.is_empty() && keys.len() as u32 >= T::MaxEncryptionKeys::get() {
+					keys.remove(0);
+				}
+				keys.try_push(key).map_err(|_| <Error<T>>::TooManyEncryptionKeys.into())
+			})
+		}
+
+		fn _attest(
+			attester: AccountOf<T>,
+			subject: AccountOf<T>,
+			attestation_type: AttestationType,
+		) -> DispatchResult {
+			ensure!(attester != subject, <Error<T>>::CannotAttestSelf);
+			ensure!(<Identities<T>>::contains_key(&attester), <Error<T>>::IdentityNotFound);
+			ensure!(<Identities<T>>::contains_key(&subject), <Error<T>>::IdentityNotFound);
+
+			<Attestations<T>>::try_mutate(&subject, |attestations| -> DispatchResult {
+				ensure!(
+					!attestations.iter().any(|attestation| attestation.attester == attester &&
+						attestation.attestation_type == attestation_type),
+					<Error<T>>::AttestationExisted
+				);
+				let attestation = Attestation {
+					attester: attester.clone(),
+					attestation_type,
+					attested_at: <frame_system::Pallet<T>>::block_number(),
+				};
+				attestations
+					.try_push(attestation)
+					.map_err(|_| <Error<T>>::TooManyAttestations)?;
+				Ok(())
+			})?;
+			Self::deposit_event(Event::AttestationIssued { attester, subject, attestation_type });
+
+			Ok(())
+		}
+
+		fn _revoke_attestation(
+			attester: AccountOf<T>,
+			subject: AccountOf<T>,
+			attestation_type: AttestationType,
+		) -> DispatchResult {
+			<Attestations<T>>::try_mutate_exists(&subject, |attestations| -> DispatchResult {
+				let list = attestations.as_mut().ok_or(<Error<T>>::AttestationNotFound)?;
+				let position = list
+					.iter()
+					.position(|attestation| {
+						attestation.attester == attester &&
+							attestation.attestation_type == attestation_type
+					})
+					.ok_or(<Error<T>>::AttestationNotFound)?;
+				list.remove(position);
+				if list.is_empty() {
+					*attestations = None;
 				}
+				Ok(())
+			})?;
+			Self::deposit_event(Event::AttestationRevoked { attester, subject, attestation_type });
+
+			Ok(())
+		}
+
+		fn _register_handle(account_id: AccountOf<T>, handle: Vec<u8>) -> DispatchResult {
+			ensure!(<Identities<T>>::contains_key(&account_id), <Error<T>>::IdentityNotFound);
+			ensure!(Self::_is_valid_handle(&handle), <Error<T>>::InvalidHandle);
+			let handle: HandleOf<T> = handle.try_into().map_err(|_| <Error<T>>::InvalidHandle)?;
+			let now = <frame_system::Pallet<T>>::block_number();
+
+			Self::_release_expired_handle(&account_id, now)?;
+			if let Some(registration) = Self::handles(&handle) {
+				ensure!(registration.expires_at <= now, <Error<T>>::HandleTaken);
+				Self::_release_handle(&registration.owner, handle.clone());
+			}
+
+			let deposit = T::HandleDeposit::get();
+			Self::_reserve_named(HANDLE_DEPOSIT_RESERVE, &account_id, deposit)?;
+			let expires_at = now + T::HandlePeriod::get();
+			<Handles<T>>::insert(
+				&handle,
+				HandleRegistration { owner: account_id.clone(), deposit, expires_at },
+			);
+			<AccountHandles<T>>::insert(&account_id, &handle);
+			Self::deposit_event(Event::HandleRegistered {
+				account_id,
+				handle: handle.into_inner(),
+				expires_at,
+			});
+
+			Ok(())
+		}
+
+		// An expired handle can be renewed until it is registered by another account.
+		fn _renew_handle(account_id: AccountOf<T>) -> DispatchResult {
+			let handle = Self::account_handles(&account_id).ok_or(<Error<T>>::HandleNotFound)?;
+			let mut registration = Self::handles(&handle).ok_or(<Error<T>>::HandleNotFound)?;
+
+			let now = <frame_system::Pallet<T>>::block_number();
+			registration.expires_at = registration.expires_at.max(now) + T::HandlePeriod::get();
+			let expires_at = registration.expires_at;
+			<Handles<T>>::insert(&handle, registration);
+			Self::deposit_event(Event::HandleRenewed {
+				account_id,
+				handle: handle.into_inner(),
+				expires_at,
+			});
+
+			Ok(())
+		}
+
+		fn _transfer_handle(from: AccountOf<T>, to: AccountOf<T>) -> DispatchResult {
+			let handle = Self::account_handles(&from).ok_or(<Error<T>>::HandleNotFound)?;
+			let mut registration = Self::handles(&handle).ok_or(<Error<T>>::HandleNotFound)?;
+			let now = <frame_system::Pallet<T>>::block_number();
+			ensure!(registration.expires_at > now, <Error<T>>::HandleExpired);
+			ensure!(<Identities<T>>::contains_key(&to), <Error<T>>::IdentityNotFound);
+			Self::_release_expired_handle(&to, now)?;
+
+			// The deposit moves with the handle, so the new owner gets it back on release.
+			let remaining = Self::_repatriate_reserved_named(
+				HANDLE_DEPOSIT_RESERVE,
+				&from,
+				&to,
+				registration.deposit,
+				BalanceStatus::Reserved,
+			)?;
+			registration.owner = to.clone();
+			registration.deposit = registration.deposit.saturating_sub(remaining);
+			<Handles<T>>::insert(&handle, registration);
+			<AccountHandles<T>>::remove(&from);
+			<AccountHandles<T>>::insert(&to, &handle);
+			Self::deposit_event(Event::HandleTransferred { from, to, handle: handle.into_inner() });
 
-				verify_requests.retain(|r| r.0 != account);
+			Ok(())
+		}
+
+		// An account owns one handle at most, its expired handle is released before it gets
+		// another one.
+		fn _release_expired_handle(
+			account_id: &AccountOf<T>,
+			now: T::BlockNumber,
+		) -> DispatchResult {
+			if let Some(owned) = Self::account_handles(account_id) {
+				let expired = Self::handles(&owned)
+					.map_or(true, |registration| registration.expires_at <= now);
+				ensure!(expired, <Error<T>>::HandleExisted);
+				Self::_release_handle(account_id, owned);
+			}
+			Ok(())
+		}
 
-				<VerifyDataRequests<T>>::insert(&evaluator, verify_requests);
-				<Identities<T>>::insert(&account, identity);
-				return Ok(())
+		fn _release_handle(account_id: &AccountOf<T>, handle: HandleOf<T>) {
+			if let Some(registration) = <Handles<T>>::take(&handle) {
+				Self::_unreserve_named(HANDLE_DEPOSIT_RESERVE, account_id, registration.deposit);
 			}
+			<AccountHandles<T>>::remove(account_id);
+			Self::deposit_event(Event::HandleReleased {
+				account_id: account_id.clone(),
+				handle: handle.into_inner(),
+			});
+		}
 
-			Err(<Error<T>>::VerifyRequestNotFound.into())
+		fn _is_valid_handle(handle: &[u8]) -> bool {
+			handle.len() >= MIN_HANDLE_LENGTH &&
+				handle.iter().all(|c| {
+					c.is_ascii_lowercase() || c.is_ascii_digit() || *c == b'_' || *c == b'-'
+				})
+		}
+
+		fn _set_field_schema(
+			name: Vec<u8>,
+			verify_method: VerifyMethod,
+			format_hint: Vec<u8>,
+		) -> DispatchResult {
+			let schema = FieldSchema {
+				verify_method: verify_method.clone(),
+				format_hint: format_hint.clone(),
+			};
+			<FieldSchemas<T>>::insert(&name, schema);
+			Self::deposit_event(Event::FieldSchemaSet { name, verify_method, format_hint });
+			Ok(())
+		}
+
+		fn _remove_field_schema(name: Vec<u8>) -> DispatchResult {
+			ensure!(<FieldSchemas<T>>::contains_key(&name), <Error<T>>::FieldSchemaNotFound);
+			<FieldSchemas<T>>::remove(&name);
+			Self::deposit_event(Event::FieldSchemaRemoved { name });
+			Ok(())
+		}
+
+		fn _suspend_evaluator(evaluator: AccountOf<T>) -> DispatchResult {
+			ensure!(<Evaluators<T>>::contains_key(&evaluator), <Error<T>>::EvaluatorNotFound);
+			ensure!(!Self::suspended_evaluators(&evaluator), <Error<T>>::EvaluatorSuspended);
+
+			<SuspendedEvaluators<T>>::insert(&evaluator, true);
+			Self::deposit_event(Event::EvaluatorSuspended { account: evaluator });
+
+			Ok(())
+		}
+
+		fn _reinstate_evaluator(evaluator: AccountOf<T>) -> DispatchResult {
+			ensure!(Self::suspended_evaluators(&evaluator), <Error<T>>::EvaluatorNotSuspended);
+
+			<SuspendedEvaluators<T>>::remove(&evaluator);
+			Self::deposit_event(Event::EvaluatorReinstated { account: evaluator });
+
+			Ok(())
 		}
 
 		fn _validate_data(data: Vec<IdentityFieldInput>) -> DispatchResult {
-			for field in data {
-				Self::_validate_data_field(field)?;
+			for (index, field) in data.iter().enumerate() {
+				let has_name = data[..index].iter().any(|other| other.name == field.name);
+				ensure!(!has_name, <Error<T>>::DuplicateFieldName);
+				Self::_validate_data_field(field.clone())?;
 			}
 			Ok(())
 		}
 
 		fn _validate_data_field(data_field: IdentityFieldInput) -> DispatchResult {
+			if let Some(schema) = Self::field_schemas(&data_field.name) {
+				ensure!(
+					schema.verify_method == data_field.verify_method,
+					<Error<T>>::FieldSchemaMismatch
+				);
+			}
+
 			match data_field.verify_method {
 				VerifyMethod::Domain => {
 					ensure!(Self::_is_valid_domain(&data_field.value), <Error<T>>::InvalidDomain);
@@ -568,6 +2337,8 @@ pub mod pallet {
 				VerifyMethod::Email => {
 					ensure!(Self::_is_valid_email(data_field.value), <Error<T>>::InvalidEmail);
 				},
+				VerifyMethod::ExternalSignature =>
+					return Err(<Error<T>>::ExternalProofRequired.into()),
 				_ => (),
 			}
 
@@ -637,7 +2408,14 @@ pub mod pallet {
 			if let Some(position) = position {
 				identity.data[position].is_verified = true;
 				identity.data[position].verify_by = None;
-
+				<Identities<T>>::insert(&owner, identity);
+
+				Self::deposit_event(Event::FieldVerified {
+					account_id: owner.clone(),
+					position: position as u64,
+					method: VerifyMethod::Domain,
+					by: None,
+				});
 				Self::deposit_event(Event::DomainVerified { domain, owner });
 
 				Ok(())
@@ -645,6 +2423,70 @@ pub mod pallet {
 				Err(<Error<T>>::DataFieldNotFound.into())
 			}
 		}
+
+		// Reserve the native tokens of the account under the reserve identifier.
+		fn _reserve_named(
+			id: ReserveIdentifier,
+			who: &AccountOf<T>,
+			amount: BalanceOf<T>,
+		) -> DispatchResult {
+			T::Currency::reserve(CurrencyId::Native, who, amount)?;
+			<NamedReserves<T>>::mutate(who, (id, CurrencyId::Native), |reserved| {
+				*reserved = reserved.saturating_add(amount)
+			});
+			Ok(())
+		}
+
+		// Unreserve at most the native tokens tracked under the reserve identifier, returns the
+		// amount that cannot be unreserved.
+		fn _unreserve_named(
+			id: ReserveIdentifier,
+			who: &AccountOf<T>,
+			amount: BalanceOf<T>,
+		) -> BalanceOf<T> {
+			let to_unreserve = amount.min(Self::named_reserves(who, (id, CurrencyId::Native)));
+			let unreserved =
+				to_unreserve - T::Currency::unreserve(CurrencyId::Native, who, to_unreserve);
+			Self::_untrack_reserve(id, who, unreserved);
+
+			amount - unreserved
+		}
+
+		// Move at most the native tokens tracked under the reserve identifier to the beneficiary,
+		// returns the amount that cannot be moved. The tokens moved to the reserved balance are
+		// tracked under the same identifier for the beneficiary.
+		fn _repatriate_reserved_named(
+			id: ReserveIdentifier,
+			slashed: &AccountOf<T>,
+			beneficiary: &AccountOf<T>,
+			amount: BalanceOf<T>,
+			status: BalanceStatus,
+		) -> Result<BalanceOf<T>, DispatchError> {
+			let to_move = amount.min(Self::named_reserves(slashed, (id, CurrencyId::Native)));
+			let moved = to_move -
+				T::Currency::repatriate_reserved(
+					CurrencyId::Native,
+					slashed,
+					beneficiary,
+					to_move,
+					status,
+				)?;
+			Self::_untrack_reserve(id, slashed, moved);
+			if status == BalanceStatus::Reserved {
+				<NamedReserves<T>>::mutate(beneficiary, (id, CurrencyId::Native), |reserved| {
+					*reserved = reserved.saturating_add(moved)
+				});
+			}
+
+			Ok(amount - moved)
+		}
+
+		fn _untrack_reserve(id: ReserveIdentifier, who: &AccountOf<T>, amount: BalanceOf<T>) {
+			<NamedReserves<T>>::mutate_exists(who, (id, CurrencyId::Native), |reserved| {
+				let left = reserved.unwrap_or_default().saturating_sub(amount);
+				*reserved = if left.is_zero() { None } else { Some(left) };
+			});
+		}
 	}
 
 	impl<T: Config> IdentitiesManager<T::AccountId> for Pallet<T> {
@@ -668,7 +2510,12 @@ pub mod pallet {
 				identity.credibility += amount;
 			}
 
+			let credibility = identity.credibility;
 			<Identities<T>>::insert(account_id, identity);
+			Self::deposit_event(Event::CredibilityUpdated {
+				account_id: account_id.clone(),
+				credibility,
+			});
 
 			Ok(())
 		}
@@ -677,8 +2524,128 @@ pub mod pallet {
 		fn decrease_credibility(account_id: &T::AccountId, amount: Credibility) -> DispatchResult {
 			let mut identity = Self::identities(&account_id).ok_or(<Error<T>>::IdentityNotFound)?;
 			identity.credibility -= amount;
+			let credibility = identity.credibility;
 			<Identities<T>>::insert(account_id, identity);
+			Self::deposit_event(Event::CredibilityUpdated {
+				account_id: account_id.clone(),
+				credibility,
+			});
 			Ok(())
 		}
+
+		fn get_verified_field(account_id: &T::AccountId, name: &[u8]) -> Option<Vec<u8>> {
+			let identity = Self::identities(account_id)?;
+			identity
+				.data
+				.into_iter()
+				.find(|field| field.name == name && field.is_verified)
+				.map(|field| field.value)
+		}
+
+		fn is_field_verified(account_id: &T::AccountId, name: &[u8]) -> bool {
+			Self::get_verified_field(account_id, name).is_some()
+		}
+
+		fn get_languages(account_id: &T::AccountId) -> Vec<LanguageCode> {
+			Self::languages(account_id).into_inner()
+		}
+
+		fn get_organization(account_id: &T::AccountId) -> Option<T::AccountId> {
+			Self::organizations(account_id)
+		}
+
+		fn is_organization(account_id: &T::AccountId) -> bool {
+			Self::identities(account_id)
+				.map_or(false, |identity| identity.identity_type == IdentityType::Organization)
+		}
+
+		fn is_reviewer(account_id: &T::AccountId, reviewer: &T::AccountId) -> bool {
+			<IdentityReviews<T>>::contains_key(account_id, reviewer)
+		}
+
+		fn has_published_review(account_id: &T::AccountId, reviewer: &T::AccountId) -> bool {
+			Self::identity_reviews(account_id, reviewer)
+				.map_or(false, |review| review.status == ReviewStatus::Published)
+		}
+
+		fn remove_contested_review(
+			account_id: &T::AccountId,
+			reviewer: &T::AccountId,
+		) -> DispatchResult {
+			<IdentityReviews<T>>::try_mutate(account_id, reviewer, |review| -> DispatchResult {
+				let review = review.as_mut().ok_or(<Error<T>>::ReviewNotFound)?;
+				ensure!(review.status == ReviewStatus::Published, <Error<T>>::ReviewNotFound);
+				review.status = ReviewStatus::ContestedRemoved;
+				Ok(())
+			})?;
+			<ReviewCounts<T>>::mutate(account_id, |count| *count = count.saturating_sub(1));
+			Self::deposit_event(Event::IdentityReviewRemoved {
+				account: account_id.clone(),
+				reviewer: reviewer.clone(),
+			});
+			Ok(())
+		}
+
+		fn get_encryption_key(account_id: &T::AccountId) -> Option<Vec<u8>> {
+			Self::encryption_keys(account_id).last().map(|key| key.public.to_vec())
+		}
+
+		fn get_attestations(account_id: &T::AccountId) -> Vec<(T::AccountId, AttestationType)> {
+			Self::attestations(account_id)
+				.into_iter()
+				.map(|attestation| (attestation.attester, attestation.attestation_type))
+				.collect()
+		}
+
+		fn is_attested_by(account_id: &T::AccountId, attester: &T::AccountId) -> bool {
+			Self::attestations(account_id)
+				.iter()
+				.any(|attestation| attestation.attester == *attester)
+		}
+
+		fn get_handle(account_id: &T::AccountId) -> Option<Vec<u8>> {
+			let handle = Self::account_handles(account_id)?;
+			let registration = Self::handles(&handle)?;
+			if registration.expires_at <= <frame_system::Pallet<T>>::block_number() {
+				return None
+			}
+			Some(handle.into_inner())
+		}
+
+		fn note_dispute_opened(account_id: &T::AccountId) {
+			<DisputeRecords<T>>::mutate(account_id, |record| {
+				record.opened = record.opened.saturating_add(1)
+			});
+		}
+
+		fn note_dispute_withdrawn(account_id: &T::AccountId) {
+			<DisputeRecords<T>>::mutate(account_id, |record| {
+				record.opened = record.opened.saturating_sub(1)
+			});
+		}
+
+		fn note_dispute_resolved(winner: &T::AccountId, loser: &T::AccountId) {
+			<DisputeRecords<T>>::mutate(winner, |record| {
+				record.won = record.won.saturating_add(1)
+			});
+			<DisputeRecords<T>>::mutate(loser, |record| {
+				record.lost = record.lost.saturating_add(1)
+			});
+		}
+
+		fn note_dispute_overturned(winner: &T::AccountId, loser: &T::AccountId) {
+			<DisputeRecords<T>>::mutate(winner, |record| {
+				record.lost = record.lost.saturating_sub(1);
+				record.won = record.won.saturating_add(1);
+			});
+			<DisputeRecords<T>>::mutate(loser, |record| {
+				record.won = record.won.saturating_sub(1);
+				record.lost = record.lost.saturating_add(1);
+			});
+		}
+
+		fn get_dispute_record(account_id: &T::AccountId) -> DisputeRecord {
+			Self::dispute_records(account_id)
+		}
 	}
 }
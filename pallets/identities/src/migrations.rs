@@ -0,0 +1,574 @@
+//! Storage migrations of the identities pallet.
+//!
+//! Each migration upgrades the `StorageVersion` of the pallet by one and only runs if the on-chain
+//! version is the previous one. The migrations can be rehearsed against a live chain with the
+//! `try-runtime` feature, which runs the `pre_upgrade` and `post_upgrade` checks.
+
+use crate::{
+	Config, Evaluator, Evaluators, EvaluatorsByField, EvaluatorsByRateBand, FieldPrices,
+	Identities, Identity, IdentityField, IdentityReview, IdentityReviews, IdentityType,
+	NamedReserves, NextVerifyRequestId, Pallet, ReviewCounts, ReviewStatus, VerifyRequest,
+	VerifyRequestDeadlines, VerifyRequestStatus, VerifyRequests,
+};
+#[cfg(feature = "try-runtime")]
+use frame_support::traits::OnRuntimeUpgradeHelpersExt;
+use frame_support::{
+	pallet_prelude::*,
+	traits::{OnRuntimeUpgrade, PalletInfoAccess},
+	StorageHasher,
+};
+
+/// Version 1 stamps the storage version. The identities and the evaluators kept their layout from
+/// the launch, so the checks only count that they still decode.
+pub mod v1 {
+	use super::*;
+
+	pub struct MigrateToV1<T>(PhantomData<T>);
+
+	impl<T: Config> OnRuntimeUpgrade for MigrateToV1<T> {
+		fn on_runtime_upgrade() -> Weight {
+			if Pallet::<T>::on_chain_storage_version() != 0 {
+				return T::DbWeight::get().reads(1)
+			}
+
+			StorageVersion::new(1).put::<Pallet<T>>();
+			T::DbWeight::get().reads_writes(1, 1)
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<(), &'static str> {
+			Self::set_temp_storage(Identities::<T>::iter_keys().count() as u32, "identities");
+			Self::set_temp_storage(Evaluators::<T>::iter_keys().count() as u32, "evaluators");
+			Ok(())
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade() -> Result<(), &'static str> {
+			ensure!(
+				Pallet::<T>::on_chain_storage_version() >= 1,
+				"Storage version is not migrated"
+			);
+			let identities: u32 =
+				Self::get_temp_storage("identities").ok_or("Missing identities count")?;
+			ensure!(
+				Identities::<T>::iter_values().count() as u32 == identities,
+				"Some identities cannot be decoded with the current layout"
+			);
+			let evaluators: u32 =
+				Self::get_temp_storage("evaluators").ok_or("Missing evaluators count")?;
+			ensure!(
+				Evaluators::<T>::iter_values().count() as u32 == evaluators,
+				"Some evaluators cannot be decoded with the current layout"
+			);
+			Ok(())
+		}
+	}
+}
+
+/// Version 2 escrows the verify data request fees and gives each request a response deadline.
+/// The fees of the existing requests were paid to the evaluators up front, so the migrated
+/// requests escrow nothing and expire `VerifyRequestTimeout` blocks after the upgrade.
+pub mod v2 {
+	use super::*;
+	use codec::{Decode, Encode};
+	use frame_support::{
+		sp_runtime::traits::Zero,
+		sp_std::vec::Vec,
+		storage::migration::{put_storage_value, storage_key_iter, PrefixIterator},
+	};
+	use orml_traits::MultiCurrency;
+
+	type BalanceOf<T> =
+		<<T as Config>::Currency as MultiCurrency<<T as frame_system::Config>::AccountId>>::Balance;
+
+	type OldVerifyDataRequests<T> = Vec<(<T as frame_system::Config>::AccountId, Vec<u64>)>;
+
+	/// A verify data request in the `VerifyDataRequests` of the evaluators, from version 2 to
+	/// version 6.
+	#[derive(Encode, Decode)]
+	pub(super) struct VerifyDataRequest<AccountId, Balance, BlockNumber> {
+		pub requestor: AccountId,
+		pub positions: Vec<u64>,
+		pub fee: Balance,
+		pub deadline: BlockNumber,
+	}
+
+	pub(super) type VerifyDataRequests<T> = Vec<
+		VerifyDataRequest<
+			<T as frame_system::Config>::AccountId,
+			BalanceOf<T>,
+			<T as frame_system::Config>::BlockNumber,
+		>,
+	>;
+
+	/// The verify data requests keyed by evaluator, from version 2 to version 6.
+	pub(super) fn verify_data_requests<T: Config>(
+	) -> PrefixIterator<(T::AccountId, VerifyDataRequests<T>)> {
+		storage_key_iter::<T::AccountId, VerifyDataRequests<T>, Twox64Concat>(
+			Pallet::<T>::name().as_bytes(),
+			b"VerifyDataRequests",
+		)
+	}
+
+	pub struct MigrateToV2<T>(PhantomData<T>);
+
+	impl<T: Config> OnRuntimeUpgrade for MigrateToV2<T> {
+		fn on_runtime_upgrade() -> Weight {
+			if Pallet::<T>::on_chain_storage_version() != 1 {
+				return T::DbWeight::get().reads(1)
+			}
+
+			let pallet = Pallet::<T>::name().as_bytes();
+			let deadline =
+				<frame_system::Pallet<T>>::block_number() + T::VerifyRequestTimeout::get();
+			let mut deadlines: Vec<(T::AccountId, T::AccountId)> = Vec::new();
+			let mut evaluators: Weight = 0;
+			let old_requests: Vec<_> = storage_key_iter::<
+				T::AccountId,
+				OldVerifyDataRequests<T>,
+				Twox64Concat,
+			>(pallet, b"VerifyDataRequests")
+			.collect();
+			for (evaluator, requests) in old_requests {
+				evaluators += 1;
+				let requests: VerifyDataRequests<T> = requests
+					.into_iter()
+					.map(|(requestor, positions)| {
+						deadlines.push((evaluator.clone(), requestor.clone()));
+						VerifyDataRequest { requestor, positions, fee: Zero::zero(), deadline }
+					})
+					.collect();
+				put_storage_value(
+					pallet,
+					b"VerifyDataRequests",
+					&Twox64Concat::hash(&evaluator.encode()),
+					requests,
+				);
+			}
+			put_storage_value(
+				pallet,
+				b"VerifyRequestDeadlines",
+				&Twox64Concat::hash(&deadline.encode()),
+				deadlines,
+			);
+
+			StorageVersion::new(2).put::<Pallet<T>>();
+			T::DbWeight::get().reads_writes(1 + evaluators, 2 + evaluators)
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<(), &'static str> {
+			let requests: u32 = storage_key_iter::<
+				T::AccountId,
+				OldVerifyDataRequests<T>,
+				Twox64Concat,
+			>(Pallet::<T>::name().as_bytes(), b"VerifyDataRequests")
+			.map(|(_, requests)| requests.len() as u32)
+			.sum();
+			Self::set_temp_storage(requests, "verify_data_requests");
+			Ok(())
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade() -> Result<(), &'static str> {
+			ensure!(
+				Pallet::<T>::on_chain_storage_version() >= 2,
+				"Storage version is not migrated"
+			);
+			let requests: u32 = Self::get_temp_storage("verify_data_requests")
+				.ok_or("Missing verify data requests count")?;
+			ensure!(
+				verify_data_requests::<T>().map(|(_, r)| r.len() as u32).sum::<u32>() == requests,
+				"Some verify data requests are not migrated"
+			);
+			Ok(())
+		}
+	}
+}
+
+/// Version 3 aggregates the reviews of the evaluators on the evaluator record. The existing
+/// evaluators start without any review.
+pub mod v3 {
+	use super::*;
+	use codec::{Decode, Encode};
+	use frame_support::sp_std::vec::Vec;
+	use orml_traits::MultiCurrency;
+
+	type BalanceOf<T> =
+		<<T as Config>::Currency as MultiCurrency<<T as frame_system::Config>::AccountId>>::Balance;
+
+	#[derive(Encode, Decode)]
+	struct OldEvaluator<Balance> {
+		name: Vec<u8>,
+		about: Vec<u8>,
+		rate: Balance,
+	}
+
+	pub struct MigrateToV3<T>(PhantomData<T>);
+
+	impl<T: Config> OnRuntimeUpgrade for MigrateToV3<T> {
+		fn on_runtime_upgrade() -> Weight {
+			if Pallet::<T>::on_chain_storage_version() != 2 {
+				return T::DbWeight::get().reads(1)
+			}
+
+			let mut evaluators: Weight = 0;
+			Evaluators::<T>::translate::<OldEvaluator<BalanceOf<T>>, _>(|_, old| {
+				evaluators += 1;
+				Some(Evaluator {
+					name: old.name,
+					about: old.about,
+					rate: old.rate,
+					review_count: 0,
+					total_stars: 0,
+				})
+			});
+
+			StorageVersion::new(3).put::<Pallet<T>>();
+			T::DbWeight::get().reads_writes(1 + evaluators, 1 + evaluators)
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<(), &'static str> {
+			Self::set_temp_storage(Evaluators::<T>::iter_keys().count() as u32, "evaluators");
+			Ok(())
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade() -> Result<(), &'static str> {
+			ensure!(
+				Pallet::<T>::on_chain_storage_version() >= 3,
+				"Storage version is not migrated"
+			);
+			let evaluators: u32 =
+				Self::get_temp_storage("evaluators").ok_or("Missing evaluators count")?;
+			ensure!(
+				Evaluators::<T>::iter_values().count() as u32 == evaluators,
+				"Some evaluators are not migrated"
+			);
+			Ok(())
+		}
+	}
+}
+
+/// Version 4 tracks the evaluator bonds and the escrowed verify fees in `NamedReserves`. The
+/// existing bonds and fees are tracked as reserved, capped by the reserved balances of the
+/// accounts as the penalties may have been slashed from the bonds.
+pub mod v4 {
+	use super::*;
+	use frame_support::{
+		sp_runtime::traits::{Saturating, Zero},
+		sp_std::{collections::btree_map::BTreeMap, vec::Vec},
+	};
+	use orml_traits::{MultiCurrency, MultiReservableCurrency};
+	use primitives::{CurrencyId, ReserveIdentifier, EVALUATOR_BOND_RESERVE, VERIFY_FEE_RESERVE};
+
+	type BalanceOf<T> =
+		<<T as Config>::Currency as MultiCurrency<<T as frame_system::Config>::AccountId>>::Balance;
+
+	pub struct MigrateToV4<T>(PhantomData<T>);
+
+	impl<T: Config> OnRuntimeUpgrade for MigrateToV4<T> {
+		fn on_runtime_upgrade() -> Weight {
+			if Pallet::<T>::on_chain_storage_version() != 3 {
+				return T::DbWeight::get().reads(1)
+			}
+
+			let mut reserves: BTreeMap<T::AccountId, Vec<(ReserveIdentifier, BalanceOf<T>)>> =
+				BTreeMap::new();
+			let mut reads: Weight = 0;
+			for evaluator in Evaluators::<T>::iter_keys() {
+				reads += 1;
+				reserves
+					.entry(evaluator)
+					.or_default()
+					.push((EVALUATOR_BOND_RESERVE, T::EvaluatorBonding::get()));
+			}
+			for (_, requests) in v2::verify_data_requests::<T>() {
+				reads += 1;
+				for request in requests {
+					reserves
+						.entry(request.requestor)
+						.or_default()
+						.push((VERIFY_FEE_RESERVE, request.fee));
+				}
+			}
+
+			let mut writes: Weight = 0;
+			for (who, amounts) in reserves {
+				let mut reserved = T::Currency::reserved_balance(CurrencyId::Native, &who);
+				for (id, amount) in amounts {
+					let tracked = amount.min(reserved);
+					reserved = reserved.saturating_sub(tracked);
+					if !tracked.is_zero() {
+						NamedReserves::<T>::mutate(&who, (id, CurrencyId::Native), |total| {
+							*total = total.saturating_add(tracked)
+						});
+						writes += 1;
+					}
+				}
+				reads += 1;
+			}
+
+			StorageVersion::new(4).put::<Pallet<T>>();
+			T::DbWeight::get().reads_writes(1 + reads, 1 + writes)
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade() -> Result<(), &'static str> {
+			ensure!(
+				Pallet::<T>::on_chain_storage_version() >= 4,
+				"Storage version is not migrated"
+			);
+			ensure!(
+				NamedReserves::<T>::iter().all(|(who, (_, currency_id), amount)| {
+					amount <= T::Currency::reserved_balance(currency_id, &who)
+				}),
+				"Some tracked funds are not reserved"
+			);
+			Ok(())
+		}
+	}
+}
+
+/// Version 5 moves the reviews out of the identity records into `IdentityReviews`, keyed by subject
+/// and reviewer, and counts them in `ReviewCounts`. The identities are read without the reviews
+/// afterwards, however many reviews they got. The migrated reviews are published.
+pub mod v5 {
+	use super::*;
+	use codec::{Decode, Encode};
+	use frame_support::sp_std::vec::Vec;
+	use primitives::Credibility;
+
+	#[derive(Encode, Decode)]
+	struct OldIdentityReview<AccountId, Hash> {
+		reviewer: AccountId,
+		content_digest: Hash,
+	}
+
+	#[derive(Encode, Decode)]
+	struct OldIdentity<T: Config> {
+		name: Vec<u8>,
+		identity_type: IdentityType,
+		credibility: Credibility,
+		data: Vec<IdentityField<T>>,
+		reviews: Vec<OldIdentityReview<T::AccountId, T::Hash>>,
+	}
+
+	pub struct MigrateToV5<T>(PhantomData<T>);
+
+	impl<T: Config> OnRuntimeUpgrade for MigrateToV5<T> {
+		fn on_runtime_upgrade() -> Weight {
+			if Pallet::<T>::on_chain_storage_version() != 4 {
+				return T::DbWeight::get().reads(1)
+			}
+
+			let mut identities: Weight = 0;
+			let mut reviews: Weight = 0;
+			Identities::<T>::translate::<OldIdentity<T>, _>(|account, old| {
+				identities += 1;
+				for review in old.reviews {
+					// The reviewers could only review an identity once.
+					if !IdentityReviews::<T>::contains_key(&account, &review.reviewer) {
+						let review = IdentityReview::<T> {
+							reviewer: review.reviewer,
+							content_digest: review.content_digest,
+							status: ReviewStatus::Published,
+						};
+						IdentityReviews::<T>::insert(&account, &review.reviewer, &review);
+						ReviewCounts::<T>::mutate(&account, |count| *count += 1);
+						reviews += 1;
+					}
+				}
+				Some(Identity {
+					name: old.name,
+					identity_type: old.identity_type,
+					credibility: old.credibility,
+					data: old.data,
+				})
+			});
+
+			StorageVersion::new(5).put::<Pallet<T>>();
+			T::DbWeight::get()
+				.reads_writes(1 + identities + 2 * reviews, 1 + identities + 2 * reviews)
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<(), &'static str> {
+			let reviews: u32 = frame_support::storage::migration::storage_key_iter::<
+				T::AccountId,
+				OldIdentity<T>,
+				frame_support::Twox64Concat,
+			>(
+				<Pallet<T> as frame_support::traits::PalletInfoAccess>::name().as_bytes(),
+				b"Identities",
+			)
+			.map(|(_, identity)| identity.reviews.len() as u32)
+			.sum();
+			Self::set_temp_storage(reviews, "identity_reviews");
+			Self::set_temp_storage(Identities::<T>::iter_keys().count() as u32, "identities");
+			Ok(())
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade() -> Result<(), &'static str> {
+			ensure!(
+				Pallet::<T>::on_chain_storage_version() >= 5,
+				"Storage version is not migrated"
+			);
+			let identities: u32 =
+				Self::get_temp_storage("identities").ok_or("Missing identities count")?;
+			ensure!(
+				Identities::<T>::iter_values().count() as u32 == identities,
+				"Some identities are not migrated"
+			);
+			let reviews: u32 =
+				Self::get_temp_storage("identity_reviews").ok_or("Missing reviews count")?;
+			ensure!(
+				ReviewCounts::<T>::iter_values().sum::<u32>() == reviews,
+				"Some identity reviews are not migrated"
+			);
+			Ok(())
+		}
+	}
+}
+
+/// Version 6 indexes the evaluators by the fields they publish a price for and by the band of
+/// their rate, for the evaluator directory.
+pub mod v6 {
+	use super::*;
+
+	pub struct MigrateToV6<T>(PhantomData<T>);
+
+	impl<T: Config> OnRuntimeUpgrade for MigrateToV6<T> {
+		fn on_runtime_upgrade() -> Weight {
+			if Pallet::<T>::on_chain_storage_version() != 5 {
+				return T::DbWeight::get().reads(1)
+			}
+
+			let mut evaluators: Weight = 0;
+			for (account, evaluator) in Evaluators::<T>::iter() {
+				Pallet::<T>::_index_evaluator_rate(&account, evaluator.rate);
+				evaluators += 1;
+			}
+			let mut prices: Weight = 0;
+			let mut evaluators_with_prices: Weight = 0;
+			for (account, field_prices) in FieldPrices::<T>::iter() {
+				for (name_hash, price) in field_prices.iter() {
+					EvaluatorsByField::<T>::insert(name_hash, &account, price);
+					prices += 1;
+				}
+				evaluators_with_prices += 1;
+			}
+
+			StorageVersion::new(6).put::<Pallet<T>>();
+			T::DbWeight::get()
+				.reads_writes(1 + evaluators + evaluators_with_prices, 1 + evaluators + prices)
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade() -> Result<(), &'static str> {
+			ensure!(
+				Pallet::<T>::on_chain_storage_version() >= 6,
+				"Storage version is not migrated"
+			);
+			ensure!(
+				EvaluatorsByRateBand::<T>::iter_keys().count() ==
+					Evaluators::<T>::iter_keys().count(),
+				"Some evaluators are not indexed by rate"
+			);
+			ensure!(
+				FieldPrices::<T>::iter().all(|(account, prices)| prices.iter().all(
+					|(name_hash, _)| EvaluatorsByField::<T>::contains_key(name_hash, &account)
+				)),
+				"Some field prices are not indexed"
+			);
+			Ok(())
+		}
+	}
+}
+
+/// Version 7 keys the verify data requests by id in `VerifyRequests`, with the evaluator, the
+/// creation block and the status, so a requestor can have concurrent requests to an evaluator.
+/// The pending requests are numbered evaluator by evaluator, their creation block is derived from
+/// the deadline, and `VerifyRequestDeadlines` is rebuilt with the ids.
+pub mod v7 {
+	use super::*;
+	use frame_support::{
+		sp_runtime::traits::Saturating, sp_std::vec::Vec, storage::migration::remove_storage_prefix,
+	};
+
+	pub struct MigrateToV7<T>(PhantomData<T>);
+
+	impl<T: Config> OnRuntimeUpgrade for MigrateToV7<T> {
+		fn on_runtime_upgrade() -> Weight {
+			if Pallet::<T>::on_chain_storage_version() != 6 {
+				return T::DbWeight::get().reads(1)
+			}
+
+			let pallet = Pallet::<T>::name().as_bytes();
+			let old_requests: Vec<_> = v2::verify_data_requests::<T>().collect();
+			remove_storage_prefix(pallet, b"VerifyDataRequests", &[]);
+			remove_storage_prefix(pallet, b"VerifyRequestDeadlines", &[]);
+
+			let mut request_id = NextVerifyRequestId::<T>::get();
+			let mut requests: Weight = 0;
+			for (evaluator, evaluator_requests) in old_requests.iter() {
+				for old in evaluator_requests {
+					let request = VerifyRequest::<T> {
+						requestor: old.requestor.clone(),
+						evaluator: evaluator.clone(),
+						positions: old.positions.clone(),
+						fee: old.fee,
+						created_at: old.deadline.saturating_sub(T::VerifyRequestTimeout::get()),
+						deadline: old.deadline,
+						status: VerifyRequestStatus::Pending,
+					};
+					VerifyRequestDeadlines::<T>::mutate(old.deadline, |ids| ids.push(request_id));
+					VerifyRequests::<T>::insert(request_id, request);
+					request_id = request_id.saturating_add(1);
+					requests += 1;
+				}
+			}
+			NextVerifyRequestId::<T>::put(request_id);
+
+			StorageVersion::new(7).put::<Pallet<T>>();
+			let evaluators = old_requests.len() as Weight;
+			T::DbWeight::get()
+				.reads_writes(2 + evaluators + requests, 4 + evaluators + 2 * requests)
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<(), &'static str> {
+			let requests: u32 =
+				v2::verify_data_requests::<T>().map(|(_, r)| r.len() as u32).sum::<u32>();
+			Self::set_temp_storage(requests, "verify_data_requests");
+			Ok(())
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade() -> Result<(), &'static str> {
+			ensure!(
+				Pallet::<T>::on_chain_storage_version() >= 7,
+				"Storage version is not migrated"
+			);
+			let requests: u32 = Self::get_temp_storage("verify_data_requests")
+				.ok_or("Missing verify data requests count")?;
+			ensure!(
+				VerifyRequests::<T>::iter_values()
+					.filter(|request| request.status == VerifyRequestStatus::Pending)
+					.count() as u32 == requests,
+				"Some verify data requests are not migrated"
+			);
+			ensure!(
+				VerifyRequests::<T>::iter()
+					.filter(|(_, request)| request.status == VerifyRequestStatus::Pending)
+					.all(|(request_id, request)| {
+						VerifyRequestDeadlines::<T>::get(request.deadline).contains(&request_id)
+					}),
+				"Some verify data requests have no deadline"
+			);
+			Ok(())
+		}
+	}
+}
@@ -1,6 +1,7 @@
 #![cfg(test)]
 
 use crate as identities;
+use crate::{IdentityFieldInput, IdentityType};
 
 use frame_support::{
 	construct_runtime, parameter_types,
@@ -27,6 +28,16 @@ pub const BOB: AccountId = 2;
 pub const CHARLIE: AccountId = 3;
 
 pub const EVALUATOR_BONDING: Balance = 100;
+pub const VERIFY_REQUEST_TIMEOUT: BlockNumber = 10;
+pub const VERIFY_REQUEST_PENALTY: Balance = 5;
+pub const MAX_FIELD_PRICES: u32 = 3;
+pub const EVALUATOR_RATE_BAND: Balance = 10;
+pub const MAX_LANGUAGES: u32 = 3;
+pub const MAX_ENCRYPTION_KEYS: u32 = 3;
+pub const MAX_ATTESTATIONS: u32 = 3;
+pub const HANDLE_DEPOSIT: Balance = 10;
+pub const HANDLE_PERIOD: BlockNumber = 100;
+pub const MAX_HANDLE_LENGTH: u32 = 16;
 pub const INITIAL_CREDIBILITY: Credibility = 60;
 pub const MAX_CREDIBILITY: Credibility = 100;
 
@@ -111,6 +122,16 @@ impl orml_currencies::Config for Runtime {
 
 parameter_types! {
 	pub const EvaluatorBonding: Balance = EVALUATOR_BONDING;
+	pub const VerifyRequestTimeout: BlockNumber = VERIFY_REQUEST_TIMEOUT;
+	pub const VerifyRequestPenalty: Balance = VERIFY_REQUEST_PENALTY;
+	pub const MaxFieldPrices: u32 = MAX_FIELD_PRICES;
+	pub const EvaluatorRateBand: Balance = EVALUATOR_RATE_BAND;
+	pub const MaxLanguages: u32 = MAX_LANGUAGES;
+	pub const MaxEncryptionKeys: u32 = MAX_ENCRYPTION_KEYS;
+	pub const MaxAttestations: u32 = MAX_ATTESTATIONS;
+	pub const HandleDeposit: Balance = HANDLE_DEPOSIT;
+	pub const HandlePeriod: BlockNumber = HANDLE_PERIOD;
+	pub const MaxHandleLength: u32 = MAX_HANDLE_LENGTH;
 	pub const InitialCredibility: Credibility = INITIAL_CREDIBILITY;
 	pub const MaxCredibility: Credibility = MAX_CREDIBILITY;
 }
@@ -119,8 +140,21 @@ impl identities::Config for Runtime {
 	type Event = Event;
 	type Currency = Currencies;
 	type EvaluatorBonding = EvaluatorBonding;
+	type GovernanceOrigin = system::EnsureRoot<AccountId>;
+	type Resolvers = ();
+	type VerifyRequestTimeout = VerifyRequestTimeout;
+	type VerifyRequestPenalty = VerifyRequestPenalty;
+	type MaxFieldPrices = MaxFieldPrices;
+	type EvaluatorRateBand = EvaluatorRateBand;
+	type MaxLanguages = MaxLanguages;
+	type MaxEncryptionKeys = MaxEncryptionKeys;
+	type MaxAttestations = MaxAttestations;
+	type HandleDeposit = HandleDeposit;
+	type HandlePeriod = HandlePeriod;
+	type MaxHandleLength = MaxHandleLength;
 	type InitialCredibility = InitialCredibility;
 	type MaxCredibility = MaxCredibility;
+	type WeightInfo = ();
 }
 
 type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Runtime>;
@@ -136,23 +170,40 @@ construct_runtime!(
 		Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
 		Tokens: orml_tokens::{Pallet, Storage, Event<T>, Config<T>},
 		Currencies: orml_currencies::{Pallet, Call, Event<T>},
-		Identities: identities::{Pallet, Call, Storage, Event<T>},
+		Identities: identities::{Pallet, Call, Storage, Config<T>, Event<T>},
 	}
 );
 
 pub struct ExtBuilder {
 	balances: Vec<(AccountId, CurrencyId<Hash>, Balance)>,
+	identities: Vec<(AccountId, Vec<u8>, IdentityType, Vec<IdentityFieldInput>)>,
+	evaluators: Vec<(AccountId, Vec<u8>, Vec<u8>, Balance)>,
 }
 
 impl Default for ExtBuilder {
 	fn default() -> Self {
 		Self {
 			balances: vec![(ALICE, CurrencyId::Native, 1_000), (BOB, CurrencyId::Native, 1_000)],
+			identities: vec![],
+			evaluators: vec![],
 		}
 	}
 }
 
 impl ExtBuilder {
+	pub fn identities(
+		mut self,
+		identities: Vec<(AccountId, Vec<u8>, IdentityType, Vec<IdentityFieldInput>)>,
+	) -> Self {
+		self.identities = identities;
+		self
+	}
+
+	pub fn evaluators(mut self, evaluators: Vec<(AccountId, Vec<u8>, Vec<u8>, Balance)>) -> Self {
+		self.evaluators = evaluators;
+		self
+	}
+
 	pub fn build(self) -> sp_io::TestExternalities {
 		let mut t = system::GenesisConfig::default().build_storage::<Runtime>().unwrap();
 
@@ -166,6 +217,13 @@ impl ExtBuilder {
 			.assimilate_storage(&mut t)
 			.unwrap();
 
+		identities::GenesisConfig::<Runtime> {
+			identities: self.identities,
+			evaluators: self.evaluators,
+		}
+		.assimilate_storage(&mut t)
+		.unwrap();
+
 		t.into()
 	}
 }
@@ -173,3 +231,11 @@ impl ExtBuilder {
 pub fn last_event() -> Event {
 	system::Pallet::<Runtime>::events().pop().expect("Event expected").event
 }
+
+pub fn identities_events() -> Vec<Event> {
+	system::Pallet::<Runtime>::events()
+		.into_iter()
+		.map(|record| record.event)
+		.filter(|event| matches!(event, Event::Identities(_)))
+		.collect()
+}
@@ -1,12 +1,29 @@
 #![cfg(test)]
 
 use super::*;
-use frame_support::{assert_noop, assert_ok};
+use codec::Encode;
+use frame_support::{
+	assert_noop, assert_ok,
+	storage::migration::{get_storage_value, put_storage_value},
+	traits::{GetStorageVersion, OnInitialize, OnRuntimeUpgrade, StorageVersion},
+	BoundedVec, StorageHasher, Twox64Concat,
+};
 use mock::{
-	last_event, Currencies, CurrencyId, Event, ExtBuilder, Identities, Origin, Runtime, System,
-	ALICE, BOB, CHARLIE,
+	identities_events, last_event, AccountId, Balance, BlockNumber, Currencies, CurrencyId, Event,
+	ExtBuilder, Hash, Identities, Origin, Runtime, System, ALICE, BOB, CHARLIE, EVALUATOR_BONDING,
+	EVALUATOR_RATE_BAND, HANDLE_DEPOSIT, HANDLE_PERIOD, INITIAL_CREDIBILITY, MAX_ATTESTATIONS,
+	MAX_CREDIBILITY, MAX_ENCRYPTION_KEYS, MAX_FIELD_PRICES, MAX_LANGUAGES, VERIFY_REQUEST_PENALTY,
+	VERIFY_REQUEST_TIMEOUT,
 };
 use orml_traits::{MultiCurrency, MultiReservableCurrency};
+use primitives::{
+	DidService, DidVerificationMethod, EvaluatorFilter, Pagination, EVALUATOR_BOND_RESERVE,
+	HANDLE_DEPOSIT_RESERVE, VERIFY_FEE_RESERVE,
+};
+use sp_runtime::{
+	traits::{BlakeTwo256, Hash},
+	Permill,
+};
 
 #[test]
 fn create_identity_works() {
@@ -31,7 +48,7 @@ fn create_identity_works() {
 		assert_eq!(identity.name, "Alice".as_bytes());
 		assert_eq!(identity.data.len(), 0);
 		assert_eq!(identity.identity_type, IdentityType::Individual);
-		assert_eq!(identity.reviews.len(), 0);
+		assert_eq!(Identities::review_counts(&ALICE), 0);
 
 		// Test identity just can create one time.
 		assert_noop!(
@@ -68,7 +85,7 @@ fn create_identity_works() {
 
 		assert_eq!(identity.name, "Bob".as_bytes());
 		assert_eq!(identity.identity_type, IdentityType::Individual);
-		assert_eq!(identity.reviews.len(), 0);
+		assert_eq!(Identities::review_counts(&BOB), 0);
 		assert_eq!(identity.data.len(), 2);
 		assert_eq!(identity.data[0].name, "domain".as_bytes());
 		assert_eq!(identity.data[0].value, "atscale.xyz".as_bytes());
@@ -129,7 +146,10 @@ fn update_identity_works() {
 		));
 		assert_eq!(
 			last_event(),
-			Event::Identities(crate::Event::IdentityUpdated { account_id: ALICE })
+			Event::Identities(crate::Event::IdentityUpdated {
+				account_id: ALICE,
+				positions: [].to_vec(),
+			})
 		);
 
 		let identity = Identities::identities(&ALICE).unwrap();
@@ -148,6 +168,13 @@ fn update_identity_works() {
 				.into()
 			),
 		));
+		assert_eq!(
+			last_event(),
+			Event::Identities(crate::Event::IdentityUpdated {
+				account_id: ALICE,
+				positions: [0].to_vec(),
+			})
+		);
 
 		// Test update identity data with invalid email
 		assert_noop!(
@@ -205,7 +232,320 @@ fn update_identity_data_works() {
 
 		assert_eq!(
 			last_event(),
-			Event::Identities(crate::Event::IdentityUpdated { account_id: ALICE })
+			Event::Identities(crate::Event::IdentityUpdated {
+				account_id: ALICE,
+				positions: [0].to_vec(),
+			})
+		);
+	});
+}
+
+#[test]
+fn update_identity_data_checks_position() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+
+		assert_noop!(
+			Identities::update_identity_data(
+				Origin::signed(ALICE),
+				0,
+				field_input("field_a", "value_a"),
+			),
+			Error::<Runtime>::IdentityNotFound
+		);
+
+		assert_ok!(Identities::create_identity(
+			Origin::signed(ALICE),
+			"Alice".into(),
+			IdentityType::Individual,
+			[field_input("field_a", "value_a"), field_input("field_b", "value_b")].into(),
+		));
+
+		// The positions out of the data are rejected instead of panicking.
+		for position in [2, u32::MAX as u64, u32::MAX as u64 + 1, u64::MAX] {
+			assert_noop!(
+				Identities::update_identity_data(
+					Origin::signed(ALICE),
+					position,
+					field_input("field_c", "value_c"),
+				),
+				Error::<Runtime>::DataFieldNotFound
+			);
+		}
+		assert_noop!(
+			Identities::request_to_verify(Origin::signed(ALICE), [u64::MAX].into(), BOB),
+			Error::<Runtime>::EvaluatorNotFound
+		);
+		assert_ok!(Identities::create_evaluator(
+			Origin::signed(BOB),
+			"Bob".into(),
+			"About Bob".into(),
+			10,
+		));
+		assert_noop!(
+			Identities::request_to_verify(Origin::signed(ALICE), [u64::MAX].into(), BOB),
+			Error::<Runtime>::DataFieldNotFound
+		);
+
+		// The last position can be updated.
+		assert_ok!(Identities::update_identity_data(
+			Origin::signed(ALICE),
+			1,
+			field_input("field_c", "value_c"),
+		));
+		assert_eq!(Identities::identities(&ALICE).unwrap().data[1].name, b"field_c".to_vec());
+
+		// Updating a verified field resets its verification.
+		assert_ok!(Identities::request_to_verify(Origin::signed(ALICE), [0].into(), BOB));
+		assert_ok!(Identities::verify_data(Origin::signed(BOB), 0, [(0, true)].into()));
+		assert!(Identities::is_field_verified(&ALICE, b"field_a"));
+
+		assert_ok!(Identities::update_identity_data(
+			Origin::signed(ALICE),
+			0,
+			field_input("field_a", "value_a"),
+		));
+		let identity = Identities::identities(&ALICE).unwrap();
+		assert!(!identity.data[0].is_verified);
+		assert_eq!(identity.data[0].verify_by, None);
+	});
+}
+
+#[test]
+fn remove_identity_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+
+		assert_noop!(
+			Identities::remove_identity(Origin::signed(ALICE)),
+			Error::<Runtime>::IdentityNotFound
+		);
+
+		assert_ok!(Identities::create_identity(
+			Origin::signed(ALICE),
+			"Alice".into(),
+			IdentityType::Individual,
+			[field_input("field_a", "value_a")].into(),
+		));
+		assert_ok!(Identities::create_identity(
+			Origin::signed(BOB),
+			"Bob".into(),
+			IdentityType::Individual,
+			[].into(),
+		));
+		assert_ok!(Identities::set_languages(Origin::signed(ALICE), [*b"en"].into()));
+		assert_ok!(Identities::register_handle(Origin::signed(ALICE), "alice".into()));
+		assert_ok!(Identities::attest(Origin::signed(BOB), ALICE, AttestationType::Employee));
+		assert_ok!(Identities::review_identity(Origin::signed(BOB), ALICE, "Good".into()));
+		assert_eq!(Currencies::reserved_balance(CurrencyId::Native, &ALICE), HANDLE_DEPOSIT);
+
+		// A pending verify request of the removed identity expires without penalty.
+		assert_ok!(Identities::create_evaluator(
+			Origin::signed(CHARLIE),
+			"Charlie".into(),
+			"About Charlie".into(),
+			10,
+		));
+		assert_ok!(Identities::request_to_verify(Origin::signed(ALICE), [0].into(), CHARLIE));
+
+		assert_ok!(Identities::remove_identity(Origin::signed(ALICE)));
+		assert_eq!(
+			last_event(),
+			Event::Identities(crate::Event::IdentityRemoved { account_id: ALICE })
+		);
+		assert_eq!(Identities::identities(&ALICE), None);
+		assert!(Identities::languages(&ALICE).is_empty());
+		assert!(Identities::attestations(&ALICE).is_empty());
+		assert_eq!(Identities::account_handles(&ALICE), None);
+		assert!(Identities::handles(&handle("alice")).is_none());
+		assert_eq!(Currencies::reserved_balance(CurrencyId::Native, &ALICE), 10);
+		assert_eq!(
+			Identities::named_reserves(ALICE, (HANDLE_DEPOSIT_RESERVE, CurrencyId::Native)),
+			0
+		);
+
+		// The reviews are kept.
+		assert!(Identities::identity_reviews(&ALICE, &BOB).is_some());
+		assert_eq!(Identities::review_counts(&ALICE), 1);
+
+		Identities::on_initialize(1 + VERIFY_REQUEST_TIMEOUT);
+		assert_eq!(
+			last_event(),
+			Event::Identities(crate::Event::VerifyDataRequestExpired {
+				request_id: 0,
+				requestor: ALICE,
+				evaluator: CHARLIE,
+				refund: 10,
+				penalty: 0,
+			})
+		);
+		assert_eq!(Currencies::reserved_balance(CurrencyId::Native, &ALICE), 0);
+		assert_eq!(Currencies::reserved_balance(CurrencyId::Native, &CHARLIE), EVALUATOR_BONDING);
+
+		assert_noop!(
+			Identities::remove_identity(Origin::signed(ALICE)),
+			Error::<Runtime>::IdentityNotFound
+		);
+
+		// The identity of an evaluator cannot be removed.
+		assert_ok!(Identities::create_identity(
+			Origin::signed(CHARLIE),
+			"Charlie".into(),
+			IdentityType::Individual,
+			[].into(),
+		));
+		assert_noop!(
+			Identities::remove_identity(Origin::signed(CHARLIE)),
+			Error::<Runtime>::EvaluatorIdentityInUse
+		);
+	});
+}
+
+fn field_input(name: &str, value: &str) -> IdentityFieldInput {
+	IdentityFieldInput {
+		name: name.into(),
+		value: value.into(),
+		verify_method: VerifyMethod::Evaluator,
+	}
+}
+
+#[test]
+fn field_events_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+
+		assert_ok!(Identities::create_identity(
+			Origin::signed(ALICE),
+			"Alice".into(),
+			IdentityType::Individual,
+			[field_input("field_a", "value_a"), field_input("field_b", "value_b")].into(),
+		));
+		assert_eq!(
+			identities_events(),
+			vec![
+				Event::Identities(crate::Event::FieldAdded {
+					account_id: ALICE,
+					position: 0,
+					name: "field_a".into(),
+				}),
+				Event::Identities(crate::Event::FieldAdded {
+					account_id: ALICE,
+					position: 1,
+					name: "field_b".into(),
+				}),
+				Event::Identities(crate::Event::IdentityCreated { account_id: ALICE }),
+			]
+		);
+
+		// The unchanged fields have no event.
+		System::reset_events();
+		assert_ok!(Identities::update_identity(
+			Origin::signed(ALICE),
+			None,
+			Some([field_input("field_a", "value_a")].into()),
+		));
+		assert_eq!(
+			identities_events(),
+			vec![
+				Event::Identities(crate::Event::FieldRemoved {
+					account_id: ALICE,
+					position: 1,
+					name: "field_b".into(),
+				}),
+				Event::Identities(crate::Event::IdentityUpdated {
+					account_id: ALICE,
+					positions: [0].to_vec(),
+				}),
+			]
+		);
+
+		System::reset_events();
+		assert_ok!(Identities::update_identity(
+			Origin::signed(ALICE),
+			None,
+			Some([field_input("field_a", "new_value"), field_input("field_c", "value_c")].into()),
+		));
+		assert_eq!(
+			identities_events(),
+			vec![
+				Event::Identities(crate::Event::FieldUpdated {
+					account_id: ALICE,
+					position: 0,
+					name: "field_a".into(),
+				}),
+				Event::Identities(crate::Event::FieldAdded {
+					account_id: ALICE,
+					position: 1,
+					name: "field_c".into(),
+				}),
+				Event::Identities(crate::Event::IdentityUpdated {
+					account_id: ALICE,
+					positions: [0, 1].to_vec(),
+				}),
+			]
+		);
+
+		System::reset_events();
+		assert_ok!(Identities::update_identity_data(
+			Origin::signed(ALICE),
+			1,
+			field_input("field_d", "value_d"),
+		));
+		assert_ok!(Identities::add_identity_data(
+			Origin::signed(ALICE),
+			field_input("field_e", "value_e"),
+		));
+		assert_eq!(
+			identities_events(),
+			vec![
+				Event::Identities(crate::Event::FieldUpdated {
+					account_id: ALICE,
+					position: 1,
+					name: "field_d".into(),
+				}),
+				Event::Identities(crate::Event::IdentityUpdated {
+					account_id: ALICE,
+					positions: [1].to_vec(),
+				}),
+				Event::Identities(crate::Event::FieldAdded {
+					account_id: ALICE,
+					position: 2,
+					name: "field_e".into(),
+				}),
+				Event::Identities(crate::Event::IdentityUpdated {
+					account_id: ALICE,
+					positions: [2].to_vec(),
+				}),
+			]
+		);
+
+		// The fields verified by an evaluator have an event each.
+		assert_ok!(Identities::create_evaluator(
+			Origin::signed(BOB),
+			"Bob".into(),
+			"About Bob".into(),
+			10,
+		));
+		assert_ok!(Identities::request_to_verify(Origin::signed(ALICE), [0, 2].into(), BOB));
+		System::reset_events();
+		assert_ok!(Identities::verify_data(Origin::signed(BOB), 0, [(0, true), (2, false)].into()));
+		assert_eq!(
+			identities_events(),
+			vec![
+				Event::Identities(crate::Event::FieldVerified {
+					account_id: ALICE,
+					position: 0,
+					method: VerifyMethod::Evaluator,
+					by: Some(BOB),
+				}),
+				Event::Identities(crate::Event::DataVerified {
+					request_id: 0,
+					account: ALICE,
+					positions: [0].to_vec(),
+					evaluator: BOB,
+					fee: 10,
+				}),
+			]
 		);
 	});
 }
@@ -235,7 +575,10 @@ fn add_identity_data_works() {
 
 		assert_eq!(
 			last_event(),
-			Event::Identities(crate::Event::IdentityUpdated { account_id: ALICE })
+			Event::Identities(crate::Event::IdentityUpdated {
+				account_id: ALICE,
+				positions: [0].to_vec(),
+			})
 		);
 
 		// Test add invalid domain.
@@ -250,94 +593,384 @@ fn add_identity_data_works() {
 			),
 			Error::<Runtime>::InvalidDomain,
 		);
+
+		// Test add a data field with an existed name.
+		assert_noop!(
+			Identities::add_identity_data(
+				Origin::signed(ALICE),
+				IdentityFieldInput {
+					name: "email".into(),
+					value: "foo@atscale.xyz".into(),
+					verify_method: VerifyMethod::Email,
+				},
+			),
+			Error::<Runtime>::DuplicateFieldName,
+		);
 	});
 }
 
 #[test]
-fn add_identity_review_works() {
+fn field_schema_works() {
 	ExtBuilder::default().build().execute_with(|| {
 		System::set_block_number(1);
 
-		// Test update identity name
+		assert_noop!(
+			Identities::set_field_schema(
+				Origin::signed(ALICE),
+				"legal_name".into(),
+				VerifyMethod::Evaluator,
+				"text".into(),
+			),
+			sp_runtime::DispatchError::BadOrigin
+		);
+		assert_ok!(Identities::set_field_schema(
+			Origin::root(),
+			"legal_name".into(),
+			VerifyMethod::Evaluator,
+			"text".into(),
+		));
+		assert_eq!(
+			last_event(),
+			Event::Identities(crate::Event::FieldSchemaSet {
+				name: "legal_name".into(),
+				verify_method: VerifyMethod::Evaluator,
+				format_hint: "text".into(),
+			})
+		);
+		assert_eq!(
+			Identities::field_schemas(b"legal_name".to_vec()),
+			Some(FieldSchema {
+				verify_method: VerifyMethod::Evaluator,
+				format_hint: "text".into()
+			})
+		);
+
+		// The data fields with a registered name must use the verify method of the schema.
+		assert_noop!(
+			Identities::create_identity(
+				Origin::signed(ALICE),
+				"Alice".into(),
+				IdentityType::Individual,
+				[IdentityFieldInput {
+					name: "legal_name".into(),
+					value: "Alice".into(),
+					verify_method: VerifyMethod::None,
+				}]
+				.into(),
+			),
+			Error::<Runtime>::FieldSchemaMismatch
+		);
+		assert_noop!(
+			Identities::create_identity(
+				Origin::signed(ALICE),
+				"Alice".into(),
+				IdentityType::Individual,
+				[
+					IdentityFieldInput {
+						name: "legal_name".into(),
+						value: "Alice".into(),
+						verify_method: VerifyMethod::Evaluator,
+					},
+					IdentityFieldInput {
+						name: "legal_name".into(),
+						value: "Alice Liddell".into(),
+						verify_method: VerifyMethod::Evaluator,
+					},
+				]
+				.into(),
+			),
+			Error::<Runtime>::DuplicateFieldName
+		);
 		assert_ok!(Identities::create_identity(
 			Origin::signed(ALICE),
 			"Alice".into(),
 			IdentityType::Individual,
-			[].into(),
+			[IdentityFieldInput {
+				name: "legal_name".into(),
+				value: "Alice".into(),
+				verify_method: VerifyMethod::Evaluator,
+			}]
+			.into(),
 		));
 
-		assert_ok!(Identities::review_identity(Origin::signed(BOB), ALICE, "Good".into(),));
-
-		let identity = Identities::identities(&ALICE).unwrap();
-
-		assert_eq!(identity.reviews.len(), 1);
-		assert_eq!(identity.reviews[0].reviewer, BOB);
-
+		assert_ok!(Identities::remove_field_schema(Origin::root(), "legal_name".into()));
+		assert_eq!(
+			last_event(),
+			Event::Identities(crate::Event::FieldSchemaRemoved { name: "legal_name".into() })
+		);
 		assert_noop!(
-			Identities::review_identity(Origin::signed(BOB), ALICE, "Good".into(),),
-			Error::<Runtime>::CanOnlyReviewOnce,
+			Identities::remove_field_schema(Origin::root(), "legal_name".into()),
+			Error::<Runtime>::FieldSchemaNotFound
 		);
 	});
 }
 
 #[test]
-fn create_evaluator_works() {
+fn add_identity_review_works() {
 	ExtBuilder::default().build().execute_with(|| {
 		System::set_block_number(1);
 
 		// Test update identity name
-		assert_ok!(Identities::create_evaluator(
+		assert_ok!(Identities::create_identity(
 			Origin::signed(ALICE),
 			"Alice".into(),
-			"About Alice".into(),
-			10,
+			IdentityType::Individual,
+			[].into(),
 		));
-		assert_eq!(Currencies::reserved_balance(CurrencyId::Native, &ALICE), 100);
-		let evaluator = Identities::evaluators(&ALICE).unwrap();
-		assert_eq!(evaluator.name, "Alice".as_bytes());
-		assert_eq!(evaluator.about, "About Alice".as_bytes());
-		assert_eq!(evaluator.rate, 10);
+
+		assert_ok!(Identities::review_identity(Origin::signed(BOB), ALICE, "Good".into(),));
+
+		assert_eq!(Identities::review_counts(&ALICE), 1);
+		assert_eq!(Identities::identity_reviews(&ALICE, &BOB).unwrap().reviewer, BOB);
+
+		assert_noop!(
+			Identities::review_identity(Origin::signed(BOB), ALICE, "Good".into(),),
+			Error::<Runtime>::CanOnlyReviewOnce,
+		);
+
+		// The reviews are read a page at a time.
+		assert_ok!(Identities::review_identity(Origin::signed(CHARLIE), ALICE, "Good".into(),));
+		assert_eq!(Identities::review_counts(&ALICE), 2);
+		assert_eq!(Identities::identity_reviews_page(&ALICE, 0, 1).len(), 1);
+		let mut reviewers: Vec<AccountId> = Identities::identity_reviews_page(&ALICE, 0, 1)
+			.into_iter()
+			.chain(Identities::identity_reviews_page(&ALICE, 1, 1))
+			.map(|review| review.reviewer)
+			.collect();
+		reviewers.sort();
+		assert_eq!(reviewers, vec![BOB, CHARLIE]);
+		assert!(Identities::identity_reviews_page(&ALICE, 2, 1).is_empty());
+
+		// A review removed by a review dispute is kept but not counted or listed.
+		assert!(Identities::has_published_review(&ALICE, &BOB));
+		assert_ok!(Identities::remove_contested_review(&ALICE, &BOB));
+		assert_eq!(
+			last_event(),
+			Event::Identities(crate::Event::IdentityReviewRemoved { account: ALICE, reviewer: BOB })
+		);
+		assert_eq!(
+			Identities::identity_reviews(&ALICE, &BOB).unwrap().status,
+			ReviewStatus::ContestedRemoved
+		);
+		assert!(!Identities::has_published_review(&ALICE, &BOB));
+		assert_eq!(Identities::review_counts(&ALICE), 1);
+		assert_eq!(Identities::identity_reviews_page(&ALICE, 0, 2).len(), 1);
+		assert_noop!(
+			Identities::remove_contested_review(&ALICE, &BOB),
+			Error::<Runtime>::ReviewNotFound
+		);
 	});
 }
 
 #[test]
-fn create_verify_request_works() {
+fn organization_membership_works() {
 	ExtBuilder::default().build().execute_with(|| {
 		System::set_block_number(1);
 
-		// Test update identity name
-		assert_ok!(Identities::create_evaluator(
+		assert_ok!(Identities::create_identity(
 			Origin::signed(ALICE),
-			"Alice".into(),
-			"About Alice".into(),
-			10,
+			"Atscale".into(),
+			IdentityType::Organization,
+			[].into(),
 		));
-
-		// Test update identity name
 		assert_ok!(Identities::create_identity(
 			Origin::signed(BOB),
-			"BOB".into(),
+			"Bob".into(),
 			IdentityType::Individual,
-			[IdentityFieldInput {
-				name: "field_a".into(),
-				value: "value_a".into(),
-				verify_method: VerifyMethod::Evaluator,
-			},]
-			.into(),
+			[].into(),
 		));
 
-		assert_ok!(Identities::request_to_verify(Origin::signed(BOB), [0].into(), ALICE,));
-		assert_eq!(Currencies::reserved_balance(CurrencyId::Native, &ALICE), 100);
-		assert_eq!(Currencies::free_balance(CurrencyId::Native, &ALICE), 910);
-		assert_eq!(Currencies::free_balance(CurrencyId::Native, &BOB), 990);
+		// Only organizations can invite members and only to existing identities.
+		assert_noop!(
+			Identities::invite_member(Origin::signed(BOB), ALICE),
+			Error::<Runtime>::NotAnOrganization
+		);
+		assert_noop!(
+			Identities::invite_member(Origin::signed(ALICE), CHARLIE),
+			Error::<Runtime>::IdentityNotFound
+		);
+		assert_noop!(
+			Identities::accept_membership(Origin::signed(BOB), ALICE),
+			Error::<Runtime>::MembershipInvitationNotFound
+		);
+
+		assert_ok!(Identities::invite_member(Origin::signed(ALICE), BOB));
+		assert_eq!(
+			last_event(),
+			Event::Identities(crate::Event::MemberInvited { organization: ALICE, member: BOB })
+		);
+		assert_eq!(Identities::membership_invitations(BOB, ALICE), Some(()));
+		// The membership is not effective until the member accepts the invitation.
+		assert_eq!(Identities::get_organization(&BOB), None);
+
+		assert_ok!(Identities::accept_membership(Origin::signed(BOB), ALICE));
+		assert_eq!(
+			last_event(),
+			Event::Identities(crate::Event::MembershipAccepted {
+				organization: ALICE,
+				member: BOB
+			})
+		);
+		assert_eq!(Identities::membership_invitations(BOB, ALICE), None);
+		assert_eq!(Identities::get_organization(&BOB), Some(ALICE));
 
-		let verify_requests = Identities::verify_data_requests(&ALICE).unwrap();
-		assert_eq!(verify_requests.len(), 1);
+		assert_noop!(
+			Identities::remove_member(Origin::signed(CHARLIE), BOB),
+			Error::<Runtime>::NotMemberOrOrganization
+		);
+		assert_ok!(Identities::remove_member(Origin::signed(BOB), BOB));
+		assert_eq!(
+			last_event(),
+			Event::Identities(crate::Event::MemberRemoved { organization: ALICE, member: BOB })
+		);
+		assert_eq!(Identities::get_organization(&BOB), None);
+		assert_noop!(
+			Identities::remove_member(Origin::signed(ALICE), BOB),
+			Error::<Runtime>::NotAMember
+		);
 	});
 }
 
 #[test]
-fn verify_data_works() {
+fn attestations_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+
+		assert_ok!(Identities::create_identity(
+			Origin::signed(ALICE),
+			"Atscale".into(),
+			IdentityType::Organization,
+			[].into(),
+		));
+		assert_noop!(
+			Identities::attest(Origin::signed(ALICE), BOB, AttestationType::Employee),
+			Error::<Runtime>::IdentityNotFound
+		);
+		assert_ok!(Identities::create_identity(
+			Origin::signed(BOB),
+			"Bob".into(),
+			IdentityType::Individual,
+			[].into(),
+		));
+		assert_noop!(
+			Identities::attest(Origin::signed(ALICE), ALICE, AttestationType::Employee),
+			Error::<Runtime>::CannotAttestSelf
+		);
+
+		assert_ok!(Identities::attest(Origin::signed(ALICE), BOB, AttestationType::Employee));
+		assert_eq!(
+			last_event(),
+			Event::Identities(crate::Event::AttestationIssued {
+				attester: ALICE,
+				subject: BOB,
+				attestation_type: AttestationType::Employee,
+			})
+		);
+		assert_eq!(
+			Identities::attestations(BOB).into_inner(),
+			vec![Attestation {
+				attester: ALICE,
+				attestation_type: AttestationType::Employee,
+				attested_at: 1
+			}]
+		);
+		assert_noop!(
+			Identities::attest(Origin::signed(ALICE), BOB, AttestationType::Employee),
+			Error::<Runtime>::AttestationExisted
+		);
+		assert_ok!(Identities::attest(Origin::signed(ALICE), BOB, AttestationType::Supplier));
+		assert_eq!(
+			Identities::get_attestations(&BOB),
+			vec![(ALICE, AttestationType::Employee), (ALICE, AttestationType::Supplier)]
+		);
+		assert!(Identities::is_attested_by(&BOB, &ALICE));
+		assert!(!Identities::is_attested_by(&ALICE, &BOB));
+
+		// An identity holds at most `MAX_ATTESTATIONS` attestations.
+		assert_ok!(Identities::create_identity(
+			Origin::signed(CHARLIE),
+			"Charlie".into(),
+			IdentityType::Individual,
+			[].into(),
+		));
+		assert_ok!(Identities::attest(Origin::signed(CHARLIE), BOB, AttestationType::Partner));
+		assert_eq!(Identities::attestations(BOB).len() as u32, MAX_ATTESTATIONS);
+		assert_noop!(
+			Identities::attest(Origin::signed(CHARLIE), BOB, AttestationType::Customer),
+			Error::<Runtime>::TooManyAttestations
+		);
+
+		// Only the attester can revoke its attestation.
+		assert_noop!(
+			Identities::revoke_attestation(Origin::signed(CHARLIE), BOB, AttestationType::Employee),
+			Error::<Runtime>::AttestationNotFound
+		);
+		assert_ok!(Identities::revoke_attestation(
+			Origin::signed(ALICE),
+			BOB,
+			AttestationType::Employee
+		));
+		assert_eq!(
+			last_event(),
+			Event::Identities(crate::Event::AttestationRevoked {
+				attester: ALICE,
+				subject: BOB,
+				attestation_type: AttestationType::Employee,
+			})
+		);
+		assert_ok!(Identities::revoke_attestation(
+			Origin::signed(ALICE),
+			BOB,
+			AttestationType::Supplier
+		));
+		assert!(!Identities::is_attested_by(&BOB, &ALICE));
+		assert!(Identities::is_attested_by(&BOB, &CHARLIE));
+	});
+}
+
+#[test]
+fn is_reviewer_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+
+		assert_ok!(Identities::create_identity(
+			Origin::signed(ALICE),
+			"Alice".into(),
+			IdentityType::Individual,
+			[].into(),
+		));
+		assert!(!Identities::is_reviewer(&ALICE, &BOB));
+
+		assert_ok!(Identities::review_identity(Origin::signed(BOB), ALICE, "Good".into()));
+
+		assert!(Identities::is_reviewer(&ALICE, &BOB));
+		assert!(!Identities::is_reviewer(&ALICE, &CHARLIE));
+		assert!(!Identities::is_reviewer(&BOB, &ALICE));
+	});
+}
+
+#[test]
+fn create_evaluator_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+
+		// Test update identity name
+		assert_ok!(Identities::create_evaluator(
+			Origin::signed(ALICE),
+			"Alice".into(),
+			"About Alice".into(),
+			10,
+		));
+		assert_eq!(Currencies::reserved_balance(CurrencyId::Native, &ALICE), 100);
+		let evaluator = Identities::evaluators(&ALICE).unwrap();
+		assert_eq!(evaluator.name, "Alice".as_bytes());
+		assert_eq!(evaluator.about, "About Alice".as_bytes());
+		assert_eq!(evaluator.rate, 10);
+	});
+}
+
+#[test]
+fn create_verify_request_works() {
 	ExtBuilder::default().build().execute_with(|| {
 		System::set_block_number(1);
 
@@ -350,53 +983,1594 @@ fn verify_data_works() {
 		));
 
 		// Test update identity name
+		assert_ok!(Identities::create_identity(
+			Origin::signed(BOB),
+			"BOB".into(),
+			IdentityType::Individual,
+			[IdentityFieldInput {
+				name: "field_a".into(),
+				value: "value_a".into(),
+				verify_method: VerifyMethod::Evaluator,
+			},]
+			.into(),
+		));
+
+		assert_ok!(Identities::request_to_verify(Origin::signed(BOB), [0].into(), ALICE,));
+		assert_eq!(
+			last_event(),
+			Event::Identities(crate::Event::VerifyDataRequestCreated {
+				request_id: 0,
+				requestor: BOB,
+				positions: [0].to_vec(),
+				evaluator: ALICE,
+				fee: 10,
+			})
+		);
+		// The fee is escrowed until the evaluator responds.
+		assert_eq!(Currencies::reserved_balance(CurrencyId::Native, &ALICE), 100);
+		assert_eq!(Currencies::free_balance(CurrencyId::Native, &ALICE), 900);
+		assert_eq!(Currencies::reserved_balance(CurrencyId::Native, &BOB), 10);
+		assert_eq!(Currencies::free_balance(CurrencyId::Native, &BOB), 990);
+
+		assert_eq!(
+			Identities::verify_requests(0),
+			Some(VerifyRequest {
+				requestor: BOB,
+				evaluator: ALICE,
+				positions: [0].to_vec(),
+				fee: 10,
+				created_at: 1,
+				deadline: 1 + VERIFY_REQUEST_TIMEOUT,
+				status: VerifyRequestStatus::Pending,
+			})
+		);
+
+		// A requestor can have concurrent requests to the same evaluator.
+		System::set_block_number(2);
+		assert_ok!(Identities::request_to_verify(Origin::signed(BOB), [0].into(), ALICE,));
+		let verify_request = Identities::verify_requests(1).unwrap();
+		assert_eq!(verify_request.created_at, 2);
+		assert_eq!(verify_request.deadline, 2 + VERIFY_REQUEST_TIMEOUT);
+		assert_eq!(VerifyRequestDeadlines::<Runtime>::get(2 + VERIFY_REQUEST_TIMEOUT), vec![1]);
+		assert_eq!(Currencies::reserved_balance(CurrencyId::Native, &BOB), 20);
+	});
+}
+
+#[test]
+fn field_prices_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+
+		let passport_price = (BlakeTwo256::hash(b"passport"), 50);
+		assert_noop!(
+			Identities::set_field_prices(Origin::signed(ALICE), [passport_price].into()),
+			Error::<Runtime>::EvaluatorNotFound
+		);
+
+		assert_ok!(Identities::create_evaluator(
+			Origin::signed(ALICE),
+			"Alice".into(),
+			"About Alice".into(),
+			10,
+		));
+		assert_noop!(
+			Identities::set_field_prices(
+				Origin::signed(ALICE),
+				vec![passport_price; MAX_FIELD_PRICES as usize + 1],
+			),
+			Error::<Runtime>::TooManyFieldPrices
+		);
+		assert_ok!(Identities::set_field_prices(Origin::signed(ALICE), [passport_price].into()));
+		assert_eq!(
+			last_event(),
+			Event::Identities(crate::Event::FieldPricesUpdated {
+				evaluator: ALICE,
+				prices: [passport_price].to_vec(),
+			})
+		);
+
 		assert_ok!(Identities::create_identity(
 			Origin::signed(BOB),
 			"BOB".into(),
 			IdentityType::Individual,
 			[
 				IdentityFieldInput {
-					name: "field_a".into(),
-					value: "value_a".into(),
-					verify_method: VerifyMethod::Evaluator,
-				},
-				IdentityFieldInput {
-					name: "field_b".into(),
-					value: "value_b".into(),
+					name: "email".into(),
+					value: "bob@atscale.xyz".into(),
 					verify_method: VerifyMethod::Evaluator,
 				},
 				IdentityFieldInput {
-					name: "field_c".into(),
-					value: "value_c".into(),
+					name: "passport".into(),
+					value: "B1234567".into(),
 					verify_method: VerifyMethod::Evaluator,
 				},
 			]
 			.into(),
 		));
 
-		assert_ok!(Identities::request_to_verify(Origin::signed(BOB), [0, 2].into(), ALICE,));
-		assert_eq!(Currencies::free_balance(CurrencyId::Native, &ALICE), 920);
-		assert_eq!(Currencies::free_balance(CurrencyId::Native, &BOB), 980);
+		assert_noop!(
+			Identities::request_to_verify(Origin::signed(BOB), [2].into(), ALICE,),
+			Error::<Runtime>::DataFieldNotFound
+		);
+
+		// The email field has no published price and costs the evaluator rate.
+		assert_ok!(Identities::request_to_verify(Origin::signed(BOB), [0, 1].into(), ALICE,));
+		assert_eq!(
+			last_event(),
+			Event::Identities(crate::Event::VerifyDataRequestCreated {
+				request_id: 0,
+				requestor: BOB,
+				positions: [0, 1].to_vec(),
+				evaluator: ALICE,
+				fee: 60,
+			})
+		);
+		assert_eq!(Currencies::reserved_balance(CurrencyId::Native, &BOB), 60);
+	});
+}
+
+fn listed_evaluators(
+	field: Option<Hash>,
+	min_price: Option<Balance>,
+	max_price: Option<Balance>,
+) -> Vec<(AccountId, Balance)> {
+	let filter = EvaluatorFilter { field, min_price, max_price };
+	let mut listed: Vec<_> =
+		Identities::list_evaluators(filter, Pagination { offset: 0, limit: MAX_DIRECTORY_PAGE })
+			.into_iter()
+			.map(|listing| (listing.account, listing.price))
+			.collect();
+	listed.sort();
+	listed
+}
+
+#[test]
+fn evaluator_directory_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+
+		let email = BlakeTwo256::hash(b"email");
+		let domain = BlakeTwo256::hash(b"domain");
+		for (evaluator, rate) in [(ALICE, 10), (BOB, 25), (CHARLIE, 100 * EVALUATOR_RATE_BAND)] {
+			assert_ok!(Identities::create_evaluator(
+				Origin::signed(evaluator),
+				"Evaluator".into(),
+				"About".into(),
+				rate,
+			));
+		}
+		assert_ok!(Identities::set_field_prices(Origin::signed(ALICE), [(email, 30)].into()));
+		assert_ok!(Identities::set_field_prices(
+			Origin::signed(BOB),
+			[(email, 5), (domain, 7)].into()
+		));
+
+		// Without a field, the evaluators are filtered by rate. The rates above the last band are
+		// indexed in the last band.
+		assert_eq!(
+			listed_evaluators(None, None, None),
+			[(ALICE, 10), (BOB, 25), (CHARLIE, 100 * EVALUATOR_RATE_BAND)]
+		);
+		assert_eq!(listed_evaluators(None, Some(20), Some(100)), [(BOB, 25)]);
+		assert_eq!(
+			listed_evaluators(None, Some(100 * EVALUATOR_RATE_BAND), None),
+			[(CHARLIE, 100 * EVALUATOR_RATE_BAND)]
+		);
+		assert!(listed_evaluators(None, Some(11), Some(24)).is_empty());
+
+		// With a field, the evaluators are filtered by the published price of the field.
+		assert_eq!(listed_evaluators(Some(email), None, None), [(ALICE, 30), (BOB, 5)]);
+		assert_eq!(listed_evaluators(Some(email), None, Some(10)), [(BOB, 5)]);
+		assert_eq!(listed_evaluators(Some(domain), None, None), [(BOB, 7)]);
+
+		// The replaced prices are unindexed.
+		assert_ok!(Identities::set_field_prices(Origin::signed(BOB), [(domain, 8)].into()));
+		assert_eq!(listed_evaluators(Some(email), None, None), [(ALICE, 30)]);
+		assert_eq!(listed_evaluators(Some(domain), None, None), [(BOB, 8)]);
+		assert_ok!(Identities::set_field_prices(Origin::signed(BOB), [].into()));
+		assert!(listed_evaluators(Some(domain), None, None).is_empty());
+
+		// The suspended evaluators are not listed.
+		assert_ok!(Identities::suspend_evaluator(Origin::root(), ALICE));
+		assert!(listed_evaluators(Some(email), None, None).is_empty());
+		assert!(listed_evaluators(None, None, Some(10)).is_empty());
+
+		let filter = EvaluatorFilter::default();
+		let all = Identities::list_evaluators(filter.clone(), Pagination { offset: 0, limit: 10 });
+		assert_eq!(all.len(), 2);
+		let page = Identities::list_evaluators(filter.clone(), Pagination { offset: 1, limit: 10 });
+		assert_eq!(page, all[1..].to_vec());
+		assert!(Identities::list_evaluators(filter, Pagination { offset: 0, limit: 0 }).is_empty());
+	});
+}
+
+#[test]
+fn set_languages_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
 
 		assert_noop!(
-			Identities::verify_data(Origin::signed(ALICE), BOB, [(1, true)].into(),),
-			Error::<Runtime>::InvalidTranscript
+			Identities::set_languages(Origin::signed(ALICE), [*b"en"].into()),
+			Error::<Runtime>::IdentityNotFound
 		);
 
-		assert_ok!(Identities::verify_data(
+		assert_ok!(Identities::create_identity(
 			Origin::signed(ALICE),
-			BOB,
-			[(0, true), (2, true)].into(),
+			"Alice".into(),
+			IdentityType::Individual,
+			[].into(),
 		));
+		assert_noop!(
+			Identities::set_languages(
+				Origin::signed(ALICE),
+				vec![*b"en"; MAX_LANGUAGES as usize + 1],
+			),
+			Error::<Runtime>::TooManyLanguages
+		);
+		assert_ok!(Identities::set_languages(Origin::signed(ALICE), [*b"vi", *b"en"].into()));
+		assert_eq!(
+			last_event(),
+			Event::Identities(crate::Event::LanguagesUpdated {
+				account_id: ALICE,
+				languages: [*b"vi", *b"en"].to_vec(),
+			})
+		);
+		assert_eq!(Identities::get_languages(&ALICE), [*b"vi", *b"en"].to_vec());
 
-		let verify_requests = Identities::verify_data_requests(&ALICE).unwrap();
-		assert_eq!(verify_requests.len(), 0);
+		// An empty list removes the languages.
+		assert_ok!(Identities::set_languages(Origin::signed(ALICE), [].into()));
+		assert!(!Languages::<Runtime>::contains_key(&ALICE));
+		assert!(Identities::get_languages(&ALICE).is_empty());
+	});
+}
 
-		let identity = Identities::identities(&BOB).unwrap();
+#[test]
+fn encryption_keys_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
 
-		assert_eq!(identity.data[0].is_verified, true);
-		assert_eq!(identity.data[0].verify_by, Some(ALICE));
-		assert_eq!(identity.data[2].is_verified, true);
-		assert_eq!(identity.data[2].verify_by, Some(ALICE));
+		assert_noop!(
+			Identities::set_encryption_key(
+				Origin::signed(ALICE),
+				EncryptionKeyType::X25519,
+				[0; 32]
+			),
+			Error::<Runtime>::IdentityNotFound
+		);
+		assert_ok!(Identities::create_identity(
+			Origin::signed(ALICE),
+			"Alice".into(),
+			IdentityType::Individual,
+			[].into(),
+		));
+		assert_noop!(
+			Identities::rotate_encryption_key(
+				Origin::signed(ALICE),
+				EncryptionKeyType::X25519,
+				[0; 32]
+			),
+			Error::<Runtime>::EncryptionKeyNotFound
+		);
+
+		assert_ok!(Identities::set_encryption_key(
+			Origin::signed(ALICE),
+			EncryptionKeyType::X25519,
+			[0; 32]
+		));
+		assert_eq!(
+			last_event(),
+			Event::Identities(crate::Event::EncryptionKeySet {
+				account_id: ALICE,
+				key_type: EncryptionKeyType::X25519,
+				public: [0; 32],
+			})
+		);
+		assert_eq!(Identities::get_encryption_key(&ALICE), Some([0; 32].to_vec()));
+		assert_noop!(
+			Identities::set_encryption_key(
+				Origin::signed(ALICE),
+				EncryptionKeyType::X25519,
+				[1; 32]
+			),
+			Error::<Runtime>::EncryptionKeyExisted
+		);
+		assert_noop!(
+			Identities::rotate_encryption_key(
+				Origin::signed(ALICE),
+				EncryptionKeyType::X25519,
+				[0; 32]
+			),
+			Error::<Runtime>::SameEncryptionKey
+		);
+
+		System::set_block_number(2);
+		assert_ok!(Identities::rotate_encryption_key(
+			Origin::signed(ALICE),
+			EncryptionKeyType::Sr25519,
+			[0; 32]
+		));
+		assert_eq!(
+			last_event(),
+			Event::Identities(crate::Event::EncryptionKeyRotated {
+				account_id: ALICE,
+				key_type: EncryptionKeyType::Sr25519,
+				public: [0; 32],
+			})
+		);
+		assert_eq!(
+			Identities::encryption_keys(&ALICE).into_inner(),
+			[
+				EncryptionKey {
+					key_type: EncryptionKeyType::X25519,
+					public: [0; 32],
+					registered_at: 1
+				},
+				EncryptionKey {
+					key_type: EncryptionKeyType::Sr25519,
+					public: [0; 32],
+					registered_at: 2
+				},
+			]
+			.to_vec()
+		);
+
+		// The oldest keys are dropped once the history is full.
+		for i in 1..=MAX_ENCRYPTION_KEYS {
+			assert_ok!(Identities::rotate_encryption_key(
+				Origin::signed(ALICE),
+				EncryptionKeyType::X25519,
+				[i as u8; 32]
+			));
+		}
+		let keys = Identities::encryption_keys(&ALICE);
+		assert_eq!(keys.len() as u32, MAX_ENCRYPTION_KEYS);
+		assert_eq!(keys[0].public, [1; 32]);
+		assert_eq!(
+			Identities::get_encryption_key(&ALICE),
+			Some([MAX_ENCRYPTION_KEYS as u8; 32].to_vec())
+		);
+	});
+}
+
+#[test]
+fn verify_data_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+
+		// Test update identity name
+		assert_ok!(Identities::create_evaluator(
+			Origin::signed(ALICE),
+			"Alice".into(),
+			"About Alice".into(),
+			10,
+		));
+
+		// Test update identity name
+		assert_ok!(Identities::create_identity(
+			Origin::signed(BOB),
+			"BOB".into(),
+			IdentityType::Individual,
+			[
+				IdentityFieldInput {
+					name: "field_a".into(),
+					value: "value_a".into(),
+					verify_method: VerifyMethod::Evaluator,
+				},
+				IdentityFieldInput {
+					name: "field_b".into(),
+					value: "value_b".into(),
+					verify_method: VerifyMethod::Evaluator,
+				},
+				IdentityFieldInput {
+					name: "field_c".into(),
+					value: "value_c".into(),
+					verify_method: VerifyMethod::Evaluator,
+				},
+			]
+			.into(),
+		));
+
+		assert_ok!(Identities::request_to_verify(Origin::signed(BOB), [0, 2].into(), ALICE,));
+		assert_eq!(Currencies::free_balance(CurrencyId::Native, &ALICE), 900);
+		assert_eq!(Currencies::free_balance(CurrencyId::Native, &BOB), 980);
+
+		assert_noop!(
+			Identities::verify_data(Origin::signed(ALICE), 0, [(1, true)].into(),),
+			Error::<Runtime>::InvalidTranscript
+		);
+		assert_noop!(
+			Identities::verify_data(Origin::signed(ALICE), 0, [(0, true), (0, false)].into(),),
+			Error::<Runtime>::InvalidTranscript
+		);
+		assert_noop!(
+			Identities::verify_data(Origin::signed(ALICE), 0, [].into(),),
+			Error::<Runtime>::InvalidTranscript
+		);
+		assert_noop!(
+			Identities::verify_data(Origin::signed(CHARLIE), 0, [(0, true), (2, true)].into(),),
+			Error::<Runtime>::NotRequestEvaluator
+		);
+		assert_noop!(
+			Identities::verify_data(Origin::signed(ALICE), 1, [(0, true), (2, true)].into(),),
+			Error::<Runtime>::VerifyRequestNotFound
+		);
+
+		assert_ok!(Identities::verify_data(
+			Origin::signed(ALICE),
+			0,
+			[(0, true), (2, true)].into(),
+		));
+		assert_eq!(
+			last_event(),
+			Event::Identities(crate::Event::DataVerified {
+				request_id: 0,
+				account: BOB,
+				positions: [0, 2].to_vec(),
+				evaluator: ALICE,
+				fee: 20,
+			})
+		);
+
+		assert_eq!(Identities::verify_requests(0).unwrap().status, VerifyRequestStatus::Verified);
+		assert_noop!(
+			Identities::verify_data(Origin::signed(ALICE), 0, [(0, true), (2, true)].into(),),
+			Error::<Runtime>::VerifyRequestNotPending
+		);
+		assert_eq!(Currencies::free_balance(CurrencyId::Native, &ALICE), 920);
+		assert_eq!(Currencies::reserved_balance(CurrencyId::Native, &BOB), 0);
+
+		let identity = Identities::identities(&BOB).unwrap();
+
+		assert_eq!(identity.data[0].is_verified, true);
+		assert_eq!(identity.data[0].verify_by, Some(ALICE));
+		assert_eq!(identity.data[2].is_verified, true);
+		assert_eq!(identity.data[2].verify_by, Some(ALICE));
+
+		// The verified fields can be queried by name.
+		assert_eq!(Identities::get_verified_field(&BOB, b"field_a"), Some(b"value_a".to_vec()));
+		assert!(Identities::is_field_verified(&BOB, b"field_c"));
+		assert_eq!(Identities::get_verified_field(&BOB, b"field_b"), None);
+		assert!(!Identities::is_field_verified(&BOB, b"field_b"));
+		assert!(!Identities::is_field_verified(&BOB, b"field_d"));
+		assert!(!Identities::is_field_verified(&CHARLIE, b"field_a"));
+	});
+}
+
+#[test]
+fn partial_verify_data_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+
+		assert_ok!(Identities::create_evaluator(
+			Origin::signed(ALICE),
+			"Alice".into(),
+			"About Alice".into(),
+			10,
+		));
+		assert_ok!(Identities::create_identity(
+			Origin::signed(BOB),
+			"BOB".into(),
+			IdentityType::Individual,
+			[
+				IdentityFieldInput {
+					name: "field_a".into(),
+					value: "value_a".into(),
+					verify_method: VerifyMethod::Evaluator,
+				},
+				IdentityFieldInput {
+					name: "field_b".into(),
+					value: "value_b".into(),
+					verify_method: VerifyMethod::Evaluator,
+				},
+				IdentityFieldInput {
+					name: "field_c".into(),
+					value: "value_c".into(),
+					verify_method: VerifyMethod::Evaluator,
+				},
+			]
+			.into(),
+		));
+		assert_ok!(Identities::request_to_verify(Origin::signed(BOB), [0, 1, 2].into(), ALICE,));
+		assert_eq!(Currencies::reserved_balance(CurrencyId::Native, &BOB), 30);
+
+		// The evaluator verifies a part of the positions, the rest keeps pending.
+		assert_ok!(Identities::verify_data(Origin::signed(ALICE), 0, [(1, true)].into(),));
+		assert_eq!(
+			last_event(),
+			Event::Identities(crate::Event::DataVerified {
+				request_id: 0,
+				account: BOB,
+				positions: [1].to_vec(),
+				evaluator: ALICE,
+				fee: 10,
+			})
+		);
+		let request = Identities::verify_requests(0).unwrap();
+		assert_eq!(request.status, VerifyRequestStatus::Pending);
+		assert_eq!(request.positions, vec![0, 2]);
+		assert_eq!(request.fee, 20);
+		assert_eq!(Currencies::free_balance(CurrencyId::Native, &ALICE), 910);
+		assert_eq!(Currencies::reserved_balance(CurrencyId::Native, &BOB), 20);
+		assert!(Identities::identities(&BOB).unwrap().data[1].is_verified);
+
+		// The responded positions can not be responded again.
+		assert_noop!(
+			Identities::verify_data(Origin::signed(ALICE), 0, [(1, true), (2, true)].into(),),
+			Error::<Runtime>::InvalidTranscript
+		);
+
+		// The last response releases the rest of the fee and completes the request.
+		assert_ok!(Identities::verify_data(
+			Origin::signed(ALICE),
+			0,
+			[(2, false), (0, true)].into(),
+		));
+		assert_eq!(
+			last_event(),
+			Event::Identities(crate::Event::DataVerified {
+				request_id: 0,
+				account: BOB,
+				positions: [0].to_vec(),
+				evaluator: ALICE,
+				fee: 20,
+			})
+		);
+		let request = Identities::verify_requests(0).unwrap();
+		assert_eq!(request.status, VerifyRequestStatus::Verified);
+		assert!(request.positions.is_empty());
+		assert_eq!(Currencies::free_balance(CurrencyId::Native, &ALICE), 930);
+		assert_eq!(Currencies::reserved_balance(CurrencyId::Native, &BOB), 0);
+
+		let identity = Identities::identities(&BOB).unwrap();
+		assert!(identity.data[0].is_verified);
+		assert!(!identity.data[2].is_verified);
+
+		// The pending positions of a partially verified request are refunded on cancellation.
+		assert_ok!(Identities::request_to_verify(Origin::signed(BOB), [1, 2].into(), ALICE,));
+		assert_ok!(Identities::verify_data(Origin::signed(ALICE), 1, [(1, true)].into(),));
+		assert_ok!(Identities::cancel_verify_request(Origin::signed(BOB), 1));
+		assert_eq!(
+			last_event(),
+			Event::Identities(crate::Event::VerifyDataRequestCancelled {
+				request_id: 1,
+				requestor: BOB,
+				evaluator: ALICE,
+				positions: [2].to_vec(),
+				refund: 10,
+			})
+		);
+		assert_eq!(Currencies::reserved_balance(CurrencyId::Native, &BOB), 0);
+	});
+}
+
+#[test]
+fn did_document_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+
+		assert_eq!(Identities::did_document(&BOB), None);
+
+		assert_ok!(Identities::create_evaluator(
+			Origin::signed(ALICE),
+			"Alice".into(),
+			"About Alice".into(),
+			10,
+		));
+		assert_ok!(Identities::create_identity(
+			Origin::signed(BOB),
+			"Bob".into(),
+			IdentityType::Individual,
+			[
+				IdentityFieldInput {
+					name: "domain".into(),
+					value: "atscale.xyz".into(),
+					verify_method: VerifyMethod::Domain,
+				},
+				IdentityFieldInput {
+					name: "email".into(),
+					value: "bob@atscale.xyz".into(),
+					verify_method: VerifyMethod::Email,
+				},
+				IdentityFieldInput {
+					name: "legal_name".into(),
+					value: "Bob".into(),
+					verify_method: VerifyMethod::Evaluator,
+				},
+				IdentityFieldInput {
+					name: "support_email".into(),
+					value: "support@atscale.xyz".into(),
+					verify_method: VerifyMethod::Email,
+				},
+			]
+			.into(),
+		));
+
+		let bob_did = b"did:libra:0x02000000000000000000000000000000".to_vec();
+		let document = Identities::did_document(&BOB).unwrap();
+		assert_eq!(document.id, bob_did);
+		assert_eq!(document.name, b"Bob".to_vec());
+		assert_eq!(document.controller, None);
+		assert!(document.verification_method.is_empty());
+		assert!(document.key_agreement.is_empty());
+		// The unverified fields are not services.
+		assert!(document.service.is_empty());
+
+		assert_ok!(Identities::request_to_verify(Origin::signed(BOB), [0, 1, 2].into(), ALICE));
+		assert_ok!(Identities::verify_data(
+			Origin::signed(ALICE),
+			0,
+			[(0, true), (1, true), (2, true)].into(),
+		));
+		assert_ok!(Identities::set_encryption_key(
+			Origin::signed(BOB),
+			EncryptionKeyType::X25519,
+			[1; 32]
+		));
+		assert_ok!(Identities::rotate_encryption_key(
+			Origin::signed(BOB),
+			EncryptionKeyType::Sr25519,
+			[2; 32]
+		));
+		assert_ok!(Identities::create_identity(
+			Origin::signed(CHARLIE),
+			"Atscale".into(),
+			IdentityType::Organization,
+			[].into(),
+		));
+		assert_ok!(Identities::invite_member(Origin::signed(CHARLIE), BOB));
+		assert_ok!(Identities::accept_membership(Origin::signed(BOB), CHARLIE));
+
+		let document = Identities::did_document(&BOB).unwrap();
+		assert_eq!(
+			document.controller,
+			Some(b"did:libra:0x03000000000000000000000000000000".to_vec())
+		);
+		assert_eq!(
+			document.verification_method,
+			vec![
+				DidVerificationMethod {
+					id: [&bob_did[..], b"#key-0"].concat(),
+					method_type: b"X25519KeyAgreementKey2020".to_vec(),
+					controller: bob_did.clone(),
+					public_key_multibase: [&b"fec01"[..], &b"01".repeat(32)[..]].concat(),
+				},
+				DidVerificationMethod {
+					id: [&bob_did[..], b"#key-1"].concat(),
+					method_type: b"Sr25519VerificationKey2020".to_vec(),
+					controller: bob_did.clone(),
+					public_key_multibase: [&b"fef01"[..], &b"02".repeat(32)[..]].concat(),
+				},
+			]
+		);
+		// The current key is the key agreement method.
+		assert_eq!(document.key_agreement, vec![[&bob_did[..], b"#key-1"].concat()]);
+		assert_eq!(
+			document.service,
+			vec![
+				DidService {
+					id: [&bob_did[..], b"#domain-0"].concat(),
+					service_type: b"LinkedDomains".to_vec(),
+					service_endpoint: b"https://atscale.xyz".to_vec(),
+				},
+				DidService {
+					id: [&bob_did[..], b"#email-1"].concat(),
+					service_type: b"Email".to_vec(),
+					service_endpoint: b"mailto:bob@atscale.xyz".to_vec(),
+				},
+			]
+		);
+	});
+}
+
+#[test]
+fn link_external_account_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+
+		// The `personal_sign` of `did:libra:0x02000000000000000000000000000000`, the DID of Bob,
+		// by the key of 0x2c7536e3605d9c16a7a3d7b1898e529396a65c23.
+		let signature: [u8; 65] = [
+			0xde, 0x75, 0x8b, 0x0e, 0x65, 0xea, 0x1d, 0x23, 0x5f, 0x05, 0x69, 0x26, 0xeb, 0xb7,
+			0x0b, 0x16, 0xc4, 0x20, 0x77, 0xe7, 0x4f, 0xca, 0xcf, 0x99, 0x95, 0x06, 0x82, 0x5e,
+			0xeb, 0xb2, 0x29, 0x43, 0x46, 0x5f, 0xb2, 0xf5, 0xd5, 0x7f, 0xa0, 0xde, 0x18, 0x6c,
+			0x4c, 0x48, 0xe9, 0xd5, 0x81, 0x47, 0xc2, 0xe7, 0xc1, 0x27, 0x08, 0xc6, 0x00, 0x1a,
+			0x7e, 0xda, 0x61, 0x5b, 0x15, 0xce, 0x45, 0x80, 0x1b,
+		];
+
+		assert_noop!(
+			Identities::link_external_account(
+				Origin::signed(BOB),
+				"ethereum".into(),
+				ExternalAccountProof::Ethereum(signature)
+			),
+			Error::<Runtime>::IdentityNotFound
+		);
+		assert_ok!(Identities::create_identity(
+			Origin::signed(BOB),
+			"Bob".into(),
+			IdentityType::Individual,
+			[].into(),
+		));
+
+		// The external accounts cannot be added as plain data.
+		assert_noop!(
+			Identities::add_identity_data(
+				Origin::signed(BOB),
+				IdentityFieldInput {
+					name: "ethereum".into(),
+					value: "0x2c7536e3605d9c16a7a3d7b1898e529396a65c23".into(),
+					verify_method: VerifyMethod::ExternalSignature,
+				}
+			),
+			Error::<Runtime>::ExternalProofRequired
+		);
+		assert_noop!(
+			Identities::link_external_account(
+				Origin::signed(BOB),
+				"ethereum".into(),
+				ExternalAccountProof::Ethereum([0; 65])
+			),
+			Error::<Runtime>::InvalidExternalProof
+		);
+
+		assert_ok!(Identities::link_external_account(
+			Origin::signed(BOB),
+			"ethereum".into(),
+			ExternalAccountProof::Ethereum(signature)
+		));
+		let value = b"0x2c7536e3605d9c16a7a3d7b1898e529396a65c23".to_vec();
+		assert_eq!(
+			last_event(),
+			Event::Identities(crate::Event::ExternalAccountLinked {
+				account_id: BOB,
+				name: "ethereum".into(),
+				value: value.clone(),
+			})
+		);
+		let identity = Identities::identities(&BOB).unwrap();
+		assert_eq!(identity.data[0].verify_method, VerifyMethod::ExternalSignature);
+		assert!(identity.data[0].is_verified);
+		assert_eq!(Identities::get_verified_field(&BOB, b"ethereum"), Some(value.clone()));
+
+		assert_noop!(
+			Identities::link_external_account(
+				Origin::signed(BOB),
+				"ethereum".into(),
+				ExternalAccountProof::Ethereum(signature)
+			),
+			Error::<Runtime>::DuplicateFieldName
+		);
+
+		// The signature of the DID of Bob does not prove the address for Charlie.
+		assert_ok!(Identities::create_identity(
+			Origin::signed(CHARLIE),
+			"Charlie".into(),
+			IdentityType::Individual,
+			[].into(),
+		));
+		assert_ok!(Identities::link_external_account(
+			Origin::signed(CHARLIE),
+			"ethereum".into(),
+			ExternalAccountProof::Ethereum(signature)
+		));
+		assert_ne!(Identities::get_verified_field(&CHARLIE, b"ethereum"), Some(value));
+	});
+}
+
+#[test]
+fn handles_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		let handle =
+			|value: &str| -> HandleOf<Runtime> { value.as_bytes().to_vec().try_into().unwrap() };
+
+		assert_noop!(
+			Identities::register_handle(Origin::signed(ALICE), "alice".into()),
+			Error::<Runtime>::IdentityNotFound
+		);
+		for (account_id, name) in [(ALICE, "Alice"), (BOB, "Bob"), (CHARLIE, "Charlie")] {
+			assert_ok!(Identities::create_identity(
+				Origin::signed(account_id),
+				name.into(),
+				IdentityType::Individual,
+				[].into(),
+			));
+		}
+
+		// The handles are lowercase letters, digits, `_` and `-`.
+		for handle in ["al", "Alice", "alice.eth", "alice-in-wonderland"] {
+			assert_noop!(
+				Identities::register_handle(Origin::signed(ALICE), handle.into()),
+				Error::<Runtime>::InvalidHandle
+			);
+		}
+
+		assert_ok!(Identities::register_handle(Origin::signed(ALICE), "alice".into()));
+		assert_eq!(
+			last_event(),
+			Event::Identities(crate::Event::HandleRegistered {
+				account_id: ALICE,
+				handle: "alice".into(),
+				expires_at: 1 + HANDLE_PERIOD,
+			})
+		);
+		assert_eq!(Currencies::reserved_balance(CurrencyId::Native, &ALICE), HANDLE_DEPOSIT);
+		assert_eq!(
+			Identities::named_reserves(&ALICE, (HANDLE_DEPOSIT_RESERVE, CurrencyId::Native)),
+			HANDLE_DEPOSIT
+		);
+		assert_eq!(Identities::get_handle(&ALICE), Some("alice".into()));
+
+		assert_noop!(
+			Identities::register_handle(Origin::signed(ALICE), "alice_2".into()),
+			Error::<Runtime>::HandleExisted
+		);
+		assert_noop!(
+			Identities::register_handle(Origin::signed(BOB), "alice".into()),
+			Error::<Runtime>::HandleTaken
+		);
+
+		// The renewal extends the registration from its expiry.
+		System::set_block_number(50);
+		assert_ok!(Identities::renew_handle(Origin::signed(ALICE)));
+		assert_eq!(
+			Identities::handles(&handle("alice")).unwrap().expires_at,
+			1 + 2 * HANDLE_PERIOD
+		);
+
+		// The deposit moves with the handle.
+		assert_ok!(Identities::register_handle(Origin::signed(CHARLIE), "charlie".into()));
+		assert_noop!(
+			Identities::transfer_handle(Origin::signed(ALICE), CHARLIE),
+			Error::<Runtime>::HandleExisted
+		);
+		assert_ok!(Identities::transfer_handle(Origin::signed(ALICE), BOB));
+		assert_eq!(
+			last_event(),
+			Event::Identities(crate::Event::HandleTransferred {
+				from: ALICE,
+				to: BOB,
+				handle: "alice".into(),
+			})
+		);
+		assert_eq!(Currencies::reserved_balance(CurrencyId::Native, &ALICE), 0);
+		assert_eq!(Currencies::reserved_balance(CurrencyId::Native, &BOB), HANDLE_DEPOSIT);
+		assert_eq!(Identities::get_handle(&ALICE), None);
+		assert_eq!(Identities::get_handle(&BOB), Some("alice".into()));
+
+		assert_ok!(Identities::release_handle(Origin::signed(BOB)));
+		assert_eq!(
+			last_event(),
+			Event::Identities(crate::Event::HandleReleased {
+				account_id: BOB,
+				handle: "alice".into(),
+			})
+		);
+		assert_eq!(Currencies::reserved_balance(CurrencyId::Native, &BOB), 0);
+		assert!(Identities::handles(&handle("alice")).is_none());
+		assert_noop!(
+			Identities::release_handle(Origin::signed(BOB)),
+			Error::<Runtime>::HandleNotFound
+		);
+
+		// An expired handle is not surfaced and can be registered by another account.
+		System::set_block_number(51 + HANDLE_PERIOD);
+		assert_eq!(Identities::get_handle(&CHARLIE), None);
+		assert_noop!(
+			Identities::transfer_handle(Origin::signed(CHARLIE), ALICE),
+			Error::<Runtime>::HandleExpired
+		);
+		assert_ok!(Identities::register_handle(Origin::signed(ALICE), "charlie".into()));
+		assert_eq!(Identities::handles(&handle("charlie")).unwrap().owner, ALICE);
+		assert_eq!(Currencies::reserved_balance(CurrencyId::Native, &CHARLIE), 0);
+		assert_eq!(Identities::account_handles(&CHARLIE), None);
+	});
+}
+
+#[test]
+fn review_evaluator_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+
+		assert_ok!(Identities::create_evaluator(
+			Origin::signed(ALICE),
+			"Alice".into(),
+			"About Alice".into(),
+			10,
+		));
+		assert_ok!(Identities::create_identity(
+			Origin::signed(BOB),
+			"BOB".into(),
+			IdentityType::Individual,
+			[IdentityFieldInput {
+				name: "field_a".into(),
+				value: "value_a".into(),
+				verify_method: VerifyMethod::Evaluator,
+			},]
+			.into(),
+		));
+		assert_ok!(Identities::request_to_verify(Origin::signed(BOB), [0].into(), ALICE,));
+
+		// Only the requestors of the completed verify data requests can review the evaluator.
+		assert_noop!(
+			Identities::review_evaluator(Origin::signed(BOB), ALICE, 5, "Fast".into()),
+			Error::<Runtime>::NoCompletedVerifyRequest
+		);
+
+		assert_ok!(Identities::verify_data(Origin::signed(ALICE), 0, [(0, true)].into(),));
+		assert_noop!(
+			Identities::review_evaluator(Origin::signed(BOB), ALICE, 0, "Fast".into()),
+			Error::<Runtime>::InvalidReviewStars
+		);
+		assert_noop!(
+			Identities::review_evaluator(Origin::signed(BOB), ALICE, 6, "Fast".into()),
+			Error::<Runtime>::InvalidReviewStars
+		);
+		assert_noop!(
+			Identities::review_evaluator(Origin::signed(BOB), CHARLIE, 5, "Fast".into()),
+			Error::<Runtime>::EvaluatorNotFound
+		);
+
+		assert_ok!(Identities::review_evaluator(Origin::signed(BOB), ALICE, 4, "Fast".into()));
+		let content_digest = BlakeTwo256::hash_of(&b"Fast".to_vec());
+		assert_eq!(
+			last_event(),
+			Event::Identities(crate::Event::EvaluatorReviewed {
+				evaluator: ALICE,
+				reviewer: BOB,
+				stars: 4,
+				content_digest,
+			})
+		);
+		assert_eq!(
+			Identities::evaluator_reviews(&ALICE, &BOB),
+			Some(EvaluatorReview { stars: 4, content_digest })
+		);
+		let evaluator = Identities::evaluators(&ALICE).unwrap();
+		assert_eq!(evaluator.review_count, 1);
+		assert_eq!(evaluator.total_stars, 4);
+
+		// A completed verify data request can be reviewed once.
+		assert_noop!(
+			Identities::review_evaluator(Origin::signed(BOB), ALICE, 5, "Fast".into()),
+			Error::<Runtime>::NoCompletedVerifyRequest
+		);
+	});
+}
+
+#[test]
+fn cancel_verify_request_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+
+		assert_ok!(Identities::create_evaluator(
+			Origin::signed(ALICE),
+			"Alice".into(),
+			"About Alice".into(),
+			10,
+		));
+		assert_ok!(Identities::create_identity(
+			Origin::signed(BOB),
+			"BOB".into(),
+			IdentityType::Individual,
+			[
+				IdentityFieldInput {
+					name: "field_a".into(),
+					value: "value_a".into(),
+					verify_method: VerifyMethod::Evaluator,
+				},
+				IdentityFieldInput {
+					name: "field_b".into(),
+					value: "value_b".into(),
+					verify_method: VerifyMethod::Evaluator,
+				},
+			]
+			.into(),
+		));
+		assert_ok!(Identities::request_to_verify(Origin::signed(BOB), [0, 1].into(), ALICE,));
+		assert_eq!(Currencies::reserved_balance(CurrencyId::Native, &BOB), 20);
+
+		assert_noop!(
+			Identities::cancel_verify_request(Origin::signed(BOB), 1),
+			Error::<Runtime>::VerifyRequestNotFound
+		);
+		assert_noop!(
+			Identities::cancel_verify_request(Origin::signed(CHARLIE), 0),
+			Error::<Runtime>::NotRequestor
+		);
+
+		assert_ok!(Identities::cancel_verify_request(Origin::signed(BOB), 0));
+		assert_eq!(
+			last_event(),
+			Event::Identities(crate::Event::VerifyDataRequestCancelled {
+				request_id: 0,
+				requestor: BOB,
+				evaluator: ALICE,
+				positions: [0, 1].to_vec(),
+				refund: 20,
+			})
+		);
+		assert_eq!(Identities::verify_requests(0).unwrap().status, VerifyRequestStatus::Cancelled);
+		assert_noop!(
+			Identities::cancel_verify_request(Origin::signed(BOB), 0),
+			Error::<Runtime>::VerifyRequestNotPending
+		);
+		assert_eq!(Currencies::reserved_balance(CurrencyId::Native, &BOB), 0);
+		assert_eq!(Currencies::free_balance(CurrencyId::Native, &BOB), 1_000);
+
+		// The cancelled request does not expire.
+		Identities::on_initialize(1 + VERIFY_REQUEST_TIMEOUT);
+		assert_eq!(Currencies::reserved_balance(CurrencyId::Native, &ALICE), EVALUATOR_BONDING);
+	});
+}
+
+#[test]
+fn expired_verify_request_is_refunded() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+
+		assert_ok!(Identities::create_evaluator(
+			Origin::signed(ALICE),
+			"Alice".into(),
+			"About Alice".into(),
+			10,
+		));
+		assert_ok!(Identities::create_identity(
+			Origin::signed(BOB),
+			"BOB".into(),
+			IdentityType::Individual,
+			[IdentityFieldInput {
+				name: "field_a".into(),
+				value: "value_a".into(),
+				verify_method: VerifyMethod::Evaluator,
+			},]
+			.into(),
+		));
+		assert_ok!(Identities::request_to_verify(Origin::signed(BOB), [0].into(), ALICE,));
+
+		let deadline = 1 + VERIFY_REQUEST_TIMEOUT;
+		Identities::on_initialize(deadline - 1);
+		assert_eq!(Identities::verify_requests(0).unwrap().status, VerifyRequestStatus::Pending);
+
+		Identities::on_initialize(deadline);
+		assert_eq!(
+			last_event(),
+			Event::Identities(crate::Event::VerifyDataRequestExpired {
+				request_id: 0,
+				requestor: BOB,
+				evaluator: ALICE,
+				refund: 10,
+				penalty: VERIFY_REQUEST_PENALTY,
+			})
+		);
+		assert_eq!(Identities::verify_requests(0).unwrap().status, VerifyRequestStatus::Expired);
+		assert_eq!(Currencies::reserved_balance(CurrencyId::Native, &BOB), 0);
+		assert_eq!(
+			Currencies::free_balance(CurrencyId::Native, &BOB),
+			1_000 + VERIFY_REQUEST_PENALTY
+		);
+		assert_eq!(
+			Currencies::reserved_balance(CurrencyId::Native, &ALICE),
+			EVALUATOR_BONDING - VERIFY_REQUEST_PENALTY
+		);
+
+		assert_noop!(
+			Identities::verify_data(Origin::signed(ALICE), 0, [(0, true)].into(),),
+			Error::<Runtime>::VerifyRequestNotPending
+		);
+	});
+}
+
+#[test]
+fn expired_verify_request_penalty_is_capped_by_bond() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+
+		assert_ok!(Identities::create_evaluator(
+			Origin::signed(ALICE),
+			"Alice".into(),
+			"About Alice".into(),
+			10,
+		));
+		assert_eq!(
+			Identities::named_reserves(ALICE, (EVALUATOR_BOND_RESERVE, CurrencyId::Native)),
+			EVALUATOR_BONDING
+		);
+		assert_ok!(Identities::create_identity(
+			Origin::signed(BOB),
+			"BOB".into(),
+			IdentityType::Individual,
+			[IdentityFieldInput {
+				name: "field_a".into(),
+				value: "value_a".into(),
+				verify_method: VerifyMethod::Evaluator,
+			},]
+			.into(),
+		));
+		assert_ok!(Identities::request_to_verify(Origin::signed(BOB), [0].into(), ALICE,));
+		assert_eq!(Identities::named_reserves(BOB, (VERIFY_FEE_RESERVE, CurrencyId::Native)), 10);
+
+		// Only 2 tokens are left of the bond, while 50 tokens are reserved by another pallet.
+		assert_eq!(
+			Currencies::slash_reserved(CurrencyId::Native, &ALICE, EVALUATOR_BONDING - 2),
+			0
+		);
+		NamedReserves::<Runtime>::insert(ALICE, (EVALUATOR_BOND_RESERVE, CurrencyId::Native), 2);
+		assert_ok!(Currencies::reserve(CurrencyId::Native, &ALICE, 50));
+
+		Identities::on_initialize(1 + VERIFY_REQUEST_TIMEOUT);
+		assert_eq!(
+			last_event(),
+			Event::Identities(crate::Event::VerifyDataRequestExpired {
+				request_id: 0,
+				requestor: BOB,
+				evaluator: ALICE,
+				refund: 10,
+				penalty: 2,
+			})
+		);
+		assert_eq!(Currencies::reserved_balance(CurrencyId::Native, &ALICE), 50);
+		assert_eq!(
+			Identities::named_reserves(ALICE, (EVALUATOR_BOND_RESERVE, CurrencyId::Native)),
+			0
+		);
+		assert_eq!(Identities::named_reserves(BOB, (VERIFY_FEE_RESERVE, CurrencyId::Native)), 0);
+	});
+}
+
+#[test]
+fn suspend_evaluator_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+
+		assert_ok!(Identities::create_evaluator(
+			Origin::signed(ALICE),
+			"Alice".into(),
+			"About Alice".into(),
+			10,
+		));
+		assert_ok!(Identities::create_identity(
+			Origin::signed(BOB),
+			"BOB".into(),
+			IdentityType::Individual,
+			[IdentityFieldInput {
+				name: "field_a".into(),
+				value: "value_a".into(),
+				verify_method: VerifyMethod::Evaluator,
+			},]
+			.into(),
+		));
+		assert_ok!(Identities::request_to_verify(Origin::signed(BOB), [0].into(), ALICE,));
+
+		// Only the governance can suspend an evaluator.
+		assert_noop!(
+			Identities::suspend_evaluator(Origin::signed(BOB), ALICE),
+			sp_runtime::DispatchError::BadOrigin
+		);
+		assert_noop!(
+			Identities::suspend_evaluator(Origin::root(), CHARLIE),
+			Error::<Runtime>::EvaluatorNotFound
+		);
+
+		assert_ok!(Identities::suspend_evaluator(Origin::root(), ALICE));
+		assert_eq!(
+			last_event(),
+			Event::Identities(crate::Event::EvaluatorSuspended { account: ALICE })
+		);
+		assert!(Identities::suspended_evaluators(&ALICE));
+		// The bond stays frozen while the evaluator is suspended.
+		assert_eq!(Currencies::reserved_balance(CurrencyId::Native, &ALICE), EVALUATOR_BONDING);
+
+		assert_noop!(
+			Identities::request_to_verify(Origin::signed(BOB), [0].into(), ALICE,),
+			Error::<Runtime>::EvaluatorSuspended
+		);
+		assert_noop!(
+			Identities::verify_data(Origin::signed(ALICE), 0, [(0, true)].into(),),
+			Error::<Runtime>::EvaluatorSuspended
+		);
+
+		assert_ok!(Identities::reinstate_evaluator(Origin::root(), ALICE));
+		assert_eq!(
+			last_event(),
+			Event::Identities(crate::Event::EvaluatorReinstated { account: ALICE })
+		);
+		assert_noop!(
+			Identities::reinstate_evaluator(Origin::root(), ALICE),
+			Error::<Runtime>::EvaluatorNotSuspended
+		);
+		assert_ok!(Identities::verify_data(Origin::signed(ALICE), 0, [(0, true)].into(),));
+	});
+}
+
+#[test]
+fn update_credibility_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+
+		assert_ok!(Identities::create_identity(
+			Origin::signed(ALICE),
+			"Alice".into(),
+			IdentityType::Individual,
+			[].into(),
+		));
+
+		// The credibility is capped at the max credibility.
+		assert_ok!(Identities::increase_credibility(&ALICE, MAX_CREDIBILITY));
+		assert_eq!(
+			last_event(),
+			Event::Identities(crate::Event::CredibilityUpdated {
+				account_id: ALICE,
+				credibility: MAX_CREDIBILITY,
+			})
+		);
+
+		assert_ok!(Identities::decrease_credibility(&ALICE, 10));
+		assert_eq!(
+			last_event(),
+			Event::Identities(crate::Event::CredibilityUpdated {
+				account_id: ALICE,
+				credibility: MAX_CREDIBILITY - 10,
+			})
+		);
+		assert_eq!(Identities::get_credibility(&ALICE), Ok(MAX_CREDIBILITY - 10));
+	});
+}
+
+#[test]
+fn dispute_records_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+
+		// The rates of an account without dispute are zero.
+		assert_eq!(Identities::get_dispute_record(&ALICE), DisputeRecord::default());
+		assert_eq!(Identities::get_dispute_record(&ALICE).loss_rate(), Permill::zero());
+		assert_eq!(Identities::get_dispute_record(&ALICE).refund_rate(), Permill::zero());
+
+		// The records are kept for the accounts without identity.
+		for _ in 0..4 {
+			Identities::note_dispute_opened(&ALICE);
+		}
+		Identities::note_dispute_resolved(&BOB, &ALICE);
+		Identities::note_dispute_resolved(&ALICE, &BOB);
+		Identities::note_dispute_resolved(&ALICE, &CHARLIE);
+		assert_eq!(
+			Identities::dispute_records(&ALICE),
+			DisputeRecord { opened: 4, won: 2, lost: 1 }
+		);
+		assert_eq!(
+			Identities::get_dispute_record(&ALICE).loss_rate(),
+			Permill::from_rational(1u32, 3)
+		);
+		assert_eq!(Identities::get_dispute_record(&ALICE).refund_rate(), Permill::from_percent(25));
+
+		// An overturned dispute is moved from the record of the previous winner.
+		Identities::note_dispute_overturned(&ALICE, &BOB);
+		assert_eq!(
+			Identities::dispute_records(&ALICE),
+			DisputeRecord { opened: 4, won: 3, lost: 0 }
+		);
+		assert_eq!(Identities::dispute_records(&BOB), DisputeRecord { opened: 0, won: 0, lost: 2 });
+		assert_eq!(Identities::get_dispute_record(&BOB).loss_rate(), Permill::one());
+	});
+}
+
+#[test]
+fn genesis_identities_and_evaluators_work() {
+	ExtBuilder::default()
+		.identities(vec![(
+			ALICE,
+			"Alice".into(),
+			IdentityType::Individual,
+			vec![IdentityFieldInput {
+				name: "email".into(),
+				value: "hello@atscale.xyz".into(),
+				verify_method: VerifyMethod::Email,
+			}],
+		)])
+		.evaluators(vec![(BOB, "Bob".into(), "KYC service".into(), 10)])
+		.build()
+		.execute_with(|| {
+			let identity = Identities::identities(&ALICE).unwrap();
+			assert_eq!(identity.name, "Alice".as_bytes());
+			assert_eq!(identity.credibility, INITIAL_CREDIBILITY);
+			assert_eq!(identity.data.len(), 1);
+			assert!(!identity.data[0].is_verified);
+
+			let evaluator = Identities::evaluators(&BOB).unwrap();
+			assert_eq!(evaluator.rate, 10);
+			assert_eq!(Currencies::reserved_balance(CurrencyId::Native, &BOB), EVALUATOR_BONDING);
+		});
+}
+
+#[test]
+fn try_state_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+
+		assert_ok!(Identities::create_evaluator(
+			Origin::signed(ALICE),
+			"Alice".into(),
+			"About Alice".into(),
+			10,
+		));
+		assert_ok!(Identities::create_identity(
+			Origin::signed(BOB),
+			"BOB".into(),
+			IdentityType::Individual,
+			[field_input("field_a", "value_a")].into(),
+		));
+		assert_ok!(Identities::register_handle(Origin::signed(BOB), "bob".into()));
+		assert_ok!(Identities::request_to_verify(Origin::signed(BOB), [0].into(), ALICE));
+		assert_ok!(Identities::do_try_state());
+
+		// The fees of the pending requests must be covered by the verify fee reserves.
+		<VerifyRequests<Runtime>>::mutate(0, |request| {
+			request.as_mut().unwrap().fee += 1;
+		});
+		assert_eq!(
+			Identities::do_try_state(),
+			Err("The funds escrowed for the identities exceed the reserves of an account")
+		);
+		<VerifyRequests<Runtime>>::mutate(0, |request| {
+			request.as_mut().unwrap().fee -= 1;
+		});
+
+		// The tracked reserves must be covered by the reserved balances.
+		NamedReserves::<Runtime>::insert(CHARLIE, (VERIFY_FEE_RESERVE, CurrencyId::Native), 1);
+		assert_eq!(
+			Identities::do_try_state(),
+			Err("The tracked reserves of an account exceed its reserved balance")
+		);
+	});
+}
+
+#[test]
+fn migrate_to_v1_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		StorageVersion::new(0).put::<Identities>();
+
+		migrations::v1::MigrateToV1::<Runtime>::on_runtime_upgrade();
+		assert_eq!(Identities::on_chain_storage_version(), 1);
+	});
+}
+
+#[test]
+fn migrate_to_v2_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(5);
+		StorageVersion::new(1).put::<Identities>();
+
+		let old_requests: Vec<(AccountId, Vec<u64>)> = vec![(BOB, vec![0, 2]), (CHARLIE, vec![1])];
+		put_storage_value(
+			b"Identities",
+			b"VerifyDataRequests",
+			&Twox64Concat::hash(&ALICE.encode()),
+			old_requests,
+		);
+
+		migrations::v2::MigrateToV2::<Runtime>::on_runtime_upgrade();
+		assert_eq!(Identities::on_chain_storage_version(), 2);
+
+		let deadline = 5 + VERIFY_REQUEST_TIMEOUT;
+		assert_eq!(
+			get_storage_value::<Vec<(AccountId, Vec<u64>, Balance, BlockNumber)>>(
+				b"Identities",
+				b"VerifyDataRequests",
+				&Twox64Concat::hash(&ALICE.encode()),
+			),
+			Some(vec![(BOB, vec![0, 2], 0, deadline), (CHARLIE, vec![1], 0, deadline)])
+		);
+		assert_eq!(
+			get_storage_value::<Vec<(AccountId, AccountId)>>(
+				b"Identities",
+				b"VerifyRequestDeadlines",
+				&Twox64Concat::hash(&deadline.encode()),
+			),
+			Some(vec![(ALICE, BOB), (ALICE, CHARLIE)])
+		);
+	});
+}
+
+#[test]
+fn migrate_to_v3_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		StorageVersion::new(2).put::<Identities>();
+
+		let old_evaluator = (b"Alice".to_vec(), b"KYC service".to_vec(), 10 as Balance);
+		put_storage_value(
+			b"Identities",
+			b"Evaluators",
+			&Twox64Concat::hash(&ALICE.encode()),
+			old_evaluator,
+		);
+
+		migrations::v3::MigrateToV3::<Runtime>::on_runtime_upgrade();
+		assert_eq!(Identities::on_chain_storage_version(), 3);
+
+		let evaluator = Identities::evaluators(&ALICE).unwrap();
+		assert_eq!(evaluator.name, b"Alice".to_vec());
+		assert_eq!(evaluator.about, b"KYC service".to_vec());
+		assert_eq!(evaluator.rate, 10);
+		assert_eq!(evaluator.review_count, 0);
+		assert_eq!(evaluator.total_stars, 0);
+	});
+}
+
+#[test]
+fn migrate_to_v4_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+
+		assert_ok!(Identities::create_evaluator(
+			Origin::signed(ALICE),
+			"Alice".into(),
+			"About Alice".into(),
+			10,
+		));
+		assert_ok!(Identities::create_identity(
+			Origin::signed(BOB),
+			"BOB".into(),
+			IdentityType::Individual,
+			[IdentityFieldInput {
+				name: "field_a".into(),
+				value: "value_a".into(),
+				verify_method: VerifyMethod::Evaluator,
+			},]
+			.into(),
+		));
+		assert_ok!(Identities::request_to_verify(Origin::signed(BOB), [0].into(), ALICE,));
+		let old_requests: Vec<(AccountId, Vec<u64>, Balance, BlockNumber)> =
+			vec![(BOB, vec![0], 10, 1 + VERIFY_REQUEST_TIMEOUT)];
+		put_storage_value(
+			b"Identities",
+			b"VerifyDataRequests",
+			&Twox64Concat::hash(&ALICE.encode()),
+			old_requests,
+		);
+
+		StorageVersion::new(3).put::<Identities>();
+		let _ = NamedReserves::<Runtime>::remove_all(None);
+
+		migrations::v4::MigrateToV4::<Runtime>::on_runtime_upgrade();
+		assert_eq!(Identities::on_chain_storage_version(), 4);
+		assert_eq!(
+			Identities::named_reserves(ALICE, (EVALUATOR_BOND_RESERVE, CurrencyId::Native)),
+			EVALUATOR_BONDING
+		);
+		assert_eq!(Identities::named_reserves(BOB, (VERIFY_FEE_RESERVE, CurrencyId::Native)), 10);
+	});
+}
+
+#[test]
+fn migrate_to_v5_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		StorageVersion::new(4).put::<Identities>();
+
+		let content_digest = BlakeTwo256::hash_of(&b"Good".to_vec());
+		let old_identity = (
+			b"Alice".to_vec(),
+			IdentityType::Individual,
+			INITIAL_CREDIBILITY,
+			Vec::<IdentityField<Runtime>>::new(),
+			vec![(BOB, content_digest), (CHARLIE, content_digest)],
+		);
+		put_storage_value(
+			b"Identities",
+			b"Identities",
+			&Twox64Concat::hash(&ALICE.encode()),
+			old_identity,
+		);
+
+		migrations::v5::MigrateToV5::<Runtime>::on_runtime_upgrade();
+		assert_eq!(Identities::on_chain_storage_version(), 5);
+
+		let identity = Identities::identities(&ALICE).unwrap();
+		assert_eq!(identity.name, b"Alice".to_vec());
+		assert_eq!(identity.credibility, INITIAL_CREDIBILITY);
+		assert_eq!(Identities::review_counts(&ALICE), 2);
+		assert_eq!(
+			Identities::identity_reviews(&ALICE, &BOB),
+			Some(IdentityReview { reviewer: BOB, content_digest, status: ReviewStatus::Published })
+		);
+		assert!(Identities::is_reviewer(&ALICE, &CHARLIE));
+	});
+}
+
+#[test]
+fn migrate_to_v6_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		StorageVersion::new(5).put::<Identities>();
+
+		let email = BlakeTwo256::hash(b"email");
+		let evaluator = Evaluator::<Runtime> {
+			name: b"Alice".to_vec(),
+			about: b"About Alice".to_vec(),
+			rate: 10,
+			review_count: 0,
+			total_stars: 0,
+		};
+		Evaluators::<Runtime>::insert(ALICE, evaluator.clone());
+		Evaluators::<Runtime>::insert(BOB, Evaluator { rate: 25, ..evaluator });
+		let prices: BoundedVec<_, _> = vec![(email, 30)].try_into().unwrap();
+		FieldPrices::<Runtime>::insert(ALICE, prices);
+		assert!(listed_evaluators(None, None, None).is_empty());
+
+		migrations::v6::MigrateToV6::<Runtime>::on_runtime_upgrade();
+		assert_eq!(Identities::on_chain_storage_version(), 6);
+
+		assert_eq!(listed_evaluators(None, None, None), [(ALICE, 10), (BOB, 25)]);
+		assert_eq!(listed_evaluators(Some(email), None, None), [(ALICE, 30)]);
+	});
+}
+
+#[test]
+fn migrate_to_v7_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		StorageVersion::new(6).put::<Identities>();
+
+		let deadline = 5 + VERIFY_REQUEST_TIMEOUT;
+		let old_requests: Vec<(AccountId, Vec<u64>, Balance, BlockNumber)> =
+			vec![(BOB, vec![0, 2], 20, deadline), (BOB, vec![1], 10, deadline)];
+		put_storage_value(
+			b"Identities",
+			b"VerifyDataRequests",
+			&Twox64Concat::hash(&ALICE.encode()),
+			old_requests,
+		);
+		put_storage_value(
+			b"Identities",
+			b"VerifyRequestDeadlines",
+			&Twox64Concat::hash(&deadline.encode()),
+			vec![(ALICE, BOB), (ALICE, BOB)],
+		);
+
+		migrations::v7::MigrateToV7::<Runtime>::on_runtime_upgrade();
+		assert_eq!(Identities::on_chain_storage_version(), 7);
+		assert_eq!(Identities::on_chain_storage_version(), Identities::current_storage_version());
+
+		assert_eq!(
+			Identities::verify_requests(0),
+			Some(VerifyRequest {
+				requestor: BOB,
+				evaluator: ALICE,
+				positions: vec![0, 2],
+				fee: 20,
+				created_at: 5,
+				deadline,
+				status: VerifyRequestStatus::Pending,
+			})
+		);
+		assert_eq!(Identities::verify_requests(1).unwrap().positions, vec![1]);
+		assert_eq!(VerifyRequestDeadlines::<Runtime>::get(deadline), vec![0, 1]);
+		assert_eq!(
+			get_storage_value::<Vec<(AccountId, Vec<u64>, Balance, BlockNumber)>>(
+				b"Identities",
+				b"VerifyDataRequests",
+				&Twox64Concat::hash(&ALICE.encode()),
+			),
+			None
+		);
+
+		// The new requests are numbered after the migrated ones.
+		assert_ok!(Identities::create_evaluator(
+			Origin::signed(ALICE),
+			"Alice".into(),
+			"About Alice".into(),
+			10,
+		));
+		assert_ok!(Identities::create_identity(
+			Origin::signed(BOB),
+			"BOB".into(),
+			IdentityType::Individual,
+			[IdentityFieldInput {
+				name: "field_a".into(),
+				value: "value_a".into(),
+				verify_method: VerifyMethod::Evaluator,
+			},]
+			.into(),
+		));
+		assert_ok!(Identities::request_to_verify(Origin::signed(BOB), [0].into(), ALICE,));
+		assert!(Identities::verify_requests(2).is_some());
 	});
 }
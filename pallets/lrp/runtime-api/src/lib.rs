@@ -0,0 +1,27 @@
+//! # LRP Runtime API
+//! The runtime API to query the LRP protocol from the clients, e.g. the account statements of the
+//! merchants through the `state_call` RPC.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+pub use primitives::{CurrencyId, PaymentFlows};
+
+sp_api::decl_runtime_apis! {
+	pub trait LrpApi<AccountId, Balance, Hash, BlockNumber> where
+		AccountId: Codec,
+		Balance: Codec,
+		Hash: Codec,
+		BlockNumber: Codec,
+	{
+		/// The funds received and sent by the account through the payments in the currency over
+		/// the block range. The flows are counted by era, so the range is widened to the eras of
+		/// its blocks.
+		fn account_statement(
+			who: AccountId,
+			currency_id: CurrencyId<Hash>,
+			from: BlockNumber,
+			to: BlockNumber,
+		) -> PaymentFlows<Balance>;
+	}
+}
@@ -7,39 +7,207 @@
 //!
 //! - `create_payment` - Create a new payment from payer to the payee.
 //!
-//! Once payment is created, an amount of currency will be reserved in the payer account. The payer
-//! need to include a description and receipt of the payment.   The receipt will be hashed and
-//! stored in off-chain indexing node to provide proof of any dispute. Payment will be expired
-//! automatically by an off-chain worker after a period if it is not accepted or rejected. If a
-//! payment is expired, and the reserved fund will be unreserved.
+//! Once payment is created, the amount is transferred from the payer account to the escrow account
+//! of the pallet, see [`Pallet::escrow_account`]. The payer need to include a description and
+//! receipt of the payment. The receipt will be hashed and stored in off-chain indexing node to
+//! provide proof of any dispute. The description can be encrypted to the encryption key of the
+//! payee, in which case only the hash of the ciphertext is stored and the ciphertext is offchain
+//! indexed as the receipt. Payment will be expired automatically by an off-chain worker after
+//! a period if it is not accepted or rejected. If a payment is expired, the escrowed fund will be
+//! refunded. The off-chain worker evaluates at most `QueueBatchSize` due payments of each queue in
+//! a run, a backlog is worked through in the next runs.
+//!
+//! The escrow account holds the funds of all the open payments, so they cannot be touched by the
+//! other pallets through the payer accounts, and pays them out on every terminal transition of the
+//! payments: to the payee (and the fee accounts) on completion, to the payer or its refund address
+//! on cancellation, rejection, expiry and reclaim, and to the winner of a dispute. The escrow
+//! account needs to be endowed with the existential deposit of the native currency.
+//!
+//! The disputes are settled through [`PaymentProtocol::resolve`] with the `Judgment` of the
+//! dispute, so the built-in dispute resolution and any other arbitration pallet settle the
+//! payments the same way.
+//!
+//! The payments are screened by the `ComplianceCheck` of the runtime on their creation and again
+//! on their acceptance, so the runtimes can refuse the payments of sanctioned accounts.
+//!
+//! The payment hash is derived from the stable fields of the payment only, the id, the payer, the
+//! payee and the account nonce of the payer, so it can be computed with
+//! [`Pallet::compute_payment_hash`] and referenced (e.g. by an invoice) before the payment is
+//! submitted.
+//!
+//! - `create_marketplace_payment` - Create a new payment through a marketplace.
+//!
+//! The same as `create_payment`, with the marketplace account receiving the fee of the payment.
+//!
+//! Both calls take an optional referrer of the payment. If the payee runs a referral program, the
+//! referrer gets the fee share of the program out of the fee of the payment when it is completed.
 //!
 //! - `accept_payment` - Accept a payment.
 //!
 //! Payment needs to be accepted before processing. After some time, if payment is not
-//! accepted it will be expired. An accepted payment cannot be canceled by the payer.
+//! accepted it will be expired. An accepted payment cannot be canceled by the payer. If the
+//! acceptance policy of the payee auto-accepts, the payments meeting the policy are accepted in
+//! the `create_payment` call, so the checkouts do not wait for the payee.
 //!
 //! - `reject_payment` - Reject a payment.
 //!
 //! The payee can reject the payment in some cases such as they can not deliver the promise or
-//! they feel the payment is high risk. If payment is rejected, the reserved fund of the payer will
-//! be unreserved.
+//! they feel the payment is high risk. If payment is rejected, the escrowed fund of the payer will
+//! be refunded.
 //!
 //! - `cancel_payment` - Cancel a payment.
 //!
 //! The payer can cancel pending payments. Once they are accepted, they just can be canceled by the
 //! payee. The locked fund of the payer will be released if payment is canceled.
 //!
+//! - `expire_payment` - Expire a pending payment.
+//!
+//! Anyone can expire a pending payment once the `PendingPaymentWaitingTime` has elapsed. The
+//! escrowed fund of the payer will be refunded. This does the same job as the off-chain worker
+//! but does not depend on it.
+//!
+//! - `reclaim_unfulfilled_payment` - Reclaim the funds of a payment which is not fulfilled.
+//!
+//! The payee has `FulfillmentTime` since the acceptance to fulfill a payment. Once the deadline of
+//! an accepted payment has passed, the payer can get the escrowed funds back (to the refund
+//! address if any) without a dispute. Like the other refund paths, it is never paused.
+//!
+//! - `confirm_payee` - Confirm a high-value payment again.
+//!
+//! The payments from `PayeeConfirmationThreshold` need to be confirmed a second time by the payee
+//! after `PayeeConfirmationCooldown` since the acceptance before they can be fulfilled. It gives the
+//! payer the time to detect a substituted payee address before the funds are transferred.
+//!
+//! - `add_delivery_proof` - Append a delivery proof to a payment.
+//!
+//! After the acceptance, the payee can append delivery proofs such as tracking numbers or signed
+//! delivery confirmations to the payment. Like the receipt, each proof is stored in the off-chain
+//! indexing node and only its hash is kept in the payment, up to `MaxDeliveryProofs` proofs, so
+//! the dispute resolvers can verify the proofs against the payment.
+//!
 //! - `fulfill_payment` - Fulfill a payment.
 //!
 //! After delivery of the promise, the payee can mark the payment as full filled. A full filled
 //! payment will auto-complete by an off-chain worker after a while if there is no dispute.
 //!
+//! The fulfillments and the disputes of each payee are counted by window of the `VelocityPolicy`.
+//! A payee reaching both thresholds of the policy, a burst of fulfillments before many disputes as
+//! in an exit scam, is put on hold: the auto-completion of its fulfilled payments is postponed to
+//! the end of the hold, so their payers still have the time to dispute them.
+//!
+//! - `partially_fulfill_payment` - Fulfill a share of a payment.
+//!
+//! For a split shipment (e.g. 3 of 5 items shipped), the payee can mark the share of the payment
+//! it has delivered. A partially fulfilled payment is completed like a fulfilled one, but only the
+//! share is transferred to the payee and the rest is refunded to the payer, unless the payer
+//! disputes it. The payee can raise the share or fulfill the whole payment once the rest ships.
+//!
 //! - `complete_payment` - Complete a payment.
 //!
 //! Payment can be marked as completed manually by the payer when they feel satisfied. When
 //! payment is completed, and the locked fund of the payer is unlocked and transferred to the payee.
+//! Only the fulfilled share of a partially fulfilled payment is transferred, the rest is refunded.
+//!
+//! The `PaymentFee` of the currency, a flat amount plus a percentage of the transferred amount, is
+//! deducted when the funds are transferred to the payee. The fee goes to the marketplace account of
+//! the payment if any, or the `TreasuryAccount`.
 //! There is nothing that can be done with a completed payment such as a dispute, ...
 //!
+//! Each payment carries a risk score computed when it is created and accepted from the payer
+//! credibility, the payer account age, the dispute history of the payer, the amount, the
+//! category and the share of the resolved disputes the payee lost. See [`risk`] for the details of
+//! the scoring.
+//!
+//! A dispute is counted as opened against the other party in its dispute record kept by the
+//! `IdentitiesManager`, so the payers can check the record of a merchant before paying.
+//!
+//! - `set_identity_requirement` - Set the identity requirement of a merchant.
+//!
+//! The payments above the threshold amount can only be created if the payer and/or payee have an
+//! identity with the minimum credibility. The merchants without their own requirement follow the
+//! `DefaultIdentityRequirement`.
+//!
+//! - `set_acceptance_policy` - Publish the acceptance policy of a merchant.
+//!
+//! The merchant can restrict the amount range, the currencies and the minimum credibility of the
+//! payer of its payments. The payments out of the policy cannot be created, so the merchant does
+//! not need to reject them manually and its pending queue cannot be flooded with them. If the
+//! policy auto-accepts, the payments meeting the policy are accepted on creation.
+//!
+//! - `set_referral_program` - Register the referral program of a merchant.
+//!
+//! The merchant can route a share of the fee of its payments, up to the `MaxReferralFeeShare`, to
+//! the referrers of the payments. The share is fixed for a payment when it is created.
+//!
+//! - `set_payout_routes` - Split the payouts of an organization to its accounts.
+//!
+//! The payouts of the completed payments to an organization are split by share to the organization
+//! account and the accounts of its members, e.g. 80% to the operations and 20% to a tax reserve,
+//! instead of landing in one account. The shares add up to the whole payout.
+//!
+//! - `set_spending_limit` - Set the daily and weekly spending limits of an account.
+//!
+//! The outgoing payments of an account in a currency are capped by its spending limits. The limits
+//! are self-imposed, or set by the spending limit admin of the account (e.g. an org admin) if any.
+//!
+//! - `set_spending_limit_admin` - Set the account that manages the spending limits of an account.
+//!
+//! - `approve_payee` - Approve a payee to pull payments from the payer.
+//! - `revoke_payee_approval` - Revoke the approval of a payee at once.
+//! - `pull_payment` - Pull a payment from a payer as an approved payee.
+//!
+//! A payer can give a standing approval to a payee, e.g. a utility, to pull the payments in a
+//! currency up to a per-payment and a total limit until the approval expires. The pulled payments
+//! are created on behalf of the payer, with the same checks as the payments it creates, and
+//! accepted by the payee at once, so the bills do not need the signature of the payer.
+//!
+//! - `set_refund_address` - Nominate the account the payer is refunded to.
+//!
+//! The payer can nominate a different refund address (e.g. a cold wallet) before cancelling or
+//! disputing a payment, so the refunds do not have to go back to a possibly compromised account.
+//! The nomination needs to be signed by the payer of the payment.
+//!
+//! - `set_payment_locale` - Set the language hint of a payment.
+//!
+//! The payer can hint the language of a payment while it is pending, so the payee agrees to it by
+//! accepting the payment. If the payment is disputed, the resolvers reading the hinted language are
+//! preferred.
+//!
+//! - `set_payment_category` - Set the category of a payment.
+//!
+//! The payee, or its proxies authorized to accept the payments, can categorize a payment with a
+//! `PaymentCategory` or a merchant category code while it is pending or along with the
+//! acceptance. The payments are indexed by category in `CategoryPayments` for the analytics. The
+//! high-risk categories increase the risk score of the payment, and the resolvers with the
+//! expertise in the category are preferred if the payment is disputed.
+//!
+//! - `set_merchant_proxy` - Authorize a proxy key to operate the payments of a merchant.
+//!
+//! The merchant can authorize proxy keys (e.g. the keys of POS terminals) to do some of the payee
+//! operations on its behalf, so the merchant key does not need to be held by the terminals. A
+//! proxy is restricted to the authorized [`MerchantOperation`]s, the refunds by cancelling, the
+//! second confirmation of the payee and the disputes are always done by the merchant itself.
+//!
+//! - `set_beneficiary` - Designate the account taking over the payments of an inactive merchant.
+//! - `claim_inheritance` - Claim the payments of an inactive merchant as its beneficiary.
+//! - `execute_inheritance` - Take over the payments of an inactive merchant after the claim.
+//!
+//! A sole-proprietor merchant can designate a beneficiary. Once the merchant has not sent any
+//! transaction, as seen from its account nonce, for `InheritanceInactivityPeriod`, the beneficiary
+//! can claim the inheritance. If the merchant still sends no transaction within the
+//! `InheritanceChallengeWindow`, the beneficiary becomes the heir of the merchant: it is authorized
+//! to all the payee operations of the merchant and the escrowed funds paid out to the merchant,
+//! e.g. when its incomplete payments are completed, go to the heir. The merchant cancels a claim
+//! by sending any transaction, and takes the payouts and the payee operations back by designating
+//! a beneficiary again.
+//!
+//! - `set_call_paused` - Pause or unpause a call in an emergency.
+//!
+//! The `PauseOrigin` can pause the [`PausableCall`]s, e.g. while an exploit is investigated. The
+//! refund paths such as cancelling, rejecting, expiring, reclaiming and disputing a payment are
+//! never paused, so the payers can always get their funds back. The auto-completion of the
+//! fulfilled payments by the off-chain worker is paused with `complete_payment`.
+//!
 //! ## Events
 //!
 //! - PaymentCreated - A payment is created by the payer.
@@ -47,14 +215,43 @@
 //! - PaymentRejected - A payment is rejected by payee.
 //! - PaymentExpired - A payment is not accepted or rejected by the payee after the pending period.
 //! - PaymentCancelled - A payment is canceled by the payer or payee.
+//! - PayeeConfirmed - A high-value payment is confirmed a second time by the payee.
+//! - DeliveryProofAdded - A delivery proof is appended to a payment by the payee.
 //! - PaymentFulfilled - A payment is marked as full-filled by the payee.
+//! - PaymentPartiallyFulfilled - A share of a payment is marked as fulfilled by the payee.
 //! - PaymentCompleted - A payment is marked as completed by the payer or autocomplete by the
 //!   off-chain worker.
+//! - PaymentFeeCharged - The fee of a completed payment is transferred to the fee account.
+//! - PayeeHeld - A payee reached the velocity policy and its fulfilled payments are held.
+//! - PaymentCompletionPostponed - The auto-completion of a payment is postponed by a hold.
+//! - PaymentReclaimed - The funds of a payment not fulfilled in time are reclaimed by the payer.
+//! - IdentityRequirementUpdated - The identity requirement of a merchant is updated.
+//! - AcceptancePolicyUpdated - The acceptance policy of a merchant is updated.
+//! - ReferralProgramUpdated - The referral program of a merchant is updated.
+//! - ReferralRewarded - The referrer of a completed payment gets its share of the fee.
+//! - PayoutRoutesUpdated - The payout routes of an organization are updated.
+//! - PayoutRouted - The payout of a completed payment is split to the payout routes of the payee.
+//! - SpendingLimitUpdated - The spending limit of an account is updated.
+//! - SpendingLimitAdminUpdated - The spending limit admin of an account is updated.
+//! - PayeeApprovalUpdated - The approval of a payee to pull payments is updated or revoked.
+//! - PaymentPulled - A payment is pulled from the payer by an approved payee.
+//! - RefundAddressUpdated - The refund address of a payment is nominated by the payer.
+//! - PaymentLocaleUpdated - The language hint of a payment is set by the payer.
+//! - PaymentCategoryUpdated - The category of a payment is set by the payee.
+//! - MerchantProxyUpdated - The operations authorized to a proxy of a merchant are updated.
+//! - BeneficiaryUpdated - The beneficiary of a merchant is designated or removed.
+//! - MerchantActivityObserved - A merchant was active since its beneficiary last observed it.
+//! - InheritanceClaimed - The inheritance of an inactive merchant is claimed by its beneficiary.
+//! - InheritanceExecuted - The beneficiary took over the payments of an inactive merchant.
+//! - CallPauseUpdated - A call is paused or unpaused.
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
 pub use pallet::*;
 
+pub mod migrations;
+pub mod risk;
+
 #[cfg(test)]
 mod mock;
 
@@ -66,18 +263,29 @@ mod benchmarking;
 
 #[frame_support::pallet]
 pub mod pallet {
+	use crate::risk::{compute_risk_score, RiskFactors, RiskScore};
 	use currencies_registry::CurrenciesManager;
 	use frame_support::{
-		dispatch::DispatchResult, log, pallet_prelude::*, sp_runtime::traits::Hash,
+		dispatch::DispatchResult,
+		log,
+		pallet_prelude::*,
+		sp_runtime::traits::{Hash, One, Saturating, Zero},
 		sp_std::vec::Vec,
+		PalletId,
 	};
 	use frame_system::pallet_prelude::*;
 	use orml_traits::{MultiCurrency, MultiReservableCurrency};
+	use pallet_identities::IdentitiesManager;
 	use pallet_timestamp::{self as timestamp};
-	use primitives::CurrencyId;
+	use primitives::{
+		indexed_content_key, Credibility, CurrencyId, Judgment, LanguageCode, PaymentCategory,
+		PaymentFlows, ReserveIdentifier,
+	};
 	use scale_info::TypeInfo;
 	use sp_io::offchain_index;
-	use sp_runtime::RuntimeDebug;
+	use sp_runtime::{
+		traits::AccountIdConversion, Percent, Permill, RuntimeDebug, SaturatedConversion,
+	};
 
 	#[cfg(feature = "std")]
 	use serde::{Deserialize, Serialize};
@@ -87,10 +295,96 @@ pub mod pallet {
 		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
 		type Currency: MultiReservableCurrency<Self::AccountId, CurrencyId = CurrencyId<Self::Hash>>;
 		type CurrenciesManager: CurrenciesManager<Self::AccountId, Self::Hash>;
+		type IdentitiesManager: IdentitiesManager<Self::AccountId>;
+		/// The fee schedules of the completed payments per currency.
+		type PaymentFee: PaymentFee<CurrencyId<Self::Hash>, BalanceOf<Self>>;
+		/// The screening of the payments on their creation and acceptance, `()` allows all of
+		/// them.
+		type ComplianceCheck: ComplianceCheck<
+			AccountOf<Self>,
+			CurrencyId<Self::Hash>,
+			BalanceOf<Self>,
+		>;
+		/// The account receiving the fees of the payments created without a marketplace.
+		#[pallet::constant]
+		type TreasuryAccount: Get<AccountOf<Self>>;
 		#[pallet::constant]
 		type PendingPaymentWaitingTime: Get<MomentOf<Self>>;
 		#[pallet::constant]
 		type FulfilledPaymentWaitingTime: Get<MomentOf<Self>>;
+		/// The time range of a bucket in the pending and fulfilled payment queues, 0 is taken as 1.
+		#[pallet::constant]
+		type QueueBucketDuration: Get<MomentOf<Self>>;
+		/// The maximum number of payments that can be queued in one bucket, the payments beyond it
+		/// spill over to the next bucket.
+		#[pallet::constant]
+		type MaxPaymentsPerBucket: Get<u32>;
+		/// The maximum number of due payments evaluated from each of the pending and fulfilled
+		/// payment queues in one run of the offchain worker.
+		#[pallet::constant]
+		type QueueBatchSize: Get<u32>;
+		/// The time range of a creation bucket in the status index of the payments, e.g. a day. 0
+		/// is taken as 1.
+		#[pallet::constant]
+		type PaymentIndexBucketDuration: Get<MomentOf<Self>>;
+		/// The identity requirement of the merchants without their own requirement.
+		#[pallet::constant]
+		type DefaultIdentityRequirement: Get<IdentityRequirement<BalanceOf<Self>>>;
+		/// The age from which a payer account is no longer considered new by the risk scoring.
+		#[pallet::constant]
+		type RiskMatureAccountAge: Get<MomentOf<Self>>;
+		/// The amount from which a payment gets the full amount risk.
+		#[pallet::constant]
+		type RiskHighAmount: Get<BalanceOf<Self>>;
+		/// The period of the daily spending limit.
+		#[pallet::constant]
+		type DailySpendingPeriod: Get<MomentOf<Self>>;
+		/// The period of the weekly spending limit.
+		#[pallet::constant]
+		type WeeklySpendingPeriod: Get<MomentOf<Self>>;
+		/// The amount from which a payment needs to be confirmed a second time by the payee.
+		#[pallet::constant]
+		type PayeeConfirmationThreshold: Get<BalanceOf<Self>>;
+		/// The time after the acceptance the payee can confirm a payment a second time.
+		#[pallet::constant]
+		type PayeeConfirmationCooldown: Get<MomentOf<Self>>;
+		/// The time after the acceptance the payee has to fulfill a payment, after which the payer
+		/// can reclaim the funds. It must be longer than the `PayeeConfirmationCooldown`.
+		#[pallet::constant]
+		type FulfillmentTime: Get<MomentOf<Self>>;
+		/// The maximum number of delivery proofs of a payment.
+		#[pallet::constant]
+		type MaxDeliveryProofs: Get<u32>;
+		/// The maximum number of currencies allowed by an acceptance policy.
+		#[pallet::constant]
+		type MaxPolicyCurrencies: Get<u32>;
+		/// The maximum share of the payment fee a merchant can route to the referrers.
+		#[pallet::constant]
+		type MaxReferralFeeShare: Get<Permill>;
+		/// The maximum number of accounts the payouts of an organization can be split to.
+		#[pallet::constant]
+		type MaxPayoutRoutes: Get<u32>;
+		/// The origin which can pause and unpause the calls in an emergency.
+		type PauseOrigin: EnsureOrigin<Self::Origin>;
+		/// The pallet id, used for deriving the escrow account of the payments.
+		#[pallet::constant]
+		type PalletId: Get<PalletId>;
+		/// The number of blocks of the eras the payment flows of the accounts are counted by, which
+		/// is the granularity of the account statements.
+		#[pallet::constant]
+		type StatementEraDuration: Get<Self::BlockNumber>;
+		/// The time without any transaction of a merchant after which its beneficiary can claim
+		/// the inheritance.
+		#[pallet::constant]
+		type InheritanceInactivityPeriod: Get<MomentOf<Self>>;
+		/// The time after the claim of an inheritance the merchant has to show any activity before
+		/// the beneficiary can take over.
+		#[pallet::constant]
+		type InheritanceChallengeWindow: Get<MomentOf<Self>>;
+		/// The policy putting the payees on hold on a burst of fulfillments followed by disputes,
+		/// `None` disables the holds.
+		#[pallet::constant]
+		type VelocityPolicy: Get<Option<VelocityPolicy<MomentOf<Self>>>>;
 	}
 
 	type AccountOf<T> = <T as frame_system::Config>::AccountId;
@@ -105,6 +399,71 @@ pub mod pallet {
 		) -> Result<(AccountId, AccountId, Balance, CurrencyId<Hash>), DispatchError>;
 
 		fn can_dispute(hash: &Hash) -> bool;
+
+		/// The account the payer is refunded to, the payer if no refund address is nominated.
+		fn get_refund_address(hash: &Hash) -> Result<AccountId, DispatchError>;
+
+		/// The language hint of the payment, if any.
+		fn get_locale(hash: &Hash) -> Option<LanguageCode>;
+
+		/// The category of the payment set by the payee, if any.
+		fn get_category(hash: &Hash) -> Option<PaymentCategory>;
+
+		/// The marketplace the payment is created through, with the fee it is due on the
+		/// completion net of the referral reward. The marketplace is an interested party of the
+		/// disputes over the payment.
+		fn get_marketplace(hash: &Hash) -> Option<(AccountId, Balance)>;
+
+		/// Settle a payment as the outcome of an arbitration, the escrowed funds are released to
+		/// the payee or refunded to the refund address of the payer. The `shares` of the interested
+		/// parties, e.g. the fee of the marketplace, are paid out of the funds released to the
+		/// payee. Any arbitration pallet can settle the payments through it, returns the
+		/// beneficiary and the amount released to it.
+		fn resolve(
+			hash: &Hash,
+			outcome: Judgment,
+			shares: &[(AccountId, Balance)],
+		) -> Result<(AccountId, Balance), DispatchError>;
+	}
+
+	/// The fee of a payment, a flat amount plus a percentage of the amount transferred to the payee.
+	#[derive(Clone, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo)]
+	pub struct FeeSchedule<Balance> {
+		pub flat: Balance,
+		pub percentage: Permill,
+	}
+
+	pub trait PaymentFee<CurrencyId, Balance> {
+		/// The fee schedule of the payments in the currency, no fee is charged if there is none.
+		fn fee_schedule(currency_id: &CurrencyId) -> Option<FeeSchedule<Balance>>;
+	}
+
+	impl<CurrencyId, Balance> PaymentFee<CurrencyId, Balance> for () {
+		fn fee_schedule(_currency_id: &CurrencyId) -> Option<FeeSchedule<Balance>> {
+			None
+		}
+	}
+
+	pub trait ComplianceCheck<AccountId, CurrencyId, Balance> {
+		/// Screen a payment from the payer to the payee, e.g. against sanctions lists. The payment
+		/// is refused with the returned error.
+		fn check_payment(
+			payer: &AccountId,
+			payee: &AccountId,
+			currency_id: &CurrencyId,
+			amount: Balance,
+		) -> DispatchResult;
+	}
+
+	impl<AccountId, CurrencyId, Balance> ComplianceCheck<AccountId, CurrencyId, Balance> for () {
+		fn check_payment(
+			_payer: &AccountId,
+			_payee: &AccountId,
+			_currency_id: &CurrencyId,
+			_amount: Balance,
+		) -> DispatchResult {
+			Ok(())
+		}
 	}
 
 	#[derive(Clone, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo)]
@@ -119,6 +478,22 @@ pub mod pallet {
 		Disputed,
 		Cancelled,
 		Completed,
+		/// The funds were released by a dispute resolution.
+		Resolved,
+		/// The funds were reclaimed by the payer as the payment was not fulfilled in time.
+		Reclaimed,
+		/// The share of the payment delivered by the payee, the rest is refunded on completion.
+		PartiallyFulfilled(Percent),
+	}
+
+	#[derive(Clone, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo)]
+	#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+	pub enum PayeeConfirmation<Moment> {
+		/// The payment is below the threshold.
+		NotRequired,
+		/// The payee needs to confirm the payment again from the moment.
+		Required(Moment),
+		Confirmed,
 	}
 
 	#[derive(Clone, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo)]
@@ -135,9 +510,175 @@ pub mod pallet {
 		pub created_at: MomentOf<T>,
 		pub updated_at: MomentOf<T>,
 		pub updated_by: AccountOf<T>,
+		pub risk_score: RiskScore,
+		pub payee_confirmation: PayeeConfirmation<MomentOf<T>>,
+		pub refund_to: Option<AccountOf<T>>,
+		pub delivery_proofs: BoundedVec<T::Hash, T::MaxDeliveryProofs>,
+		/// The moment the payer can reclaim the funds if the payment is still not fulfilled, set
+		/// on the acceptance.
+		pub fulfillment_deadline: Option<MomentOf<T>>,
+	}
+
+	/// The description of a payment submitted by the payer.
+	#[derive(Clone, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo)]
+	#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+	pub enum PaymentDescription {
+		/// The description stored as is in the payment.
+		Plain(Vec<u8>),
+		/// The ciphertext of the description encrypted by the payer to the encryption key of the
+		/// payee, kept off the chain state.
+		EncryptedDescription(Vec<u8>),
+	}
+
+	/// The reference of an encrypted description. The ciphertext is offchain indexed by its hash.
+	#[derive(Clone, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo)]
+	pub struct EncryptedDescription<Hash> {
+		/// The encryption key of the payee the description is encrypted to.
+		pub key: Vec<u8>,
+		pub cipher_hash: Hash,
+	}
+
+	/// The non-transferable receipt of a completed payment, held by both parties as a proof of
+	/// purchase.
+	#[derive(Clone, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo)]
+	#[scale_info(skip_type_params(T))]
+	pub struct PaymentReceipt<T: Config> {
+		pub payer: AccountOf<T>,
+		pub payee: AccountOf<T>,
+		pub currency_id: CurrencyId<T::Hash>,
+		/// The amount paid for the delivered share of the payment, the fee included.
+		pub amount: BalanceOf<T>,
+		pub receipt_hash: T::Hash,
+		pub category: Option<PaymentCategory>,
+		pub completed_at: MomentOf<T>,
+	}
+
+	/// The identity requirement of the payment parties for the payments above the threshold.
+	#[derive(Clone, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo)]
+	pub struct IdentityRequirement<Balance> {
+		/// The payer needs an identity.
+		pub payer: bool,
+		/// The payee needs an identity.
+		pub payee: bool,
+		/// The minimum credibility of the required identities.
+		pub min_credibility: Credibility,
+		/// The requirement applies to the payments with the amount from the threshold.
+		pub threshold: Balance,
+	}
+
+	/// The conditions of the payments a merchant accepts, evaluated when the payments to the
+	/// merchant are created and accepted.
+	#[derive(Clone, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo)]
+	pub struct AcceptancePolicy<Balance, Hash> {
+		pub min_amount: Option<Balance>,
+		pub max_amount: Option<Balance>,
+		/// The currencies of the payments, any currency accepted by the merchant in the currencies
+		/// registry if it is empty.
+		pub currencies: Vec<CurrencyId<Hash>>,
+		/// The payer needs an identity with the minimum credibility.
+		pub min_payer_credibility: Option<Credibility>,
+		/// The payments meeting the policy are accepted on creation.
+		pub auto_accept: bool,
+	}
+
+	/// The referral program of a merchant, rewarding the referrers of its payments.
+	#[derive(Clone, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo)]
+	pub struct ReferralProgram {
+		/// The share of the payment fee routed to the referrer when a payment is completed.
+		pub fee_share: Permill,
+	}
+
+	/// The account designated by a merchant to take over its payments if the merchant becomes
+	/// inactive. The activity is the account nonce of the merchant, observed when the beneficiary
+	/// is designated and when the beneficiary claims the inheritance.
+	#[derive(Clone, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo)]
+	pub struct Beneficiary<AccountId, Index, Moment> {
+		pub account: AccountId,
+		/// The account nonce of the merchant when it was last observed.
+		pub nonce: Index,
+		/// The moment the nonce was last seen changing.
+		pub active_at: Moment,
+		/// The moment the beneficiary claimed the inheritance, if it did.
+		pub claimed_at: Option<Moment>,
+	}
+
+	/// The caps of the outgoing payments of an account in a currency.
+	#[derive(Clone, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo)]
+	pub struct SpendingLimit<Balance> {
+		pub daily: Option<Balance>,
+		pub weekly: Option<Balance>,
+	}
+
+	/// The standing approval of a payer for a payee to pull payments in a currency, e.g. to bill
+	/// the utilities, without the payer signing each payment.
+	#[derive(Clone, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo)]
+	pub struct PayeeApproval<Balance, Moment> {
+		/// The maximum amount of one pulled payment.
+		pub per_payment_limit: Balance,
+		/// The maximum amount of all the payments pulled under the approval.
+		pub total_limit: Balance,
+		/// The amount of the payments pulled so far, the refunds are not deducted.
+		pub pulled: Balance,
+		/// The payments can no longer be pulled from the moment.
+		pub expires_at: Moment,
+	}
+
+	/// A payee is put on hold when it gets at least `fulfillments` fulfillments and `disputes`
+	/// disputes within a `window`, the pattern of an exit scam. The auto-completion of its fulfilled
+	/// payments is then postponed for `hold`, so the payers have more time to dispute them.
+	#[derive(Clone, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo)]
+	pub struct VelocityPolicy<Moment> {
+		pub window: Moment,
+		pub fulfillments: u32,
+		pub disputes: u32,
+		pub hold: Moment,
+	}
+
+	/// The fulfillments and the disputes of a payee in the current window of the velocity policy.
+	#[derive(Clone, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo, Default)]
+	pub struct PayeeVelocity<Moment> {
+		pub window_started_at: Moment,
+		pub fulfillments: u32,
+		pub disputes: u32,
+		/// The fulfilled payments of the payee are not auto-completed before the moment.
+		pub held_until: Moment,
+	}
+
+	/// The amount spent by an account in a currency in the current periods.
+	#[derive(Clone, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo, Default)]
+	pub struct SpendingRecord<Balance, Moment> {
+		pub day_started_at: Moment,
+		pub day_spent: Balance,
+		pub week_started_at: Moment,
+		pub week_spent: Balance,
+	}
+
+	/// The payee operations a merchant can authorize its proxies to do.
+	#[derive(Clone, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo)]
+	#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+	pub enum MerchantOperation {
+		AcceptPayment,
+		RejectPayment,
+		AddDeliveryProof,
+		FulfillPayment,
+	}
+
+	/// The calls which can be paused in an emergency.
+	#[derive(Clone, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo)]
+	#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+	pub enum PausableCall {
+		CreatePayment,
+		AcceptPayment,
+		ConfirmPayee,
+		FulfillPayment,
+		CompletePayment,
 	}
 
+	/// The current storage version, see [`crate::migrations`].
+	pub(crate) const STORAGE_VERSION: StorageVersion = StorageVersion::new(6);
+
 	#[pallet::pallet]
+	#[pallet::storage_version(STORAGE_VERSION)]
 	#[pallet::without_storage_info]
 	pub struct Pallet<T>(_);
 
@@ -148,19 +689,289 @@ pub mod pallet {
 	#[pallet::getter(fn payments)]
 	pub(super) type Payments<T: Config> = StorageMap<_, Twox64Concat, PaymentHashOf<T>, Payment<T>>;
 
+	/// Pending payments grouped by the bucket of their expiry time.
+	#[pallet::storage]
+	pub(super) type PendingPaymentsQueue<T: Config> = StorageMap<
+		_,
+		Twox64Concat,
+		MomentOf<T>,
+		BoundedVec<PaymentHashOf<T>, T::MaxPaymentsPerBucket>,
+		ValueQuery,
+	>;
+
+	/// The next bucket of the pending payments queue to be processed.
+	#[pallet::storage]
+	pub(super) type NextPendingPaymentsBucket<T: Config> = StorageValue<_, MomentOf<T>>;
+
+	/// Fulfilled payments grouped by the bucket of their auto-complete time.
+	#[pallet::storage]
+	pub(super) type FulfilledPaymentsQueue<T: Config> = StorageMap<
+		_,
+		Twox64Concat,
+		MomentOf<T>,
+		BoundedVec<PaymentHashOf<T>, T::MaxPaymentsPerBucket>,
+		ValueQuery,
+	>;
+
+	/// The next bucket of the fulfilled payments queue to be processed.
 	#[pallet::storage]
-	pub(super) type PendingPaymentHashes<T: Config> =
-		StorageValue<_, Vec<PaymentHashOf<T>>, ValueQuery>;
+	pub(super) type NextFulfilledPaymentsBucket<T: Config> = StorageValue<_, MomentOf<T>>;
 
+	/// The bucket of the pending or fulfilled payments queue a payment spilled over to, as the
+	/// bucket of its due time was full.
 	#[pallet::storage]
-	pub(super) type FulfilledPaymentHashes<T: Config> =
-		StorageValue<_, Vec<PaymentHashOf<T>>, ValueQuery>;
+	pub(super) type SpilledPaymentBuckets<T: Config> =
+		StorageMap<_, Twox64Concat, PaymentHashOf<T>, MomentOf<T>>;
 
 	#[pallet::storage]
 	#[pallet::getter(fn payments_owned)]
 	pub(super) type PaymentsOwned<T: Config> =
 		StorageMap<_, Twox64Concat, AccountOf<T>, Vec<PaymentHashOf<T>>, ValueQuery>;
 
+	/// The time an account created its first payment.
+	#[pallet::storage]
+	#[pallet::getter(fn accounts_first_seen)]
+	pub(super) type AccountsFirstSeen<T: Config> =
+		StorageMap<_, Twox64Concat, AccountOf<T>, MomentOf<T>>;
+
+	/// The number of disputed payments of an account as the payer.
+	#[pallet::storage]
+	#[pallet::getter(fn disputed_payments_count)]
+	pub(super) type DisputedPaymentsCount<T: Config> =
+		StorageMap<_, Twox64Concat, AccountOf<T>, u32, ValueQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn spending_limits)]
+	pub(super) type SpendingLimits<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		AccountOf<T>,
+		Twox64Concat,
+		CurrencyId<T::Hash>,
+		SpendingLimit<BalanceOf<T>>,
+	>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn spending_records)]
+	pub(super) type SpendingRecords<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		AccountOf<T>,
+		Twox64Concat,
+		CurrencyId<T::Hash>,
+		SpendingRecord<BalanceOf<T>, MomentOf<T>>,
+		ValueQuery,
+	>;
+
+	/// The velocity counters of the payees.
+	#[pallet::storage]
+	#[pallet::getter(fn payee_velocities)]
+	pub(super) type PayeeVelocities<T: Config> =
+		StorageMap<_, Twox64Concat, AccountOf<T>, PayeeVelocity<MomentOf<T>>, ValueQuery>;
+
+	/// The moment the fulfilled payments postponed by the hold of their payee are auto-completed.
+	#[pallet::storage]
+	#[pallet::getter(fn payment_holds)]
+	pub(super) type PaymentHolds<T: Config> =
+		StorageMap<_, Twox64Concat, PaymentHashOf<T>, MomentOf<T>>;
+
+	/// The account that manages the spending limits of an account instead of the account itself.
+	#[pallet::storage]
+	#[pallet::getter(fn spending_limit_admins)]
+	pub(super) type SpendingLimitAdmins<T: Config> =
+		StorageMap<_, Twox64Concat, AccountOf<T>, AccountOf<T>>;
+
+	/// The standing approvals of the payers for the payees to pull payments in a currency.
+	#[pallet::storage]
+	#[pallet::getter(fn payee_approvals)]
+	pub(super) type PayeeApprovals<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		AccountOf<T>,
+		Twox64Concat,
+		(AccountOf<T>, CurrencyId<T::Hash>),
+		PayeeApproval<BalanceOf<T>, MomentOf<T>>,
+	>;
+
+	/// The identity requirement set by the merchants for their payments.
+	#[pallet::storage]
+	#[pallet::getter(fn identity_requirements)]
+	pub(super) type IdentityRequirements<T: Config> =
+		StorageMap<_, Twox64Concat, AccountOf<T>, IdentityRequirement<BalanceOf<T>>>;
+
+	/// The acceptance policies published by the merchants.
+	#[pallet::storage]
+	#[pallet::getter(fn acceptance_policies)]
+	pub(super) type AcceptancePolicies<T: Config> =
+		StorageMap<_, Twox64Concat, AccountOf<T>, AcceptancePolicy<BalanceOf<T>, T::Hash>>;
+
+	/// The referral programs registered by the merchants.
+	#[pallet::storage]
+	#[pallet::getter(fn referral_programs)]
+	pub(super) type ReferralPrograms<T: Config> =
+		StorageMap<_, Twox64Concat, AccountOf<T>, ReferralProgram>;
+
+	/// The referrers of the payments, with the fee share of the referral program of the payee when
+	/// the payments are created.
+	#[pallet::storage]
+	#[pallet::getter(fn payment_referrers)]
+	pub(super) type PaymentReferrers<T: Config> =
+		StorageMap<_, Twox64Concat, PaymentHashOf<T>, (AccountOf<T>, Permill)>;
+
+	/// The accounts the payouts of the completed payments of the organizations are split to, with
+	/// their shares.
+	#[pallet::storage]
+	#[pallet::getter(fn payout_routes)]
+	pub(super) type PayoutRoutes<T: Config> = StorageMap<
+		_,
+		Twox64Concat,
+		AccountOf<T>,
+		BoundedVec<(AccountOf<T>, Permill), T::MaxPayoutRoutes>,
+		ValueQuery,
+	>;
+
+	/// The beneficiaries designated by the merchants.
+	#[pallet::storage]
+	#[pallet::getter(fn beneficiaries)]
+	pub(super) type Beneficiaries<T: Config> =
+		StorageMap<_, Twox64Concat, AccountOf<T>, Beneficiary<AccountOf<T>, T::Index, MomentOf<T>>>;
+
+	/// The beneficiaries who took over the inactive merchants. The escrowed funds paid out to the
+	/// merchants are paid to their heirs instead.
+	#[pallet::storage]
+	#[pallet::getter(fn heirs)]
+	pub(super) type Heirs<T: Config> = StorageMap<_, Twox64Concat, AccountOf<T>, AccountOf<T>>;
+
+	/// The operations authorized by the merchants to their proxies.
+	#[pallet::storage]
+	#[pallet::getter(fn merchant_proxies)]
+	pub(super) type MerchantProxies<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		AccountOf<T>,
+		Twox64Concat,
+		AccountOf<T>,
+		Vec<MerchantOperation>,
+	>;
+
+	/// The calls paused in an emergency.
+	#[pallet::storage]
+	#[pallet::getter(fn paused_calls)]
+	pub(super) type PausedCalls<T: Config> =
+		StorageMap<_, Twox64Concat, PausableCall, bool, ValueQuery>;
+
+	/// The language hints of the payments.
+	#[pallet::storage]
+	#[pallet::getter(fn payment_locales)]
+	pub(super) type PaymentLocales<T: Config> =
+		StorageMap<_, Twox64Concat, PaymentHashOf<T>, LanguageCode>;
+
+	/// The categories of the payments set by the payees.
+	#[pallet::storage]
+	#[pallet::getter(fn payment_categories)]
+	pub(super) type PaymentCategories<T: Config> =
+		StorageMap<_, Twox64Concat, PaymentHashOf<T>, PaymentCategory>;
+
+	/// The encrypted descriptions of the payments, the plain description of these payments is
+	/// empty.
+	#[pallet::storage]
+	#[pallet::getter(fn encrypted_descriptions)]
+	pub(super) type EncryptedDescriptions<T: Config> =
+		StorageMap<_, Twox64Concat, PaymentHashOf<T>, EncryptedDescription<T::Hash>>;
+
+	/// The marketplace accounts receiving the fees of the payments, the fees of the other payments
+	/// go to the `TreasuryAccount`.
+	#[pallet::storage]
+	#[pallet::getter(fn payment_fee_accounts)]
+	pub(super) type PaymentFeeAccounts<T: Config> =
+		StorageMap<_, Twox64Concat, PaymentHashOf<T>, AccountOf<T>>;
+
+	/// The party which requested a receipt of the payment on completion.
+	#[pallet::storage]
+	#[pallet::getter(fn payment_receipt_requests)]
+	pub(super) type PaymentReceiptRequests<T: Config> =
+		StorageMap<_, Twox64Concat, PaymentHashOf<T>, AccountOf<T>>;
+
+	/// The receipts of the completed payments.
+	#[pallet::storage]
+	#[pallet::getter(fn payment_receipts)]
+	pub(super) type PaymentReceipts<T: Config> =
+		StorageMap<_, Twox64Concat, PaymentHashOf<T>, PaymentReceipt<T>>;
+
+	/// The receipts held by the payers and the payees.
+	#[pallet::storage]
+	#[pallet::getter(fn receipts_owned)]
+	pub(super) type ReceiptsOwned<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		AccountOf<T>,
+		Twox64Concat,
+		PaymentHashOf<T>,
+		(),
+	>;
+
+	/// The funds received and sent through the payments by account and currency, counted by era
+	/// of `StatementEraDuration` blocks.
+	#[pallet::storage]
+	#[pallet::getter(fn account_flows)]
+	pub(super) type AccountFlows<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		(AccountOf<T>, CurrencyId<T::Hash>),
+		Twox64Concat,
+		T::BlockNumber,
+		PaymentFlows<BalanceOf<T>>,
+		ValueQuery,
+	>;
+
+	/// The payments indexed by their category.
+	#[pallet::storage]
+	#[pallet::getter(fn category_payments)]
+	pub(super) type CategoryPayments<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		PaymentCategory,
+		Twox64Concat,
+		PaymentHashOf<T>,
+		(),
+	>;
+
+	/// The payments indexed by their status and the bucket of their creation time, see
+	/// [`Pallet::payments_with_status`]. The partially fulfilled payments are indexed under
+	/// `PartiallyFulfilled(0%)` whatever their fulfilled share. A bucket is not bounded, so the
+	/// index never stops the payments from being created.
+	#[pallet::storage]
+	pub(super) type PaymentsByStatus<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		(PaymentStatus, MomentOf<T>),
+		Twox64Concat,
+		PaymentHashOf<T>,
+		(),
+	>;
+
+	/// The funds reserved by the pallet by account, reserve identifier and currency, before the
+	/// payments were escrowed. The reserves are moved to the escrow account by
+	/// [`crate::migrations::v4`], so it is empty on the migrated chains.
+	#[pallet::storage]
+	#[pallet::getter(fn named_reserves)]
+	pub(super) type NamedReserves<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		AccountOf<T>,
+		Twox64Concat,
+		(ReserveIdentifier, CurrencyId<T::Hash>),
+		BalanceOf<T>,
+		ValueQuery,
+	>;
+
+	/// The funds of the open payments held by the escrow account per currency, kept up to date
+	/// with the escrow so the value locked in the protocol is read without scanning the payments.
+	#[pallet::storage]
+	#[pallet::getter(fn total_value_locked)]
+	pub(super) type TotalValueLocked<T: Config> =
+		StorageMap<_, Twox64Concat, CurrencyId<T::Hash>, BalanceOf<T>, ValueQuery>;
+
 	#[pallet::event]
 	#[pallet::generate_deposit(pub(super) fn deposit_event)]
 	pub enum Event<T: Config> {
@@ -170,6 +981,9 @@ pub mod pallet {
 			payee: AccountOf<T>,
 			currency_id: CurrencyId<T::Hash>,
 			amount: BalanceOf<T>,
+			risk_score: RiskScore,
+			payer_handle: Option<Vec<u8>>,
+			payee_handle: Option<Vec<u8>>,
 		},
 		PaymentAccepted {
 			payment_hash: PaymentHashOf<T>,
@@ -177,6 +991,8 @@ pub mod pallet {
 			payee: AccountOf<T>,
 			currency_id: CurrencyId<T::Hash>,
 			amount: BalanceOf<T>,
+			risk_score: RiskScore,
+			category: Option<PaymentCategory>,
 		},
 		PaymentRejected {
 			payment_hash: PaymentHashOf<T>,
@@ -185,6 +1001,18 @@ pub mod pallet {
 			currency_id: CurrencyId<T::Hash>,
 			amount: BalanceOf<T>,
 		},
+		/// A payment is settled as the outcome of an arbitration.
+		PaymentResolved {
+			payment_hash: PaymentHashOf<T>,
+			beneficiary: AccountOf<T>,
+			amount: BalanceOf<T>,
+		},
+		/// The share of an interested party is paid out of the funds of a resolved payment.
+		PaymentShareReleased {
+			payment_hash: PaymentHashOf<T>,
+			stakeholder: AccountOf<T>,
+			amount: BalanceOf<T>,
+		},
 		PaymentExpired {
 			payment_hash: PaymentHashOf<T>,
 			payer: AccountOf<T>,
@@ -192,6 +1020,18 @@ pub mod pallet {
 			currency_id: CurrencyId<T::Hash>,
 			amount: BalanceOf<T>,
 		},
+		PayeeConfirmed {
+			payment_hash: PaymentHashOf<T>,
+			payer: AccountOf<T>,
+			payee: AccountOf<T>,
+			currency_id: CurrencyId<T::Hash>,
+			amount: BalanceOf<T>,
+		},
+		DeliveryProofAdded {
+			payment_hash: PaymentHashOf<T>,
+			payee: AccountOf<T>,
+			proof_hash: T::Hash,
+		},
 		PaymentFulfilled {
 			payment_hash: PaymentHashOf<T>,
 			payer: AccountOf<T>,
@@ -199,6 +1039,20 @@ pub mod pallet {
 			currency_id: CurrencyId<T::Hash>,
 			amount: BalanceOf<T>,
 		},
+		PaymentFeeCharged {
+			payment_hash: PaymentHashOf<T>,
+			fee_account: AccountOf<T>,
+			currency_id: CurrencyId<T::Hash>,
+			fee: BalanceOf<T>,
+		},
+		PaymentPartiallyFulfilled {
+			payment_hash: PaymentHashOf<T>,
+			payer: AccountOf<T>,
+			payee: AccountOf<T>,
+			currency_id: CurrencyId<T::Hash>,
+			amount: BalanceOf<T>,
+			fulfilled: Percent,
+		},
 		PaymentCancelled {
 			payment_hash: PaymentHashOf<T>,
 			payer: AccountOf<T>,
@@ -220,40 +1074,267 @@ pub mod pallet {
 			currency_id: CurrencyId<T::Hash>,
 			amount: BalanceOf<T>,
 		},
-	}
-
-	#[pallet::error]
-	pub enum Error<T> {
-		Overflow,
-		InsufficientBalance,
-		PaymentNotFound,
-		AccessDenied,
-		InvalidStatusChange,
-		PaymentNonexpired,
-		UnacceptedCurrency,
-	}
-
-	#[pallet::hooks]
-	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
-		fn offchain_worker(block_number: T::BlockNumber) {
-			if let Err(err) = Self::run_offchain_worker() {
-				log::error!(
-					target: "LRP protocol offchain worker",
-					"Fail to run offchain worker at block {:?}: {:?}",
-					block_number,
-					err,
-				);
-			} else {
-				log::debug!(
-					target: "LRP protocol offchain worker",
-					"offchain worker start at block: {:?} already done!",
-					block_number,
-				);
-			}
-		}
-	}
-
-	#[pallet::call]
+		PaymentReclaimed {
+			payment_hash: PaymentHashOf<T>,
+			payer: AccountOf<T>,
+			payee: AccountOf<T>,
+			currency_id: CurrencyId<T::Hash>,
+			amount: BalanceOf<T>,
+		},
+		IdentityRequirementUpdated {
+			merchant: AccountOf<T>,
+			requirement: Option<IdentityRequirement<BalanceOf<T>>>,
+		},
+		AcceptancePolicyUpdated {
+			merchant: AccountOf<T>,
+			policy: Option<AcceptancePolicy<BalanceOf<T>, T::Hash>>,
+		},
+		ReferralProgramUpdated {
+			merchant: AccountOf<T>,
+			program: Option<ReferralProgram>,
+		},
+		/// The payout routes of an organization are updated, no routes means the payouts go to the
+		/// organization account.
+		PayoutRoutesUpdated {
+			merchant: AccountOf<T>,
+			routes: Vec<(AccountOf<T>, Permill)>,
+		},
+		/// The payout of a completed payment is split to the accounts of the payout routes of the
+		/// payee.
+		PayoutRouted {
+			payment_hash: PaymentHashOf<T>,
+			payee: AccountOf<T>,
+			currency_id: CurrencyId<T::Hash>,
+			payouts: Vec<(AccountOf<T>, BalanceOf<T>)>,
+		},
+		BeneficiaryUpdated {
+			merchant: AccountOf<T>,
+			beneficiary: Option<AccountOf<T>>,
+		},
+		/// The payee reached the velocity policy, its fulfilled payments are not auto-completed
+		/// before `held_until`.
+		PayeeHeld {
+			payee: AccountOf<T>,
+			held_until: MomentOf<T>,
+		},
+		/// The auto-completion of a fulfilled payment is postponed by the hold of its payee.
+		PaymentCompletionPostponed {
+			payment_hash: PaymentHashOf<T>,
+			completed_at: MomentOf<T>,
+		},
+		/// The merchant was active since the beneficiary last observed it, the inactivity period
+		/// starts again.
+		MerchantActivityObserved {
+			merchant: AccountOf<T>,
+			active_at: MomentOf<T>,
+		},
+		InheritanceClaimed {
+			merchant: AccountOf<T>,
+			beneficiary: AccountOf<T>,
+			executable_at: MomentOf<T>,
+		},
+		InheritanceExecuted {
+			merchant: AccountOf<T>,
+			heir: AccountOf<T>,
+		},
+		ReferralRewarded {
+			payment_hash: PaymentHashOf<T>,
+			referrer: AccountOf<T>,
+			currency_id: CurrencyId<T::Hash>,
+			reward: BalanceOf<T>,
+		},
+		SpendingLimitUpdated {
+			who: AccountOf<T>,
+			currency_id: CurrencyId<T::Hash>,
+			limit: Option<SpendingLimit<BalanceOf<T>>>,
+		},
+		SpendingLimitAdminUpdated {
+			who: AccountOf<T>,
+			admin: Option<AccountOf<T>>,
+		},
+		PayeeApprovalUpdated {
+			payer: AccountOf<T>,
+			payee: AccountOf<T>,
+			currency_id: CurrencyId<T::Hash>,
+			approval: Option<PayeeApproval<BalanceOf<T>, MomentOf<T>>>,
+		},
+		PaymentPulled {
+			payment_hash: PaymentHashOf<T>,
+			payer: AccountOf<T>,
+			payee: AccountOf<T>,
+			amount: BalanceOf<T>,
+		},
+		RefundAddressUpdated {
+			payment_hash: PaymentHashOf<T>,
+			payer: AccountOf<T>,
+			refund_to: Option<AccountOf<T>>,
+		},
+		PaymentLocaleUpdated {
+			payment_hash: PaymentHashOf<T>,
+			locale: Option<LanguageCode>,
+		},
+		PaymentCategoryUpdated {
+			payment_hash: PaymentHashOf<T>,
+			payee: AccountOf<T>,
+			category: Option<PaymentCategory>,
+			risk_score: RiskScore,
+		},
+		PaymentReceiptRequested {
+			payment_hash: PaymentHashOf<T>,
+			who: AccountOf<T>,
+		},
+		PaymentReceiptMinted {
+			payment_hash: PaymentHashOf<T>,
+			payer: AccountOf<T>,
+			payee: AccountOf<T>,
+		},
+		MerchantProxyUpdated {
+			merchant: AccountOf<T>,
+			proxy: AccountOf<T>,
+			operations: Option<Vec<MerchantOperation>>,
+		},
+		CallPauseUpdated {
+			call: PausableCall,
+			paused: bool,
+		},
+		QueuedPaymentFailed {
+			payment_hash: PaymentHashOf<T>,
+			error: DispatchError,
+		},
+		PaymentEscrowShortfall {
+			payment_hash: PaymentHashOf<T>,
+			escrowed: BalanceOf<T>,
+			shortfall: BalanceOf<T>,
+		},
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		Overflow,
+		InsufficientBalance,
+		PaymentNotFound,
+		/// The payment is not in a status the operation applies to.
+		InvalidStatusChange,
+		PaymentNonexpired,
+		UnacceptedCurrency,
+		IdentityRequired,
+		InsufficientCredibility,
+		SpendingLimitExceeded,
+		PayeeConfirmationNotRequired,
+		PayeeConfirmationCooldown,
+		PayeeNotConfirmed,
+		TooManyDeliveryProofs,
+		CallPaused,
+		/// The amount is out of the range of the acceptance policy of the payee.
+		AmountOutOfPolicy,
+		/// The minimum amount of the acceptance policy is above the maximum amount.
+		InvalidPolicy,
+		/// The acceptance policy allows more than `MaxPolicyCurrencies` currencies.
+		TooManyPolicyCurrencies,
+		/// The payee still has time to fulfill the payment.
+		FulfillmentDeadlineNotReached,
+		/// The fulfilled share is not between none and the whole payment, or not above the share
+		/// fulfilled before.
+		InvalidFulfilledShare,
+		/// The fee share of the referral program is above the `MaxReferralFeeShare`.
+		InvalidReferralProgram,
+		/// The payee has no referral program.
+		ReferralProgramNotFound,
+		/// The payment parties cannot refer their own payment.
+		InvalidReferrer,
+		/// Only the organizations can route their payouts.
+		NotAnOrganization,
+		/// The shares of the payout routes do not add up to the whole payout, or an account is
+		/// routed twice.
+		InvalidPayoutRoutes,
+		/// The payouts are routed to more than `MaxPayoutRoutes` accounts.
+		TooManyPayoutRoutes,
+		/// The payouts can only be routed to the organization account and its members.
+		PayoutAccountNotMember,
+		/// A receipt is already requested for the payment.
+		PaymentReceiptAlreadyRequested,
+		/// The merchant cannot be its own beneficiary.
+		InvalidBeneficiary,
+		/// The merchant has no beneficiary.
+		BeneficiaryNotFound,
+		/// The merchant was active within the `InheritanceInactivityPeriod`.
+		MerchantNotInactive,
+		/// The inheritance is already claimed.
+		InheritanceAlreadyClaimed,
+		/// The inheritance is not claimed yet.
+		InheritanceNotClaimed,
+		/// The merchant still has time to show its activity.
+		ChallengeWindowNotOver,
+		/// The merchant was active since the inheritance was claimed.
+		MerchantActive,
+		/// The payee has not registered an encryption key to encrypt the description to.
+		EncryptionKeyNotFound,
+		/// Only the payer can do the operation.
+		NotPayer,
+		/// Only the payee can do the operation.
+		NotPayee,
+		/// Only the payer or the payee can do the operation.
+		NotPaymentParty,
+		/// Only the payee or its proxies authorized to the operation can do the operation.
+		NotPayeeOrProxy,
+		/// The spending limits are managed by the admin of the account, or the account itself if
+		/// it has no admin.
+		NotSpendingLimitManager,
+		/// The merchant cannot be its own proxy.
+		InvalidMerchantProxy,
+		/// Only the beneficiary of the merchant can claim and execute the inheritance.
+		NotBeneficiary,
+		/// The payment is not pending.
+		PaymentNotPending,
+		/// The payment is not accepted.
+		PaymentNotAccepted,
+		/// The payment is not fulfilled, wholly or partially.
+		PaymentNotFulfilled,
+		/// Only the accepted or partially fulfilled payments can be fulfilled.
+		PaymentNotFulfillable,
+		/// Only the pending or accepted payments can be cancelled.
+		PaymentNotCancellable,
+		/// Only the accepted or fulfilled payments can be disputed.
+		PaymentNotDisputable,
+		/// The payment is already closed.
+		PaymentClosed,
+		/// The payer has not approved the payee to pull payments in the currency.
+		PayeeNotApproved,
+		/// The approval of the payee has expired.
+		PayeeApprovalExpired,
+		/// The pulled payment exceeds the per-payment or the total limit of the approval.
+		PullLimitExceeded,
+		/// The approval is for the payer itself, is already expired or its per-payment limit
+		/// exceeds its total limit.
+		InvalidPayeeApproval,
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn offchain_worker(block_number: T::BlockNumber) {
+			if let Err(err) = Self::run_offchain_worker() {
+				log::error!(
+					target: "LRP protocol offchain worker",
+					"Fail to run offchain worker at block {:?}: {:?}",
+					block_number,
+					err,
+				);
+			} else {
+				log::debug!(
+					target: "LRP protocol offchain worker",
+					"offchain worker start at block: {:?} already done!",
+					block_number,
+				);
+			}
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade() -> Result<(), &'static str> {
+			Self::do_try_state()
+		}
+	}
+
+	#[pallet::call]
 	impl<T: Config> Pallet<T> {
 		#[pallet::weight(1_000)]
 		pub fn create_payment(
@@ -261,11 +1342,48 @@ pub mod pallet {
 			payee: AccountOf<T>,
 			amount: BalanceOf<T>,
 			currency_id: CurrencyId<T::Hash>,
-			description: Vec<u8>,
+			description: PaymentDescription,
+			receipt: Vec<u8>,
+			referrer: Option<AccountOf<T>>,
+		) -> DispatchResult {
+			let payer = ensure_signed(origin)?;
+			Self::ensure_not_paused(PausableCall::CreatePayment)?;
+			Self::do_create_payment(
+				payer,
+				payee,
+				amount,
+				currency_id,
+				description,
+				receipt,
+				None,
+				referrer,
+			)?;
+			Ok(())
+		}
+
+		#[pallet::weight(1_000)]
+		pub fn create_marketplace_payment(
+			origin: OriginFor<T>,
+			payee: AccountOf<T>,
+			amount: BalanceOf<T>,
+			currency_id: CurrencyId<T::Hash>,
+			description: PaymentDescription,
 			receipt: Vec<u8>,
+			fee_account: AccountOf<T>,
+			referrer: Option<AccountOf<T>>,
 		) -> DispatchResult {
 			let payer = ensure_signed(origin)?;
-			Self::do_create_payment(payer, payee, amount, currency_id, description, receipt)?;
+			Self::ensure_not_paused(PausableCall::CreatePayment)?;
+			Self::do_create_payment(
+				payer,
+				payee,
+				amount,
+				currency_id,
+				description,
+				receipt,
+				Some(fee_account),
+				referrer,
+			)?;
 			Ok(())
 		}
 
@@ -275,6 +1393,7 @@ pub mod pallet {
 			payment_hash: PaymentHashOf<T>,
 		) -> DispatchResult {
 			let sender = ensure_signed(origin)?;
+			Self::ensure_not_paused(PausableCall::AcceptPayment)?;
 
 			Self::do_accept_payment(sender, payment_hash)?;
 
@@ -302,6 +1421,16 @@ pub mod pallet {
 			Ok(())
 		}
 
+		#[pallet::weight(1_000)]
+		pub fn expire_payment(
+			origin: OriginFor<T>,
+			payment_hash: PaymentHashOf<T>,
+		) -> DispatchResult {
+			ensure_signed(origin)?;
+			Self::do_expire_payment(payment_hash)?;
+			Ok(())
+		}
+
 		#[pallet::weight(1_000)]
 		pub fn dispute_payment(
 			origin: OriginFor<T>,
@@ -312,158 +1441,1897 @@ pub mod pallet {
 			Ok(())
 		}
 
+		#[pallet::weight(1_000)]
+		pub fn reclaim_unfulfilled_payment(
+			origin: OriginFor<T>,
+			payment_hash: PaymentHashOf<T>,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			Self::do_reclaim_unfulfilled_payment(sender, payment_hash)?;
+			Ok(())
+		}
+
+		#[pallet::weight(1_000)]
+		pub fn confirm_payee(
+			origin: OriginFor<T>,
+			payment_hash: PaymentHashOf<T>,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			Self::ensure_not_paused(PausableCall::ConfirmPayee)?;
+			Self::do_confirm_payee(sender, payment_hash)?;
+			Ok(())
+		}
+
+		#[pallet::weight(1_000)]
+		pub fn add_delivery_proof(
+			origin: OriginFor<T>,
+			payment_hash: PaymentHashOf<T>,
+			proof: Vec<u8>,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			Self::do_add_delivery_proof(sender, payment_hash, proof)?;
+			Ok(())
+		}
+
 		#[pallet::weight(1_000)]
 		pub fn fulfill_payment(
 			origin: OriginFor<T>,
 			payment_hash: PaymentHashOf<T>,
 		) -> DispatchResult {
 			let sender = ensure_signed(origin)?;
+			Self::ensure_not_paused(PausableCall::FulfillPayment)?;
 			Self::do_fulfill_payment(sender, payment_hash)?;
 			Ok(())
 		}
 
+		#[pallet::weight(1_000)]
+		pub fn partially_fulfill_payment(
+			origin: OriginFor<T>,
+			payment_hash: PaymentHashOf<T>,
+			fulfilled: Percent,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			Self::ensure_not_paused(PausableCall::FulfillPayment)?;
+			Self::do_partially_fulfill_payment(sender, payment_hash, fulfilled)?;
+			Ok(())
+		}
+
 		#[pallet::weight(1_000)]
 		pub fn complete_payment(
 			origin: OriginFor<T>,
 			payment_hash: PaymentHashOf<T>,
 		) -> DispatchResult {
 			let sender = ensure_signed(origin)?;
+			Self::ensure_not_paused(PausableCall::CompletePayment)?;
 			Self::do_complete_payment(sender, payment_hash)?;
 			Ok(())
 		}
-	}
 
-	impl<T: Config> Pallet<T> {
-		fn run_offchain_worker() -> DispatchResult {
-			Self::evaluate_pending_payments()?;
-			Self::evaluate_full_filled_payments()?;
+		#[pallet::weight(1_000)]
+		pub fn set_identity_requirement(
+			origin: OriginFor<T>,
+			requirement: Option<IdentityRequirement<BalanceOf<T>>>,
+		) -> DispatchResult {
+			let merchant = ensure_signed(origin)?;
+			Self::do_set_identity_requirement(merchant, requirement)?;
+			Ok(())
+		}
+
+		#[pallet::weight(1_000)]
+		pub fn set_acceptance_policy(
+			origin: OriginFor<T>,
+			policy: Option<AcceptancePolicy<BalanceOf<T>, T::Hash>>,
+		) -> DispatchResult {
+			let merchant = ensure_signed(origin)?;
+			Self::do_set_acceptance_policy(merchant, policy)?;
+			Ok(())
+		}
+
+		#[pallet::weight(1_000)]
+		pub fn set_referral_program(
+			origin: OriginFor<T>,
+			program: Option<ReferralProgram>,
+		) -> DispatchResult {
+			let merchant = ensure_signed(origin)?;
+			Self::do_set_referral_program(merchant, program)?;
+			Ok(())
+		}
+
+		#[pallet::weight(1_000)]
+		pub fn set_payout_routes(
+			origin: OriginFor<T>,
+			routes: Vec<(AccountOf<T>, Permill)>,
+		) -> DispatchResult {
+			let merchant = ensure_signed(origin)?;
+			Self::do_set_payout_routes(merchant, routes)?;
+			Ok(())
+		}
+
+		#[pallet::weight(1_000)]
+		pub fn set_spending_limit(
+			origin: OriginFor<T>,
+			who: AccountOf<T>,
+			currency_id: CurrencyId<T::Hash>,
+			limit: Option<SpendingLimit<BalanceOf<T>>>,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			Self::do_set_spending_limit(sender, who, currency_id, limit)?;
+			Ok(())
+		}
+
+		#[pallet::weight(1_000)]
+		pub fn set_spending_limit_admin(
+			origin: OriginFor<T>,
+			who: AccountOf<T>,
+			admin: Option<AccountOf<T>>,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			Self::do_set_spending_limit_admin(sender, who, admin)?;
+			Ok(())
+		}
+
+		#[pallet::weight(1_000)]
+		pub fn approve_payee(
+			origin: OriginFor<T>,
+			payee: AccountOf<T>,
+			currency_id: CurrencyId<T::Hash>,
+			per_payment_limit: BalanceOf<T>,
+			total_limit: BalanceOf<T>,
+			expires_at: MomentOf<T>,
+		) -> DispatchResult {
+			let payer = ensure_signed(origin)?;
+			Self::do_approve_payee(
+				payer,
+				payee,
+				currency_id,
+				per_payment_limit,
+				total_limit,
+				expires_at,
+			)?;
+			Ok(())
+		}
+
+		#[pallet::weight(1_000)]
+		pub fn revoke_payee_approval(
+			origin: OriginFor<T>,
+			payee: AccountOf<T>,
+			currency_id: CurrencyId<T::Hash>,
+		) -> DispatchResult {
+			let payer = ensure_signed(origin)?;
+			Self::do_revoke_payee_approval(payer, payee, currency_id)?;
+			Ok(())
+		}
+
+		#[pallet::weight(1_000)]
+		pub fn pull_payment(
+			origin: OriginFor<T>,
+			payer: AccountOf<T>,
+			amount: BalanceOf<T>,
+			currency_id: CurrencyId<T::Hash>,
+			description: PaymentDescription,
+			receipt: Vec<u8>,
+		) -> DispatchResult {
+			let payee = ensure_signed(origin)?;
+			Self::ensure_not_paused(PausableCall::CreatePayment)?;
+			Self::ensure_not_paused(PausableCall::AcceptPayment)?;
+			Self::do_pull_payment(payee, payer, amount, currency_id, description, receipt)?;
+			Ok(())
+		}
+
+		#[pallet::weight(1_000)]
+		pub fn set_refund_address(
+			origin: OriginFor<T>,
+			payment_hash: PaymentHashOf<T>,
+			refund_to: Option<AccountOf<T>>,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			Self::do_set_refund_address(sender, payment_hash, refund_to)?;
+			Ok(())
+		}
+
+		#[pallet::weight(1_000)]
+		pub fn set_payment_locale(
+			origin: OriginFor<T>,
+			payment_hash: PaymentHashOf<T>,
+			locale: Option<LanguageCode>,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			Self::do_set_payment_locale(sender, payment_hash, locale)?;
+			Ok(())
+		}
+
+		#[pallet::weight(1_000)]
+		pub fn set_payment_category(
+			origin: OriginFor<T>,
+			payment_hash: PaymentHashOf<T>,
+			category: Option<PaymentCategory>,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			Self::do_set_payment_category(sender, payment_hash, category)?;
+			Ok(())
+		}
+
+		#[pallet::weight(1_000)]
+		pub fn request_payment_receipt(
+			origin: OriginFor<T>,
+			payment_hash: PaymentHashOf<T>,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			Self::do_request_payment_receipt(sender, payment_hash)?;
+			Ok(())
+		}
+
+		#[pallet::weight(1_000)]
+		pub fn set_beneficiary(
+			origin: OriginFor<T>,
+			beneficiary: Option<AccountOf<T>>,
+		) -> DispatchResult {
+			let merchant = ensure_signed(origin)?;
+			Self::do_set_beneficiary(merchant, beneficiary)?;
+			Ok(())
+		}
+
+		#[pallet::weight(1_000)]
+		pub fn claim_inheritance(origin: OriginFor<T>, merchant: AccountOf<T>) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			Self::do_claim_inheritance(sender, merchant)?;
+			Ok(())
+		}
+
+		#[pallet::weight(1_000)]
+		pub fn execute_inheritance(origin: OriginFor<T>, merchant: AccountOf<T>) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			Self::do_execute_inheritance(sender, merchant)?;
+			Ok(())
+		}
+
+		#[pallet::weight(1_000)]
+		pub fn set_merchant_proxy(
+			origin: OriginFor<T>,
+			proxy: AccountOf<T>,
+			operations: Option<Vec<MerchantOperation>>,
+		) -> DispatchResult {
+			let merchant = ensure_signed(origin)?;
+			Self::do_set_merchant_proxy(merchant, proxy, operations)?;
+			Ok(())
+		}
+
+		#[pallet::weight(1_000)]
+		pub fn set_call_paused(
+			origin: OriginFor<T>,
+			call: PausableCall,
+			paused: bool,
+		) -> DispatchResult {
+			T::PauseOrigin::ensure_origin(origin)?;
+			Self::do_set_call_paused(call, paused)?;
+			Ok(())
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		fn run_offchain_worker() -> DispatchResult {
+			Self::evaluate_pending_payments()?;
+			Self::evaluate_full_filled_payments()?;
+
+			Ok(())
+		}
+
+		fn evaluate_pending_payments() -> DispatchResult {
+			let mut bucket = match <NextPendingPaymentsBucket<T>>::get() {
+				Some(bucket) => bucket,
+				None => return Ok(()),
+			};
+			let due_bucket = <timestamp::Pallet<T>>::get() / Self::queue_bucket_duration();
+			let mut batch_size = T::QueueBatchSize::get() as usize;
+
+			// Only the buckets that are out of the waiting time are touched.
+			while bucket <= due_bucket {
+				let payment_hashes = <PendingPaymentsQueue<T>>::get(bucket);
+				for payment_hash in payment_hashes.iter().take(batch_size) {
+					// A payment failing to expire leaves the queue, so it does not hold up the
+					// rest of the queue.
+					if let Err(error) = Self::do_expire_payment(*payment_hash) {
+						<PendingPaymentsQueue<T>>::mutate_exists(bucket, |hashes| {
+							Self::remove_from_bucket(hashes, payment_hash)
+						});
+						Self::note_queued_payment_failed(*payment_hash, error);
+					}
+				}
+				// The expired payments leave the bucket, the rest of it is evaluated in the next
+				// run.
+				if payment_hashes.len() > batch_size {
+					break
+				}
+				batch_size -= payment_hashes.len();
+				bucket += One::one();
+			}
+
+			<NextPendingPaymentsBucket<T>>::put(bucket);
+
+			Ok(())
+		}
+
+		fn evaluate_full_filled_payments() -> DispatchResult {
+			// The due buckets are evaluated once the completion is unpaused.
+			if Self::paused_calls(PausableCall::CompletePayment) {
+				return Ok(())
+			}
+
+			let mut bucket = match <NextFulfilledPaymentsBucket<T>>::get() {
+				Some(bucket) => bucket,
+				None => return Ok(()),
+			};
+			let due_bucket = <timestamp::Pallet<T>>::get() / Self::queue_bucket_duration();
+			let mut batch_size = T::QueueBatchSize::get() as usize;
+
+			while bucket <= due_bucket {
+				let payment_hashes = <FulfilledPaymentsQueue<T>>::get(bucket);
+				for payment_hash in payment_hashes.iter().take(batch_size) {
+					if let Err(error) = Self::do_auto_complete_full_filled_payments(*payment_hash) {
+						<FulfilledPaymentsQueue<T>>::mutate_exists(bucket, |hashes| {
+							Self::remove_from_bucket(hashes, payment_hash)
+						});
+						Self::note_queued_payment_failed(*payment_hash, error);
+					}
+				}
+				// The completed or postponed payments leave the bucket, the rest of it is
+				// evaluated in the next run.
+				if payment_hashes.len() > batch_size {
+					break
+				}
+				batch_size -= payment_hashes.len();
+				bucket += One::one();
+			}
+
+			<NextFulfilledPaymentsBucket<T>>::put(bucket);
+
+			Ok(())
+		}
+
+		fn remove_from_bucket(
+			payment_hashes: &mut Option<BoundedVec<PaymentHashOf<T>, T::MaxPaymentsPerBucket>>,
+			payment_hash: &PaymentHashOf<T>,
+		) {
+			if let Some(hashes) = payment_hashes {
+				hashes.retain(|hash| hash != payment_hash);
+				if hashes.is_empty() {
+					*payment_hashes = None;
+				}
+			}
+		}
+
+		fn note_queued_payment_failed(payment_hash: PaymentHashOf<T>, error: DispatchError) {
+			log::warn!(
+				target: "LRP protocol offchain worker",
+				"Fail to evaluate queued payment {:?}: {:?}",
+				payment_hash,
+				error,
+			);
+			<SpilledPaymentBuckets<T>>::remove(&payment_hash);
+			Self::deposit_event(Event::QueuedPaymentFailed { payment_hash, error });
+		}
+
+		// The bucket of a moment is rounded up, so every payment in a bucket is due once the
+		// bucket is due.
+		fn queue_bucket_of(moment: MomentOf<T>) -> MomentOf<T> {
+			let duration = Self::queue_bucket_duration();
+			(moment + duration - One::one()) / duration
+		}
+
+		fn queue_bucket_duration() -> MomentOf<T> {
+			T::QueueBucketDuration::get().max(One::one())
+		}
+
+		// A full bucket spills the payment over to the next bucket with room, so queueing never
+		// fails once the funds are escrowed.
+		pub(crate) fn enqueue_pending_payment(
+			payment_hash: PaymentHashOf<T>,
+			expired_time: MomentOf<T>,
+		) {
+			let mut bucket = Self::queue_bucket_of(expired_time);
+
+			match <NextPendingPaymentsBucket<T>>::get() {
+				Some(next_bucket) if bucket < next_bucket => bucket = next_bucket,
+				None => <NextPendingPaymentsBucket<T>>::put(bucket),
+				_ => (),
+			}
+
+			let due_bucket = bucket;
+			let capacity = T::MaxPaymentsPerBucket::get() as usize;
+			while <PendingPaymentsQueue<T>>::decode_len(bucket).unwrap_or_default() >= capacity {
+				bucket += One::one();
+			}
+			if bucket != due_bucket {
+				<SpilledPaymentBuckets<T>>::insert(&payment_hash, bucket);
+			}
+
+			<PendingPaymentsQueue<T>>::mutate(bucket, |payment_hashes| {
+				// The bucket has room, checked above.
+				let _ = payment_hashes.try_push(payment_hash);
+			});
+		}
+
+		fn dequeue_pending_payment(payment_hash: PaymentHashOf<T>, expired_time: MomentOf<T>) {
+			let mut bucket = Self::queue_bucket_of(expired_time);
+
+			if let Some(next_bucket) = <NextPendingPaymentsBucket<T>>::get() {
+				if bucket < next_bucket {
+					bucket = next_bucket;
+				}
+			}
+			let bucket = <SpilledPaymentBuckets<T>>::take(&payment_hash).unwrap_or(bucket);
+
+			<PendingPaymentsQueue<T>>::mutate_exists(bucket, |payment_hashes| {
+				if let Some(hashes) = payment_hashes {
+					hashes.retain(|&hash| hash != payment_hash);
+					if hashes.is_empty() {
+						*payment_hashes = None;
+					}
+				}
+			});
+		}
+
+		pub(crate) fn enqueue_fulfilled_payment(
+			payment_hash: PaymentHashOf<T>,
+			completed_time: MomentOf<T>,
+		) {
+			let mut bucket = Self::queue_bucket_of(completed_time);
+
+			match <NextFulfilledPaymentsBucket<T>>::get() {
+				Some(next_bucket) if bucket < next_bucket => bucket = next_bucket,
+				None => <NextFulfilledPaymentsBucket<T>>::put(bucket),
+				_ => (),
+			}
+
+			let due_bucket = bucket;
+			let capacity = T::MaxPaymentsPerBucket::get() as usize;
+			while <FulfilledPaymentsQueue<T>>::decode_len(bucket).unwrap_or_default() >= capacity {
+				bucket += One::one();
+			}
+			if bucket != due_bucket {
+				<SpilledPaymentBuckets<T>>::insert(&payment_hash, bucket);
+			}
+
+			<FulfilledPaymentsQueue<T>>::mutate(bucket, |payment_hashes| {
+				// The bucket has room, checked above.
+				let _ = payment_hashes.try_push(payment_hash);
+			});
+		}
+
+		fn dequeue_fulfilled_payment(payment_hash: PaymentHashOf<T>, completed_time: MomentOf<T>) {
+			let mut bucket = Self::queue_bucket_of(completed_time);
+
+			if let Some(next_bucket) = <NextFulfilledPaymentsBucket<T>>::get() {
+				if bucket < next_bucket {
+					bucket = next_bucket;
+				}
+			}
+			let bucket = <SpilledPaymentBuckets<T>>::take(&payment_hash).unwrap_or(bucket);
+
+			<FulfilledPaymentsQueue<T>>::mutate_exists(bucket, |payment_hashes| {
+				if let Some(hashes) = payment_hashes {
+					hashes.retain(|&hash| hash != payment_hash);
+					if hashes.is_empty() {
+						*payment_hashes = None;
+					}
+				}
+			});
+		}
+
+		/// The moment a fulfilled payment is due for the auto-completion, postponed by the hold of
+		/// its payee if any.
+		fn fulfilled_completion_time(
+			payment_hash: &PaymentHashOf<T>,
+			payment: &Payment<T>,
+		) -> MomentOf<T> {
+			let completion_time = payment.updated_at + T::FulfilledPaymentWaitingTime::get();
+			Self::payment_holds(payment_hash)
+				.map_or(completion_time, |held_until| held_until.max(completion_time))
+		}
+
+		fn dequeue_fulfilled_payment_of(payment_hash: PaymentHashOf<T>, payment: &Payment<T>) {
+			let completion_time = Self::fulfilled_completion_time(&payment_hash, payment);
+			Self::dequeue_fulfilled_payment(payment_hash, completion_time);
+			<PaymentHolds<T>>::remove(&payment_hash);
+		}
+
+		// The counters restart with every window. The payee is held once both of them reach the
+		// policy, and the hold is extended by every further dispute of the window.
+		fn track_payee_velocity(payee: &AccountOf<T>, disputed: bool) {
+			let policy = match T::VelocityPolicy::get() {
+				Some(policy) => policy,
+				None => return,
+			};
+
+			let now = <timestamp::Pallet<T>>::get();
+			let mut velocity = Self::payee_velocities(payee);
+
+			if now >= velocity.window_started_at + policy.window {
+				velocity.window_started_at = now;
+				velocity.fulfillments = 0;
+				velocity.disputes = 0;
+			}
+
+			if disputed {
+				velocity.disputes = velocity.disputes.saturating_add(1);
+			} else {
+				velocity.fulfillments = velocity.fulfillments.saturating_add(1);
+			}
+
+			let triggered = disputed &&
+				velocity.fulfillments >= policy.fulfillments &&
+				velocity.disputes >= policy.disputes;
+			if triggered {
+				velocity.held_until = now + policy.hold;
+				Self::deposit_event(Event::PayeeHeld {
+					payee: payee.clone(),
+					held_until: velocity.held_until,
+				});
+			}
+
+			<PayeeVelocities<T>>::insert(payee, velocity);
+		}
+
+		fn do_set_identity_requirement(
+			merchant: AccountOf<T>,
+			requirement: Option<IdentityRequirement<BalanceOf<T>>>,
+		) -> DispatchResult {
+			match requirement.clone() {
+				Some(requirement) => <IdentityRequirements<T>>::insert(&merchant, requirement),
+				None => <IdentityRequirements<T>>::remove(&merchant),
+			}
+
+			Self::deposit_event(Event::IdentityRequirementUpdated { merchant, requirement });
+
+			Ok(())
+		}
+
+		fn ensure_identity_requirement(
+			payer: &AccountOf<T>,
+			payee: &AccountOf<T>,
+			amount: BalanceOf<T>,
+		) -> DispatchResult {
+			let requirement = Self::identity_requirements(payee)
+				.unwrap_or_else(T::DefaultIdentityRequirement::get);
+
+			if amount < requirement.threshold {
+				return Ok(())
+			}
+			if requirement.payer {
+				Self::ensure_credible_identity(payer, requirement.min_credibility)?;
+			}
+			if requirement.payee {
+				Self::ensure_credible_identity(payee, requirement.min_credibility)?;
+			}
+
+			Ok(())
+		}
+
+		fn ensure_credible_identity(
+			who: &AccountOf<T>,
+			min_credibility: Credibility,
+		) -> DispatchResult {
+			ensure!(T::IdentitiesManager::has_identity(who), <Error<T>>::IdentityRequired);
+			ensure!(
+				T::IdentitiesManager::get_credibility(who)? >= min_credibility,
+				<Error<T>>::InsufficientCredibility
+			);
+			Ok(())
+		}
+
+		fn do_set_acceptance_policy(
+			merchant: AccountOf<T>,
+			policy: Option<AcceptancePolicy<BalanceOf<T>, T::Hash>>,
+		) -> DispatchResult {
+			match policy.clone() {
+				Some(policy) => {
+					if let (Some(min_amount), Some(max_amount)) =
+						(policy.min_amount, policy.max_amount)
+					{
+						ensure!(min_amount <= max_amount, <Error<T>>::InvalidPolicy);
+					}
+					ensure!(
+						policy.currencies.len() <= T::MaxPolicyCurrencies::get() as usize,
+						<Error<T>>::TooManyPolicyCurrencies
+					);
+					<AcceptancePolicies<T>>::insert(&merchant, policy);
+				},
+				None => <AcceptancePolicies<T>>::remove(&merchant),
+			}
+
+			Self::deposit_event(Event::AcceptancePolicyUpdated { merchant, policy });
+
+			Ok(())
+		}
+
+		fn do_set_referral_program(
+			merchant: AccountOf<T>,
+			program: Option<ReferralProgram>,
+		) -> DispatchResult {
+			match program.clone() {
+				Some(program) => {
+					ensure!(
+						program.fee_share <= T::MaxReferralFeeShare::get(),
+						<Error<T>>::InvalidReferralProgram
+					);
+					<ReferralPrograms<T>>::insert(&merchant, program);
+				},
+				None => <ReferralPrograms<T>>::remove(&merchant),
+			}
+
+			Self::deposit_event(Event::ReferralProgramUpdated { merchant, program });
+
+			Ok(())
+		}
+
+		fn do_set_payout_routes(
+			merchant: AccountOf<T>,
+			routes: Vec<(AccountOf<T>, Permill)>,
+		) -> DispatchResult {
+			ensure!(
+				T::IdentitiesManager::is_organization(&merchant),
+				<Error<T>>::NotAnOrganization
+			);
+
+			if routes.is_empty() {
+				<PayoutRoutes<T>>::remove(&merchant);
+			} else {
+				let bounded_routes: BoundedVec<_, T::MaxPayoutRoutes> =
+					routes.clone().try_into().map_err(|_| <Error<T>>::TooManyPayoutRoutes)?;
+
+				let mut total: u32 = 0;
+				for (index, (account, share)) in routes.iter().enumerate() {
+					ensure!(
+						!routes[..index].iter().any(|(other, _)| other == account),
+						<Error<T>>::InvalidPayoutRoutes
+					);
+					ensure!(
+						*account == merchant ||
+							T::IdentitiesManager::get_organization(account).as_ref() ==
+								Some(&merchant),
+						<Error<T>>::PayoutAccountNotMember
+					);
+					total = total.saturating_add(share.deconstruct());
+				}
+				ensure!(total == Permill::one().deconstruct(), <Error<T>>::InvalidPayoutRoutes);
+
+				<PayoutRoutes<T>>::insert(&merchant, bounded_routes);
+			}
+
+			Self::deposit_event(Event::PayoutRoutesUpdated { merchant, routes });
+
+			Ok(())
+		}
+
+		/// Pay the payout of a completed payment to the payee, split to its payout routes if it
+		/// has any. The last route gets the rounding remainder.
+		fn do_pay_payout(
+			payment_hash: PaymentHashOf<T>,
+			payment: &Payment<T>,
+			payout: BalanceOf<T>,
+		) -> DispatchResult {
+			let routes = Self::payout_routes(&payment.payee);
+			if routes.is_empty() {
+				let payee = Self::payee_payout_account(&payment.payee);
+				return Self::do_pay_from_escrow(payment.currency_id, &payee, payout)
+			}
+
+			let mut remaining = payout;
+			let mut payouts = Vec::new();
+			for (index, (account, share)) in routes.iter().enumerate() {
+				let amount = if index + 1 == routes.len() {
+					remaining
+				} else {
+					share.mul_floor(payout).min(remaining)
+				};
+				remaining -= amount;
+				Self::do_pay_from_escrow(payment.currency_id, account, amount)?;
+				payouts.push((account.clone(), amount));
+			}
+
+			Self::deposit_event(Event::PayoutRouted {
+				payment_hash,
+				payee: payment.payee.clone(),
+				currency_id: payment.currency_id,
+				payouts,
+			});
+
+			Ok(())
+		}
+
+		// The referral is only attributed if the payee runs a referral program, with the fee share
+		// of the program at the creation.
+		fn ensure_referral(
+			payer: &AccountOf<T>,
+			payee: &AccountOf<T>,
+			referrer: &AccountOf<T>,
+		) -> Result<Permill, DispatchError> {
+			ensure!(referrer != payer && referrer != payee, <Error<T>>::InvalidReferrer);
+			let program =
+				Self::referral_programs(payee).ok_or(<Error<T>>::ReferralProgramNotFound)?;
+			Ok(program.fee_share)
+		}
+
+		// The payments out of the acceptance policy of the payee cannot be created or accepted,
+		// so the payee does not need to reject them manually.
+		fn ensure_acceptance_policy(
+			payer: &AccountOf<T>,
+			payee: &AccountOf<T>,
+			amount: BalanceOf<T>,
+			currency_id: CurrencyId<T::Hash>,
+		) -> DispatchResult {
+			let policy = match Self::acceptance_policies(payee) {
+				Some(policy) => policy,
+				None => return Ok(()),
+			};
+
+			if let Some(min_amount) = policy.min_amount {
+				ensure!(amount >= min_amount, <Error<T>>::AmountOutOfPolicy);
+			}
+			if let Some(max_amount) = policy.max_amount {
+				ensure!(amount <= max_amount, <Error<T>>::AmountOutOfPolicy);
+			}
+			ensure!(
+				policy.currencies.is_empty() || policy.currencies.contains(&currency_id),
+				<Error<T>>::UnacceptedCurrency
+			);
+			if let Some(min_credibility) = policy.min_payer_credibility {
+				Self::ensure_credible_identity(payer, min_credibility)?;
+			}
+
+			Ok(())
+		}
+
+		// The spending limits of an account are managed by its admin, or the account itself if
+		// there is no admin.
+		fn ensure_spending_limit_manager(
+			sender: &AccountOf<T>,
+			who: &AccountOf<T>,
+		) -> DispatchResult {
+			match Self::spending_limit_admins(who) {
+				Some(admin) => ensure!(*sender == admin, <Error<T>>::NotSpendingLimitManager),
+				None => ensure!(sender == who, <Error<T>>::NotSpendingLimitManager),
+			}
+			Ok(())
+		}
+
+		fn do_set_spending_limit(
+			sender: AccountOf<T>,
+			who: AccountOf<T>,
+			currency_id: CurrencyId<T::Hash>,
+			limit: Option<SpendingLimit<BalanceOf<T>>>,
+		) -> DispatchResult {
+			Self::ensure_spending_limit_manager(&sender, &who)?;
+
+			match limit.clone() {
+				Some(limit) => <SpendingLimits<T>>::insert(&who, currency_id, limit),
+				None => <SpendingLimits<T>>::remove(&who, currency_id),
+			}
+
+			Self::deposit_event(Event::SpendingLimitUpdated { who, currency_id, limit });
+
+			Ok(())
+		}
+
+		fn do_set_spending_limit_admin(
+			sender: AccountOf<T>,
+			who: AccountOf<T>,
+			admin: Option<AccountOf<T>>,
+		) -> DispatchResult {
+			Self::ensure_spending_limit_manager(&sender, &who)?;
+
+			match admin.clone() {
+				Some(admin) => <SpendingLimitAdmins<T>>::insert(&who, admin),
+				None => <SpendingLimitAdmins<T>>::remove(&who),
+			}
+
+			Self::deposit_event(Event::SpendingLimitAdminUpdated { who, admin });
+
+			Ok(())
+		}
+
+		fn do_approve_payee(
+			payer: AccountOf<T>,
+			payee: AccountOf<T>,
+			currency_id: CurrencyId<T::Hash>,
+			per_payment_limit: BalanceOf<T>,
+			total_limit: BalanceOf<T>,
+			expires_at: MomentOf<T>,
+		) -> DispatchResult {
+			ensure!(payer != payee, <Error<T>>::InvalidPayeeApproval);
+			ensure!(per_payment_limit <= total_limit, <Error<T>>::InvalidPayeeApproval);
+			ensure!(expires_at > <timestamp::Pallet<T>>::get(), <Error<T>>::InvalidPayeeApproval);
+
+			// A new approval replaces the previous one, the pulled amount is counted from zero.
+			let approval =
+				PayeeApproval { per_payment_limit, total_limit, pulled: Zero::zero(), expires_at };
+			<PayeeApprovals<T>>::insert(&payer, (payee.clone(), currency_id), approval.clone());
+
+			Self::deposit_event(Event::PayeeApprovalUpdated {
+				payer,
+				payee,
+				currency_id,
+				approval: Some(approval),
+			});
+
+			Ok(())
+		}
+
+		fn do_revoke_payee_approval(
+			payer: AccountOf<T>,
+			payee: AccountOf<T>,
+			currency_id: CurrencyId<T::Hash>,
+		) -> DispatchResult {
+			let key = (payee.clone(), currency_id);
+			ensure!(<PayeeApprovals<T>>::contains_key(&payer, &key), <Error<T>>::PayeeNotApproved);
+
+			<PayeeApprovals<T>>::remove(&payer, &key);
+
+			Self::deposit_event(Event::PayeeApprovalUpdated {
+				payer,
+				payee,
+				currency_id,
+				approval: None,
+			});
+
+			Ok(())
+		}
+
+		fn do_pull_payment(
+			payee: AccountOf<T>,
+			payer: AccountOf<T>,
+			amount: BalanceOf<T>,
+			currency_id: CurrencyId<T::Hash>,
+			description: PaymentDescription,
+			receipt: Vec<u8>,
+		) -> DispatchResult {
+			let key = (payee.clone(), currency_id);
+			let mut approval =
+				Self::payee_approvals(&payer, &key).ok_or(<Error<T>>::PayeeNotApproved)?;
+
+			ensure!(
+				<timestamp::Pallet<T>>::get() < approval.expires_at,
+				<Error<T>>::PayeeApprovalExpired
+			);
+			ensure!(amount <= approval.per_payment_limit, <Error<T>>::PullLimitExceeded);
+			let pulled = approval.pulled.saturating_add(amount);
+			ensure!(pulled <= approval.total_limit, <Error<T>>::PullLimitExceeded);
+
+			// The pulled payment goes through the same checks as the payments created by the payer.
+			let payment_hash = Self::do_create_payment(
+				payer.clone(),
+				payee.clone(),
+				amount,
+				currency_id,
+				description,
+				receipt,
+				None,
+				None,
+			)?;
+			// The payee accepts the payment along with the pull, unless its acceptance policy
+			// already did on the creation.
+			let pending = Self::payments(&payment_hash)
+				.map_or(false, |payment| payment.status == PaymentStatus::Pending);
+			if pending {
+				Self::do_accept_pending_payment(payee.clone(), payment_hash)?;
+			}
+
+			approval.pulled = pulled;
+			<PayeeApprovals<T>>::insert(&payer, &key, approval);
+
+			Self::deposit_event(Event::PaymentPulled { payment_hash, payer, payee, amount });
+
+			Ok(())
+		}
+
+		fn do_set_merchant_proxy(
+			merchant: AccountOf<T>,
+			proxy: AccountOf<T>,
+			operations: Option<Vec<MerchantOperation>>,
+		) -> DispatchResult {
+			ensure!(merchant != proxy, <Error<T>>::InvalidMerchantProxy);
+
+			match operations.clone() {
+				Some(operations) => <MerchantProxies<T>>::insert(&merchant, &proxy, operations),
+				None => <MerchantProxies<T>>::remove(&merchant, &proxy),
+			}
+
+			Self::deposit_event(Event::MerchantProxyUpdated { merchant, proxy, operations });
+
+			Ok(())
+		}
+
+		// Designating a beneficiary cancels the pending claim, and takes the payouts back from the
+		// heir if the merchant was taken over.
+		fn do_set_beneficiary(
+			merchant: AccountOf<T>,
+			beneficiary: Option<AccountOf<T>>,
+		) -> DispatchResult {
+			ensure!(beneficiary.as_ref() != Some(&merchant), <Error<T>>::InvalidBeneficiary);
+
+			match beneficiary.clone() {
+				Some(account) => <Beneficiaries<T>>::insert(
+					&merchant,
+					Beneficiary {
+						account,
+						nonce: <frame_system::Pallet<T>>::account_nonce(&merchant),
+						active_at: <timestamp::Pallet<T>>::get(),
+						claimed_at: None,
+					},
+				),
+				None => <Beneficiaries<T>>::remove(&merchant),
+			}
+			// The heir loses the operations granted by the executed inheritance.
+			if let Some(heir) = <Heirs<T>>::take(&merchant) {
+				<MerchantProxies<T>>::remove(&merchant, &heir);
+			}
+
+			Self::deposit_event(Event::BeneficiaryUpdated { merchant, beneficiary });
+
+			Ok(())
+		}
+
+		// The claim observes the nonce of the merchant first, a merchant who sent any transaction
+		// since the last observation is not inactive.
+		fn do_claim_inheritance(sender: AccountOf<T>, merchant: AccountOf<T>) -> DispatchResult {
+			let mut beneficiary =
+				Self::beneficiaries(&merchant).ok_or(<Error<T>>::BeneficiaryNotFound)?;
+			ensure!(sender == beneficiary.account, <Error<T>>::NotBeneficiary);
+
+			let now = <timestamp::Pallet<T>>::get();
+			let nonce = <frame_system::Pallet<T>>::account_nonce(&merchant);
+			if nonce != beneficiary.nonce {
+				beneficiary.nonce = nonce;
+				beneficiary.active_at = now;
+				beneficiary.claimed_at = None;
+				<Beneficiaries<T>>::insert(&merchant, beneficiary);
+				Self::deposit_event(Event::MerchantActivityObserved { merchant, active_at: now });
+				return Ok(())
+			}
+
+			ensure!(
+				now >= beneficiary.active_at + T::InheritanceInactivityPeriod::get(),
+				<Error<T>>::MerchantNotInactive
+			);
+			ensure!(beneficiary.claimed_at.is_none(), <Error<T>>::InheritanceAlreadyClaimed);
+
+			beneficiary.claimed_at = Some(now);
+			<Beneficiaries<T>>::insert(&merchant, beneficiary);
+
+			Self::deposit_event(Event::InheritanceClaimed {
+				merchant,
+				beneficiary: sender,
+				executable_at: now + T::InheritanceChallengeWindow::get(),
+			});
+
+			Ok(())
+		}
+
+		// The heir operates the payments of the merchant as a proxy with all the operations, and
+		// receives its payouts.
+		fn do_execute_inheritance(sender: AccountOf<T>, merchant: AccountOf<T>) -> DispatchResult {
+			let beneficiary =
+				Self::beneficiaries(&merchant).ok_or(<Error<T>>::BeneficiaryNotFound)?;
+			ensure!(sender == beneficiary.account, <Error<T>>::NotBeneficiary);
+
+			let claimed_at = beneficiary.claimed_at.ok_or(<Error<T>>::InheritanceNotClaimed)?;
+			ensure!(
+				<timestamp::Pallet<T>>::get() >= claimed_at + T::InheritanceChallengeWindow::get(),
+				<Error<T>>::ChallengeWindowNotOver
+			);
+			ensure!(
+				<frame_system::Pallet<T>>::account_nonce(&merchant) == beneficiary.nonce,
+				<Error<T>>::MerchantActive
+			);
+
+			<Beneficiaries<T>>::remove(&merchant);
+			<Heirs<T>>::insert(&merchant, &sender);
+			<MerchantProxies<T>>::insert(
+				&merchant,
+				&sender,
+				[
+					MerchantOperation::AcceptPayment,
+					MerchantOperation::RejectPayment,
+					MerchantOperation::AddDeliveryProof,
+					MerchantOperation::FulfillPayment,
+				]
+				.to_vec(),
+			);
+
+			Self::deposit_event(Event::InheritanceExecuted { merchant, heir: sender });
+
+			Ok(())
+		}
+
+		fn do_set_call_paused(call: PausableCall, paused: bool) -> DispatchResult {
+			if paused {
+				<PausedCalls<T>>::insert(&call, true);
+			} else {
+				<PausedCalls<T>>::remove(&call);
+			}
+
+			Self::deposit_event(Event::CallPauseUpdated { call, paused });
+
+			Ok(())
+		}
+
+		fn ensure_not_paused(call: PausableCall) -> DispatchResult {
+			ensure!(!Self::paused_calls(call), <Error<T>>::CallPaused);
+			Ok(())
+		}
+
+		// The payee operations are done by the payee or its proxies authorized to the operation.
+		fn ensure_payee_or_proxy(
+			sender: &AccountOf<T>,
+			payment: &Payment<T>,
+			operation: MerchantOperation,
+		) -> DispatchResult {
+			if *sender == payment.payee {
+				return Ok(())
+			}
+			let operations = Self::merchant_proxies(&payment.payee, sender).unwrap_or_default();
+			ensure!(operations.contains(&operation), <Error<T>>::NotPayeeOrProxy);
+			Ok(())
+		}
+
+		// Add the amount to the spending of the current periods, the periods are restarted once
+		// they are over. The record is checked against the limit but not stored, it is stored by
+		// `record_spending` once the payment is created.
+		fn track_spending(
+			payer: &AccountOf<T>,
+			currency_id: CurrencyId<T::Hash>,
+			amount: BalanceOf<T>,
+		) -> Result<Option<SpendingRecord<BalanceOf<T>, MomentOf<T>>>, DispatchError> {
+			let limit = match Self::spending_limits(payer, currency_id) {
+				Some(limit) => limit,
+				None => return Ok(None),
+			};
+
+			let now = <timestamp::Pallet<T>>::get();
+			let mut record = Self::spending_records(payer, currency_id);
+
+			if now >= record.day_started_at + T::DailySpendingPeriod::get() {
+				record.day_started_at = now;
+				record.day_spent = Zero::zero();
+			}
+			if now >= record.week_started_at + T::WeeklySpendingPeriod::get() {
+				record.week_started_at = now;
+				record.week_spent = Zero::zero();
+			}
+
+			record.day_spent = record.day_spent.saturating_add(amount);
+			record.week_spent = record.week_spent.saturating_add(amount);
+
+			if let Some(daily) = limit.daily {
+				ensure!(record.day_spent <= daily, <Error<T>>::SpendingLimitExceeded);
+			}
+			if let Some(weekly) = limit.weekly {
+				ensure!(record.week_spent <= weekly, <Error<T>>::SpendingLimitExceeded);
+			}
+
+			Ok(Some(record))
+		}
+
+		fn record_spending(
+			payer: &AccountOf<T>,
+			currency_id: CurrencyId<T::Hash>,
+			record: Option<SpendingRecord<BalanceOf<T>, MomentOf<T>>>,
+		) {
+			if let Some(record) = record {
+				<SpendingRecords<T>>::insert(payer, currency_id, record);
+			}
+		}
+
+		/// Whether the account holds the receipt of the payment, as a payer or a payee.
+		pub fn has_payment_receipt(who: &AccountOf<T>, payment_hash: &PaymentHashOf<T>) -> bool {
+			<ReceiptsOwned<T>>::contains_key(who, payment_hash)
+		}
+
+		/// The account holding the funds of the open payments.
+		pub fn escrow_account() -> AccountOf<T> {
+			T::PalletId::get().into_account()
+		}
+
+		/// Check the accounting invariants of the payments, run by `try-runtime` after the runtime
+		/// upgrades:
+		/// - The escrow account holds the funds of the open payments in each currency.
+		/// - The total value locked in each currency is the funds of the open payments.
+		/// - Every payment is indexed by its status and creation time.
+		/// - No funds are reserved by the pallet since the payments are escrowed.
+		#[cfg(any(feature = "try-runtime", test))]
+		pub fn do_try_state() -> Result<(), &'static str> {
+			let mut escrowed: Vec<(CurrencyId<T::Hash>, BalanceOf<T>)> = Vec::new();
+			for (payment_hash, payment) in <Payments<T>>::iter() {
+				ensure!(
+					<PaymentsByStatus<T>>::contains_key(
+						Self::status_index_bucket(&payment.status, payment.created_at),
+						payment_hash
+					),
+					"A payment is not indexed by its status"
+				);
+
+				let open = matches!(
+					payment.status,
+					PaymentStatus::Pending |
+						PaymentStatus::Accepted |
+						PaymentStatus::Fulfilled |
+						PaymentStatus::PartiallyFulfilled(_) |
+						PaymentStatus::Disputed
+				);
+				if !open {
+					continue
+				}
+				let currency_id = payment.currency_id;
+				match escrowed.iter_mut().find(|(escrowed_id, _)| *escrowed_id == currency_id) {
+					Some((_, total)) => *total += payment.amount,
+					None => escrowed.push((currency_id, payment.amount)),
+				}
+			}
+
+			let escrow_account = Self::escrow_account();
+			for (currency_id, total) in escrowed.iter() {
+				ensure!(
+					*total <= T::Currency::free_balance(*currency_id, &escrow_account),
+					"The escrow account does not hold the funds of the open payments"
+				);
+				ensure!(
+					*total == Self::total_value_locked(currency_id),
+					"The total value locked is not the funds of the open payments"
+				);
+			}
+			ensure!(
+				<TotalValueLocked<T>>::iter().all(|(currency_id, locked)| {
+					locked.is_zero() ||
+						escrowed.iter().any(|(escrowed_id, _)| *escrowed_id == currency_id)
+				}),
+				"The total value locked is not the funds of the open payments"
+			);
+
+			ensure!(
+				<NamedReserves<T>>::iter().next().is_none(),
+				"The funds of the payments are reserved instead of escrowed"
+			);
+
+			Ok(())
+		}
+
+		/// The funds received and sent by the account through the payments in the currency, in the
+		/// eras of the blocks from `from` to `to`.
+		pub fn account_statement(
+			who: AccountOf<T>,
+			currency_id: CurrencyId<T::Hash>,
+			from: T::BlockNumber,
+			to: T::BlockNumber,
+		) -> PaymentFlows<BalanceOf<T>> {
+			let (from, to) = (Self::statement_era(from), Self::statement_era(to));
+			<AccountFlows<T>>::iter_prefix((who, currency_id))
+				.filter(|(era, _)| *era >= from && *era <= to)
+				.fold(PaymentFlows::default(), |mut statement, (_, flows)| {
+					statement.inflow = statement.inflow.saturating_add(flows.inflow);
+					statement.outflow = statement.outflow.saturating_add(flows.outflow);
+					statement
+				})
+		}
+
+		/// The hashes of the payments with the status created in the same bucket of the status
+		/// index as `created_at`, at most `limit` of them from the `start` position of the bucket.
+		/// The payments of a bucket are ordered by the hash of their payment hash.
+		pub fn payments_with_status(
+			status: PaymentStatus,
+			created_at: MomentOf<T>,
+			start: u32,
+			limit: u32,
+		) -> Vec<PaymentHashOf<T>> {
+			<PaymentsByStatus<T>>::iter_key_prefix(Self::status_index_bucket(&status, created_at))
+				.skip(start as usize)
+				.take(limit as usize)
+				.collect()
+		}
+
+		/// The bucket of the status index of the payments created at the moment.
+		pub fn index_bucket_of(moment: MomentOf<T>) -> MomentOf<T> {
+			moment / T::PaymentIndexBucketDuration::get().max(One::one())
+		}
+
+		// The partially fulfilled payments share one key of the status index.
+		fn status_index_key(status: &PaymentStatus) -> PaymentStatus {
+			match status {
+				PaymentStatus::PartiallyFulfilled(_) =>
+					PaymentStatus::PartiallyFulfilled(Percent::from_percent(0)),
+				status => status.clone(),
+			}
+		}
+
+		// The bucket of the status index holding the payments with the status created at the time.
+		fn status_index_bucket(
+			status: &PaymentStatus,
+			created_at: MomentOf<T>,
+		) -> (PaymentStatus, MomentOf<T>) {
+			(Self::status_index_key(status), Self::index_bucket_of(created_at))
+		}
+
+		pub(crate) fn index_payment(
+			payment_hash: PaymentHashOf<T>,
+			status: &PaymentStatus,
+			created_at: MomentOf<T>,
+		) {
+			<PaymentsByStatus<T>>::insert(
+				Self::status_index_bucket(status, created_at),
+				payment_hash,
+				(),
+			);
+		}
+
+		fn unindex_payment(
+			payment_hash: PaymentHashOf<T>,
+			status: &PaymentStatus,
+			created_at: MomentOf<T>,
+		) {
+			<PaymentsByStatus<T>>::remove(
+				Self::status_index_bucket(status, created_at),
+				payment_hash,
+			);
+		}
+
+		fn statement_era(block_number: T::BlockNumber) -> T::BlockNumber {
+			block_number / T::StatementEraDuration::get().max(One::one())
+		}
+
+		// Count the flow in the current era of the account.
+		fn record_flow(
+			who: &AccountOf<T>,
+			currency_id: CurrencyId<T::Hash>,
+			f: impl FnOnce(&mut PaymentFlows<BalanceOf<T>>),
+		) {
+			let era = Self::statement_era(<frame_system::Pallet<T>>::block_number());
+			<AccountFlows<T>>::mutate((who.clone(), currency_id), era, f);
+		}
+
+		// The escrowed funds of the merchants taken over by their heirs are paid to the heirs.
+		fn do_pay_from_escrow(
+			currency_id: CurrencyId<T::Hash>,
+			to: &AccountOf<T>,
+			amount: BalanceOf<T>,
+		) -> DispatchResult {
+			T::Currency::transfer(currency_id, &Self::escrow_account(), to, amount)?;
+			<TotalValueLocked<T>>::mutate(currency_id, |locked| {
+				*locked = locked.saturating_sub(amount)
+			});
+			Self::record_flow(to, currency_id, |flows| {
+				flows.inflow = flows.inflow.saturating_add(amount)
+			});
+			Ok(())
+		}
+
+		// The payouts of the payments of an inherited merchant go to its heir.
+		fn payee_payout_account(payee: &AccountOf<T>) -> AccountOf<T> {
+			Self::heirs(payee).unwrap_or_else(|| payee.clone())
+		}
+
+		/// The fee charged when the amount is transferred to the payee, capped by the amount.
+		pub fn compute_payment_fee(
+			currency_id: CurrencyId<T::Hash>,
+			amount: BalanceOf<T>,
+		) -> BalanceOf<T> {
+			T::PaymentFee::fee_schedule(&currency_id).map_or_else(Zero::zero, |schedule| {
+				schedule.flat.saturating_add(schedule.percentage.mul_floor(amount)).min(amount)
+			})
+		}
+
+		pub fn compute_payment_risk_score(
+			payer: &AccountOf<T>,
+			payee: &AccountOf<T>,
+			amount: BalanceOf<T>,
+			category: Option<PaymentCategory>,
+		) -> RiskScore {
+			let payer_credibility = if T::IdentitiesManager::has_identity(payer) {
+				T::IdentitiesManager::get_credibility(payer).ok()
+			} else {
+				None
+			};
+
+			let now = <timestamp::Pallet<T>>::get();
+			let account_age = Self::accounts_first_seen(payer)
+				.map(|first_seen| now - first_seen)
+				.unwrap_or_else(Zero::zero);
+
+			compute_risk_score(&RiskFactors {
+				payer_credibility,
+				account_maturity: Permill::from_rational(
+					account_age.saturated_into::<u128>(),
+					T::RiskMatureAccountAge::get().saturated_into::<u128>(),
+				),
+				disputed_payments: Self::disputed_payments_count(payer),
+				amount_ratio: Permill::from_rational(
+					amount.saturated_into::<u128>(),
+					T::RiskHighAmount::get().saturated_into::<u128>(),
+				),
+				high_risk_category: category.map_or(false, |category| category.is_high_risk()),
+				payee_loss_rate: T::IdentitiesManager::get_dispute_record(payee).loss_rate(),
+				attested_by_payee: T::IdentitiesManager::is_attested_by(payer, payee),
+			})
+		}
+
+		/// The hash of the payment with the id, created by the payer to the payee at the account
+		/// nonce of the payer.
+		pub fn compute_payment_hash(
+			id: u128,
+			payer: &AccountOf<T>,
+			payee: &AccountOf<T>,
+			nonce: T::Index,
+		) -> PaymentHashOf<T> {
+			T::Hashing::hash_of(&(id, payer, payee, nonce))
+		}
+
+		fn do_create_payment(
+			payer: AccountOf<T>,
+			payee: AccountOf<T>,
+			amount: BalanceOf<T>,
+			currency_id: CurrencyId<T::Hash>,
+			description: PaymentDescription,
+			receipt: Vec<u8>,
+			fee_account: Option<AccountOf<T>>,
+			referrer: Option<AccountOf<T>>,
+		) -> Result<PaymentHashOf<T>, DispatchError> {
+			let id = <LatestPaymentId<T>>::get().checked_add(1).ok_or(<Error<T>>::Overflow)?;
+
+			ensure!(
+				T::Currency::free_balance(currency_id, &payer) >= amount,
+				<Error<T>>::InsufficientBalance,
+			);
+
+			ensure!(
+				T::CurrenciesManager::is_currency_accepted(&payee, &currency_id),
+				<Error<T>>::UnacceptedCurrency
+			);
+
+			let (description, encrypted_description) = match description {
+				PaymentDescription::Plain(description) => (description, None),
+				PaymentDescription::EncryptedDescription(cipher) => {
+					let key = T::IdentitiesManager::get_encryption_key(&payee)
+						.ok_or(<Error<T>>::EncryptionKeyNotFound)?;
+					(Vec::new(), Some((key, cipher)))
+				},
+			};
+
+			Self::ensure_identity_requirement(&payer, &payee, amount)?;
+			Self::ensure_acceptance_policy(&payer, &payee, amount, currency_id)?;
+			T::ComplianceCheck::check_payment(&payer, &payee, &currency_id, amount)?;
+			let referral = match referrer {
+				Some(referrer) => {
+					let fee_share = Self::ensure_referral(&payer, &payee, &referrer)?;
+					Some((referrer, fee_share))
+				},
+				None => None,
+			};
+			let spending = Self::track_spending(&payer, currency_id, amount)?;
+
+			let now = <timestamp::Pallet<T>>::get();
+			let nonce = <frame_system::Pallet<T>>::account_nonce(&payer);
+			let payment_hash = Self::compute_payment_hash(id, &payer, &payee, nonce);
+
+			T::Currency::transfer(currency_id, &payer, &Self::escrow_account(), amount)?;
+			<TotalValueLocked<T>>::mutate(currency_id, |locked| {
+				*locked = locked.saturating_add(amount)
+			});
+			Self::record_flow(&payer, currency_id, |flows| {
+				flows.outflow = flows.outflow.saturating_add(amount)
+			});
+
+			let receipt_hash = T::Hashing::hash_of(&receipt);
+
+			if !<AccountsFirstSeen<T>>::contains_key(&payer) {
+				<AccountsFirstSeen<T>>::insert(&payer, now);
+			}
+			let risk_score = Self::compute_payment_risk_score(&payer, &payee, amount, None);
+			let payee_confirmation = Self::required_payee_confirmation(amount);
+
+			offchain_index::set(&indexed_content_key(&receipt_hash), &receipt);
+			let encrypted_description = encrypted_description.map(|(key, cipher)| {
+				let cipher_hash = T::Hashing::hash_of(&cipher);
+				offchain_index::set(&indexed_content_key(&cipher_hash), &cipher);
+				EncryptedDescription { key, cipher_hash }
+			});
+
+			let payment = Payment::<T> {
+				id,
+				payer: payer.clone(),
+				payee: payee.clone(),
+				amount,
+				currency_id,
+				description,
+				receipt_hash,
+				created_at: now,
+				updated_at: now,
+				updated_by: payer.clone(),
+				status: PaymentStatus::Pending,
+				risk_score,
+				payee_confirmation,
+				refund_to: None,
+				delivery_proofs: Default::default(),
+				fulfillment_deadline: None,
+			};
+
+			<Payments<T>>::insert(&payment_hash, payment);
+			Self::index_payment(payment_hash, &PaymentStatus::Pending, now);
+			Self::record_spending(&payer, currency_id, spending);
+			if let Some(encrypted_description) = encrypted_description {
+				<EncryptedDescriptions<T>>::insert(&payment_hash, encrypted_description);
+			}
+			if let Some(fee_account) = fee_account {
+				<PaymentFeeAccounts<T>>::insert(&payment_hash, fee_account);
+			}
+			if let Some(referral) = referral {
+				<PaymentReferrers<T>>::insert(&payment_hash, referral);
+			}
+			<PaymentsOwned<T>>::mutate(&payer, |payment_hashes| {
+				payment_hashes.push(payment_hash)
+			});
+			<LatestPaymentId<T>>::put(id);
+
+			// The payments meeting the policy of the payee skip the pending queue, unless the
+			// acceptance is paused.
+			let auto_accept = !Self::paused_calls(PausableCall::AcceptPayment) &&
+				Self::acceptance_policies(&payee).map_or(false, |policy| policy.auto_accept);
+			if !auto_accept {
+				Self::enqueue_pending_payment(
+					payment_hash,
+					now + T::PendingPaymentWaitingTime::get(),
+				);
+			}
+
+			Self::deposit_event(Event::PaymentCreated {
+				payment_hash,
+				payer_handle: T::IdentitiesManager::get_handle(&payer),
+				payee_handle: T::IdentitiesManager::get_handle(&payee),
+				payer,
+				payee: payee.clone(),
+				currency_id,
+				amount,
+				risk_score,
+			});
+
+			if auto_accept {
+				Self::do_accept_pending_payment(payee, payment_hash)?;
+			}
+
+			Ok(payment_hash)
+		}
+
+		fn do_update_payment(
+			updated_by: AccountOf<T>,
+			payment_hash: PaymentHashOf<T>,
+			status: PaymentStatus,
+		) -> DispatchResult {
+			let mut payment = Self::payments(&payment_hash).ok_or(<Error<T>>::PaymentNotFound)?;
+
+			let now = <timestamp::Pallet<T>>::get();
+
+			if Self::status_index_key(&payment.status) != Self::status_index_key(&status) {
+				Self::unindex_payment(payment_hash, &payment.status, payment.created_at);
+				Self::index_payment(payment_hash, &status, payment.created_at);
+			}
+
+			payment.updated_at = now;
+			payment.updated_by = updated_by;
+			payment.status = status;
+
+			<Payments<T>>::insert(&payment_hash, payment);
+			Ok(())
+		}
+
+		fn do_accept_payment(
+			sender: AccountOf<T>,
+			payment_hash: PaymentHashOf<T>,
+		) -> DispatchResult {
+			let payment = Self::payments(&payment_hash).ok_or(<Error<T>>::PaymentNotFound)?;
+
+			Self::ensure_payee_or_proxy(&sender, &payment, MerchantOperation::AcceptPayment)?;
+			// The policy may have changed since the creation.
+			Self::ensure_acceptance_policy(
+				&payment.payer,
+				&payment.payee,
+				payment.amount,
+				payment.currency_id,
+			)?;
+			// The screening lists may have been updated since the creation too.
+			T::ComplianceCheck::check_payment(
+				&payment.payer,
+				&payment.payee,
+				&payment.currency_id,
+				payment.amount,
+			)?;
+
+			Self::do_accept_pending_payment(sender, payment_hash)
+		}
+
+		fn do_accept_pending_payment(
+			accepted_by: AccountOf<T>,
+			payment_hash: PaymentHashOf<T>,
+		) -> DispatchResult {
+			let payment = Self::payments(&payment_hash).ok_or(<Error<T>>::PaymentNotFound)?;
+
+			ensure!(payment.status == PaymentStatus::Pending, <Error<T>>::PaymentNotPending);
+
+			Self::do_update_payment(accepted_by, payment_hash, PaymentStatus::Accepted)?;
+
+			// The risk score is refreshed as the risk factors may change since the creation. The
+			// cooldown of the payee confirmation starts from the acceptance.
+			let category = Self::payment_categories(&payment_hash);
+			let risk_score = Self::compute_payment_risk_score(
+				&payment.payer,
+				&payment.payee,
+				payment.amount,
+				category,
+			);
+			let payee_confirmation = Self::required_payee_confirmation(payment.amount);
+			let fulfillment_deadline = <timestamp::Pallet<T>>::get() + T::FulfillmentTime::get();
+			<Payments<T>>::mutate(&payment_hash, |payment| {
+				if let Some(payment) = payment {
+					payment.risk_score = risk_score;
+					payment.payee_confirmation = payee_confirmation;
+					payment.fulfillment_deadline = Some(fulfillment_deadline);
+				}
+			});
+
+			Self::dequeue_pending_payment(
+				payment_hash,
+				payment.updated_at + T::PendingPaymentWaitingTime::get(),
+			);
+
+			Self::deposit_event(Event::PaymentAccepted {
+				payment_hash,
+				payer: payment.payer,
+				payee: payment.payee,
+				currency_id: payment.currency_id,
+				amount: payment.amount,
+				risk_score,
+				category,
+			});
+
+			Ok(())
+		}
+
+		fn do_reject_payment(
+			sender: AccountOf<T>,
+			payment_hash: PaymentHashOf<T>,
+		) -> DispatchResult {
+			let payment = Self::payments(&payment_hash).ok_or(<Error<T>>::PaymentNotFound)?;
+
+			Self::ensure_payee_or_proxy(&sender, &payment, MerchantOperation::RejectPayment)?;
+			ensure!(payment.status == PaymentStatus::Pending, <Error<T>>::PaymentNotPending);
+
+			Self::do_refund_payment(&payment)?;
+
+			Self::do_update_payment(sender, payment_hash, PaymentStatus::Rejected)?;
+
+			Self::dequeue_pending_payment(
+				payment_hash,
+				payment.updated_at + T::PendingPaymentWaitingTime::get(),
+			);
+
+			Self::deposit_event(Event::PaymentRejected {
+				payment_hash,
+				payer: payment.payer,
+				payee: payment.payee,
+				currency_id: payment.currency_id,
+				amount: payment.amount,
+			});
+
+			Ok(())
+		}
+
+		fn do_expire_payment(payment_hash: PaymentHashOf<T>) -> DispatchResult {
+			let payment = Self::payments(&payment_hash).ok_or(<Error<T>>::PaymentNotFound)?;
+
+			ensure!(payment.status == PaymentStatus::Pending, <Error<T>>::PaymentNotPending);
+
+			let now = <timestamp::Pallet<T>>::get();
+			let expired_time = payment.updated_at + T::PendingPaymentWaitingTime::get();
+
+			ensure!(expired_time <= now, <Error<T>>::PaymentNonexpired);
+
+			Self::do_refund_payment(&payment)?;
+
+			Self::do_update_payment(payment.updated_by, payment_hash, PaymentStatus::Expired)?;
+
+			Self::dequeue_pending_payment(
+				payment_hash,
+				payment.updated_at + T::PendingPaymentWaitingTime::get(),
+			);
+
+			Self::deposit_event(Event::PaymentExpired {
+				payment_hash,
+				payer: payment.payer,
+				payee: payment.payee,
+				currency_id: payment.currency_id,
+				amount: payment.amount,
+			});
+
+			Ok(())
+		}
+
+		fn do_cancel_payment(
+			sender: AccountOf<T>,
+			payment_hash: PaymentHashOf<T>,
+		) -> DispatchResult {
+			let payment = Self::payments(&payment_hash).ok_or(<Error<T>>::PaymentNotFound)?;
+
+			match payment.status {
+				PaymentStatus::Pending => ensure!(sender == payment.payer, <Error<T>>::NotPayer),
+				PaymentStatus::Accepted => ensure!(sender == payment.payee, <Error<T>>::NotPayee),
+				_ => return Err(<Error<T>>::PaymentNotCancellable.into()),
+			}
+
+			Self::do_refund_payment(&payment)?;
+
+			if payment.status == PaymentStatus::Pending {
+				Self::dequeue_pending_payment(
+					payment_hash,
+					payment.updated_at + T::PendingPaymentWaitingTime::get(),
+				);
+			}
+
+			Self::do_update_payment(sender, payment_hash, PaymentStatus::Cancelled)?;
+
+			Self::deposit_event(Event::PaymentCancelled {
+				payment_hash,
+				payer: payment.payer,
+				payee: payment.payee,
+				currency_id: payment.currency_id,
+				amount: payment.amount,
+			});
+
+			Ok(())
+		}
+
+		// The payer can get the funds back without a dispute if the payee did not fulfill the
+		// payment before the deadline.
+		fn do_reclaim_unfulfilled_payment(
+			sender: AccountOf<T>,
+			payment_hash: PaymentHashOf<T>,
+		) -> DispatchResult {
+			let payment = Self::payments(&payment_hash).ok_or(<Error<T>>::PaymentNotFound)?;
+
+			ensure!(sender == payment.payer, <Error<T>>::NotPayer);
+			ensure!(payment.status == PaymentStatus::Accepted, <Error<T>>::PaymentNotAccepted);
+			let deadline =
+				payment.fulfillment_deadline.ok_or(<Error<T>>::FulfillmentDeadlineNotReached)?;
+			ensure!(
+				<timestamp::Pallet<T>>::get() >= deadline,
+				<Error<T>>::FulfillmentDeadlineNotReached
+			);
+
+			Self::do_refund_payment(&payment)?;
+
+			Self::do_update_payment(sender, payment_hash, PaymentStatus::Reclaimed)?;
+
+			Self::deposit_event(Event::PaymentReclaimed {
+				payment_hash,
+				payer: payment.payer,
+				payee: payment.payee,
+				currency_id: payment.currency_id,
+				amount: payment.amount,
+			});
+
+			Ok(())
+		}
+
+		fn do_refund_payment(payment: &Payment<T>) -> DispatchResult {
+			Self::do_refund_escrowed(payment, payment.amount)
+		}
+
+		// Pay the escrowed funds back to the refund address of the payment, or the payer.
+		fn do_refund_escrowed(payment: &Payment<T>, amount: BalanceOf<T>) -> DispatchResult {
+			if amount.is_zero() {
+				return Ok(())
+			}
+
+			let refund_to = payment.refund_to.as_ref().unwrap_or(&payment.payer);
+			Self::do_pay_from_escrow(payment.currency_id, refund_to, amount)
+		}
+
+		fn do_set_refund_address(
+			sender: AccountOf<T>,
+			payment_hash: PaymentHashOf<T>,
+			refund_to: Option<AccountOf<T>>,
+		) -> DispatchResult {
+			let mut payment = Self::payments(&payment_hash).ok_or(<Error<T>>::PaymentNotFound)?;
+
+			ensure!(sender == payment.payer, <Error<T>>::NotPayer);
+			ensure!(
+				matches!(
+					payment.status,
+					PaymentStatus::Pending |
+						PaymentStatus::Accepted |
+						PaymentStatus::Fulfilled |
+						PaymentStatus::PartiallyFulfilled(_) |
+						PaymentStatus::Disputed
+				),
+				<Error<T>>::PaymentClosed
+			);
+
+			// Nominating the payer itself is the same as no nomination.
+			payment.refund_to = refund_to.filter(|refund_to| *refund_to != payment.payer);
+			<Payments<T>>::insert(&payment_hash, payment.clone());
+
+			Self::deposit_event(Event::RefundAddressUpdated {
+				payment_hash,
+				payer: payment.payer,
+				refund_to: payment.refund_to,
+			});
+
+			Ok(())
+		}
+
+		fn do_set_payment_locale(
+			sender: AccountOf<T>,
+			payment_hash: PaymentHashOf<T>,
+			locale: Option<LanguageCode>,
+		) -> DispatchResult {
+			let payment = Self::payments(&payment_hash).ok_or(<Error<T>>::PaymentNotFound)?;
 
-			Ok(())
-		}
+			ensure!(sender == payment.payer, <Error<T>>::NotPayer);
+			// The payee agrees to the hint by accepting the payment.
+			ensure!(payment.status == PaymentStatus::Pending, <Error<T>>::PaymentNotPending);
 
-		fn evaluate_pending_payments() -> DispatchResult {
-			let pending_payment_hashes = <PendingPaymentHashes<T>>::get();
-			if pending_payment_hashes.is_empty() {
-				return Ok(())
+			match locale {
+				Some(locale) => <PaymentLocales<T>>::insert(&payment_hash, locale),
+				None => <PaymentLocales<T>>::remove(&payment_hash),
 			}
 
-			for payment_hash in pending_payment_hashes.iter() {
-				Self::do_expire_payment(*payment_hash)?;
-			}
+			Self::deposit_event(Event::PaymentLocaleUpdated { payment_hash, locale });
 
 			Ok(())
 		}
 
-		fn evaluate_full_filled_payments() -> DispatchResult {
-			let full_filled_payment_hashes = <FulfilledPaymentHashes<T>>::get();
+		// The category is set by the payee while the payment is pending, or along with the
+		// acceptance, and refreshes the risk score of the payment.
+		fn do_set_payment_category(
+			sender: AccountOf<T>,
+			payment_hash: PaymentHashOf<T>,
+			category: Option<PaymentCategory>,
+		) -> DispatchResult {
+			let mut payment = Self::payments(&payment_hash).ok_or(<Error<T>>::PaymentNotFound)?;
 
-			if full_filled_payment_hashes.is_empty() {
-				return Ok(())
-			}
+			Self::ensure_payee_or_proxy(&sender, &payment, MerchantOperation::AcceptPayment)?;
+			ensure!(
+				matches!(payment.status, PaymentStatus::Pending | PaymentStatus::Accepted),
+				<Error<T>>::InvalidStatusChange
+			);
 
-			for payment_hash in full_filled_payment_hashes.iter() {
-				Self::do_auto_complete_full_filled_payments(*payment_hash)?;
+			if let Some(previous) = Self::payment_categories(&payment_hash) {
+				<CategoryPayments<T>>::remove(previous, &payment_hash);
+			}
+			match category {
+				Some(category) => {
+					<PaymentCategories<T>>::insert(&payment_hash, category);
+					<CategoryPayments<T>>::insert(category, &payment_hash, ());
+				},
+				None => <PaymentCategories<T>>::remove(&payment_hash),
 			}
 
+			payment.risk_score = Self::compute_payment_risk_score(
+				&payment.payer,
+				&payment.payee,
+				payment.amount,
+				category,
+			);
+			<Payments<T>>::insert(&payment_hash, payment.clone());
+
+			Self::deposit_event(Event::PaymentCategoryUpdated {
+				payment_hash,
+				payee: payment.payee,
+				category,
+				risk_score: payment.risk_score,
+			});
+
 			Ok(())
 		}
 
-		fn do_create_payment(
-			payer: AccountOf<T>,
-			payee: AccountOf<T>,
-			amount: BalanceOf<T>,
-			currency_id: CurrencyId<T::Hash>,
-			description: Vec<u8>,
-			receipt: Vec<u8>,
+		// Either party can request the receipt until the payment is completed.
+		fn do_request_payment_receipt(
+			sender: AccountOf<T>,
+			payment_hash: PaymentHashOf<T>,
 		) -> DispatchResult {
-			let id = <LatestPaymentId<T>>::get().checked_add(1).ok_or(<Error<T>>::Overflow)?;
+			let payment = Self::payments(&payment_hash).ok_or(<Error<T>>::PaymentNotFound)?;
 
 			ensure!(
-				T::Currency::free_balance(currency_id, &payer) >= amount,
-				<Error<T>>::InsufficientBalance,
+				sender == payment.payer || sender == payment.payee,
+				<Error<T>>::NotPaymentParty
 			);
-
 			ensure!(
-				T::CurrenciesManager::is_currency_accepted(&payee, &currency_id),
-				<Error<T>>::UnacceptedCurrency
+				matches!(
+					payment.status,
+					PaymentStatus::Pending |
+						PaymentStatus::Accepted |
+						PaymentStatus::Fulfilled |
+						PaymentStatus::PartiallyFulfilled(_)
+				),
+				<Error<T>>::InvalidStatusChange
+			);
+			ensure!(
+				!<PaymentReceiptRequests<T>>::contains_key(&payment_hash),
+				<Error<T>>::PaymentReceiptAlreadyRequested
 			);
 
-			T::Currency::reserve(currency_id, &payer, amount)?;
-
-			let now = <timestamp::Pallet<T>>::get();
-			let receipt_hash = T::Hashing::hash_of(&receipt);
+			<PaymentReceiptRequests<T>>::insert(&payment_hash, &sender);
 
-			offchain_index::set(&receipt_hash.encode(), &receipt);
+			Self::deposit_event(Event::PaymentReceiptRequested { payment_hash, who: sender });
 
-			let payment = Payment::<T> {
-				id,
-				payer: payer.clone(),
-				payee: payee.clone(),
-				amount,
-				currency_id,
-				description,
-				receipt_hash,
-				created_at: now,
-				updated_at: now,
-				updated_by: payer.clone(),
-				status: PaymentStatus::Pending,
-			};
+			Ok(())
+		}
 
-			let payment_hash = T::Hashing::hash_of(&payment);
+		// Mint the receipt of a completed payment to both parties, if it was requested.
+		fn do_mint_payment_receipt(
+			payment_hash: PaymentHashOf<T>,
+			payment: &Payment<T>,
+			amount: BalanceOf<T>,
+		) {
+			if <PaymentReceiptRequests<T>>::take(&payment_hash).is_none() {
+				return
+			}
 
-			<Payments<T>>::insert(&payment_hash, payment);
-			<PaymentsOwned<T>>::mutate(&payer, |payment_hashes| {
-				payment_hashes.push(payment_hash)
-			});
-			<LatestPaymentId<T>>::put(id);
-			<PendingPaymentHashes<T>>::mutate(|payment_hashes| {
-				payment_hashes.push(payment_hash)
-			});
+			<PaymentReceipts<T>>::insert(
+				&payment_hash,
+				PaymentReceipt {
+					payer: payment.payer.clone(),
+					payee: payment.payee.clone(),
+					currency_id: payment.currency_id,
+					amount,
+					receipt_hash: payment.receipt_hash,
+					category: Self::payment_categories(&payment_hash),
+					completed_at: <timestamp::Pallet<T>>::get(),
+				},
+			);
+			<ReceiptsOwned<T>>::insert(&payment.payer, &payment_hash, ());
+			<ReceiptsOwned<T>>::insert(&payment.payee, &payment_hash, ());
 
-			Self::deposit_event(Event::PaymentCreated {
+			Self::deposit_event(Event::PaymentReceiptMinted {
 				payment_hash,
-				payer,
-				payee,
-				currency_id,
-				amount,
+				payer: payment.payer.clone(),
+				payee: payment.payee.clone(),
 			});
-
-			Ok(())
 		}
 
-		fn do_update_payment(
-			updated_by: AccountOf<T>,
-			payment_hash: PaymentHashOf<T>,
-			status: PaymentStatus,
-		) -> DispatchResult {
-			let mut payment = Self::payments(&payment_hash).ok_or(<Error<T>>::PaymentNotFound)?;
-
+		fn required_payee_confirmation(amount: BalanceOf<T>) -> PayeeConfirmation<MomentOf<T>> {
+			if amount < T::PayeeConfirmationThreshold::get() {
+				return PayeeConfirmation::NotRequired
+			}
 			let now = <timestamp::Pallet<T>>::get();
+			PayeeConfirmation::Required(now + T::PayeeConfirmationCooldown::get())
+		}
 
-			payment.updated_at = now;
-			payment.updated_by = updated_by;
-			payment.status = status;
-
-			<Payments<T>>::insert(&payment_hash, payment);
+		fn ensure_payee_confirmed(payment: &Payment<T>) -> DispatchResult {
+			ensure!(
+				!matches!(payment.payee_confirmation, PayeeConfirmation::Required(_)),
+				<Error<T>>::PayeeNotConfirmed
+			);
 			Ok(())
 		}
 
-		fn do_accept_payment(
+		fn do_confirm_payee(
 			sender: AccountOf<T>,
 			payment_hash: PaymentHashOf<T>,
 		) -> DispatchResult {
-			let payment = Self::payments(&payment_hash).ok_or(<Error<T>>::PaymentNotFound)?;
+			let mut payment = Self::payments(&payment_hash).ok_or(<Error<T>>::PaymentNotFound)?;
 
-			ensure!(sender == payment.payee, <Error<T>>::AccessDenied);
-			ensure!(payment.status == PaymentStatus::Pending, <Error<T>>::InvalidStatusChange);
+			ensure!(sender == payment.payee, <Error<T>>::NotPayee);
+			ensure!(payment.status == PaymentStatus::Accepted, <Error<T>>::PaymentNotAccepted);
 
-			Self::do_update_payment(sender, payment_hash, PaymentStatus::Accepted)?;
+			let confirmable_at = match payment.payee_confirmation {
+				PayeeConfirmation::Required(confirmable_at) => confirmable_at,
+				_ => return Err(<Error<T>>::PayeeConfirmationNotRequired.into()),
+			};
+			ensure!(
+				<timestamp::Pallet<T>>::get() >= confirmable_at,
+				<Error<T>>::PayeeConfirmationCooldown
+			);
 
-			<PendingPaymentHashes<T>>::mutate(|payment_hashes| {
-				payment_hashes.retain(|&hash| hash != payment_hash)
-			});
+			payment.payee_confirmation = PayeeConfirmation::Confirmed;
+			<Payments<T>>::insert(&payment_hash, payment.clone());
 
-			Self::deposit_event(Event::PaymentAccepted {
+			Self::deposit_event(Event::PayeeConfirmed {
 				payment_hash,
 				payer: payment.payer,
 				payee: payment.payee,
@@ -474,63 +3342,67 @@ pub mod pallet {
 			Ok(())
 		}
 
-		fn do_reject_payment(
+		fn do_add_delivery_proof(
 			sender: AccountOf<T>,
 			payment_hash: PaymentHashOf<T>,
+			proof: Vec<u8>,
 		) -> DispatchResult {
-			let payment = Self::payments(&payment_hash).ok_or(<Error<T>>::PaymentNotFound)?;
-
-			ensure!(sender == payment.payee, <Error<T>>::AccessDenied);
-			ensure!(payment.status == PaymentStatus::Pending, <Error<T>>::InvalidStatusChange);
+			let mut payment = Self::payments(&payment_hash).ok_or(<Error<T>>::PaymentNotFound)?;
 
-			T::Currency::unreserve(
-				payment.currency_id,
-				&payment.payer,
-				payment.amount,
+			Self::ensure_payee_or_proxy(&sender, &payment, MerchantOperation::AddDeliveryProof)?;
+			ensure!(
+				matches!(
+					payment.status,
+					PaymentStatus::Accepted |
+						PaymentStatus::Fulfilled |
+						PaymentStatus::PartiallyFulfilled(_) |
+						PaymentStatus::Disputed
+				),
+				<Error<T>>::InvalidStatusChange
 			);
 
-			Self::do_update_payment(sender, payment_hash, PaymentStatus::Rejected)?;
+			let proof_hash = T::Hashing::hash_of(&proof);
+			payment
+				.delivery_proofs
+				.try_push(proof_hash)
+				.map_err(|_| <Error<T>>::TooManyDeliveryProofs)?;
 
-			<PendingPaymentHashes<T>>::mutate(|payment_hashes| {
-				payment_hashes.retain(|&hash| hash != payment_hash)
-			});
+			offchain_index::set(&indexed_content_key(&proof_hash), &proof);
+			<Payments<T>>::insert(&payment_hash, payment.clone());
 
-			Self::deposit_event(Event::PaymentRejected {
+			Self::deposit_event(Event::DeliveryProofAdded {
 				payment_hash,
-				payer: payment.payer,
 				payee: payment.payee,
-				currency_id: payment.currency_id,
-				amount: payment.amount,
+				proof_hash,
 			});
 
 			Ok(())
 		}
 
-		fn do_expire_payment(payment_hash: PaymentHashOf<T>) -> DispatchResult {
+		fn do_fulfill_payment(
+			sender: AccountOf<T>,
+			payment_hash: PaymentHashOf<T>,
+		) -> DispatchResult {
 			let payment = Self::payments(&payment_hash).ok_or(<Error<T>>::PaymentNotFound)?;
 
-			ensure!(payment.status == PaymentStatus::Pending, <Error<T>>::InvalidStatusChange);
-
-			let now = <timestamp::Pallet<T>>::get();
-			let expired_time = payment.updated_at + T::PendingPaymentWaitingTime::get();
-
-			if expired_time < now {
-				return Ok(())
-			}
-
-			T::Currency::unreserve(
-				payment.currency_id,
-				&payment.payer,
-				payment.amount,
+			Self::ensure_payee_or_proxy(&sender, &payment, MerchantOperation::FulfillPayment)?;
+			ensure!(
+				matches!(
+					payment.status,
+					PaymentStatus::Accepted | PaymentStatus::PartiallyFulfilled(_)
+				),
+				<Error<T>>::PaymentNotFulfillable
 			);
+			Self::ensure_payee_confirmed(&payment)?;
 
-			Self::do_update_payment(payment.updated_by, payment_hash, PaymentStatus::Expired)?;
-
-			<PendingPaymentHashes<T>>::mutate(|payment_hashes| {
-				payment_hashes.retain(|&hash| hash != payment_hash)
-			});
+			Self::do_requeue_fulfilled_payment(
+				sender,
+				payment_hash,
+				&payment,
+				PaymentStatus::Fulfilled,
+			)?;
 
-			Self::deposit_event(Event::PaymentExpired {
+			Self::deposit_event(Event::PaymentFulfilled {
 				payment_hash,
 				payer: payment.payer,
 				payee: payment.payee,
@@ -541,103 +3413,99 @@ pub mod pallet {
 			Ok(())
 		}
 
-		fn do_cancel_payment(
+		fn do_partially_fulfill_payment(
 			sender: AccountOf<T>,
 			payment_hash: PaymentHashOf<T>,
+			fulfilled: Percent,
 		) -> DispatchResult {
 			let payment = Self::payments(&payment_hash).ok_or(<Error<T>>::PaymentNotFound)?;
 
-			match payment.status {
-				PaymentStatus::Pending =>
-					ensure!(sender == payment.payer, <Error<T>>::AccessDenied),
-				PaymentStatus::Accepted =>
-					ensure!(sender == payment.payee, <Error<T>>::AccessDenied),
-				_ => return Err(<Error<T>>::InvalidStatusChange.into()),
-			}
-
-			T::Currency::unreserve(
-				payment.currency_id,
-				&payment.payer,
-				payment.amount,
+			Self::ensure_payee_or_proxy(&sender, &payment, MerchantOperation::FulfillPayment)?;
+			let fulfilled_before = match payment.status {
+				PaymentStatus::Accepted => Percent::zero(),
+				PaymentStatus::PartiallyFulfilled(fulfilled_before) => fulfilled_before,
+				_ => return Err(<Error<T>>::PaymentNotFulfillable.into()),
+			};
+			// The whole payment is fulfilled with `fulfill_payment`.
+			ensure!(
+				fulfilled > fulfilled_before && fulfilled < Percent::one(),
+				<Error<T>>::InvalidFulfilledShare
 			);
+			Self::ensure_payee_confirmed(&payment)?;
 
-			Self::do_update_payment(sender, payment_hash, PaymentStatus::Cancelled)?;
+			Self::do_requeue_fulfilled_payment(
+				sender,
+				payment_hash,
+				&payment,
+				PaymentStatus::PartiallyFulfilled(fulfilled),
+			)?;
 
-			Self::deposit_event(Event::PaymentCancelled {
+			Self::deposit_event(Event::PaymentPartiallyFulfilled {
 				payment_hash,
 				payer: payment.payer,
 				payee: payment.payee,
 				currency_id: payment.currency_id,
 				amount: payment.amount,
+				fulfilled,
 			});
 
 			Ok(())
 		}
 
-		fn do_fulfill_payment(
+		// The waiting time before the auto-completion restarts whenever more of the payment is
+		// fulfilled, so the payer can check the last delivery.
+		fn do_requeue_fulfilled_payment(
 			sender: AccountOf<T>,
 			payment_hash: PaymentHashOf<T>,
+			payment: &Payment<T>,
+			status: PaymentStatus,
 		) -> DispatchResult {
-			let payment = Self::payments(&payment_hash).ok_or(<Error<T>>::PaymentNotFound)?;
-
-			ensure!(sender == payment.payee, <Error<T>>::AccessDenied);
-			ensure!(payment.status == PaymentStatus::Accepted, <Error<T>>::InvalidStatusChange);
-
-			Self::do_update_payment(sender, payment_hash, PaymentStatus::Fulfilled)?;
+			if Self::is_fulfilled(&payment.status) {
+				Self::dequeue_fulfilled_payment_of(payment_hash, payment);
+			}
 
-			<FulfilledPaymentHashes<T>>::mutate(|payment_hashes| {
-				payment_hashes.push(payment_hash)
-			});
+			Self::do_update_payment(sender, payment_hash, status)?;
 
-			Self::deposit_event(Event::PaymentFulfilled {
+			let now = <timestamp::Pallet<T>>::get();
+			Self::enqueue_fulfilled_payment(
 				payment_hash,
-				payer: payment.payer,
-				payee: payment.payee,
-				currency_id: payment.currency_id,
-				amount: payment.amount,
-			});
+				now + T::FulfilledPaymentWaitingTime::get(),
+			);
+			Self::track_payee_velocity(&payment.payee, false);
 
 			Ok(())
 		}
 
+		fn is_fulfilled(status: &PaymentStatus) -> bool {
+			matches!(status, PaymentStatus::Fulfilled | PaymentStatus::PartiallyFulfilled(_))
+		}
+
 		fn do_auto_complete_full_filled_payments(payment_hash: PaymentHashOf<T>) -> DispatchResult {
 			let payment = Self::payments(&payment_hash).ok_or(<Error<T>>::PaymentNotFound)?;
-			let now = <timestamp::Pallet<T>>::get();
 
-			let expired_time = payment.updated_at + T::FulfilledPaymentWaitingTime::get();
+			ensure!(Self::is_fulfilled(&payment.status), <Error<T>>::PaymentNotFulfilled);
 
-			if expired_time < now {
+			let now = <timestamp::Pallet<T>>::get();
+			let expired_time = Self::fulfilled_completion_time(&payment_hash, &payment);
+
+			ensure!(expired_time <= now, <Error<T>>::PaymentNonexpired);
+
+			// The payment is queued again for the end of the hold of the payee.
+			let held_until = Self::payee_velocities(&payment.payee).held_until;
+			if held_until > now {
+				Self::dequeue_fulfilled_payment(payment_hash, expired_time);
+				Self::enqueue_fulfilled_payment(payment_hash, held_until);
+				<PaymentHolds<T>>::insert(&payment_hash, held_until);
+				Self::deposit_event(Event::PaymentCompletionPostponed {
+					payment_hash,
+					completed_at: held_until,
+				});
 				return Ok(())
 			}
 
-			T::Currency::unreserve(
-				payment.currency_id,
-				&payment.payer,
-				payment.amount,
-			);
-
-			T::Currency::transfer(
-				payment.currency_id,
-				&payment.payer,
-				&payment.payee,
-				payment.amount,
-			)?;
-
-			Self::do_update_payment(payment.updated_by, payment_hash, PaymentStatus::Completed)?;
-
-			<FulfilledPaymentHashes<T>>::mutate(|payment_hashes| {
-				payment_hashes.retain(|&hash| hash != payment_hash)
-			});
-
-			Self::deposit_event(Event::PaymentCompleted {
-				payment_hash,
-				payer: payment.payer,
-				payee: payment.payee,
-				currency_id: payment.currency_id,
-				amount: payment.amount,
-			});
+			Self::ensure_payee_confirmed(&payment)?;
 
-			Ok(())
+			Self::do_settle_fulfilled_payment(payment.updated_by.clone(), payment_hash, &payment)
 		}
 
 		fn do_dispute_payment(
@@ -649,23 +3517,31 @@ pub mod pallet {
 			match payment.status {
 				PaymentStatus::Accepted => ensure!(
 					sender == payment.payer || sender == payment.payee,
-					<Error<T>>::AccessDenied
+					<Error<T>>::NotPaymentParty
 				),
-				PaymentStatus::Fulfilled => ensure!(
+				PaymentStatus::Fulfilled | PaymentStatus::PartiallyFulfilled(_) => ensure!(
 					sender == payment.payer || sender == payment.payee,
-					<Error<T>>::AccessDenied
+					<Error<T>>::NotPaymentParty
 				),
-				_ => return Err(<Error<T>>::InvalidStatusChange.into()),
+				_ => return Err(<Error<T>>::PaymentNotDisputable.into()),
+			}
+
+			if Self::is_fulfilled(&payment.status) {
+				Self::dequeue_fulfilled_payment_of(payment_hash, &payment);
 			}
 
 			Self::do_update_payment(sender, payment_hash, PaymentStatus::Disputed)?;
+			<DisputedPaymentsCount<T>>::mutate(&payment.payer, |count| *count += 1);
+			let disputed = if sender == payment.payer { &payment.payee } else { &payment.payer };
+			T::IdentitiesManager::note_dispute_opened(disputed);
 			Self::deposit_event(Event::PaymentDisputed {
 				payment_hash,
 				payer: payment.payer,
-				payee: payment.payee,
+				payee: payment.payee.clone(),
 				currency_id: payment.currency_id,
 				amount: payment.amount,
 			});
+			Self::track_payee_velocity(&payment.payee, true);
 
 			Ok(())
 		}
@@ -675,38 +3551,121 @@ pub mod pallet {
 			payment_hash: PaymentHashOf<T>,
 		) -> DispatchResult {
 			let payment = Self::payments(&payment_hash).ok_or(<Error<T>>::PaymentNotFound)?;
-			ensure!(sender == payment.payer, <Error<T>>::AccessDenied);
-			ensure!(payment.status == PaymentStatus::Fulfilled, <Error<T>>::InvalidStatusChange);
+			ensure!(sender == payment.payer, <Error<T>>::NotPayer);
+			ensure!(Self::is_fulfilled(&payment.status), <Error<T>>::PaymentNotFulfilled);
+			Self::ensure_payee_confirmed(&payment)?;
 
-			T::Currency::unreserve(
-				payment.currency_id,
-				&payment.payer,
-				payment.amount,
-			);
+			Self::do_settle_fulfilled_payment(sender, payment_hash, &payment)
+		}
 
-			T::Currency::transfer(
-				payment.currency_id,
-				&payment.payer,
-				&payment.payee,
-				payment.amount,
-			)?;
+		// Transfer the fulfilled share of the payment to the payee and refund the rest, the
+		// completion event carries the amount transferred to the payee, net of the fee.
+		fn do_settle_fulfilled_payment(
+			updated_by: AccountOf<T>,
+			payment_hash: PaymentHashOf<T>,
+			payment: &Payment<T>,
+		) -> DispatchResult {
+			let paid = match payment.status {
+				PaymentStatus::PartiallyFulfilled(fulfilled) => fulfilled.mul_floor(payment.amount),
+				_ => payment.amount,
+			};
+			let fee = Self::compute_payment_fee(payment.currency_id, paid);
+
+			Self::do_pay_payout(payment_hash, payment, paid - fee)?;
+
+			if !fee.is_zero() {
+				// The referrer gets its cut of the fee, the fee account the rest.
+				let mut charged = fee;
+				if let Some((referrer, fee_share)) = Self::payment_referrers(&payment_hash) {
+					let reward = fee_share.mul_floor(fee);
+					if !reward.is_zero() {
+						Self::do_pay_from_escrow(payment.currency_id, &referrer, reward)?;
+						charged -= reward;
+						Self::deposit_event(Event::ReferralRewarded {
+							payment_hash,
+							referrer,
+							currency_id: payment.currency_id,
+							reward,
+						});
+					}
+				}
+
+				if !charged.is_zero() {
+					let fee_account = Self::payment_fee_accounts(&payment_hash)
+						.unwrap_or_else(T::TreasuryAccount::get);
+					Self::do_pay_from_escrow(payment.currency_id, &fee_account, charged)?;
+					Self::deposit_event(Event::PaymentFeeCharged {
+						payment_hash,
+						fee_account,
+						currency_id: payment.currency_id,
+						fee: charged,
+					});
+				}
+			}
 
-			Self::do_update_payment(sender, payment_hash, PaymentStatus::Completed)?;
+			Self::do_refund_escrowed(payment, payment.amount - paid)?;
 
-			<FulfilledPaymentHashes<T>>::mutate(|payment_hashes| {
-				payment_hashes.retain(|&hash| hash != payment_hash)
-			});
+			Self::do_update_payment(updated_by, payment_hash, PaymentStatus::Completed)?;
+
+			Self::dequeue_fulfilled_payment_of(payment_hash, payment);
 
 			Self::deposit_event(Event::PaymentCompleted {
 				payment_hash,
-				payer: payment.payer,
-				payee: payment.payee,
+				payer: payment.payer.clone(),
+				payee: payment.payee.clone(),
 				currency_id: payment.currency_id,
-				amount: payment.amount,
+				amount: paid - fee,
 			});
 
+			Self::do_mint_payment_receipt(payment_hash, payment, paid);
+
 			Ok(())
 		}
+
+		// Pay the shares of the interested parties out of the escrowed funds of a payment settled
+		// by a dispute, capped by the funds, and the rest to the beneficiary.
+		fn do_release_payment(
+			hash: &T::Hash,
+			beneficiary: &T::AccountId,
+			shares: &[(T::AccountId, BalanceOf<T>)],
+		) -> Result<BalanceOf<T>, DispatchError> {
+			let payment = Self::payments(hash).ok_or(<Error<T>>::PaymentNotFound)?;
+
+			ensure!(
+				matches!(
+					payment.status,
+					PaymentStatus::Accepted |
+						PaymentStatus::Fulfilled |
+						PaymentStatus::PartiallyFulfilled(_) |
+						PaymentStatus::Disputed
+				),
+				<Error<T>>::InvalidStatusChange
+			);
+
+			let mut released = payment.amount;
+			for (stakeholder, share) in shares {
+				let share = (*share).min(released);
+				if share.is_zero() {
+					continue
+				}
+				Self::do_pay_from_escrow(payment.currency_id, stakeholder, share)?;
+				released -= share;
+				Self::deposit_event(Event::PaymentShareReleased {
+					payment_hash: *hash,
+					stakeholder: stakeholder.clone(),
+					amount: share,
+				});
+			}
+			Self::do_pay_from_escrow(payment.currency_id, beneficiary, released)?;
+
+			if Self::is_fulfilled(&payment.status) {
+				Self::dequeue_fulfilled_payment_of(*hash, &payment);
+			}
+
+			Self::do_update_payment(payment.updated_by, *hash, PaymentStatus::Resolved)?;
+
+			Ok(released)
+		}
 	}
 
 	impl<T: Config> PaymentProtocol<T::Hash, T::AccountId, BalanceOf<T>> for Pallet<T> {
@@ -723,10 +3682,59 @@ pub mod pallet {
 			let payment = Self::payments(hash);
 
 			if let Some(payment) = payment {
-				return payment.status == PaymentStatus::Fulfilled || payment.status == PaymentStatus::Accepted
+				return payment.status == PaymentStatus::Accepted ||
+					Self::is_fulfilled(&payment.status)
 			}
 
 			false
 		}
+
+		fn get_refund_address(hash: &T::Hash) -> Result<T::AccountId, DispatchError> {
+			let payment = Self::payments(hash).ok_or(<Error<T>>::PaymentNotFound)?;
+
+			Ok(payment.refund_to.unwrap_or(payment.payer))
+		}
+
+		fn get_locale(hash: &T::Hash) -> Option<LanguageCode> {
+			Self::payment_locales(hash)
+		}
+
+		fn get_category(hash: &T::Hash) -> Option<PaymentCategory> {
+			Self::payment_categories(hash)
+		}
+
+		fn get_marketplace(hash: &T::Hash) -> Option<(T::AccountId, BalanceOf<T>)> {
+			let marketplace = Self::payment_fee_accounts(hash)?;
+			let payment = Self::payments(hash)?;
+
+			let fee = Self::compute_payment_fee(payment.currency_id, payment.amount);
+			let reward = Self::payment_referrers(hash)
+				.map_or_else(Zero::zero, |(_, fee_share)| fee_share.mul_floor(fee));
+
+			Some((marketplace, fee - reward))
+		}
+
+		fn resolve(
+			hash: &T::Hash,
+			outcome: Judgment,
+			shares: &[(T::AccountId, BalanceOf<T>)],
+		) -> Result<(T::AccountId, BalanceOf<T>), DispatchError> {
+			let payment = Self::payments(hash).ok_or(<Error<T>>::PaymentNotFound)?;
+			let (beneficiary, shares) = match outcome {
+				Judgment::ReleaseFundToPayee =>
+					(Self::payee_payout_account(&payment.payee), shares),
+				Judgment::ReleaseFundToPayer =>
+					(payment.refund_to.unwrap_or(payment.payer), &[][..]),
+			};
+			let amount = Self::do_release_payment(hash, &beneficiary, shares)?;
+
+			Self::deposit_event(Event::PaymentResolved {
+				payment_hash: *hash,
+				beneficiary: beneficiary.clone(),
+				amount,
+			});
+
+			Ok((beneficiary, amount))
+		}
 	}
 }
@@ -0,0 +1,530 @@
+//! Storage migrations of the LRP protocol pallet.
+//!
+//! Each migration upgrades the `StorageVersion` of the pallet by one and only runs if the on-chain
+//! version is the previous one. The migrations can be rehearsed against a live chain with the
+//! `try-runtime` feature, which runs the `pre_upgrade` and `post_upgrade` checks.
+
+use crate::{
+	Config, NamedReserves, Pallet, PaymentStatus, Payments, PaymentsByStatus, TotalValueLocked,
+};
+#[cfg(feature = "try-runtime")]
+use frame_support::traits::OnRuntimeUpgradeHelpersExt;
+use frame_support::{pallet_prelude::*, traits::OnRuntimeUpgrade};
+
+/// Version 1 gives the payments created before the storage versioning the risk score, the payee
+/// confirmation, the refund address and the delivery proofs. These payments were created without
+/// the checks, so they are scored 0, need no confirmation, are refunded to the payers and have no
+/// delivery proof. The lists of the pending and fulfilled payments are replaced by the bucketed
+/// queues, so these payments still expire or complete at their due time.
+pub mod v1 {
+	use super::*;
+	use crate::{risk::RiskScore, PayeeConfirmation};
+	use frame_support::{
+		sp_std::vec::Vec,
+		storage::migration::{
+			put_storage_value, storage_key_iter, take_storage_value, PrefixIterator,
+		},
+		traits::PalletInfoAccess,
+		StorageHasher,
+	};
+	use orml_traits::MultiCurrency;
+	use primitives::CurrencyId;
+
+	type BalanceOf<T> =
+		<<T as Config>::Currency as MultiCurrency<<T as frame_system::Config>::AccountId>>::Balance;
+	type MomentOf<T> = <T as pallet_timestamp::Config>::Moment;
+
+	#[derive(Encode, Decode)]
+	struct OldPayment<T: Config> {
+		id: u128,
+		payer: T::AccountId,
+		payee: T::AccountId,
+		amount: BalanceOf<T>,
+		currency_id: CurrencyId<T::Hash>,
+		description: Vec<u8>,
+		status: PaymentStatus,
+		receipt_hash: T::Hash,
+		created_at: MomentOf<T>,
+		updated_at: MomentOf<T>,
+		updated_by: T::AccountId,
+	}
+
+	/// The layout of the payments at versions 1 and 2.
+	#[derive(Encode, Decode)]
+	pub(super) struct PaymentV1<T: Config> {
+		pub(super) id: u128,
+		pub(super) payer: T::AccountId,
+		pub(super) payee: T::AccountId,
+		pub(super) amount: BalanceOf<T>,
+		pub(super) currency_id: CurrencyId<T::Hash>,
+		pub(super) description: Vec<u8>,
+		pub(super) status: PaymentStatus,
+		pub(super) receipt_hash: T::Hash,
+		pub(super) created_at: MomentOf<T>,
+		pub(super) updated_at: MomentOf<T>,
+		pub(super) updated_by: T::AccountId,
+		pub(super) risk_score: RiskScore,
+		pub(super) payee_confirmation: PayeeConfirmation<MomentOf<T>>,
+		pub(super) refund_to: Option<T::AccountId>,
+		pub(super) delivery_proofs: BoundedVec<T::Hash, T::MaxDeliveryProofs>,
+	}
+
+	/// The payments in the layout of the versions 1 and 2.
+	pub(super) fn payments<T: Config>() -> PrefixIterator<(T::Hash, PaymentV1<T>)> {
+		storage_key_iter::<T::Hash, PaymentV1<T>, Twox64Concat>(
+			Pallet::<T>::name().as_bytes(),
+			b"Payments",
+		)
+	}
+
+	pub struct MigrateToV1<T>(PhantomData<T>);
+
+	impl<T: Config> OnRuntimeUpgrade for MigrateToV1<T> {
+		fn on_runtime_upgrade() -> Weight {
+			if Pallet::<T>::on_chain_storage_version() != 0 {
+				return T::DbWeight::get().reads(1)
+			}
+
+			let pallet = Pallet::<T>::name().as_bytes();
+			let old_payments: Vec<_> =
+				storage_key_iter::<T::Hash, OldPayment<T>, Twox64Concat>(pallet, b"Payments")
+					.collect();
+			let payments = old_payments.len() as Weight;
+			// The lists of the pending and fulfilled payments are replaced by the bucketed queues.
+			take_storage_value::<Vec<T::Hash>>(pallet, b"PendingPaymentHashes", &[]);
+			take_storage_value::<Vec<T::Hash>>(pallet, b"FulfilledPaymentHashes", &[]);
+			for (payment_hash, old) in old_payments {
+				match old.status {
+					PaymentStatus::Pending => Pallet::<T>::enqueue_pending_payment(
+						payment_hash,
+						old.updated_at + T::PendingPaymentWaitingTime::get(),
+					),
+					PaymentStatus::Fulfilled => Pallet::<T>::enqueue_fulfilled_payment(
+						payment_hash,
+						old.updated_at + T::FulfilledPaymentWaitingTime::get(),
+					),
+					_ => (),
+				}
+				let payment = PaymentV1::<T> {
+					id: old.id,
+					payer: old.payer,
+					payee: old.payee,
+					amount: old.amount,
+					currency_id: old.currency_id,
+					description: old.description,
+					status: old.status,
+					receipt_hash: old.receipt_hash,
+					created_at: old.created_at,
+					updated_at: old.updated_at,
+					updated_by: old.updated_by,
+					risk_score: 0,
+					payee_confirmation: PayeeConfirmation::NotRequired,
+					refund_to: None,
+					delivery_proofs: Default::default(),
+				};
+				put_storage_value(
+					pallet,
+					b"Payments",
+					&Twox64Concat::hash(&payment_hash.encode()),
+					payment,
+				);
+			}
+
+			StorageVersion::new(1).put::<Pallet<T>>();
+			T::DbWeight::get().reads_writes(3 + payments * 3, 3 + payments * 3)
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<(), &'static str> {
+			Self::set_temp_storage(Payments::<T>::iter_keys().count() as u32, "payments");
+			Ok(())
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade() -> Result<(), &'static str> {
+			ensure!(
+				Pallet::<T>::on_chain_storage_version() >= 1,
+				"Storage version is not migrated"
+			);
+			let payments: u32 =
+				Self::get_temp_storage("payments").ok_or("Missing payments count")?;
+			ensure!(
+				Payments::<T>::iter_values().count() as u32 == payments,
+				"Some payments are not migrated"
+			);
+			Ok(())
+		}
+	}
+}
+
+/// Version 2 tracks the funds of the open payments in `NamedReserves`. The funds are tracked as
+/// reserved, capped by the reserved balances of the payers.
+pub mod v2 {
+	use super::*;
+	use frame_support::{
+		sp_runtime::traits::{Saturating, Zero},
+		sp_std::collections::btree_map::BTreeMap,
+	};
+	use orml_traits::{MultiCurrency, MultiReservableCurrency};
+	use primitives::{CurrencyId, PAYMENT_RESERVE};
+
+	type BalanceOf<T> =
+		<<T as Config>::Currency as MultiCurrency<<T as frame_system::Config>::AccountId>>::Balance;
+
+	pub struct MigrateToV2<T>(PhantomData<T>);
+
+	impl<T: Config> OnRuntimeUpgrade for MigrateToV2<T> {
+		fn on_runtime_upgrade() -> Weight {
+			if Pallet::<T>::on_chain_storage_version() != 1 {
+				return T::DbWeight::get().reads(1)
+			}
+
+			let mut reserves: BTreeMap<(T::AccountId, CurrencyId<T::Hash>), BalanceOf<T>> =
+				BTreeMap::new();
+
+			let mut payments: Weight = 0;
+			for (_, payment) in v1::payments::<T>() {
+				payments += 1;
+				if matches!(
+					payment.status,
+					PaymentStatus::Pending |
+						PaymentStatus::Accepted |
+						PaymentStatus::Fulfilled |
+						PaymentStatus::Disputed
+				) {
+					let total = reserves
+						.entry((payment.payer, payment.currency_id))
+						.or_insert_with(Zero::zero);
+					*total = total.saturating_add(payment.amount);
+				}
+			}
+
+			let mut writes: Weight = 0;
+			for ((who, currency_id), amount) in reserves {
+				let tracked = amount.min(T::Currency::reserved_balance(currency_id, &who));
+				if !tracked.is_zero() {
+					NamedReserves::<T>::insert(&who, (PAYMENT_RESERVE, currency_id), tracked);
+					writes += 1;
+				}
+			}
+
+			StorageVersion::new(2).put::<Pallet<T>>();
+			T::DbWeight::get().reads_writes(1 + payments + writes, 1 + writes)
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade() -> Result<(), &'static str> {
+			ensure!(
+				Pallet::<T>::on_chain_storage_version() >= 2,
+				"Storage version is not migrated"
+			);
+			ensure!(
+				NamedReserves::<T>::iter().all(|(who, (_, currency_id), amount)| {
+					amount <= T::Currency::reserved_balance(currency_id, &who)
+				}),
+				"Some tracked payments are not reserved"
+			);
+			Ok(())
+		}
+	}
+}
+
+/// Version 3 gives the accepted payments a fulfillment deadline. The payees of the existing
+/// accepted payments did not know about the deadline, so it is `FulfillmentTime` after the upgrade.
+pub mod v3 {
+	use super::*;
+	use crate::Payment;
+
+	pub struct MigrateToV3<T>(PhantomData<T>);
+
+	impl<T: Config> OnRuntimeUpgrade for MigrateToV3<T> {
+		fn on_runtime_upgrade() -> Weight {
+			if Pallet::<T>::on_chain_storage_version() != 2 {
+				return T::DbWeight::get().reads(1)
+			}
+
+			let deadline = <pallet_timestamp::Pallet<T>>::get() + T::FulfillmentTime::get();
+			let mut payments: Weight = 0;
+			Payments::<T>::translate::<v1::PaymentV1<T>, _>(|_, old| {
+				payments += 1;
+				let fulfillment_deadline =
+					if old.status == PaymentStatus::Accepted { Some(deadline) } else { None };
+				Some(Payment {
+					id: old.id,
+					payer: old.payer,
+					payee: old.payee,
+					amount: old.amount,
+					currency_id: old.currency_id,
+					description: old.description,
+					status: old.status,
+					receipt_hash: old.receipt_hash,
+					created_at: old.created_at,
+					updated_at: old.updated_at,
+					updated_by: old.updated_by,
+					risk_score: old.risk_score,
+					payee_confirmation: old.payee_confirmation,
+					refund_to: old.refund_to,
+					delivery_proofs: old.delivery_proofs,
+					fulfillment_deadline,
+				})
+			});
+
+			StorageVersion::new(3).put::<Pallet<T>>();
+			T::DbWeight::get().reads_writes(2 + payments, 1 + payments)
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<(), &'static str> {
+			Self::set_temp_storage(Payments::<T>::iter_keys().count() as u32, "payments");
+			Ok(())
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade() -> Result<(), &'static str> {
+			ensure!(
+				Pallet::<T>::on_chain_storage_version() >= 3,
+				"Storage version is not migrated"
+			);
+			let payments: u32 =
+				Self::get_temp_storage("payments").ok_or("Missing payments count")?;
+			ensure!(
+				Payments::<T>::iter_values().count() as u32 == payments,
+				"Some payments are not migrated"
+			);
+			Ok(())
+		}
+	}
+}
+
+/// Version 4 escrows the funds of the open payments. The funds tracked in `NamedReserves` are
+/// moved from the reserves of the payers to the escrow account, so they are never free in between.
+/// A payment whose payer has not enough tracked funds is reduced to the escrowed amount
+/// (`PaymentEscrowShortfall`), so the escrow account always backs the open payments. The rest of
+/// the tracked funds are unreserved.
+pub mod v4 {
+	use super::*;
+	use crate::Event;
+	use frame_support::{
+		log,
+		sp_runtime::traits::{Saturating, Zero},
+		sp_std::collections::btree_map::BTreeMap,
+		traits::BalanceStatus,
+	};
+	use orml_traits::{MultiCurrency, MultiReservableCurrency};
+	use primitives::CurrencyId;
+
+	type BalanceOf<T> =
+		<<T as Config>::Currency as MultiCurrency<<T as frame_system::Config>::AccountId>>::Balance;
+
+	pub struct MigrateToV4<T>(PhantomData<T>);
+
+	impl<T: Config> OnRuntimeUpgrade for MigrateToV4<T> {
+		fn on_runtime_upgrade() -> Weight {
+			if Pallet::<T>::on_chain_storage_version() != 3 {
+				return T::DbWeight::get().reads(1)
+			}
+
+			let mut tracked: BTreeMap<(T::AccountId, CurrencyId<T::Hash>), BalanceOf<T>> =
+				BTreeMap::new();
+			let mut reserves: Weight = 0;
+			for (who, (_, currency_id), amount) in NamedReserves::<T>::drain() {
+				reserves += 1;
+				let total = tracked.entry((who, currency_id)).or_insert_with(Zero::zero);
+				*total = total.saturating_add(amount);
+			}
+
+			let escrow = Pallet::<T>::escrow_account();
+			let mut payments: Weight = 0;
+			let mut transfers: Weight = 0;
+			let mut shortfalls: Weight = 0;
+			for (payment_hash, mut payment) in Payments::<T>::iter() {
+				payments += 1;
+				if !is_open(&payment.status) {
+					continue
+				}
+
+				let key = (payment.payer.clone(), payment.currency_id);
+				let available = tracked.get(&key).copied().unwrap_or_else(Zero::zero);
+				let to_escrow = payment.amount.min(available);
+				let not_moved = T::Currency::repatriate_reserved(
+					payment.currency_id,
+					&payment.payer,
+					&escrow,
+					to_escrow,
+					BalanceStatus::Free,
+				)
+				.unwrap_or(to_escrow);
+				let escrowed = to_escrow.saturating_sub(not_moved);
+				tracked.insert(key, available.saturating_sub(escrowed));
+				transfers += 1;
+
+				if escrowed < payment.amount {
+					let shortfall = payment.amount.saturating_sub(escrowed);
+					log::warn!(
+						target: "runtime::lrp",
+						"Payment {:?} is escrowed {:?} out of {:?}",
+						payment_hash,
+						escrowed,
+						payment.amount,
+					);
+					payment.amount = escrowed;
+					Payments::<T>::insert(&payment_hash, payment);
+					Pallet::<T>::deposit_event(Event::PaymentEscrowShortfall {
+						payment_hash,
+						escrowed,
+						shortfall,
+					});
+					shortfalls += 1;
+				}
+			}
+
+			// The rest of the tracked funds are not held for any open payment.
+			for ((who, currency_id), amount) in tracked.iter() {
+				T::Currency::unreserve(*currency_id, who, *amount);
+			}
+
+			StorageVersion::new(4).put::<Pallet<T>>();
+			T::DbWeight::get().reads_writes(
+				1 + reserves + payments + transfers * 2 + tracked.len() as Weight,
+				1 + reserves + transfers * 2 + shortfalls * 2 + tracked.len() as Weight,
+			)
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade() -> Result<(), &'static str> {
+			ensure!(
+				Pallet::<T>::on_chain_storage_version() >= 4,
+				"Storage version is not migrated"
+			);
+			ensure!(NamedReserves::<T>::iter().next().is_none(), "Some reserves are not released");
+
+			let mut escrowed: BTreeMap<CurrencyId<T::Hash>, BalanceOf<T>> = BTreeMap::new();
+			for payment in Payments::<T>::iter_values().filter(|payment| is_open(&payment.status)) {
+				let total = escrowed.entry(payment.currency_id).or_insert_with(Zero::zero);
+				*total = total.saturating_add(payment.amount);
+			}
+			let escrow = Pallet::<T>::escrow_account();
+			ensure!(
+				escrowed.into_iter().all(|(currency_id, total)| {
+					T::Currency::free_balance(currency_id, &escrow) >= total
+				}),
+				"Some payments are not escrowed"
+			);
+			Ok(())
+		}
+	}
+
+	// The payments whose funds are still held by the pallet.
+	pub(super) fn is_open(status: &PaymentStatus) -> bool {
+		matches!(
+			status,
+			PaymentStatus::Pending |
+				PaymentStatus::Accepted |
+				PaymentStatus::Fulfilled |
+				PaymentStatus::PartiallyFulfilled(_) |
+				PaymentStatus::Disputed
+		)
+	}
+}
+
+/// Version 5 tracks the funds of the open payments held by the escrow account in
+/// `TotalValueLocked`, summed from the open payments.
+pub mod v5 {
+	use super::*;
+	use frame_support::{
+		sp_runtime::traits::{Saturating, Zero},
+		sp_std::collections::btree_map::BTreeMap,
+	};
+	use orml_traits::MultiCurrency;
+	use primitives::CurrencyId;
+
+	type BalanceOf<T> =
+		<<T as Config>::Currency as MultiCurrency<<T as frame_system::Config>::AccountId>>::Balance;
+
+	pub struct MigrateToV5<T>(PhantomData<T>);
+
+	impl<T: Config> OnRuntimeUpgrade for MigrateToV5<T> {
+		fn on_runtime_upgrade() -> Weight {
+			if Pallet::<T>::on_chain_storage_version() != 4 {
+				return T::DbWeight::get().reads(1)
+			}
+
+			let (locked, payments) = open_funds::<T>();
+			let writes = locked.len() as Weight;
+			for (currency_id, total) in locked {
+				TotalValueLocked::<T>::insert(currency_id, total);
+			}
+
+			StorageVersion::new(5).put::<Pallet<T>>();
+			T::DbWeight::get().reads_writes(1 + payments, 1 + writes)
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade() -> Result<(), &'static str> {
+			ensure!(
+				Pallet::<T>::on_chain_storage_version() >= 5,
+				"Storage version is not migrated"
+			);
+			let (locked, _) = open_funds::<T>();
+			ensure!(
+				locked
+					.into_iter()
+					.all(|(currency_id, total)| TotalValueLocked::<T>::get(currency_id) == total),
+				"Some funds of the open payments are not tracked"
+			);
+			Ok(())
+		}
+	}
+
+	// The funds of the open payments per currency, with the number of the payments read.
+	fn open_funds<T: Config>() -> (BTreeMap<CurrencyId<T::Hash>, BalanceOf<T>>, Weight) {
+		let mut locked: BTreeMap<CurrencyId<T::Hash>, BalanceOf<T>> = BTreeMap::new();
+		let mut payments: Weight = 0;
+		for payment in Payments::<T>::iter_values() {
+			payments += 1;
+			if v4::is_open(&payment.status) {
+				let total = locked.entry(payment.currency_id).or_insert_with(Zero::zero);
+				*total = total.saturating_add(payment.amount);
+			}
+		}
+		(locked, payments)
+	}
+}
+
+/// Version 6 indexes the payments by their status and the bucket of their creation time in
+/// `PaymentsByStatus`. The buckets are not bounded, so every existing payment is indexed.
+pub mod v6 {
+	use super::*;
+
+	pub struct MigrateToV6<T>(PhantomData<T>);
+
+	impl<T: Config> OnRuntimeUpgrade for MigrateToV6<T> {
+		fn on_runtime_upgrade() -> Weight {
+			if Pallet::<T>::on_chain_storage_version() != 5 {
+				return T::DbWeight::get().reads(1)
+			}
+
+			let mut payments: Weight = 0;
+			for (payment_hash, payment) in Payments::<T>::iter() {
+				payments += 1;
+				Pallet::<T>::index_payment(payment_hash, &payment.status, payment.created_at);
+			}
+
+			StorageVersion::new(6).put::<Pallet<T>>();
+			T::DbWeight::get().reads_writes(1 + payments, 1 + payments)
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade() -> Result<(), &'static str> {
+			ensure!(
+				Pallet::<T>::on_chain_storage_version() >= 6,
+				"Storage version is not migrated"
+			);
+			ensure!(
+				PaymentsByStatus::<T>::iter_keys().count() == Payments::<T>::iter_keys().count(),
+				"Some payments are not indexed"
+			);
+			Ok(())
+		}
+	}
+}
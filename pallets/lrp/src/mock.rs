@@ -3,18 +3,22 @@
 use crate as pallet_lrp;
 
 use frame_support::{
-	construct_runtime, parameter_types,
+	construct_runtime,
+	dispatch::{DispatchError, DispatchResult},
+	parameter_types,
 	traits::{GenesisBuild, Nothing},
+	PalletId,
 };
 use frame_system as system;
 use orml_currencies::BasicCurrencyAdapter;
 use orml_traits::parameter_type_with_key;
 pub use pallet_balances::Call as BalancesCall;
 use pallet_timestamp::{self as timestamp};
-pub use primitives::{CurrencyId, Hash};
+pub use primitives::{Credibility, CurrencyId, Hash};
 use sp_runtime::{
 	generic,
 	traits::{BlakeTwo256, IdentityLookup},
+	Permill,
 };
 
 pub type BlockNumber = u64;
@@ -30,6 +34,24 @@ pub const CHARLIE: AccountId = 3;
 
 pub const PENDING_PAYMENT_WAITING_TIME: Moment = 172800000;
 pub const FULFILLED_WAITING_TIME: Moment = 2592000000;
+pub const QUEUE_BUCKET_DURATION: Moment = 6_000;
+pub const PAYMENT_INDEX_BUCKET_DURATION: Moment = 86_400_000;
+pub const RISK_MATURE_ACCOUNT_AGE: Moment = 2592000000;
+pub const RISK_HIGH_AMOUNT: Balance = 1_000;
+pub const DAILY_SPENDING_PERIOD: Moment = 86_400_000;
+pub const WEEKLY_SPENDING_PERIOD: Moment = 604_800_000;
+pub const PAYEE_CONFIRMATION_THRESHOLD: Balance = 500;
+pub const PAYEE_CONFIRMATION_COOLDOWN: Moment = 86_400_000;
+pub const MAX_DELIVERY_PROOFS: u32 = 3;
+pub const MAX_POLICY_CURRENCIES: u32 = 2;
+pub const MAX_PAYOUT_ROUTES: u32 = 3;
+pub const FULFILLMENT_TIME: Moment = 604_800_000;
+pub const INITIAL_CREDIBILITY: Credibility = 60;
+pub const MAX_CREDIBILITY: Credibility = 100;
+pub const TREASURY: AccountId = 99;
+pub const STATEMENT_ERA_DURATION: BlockNumber = 10;
+pub const INHERITANCE_INACTIVITY_PERIOD: Moment = 15_552_000_000;
+pub const INHERITANCE_CHALLENGE_WINDOW: Moment = 1_209_600_000;
 
 parameter_types! {
 	pub const BlockHashCount: u64 = 250;
@@ -64,7 +86,7 @@ impl frame_system::Config for Runtime {
 }
 
 parameter_types! {
-	pub const ExistentialDeposit: u128 = 500;
+	pub const ExistentialDeposit: u128 = 1;
 	pub const MaxLocks: u32 = 50;
 }
 
@@ -122,26 +144,151 @@ impl orml_currencies::Config for Runtime {
 }
 
 parameter_types! {
-	pub const BondingAmount: Balance = 100_000_000_000_000;
+	pub const CommunityListingBond: Balance = 100_000_000_000_000;
+	pub const VerifiedListingBond: Balance = 300_000_000_000_000;
+	pub const VerifiedListingFee: Balance = 50_000_000_000_000;
+	pub const TreasuryAccount: AccountId = TREASURY;
 }
 
 impl currencies_registry::Config for Runtime {
 	type Event = Event;
 	type Currency = Currencies;
-	type BondingAmount = BondingAmount;
+	type CommunityListingBond = CommunityListingBond;
+	type VerifiedListingBond = VerifiedListingBond;
+	type VerifiedListingFee = VerifiedListingFee;
+	type TreasuryAccount = TreasuryAccount;
+	type WeightInfo = ();
+}
+
+parameter_types! {
+	pub const EvaluatorBonding: Balance = 100;
+	pub const VerifyRequestTimeout: BlockNumber = 10;
+	pub const VerifyRequestPenalty: Balance = 5;
+	pub const MaxFieldPrices: u32 = 3;
+	pub const EvaluatorRateBand: Balance = 10;
+	pub const MaxLanguages: u32 = 3;
+	pub const MaxEncryptionKeys: u32 = 3;
+	pub const MaxAttestations: u32 = 3;
+	pub const HandleDeposit: Balance = 10;
+	pub const HandlePeriod: BlockNumber = 100;
+	pub const MaxHandleLength: u32 = 16;
+	pub const InitialCredibility: Credibility = INITIAL_CREDIBILITY;
+	pub const MaxCredibility: Credibility = MAX_CREDIBILITY;
+}
+
+impl pallet_identities::Config for Runtime {
+	type Event = Event;
+	type Currency = Currencies;
+	type EvaluatorBonding = EvaluatorBonding;
+	type GovernanceOrigin = system::EnsureRoot<AccountId>;
+	type Resolvers = ();
+	type VerifyRequestTimeout = VerifyRequestTimeout;
+	type VerifyRequestPenalty = VerifyRequestPenalty;
+	type MaxFieldPrices = MaxFieldPrices;
+	type EvaluatorRateBand = EvaluatorRateBand;
+	type MaxLanguages = MaxLanguages;
+	type MaxEncryptionKeys = MaxEncryptionKeys;
+	type MaxAttestations = MaxAttestations;
+	type HandleDeposit = HandleDeposit;
+	type HandlePeriod = HandlePeriod;
+	type MaxHandleLength = MaxHandleLength;
+	type InitialCredibility = InitialCredibility;
+	type MaxCredibility = MaxCredibility;
+	type WeightInfo = ();
 }
 
 parameter_types! {
 	pub const PendingPaymentWaitingTime: Moment = PENDING_PAYMENT_WAITING_TIME;
 	pub const FulfilledPaymentWaitingTime: Moment = FULFILLED_WAITING_TIME;
+	pub const QueueBucketDuration: Moment = QUEUE_BUCKET_DURATION;
+	pub const PaymentIndexBucketDuration: Moment = PAYMENT_INDEX_BUCKET_DURATION;
+	pub const RiskMatureAccountAge: Moment = RISK_MATURE_ACCOUNT_AGE;
+	pub const RiskHighAmount: Balance = RISK_HIGH_AMOUNT;
+	pub const DailySpendingPeriod: Moment = DAILY_SPENDING_PERIOD;
+	pub const WeeklySpendingPeriod: Moment = WEEKLY_SPENDING_PERIOD;
+	pub const PayeeConfirmationThreshold: Balance = PAYEE_CONFIRMATION_THRESHOLD;
+	pub const PayeeConfirmationCooldown: Moment = PAYEE_CONFIRMATION_COOLDOWN;
+	pub const FulfillmentTime: Moment = FULFILLMENT_TIME;
+	pub const MaxDeliveryProofs: u32 = MAX_DELIVERY_PROOFS;
+	pub const MaxPolicyCurrencies: u32 = MAX_POLICY_CURRENCIES;
+	pub const MaxReferralFeeShare: Permill = Permill::from_percent(50);
+	pub const MaxPayoutRoutes: u32 = MAX_PAYOUT_ROUTES;
+	pub const LrpPalletId: PalletId = PalletId(*b"lb/lrpes");
+	pub const StatementEraDuration: BlockNumber = STATEMENT_ERA_DURATION;
+	pub const InheritanceInactivityPeriod: Moment = INHERITANCE_INACTIVITY_PERIOD;
+	pub const InheritanceChallengeWindow: Moment = INHERITANCE_CHALLENGE_WINDOW;
+	pub static DefaultIdentityRequirement: pallet_lrp::IdentityRequirement<Balance> =
+		pallet_lrp::IdentityRequirement {
+			payer: false,
+			payee: false,
+			min_credibility: 0,
+			threshold: 0,
+		};
+	pub static QueueBatchSize: u32 = 100;
+	pub static MaxPaymentsPerBucket: u32 = 100;
+	pub static NativePaymentFee: Option<pallet_lrp::FeeSchedule<Balance>> = None;
+	pub static VelocityPolicy: Option<pallet_lrp::VelocityPolicy<Moment>> = None;
+	pub static DeniedAccounts: Vec<AccountId> = vec![];
+}
+
+pub struct PaymentFees;
+impl pallet_lrp::PaymentFee<CurrencyId<Hash>, Balance> for PaymentFees {
+	fn fee_schedule(currency_id: &CurrencyId<Hash>) -> Option<pallet_lrp::FeeSchedule<Balance>> {
+		match currency_id {
+			CurrencyId::Native => NativePaymentFee::get(),
+			_ => None,
+		}
+	}
+}
+
+pub struct Compliance;
+impl pallet_lrp::ComplianceCheck<AccountId, CurrencyId<Hash>, Balance> for Compliance {
+	fn check_payment(
+		payer: &AccountId,
+		payee: &AccountId,
+		_currency_id: &CurrencyId<Hash>,
+		_amount: Balance,
+	) -> DispatchResult {
+		let denied = DeniedAccounts::get();
+		if denied.contains(payer) || denied.contains(payee) {
+			return Err(DispatchError::Other("AccountDenied"))
+		}
+		Ok(())
+	}
 }
 
 impl pallet_lrp::Config for Runtime {
 	type Event = Event;
 	type Currency = Currencies;
 	type CurrenciesManager = CurrenciesRegistry;
+	type IdentitiesManager = Identities;
+	type PaymentFee = PaymentFees;
+	type ComplianceCheck = Compliance;
+	type TreasuryAccount = TreasuryAccount;
 	type PendingPaymentWaitingTime = PendingPaymentWaitingTime;
 	type FulfilledPaymentWaitingTime = FulfilledPaymentWaitingTime;
+	type QueueBucketDuration = QueueBucketDuration;
+	type MaxPaymentsPerBucket = MaxPaymentsPerBucket;
+	type QueueBatchSize = QueueBatchSize;
+	type PaymentIndexBucketDuration = PaymentIndexBucketDuration;
+	type DefaultIdentityRequirement = DefaultIdentityRequirement;
+	type RiskMatureAccountAge = RiskMatureAccountAge;
+	type RiskHighAmount = RiskHighAmount;
+	type DailySpendingPeriod = DailySpendingPeriod;
+	type WeeklySpendingPeriod = WeeklySpendingPeriod;
+	type PayeeConfirmationThreshold = PayeeConfirmationThreshold;
+	type PayeeConfirmationCooldown = PayeeConfirmationCooldown;
+	type FulfillmentTime = FulfillmentTime;
+	type MaxDeliveryProofs = MaxDeliveryProofs;
+	type MaxPolicyCurrencies = MaxPolicyCurrencies;
+	type MaxReferralFeeShare = MaxReferralFeeShare;
+	type MaxPayoutRoutes = MaxPayoutRoutes;
+	type PauseOrigin = system::EnsureRoot<AccountId>;
+	type PalletId = LrpPalletId;
+	type StatementEraDuration = StatementEraDuration;
+	type InheritanceInactivityPeriod = InheritanceInactivityPeriod;
+	type InheritanceChallengeWindow = InheritanceChallengeWindow;
+	type VelocityPolicy = VelocityPolicy;
 }
 
 type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Runtime>;
@@ -159,6 +306,7 @@ construct_runtime!(
 		Tokens: orml_tokens::{Pallet, Storage, Event<T>, Config<T>},
 		Currencies: orml_currencies::{Pallet, Call, Event<T>},
 		CurrenciesRegistry: currencies_registry::{Pallet, Call, Storage, Event<T>},
+		Identities: pallet_identities::{Pallet, Call, Storage, Event<T>},
 		LRP: pallet_lrp::{Pallet, Call, Storage, Event<T>},
 	}
 );
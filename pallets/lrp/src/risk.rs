@@ -0,0 +1,82 @@
+//! # Risk scoring
+//!
+//! The risk score of a payment is computed from the risk factors of the payment when it is
+//! created and accepted. The score ranges from 0 (no risk) to `MAX_RISK_SCORE`, so the merchants
+//! can auto-reject or require extra confirmation for the high-risk payments.
+//!
+//! The score is the sum of the weighted risk factors:
+//! - Payer credibility: up to 40 points, the full weight if the payer has no identity.
+//! - Account age: up to 20 points, decreasing to 0 once the payer account is mature.
+//! - Dispute history: 5 points per disputed payment of the payer, up to 20 points.
+//! - Amount: up to 20 points, proportional to the amount until the high-risk amount.
+//! - Category: 10 points if the payee categorized the payment in a high-risk category.
+//! - Payee dispute losses: up to 20 points, proportional to the share of the resolved disputes the
+//!   payee lost.
+//!
+//! If the payee attested a relationship with the payer, e.g. the payer is a known customer or
+//! supplier of the payee, 20 points are taken off the sum. The score is capped at
+//! `MAX_RISK_SCORE`.
+
+use primitives::Credibility;
+use sp_runtime::Permill;
+
+/// The risk score of a payment.
+pub type RiskScore = u8;
+
+pub const MAX_RISK_SCORE: RiskScore = 100;
+
+const CREDIBILITY_WEIGHT: u32 = 40;
+const ACCOUNT_AGE_WEIGHT: u32 = 20;
+const DISPUTE_HISTORY_WEIGHT: u32 = 20;
+const AMOUNT_WEIGHT: u32 = 20;
+const CATEGORY_WEIGHT: u32 = 10;
+const PAYEE_LOSS_WEIGHT: u32 = 20;
+const ATTESTATION_DISCOUNT: u32 = 20;
+const RISK_PER_DISPUTE: u32 = 5;
+const MAX_CREDIBILITY: Credibility = 100;
+
+pub struct RiskFactors {
+	/// The credibility of the payer, `None` if the payer does not have an identity.
+	pub payer_credibility: Option<Credibility>,
+	/// The age of the payer account relative to the mature account age.
+	pub account_maturity: Permill,
+	/// The number of disputed payments of the payer.
+	pub disputed_payments: u32,
+	/// The amount of the payment relative to the high-risk amount.
+	pub amount_ratio: Permill,
+	/// Whether the payment is in a high-risk category.
+	pub high_risk_category: bool,
+	/// The share of the resolved disputes the payee lost.
+	pub payee_loss_rate: Permill,
+	/// Whether the payee attested a relationship with the payer.
+	pub attested_by_payee: bool,
+}
+
+pub fn compute_risk_score(factors: &RiskFactors) -> RiskScore {
+	let credibility_risk = match factors.payer_credibility {
+		Some(credibility) => {
+			let missing = MAX_CREDIBILITY - credibility.min(MAX_CREDIBILITY);
+			(missing * CREDIBILITY_WEIGHT as Credibility / MAX_CREDIBILITY) as u32
+		},
+		None => CREDIBILITY_WEIGHT,
+	};
+	let account_age_risk = ACCOUNT_AGE_WEIGHT - factors.account_maturity * ACCOUNT_AGE_WEIGHT;
+	let dispute_history_risk = factors
+		.disputed_payments
+		.saturating_mul(RISK_PER_DISPUTE)
+		.min(DISPUTE_HISTORY_WEIGHT);
+	let amount_risk = factors.amount_ratio * AMOUNT_WEIGHT;
+	let category_risk = if factors.high_risk_category { CATEGORY_WEIGHT } else { 0 };
+	let payee_loss_risk = factors.payee_loss_rate * PAYEE_LOSS_WEIGHT;
+
+	let score = credibility_risk +
+		account_age_risk +
+		dispute_history_risk +
+		amount_risk +
+		category_risk +
+		payee_loss_risk;
+	let score =
+		if factors.attested_by_payee { score.saturating_sub(ATTESTATION_DISCOUNT) } else { score };
+
+	score.min(MAX_RISK_SCORE as u32) as RiskScore
+}
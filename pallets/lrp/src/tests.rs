@@ -1,18 +1,43 @@
 #![cfg(test)]
 
 use super::*;
-use frame_support::{assert_noop, assert_ok, traits::{ OffchainWorker }};
+use crate::risk::{compute_risk_score, RiskFactors, MAX_RISK_SCORE};
+use codec::Encode;
+use frame_support::{
+	assert_noop, assert_ok,
+	traits::{GetStorageVersion, OffchainWorker, OnRuntimeUpgrade, StorageVersion},
+};
 use frame_system as system;
 use mock::{
-	last_event, Currencies, CurrencyId, Event, ExtBuilder, Origin, Runtime, System, ALICE, BOB,
-	CHARLIE, LRP, Timestamp, PENDING_PAYMENT_WAITING_TIME, FULFILLED_WAITING_TIME,
+	last_event, AccountId, Balance, Currencies, CurrencyId, DefaultIdentityRequirement,
+	DeniedAccounts, Event, ExtBuilder, Identities, MaxPaymentsPerBucket, NativePaymentFee, Origin,
+	QueueBatchSize, Runtime, System, Timestamp, VelocityPolicy, ALICE, BOB, CHARLIE,
+	DAILY_SPENDING_PERIOD, FULFILLED_WAITING_TIME, FULFILLMENT_TIME, INHERITANCE_CHALLENGE_WINDOW,
+	INHERITANCE_INACTIVITY_PERIOD, INITIAL_CREDIBILITY, LRP, MAX_DELIVERY_PROOFS,
+	MAX_PAYOUT_ROUTES, MAX_POLICY_CURRENCIES, PAYEE_CONFIRMATION_COOLDOWN,
+	PAYEE_CONFIRMATION_THRESHOLD, PAYMENT_INDEX_BUCKET_DURATION, PENDING_PAYMENT_WAITING_TIME,
+	QUEUE_BUCKET_DURATION, RISK_MATURE_ACCOUNT_AGE, STATEMENT_ERA_DURATION, TREASURY,
 };
 use orml_traits::{MultiCurrency, MultiReservableCurrency};
-use sp_runtime::traits::Hash;
+use pallet_identities::{EncryptionKeyType, IdentityType};
+use primitives::{Judgment, PaymentCategory, PaymentFlows, PAYMENT_RESERVE};
+use sp_runtime::{traits::Hash, Percent, Permill};
 
 pub const INIT_TIMESTAMP: u64 = 1_000;
 pub const BLOCK_TIME: u64 = 6_000;
 
+// Hold the escrowed funds of the payments on the payer, as they were before the version 4.
+fn reserve_escrowed_funds(payer: AccountId, amount: Balance) {
+	let escrow = LRP::escrow_account();
+	assert_ok!(<Currencies as MultiCurrency<_>>::transfer(
+		CurrencyId::Native,
+		&escrow,
+		&payer,
+		amount
+	));
+	assert_ok!(Currencies::reserve(CurrencyId::Native, &payer, amount));
+}
+
 fn run_to_block_number(block_number: u64) {
 	while System::block_number() < block_number {
 		System::set_block_number(System::block_number() + 1);
@@ -21,6 +46,10 @@ fn run_to_block_number(block_number: u64) {
 	}
 }
 
+fn queue_bucket_of(moment: u64) -> u64 {
+	(moment + QUEUE_BUCKET_DURATION - 1) / QUEUE_BUCKET_DURATION
+}
+
 #[test]
 fn create_payment_works() {
 	ExtBuilder::default().build().execute_with(|| {
@@ -32,8 +61,9 @@ fn create_payment_works() {
 				BOB,
 				1001,
 				CurrencyId::Native,
+				PaymentDescription::Plain("".into()),
 				"".into(),
-				"".into(),
+				None,
 			),
 			Error::<Runtime>::InsufficientBalance
 		);
@@ -43,8 +73,9 @@ fn create_payment_works() {
 			BOB,
 			100,
 			CurrencyId::Native,
+			PaymentDescription::Plain("".into()),
 			"".into(),
-			"".into(),
+			None,
 		));
 
 		let payment_hashes = LRP::payments_owned(&ALICE);
@@ -57,9 +88,12 @@ fn create_payment_works() {
 				payee: BOB,
 				currency_id: CurrencyId::Native,
 				amount: 100,
+				risk_score: 62,
+				payer_handle: None,
+				payee_handle: None,
 			}),
 		);
-		assert_eq!(Currencies::reserved_balance(CurrencyId::Native, &ALICE), 100);
+		assert_eq!(Currencies::free_balance(CurrencyId::Native, &LRP::escrow_account()), 100);
 		assert_eq!(Currencies::free_balance(CurrencyId::Native, &ALICE), 900);
 
 		let payment = LRP::payments(payment_hashes[0]).unwrap();
@@ -74,24 +108,143 @@ fn create_payment_works() {
 }
 
 #[test]
-fn accept_payment_works() {
+fn create_payment_with_foreign_asset_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+
+		let usdc = CurrencyId::ForeignAsset(1);
+		assert_ok!(Currencies::deposit(usdc, &ALICE, 1_000));
+
+		// The foreign assets do not need to be registered and accepted by the payee.
+		assert_ok!(LRP::create_payment(
+			Origin::signed(ALICE),
+			BOB,
+			100,
+			usdc,
+			PaymentDescription::Plain("".into()),
+			"".into(),
+			None,
+		));
+
+		let payment = LRP::payments(LRP::payments_owned(&ALICE)[0]).unwrap();
+		assert_eq!(payment.currency_id, usdc);
+		assert_eq!(Currencies::free_balance(usdc, &LRP::escrow_account()), 100);
+		assert_eq!(Currencies::free_balance(CurrencyId::Native, &LRP::escrow_account()), 0);
+	});
+}
+
+#[test]
+fn payment_hash_can_be_computed_before_submission() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+
+		let nonce = System::account_nonce(&ALICE);
+		let payment_hash = LRP::compute_payment_hash(1, &ALICE, &BOB, nonce);
+
+		Timestamp::set_timestamp(INIT_TIMESTAMP);
+		assert_ok!(LRP::create_payment(
+			Origin::signed(ALICE),
+			BOB,
+			100,
+			CurrencyId::Native,
+			PaymentDescription::Plain("".into()),
+			"".into(),
+			None,
+		));
+		assert_eq!(LRP::payments_owned(&ALICE), [payment_hash].to_vec());
+		assert!(LRP::payments(payment_hash).is_some());
+
+		// The next payment gets a different hash even with the same fields.
+		System::inc_account_nonce(&ALICE);
+		let next_payment_hash =
+			LRP::compute_payment_hash(2, &ALICE, &BOB, System::account_nonce(&ALICE));
+		assert_ok!(LRP::create_payment(
+			Origin::signed(ALICE),
+			BOB,
+			100,
+			CurrencyId::Native,
+			PaymentDescription::Plain("".into()),
+			"".into(),
+			None,
+		));
+		assert_ne!(next_payment_hash, payment_hash);
+		assert_eq!(LRP::payments_owned(&ALICE), [payment_hash, next_payment_hash].to_vec());
+	});
+}
+
+#[test]
+fn create_payment_with_encrypted_description_works() {
 	ExtBuilder::default().build().execute_with(|| {
 		System::set_block_number(1);
 
+		let cipher = b"ciphertext".to_vec();
+		assert_noop!(
+			LRP::create_payment(
+				Origin::signed(ALICE),
+				BOB,
+				100,
+				CurrencyId::Native,
+				PaymentDescription::EncryptedDescription(cipher.clone()),
+				"".into(),
+				None
+			),
+			Error::<Runtime>::EncryptionKeyNotFound
+		);
+
+		assert_ok!(Identities::create_identity(
+			Origin::signed(BOB),
+			"Bob".into(),
+			IdentityType::Individual,
+			[].into(),
+		));
+		assert_ok!(Identities::set_encryption_key(
+			Origin::signed(BOB),
+			EncryptionKeyType::X25519,
+			[7; 32]
+		));
 		assert_ok!(LRP::create_payment(
 			Origin::signed(ALICE),
 			BOB,
 			100,
 			CurrencyId::Native,
+			PaymentDescription::EncryptedDescription(cipher.clone()),
 			"".into(),
+			None,
+		));
+
+		// Only the hash of the ciphertext is stored on chain.
+		let payment_hash = LRP::payments_owned(&ALICE)[0];
+		assert!(LRP::payments(payment_hash).unwrap().description.is_empty());
+		assert_eq!(
+			LRP::encrypted_descriptions(payment_hash),
+			Some(EncryptedDescription {
+				key: [7; 32].to_vec(),
+				cipher_hash: <Runtime as system::Config>::Hashing::hash_of(&cipher),
+			}),
+		);
+	});
+}
+
+#[test]
+fn accept_payment_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+
+		assert_ok!(LRP::create_payment(
+			Origin::signed(ALICE),
+			BOB,
+			100,
+			CurrencyId::Native,
+			PaymentDescription::Plain("".into()),
 			"".into(),
+			None,
 		));
 		let payment_hashes = LRP::payments_owned(&ALICE);
 
 		assert_eq!(payment_hashes.len(), 1);
 		assert_noop!(
 			LRP::accept_payment(Origin::signed(CHARLIE), payment_hashes[0]),
-			Error::<Runtime>::AccessDenied
+			Error::<Runtime>::NotPayeeOrProxy
 		);
 
 		assert_ok!(LRP::accept_payment(Origin::signed(BOB), payment_hashes[0]));
@@ -103,6 +256,8 @@ fn accept_payment_works() {
 				payee: BOB,
 				currency_id: CurrencyId::Native,
 				amount: 100,
+				risk_score: 62,
+				category: None,
 			}),
 		);
 
@@ -121,19 +276,20 @@ fn reject_payment_works() {
 			BOB,
 			100,
 			CurrencyId::Native,
+			PaymentDescription::Plain("".into()),
 			"".into(),
-			"".into(),
+			None,
 		));
 		let payment_hashes = LRP::payments_owned(&ALICE);
 
 		assert_eq!(payment_hashes.len(), 1);
 		assert_noop!(
 			LRP::reject_payment(Origin::signed(CHARLIE), payment_hashes[0]),
-			Error::<Runtime>::AccessDenied
+			Error::<Runtime>::NotPayeeOrProxy
 		);
 		assert_noop!(
 			LRP::reject_payment(Origin::signed(ALICE), payment_hashes[0]),
-			Error::<Runtime>::AccessDenied
+			Error::<Runtime>::NotPayeeOrProxy
 		);
 
 		assert_ok!(LRP::reject_payment(Origin::signed(BOB), payment_hashes[0]));
@@ -151,7 +307,7 @@ fn reject_payment_works() {
 		let payment = LRP::payments(payment_hashes[0]).unwrap();
 		assert_eq!(payment.status, crate::PaymentStatus::Rejected);
 
-		assert_eq!(Currencies::reserved_balance(CurrencyId::Native, &ALICE), 0);
+		assert_eq!(Currencies::free_balance(CurrencyId::Native, &LRP::escrow_account()), 0);
 		assert_eq!(Currencies::free_balance(CurrencyId::Native, &ALICE), 1000);
 	});
 }
@@ -166,8 +322,9 @@ fn auto_expire_payment_by_offchain_worker_works() {
 			BOB,
 			100,
 			CurrencyId::Native,
+			PaymentDescription::Plain("".into()),
 			"".into(),
-			"".into(),
+			None,
 		));
 
 		run_to_block_number((PENDING_PAYMENT_WAITING_TIME / BLOCK_TIME).into());
@@ -186,36 +343,116 @@ fn auto_expire_payment_by_offchain_worker_works() {
 			}),
 		);
 		assert_eq!(payment.status, PaymentStatus::Expired);
-		assert_eq!(Currencies::reserved_balance(CurrencyId::Native, &ALICE), 0);
+		assert_eq!(Currencies::free_balance(CurrencyId::Native, &LRP::escrow_account()), 0);
 		assert_eq!(Currencies::free_balance(CurrencyId::Native, &ALICE), 1_000);
 		assert_eq!(Currencies::free_balance(CurrencyId::Native, &BOB), 1_000);
 	});
 }
 
 #[test]
-fn cancel_payment_works_with_payer() {
+fn offchain_worker_evaluates_queue_in_batches() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		Timestamp::set_timestamp(INIT_TIMESTAMP);
+		QueueBatchSize::set(1);
+
+		for _ in 0..2 {
+			assert_ok!(LRP::create_payment(
+				Origin::signed(ALICE),
+				BOB,
+				100,
+				CurrencyId::Native,
+				PaymentDescription::Plain("".into()),
+				"".into(),
+				None,
+			));
+		}
+		let status =
+			|index: usize| LRP::payments(LRP::payments_owned(&ALICE)[index]).unwrap().status;
+
+		Timestamp::set_timestamp(
+			INIT_TIMESTAMP + PENDING_PAYMENT_WAITING_TIME + QUEUE_BUCKET_DURATION,
+		);
+
+		// Only one payment of the due bucket is expired in a run, the rest is left in the bucket.
+		LRP::offchain_worker(System::block_number());
+		assert_eq!(status(0), PaymentStatus::Expired);
+		assert_eq!(status(1), PaymentStatus::Pending);
+
+		LRP::offchain_worker(System::block_number());
+		assert_eq!(status(1), PaymentStatus::Expired);
+		assert_eq!(Currencies::free_balance(CurrencyId::Native, &ALICE), 1_000);
+	});
+}
+
+#[test]
+fn failing_queued_payment_does_not_block_the_queue() {
 	ExtBuilder::default().build().execute_with(|| {
 		System::set_block_number(1);
+		Timestamp::set_timestamp(INIT_TIMESTAMP);
 
 		assert_ok!(LRP::create_payment(
 			Origin::signed(ALICE),
 			BOB,
 			100,
 			CurrencyId::Native,
+			PaymentDescription::Plain("".into()),
 			"".into(),
+			None,
+		));
+		let payment_hash = LRP::payments_owned(&ALICE)[0];
+
+		// A payment which cannot be expired is queued ahead of the payment.
+		let unknown_hash = <Runtime as system::Config>::Hashing::hash_of(&"unknown");
+		let pending_bucket = queue_bucket_of(INIT_TIMESTAMP + PENDING_PAYMENT_WAITING_TIME);
+		PendingPaymentsQueue::<Runtime>::mutate(pending_bucket, |payment_hashes| {
+			assert_ok!(payment_hashes.try_insert(0, unknown_hash));
+		});
+
+		Timestamp::set_timestamp(
+			INIT_TIMESTAMP + PENDING_PAYMENT_WAITING_TIME + QUEUE_BUCKET_DURATION,
+		);
+		LRP::offchain_worker(System::block_number());
+
+		assert!(System::events().iter().any(|record| record.event ==
+			Event::LRP(crate::Event::QueuedPaymentFailed {
+				payment_hash: unknown_hash,
+				error: Error::<Runtime>::PaymentNotFound.into(),
+			})));
+		assert_eq!(LRP::payments(payment_hash).unwrap().status, PaymentStatus::Expired);
+		assert!(!PendingPaymentsQueue::<Runtime>::contains_key(pending_bucket));
+		assert!(NextPendingPaymentsBucket::<Runtime>::get() > Some(pending_bucket));
+	});
+}
+
+#[test]
+fn expire_payment_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		Timestamp::set_timestamp(INIT_TIMESTAMP);
+
+		assert_ok!(LRP::create_payment(
+			Origin::signed(ALICE),
+			BOB,
+			100,
+			CurrencyId::Native,
+			PaymentDescription::Plain("".into()),
 			"".into(),
+			None,
 		));
 		let payment_hash = LRP::payments_owned(&ALICE)[0];
 
 		assert_noop!(
-			LRP::cancel_payment(Origin::signed(CHARLIE), payment_hash),
-			Error::<Runtime>::AccessDenied
+			LRP::expire_payment(Origin::signed(CHARLIE), payment_hash),
+			Error::<Runtime>::PaymentNonexpired
 		);
 
-		assert_ok!(LRP::cancel_payment(Origin::signed(ALICE), payment_hash));
+		Timestamp::set_timestamp(INIT_TIMESTAMP + PENDING_PAYMENT_WAITING_TIME);
+
+		assert_ok!(LRP::expire_payment(Origin::signed(CHARLIE), payment_hash));
 		assert_eq!(
 			last_event(),
-			Event::LRP(crate::Event::PaymentCancelled {
+			Event::LRP(crate::Event::PaymentExpired {
 				payment_hash,
 				payer: ALICE,
 				payee: BOB,
@@ -223,13 +460,21 @@ fn cancel_payment_works_with_payer() {
 				amount: 100,
 			}),
 		);
-		assert_eq!(Currencies::reserved_balance(CurrencyId::Native, &ALICE), 0);
-		assert_eq!(Currencies::free_balance(CurrencyId::Native, &ALICE), 1000);
+
+		let payment = LRP::payments(payment_hash).unwrap();
+		assert_eq!(payment.status, PaymentStatus::Expired);
+		assert_eq!(Currencies::free_balance(CurrencyId::Native, &LRP::escrow_account()), 0);
+		assert_eq!(Currencies::free_balance(CurrencyId::Native, &ALICE), 1_000);
+
+		assert_noop!(
+			LRP::expire_payment(Origin::signed(CHARLIE), payment_hash),
+			Error::<Runtime>::PaymentNotPending
+		);
 	});
 }
 
 #[test]
-fn cancel_payment_only_works_with_payee_if_payment_accepted() {
+fn cancel_payment_works_with_payer() {
 	ExtBuilder::default().build().execute_with(|| {
 		System::set_block_number(1);
 
@@ -238,19 +483,18 @@ fn cancel_payment_only_works_with_payee_if_payment_accepted() {
 			BOB,
 			100,
 			CurrencyId::Native,
+			PaymentDescription::Plain("".into()),
 			"".into(),
-			"".into(),
+			None,
 		));
 		let payment_hash = LRP::payments_owned(&ALICE)[0];
-		assert_ok!(LRP::accept_payment(Origin::signed(BOB), payment_hash));
+
 		assert_noop!(
-			LRP::cancel_payment(Origin::signed(ALICE), payment_hash),
-			Error::<Runtime>::AccessDenied
+			LRP::cancel_payment(Origin::signed(CHARLIE), payment_hash),
+			Error::<Runtime>::NotPayer
 		);
-		assert_eq!(Currencies::reserved_balance(CurrencyId::Native, &ALICE), 100);
-		assert_eq!(Currencies::free_balance(CurrencyId::Native, &ALICE), 900);
 
-		assert_ok!(LRP::cancel_payment(Origin::signed(BOB), payment_hash));
+		assert_ok!(LRP::cancel_payment(Origin::signed(ALICE), payment_hash));
 		assert_eq!(
 			last_event(),
 			Event::LRP(crate::Event::PaymentCancelled {
@@ -261,13 +505,13 @@ fn cancel_payment_only_works_with_payee_if_payment_accepted() {
 				amount: 100,
 			}),
 		);
-		assert_eq!(Currencies::reserved_balance(CurrencyId::Native, &ALICE), 0);
+		assert_eq!(Currencies::free_balance(CurrencyId::Native, &LRP::escrow_account()), 0);
 		assert_eq!(Currencies::free_balance(CurrencyId::Native, &ALICE), 1000);
 	});
 }
 
 #[test]
-fn full_fill_payment_works() {
+fn cancel_payment_keeps_other_reserves() {
 	ExtBuilder::default().build().execute_with(|| {
 		System::set_block_number(1);
 
@@ -276,36 +520,131 @@ fn full_fill_payment_works() {
 			BOB,
 			100,
 			CurrencyId::Native,
+			PaymentDescription::Plain("".into()),
 			"".into(),
-			"".into(),
+			None,
 		));
 		let payment_hash = LRP::payments_owned(&ALICE)[0];
-		assert_ok!(LRP::accept_payment(Origin::signed(BOB), payment_hash));
+		assert_eq!(Currencies::free_balance(CurrencyId::Native, &LRP::escrow_account()), 100);
 
-		assert_noop!(
-			LRP::fulfill_payment(Origin::signed(CHARLIE), payment_hash),
-			Error::<Runtime>::AccessDenied
-		);
+		// The funds reserved by another pallet are not released with the payment.
+		assert_ok!(Currencies::reserve(CurrencyId::Native, &ALICE, 50));
+		assert_ok!(LRP::cancel_payment(Origin::signed(ALICE), payment_hash));
 
-		assert_ok!(LRP::fulfill_payment(Origin::signed(BOB), payment_hash));
+		assert_eq!(Currencies::reserved_balance(CurrencyId::Native, &ALICE), 50);
+		assert_eq!(Currencies::free_balance(CurrencyId::Native, &ALICE), 950);
+		assert_eq!(Currencies::free_balance(CurrencyId::Native, &LRP::escrow_account()), 0);
+	});
+}
+
+#[test]
+fn resolve_payment_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+
+		for _ in 0..2 {
+			assert_ok!(LRP::create_payment(
+				Origin::signed(ALICE),
+				BOB,
+				100,
+				CurrencyId::Native,
+				PaymentDescription::Plain("".into()),
+				"".into(),
+				None,
+			));
+		}
+		let payment_hashes = LRP::payments_owned(&ALICE);
+		for payment_hash in payment_hashes.iter() {
+			assert_ok!(LRP::accept_payment(Origin::signed(BOB), *payment_hash));
+		}
+		assert_ok!(LRP::set_refund_address(
+			Origin::signed(ALICE),
+			payment_hashes[1],
+			Some(CHARLIE)
+		));
+
+		assert_eq!(
+			LRP::resolve(&payment_hashes[0], Judgment::ReleaseFundToPayee, &[]),
+			Ok((BOB, 100))
+		);
 		assert_eq!(
 			last_event(),
-			Event::LRP(crate::Event::PaymentFulfilled {
-				payment_hash,
-				payer: ALICE,
-				payee: BOB,
-				currency_id: CurrencyId::Native,
+			Event::LRP(crate::Event::PaymentResolved {
+				payment_hash: payment_hashes[0],
+				beneficiary: BOB,
 				amount: 100,
 			}),
 		);
+		assert_eq!(Currencies::free_balance(CurrencyId::Native, &BOB), 1_100);
 
-		let payment = LRP::payments(payment_hash).unwrap();
-		assert_eq!(payment.status, crate::PaymentStatus::Fulfilled);
+		// The payer is refunded to its refund address.
+		assert_eq!(
+			LRP::resolve(&payment_hashes[1], Judgment::ReleaseFundToPayer, &[]),
+			Ok((CHARLIE, 100))
+		);
+		assert_eq!(LRP::payments(payment_hashes[1]).unwrap().status, PaymentStatus::Resolved);
+		assert_eq!(Currencies::free_balance(CurrencyId::Native, &LRP::escrow_account()), 0);
+
+		// A resolved payment cannot be resolved again.
+		assert_noop!(
+			LRP::resolve(&payment_hashes[0], Judgment::ReleaseFundToPayer, &[]),
+			Error::<Runtime>::InvalidStatusChange
+		);
 	});
 }
 
 #[test]
-fn complete_payment_works() {
+fn resolve_marketplace_payment_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		NativePaymentFee::set(Some(FeeSchedule { flat: 5, percentage: Permill::from_percent(10) }));
+		assert_ok!(Currencies::deposit(CurrencyId::Native, &CHARLIE, 1_000));
+
+		for _ in 0..2 {
+			assert_ok!(LRP::create_marketplace_payment(
+				Origin::signed(ALICE),
+				BOB,
+				100,
+				CurrencyId::Native,
+				PaymentDescription::Plain("".into()),
+				"".into(),
+				CHARLIE,
+				None,
+			));
+		}
+		let payment_hashes = LRP::payments_owned(&ALICE);
+		for payment_hash in payment_hashes.iter() {
+			assert_ok!(LRP::accept_payment(Origin::signed(BOB), *payment_hash));
+		}
+		assert_eq!(LRP::get_marketplace(&payment_hashes[0]), Some((CHARLIE, 15)));
+
+		// The share of the marketplace is paid out of the funds released to the payee.
+		assert_eq!(
+			LRP::resolve(&payment_hashes[0], Judgment::ReleaseFundToPayee, &[(CHARLIE, 15)]),
+			Ok((BOB, 85))
+		);
+		assert!(System::events().iter().any(|record| record.event ==
+			Event::LRP(crate::Event::PaymentShareReleased {
+				payment_hash: payment_hashes[0],
+				stakeholder: CHARLIE,
+				amount: 15,
+			})));
+		assert_eq!(Currencies::free_balance(CurrencyId::Native, &CHARLIE), 1_015);
+		assert_eq!(Currencies::free_balance(CurrencyId::Native, &BOB), 1_085);
+
+		// The payer is refunded in full.
+		assert_eq!(
+			LRP::resolve(&payment_hashes[1], Judgment::ReleaseFundToPayer, &[(CHARLIE, 15)]),
+			Ok((ALICE, 100))
+		);
+		assert_eq!(Currencies::free_balance(CurrencyId::Native, &CHARLIE), 1_015);
+		assert_eq!(Currencies::free_balance(CurrencyId::Native, &LRP::escrow_account()), 0);
+		assert_ok!(LRP::do_try_state());
+	});
+}
+
+#[test]
+fn resolve_payment_works_once() {
 	ExtBuilder::default().build().execute_with(|| {
 		System::set_block_number(1);
 
@@ -314,77 +653,2705 @@ fn complete_payment_works() {
 			BOB,
 			100,
 			CurrencyId::Native,
+			PaymentDescription::Plain("".into()),
 			"".into(),
-			"".into(),
+			None,
 		));
 		let payment_hash = LRP::payments_owned(&ALICE)[0];
 
+		assert_noop!(
+			LRP::resolve(&payment_hash, Judgment::ReleaseFundToPayer, &[]),
+			Error::<Runtime>::InvalidStatusChange
+		);
 		assert_ok!(LRP::accept_payment(Origin::signed(BOB), payment_hash));
-		assert_ok!(LRP::fulfill_payment(Origin::signed(BOB), payment_hash));
+
+		assert_eq!(
+			LRP::resolve(&payment_hash, Judgment::ReleaseFundToPayer, &[]),
+			Ok((ALICE, 100))
+		);
+		assert_eq!(LRP::payments(payment_hash).unwrap().status, PaymentStatus::Resolved);
+		assert_eq!(Currencies::free_balance(CurrencyId::Native, &LRP::escrow_account()), 0);
+		assert_eq!(Currencies::free_balance(CurrencyId::Native, &ALICE), 1000);
 
 		assert_noop!(
-			LRP::complete_payment(Origin::signed(CHARLIE), payment_hash),
-			Error::<Runtime>::AccessDenied
+			LRP::resolve(&payment_hash, Judgment::ReleaseFundToPayee, &[]),
+			Error::<Runtime>::InvalidStatusChange
+		);
+		assert_noop!(
+			LRP::cancel_payment(Origin::signed(BOB), payment_hash),
+			Error::<Runtime>::PaymentNotCancellable
 		);
+	});
+}
+
+#[test]
+fn refund_address_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+
+		assert_ok!(LRP::create_payment(
+			Origin::signed(ALICE),
+			BOB,
+			500,
+			CurrencyId::Native,
+			PaymentDescription::Plain("".into()),
+			"".into(),
+			None,
+		));
+		let payment_hash = LRP::payments_owned(&ALICE)[0];
 
 		assert_noop!(
-			LRP::complete_payment(Origin::signed(BOB), payment_hash),
-			Error::<Runtime>::AccessDenied
+			LRP::set_refund_address(Origin::signed(CHARLIE), payment_hash, Some(CHARLIE)),
+			Error::<Runtime>::NotPayer
 		);
 
-		assert_ok!(LRP::complete_payment(Origin::signed(ALICE), payment_hash));
+		assert_ok!(LRP::set_refund_address(Origin::signed(ALICE), payment_hash, Some(CHARLIE)));
 		assert_eq!(
 			last_event(),
-			Event::LRP(crate::Event::PaymentCompleted {
+			Event::LRP(crate::Event::RefundAddressUpdated {
 				payment_hash,
 				payer: ALICE,
-				payee: BOB,
-				currency_id: CurrencyId::Native,
-				amount: 100,
+				refund_to: Some(CHARLIE),
 			}),
 		);
-		assert_eq!(Currencies::reserved_balance(CurrencyId::Native, &ALICE), 0);
-		assert_eq!(Currencies::free_balance(CurrencyId::Native, &ALICE), 900);
-		assert_eq!(Currencies::free_balance(CurrencyId::Native, &BOB), 1100);
+		assert_eq!(LRP::get_refund_address(&payment_hash), Ok(CHARLIE));
+
+		assert_ok!(LRP::cancel_payment(Origin::signed(ALICE), payment_hash));
+		assert_eq!(Currencies::free_balance(CurrencyId::Native, &LRP::escrow_account()), 0);
+		assert_eq!(Currencies::free_balance(CurrencyId::Native, &ALICE), 500);
+		assert_eq!(Currencies::free_balance(CurrencyId::Native, &CHARLIE), 500);
+
+		assert_noop!(
+			LRP::set_refund_address(Origin::signed(ALICE), payment_hash, None),
+			Error::<Runtime>::PaymentClosed
+		);
 	});
 }
 
 #[test]
-fn auto_complete_payment_by_offchain_worker_works() {
+fn payment_locale_works() {
 	ExtBuilder::default().build().execute_with(|| {
 		System::set_block_number(1);
 
 		assert_ok!(LRP::create_payment(
 			Origin::signed(ALICE),
 			BOB,
-			100,
+			500,
 			CurrencyId::Native,
+			PaymentDescription::Plain("".into()),
 			"".into(),
-			"".into(),
+			None,
 		));
 		let payment_hash = LRP::payments_owned(&ALICE)[0];
-	
-		assert_ok!(LRP::accept_payment(Origin::signed(BOB), payment_hash));
-		assert_ok!(LRP::fulfill_payment(Origin::signed(BOB), payment_hash));
-
-		run_to_block_number((FULFILLED_WAITING_TIME / BLOCK_TIME).into());
+		assert_eq!(LRP::get_locale(&payment_hash), None);
 
-		let payment_hash = LRP::payments_owned(&ALICE)[0];
-		let payment = LRP::payments(payment_hash).unwrap();
+		assert_noop!(
+			LRP::set_payment_locale(Origin::signed(BOB), payment_hash, Some(*b"vi")),
+			Error::<Runtime>::NotPayer
+		);
 
-		assert_eq!(payment.status, PaymentStatus::Completed);
+		assert_ok!(LRP::set_payment_locale(Origin::signed(ALICE), payment_hash, Some(*b"vi")));
 		assert_eq!(
 			last_event(),
-			Event::LRP(crate::Event::PaymentCompleted {
-				payment_hash,
-				payer: ALICE,
+			Event::LRP(crate::Event::PaymentLocaleUpdated { payment_hash, locale: Some(*b"vi") }),
+		);
+		assert_eq!(LRP::get_locale(&payment_hash), Some(*b"vi"));
+
+		// The hint cannot be changed once the payee accepts the payment.
+		assert_ok!(LRP::accept_payment(Origin::signed(BOB), payment_hash));
+		assert_noop!(
+			LRP::set_payment_locale(Origin::signed(ALICE), payment_hash, None),
+			Error::<Runtime>::PaymentNotPending
+		);
+		assert_eq!(LRP::get_locale(&payment_hash), Some(*b"vi"));
+	});
+}
+
+#[test]
+fn payment_category_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+
+		assert_ok!(LRP::create_payment(
+			Origin::signed(ALICE),
+			BOB,
+			100,
+			CurrencyId::Native,
+			PaymentDescription::Plain("".into()),
+			"".into(),
+			None,
+		));
+		let payment_hash = LRP::payments_owned(&ALICE)[0];
+		assert_eq!(LRP::get_category(&payment_hash), None);
+
+		assert_noop!(
+			LRP::set_payment_category(
+				Origin::signed(ALICE),
+				payment_hash,
+				Some(PaymentCategory::DigitalGoods)
+			),
+			Error::<Runtime>::NotPayeeOrProxy
+		);
+
+		// The high-risk category increases the risk score.
+		assert_ok!(LRP::set_payment_category(
+			Origin::signed(BOB),
+			payment_hash,
+			Some(PaymentCategory::DigitalGoods)
+		));
+		assert_eq!(
+			last_event(),
+			Event::LRP(crate::Event::PaymentCategoryUpdated {
+				payment_hash,
+				payee: BOB,
+				category: Some(PaymentCategory::DigitalGoods),
+				risk_score: 72,
+			}),
+		);
+		assert_eq!(LRP::get_category(&payment_hash), Some(PaymentCategory::DigitalGoods));
+		assert_eq!(LRP::category_payments(PaymentCategory::DigitalGoods, payment_hash), Some(()));
+
+		// The payment is re-indexed under the new category.
+		let restaurants = PaymentCategory::MerchantCategoryCode(5812);
+		assert_ok!(LRP::set_payment_category(Origin::signed(BOB), payment_hash, Some(restaurants)));
+		assert_eq!(LRP::category_payments(PaymentCategory::DigitalGoods, payment_hash), None);
+		assert_eq!(LRP::category_payments(restaurants, payment_hash), Some(()));
+		assert_eq!(LRP::payments(payment_hash).unwrap().risk_score, 62);
+
+		assert_ok!(LRP::accept_payment(Origin::signed(BOB), payment_hash));
+		assert_eq!(
+			last_event(),
+			Event::LRP(crate::Event::PaymentAccepted {
+				payment_hash,
+				payer: ALICE,
 				payee: BOB,
 				currency_id: CurrencyId::Native,
 				amount: 100,
+				risk_score: 62,
+				category: Some(restaurants),
 			}),
 		);
-		assert_eq!(Currencies::reserved_balance(CurrencyId::Native, &ALICE), 0);
+
+		// The category cannot be changed once the payment is fulfilled.
+		assert_ok!(LRP::fulfill_payment(Origin::signed(BOB), payment_hash));
+		assert_noop!(
+			LRP::set_payment_category(Origin::signed(BOB), payment_hash, None),
+			Error::<Runtime>::InvalidStatusChange
+		);
+	});
+}
+
+#[test]
+fn cancel_payment_only_works_with_payee_if_payment_accepted() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+
+		assert_ok!(LRP::create_payment(
+			Origin::signed(ALICE),
+			BOB,
+			100,
+			CurrencyId::Native,
+			PaymentDescription::Plain("".into()),
+			"".into(),
+			None,
+		));
+		let payment_hash = LRP::payments_owned(&ALICE)[0];
+		assert_ok!(LRP::accept_payment(Origin::signed(BOB), payment_hash));
+		assert_noop!(
+			LRP::cancel_payment(Origin::signed(ALICE), payment_hash),
+			Error::<Runtime>::NotPayee
+		);
+		assert_eq!(Currencies::free_balance(CurrencyId::Native, &LRP::escrow_account()), 100);
+		assert_eq!(Currencies::free_balance(CurrencyId::Native, &ALICE), 900);
+
+		assert_ok!(LRP::cancel_payment(Origin::signed(BOB), payment_hash));
+		assert_eq!(
+			last_event(),
+			Event::LRP(crate::Event::PaymentCancelled {
+				payment_hash,
+				payer: ALICE,
+				payee: BOB,
+				currency_id: CurrencyId::Native,
+				amount: 100,
+			}),
+		);
+		assert_eq!(Currencies::free_balance(CurrencyId::Native, &LRP::escrow_account()), 0);
+		assert_eq!(Currencies::free_balance(CurrencyId::Native, &ALICE), 1000);
+	});
+}
+
+#[test]
+fn full_fill_payment_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+
+		assert_ok!(LRP::create_payment(
+			Origin::signed(ALICE),
+			BOB,
+			100,
+			CurrencyId::Native,
+			PaymentDescription::Plain("".into()),
+			"".into(),
+			None,
+		));
+		let payment_hash = LRP::payments_owned(&ALICE)[0];
+		assert_ok!(LRP::accept_payment(Origin::signed(BOB), payment_hash));
+
+		assert_noop!(
+			LRP::fulfill_payment(Origin::signed(CHARLIE), payment_hash),
+			Error::<Runtime>::NotPayeeOrProxy
+		);
+
+		assert_ok!(LRP::fulfill_payment(Origin::signed(BOB), payment_hash));
+		assert_eq!(
+			last_event(),
+			Event::LRP(crate::Event::PaymentFulfilled {
+				payment_hash,
+				payer: ALICE,
+				payee: BOB,
+				currency_id: CurrencyId::Native,
+				amount: 100,
+			}),
+		);
+
+		let payment = LRP::payments(payment_hash).unwrap();
+		assert_eq!(payment.status, crate::PaymentStatus::Fulfilled);
+	});
+}
+
+#[test]
+fn add_delivery_proof_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+
+		assert_ok!(LRP::create_payment(
+			Origin::signed(ALICE),
+			BOB,
+			100,
+			CurrencyId::Native,
+			PaymentDescription::Plain("".into()),
+			"".into(),
+			None,
+		));
+		let payment_hash = LRP::payments_owned(&ALICE)[0];
+
+		assert_noop!(
+			LRP::add_delivery_proof(Origin::signed(BOB), payment_hash, "tracking".into()),
+			Error::<Runtime>::InvalidStatusChange
+		);
+
+		assert_ok!(LRP::accept_payment(Origin::signed(BOB), payment_hash));
+
+		assert_noop!(
+			LRP::add_delivery_proof(Origin::signed(ALICE), payment_hash, "tracking".into()),
+			Error::<Runtime>::NotPayeeOrProxy
+		);
+
+		let proofs: Vec<Vec<u8>> =
+			(0..MAX_DELIVERY_PROOFS).map(|i| format!("tracking-{}", i).into()).collect();
+		for proof in &proofs {
+			assert_ok!(LRP::add_delivery_proof(Origin::signed(BOB), payment_hash, proof.clone()));
+		}
+		assert_eq!(
+			last_event(),
+			Event::LRP(crate::Event::DeliveryProofAdded {
+				payment_hash,
+				payee: BOB,
+				proof_hash: <Runtime as system::Config>::Hashing::hash_of(proofs.last().unwrap()),
+			}),
+		);
+
+		let payment = LRP::payments(payment_hash).unwrap();
+		assert_eq!(
+			payment.delivery_proofs.to_vec(),
+			proofs
+				.iter()
+				.map(|proof| <Runtime as system::Config>::Hashing::hash_of(proof))
+				.collect::<Vec<_>>()
+		);
+
+		assert_noop!(
+			LRP::add_delivery_proof(Origin::signed(BOB), payment_hash, "signed delivery".into()),
+			Error::<Runtime>::TooManyDeliveryProofs
+		);
+	});
+}
+
+#[test]
+fn complete_payment_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+
+		assert_ok!(LRP::create_payment(
+			Origin::signed(ALICE),
+			BOB,
+			100,
+			CurrencyId::Native,
+			PaymentDescription::Plain("".into()),
+			"".into(),
+			None,
+		));
+		let payment_hash = LRP::payments_owned(&ALICE)[0];
+
+		assert_ok!(LRP::accept_payment(Origin::signed(BOB), payment_hash));
+		assert_ok!(LRP::fulfill_payment(Origin::signed(BOB), payment_hash));
+
+		assert_noop!(
+			LRP::complete_payment(Origin::signed(CHARLIE), payment_hash),
+			Error::<Runtime>::NotPayer
+		);
+
+		assert_noop!(
+			LRP::complete_payment(Origin::signed(BOB), payment_hash),
+			Error::<Runtime>::NotPayer
+		);
+
+		assert_ok!(LRP::complete_payment(Origin::signed(ALICE), payment_hash));
+		assert_eq!(
+			last_event(),
+			Event::LRP(crate::Event::PaymentCompleted {
+				payment_hash,
+				payer: ALICE,
+				payee: BOB,
+				currency_id: CurrencyId::Native,
+				amount: 100,
+			}),
+		);
+		assert_eq!(Currencies::free_balance(CurrencyId::Native, &LRP::escrow_account()), 0);
 		assert_eq!(Currencies::free_balance(CurrencyId::Native, &ALICE), 900);
 		assert_eq!(Currencies::free_balance(CurrencyId::Native, &BOB), 1100);
 	});
 }
+
+#[test]
+fn payee_confirmation_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		Timestamp::set_timestamp(INIT_TIMESTAMP);
+
+		assert_ok!(LRP::create_payment(
+			Origin::signed(ALICE),
+			BOB,
+			PAYEE_CONFIRMATION_THRESHOLD,
+			CurrencyId::Native,
+			PaymentDescription::Plain("".into()),
+			"".into(),
+			None,
+		));
+		let payment_hash = LRP::payments_owned(&ALICE)[0];
+
+		assert_noop!(
+			LRP::confirm_payee(Origin::signed(BOB), payment_hash),
+			Error::<Runtime>::PaymentNotAccepted
+		);
+
+		Timestamp::set_timestamp(INIT_TIMESTAMP + BLOCK_TIME);
+		assert_ok!(LRP::accept_payment(Origin::signed(BOB), payment_hash));
+		let payment = LRP::payments(payment_hash).unwrap();
+		assert_eq!(
+			payment.payee_confirmation,
+			crate::PayeeConfirmation::Required(
+				INIT_TIMESTAMP + BLOCK_TIME + PAYEE_CONFIRMATION_COOLDOWN
+			)
+		);
+
+		assert_noop!(
+			LRP::fulfill_payment(Origin::signed(BOB), payment_hash),
+			Error::<Runtime>::PayeeNotConfirmed
+		);
+		assert_noop!(
+			LRP::confirm_payee(Origin::signed(BOB), payment_hash),
+			Error::<Runtime>::PayeeConfirmationCooldown
+		);
+
+		Timestamp::set_timestamp(INIT_TIMESTAMP + BLOCK_TIME + PAYEE_CONFIRMATION_COOLDOWN);
+		assert_noop!(
+			LRP::confirm_payee(Origin::signed(CHARLIE), payment_hash),
+			Error::<Runtime>::NotPayee
+		);
+		assert_ok!(LRP::confirm_payee(Origin::signed(BOB), payment_hash));
+		assert_eq!(
+			last_event(),
+			Event::LRP(crate::Event::PayeeConfirmed {
+				payment_hash,
+				payer: ALICE,
+				payee: BOB,
+				currency_id: CurrencyId::Native,
+				amount: PAYEE_CONFIRMATION_THRESHOLD,
+			}),
+		);
+		assert_noop!(
+			LRP::confirm_payee(Origin::signed(BOB), payment_hash),
+			Error::<Runtime>::PayeeConfirmationNotRequired
+		);
+
+		assert_ok!(LRP::fulfill_payment(Origin::signed(BOB), payment_hash));
+		assert_ok!(LRP::complete_payment(Origin::signed(ALICE), payment_hash));
+		assert_eq!(
+			Currencies::free_balance(CurrencyId::Native, &BOB),
+			1000 + PAYEE_CONFIRMATION_THRESHOLD
+		);
+	});
+}
+
+#[test]
+fn auto_complete_payment_by_offchain_worker_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+
+		assert_ok!(LRP::create_payment(
+			Origin::signed(ALICE),
+			BOB,
+			100,
+			CurrencyId::Native,
+			PaymentDescription::Plain("".into()),
+			"".into(),
+			None,
+		));
+		let payment_hash = LRP::payments_owned(&ALICE)[0];
+	
+		assert_ok!(LRP::accept_payment(Origin::signed(BOB), payment_hash));
+		assert_ok!(LRP::fulfill_payment(Origin::signed(BOB), payment_hash));
+
+		run_to_block_number((FULFILLED_WAITING_TIME / BLOCK_TIME).into());
+
+		let payment_hash = LRP::payments_owned(&ALICE)[0];
+		let payment = LRP::payments(payment_hash).unwrap();
+
+		assert_eq!(payment.status, PaymentStatus::Completed);
+		assert_eq!(
+			last_event(),
+			Event::LRP(crate::Event::PaymentCompleted {
+				payment_hash,
+				payer: ALICE,
+				payee: BOB,
+				currency_id: CurrencyId::Native,
+				amount: 100,
+			}),
+		);
+		assert_eq!(Currencies::free_balance(CurrencyId::Native, &LRP::escrow_account()), 0);
+		assert_eq!(Currencies::free_balance(CurrencyId::Native, &ALICE), 900);
+		assert_eq!(Currencies::free_balance(CurrencyId::Native, &BOB), 1100);
+	});
+}
+
+#[test]
+fn partially_fulfill_payment_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+
+		for _ in 0..3 {
+			assert_ok!(LRP::create_payment(
+				Origin::signed(ALICE),
+				BOB,
+				100,
+				CurrencyId::Native,
+				PaymentDescription::Plain("".into()),
+				"".into(),
+				None,
+			));
+		}
+		let payment_hashes = LRP::payments_owned(&ALICE);
+
+		assert_noop!(
+			LRP::partially_fulfill_payment(
+				Origin::signed(BOB),
+				payment_hashes[0],
+				Percent::from_percent(60)
+			),
+			Error::<Runtime>::PaymentNotFulfillable
+		);
+
+		for payment_hash in &payment_hashes {
+			assert_ok!(LRP::accept_payment(Origin::signed(BOB), *payment_hash));
+		}
+
+		assert_noop!(
+			LRP::partially_fulfill_payment(
+				Origin::signed(ALICE),
+				payment_hashes[0],
+				Percent::from_percent(60)
+			),
+			Error::<Runtime>::NotPayeeOrProxy
+		);
+		for fulfilled in [Percent::zero(), Percent::one()] {
+			assert_noop!(
+				LRP::partially_fulfill_payment(Origin::signed(BOB), payment_hashes[0], fulfilled),
+				Error::<Runtime>::InvalidFulfilledShare
+			);
+		}
+
+		// 3 of 5 items are shipped.
+		assert_ok!(LRP::partially_fulfill_payment(
+			Origin::signed(BOB),
+			payment_hashes[0],
+			Percent::from_percent(60)
+		));
+		assert_eq!(
+			LRP::payments(payment_hashes[0]).unwrap().status,
+			PaymentStatus::PartiallyFulfilled(Percent::from_percent(60))
+		);
+		assert_eq!(
+			last_event(),
+			Event::LRP(crate::Event::PaymentPartiallyFulfilled {
+				payment_hash: payment_hashes[0],
+				payer: ALICE,
+				payee: BOB,
+				currency_id: CurrencyId::Native,
+				amount: 100,
+				fulfilled: Percent::from_percent(60),
+			}),
+		);
+
+		// The share can only be raised.
+		assert_noop!(
+			LRP::partially_fulfill_payment(
+				Origin::signed(BOB),
+				payment_hashes[0],
+				Percent::from_percent(40)
+			),
+			Error::<Runtime>::InvalidFulfilledShare
+		);
+
+		// The payer completes the payment with the delivered share only.
+		assert_ok!(LRP::complete_payment(Origin::signed(ALICE), payment_hashes[0]));
+		assert_eq!(LRP::payments(payment_hashes[0]).unwrap().status, PaymentStatus::Completed);
+		assert_eq!(
+			last_event(),
+			Event::LRP(crate::Event::PaymentCompleted {
+				payment_hash: payment_hashes[0],
+				payer: ALICE,
+				payee: BOB,
+				currency_id: CurrencyId::Native,
+				amount: 60,
+			}),
+		);
+		assert_eq!(Currencies::free_balance(CurrencyId::Native, &LRP::escrow_account()), 200);
+		assert_eq!(Currencies::free_balance(CurrencyId::Native, &ALICE), 740);
+		assert_eq!(Currencies::free_balance(CurrencyId::Native, &BOB), 1060);
+
+		// The rest ships later and the whole payment is fulfilled.
+		assert_ok!(LRP::partially_fulfill_payment(
+			Origin::signed(BOB),
+			payment_hashes[1],
+			Percent::from_percent(60)
+		));
+		assert_ok!(LRP::fulfill_payment(Origin::signed(BOB), payment_hashes[1]));
+		assert_eq!(LRP::payments(payment_hashes[1]).unwrap().status, PaymentStatus::Fulfilled);
+
+		// The payer can still dispute the split shipment.
+		assert_ok!(LRP::partially_fulfill_payment(
+			Origin::signed(BOB),
+			payment_hashes[2],
+			Percent::from_percent(60)
+		));
+		assert!(LRP::can_dispute(&payment_hashes[2]));
+		assert_ok!(LRP::dispute_payment(Origin::signed(ALICE), payment_hashes[2]));
+		assert_eq!(LRP::payments(payment_hashes[2]).unwrap().status, PaymentStatus::Disputed);
+	});
+}
+
+#[test]
+fn auto_complete_partially_fulfilled_payment_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+
+		assert_ok!(LRP::create_payment(
+			Origin::signed(ALICE),
+			BOB,
+			100,
+			CurrencyId::Native,
+			PaymentDescription::Plain("".into()),
+			"".into(),
+			None,
+		));
+		let payment_hash = LRP::payments_owned(&ALICE)[0];
+
+		assert_ok!(LRP::accept_payment(Origin::signed(BOB), payment_hash));
+		assert_ok!(LRP::partially_fulfill_payment(
+			Origin::signed(BOB),
+			payment_hash,
+			Percent::from_percent(60)
+		));
+
+		run_to_block_number((FULFILLED_WAITING_TIME / BLOCK_TIME).into());
+
+		assert_eq!(LRP::payments(payment_hash).unwrap().status, PaymentStatus::Completed);
+		assert_eq!(
+			last_event(),
+			Event::LRP(crate::Event::PaymentCompleted {
+				payment_hash,
+				payer: ALICE,
+				payee: BOB,
+				currency_id: CurrencyId::Native,
+				amount: 60,
+			}),
+		);
+		// The rest is refunded to the payer.
+		assert_eq!(Currencies::free_balance(CurrencyId::Native, &LRP::escrow_account()), 0);
+		assert_eq!(Currencies::free_balance(CurrencyId::Native, &ALICE), 940);
+		assert_eq!(Currencies::free_balance(CurrencyId::Native, &BOB), 1060);
+	});
+}
+
+#[test]
+fn velocity_hold_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+
+		let hold = FULFILLED_WAITING_TIME + 10 * BLOCK_TIME;
+		VelocityPolicy::set(Some(crate::VelocityPolicy {
+			window: FULFILLED_WAITING_TIME,
+			fulfillments: 2,
+			disputes: 1,
+			hold,
+		}));
+
+		for _ in 0..3 {
+			assert_ok!(LRP::create_payment(
+				Origin::signed(ALICE),
+				BOB,
+				100,
+				CurrencyId::Native,
+				PaymentDescription::Plain("".into()),
+				"".into(),
+				None,
+			));
+		}
+		let payment_hashes = LRP::payments_owned(&ALICE);
+		for payment_hash in payment_hashes.iter() {
+			assert_ok!(LRP::accept_payment(Origin::signed(BOB), *payment_hash));
+			assert_ok!(LRP::fulfill_payment(Origin::signed(BOB), *payment_hash));
+		}
+		assert_eq!(LRP::payee_velocities(BOB).fulfillments, 3);
+		assert_eq!(LRP::payee_velocities(BOB).held_until, 0);
+
+		// The burst of fulfillments followed by a dispute puts the payee on hold.
+		assert_ok!(LRP::dispute_payment(Origin::signed(ALICE), payment_hashes[0]));
+		assert_eq!(
+			last_event(),
+			Event::LRP(crate::Event::PayeeHeld { payee: BOB, held_until: hold }),
+		);
+
+		run_to_block_number((FULFILLED_WAITING_TIME / BLOCK_TIME).into());
+
+		for payment_hash in payment_hashes[1..].iter() {
+			assert_eq!(LRP::payments(payment_hash).unwrap().status, PaymentStatus::Fulfilled);
+			assert_eq!(LRP::payment_holds(payment_hash), Some(hold));
+			assert!(System::events().iter().any(|record| record.event ==
+				Event::LRP(crate::Event::PaymentCompletionPostponed {
+					payment_hash: *payment_hash,
+					completed_at: hold,
+				})));
+		}
+
+		run_to_block_number((hold / BLOCK_TIME + 1).into());
+
+		for payment_hash in payment_hashes[1..].iter() {
+			assert_eq!(LRP::payments(payment_hash).unwrap().status, PaymentStatus::Completed);
+			assert_eq!(LRP::payment_holds(payment_hash), None);
+		}
+	});
+}
+
+#[test]
+fn payment_fee_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+
+		NativePaymentFee::set(Some(FeeSchedule { flat: 5, percentage: Permill::from_percent(10) }));
+		assert_eq!(LRP::compute_payment_fee(CurrencyId::Native, 100), 15);
+		// The fee is capped by the amount.
+		assert_eq!(LRP::compute_payment_fee(CurrencyId::Native, 3), 3);
+
+		// The fee accounts are above the existential deposit.
+		assert_ok!(Currencies::deposit(CurrencyId::Native, &TREASURY, 1_000));
+		assert_ok!(Currencies::deposit(CurrencyId::Native, &CHARLIE, 1_000));
+
+		assert_ok!(LRP::create_payment(
+			Origin::signed(ALICE),
+			BOB,
+			100,
+			CurrencyId::Native,
+			PaymentDescription::Plain("".into()),
+			"".into(),
+			None,
+		));
+		assert_ok!(LRP::create_marketplace_payment(
+			Origin::signed(ALICE),
+			BOB,
+			200,
+			CurrencyId::Native,
+			PaymentDescription::Plain("".into()),
+			"".into(),
+			CHARLIE,
+			None,
+		));
+		let payment_hashes = LRP::payments_owned(&ALICE);
+		assert_eq!(LRP::payment_fee_accounts(payment_hashes[0]), None);
+		assert_eq!(LRP::payment_fee_accounts(payment_hashes[1]), Some(CHARLIE));
+
+		// The fee of the payments without a marketplace goes to the treasury.
+		assert_ok!(LRP::accept_payment(Origin::signed(BOB), payment_hashes[0]));
+		assert_ok!(LRP::fulfill_payment(Origin::signed(BOB), payment_hashes[0]));
+		assert_ok!(LRP::complete_payment(Origin::signed(ALICE), payment_hashes[0]));
+		assert!(System::events().iter().any(|record| record.event ==
+			Event::LRP(crate::Event::PaymentFeeCharged {
+				payment_hash: payment_hashes[0],
+				fee_account: TREASURY,
+				currency_id: CurrencyId::Native,
+				fee: 15,
+			})));
+		assert_eq!(
+			last_event(),
+			Event::LRP(crate::Event::PaymentCompleted {
+				payment_hash: payment_hashes[0],
+				payer: ALICE,
+				payee: BOB,
+				currency_id: CurrencyId::Native,
+				amount: 85,
+			}),
+		);
+		assert_eq!(Currencies::free_balance(CurrencyId::Native, &BOB), 1085);
+		assert_eq!(Currencies::free_balance(CurrencyId::Native, &TREASURY), 1015);
+
+		// The fee is charged on the fulfilled share only and goes to the marketplace.
+		assert_ok!(LRP::accept_payment(Origin::signed(BOB), payment_hashes[1]));
+		assert_ok!(LRP::partially_fulfill_payment(
+			Origin::signed(BOB),
+			payment_hashes[1],
+			Percent::from_percent(50)
+		));
+		assert_ok!(LRP::complete_payment(Origin::signed(ALICE), payment_hashes[1]));
+		assert!(System::events().iter().any(|record| record.event ==
+			Event::LRP(crate::Event::PaymentFeeCharged {
+				payment_hash: payment_hashes[1],
+				fee_account: CHARLIE,
+				currency_id: CurrencyId::Native,
+				fee: 15,
+			})));
+		assert_eq!(Currencies::free_balance(CurrencyId::Native, &BOB), 1170);
+		assert_eq!(Currencies::free_balance(CurrencyId::Native, &CHARLIE), 1015);
+		assert_eq!(Currencies::free_balance(CurrencyId::Native, &TREASURY), 1015);
+		assert_eq!(Currencies::free_balance(CurrencyId::Native, &LRP::escrow_account()), 0);
+		assert_eq!(Currencies::free_balance(CurrencyId::Native, &ALICE), 800);
+	});
+}
+
+#[test]
+fn referral_program_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+
+		NativePaymentFee::set(Some(FeeSchedule { flat: 5, percentage: Permill::from_percent(10) }));
+		assert_ok!(Currencies::deposit(CurrencyId::Native, &TREASURY, 1_000));
+		assert_ok!(Currencies::deposit(CurrencyId::Native, &CHARLIE, 1_000));
+
+		assert_noop!(
+			LRP::create_payment(
+				Origin::signed(ALICE),
+				BOB,
+				100,
+				CurrencyId::Native,
+				PaymentDescription::Plain("".into()),
+				"".into(),
+				Some(CHARLIE)
+			),
+			Error::<Runtime>::ReferralProgramNotFound
+		);
+
+		assert_noop!(
+			LRP::set_referral_program(
+				Origin::signed(BOB),
+				Some(ReferralProgram { fee_share: Permill::from_percent(60) })
+			),
+			Error::<Runtime>::InvalidReferralProgram
+		);
+		let program = ReferralProgram { fee_share: Permill::from_percent(20) };
+		assert_ok!(LRP::set_referral_program(Origin::signed(BOB), Some(program.clone())));
+		assert_eq!(LRP::referral_programs(BOB), Some(program.clone()));
+		assert_eq!(
+			last_event(),
+			Event::LRP(crate::Event::ReferralProgramUpdated {
+				merchant: BOB,
+				program: Some(program),
+			}),
+		);
+
+		for referrer in [ALICE, BOB] {
+			assert_noop!(
+				LRP::create_payment(
+					Origin::signed(ALICE),
+					BOB,
+					100,
+					CurrencyId::Native,
+					PaymentDescription::Plain("".into()),
+					"".into(),
+					Some(referrer)
+				),
+				Error::<Runtime>::InvalidReferrer
+			);
+		}
+
+		assert_ok!(LRP::create_payment(
+			Origin::signed(ALICE),
+			BOB,
+			100,
+			CurrencyId::Native,
+			PaymentDescription::Plain("".into()),
+			"".into(),
+			Some(CHARLIE),
+		));
+		let payment_hash = LRP::payments_owned(&ALICE)[0];
+		assert_eq!(
+			LRP::payment_referrers(payment_hash),
+			Some((CHARLIE, Permill::from_percent(20)))
+		);
+
+		// The share is fixed at the creation.
+		assert_ok!(LRP::set_referral_program(Origin::signed(BOB), None));
+		assert_eq!(LRP::referral_programs(BOB), None);
+
+		assert_ok!(LRP::accept_payment(Origin::signed(BOB), payment_hash));
+		assert_ok!(LRP::fulfill_payment(Origin::signed(BOB), payment_hash));
+		assert_ok!(LRP::complete_payment(Origin::signed(ALICE), payment_hash));
+		assert!(System::events().iter().any(|record| record.event ==
+			Event::LRP(crate::Event::ReferralRewarded {
+				payment_hash,
+				referrer: CHARLIE,
+				currency_id: CurrencyId::Native,
+				reward: 3,
+			})));
+		assert!(System::events().iter().any(|record| record.event ==
+			Event::LRP(crate::Event::PaymentFeeCharged {
+				payment_hash,
+				fee_account: TREASURY,
+				currency_id: CurrencyId::Native,
+				fee: 12,
+			})));
+		assert_eq!(Currencies::free_balance(CurrencyId::Native, &BOB), 1085);
+		assert_eq!(Currencies::free_balance(CurrencyId::Native, &CHARLIE), 1003);
+		assert_eq!(Currencies::free_balance(CurrencyId::Native, &TREASURY), 1012);
+		assert_eq!(Currencies::free_balance(CurrencyId::Native, &ALICE), 900);
+	});
+}
+
+#[test]
+fn payout_routes_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+
+		let routes = [(BOB, Permill::from_percent(70)), (CHARLIE, Permill::from_percent(30))];
+		assert_noop!(
+			LRP::set_payout_routes(Origin::signed(BOB), routes.to_vec()),
+			Error::<Runtime>::NotAnOrganization
+		);
+
+		assert_ok!(Identities::create_identity(
+			Origin::signed(BOB),
+			"Bob Inc.".into(),
+			IdentityType::Organization,
+			[].into(),
+		));
+		assert_noop!(
+			LRP::set_payout_routes(Origin::signed(BOB), routes.to_vec()),
+			Error::<Runtime>::PayoutAccountNotMember
+		);
+
+		assert_ok!(Identities::create_identity(
+			Origin::signed(CHARLIE),
+			"Charlie".into(),
+			IdentityType::Individual,
+			[].into(),
+		));
+		assert_ok!(Identities::invite_member(Origin::signed(BOB), CHARLIE));
+		assert_ok!(Identities::accept_membership(Origin::signed(CHARLIE), BOB));
+		assert_noop!(
+			LRP::set_payout_routes(
+				Origin::signed(BOB),
+				[(BOB, Permill::from_percent(70)), (CHARLIE, Permill::from_percent(20))].to_vec()
+			),
+			Error::<Runtime>::InvalidPayoutRoutes
+		);
+		assert_noop!(
+			LRP::set_payout_routes(
+				Origin::signed(BOB),
+				[(BOB, Permill::from_percent(50)), (BOB, Permill::from_percent(50))].to_vec()
+			),
+			Error::<Runtime>::InvalidPayoutRoutes
+		);
+		assert_noop!(
+			LRP::set_payout_routes(
+				Origin::signed(BOB),
+				[(BOB, Permill::from_percent(25)); MAX_PAYOUT_ROUTES as usize + 1].to_vec()
+			),
+			Error::<Runtime>::TooManyPayoutRoutes
+		);
+
+		assert_ok!(LRP::set_payout_routes(Origin::signed(BOB), routes.to_vec()));
+		assert_eq!(LRP::payout_routes(BOB).into_inner(), routes.to_vec());
+		assert_eq!(
+			last_event(),
+			Event::LRP(crate::Event::PayoutRoutesUpdated {
+				merchant: BOB,
+				routes: routes.to_vec(),
+			}),
+		);
+
+		assert_ok!(LRP::create_payment(
+			Origin::signed(ALICE),
+			BOB,
+			101,
+			CurrencyId::Native,
+			PaymentDescription::Plain("".into()),
+			"".into(),
+			None,
+		));
+		let payment_hash = LRP::payments_owned(&ALICE)[0];
+		assert_ok!(LRP::accept_payment(Origin::signed(BOB), payment_hash));
+		assert_ok!(LRP::fulfill_payment(Origin::signed(BOB), payment_hash));
+		assert_ok!(LRP::complete_payment(Origin::signed(ALICE), payment_hash));
+
+		// The last route gets the rounding remainder.
+		assert!(System::events().iter().any(|record| record.event ==
+			Event::LRP(crate::Event::PayoutRouted {
+				payment_hash,
+				payee: BOB,
+				currency_id: CurrencyId::Native,
+				payouts: [(BOB, 70), (CHARLIE, 31)].to_vec(),
+			})));
+		assert_eq!(Currencies::free_balance(CurrencyId::Native, &BOB), 1070);
+		assert_eq!(Currencies::free_balance(CurrencyId::Native, &CHARLIE), 31);
+
+		// Without routes the payouts go to the organization account.
+		assert_ok!(LRP::set_payout_routes(Origin::signed(BOB), [].into()));
+		assert!(LRP::payout_routes(BOB).is_empty());
+	});
+}
+
+#[test]
+fn payment_receipt_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		Timestamp::set_timestamp(INIT_TIMESTAMP);
+
+		for _ in 0..2 {
+			assert_ok!(LRP::create_payment(
+				Origin::signed(ALICE),
+				BOB,
+				100,
+				CurrencyId::Native,
+				PaymentDescription::Plain("".into()),
+				"Receipt".into(),
+				None,
+			));
+		}
+		let payment_hashes = LRP::payments_owned(&ALICE);
+
+		assert_noop!(
+			LRP::request_payment_receipt(Origin::signed(CHARLIE), payment_hashes[0]),
+			Error::<Runtime>::NotPaymentParty
+		);
+		assert_ok!(LRP::request_payment_receipt(Origin::signed(BOB), payment_hashes[0]));
+		assert_eq!(LRP::payment_receipt_requests(payment_hashes[0]), Some(BOB));
+		assert_eq!(
+			last_event(),
+			Event::LRP(crate::Event::PaymentReceiptRequested {
+				payment_hash: payment_hashes[0],
+				who: BOB,
+			}),
+		);
+		assert_noop!(
+			LRP::request_payment_receipt(Origin::signed(ALICE), payment_hashes[0]),
+			Error::<Runtime>::PaymentReceiptAlreadyRequested
+		);
+
+		for payment_hash in &payment_hashes {
+			assert_ok!(LRP::accept_payment(Origin::signed(BOB), *payment_hash));
+		}
+		assert_ok!(LRP::partially_fulfill_payment(
+			Origin::signed(BOB),
+			payment_hashes[0],
+			Percent::from_percent(60)
+		));
+		assert_ok!(LRP::fulfill_payment(Origin::signed(BOB), payment_hashes[1]));
+
+		Timestamp::set_timestamp(INIT_TIMESTAMP + BLOCK_TIME);
+		assert_ok!(LRP::complete_payment(Origin::signed(ALICE), payment_hashes[0]));
+		assert_eq!(
+			last_event(),
+			Event::LRP(crate::Event::PaymentReceiptMinted {
+				payment_hash: payment_hashes[0],
+				payer: ALICE,
+				payee: BOB,
+			}),
+		);
+		assert_eq!(
+			LRP::payment_receipts(payment_hashes[0]),
+			Some(PaymentReceipt {
+				payer: ALICE,
+				payee: BOB,
+				currency_id: CurrencyId::Native,
+				amount: 60,
+				receipt_hash: <Runtime as system::Config>::Hashing::hash_of(&"Receipt".as_bytes()),
+				category: None,
+				completed_at: INIT_TIMESTAMP + BLOCK_TIME,
+			})
+		);
+		assert_eq!(LRP::payment_receipt_requests(payment_hashes[0]), None);
+		assert!(LRP::has_payment_receipt(&ALICE, &payment_hashes[0]));
+		assert!(LRP::has_payment_receipt(&BOB, &payment_hashes[0]));
+		assert!(!LRP::has_payment_receipt(&CHARLIE, &payment_hashes[0]));
+
+		// No receipt is minted unless it was requested, and none can be requested once completed.
+		assert_ok!(LRP::complete_payment(Origin::signed(ALICE), payment_hashes[1]));
+		assert_eq!(LRP::payment_receipts(payment_hashes[1]), None);
+		assert!(!LRP::has_payment_receipt(&ALICE, &payment_hashes[1]));
+		assert_noop!(
+			LRP::request_payment_receipt(Origin::signed(ALICE), payment_hashes[1]),
+			Error::<Runtime>::InvalidStatusChange
+		);
+	});
+}
+
+#[test]
+fn account_statement_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+
+		for amount in [100, 200] {
+			assert_ok!(LRP::create_payment(
+				Origin::signed(ALICE),
+				BOB,
+				amount,
+				CurrencyId::Native,
+				PaymentDescription::Plain("".into()),
+				"".into(),
+				None,
+			));
+		}
+		let payment_hashes = LRP::payments_owned(&ALICE);
+		assert_ok!(LRP::accept_payment(Origin::signed(BOB), payment_hashes[0]));
+		assert_ok!(LRP::fulfill_payment(Origin::signed(BOB), payment_hashes[0]));
+
+		System::set_block_number(STATEMENT_ERA_DURATION + 2);
+		assert_ok!(LRP::complete_payment(Origin::signed(ALICE), payment_hashes[0]));
+
+		System::set_block_number(2 * STATEMENT_ERA_DURATION + 5);
+		assert_ok!(LRP::cancel_payment(Origin::signed(ALICE), payment_hashes[1]));
+
+		assert_eq!(
+			LRP::account_flows((ALICE, CurrencyId::Native), 0),
+			PaymentFlows { inflow: 0, outflow: 300 }
+		);
+		assert_eq!(
+			LRP::account_statement(ALICE, CurrencyId::Native, 0, 3 * STATEMENT_ERA_DURATION),
+			PaymentFlows { inflow: 200, outflow: 300 }
+		);
+		assert_eq!(
+			LRP::account_statement(BOB, CurrencyId::Native, 0, STATEMENT_ERA_DURATION - 1),
+			PaymentFlows { inflow: 0, outflow: 0 }
+		);
+
+		// The range is widened to the eras of its blocks.
+		assert_eq!(
+			LRP::account_statement(BOB, CurrencyId::Native, 15, 15),
+			PaymentFlows { inflow: 100, outflow: 0 }
+		);
+		assert_eq!(
+			LRP::account_statement(ALICE, CurrencyId::Native, 1, 2 * STATEMENT_ERA_DURATION),
+			PaymentFlows { inflow: 200, outflow: 300 }
+		);
+		assert_eq!(
+			LRP::account_statement(ALICE, CurrencyId::Native, 1, STATEMENT_ERA_DURATION),
+			PaymentFlows { inflow: 0, outflow: 300 }
+		);
+	});
+}
+
+#[test]
+fn inheritance_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		Timestamp::set_timestamp(INIT_TIMESTAMP);
+
+		assert_noop!(
+			LRP::set_beneficiary(Origin::signed(BOB), Some(BOB)),
+			Error::<Runtime>::InvalidBeneficiary
+		);
+		assert_ok!(LRP::set_beneficiary(Origin::signed(BOB), Some(CHARLIE)));
+		assert_eq!(
+			last_event(),
+			Event::LRP(crate::Event::BeneficiaryUpdated {
+				merchant: BOB,
+				beneficiary: Some(CHARLIE)
+			}),
+		);
+
+		assert_ok!(LRP::create_payment(
+			Origin::signed(ALICE),
+			BOB,
+			100,
+			CurrencyId::Native,
+			PaymentDescription::Plain("".into()),
+			"".into(),
+			None,
+		));
+		let payment_hash = LRP::payments_owned(&ALICE)[0];
+		assert_ok!(LRP::accept_payment(Origin::signed(BOB), payment_hash));
+		assert_ok!(LRP::fulfill_payment(Origin::signed(BOB), payment_hash));
+
+		assert_noop!(
+			LRP::claim_inheritance(Origin::signed(ALICE), BOB),
+			Error::<Runtime>::NotBeneficiary
+		);
+		assert_noop!(
+			LRP::claim_inheritance(Origin::signed(CHARLIE), BOB),
+			Error::<Runtime>::MerchantNotInactive
+		);
+
+		// A merchant who sent a transaction is not inactive, the period starts again.
+		let mut now = INIT_TIMESTAMP + INHERITANCE_INACTIVITY_PERIOD;
+		Timestamp::set_timestamp(now);
+		System::inc_account_nonce(&BOB);
+		assert_ok!(LRP::claim_inheritance(Origin::signed(CHARLIE), BOB));
+		assert_eq!(
+			last_event(),
+			Event::LRP(crate::Event::MerchantActivityObserved { merchant: BOB, active_at: now }),
+		);
+		assert_noop!(
+			LRP::claim_inheritance(Origin::signed(CHARLIE), BOB),
+			Error::<Runtime>::MerchantNotInactive
+		);
+
+		// The merchant shows its activity within the challenge window.
+		now += INHERITANCE_INACTIVITY_PERIOD;
+		Timestamp::set_timestamp(now);
+		assert_ok!(LRP::claim_inheritance(Origin::signed(CHARLIE), BOB));
+		now += INHERITANCE_CHALLENGE_WINDOW;
+		Timestamp::set_timestamp(now);
+		System::inc_account_nonce(&BOB);
+		assert_noop!(
+			LRP::execute_inheritance(Origin::signed(CHARLIE), BOB),
+			Error::<Runtime>::MerchantActive
+		);
+		assert_ok!(LRP::claim_inheritance(Origin::signed(CHARLIE), BOB));
+		assert_eq!(LRP::beneficiaries(BOB).unwrap().claimed_at, None);
+
+		now += INHERITANCE_INACTIVITY_PERIOD;
+		Timestamp::set_timestamp(now);
+		assert_noop!(
+			LRP::execute_inheritance(Origin::signed(CHARLIE), BOB),
+			Error::<Runtime>::InheritanceNotClaimed
+		);
+		assert_ok!(LRP::claim_inheritance(Origin::signed(CHARLIE), BOB));
+		assert_eq!(
+			last_event(),
+			Event::LRP(crate::Event::InheritanceClaimed {
+				merchant: BOB,
+				beneficiary: CHARLIE,
+				executable_at: now + INHERITANCE_CHALLENGE_WINDOW,
+			}),
+		);
+		assert_noop!(
+			LRP::claim_inheritance(Origin::signed(CHARLIE), BOB),
+			Error::<Runtime>::InheritanceAlreadyClaimed
+		);
+		assert_noop!(
+			LRP::execute_inheritance(Origin::signed(CHARLIE), BOB),
+			Error::<Runtime>::ChallengeWindowNotOver
+		);
+
+		Timestamp::set_timestamp(now + INHERITANCE_CHALLENGE_WINDOW);
+		assert_ok!(LRP::execute_inheritance(Origin::signed(CHARLIE), BOB));
+		assert_eq!(
+			last_event(),
+			Event::LRP(crate::Event::InheritanceExecuted { merchant: BOB, heir: CHARLIE }),
+		);
+		assert_eq!(LRP::beneficiaries(BOB), None);
+		assert_eq!(LRP::heirs(BOB), Some(CHARLIE));
+		assert!(LRP::merchant_proxies(BOB, CHARLIE)
+			.unwrap()
+			.contains(&MerchantOperation::FulfillPayment));
+
+		// The payouts of the merchant go to the heir.
+		assert_ok!(LRP::complete_payment(Origin::signed(ALICE), payment_hash));
+		assert_eq!(Currencies::free_balance(CurrencyId::Native, &CHARLIE), 100);
+		assert_eq!(Currencies::free_balance(CurrencyId::Native, &BOB), 1_000);
+
+		// The refunds of the merchant as a payer are not payouts, they are kept by the merchant.
+		assert_ok!(LRP::create_payment(
+			Origin::signed(BOB),
+			ALICE,
+			100,
+			CurrencyId::Native,
+			PaymentDescription::Plain("".into()),
+			"".into(),
+			None,
+		));
+		let refunded_hash = LRP::payments_owned(&BOB)[0];
+		assert_ok!(LRP::cancel_payment(Origin::signed(BOB), refunded_hash));
+		assert_eq!(Currencies::free_balance(CurrencyId::Native, &CHARLIE), 100);
+		assert_eq!(Currencies::free_balance(CurrencyId::Native, &BOB), 1_000);
+
+		// The merchant takes the payouts and the operations back by designating a beneficiary
+		// again.
+		assert_ok!(LRP::set_beneficiary(Origin::signed(BOB), None));
+		assert_eq!(LRP::heirs(BOB), None);
+		assert_eq!(LRP::merchant_proxies(BOB, CHARLIE), None);
+	});
+}
+
+#[test]
+fn full_queue_bucket_spills_over() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		Timestamp::set_timestamp(INIT_TIMESTAMP);
+		MaxPaymentsPerBucket::set(1);
+
+		for amount in [100, 200] {
+			assert_ok!(LRP::create_payment(
+				Origin::signed(ALICE),
+				BOB,
+				amount,
+				CurrencyId::Native,
+				PaymentDescription::Plain("".into()),
+				"".into(),
+				None,
+			));
+		}
+		let payment_hashes = LRP::payments_owned(&ALICE);
+
+		// The full bucket does not stop the payment, it is queued in the next bucket.
+		let pending_bucket = queue_bucket_of(INIT_TIMESTAMP + PENDING_PAYMENT_WAITING_TIME);
+		assert_eq!(
+			PendingPaymentsQueue::<Runtime>::get(pending_bucket).to_vec(),
+			vec![payment_hashes[0]]
+		);
+		assert_eq!(
+			PendingPaymentsQueue::<Runtime>::get(pending_bucket + 1).to_vec(),
+			vec![payment_hashes[1]]
+		);
+		assert_eq!(
+			SpilledPaymentBuckets::<Runtime>::get(payment_hashes[1]),
+			Some(pending_bucket + 1)
+		);
+
+		// The spilled payment is taken out of the bucket it was queued in.
+		assert_ok!(LRP::accept_payment(Origin::signed(BOB), payment_hashes[1]));
+		assert!(!PendingPaymentsQueue::<Runtime>::contains_key(pending_bucket + 1));
+		assert_eq!(SpilledPaymentBuckets::<Runtime>::get(payment_hashes[1]), None);
+
+		Timestamp::set_timestamp(INIT_TIMESTAMP + PENDING_PAYMENT_WAITING_TIME);
+		assert_ok!(LRP::expire_payment(Origin::signed(CHARLIE), payment_hashes[0]));
+		assert_eq!(LRP::payments(payment_hashes[0]).unwrap().status, PaymentStatus::Expired);
+		assert!(!PendingPaymentsQueue::<Runtime>::contains_key(pending_bucket));
+	});
+}
+
+#[test]
+fn payment_queues_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		Timestamp::set_timestamp(INIT_TIMESTAMP);
+
+		assert_ok!(LRP::create_payment(
+			Origin::signed(ALICE),
+			BOB,
+			100,
+			CurrencyId::Native,
+			PaymentDescription::Plain("".into()),
+			"".into(),
+			None,
+		));
+		let payment_hash = LRP::payments_owned(&ALICE)[0];
+
+		// The payment is queued in the bucket of its expiry time.
+		let pending_bucket = queue_bucket_of(INIT_TIMESTAMP + PENDING_PAYMENT_WAITING_TIME);
+		assert_eq!(
+			PendingPaymentsQueue::<Runtime>::get(pending_bucket).to_vec(),
+			vec![payment_hash]
+		);
+
+		assert_ok!(LRP::accept_payment(Origin::signed(BOB), payment_hash));
+		assert!(!PendingPaymentsQueue::<Runtime>::contains_key(pending_bucket));
+
+		assert_ok!(LRP::fulfill_payment(Origin::signed(BOB), payment_hash));
+
+		// The payment is queued in the bucket of its auto-complete time.
+		let fulfilled_bucket = queue_bucket_of(INIT_TIMESTAMP + FULFILLED_WAITING_TIME);
+		assert_eq!(
+			FulfilledPaymentsQueue::<Runtime>::get(fulfilled_bucket).to_vec(),
+			vec![payment_hash]
+		);
+
+		assert_ok!(LRP::complete_payment(Origin::signed(ALICE), payment_hash));
+		assert!(!FulfilledPaymentsQueue::<Runtime>::contains_key(fulfilled_bucket));
+	});
+}
+
+#[test]
+fn acceptance_policy_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+
+		let policy = AcceptancePolicy {
+			min_amount: Some(10),
+			max_amount: Some(500),
+			currencies: [].into(),
+			min_payer_credibility: Some(INITIAL_CREDIBILITY),
+			auto_accept: false,
+		};
+		assert_noop!(
+			LRP::set_acceptance_policy(
+				Origin::signed(BOB),
+				Some(AcceptancePolicy { min_amount: Some(501), ..policy.clone() })
+			),
+			Error::<Runtime>::InvalidPolicy
+		);
+		assert_noop!(
+			LRP::set_acceptance_policy(
+				Origin::signed(BOB),
+				Some(AcceptancePolicy {
+					currencies: (0..=MAX_POLICY_CURRENCIES).map(CurrencyId::ForeignAsset).collect(),
+					..policy.clone()
+				})
+			),
+			Error::<Runtime>::TooManyPolicyCurrencies
+		);
+
+		assert_ok!(LRP::set_acceptance_policy(Origin::signed(BOB), Some(policy.clone())));
+		assert_eq!(
+			last_event(),
+			Event::LRP(crate::Event::AcceptancePolicyUpdated {
+				merchant: BOB,
+				policy: Some(policy.clone()),
+			}),
+		);
+
+		// The payments out of the policy cannot be created.
+		for amount in [9, 501] {
+			assert_noop!(
+				LRP::create_payment(
+					Origin::signed(ALICE),
+					BOB,
+					amount,
+					CurrencyId::Native,
+					PaymentDescription::Plain("".into()),
+					"".into(),
+					None
+				),
+				Error::<Runtime>::AmountOutOfPolicy
+			);
+		}
+		assert_noop!(
+			LRP::create_payment(
+				Origin::signed(ALICE),
+				BOB,
+				100,
+				CurrencyId::Native,
+				PaymentDescription::Plain("".into()),
+				"".into(),
+				None
+			),
+			Error::<Runtime>::IdentityRequired
+		);
+
+		assert_ok!(Identities::create_identity(
+			Origin::signed(ALICE),
+			"Alice".into(),
+			IdentityType::Individual,
+			[].into(),
+		));
+		assert_ok!(LRP::create_payment(
+			Origin::signed(ALICE),
+			BOB,
+			100,
+			CurrencyId::Native,
+			PaymentDescription::Plain("".into()),
+			"".into(),
+			None,
+		));
+		let payment_hash = LRP::payments_owned(&ALICE)[0];
+		assert_eq!(LRP::payments(payment_hash).unwrap().status, PaymentStatus::Pending);
+
+		// The policy is evaluated again on the acceptance.
+		let policy =
+			AcceptancePolicy { currencies: [CurrencyId::ForeignAsset(1)].into(), ..policy };
+		assert_ok!(LRP::set_acceptance_policy(Origin::signed(BOB), Some(policy)));
+		assert_noop!(
+			LRP::accept_payment(Origin::signed(BOB), payment_hash),
+			Error::<Runtime>::UnacceptedCurrency
+		);
+
+		assert_ok!(LRP::set_acceptance_policy(Origin::signed(BOB), None));
+		assert_eq!(LRP::acceptance_policies(BOB), None);
+		assert_ok!(LRP::accept_payment(Origin::signed(BOB), payment_hash));
+	});
+}
+
+#[test]
+fn auto_accept_policy_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		Timestamp::set_timestamp(INIT_TIMESTAMP);
+
+		assert_ok!(LRP::set_acceptance_policy(
+			Origin::signed(BOB),
+			Some(AcceptancePolicy {
+				min_amount: None,
+				max_amount: None,
+				currencies: [].into(),
+				min_payer_credibility: None,
+				auto_accept: true,
+			})
+		));
+
+		assert_ok!(LRP::create_payment(
+			Origin::signed(ALICE),
+			BOB,
+			100,
+			CurrencyId::Native,
+			PaymentDescription::Plain("".into()),
+			"".into(),
+			None,
+		));
+		let payment_hash = LRP::payments_owned(&ALICE)[0];
+		assert_eq!(
+			last_event(),
+			Event::LRP(crate::Event::PaymentAccepted {
+				payment_hash,
+				payer: ALICE,
+				payee: BOB,
+				currency_id: CurrencyId::Native,
+				amount: 100,
+				risk_score: 62,
+				category: None,
+			}),
+		);
+		let payment = LRP::payments(payment_hash).unwrap();
+		assert_eq!(payment.status, PaymentStatus::Accepted);
+		assert_eq!(payment.updated_by, BOB);
+		// The accepted payment is not queued to expire.
+		let pending_bucket = queue_bucket_of(INIT_TIMESTAMP + PENDING_PAYMENT_WAITING_TIME);
+		assert!(!PendingPaymentsQueue::<Runtime>::contains_key(pending_bucket));
+		assert_noop!(
+			LRP::cancel_payment(Origin::signed(ALICE), payment_hash),
+			Error::<Runtime>::NotPayee
+		);
+
+		// The payee can deliver in the same block without accepting.
+		assert_ok!(LRP::fulfill_payment(Origin::signed(BOB), payment_hash));
+		assert_ok!(LRP::complete_payment(Origin::signed(ALICE), payment_hash));
+		assert_eq!(Currencies::free_balance(CurrencyId::Native, &BOB), 1100);
+
+		// The payments stay pending while the acceptance is paused.
+		assert_ok!(LRP::set_call_paused(Origin::root(), PausableCall::AcceptPayment, true));
+		assert_ok!(LRP::create_payment(
+			Origin::signed(ALICE),
+			BOB,
+			100,
+			CurrencyId::Native,
+			PaymentDescription::Plain("".into()),
+			"".into(),
+			None,
+		));
+		let payment_hash = LRP::payments_owned(&ALICE)[1];
+		assert_eq!(LRP::payments(payment_hash).unwrap().status, PaymentStatus::Pending);
+	});
+}
+
+#[test]
+fn compliance_check_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+
+		DeniedAccounts::set(vec![BOB]);
+		assert_noop!(
+			LRP::create_payment(
+				Origin::signed(ALICE),
+				BOB,
+				100,
+				CurrencyId::Native,
+				PaymentDescription::Plain("".into()),
+				"".into(),
+				None
+			),
+			sp_runtime::DispatchError::Other("AccountDenied")
+		);
+
+		DeniedAccounts::set(vec![]);
+		assert_ok!(LRP::create_payment(
+			Origin::signed(ALICE),
+			BOB,
+			100,
+			CurrencyId::Native,
+			PaymentDescription::Plain("".into()),
+			"".into(),
+			None,
+		));
+		let payment_hash = LRP::payments_owned(&ALICE)[0];
+
+		// The payment is screened again on the acceptance.
+		DeniedAccounts::set(vec![ALICE]);
+		assert_noop!(
+			LRP::accept_payment(Origin::signed(BOB), payment_hash),
+			sp_runtime::DispatchError::Other("AccountDenied")
+		);
+
+		DeniedAccounts::set(vec![]);
+		assert_ok!(LRP::accept_payment(Origin::signed(BOB), payment_hash));
+		assert_eq!(LRP::payments(payment_hash).unwrap().status, PaymentStatus::Accepted);
+	});
+}
+
+#[test]
+fn identity_requirement_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+
+		let requirement =
+			IdentityRequirement { payer: true, payee: false, min_credibility: 70, threshold: 100 };
+		assert_ok!(LRP::set_identity_requirement(Origin::signed(BOB), Some(requirement.clone())));
+		assert_eq!(
+			last_event(),
+			Event::LRP(crate::Event::IdentityRequirementUpdated {
+				merchant: BOB,
+				requirement: Some(requirement.clone()),
+			}),
+		);
+
+		// The requirement only applies to the payments from the threshold.
+		assert_ok!(LRP::create_payment(
+			Origin::signed(ALICE),
+			BOB,
+			99,
+			CurrencyId::Native,
+			PaymentDescription::Plain("".into()),
+			"".into(),
+			None,
+		));
+		assert_noop!(
+			LRP::create_payment(
+				Origin::signed(ALICE),
+				BOB,
+				100,
+				CurrencyId::Native,
+				PaymentDescription::Plain("".into()),
+				"".into(),
+				None
+			),
+			Error::<Runtime>::IdentityRequired
+		);
+
+		assert_ok!(Identities::create_identity(
+			Origin::signed(ALICE),
+			"Alice".into(),
+			IdentityType::Individual,
+			[].into(),
+		));
+		assert_noop!(
+			LRP::create_payment(
+				Origin::signed(ALICE),
+				BOB,
+				100,
+				CurrencyId::Native,
+				PaymentDescription::Plain("".into()),
+				"".into(),
+				None
+			),
+			Error::<Runtime>::InsufficientCredibility
+		);
+
+		assert_ok!(LRP::set_identity_requirement(
+			Origin::signed(BOB),
+			Some(IdentityRequirement { min_credibility: INITIAL_CREDIBILITY, ..requirement })
+		));
+		assert_ok!(LRP::create_payment(
+			Origin::signed(ALICE),
+			BOB,
+			100,
+			CurrencyId::Native,
+			PaymentDescription::Plain("".into()),
+			"".into(),
+			None,
+		));
+
+		// The merchants without their own requirement follow the default requirement.
+		DefaultIdentityRequirement::set(IdentityRequirement {
+			payer: false,
+			payee: true,
+			min_credibility: 0,
+			threshold: 0,
+		});
+		assert_ok!(LRP::set_identity_requirement(Origin::signed(BOB), None));
+		assert_eq!(LRP::identity_requirements(&BOB), None);
+		assert_noop!(
+			LRP::create_payment(
+				Origin::signed(ALICE),
+				BOB,
+				100,
+				CurrencyId::Native,
+				PaymentDescription::Plain("".into()),
+				"".into(),
+				None
+			),
+			Error::<Runtime>::IdentityRequired
+		);
+	});
+}
+
+#[test]
+fn compute_risk_score_works() {
+	let mut factors = RiskFactors {
+		payer_credibility: None,
+		account_maturity: Permill::zero(),
+		disputed_payments: 10,
+		amount_ratio: Permill::one(),
+		high_risk_category: true,
+		payee_loss_rate: Permill::one(),
+		attested_by_payee: false,
+	};
+	assert_eq!(compute_risk_score(&factors), MAX_RISK_SCORE);
+
+	factors.payer_credibility = Some(100);
+	factors.account_maturity = Permill::one();
+	factors.disputed_payments = 0;
+	factors.amount_ratio = Permill::zero();
+	factors.payee_loss_rate = Permill::from_percent(50);
+	assert_eq!(compute_risk_score(&factors), 20);
+
+	// The attestation of the payee lowers the score.
+	factors.attested_by_payee = true;
+	assert_eq!(compute_risk_score(&factors), 0);
+	factors.attested_by_payee = false;
+
+	factors.payee_loss_rate = Permill::zero();
+	assert_eq!(compute_risk_score(&factors), 10);
+
+	factors.high_risk_category = false;
+	assert_eq!(compute_risk_score(&factors), 0);
+}
+
+#[test]
+fn payment_risk_score_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		Timestamp::set_timestamp(INIT_TIMESTAMP);
+
+		// A new payer without identity.
+		assert_ok!(LRP::create_payment(
+			Origin::signed(ALICE),
+			BOB,
+			100,
+			CurrencyId::Native,
+			PaymentDescription::Plain("".into()),
+			"".into(),
+			None,
+		));
+		let payment_hash = LRP::payments_owned(&ALICE)[0];
+		assert_eq!(LRP::payments(payment_hash).unwrap().risk_score, 62);
+		assert_eq!(LRP::accounts_first_seen(&ALICE), Some(INIT_TIMESTAMP));
+
+		assert_ok!(Identities::create_identity(
+			Origin::signed(ALICE),
+			"Alice".into(),
+			IdentityType::Individual,
+			[].into(),
+		));
+		assert_ok!(LRP::accept_payment(Origin::signed(BOB), payment_hash));
+		assert_eq!(LRP::payments(payment_hash).unwrap().risk_score, 38);
+
+		assert_ok!(LRP::dispute_payment(Origin::signed(ALICE), payment_hash));
+		assert_eq!(LRP::disputed_payments_count(&ALICE), 1);
+		assert_eq!(Identities::dispute_records(&BOB).opened, 1);
+		assert_eq!(Identities::dispute_records(&ALICE).opened, 0);
+
+		// A mature payer with an identity and a disputed payment.
+		Timestamp::set_timestamp(INIT_TIMESTAMP + RISK_MATURE_ACCOUNT_AGE);
+		assert_ok!(LRP::create_payment(
+			Origin::signed(ALICE),
+			BOB,
+			500,
+			CurrencyId::Native,
+			PaymentDescription::Plain("".into()),
+			"".into(),
+			None,
+		));
+		let payment_hash = LRP::payments_owned(&ALICE)[1];
+		assert_eq!(LRP::payments(payment_hash).unwrap().risk_score, 31);
+	});
+}
+
+#[test]
+fn spending_limit_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		Timestamp::set_timestamp(INIT_TIMESTAMP);
+
+		let limit = SpendingLimit { daily: Some(150), weekly: Some(250) };
+		assert_ok!(LRP::set_spending_limit(
+			Origin::signed(ALICE),
+			ALICE,
+			CurrencyId::Native,
+			Some(limit.clone())
+		));
+		assert_eq!(
+			last_event(),
+			Event::LRP(crate::Event::SpendingLimitUpdated {
+				who: ALICE,
+				currency_id: CurrencyId::Native,
+				limit: Some(limit),
+			}),
+		);
+
+		assert_ok!(LRP::create_payment(
+			Origin::signed(ALICE),
+			BOB,
+			100,
+			CurrencyId::Native,
+			PaymentDescription::Plain("".into()),
+			"".into(),
+			None,
+		));
+		assert_noop!(
+			LRP::create_payment(
+				Origin::signed(ALICE),
+				BOB,
+				100,
+				CurrencyId::Native,
+				PaymentDescription::Plain("".into()),
+				"".into(),
+				None
+			),
+			Error::<Runtime>::SpendingLimitExceeded
+		);
+
+		// The daily spending is restarted after the daily period.
+		Timestamp::set_timestamp(INIT_TIMESTAMP + DAILY_SPENDING_PERIOD);
+		assert_ok!(LRP::create_payment(
+			Origin::signed(ALICE),
+			BOB,
+			100,
+			CurrencyId::Native,
+			PaymentDescription::Plain("".into()),
+			"".into(),
+			None,
+		));
+
+		// The weekly spending is still counted.
+		Timestamp::set_timestamp(INIT_TIMESTAMP + 2 * DAILY_SPENDING_PERIOD);
+		assert_noop!(
+			LRP::create_payment(
+				Origin::signed(ALICE),
+				BOB,
+				100,
+				CurrencyId::Native,
+				PaymentDescription::Plain("".into()),
+				"".into(),
+				None
+			),
+			Error::<Runtime>::SpendingLimitExceeded
+		);
+
+		// Once the admin is set, only the admin can manage the spending limits.
+		assert_ok!(LRP::set_spending_limit_admin(Origin::signed(ALICE), ALICE, Some(CHARLIE)));
+		assert_noop!(
+			LRP::set_spending_limit(Origin::signed(ALICE), ALICE, CurrencyId::Native, None),
+			Error::<Runtime>::NotSpendingLimitManager
+		);
+		assert_ok!(LRP::set_spending_limit(
+			Origin::signed(CHARLIE),
+			ALICE,
+			CurrencyId::Native,
+			None
+		));
+		assert_ok!(LRP::create_payment(
+			Origin::signed(ALICE),
+			BOB,
+			100,
+			CurrencyId::Native,
+			PaymentDescription::Plain("".into()),
+			"".into(),
+			None,
+		));
+	});
+}
+
+#[test]
+fn pull_payment_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		Timestamp::set_timestamp(INIT_TIMESTAMP);
+
+		let expires_at = INIT_TIMESTAMP + DAILY_SPENDING_PERIOD;
+		assert_ok!(LRP::approve_payee(
+			Origin::signed(ALICE),
+			BOB,
+			CurrencyId::Native,
+			100,
+			250,
+			expires_at
+		));
+		assert_eq!(
+			last_event(),
+			Event::LRP(crate::Event::PayeeApprovalUpdated {
+				payer: ALICE,
+				payee: BOB,
+				currency_id: CurrencyId::Native,
+				approval: Some(PayeeApproval {
+					per_payment_limit: 100,
+					total_limit: 250,
+					pulled: 0,
+					expires_at,
+				}),
+			}),
+		);
+
+		// Only the approved payee pulls, within the per-payment limit.
+		assert_noop!(
+			LRP::pull_payment(
+				Origin::signed(CHARLIE),
+				ALICE,
+				100,
+				CurrencyId::Native,
+				PaymentDescription::Plain("".into()),
+				"".into(),
+			),
+			Error::<Runtime>::PayeeNotApproved
+		);
+		assert_noop!(
+			LRP::pull_payment(
+				Origin::signed(BOB),
+				ALICE,
+				150,
+				CurrencyId::Native,
+				PaymentDescription::Plain("".into()),
+				"".into(),
+			),
+			Error::<Runtime>::PullLimitExceeded
+		);
+
+		// The pulled payment is accepted at once.
+		assert_ok!(LRP::pull_payment(
+			Origin::signed(BOB),
+			ALICE,
+			100,
+			CurrencyId::Native,
+			PaymentDescription::Plain("".into()),
+			"".into(),
+		));
+		let payment_hash = *LRP::payments_owned(&ALICE).last().unwrap();
+		assert_eq!(LRP::payments(payment_hash).unwrap().status, PaymentStatus::Accepted);
+		assert_eq!(
+			last_event(),
+			Event::LRP(crate::Event::PaymentPulled {
+				payment_hash,
+				payer: ALICE,
+				payee: BOB,
+				amount: 100
+			}),
+		);
+		assert_eq!(Currencies::free_balance(CurrencyId::Native, &ALICE), 900);
+
+		// The total limit caps all the pulled payments.
+		assert_ok!(LRP::pull_payment(
+			Origin::signed(BOB),
+			ALICE,
+			100,
+			CurrencyId::Native,
+			PaymentDescription::Plain("".into()),
+			"".into(),
+		));
+		assert_eq!(LRP::payee_approvals(ALICE, (BOB, CurrencyId::Native)).unwrap().pulled, 200);
+		assert_noop!(
+			LRP::pull_payment(
+				Origin::signed(BOB),
+				ALICE,
+				100,
+				CurrencyId::Native,
+				PaymentDescription::Plain("".into()),
+				"".into(),
+			),
+			Error::<Runtime>::PullLimitExceeded
+		);
+
+		// The revoked payee can no longer pull.
+		assert_ok!(LRP::revoke_payee_approval(Origin::signed(ALICE), BOB, CurrencyId::Native));
+		assert_eq!(
+			last_event(),
+			Event::LRP(crate::Event::PayeeApprovalUpdated {
+				payer: ALICE,
+				payee: BOB,
+				currency_id: CurrencyId::Native,
+				approval: None,
+			}),
+		);
+		assert_noop!(
+			LRP::pull_payment(
+				Origin::signed(BOB),
+				ALICE,
+				50,
+				CurrencyId::Native,
+				PaymentDescription::Plain("".into()),
+				"".into(),
+			),
+			Error::<Runtime>::PayeeNotApproved
+		);
+
+		// The expired approval can no longer be pulled from.
+		assert_ok!(LRP::approve_payee(
+			Origin::signed(ALICE),
+			BOB,
+			CurrencyId::Native,
+			100,
+			250,
+			expires_at
+		));
+		Timestamp::set_timestamp(expires_at);
+		assert_noop!(
+			LRP::pull_payment(
+				Origin::signed(BOB),
+				ALICE,
+				50,
+				CurrencyId::Native,
+				PaymentDescription::Plain("".into()),
+				"".into(),
+			),
+			Error::<Runtime>::PayeeApprovalExpired
+		);
+	});
+}
+
+#[test]
+fn approve_payee_with_invalid_approval_fails() {
+	ExtBuilder::default().build().execute_with(|| {
+		Timestamp::set_timestamp(INIT_TIMESTAMP);
+
+		assert_noop!(
+			LRP::approve_payee(Origin::signed(ALICE), ALICE, CurrencyId::Native, 100, 250, 2_000),
+			Error::<Runtime>::InvalidPayeeApproval
+		);
+		assert_noop!(
+			LRP::approve_payee(Origin::signed(ALICE), BOB, CurrencyId::Native, 300, 250, 2_000),
+			Error::<Runtime>::InvalidPayeeApproval
+		);
+		assert_noop!(
+			LRP::approve_payee(
+				Origin::signed(ALICE),
+				BOB,
+				CurrencyId::Native,
+				100,
+				250,
+				INIT_TIMESTAMP
+			),
+			Error::<Runtime>::InvalidPayeeApproval
+		);
+		assert_noop!(
+			LRP::revoke_payee_approval(Origin::signed(ALICE), BOB, CurrencyId::Native),
+			Error::<Runtime>::PayeeNotApproved
+		);
+	});
+}
+#[test]
+fn merchant_proxy_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+
+		assert_ok!(LRP::create_payment(
+			Origin::signed(ALICE),
+			BOB,
+			100,
+			CurrencyId::Native,
+			PaymentDescription::Plain("".into()),
+			"".into(),
+			None,
+		));
+		let payment_hash = LRP::payments_owned(&ALICE)[0];
+
+		// The proxy cannot operate the payments before it is authorized.
+		assert_noop!(
+			LRP::accept_payment(Origin::signed(CHARLIE), payment_hash),
+			Error::<Runtime>::NotPayeeOrProxy
+		);
+		assert_noop!(
+			LRP::set_merchant_proxy(Origin::signed(BOB), BOB, Some([].to_vec())),
+			Error::<Runtime>::InvalidMerchantProxy
+		);
+
+		let operations = [MerchantOperation::AcceptPayment, MerchantOperation::FulfillPayment];
+		assert_ok!(LRP::set_merchant_proxy(
+			Origin::signed(BOB),
+			CHARLIE,
+			Some(operations.to_vec()),
+		));
+		assert_eq!(
+			last_event(),
+			Event::LRP(crate::Event::MerchantProxyUpdated {
+				merchant: BOB,
+				proxy: CHARLIE,
+				operations: Some(operations.to_vec()),
+			}),
+		);
+		assert_eq!(LRP::merchant_proxies(BOB, CHARLIE), Some(operations.to_vec()));
+
+		assert_ok!(LRP::accept_payment(Origin::signed(CHARLIE), payment_hash));
+		let payment = LRP::payments(payment_hash).unwrap();
+		assert_eq!(payment.status, PaymentStatus::Accepted);
+		assert_eq!(payment.updated_by, CHARLIE);
+
+		// The proxy is restricted to the authorized operations.
+		assert_noop!(
+			LRP::add_delivery_proof(Origin::signed(CHARLIE), payment_hash, "tracking".into()),
+			Error::<Runtime>::NotPayeeOrProxy
+		);
+		assert_noop!(
+			LRP::cancel_payment(Origin::signed(CHARLIE), payment_hash),
+			Error::<Runtime>::NotPayee
+		);
+
+		assert_ok!(LRP::fulfill_payment(Origin::signed(CHARLIE), payment_hash));
+		assert_eq!(LRP::payments(payment_hash).unwrap().status, PaymentStatus::Fulfilled);
+
+		// The proxy of a merchant cannot operate the payments of the other merchants.
+		assert_ok!(LRP::create_payment(
+			Origin::signed(BOB),
+			ALICE,
+			100,
+			CurrencyId::Native,
+			PaymentDescription::Plain("".into()),
+			"".into(),
+			None,
+		));
+		let other_payment_hash = LRP::payments_owned(&BOB)[0];
+		assert_noop!(
+			LRP::accept_payment(Origin::signed(CHARLIE), other_payment_hash),
+			Error::<Runtime>::NotPayeeOrProxy
+		);
+
+		assert_ok!(LRP::set_merchant_proxy(Origin::signed(BOB), CHARLIE, None));
+		assert_eq!(LRP::merchant_proxies(BOB, CHARLIE), None);
+	});
+}
+
+#[test]
+fn paused_calls_are_rejected() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+
+		assert_noop!(
+			LRP::set_call_paused(Origin::signed(ALICE), PausableCall::CreatePayment, true),
+			sp_runtime::DispatchError::BadOrigin
+		);
+		assert_ok!(LRP::set_call_paused(Origin::root(), PausableCall::CreatePayment, true));
+		assert_eq!(
+			last_event(),
+			Event::LRP(crate::Event::CallPauseUpdated {
+				call: PausableCall::CreatePayment,
+				paused: true,
+			}),
+		);
+		assert!(LRP::paused_calls(PausableCall::CreatePayment));
+		assert_noop!(
+			LRP::create_payment(
+				Origin::signed(ALICE),
+				BOB,
+				100,
+				CurrencyId::Native,
+				PaymentDescription::Plain("".into()),
+				"".into(),
+				None,
+			),
+			Error::<Runtime>::CallPaused
+		);
+
+		assert_ok!(LRP::set_call_paused(Origin::root(), PausableCall::CreatePayment, false));
+		assert!(!LRP::paused_calls(PausableCall::CreatePayment));
+		assert_ok!(LRP::create_payment(
+			Origin::signed(ALICE),
+			BOB,
+			100,
+			CurrencyId::Native,
+			PaymentDescription::Plain("".into()),
+			"".into(),
+			None,
+		));
+		let payment_hash = LRP::payments_owned(&ALICE)[0];
+		assert_ok!(LRP::accept_payment(Origin::signed(BOB), payment_hash));
+		assert_ok!(LRP::fulfill_payment(Origin::signed(BOB), payment_hash));
+
+		// The auto-completion waits until the completion is unpaused.
+		assert_ok!(LRP::set_call_paused(Origin::root(), PausableCall::CompletePayment, true));
+		assert_noop!(
+			LRP::complete_payment(Origin::signed(ALICE), payment_hash),
+			Error::<Runtime>::CallPaused
+		);
+		run_to_block_number((FULFILLED_WAITING_TIME / BLOCK_TIME).into());
+		assert_eq!(LRP::payments(payment_hash).unwrap().status, PaymentStatus::Fulfilled);
+
+		assert_ok!(LRP::set_call_paused(Origin::root(), PausableCall::CompletePayment, false));
+		run_to_block_number((FULFILLED_WAITING_TIME / BLOCK_TIME + 1).into());
+		assert_eq!(LRP::payments(payment_hash).unwrap().status, PaymentStatus::Completed);
+
+		// The refund paths are never paused.
+		assert_ok!(LRP::set_call_paused(Origin::root(), PausableCall::AcceptPayment, true));
+		assert_ok!(LRP::create_payment(
+			Origin::signed(ALICE),
+			BOB,
+			100,
+			CurrencyId::Native,
+			PaymentDescription::Plain("".into()),
+			"".into(),
+			None,
+		));
+		let payment_hash = LRP::payments_owned(&ALICE)[1];
+		assert_noop!(
+			LRP::accept_payment(Origin::signed(BOB), payment_hash),
+			Error::<Runtime>::CallPaused
+		);
+		assert_ok!(LRP::cancel_payment(Origin::signed(ALICE), payment_hash));
+	});
+}
+
+#[test]
+fn total_value_locked_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+
+		for amount in [100, 200] {
+			assert_ok!(LRP::create_payment(
+				Origin::signed(ALICE),
+				BOB,
+				amount,
+				CurrencyId::Native,
+				PaymentDescription::Plain("".into()),
+				"".into(),
+				None,
+			));
+		}
+		let payment_hashes = LRP::payments_owned(&ALICE);
+		assert_eq!(LRP::total_value_locked(CurrencyId::Native), 300);
+
+		// The refunded funds are released.
+		assert_ok!(LRP::cancel_payment(Origin::signed(ALICE), payment_hashes[0]));
+		assert_eq!(LRP::total_value_locked(CurrencyId::Native), 200);
+
+		// The funds paid to the payee and the fees are released.
+		assert_ok!(LRP::accept_payment(Origin::signed(BOB), payment_hashes[1]));
+		assert_ok!(LRP::fulfill_payment(Origin::signed(BOB), payment_hashes[1]));
+		assert_eq!(LRP::total_value_locked(CurrencyId::Native), 200);
+		assert_ok!(LRP::complete_payment(Origin::signed(ALICE), payment_hashes[1]));
+		assert_eq!(LRP::total_value_locked(CurrencyId::Native), 0);
+		assert_ok!(LRP::do_try_state());
+	});
+}
+
+#[test]
+fn payments_by_status_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		Timestamp::set_timestamp(INIT_TIMESTAMP);
+
+		for amount in [100, 200, 300] {
+			assert_ok!(LRP::create_payment(
+				Origin::signed(ALICE),
+				BOB,
+				amount,
+				CurrencyId::Native,
+				PaymentDescription::Plain("".into()),
+				"".into(),
+				None,
+			));
+		}
+		let payment_hashes = LRP::payments_owned(&ALICE);
+		let mut pending = LRP::payments_with_status(PaymentStatus::Pending, INIT_TIMESTAMP, 0, 10);
+		assert_eq!(
+			LRP::payments_with_status(PaymentStatus::Pending, INIT_TIMESTAMP, 1, 1),
+			vec![pending[1]]
+		);
+		pending.sort();
+		let mut expected = payment_hashes.clone();
+		expected.sort();
+		assert_eq!(pending, expected);
+
+		// The payments move to the index of their new status.
+		assert_ok!(LRP::cancel_payment(Origin::signed(ALICE), payment_hashes[0]));
+		assert_ok!(LRP::accept_payment(Origin::signed(BOB), payment_hashes[1]));
+		assert_eq!(
+			LRP::payments_with_status(PaymentStatus::Pending, INIT_TIMESTAMP, 0, 10),
+			vec![payment_hashes[2]]
+		);
+		assert_eq!(
+			LRP::payments_with_status(PaymentStatus::Cancelled, INIT_TIMESTAMP, 0, 10),
+			vec![payment_hashes[0]]
+		);
+		assert_eq!(
+			LRP::payments_with_status(PaymentStatus::Accepted, INIT_TIMESTAMP, 0, 10),
+			vec![payment_hashes[1]]
+		);
+
+		// The payments created on the next day are indexed in the next bucket.
+		let next_day = INIT_TIMESTAMP + PAYMENT_INDEX_BUCKET_DURATION;
+		Timestamp::set_timestamp(next_day);
+		assert_ok!(LRP::create_payment(
+			Origin::signed(ALICE),
+			BOB,
+			100,
+			CurrencyId::Native,
+			PaymentDescription::Plain("".into()),
+			"".into(),
+			None,
+		));
+		assert_eq!(
+			LRP::payments_with_status(PaymentStatus::Pending, next_day, 0, 10),
+			vec![LRP::payments_owned(&ALICE)[3]]
+		);
+		assert_eq!(
+			LRP::payments_with_status(PaymentStatus::Pending, INIT_TIMESTAMP, 0, 10).len(),
+			1
+		);
+		assert_ok!(LRP::do_try_state());
+	});
+}
+
+#[test]
+fn try_state_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(LRP::create_payment(
+			Origin::signed(ALICE),
+			BOB,
+			100,
+			CurrencyId::Native,
+			PaymentDescription::Plain("".into()),
+			"".into(),
+			None,
+		));
+		assert_ok!(LRP::do_try_state());
+
+		// The escrow account must hold the funds of the open payments.
+		assert_ok!(<Currencies as MultiCurrency<_>>::transfer(
+			CurrencyId::Native,
+			&LRP::escrow_account(),
+			&ALICE,
+			1
+		));
+		assert_eq!(
+			LRP::do_try_state(),
+			Err("The escrow account does not hold the funds of the open payments")
+		);
+		assert_ok!(<Currencies as MultiCurrency<_>>::transfer(
+			CurrencyId::Native,
+			&ALICE,
+			&LRP::escrow_account(),
+			1
+		));
+
+		// The total value locked must be the funds of the open payments.
+		TotalValueLocked::<Runtime>::insert(CurrencyId::Native, 99);
+		assert_eq!(
+			LRP::do_try_state(),
+			Err("The total value locked is not the funds of the open payments")
+		);
+		TotalValueLocked::<Runtime>::insert(CurrencyId::Native, 100);
+
+		// Every payment must be indexed by its status.
+		let payment_hash = LRP::payments_owned(&ALICE)[0];
+		let bucket = (
+			PaymentStatus::Pending,
+			LRP::index_bucket_of(LRP::payments(payment_hash).unwrap().created_at),
+		);
+		PaymentsByStatus::<Runtime>::remove(&bucket, payment_hash);
+		assert_eq!(LRP::do_try_state(), Err("A payment is not indexed by its status"));
+		PaymentsByStatus::<Runtime>::insert(&bucket, payment_hash, ());
+
+		// The funds must not be reserved by the pallet.
+		NamedReserves::<Runtime>::insert(ALICE, (PAYMENT_RESERVE, CurrencyId::Native), 100);
+		assert_eq!(
+			LRP::do_try_state(),
+			Err("The funds of the payments are reserved instead of escrowed")
+		);
+	});
+}
+
+#[test]
+fn migrate_to_v1_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+
+		assert_ok!(LRP::create_payment(
+			Origin::signed(ALICE),
+			BOB,
+			100,
+			CurrencyId::Native,
+			PaymentDescription::Plain("".into()),
+			"".into(),
+			None,
+		));
+		let payment_hash = LRP::payments_owned(&ALICE)[0];
+		let payment = LRP::payments(payment_hash).unwrap();
+
+		// Store the payment with the layout before the storage versioning.
+		let key = Payments::<Runtime>::hashed_key_for(payment_hash);
+		frame_support::storage::unhashed::put_raw(
+			&key,
+			&(
+				payment.id,
+				payment.payer,
+				payment.payee,
+				payment.amount,
+				payment.currency_id,
+				payment.description.clone(),
+				payment.status.clone(),
+				payment.receipt_hash,
+				payment.created_at,
+				payment.updated_at,
+				payment.updated_by,
+			)
+				.encode(),
+		);
+		// The pending payments are listed instead of queued.
+		let _ = PendingPaymentsQueue::<Runtime>::remove_all(None);
+		NextPendingPaymentsBucket::<Runtime>::kill();
+		frame_support::storage::migration::put_storage_value(
+			b"LRP",
+			b"PendingPaymentHashes",
+			&[],
+			vec![payment_hash],
+		);
+		StorageVersion::new(0).put::<LRP>();
+
+		migrations::v1::MigrateToV1::<Runtime>::on_runtime_upgrade();
+		assert_eq!(LRP::on_chain_storage_version(), 1);
+
+		// The pending payment is queued for its expiry.
+		assert_eq!(
+			PendingPaymentsQueue::<Runtime>::get(queue_bucket_of(
+				payment.updated_at + PENDING_PAYMENT_WAITING_TIME
+			))
+			.to_vec(),
+			vec![payment_hash]
+		);
+		assert!(!frame_support::storage::migration::have_storage_value(
+			b"LRP",
+			b"PendingPaymentHashes",
+			&[]
+		));
+
+		// The payment is stored with the version 1 layout, without the fulfillment deadline.
+		let migrated = Payment::<Runtime> {
+			risk_score: 0,
+			payee_confirmation: PayeeConfirmation::NotRequired,
+			refund_to: None,
+			delivery_proofs: Default::default(),
+			fulfillment_deadline: None,
+			..payment
+		}
+		.encode();
+		assert_eq!(
+			frame_support::storage::unhashed::get_raw(&key),
+			Some(migrated[..migrated.len() - 1].to_vec())
+		);
+	});
+}
+
+#[test]
+fn migrate_to_v2_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+
+		for amount in [100, 200, 300] {
+			assert_ok!(LRP::create_payment(
+				Origin::signed(ALICE),
+				BOB,
+				amount,
+				CurrencyId::Native,
+				PaymentDescription::Plain("".into()),
+				"".into(),
+				None,
+			));
+		}
+		let payment_hash = LRP::payments_owned(&ALICE)[2];
+		assert_ok!(LRP::cancel_payment(Origin::signed(ALICE), payment_hash));
+
+		StorageVersion::new(1).put::<LRP>();
+		reserve_escrowed_funds(ALICE, 300);
+
+		migrations::v2::MigrateToV2::<Runtime>::on_runtime_upgrade();
+		assert_eq!(LRP::on_chain_storage_version(), 2);
+
+		// Only the open payments are tracked.
+		assert_eq!(LRP::named_reserves(ALICE, (PAYMENT_RESERVE, CurrencyId::Native)), 300);
+	});
+}
+
+#[test]
+fn migrate_to_v3_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		Timestamp::set_timestamp(INIT_TIMESTAMP);
+
+		for amount in [100, 200] {
+			assert_ok!(LRP::create_payment(
+				Origin::signed(ALICE),
+				BOB,
+				amount,
+				CurrencyId::Native,
+				PaymentDescription::Plain("".into()),
+				"".into(),
+				None,
+			));
+		}
+		let payment_hashes = LRP::payments_owned(&ALICE);
+		assert_ok!(LRP::accept_payment(Origin::signed(BOB), payment_hashes[0]));
+
+		// Store the payments with the version 2 layout, without the fulfillment deadline.
+		for payment_hash in &payment_hashes {
+			let mut payment = LRP::payments(payment_hash).unwrap();
+			payment.fulfillment_deadline = None;
+			let encoded = payment.encode();
+			frame_support::storage::unhashed::put_raw(
+				&Payments::<Runtime>::hashed_key_for(payment_hash),
+				&encoded[..encoded.len() - 1],
+			);
+		}
+		StorageVersion::new(2).put::<LRP>();
+
+		Timestamp::set_timestamp(INIT_TIMESTAMP + BLOCK_TIME);
+		migrations::v3::MigrateToV3::<Runtime>::on_runtime_upgrade();
+		assert_eq!(LRP::on_chain_storage_version(), 3);
+
+		// Only the accepted payments get a deadline, counted from the upgrade.
+		assert_eq!(
+			LRP::payments(payment_hashes[0]).unwrap().fulfillment_deadline,
+			Some(INIT_TIMESTAMP + BLOCK_TIME + FULFILLMENT_TIME)
+		);
+		assert_eq!(LRP::payments(payment_hashes[1]).unwrap().fulfillment_deadline, None);
+		assert_eq!(LRP::payments(payment_hashes[1]).unwrap().status, PaymentStatus::Pending);
+	});
+}
+
+#[test]
+fn migrate_to_v4_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+
+		for amount in [100, 200, 300] {
+			assert_ok!(LRP::create_payment(
+				Origin::signed(ALICE),
+				BOB,
+				amount,
+				CurrencyId::Native,
+				PaymentDescription::Plain("".into()),
+				"".into(),
+				None,
+			));
+		}
+		let payment_hash = LRP::payments_owned(&ALICE)[2];
+		assert_ok!(LRP::cancel_payment(Origin::signed(ALICE), payment_hash));
+
+		// The funds of the open payments are reserved and tracked with the version 3 layout.
+		reserve_escrowed_funds(ALICE, 300);
+		NamedReserves::<Runtime>::insert(ALICE, (PAYMENT_RESERVE, CurrencyId::Native), 300);
+		StorageVersion::new(3).put::<LRP>();
+
+		// The funds reserved by another pallet are not escrowed.
+		assert_ok!(Currencies::reserve(CurrencyId::Native, &ALICE, 50));
+
+		migrations::v4::MigrateToV4::<Runtime>::on_runtime_upgrade();
+		assert_eq!(LRP::on_chain_storage_version(), 4);
+
+		assert_eq!(LRP::named_reserves(ALICE, (PAYMENT_RESERVE, CurrencyId::Native)), 0);
+		assert_eq!(Currencies::reserved_balance(CurrencyId::Native, &ALICE), 50);
+		assert_eq!(Currencies::free_balance(CurrencyId::Native, &ALICE), 650);
+		assert_eq!(Currencies::free_balance(CurrencyId::Native, &LRP::escrow_account()), 300);
+	});
+}
+
+#[test]
+fn migrate_to_v4_reduces_under_funded_payments() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+
+		for amount in [100, 200] {
+			assert_ok!(LRP::create_payment(
+				Origin::signed(ALICE),
+				BOB,
+				amount,
+				CurrencyId::Native,
+				PaymentDescription::Plain("".into()),
+				"".into(),
+				None,
+			));
+		}
+
+		// Only 250 of the 300 of the open payments are reserved and tracked.
+		reserve_escrowed_funds(ALICE, 250);
+		assert_ok!(<Currencies as MultiCurrency<_>>::transfer(
+			CurrencyId::Native,
+			&LRP::escrow_account(),
+			&ALICE,
+			50
+		));
+		NamedReserves::<Runtime>::insert(ALICE, (PAYMENT_RESERVE, CurrencyId::Native), 250);
+		StorageVersion::new(3).put::<LRP>();
+
+		migrations::v4::MigrateToV4::<Runtime>::on_runtime_upgrade();
+
+		// The free funds of the payer are not taken, the short payment is reduced instead.
+		assert_eq!(Currencies::reserved_balance(CurrencyId::Native, &ALICE), 0);
+		assert_eq!(Currencies::free_balance(CurrencyId::Native, &ALICE), 750);
+		assert_eq!(Currencies::free_balance(CurrencyId::Native, &LRP::escrow_account()), 250);
+		let amounts: u128 = LRP::payments_owned(&ALICE)
+			.iter()
+			.map(|payment_hash| LRP::payments(payment_hash).unwrap().amount)
+			.sum();
+		assert_eq!(amounts, 250);
+		assert!(System::events().iter().any(|record| matches!(
+			record.event,
+			Event::LRP(crate::Event::PaymentEscrowShortfall { shortfall: 50, .. })
+		)));
+	});
+}
+
+#[test]
+fn migrate_to_v5_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+
+		for amount in [100, 200, 300] {
+			assert_ok!(LRP::create_payment(
+				Origin::signed(ALICE),
+				BOB,
+				amount,
+				CurrencyId::Native,
+				PaymentDescription::Plain("".into()),
+				"".into(),
+				None,
+			));
+		}
+		let payment_hash = LRP::payments_owned(&ALICE)[2];
+		assert_ok!(LRP::cancel_payment(Origin::signed(ALICE), payment_hash));
+
+		// The value locked is not tracked with the version 4 layout.
+		let _ = TotalValueLocked::<Runtime>::remove_all(None);
+		StorageVersion::new(4).put::<LRP>();
+
+		migrations::v5::MigrateToV5::<Runtime>::on_runtime_upgrade();
+		assert_eq!(LRP::on_chain_storage_version(), 5);
+
+		assert_eq!(LRP::total_value_locked(CurrencyId::Native), 300);
+		assert_ok!(LRP::do_try_state());
+	});
+}
+
+#[test]
+fn migrate_to_v6_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		Timestamp::set_timestamp(INIT_TIMESTAMP);
+
+		for amount in [100, 200, 300] {
+			assert_ok!(LRP::create_payment(
+				Origin::signed(ALICE),
+				BOB,
+				amount,
+				CurrencyId::Native,
+				PaymentDescription::Plain("".into()),
+				"".into(),
+				None,
+			));
+		}
+		let payment_hashes = LRP::payments_owned(&ALICE);
+		assert_ok!(LRP::cancel_payment(Origin::signed(ALICE), payment_hashes[2]));
+
+		// The payments are not indexed with the version 5 layout.
+		let _ = PaymentsByStatus::<Runtime>::remove_all(None);
+		StorageVersion::new(5).put::<LRP>();
+
+		migrations::v6::MigrateToV6::<Runtime>::on_runtime_upgrade();
+		assert_eq!(LRP::on_chain_storage_version(), 6);
+		assert_eq!(LRP::on_chain_storage_version(), LRP::current_storage_version());
+
+		assert_eq!(
+			LRP::payments_with_status(PaymentStatus::Cancelled, INIT_TIMESTAMP, 0, 10),
+			vec![payment_hashes[2]]
+		);
+		assert_eq!(
+			LRP::payments_with_status(PaymentStatus::Pending, INIT_TIMESTAMP, 0, 10).len(),
+			2
+		);
+		assert_ok!(LRP::do_try_state());
+	});
+}
+
+#[test]
+fn reclaim_unfulfilled_payment_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		Timestamp::set_timestamp(INIT_TIMESTAMP);
+
+		for amount in [100, 200] {
+			assert_ok!(LRP::create_payment(
+				Origin::signed(ALICE),
+				BOB,
+				amount,
+				CurrencyId::Native,
+				PaymentDescription::Plain("".into()),
+				"".into(),
+				None,
+			));
+		}
+		let payment_hashes = LRP::payments_owned(&ALICE);
+
+		// A pending payment has no deadline, the payer can cancel it instead.
+		assert_noop!(
+			LRP::reclaim_unfulfilled_payment(Origin::signed(ALICE), payment_hashes[0]),
+			Error::<Runtime>::PaymentNotAccepted
+		);
+
+		assert_ok!(LRP::accept_payment(Origin::signed(BOB), payment_hashes[0]));
+		assert_ok!(LRP::accept_payment(Origin::signed(BOB), payment_hashes[1]));
+		assert_eq!(
+			LRP::payments(payment_hashes[0]).unwrap().fulfillment_deadline,
+			Some(INIT_TIMESTAMP + FULFILLMENT_TIME)
+		);
+
+		assert_noop!(
+			LRP::reclaim_unfulfilled_payment(Origin::signed(ALICE), payment_hashes[0]),
+			Error::<Runtime>::FulfillmentDeadlineNotReached
+		);
+
+		Timestamp::set_timestamp(INIT_TIMESTAMP + FULFILLMENT_TIME);
+		assert_noop!(
+			LRP::reclaim_unfulfilled_payment(Origin::signed(BOB), payment_hashes[0]),
+			Error::<Runtime>::NotPayer
+		);
+
+		assert_ok!(LRP::reclaim_unfulfilled_payment(Origin::signed(ALICE), payment_hashes[0]));
+		assert_eq!(LRP::payments(payment_hashes[0]).unwrap().status, PaymentStatus::Reclaimed);
+		assert_eq!(Currencies::free_balance(CurrencyId::Native, &LRP::escrow_account()), 200);
+		assert_eq!(Currencies::free_balance(CurrencyId::Native, &ALICE), 800);
+		assert_eq!(
+			last_event(),
+			Event::LRP(crate::Event::PaymentReclaimed {
+				payment_hash: payment_hashes[0],
+				payer: ALICE,
+				payee: BOB,
+				currency_id: CurrencyId::Native,
+				amount: 100,
+			})
+		);
+
+		assert_noop!(
+			LRP::reclaim_unfulfilled_payment(Origin::signed(ALICE), payment_hashes[0]),
+			Error::<Runtime>::PaymentNotAccepted
+		);
+
+		// A payment fulfilled after the deadline cannot be reclaimed any more.
+		assert_ok!(LRP::fulfill_payment(Origin::signed(BOB), payment_hashes[1]));
+		assert_noop!(
+			LRP::reclaim_unfulfilled_payment(Origin::signed(ALICE), payment_hashes[1]),
+			Error::<Runtime>::PaymentNotAccepted
+		);
+	});
+}
@@ -0,0 +1,326 @@
+//! Benchmarking setup for resolvers network.
+
+use super::*;
+
+use frame_benchmarking::{account, benchmarks, whitelisted_caller};
+use frame_support::{
+	sp_runtime::traits::{Saturating, Zero},
+	sp_std::vec::Vec,
+	traits::Get,
+};
+use frame_system::RawOrigin;
+use orml_traits::{MultiCurrency, MultiReservableCurrency};
+use pallet_identities::IdentityType;
+use primitives::PaymentCategory;
+
+type BalanceOf<T> =
+	<<T as Config>::Currency as MultiCurrency<<T as frame_system::Config>::AccountId>>::Balance;
+
+const SEED: u32 = 0;
+/// The number of delegations of a resolver before the benchmarked call.
+const MAX_DELEGATORS: u32 = 100;
+/// The number of blacklisted accounts before the benchmarked call.
+const MAX_BLACKLISTED: u32 = 100;
+/// The maximum number of resolvers delegated to in one call.
+const MAX_DELEGATIONS: u32 = 100;
+/// The number of pending funds of an account before the benchmarked release.
+const MAX_PENDING_FUNDS: u32 = 100;
+
+fn assert_last_event<T: Config>(generic_event: <T as Config>::Event) {
+	frame_system::Pallet::<T>::assert_last_event(generic_event.into());
+}
+
+fn funded_account<T: Config>(name: &'static str, index: u32) -> T::AccountId {
+	let who: T::AccountId = account(name, index, SEED);
+	let amount = T::ActivationStakeAmount::get() * 100u32.into();
+	<T as Config>::Currency::deposit(T::FeeCurrency::get(), &who, amount).unwrap();
+	who
+}
+
+fn create_resolver<T: Config + pallet_identities::Config>(
+	who: &T::AccountId,
+	self_stake: BalanceOf<T>,
+) {
+	pallet_identities::Pallet::<T>::create_identity(
+		RawOrigin::Signed(who.clone()).into(),
+		b"Resolver".to_vec(),
+		IdentityType::Individual,
+		[].to_vec(),
+	)
+	.unwrap();
+	Pallet::<T>::join_resolvers_network(
+		RawOrigin::Signed(who.clone()).into(),
+		b"Resolver application".to_vec(),
+		self_stake,
+		[0; 32],
+	)
+	.unwrap();
+}
+
+fn add_delegators<T: Config>(resolver: &T::AccountId, n: u32) {
+	for i in 0..n {
+		let delegator = funded_account::<T>("delegator", i);
+		Pallet::<T>::delegate(RawOrigin::Signed(delegator).into(), resolver.clone(), 1u32.into())
+			.unwrap();
+	}
+}
+
+// Create a nomination pool with `t` active resolvers to delegate to.
+fn create_pool_with_targets<T: Config + pallet_identities::Config>(t: u32) -> PoolId {
+	for i in 0..t {
+		let resolver = funded_account::<T>("resolver", i);
+		create_resolver::<T>(&resolver, T::ActivationStakeAmount::get());
+	}
+	let operator = funded_account::<T>("operator", 0);
+	Pallet::<T>::create_pool(
+		RawOrigin::Signed(operator).into(),
+		PoolStrategy::TopStaked,
+		T::MaxPoolCommission::get(),
+	)
+	.unwrap();
+	Pallet::<T>::next_pool_id() - 1
+}
+
+benchmarks! {
+	where_clause { where T: pallet_identities::Config }
+
+	join_resolvers_network {
+		let blacklisted: Vec<T::AccountId> =
+			(0..MAX_BLACKLISTED).map(|i| account("blacklisted", i, SEED)).collect();
+		<BlacklistedAccounts<T>>::put(blacklisted);
+		let caller = funded_account::<T>("caller", 0);
+		pallet_identities::Pallet::<T>::create_identity(
+			RawOrigin::Signed(caller.clone()).into(),
+			b"Resolver".to_vec(),
+			IdentityType::Individual,
+			[].to_vec(),
+		)?;
+		let self_stake = T::ActivationStakeAmount::get();
+	}: _(RawOrigin::Signed(caller.clone()), b"Resolver application".to_vec(), self_stake, [0; 32])
+	verify {
+		assert_last_event::<T>(Event::ResolverCreated { account: caller, self_stake }.into());
+	}
+
+	// The delegation activates a candidacy resolver.
+	delegate {
+		let resolver = funded_account::<T>("resolver", 0);
+		create_resolver::<T>(&resolver, T::MinimumSelfStake::get());
+		add_delegators::<T>(&resolver, MAX_DELEGATORS);
+		let caller = funded_account::<T>("caller", 0);
+		let amount = T::ActivationStakeAmount::get();
+	}: _(RawOrigin::Signed(caller.clone()), resolver.clone(), amount)
+	verify {
+		assert_last_event::<T>(Event::DelegationAdded { resolver, delegator: caller, amount }.into());
+	}
+
+	// The undelegation deactivates an active resolver.
+	undelegate {
+		let resolver = funded_account::<T>("resolver", 0);
+		create_resolver::<T>(&resolver, T::MinimumSelfStake::get());
+		add_delegators::<T>(&resolver, MAX_DELEGATORS);
+		let caller = funded_account::<T>("caller", 0);
+		let amount = T::ActivationStakeAmount::get();
+		Pallet::<T>::delegate(RawOrigin::Signed(caller.clone()).into(), resolver.clone(), amount)?;
+	}: _(RawOrigin::Signed(caller.clone()), resolver.clone(), amount)
+	verify {
+		let release_at = pallet_timestamp::Pallet::<T>::get() + T::UndelegateTime::get();
+		assert_last_event::<T>(
+			Event::DelegationRemoved { resolver, delegator: caller, amount, release_at }.into(),
+		);
+	}
+
+	// The redelegation deactivates the source resolver and activates the target resolver.
+	redelegate {
+		let from = funded_account::<T>("resolver", 0);
+		create_resolver::<T>(&from, T::MinimumSelfStake::get());
+		add_delegators::<T>(&from, MAX_DELEGATORS);
+		let to = funded_account::<T>("resolver", 1);
+		create_resolver::<T>(&to, T::MinimumSelfStake::get());
+		add_delegators::<T>(&to, MAX_DELEGATORS);
+		let caller = funded_account::<T>("caller", 0);
+		let amount = T::ActivationStakeAmount::get();
+		Pallet::<T>::delegate(RawOrigin::Signed(caller.clone()).into(), from.clone(), amount)?;
+	}: _(RawOrigin::Signed(caller.clone()), from.clone(), to.clone(), amount)
+	verify {
+		assert_last_event::<T>(
+			Event::DelegationMoved { delegator: caller, from_resolver: from, to_resolver: to, amount }
+				.into(),
+		);
+	}
+
+	delegate_many {
+		let n in 1 .. MAX_DELEGATIONS;
+		let amount = T::MinimumSelfStake::get();
+		let delegations: Vec<(T::AccountId, BalanceOf<T>)> = (0..n)
+			.map(|i| {
+				let resolver = funded_account::<T>("resolver", i);
+				create_resolver::<T>(&resolver, amount);
+				(resolver, amount)
+			})
+			.collect();
+		let caller = funded_account::<T>("caller", 0);
+	}: _(RawOrigin::Signed(caller.clone()), delegations)
+	verify {
+		let reserved = <T as Config>::Currency::reserved_balance(T::FeeCurrency::get(), &caller);
+		assert_eq!(reserved, amount.saturating_mul(n.into()));
+	}
+
+	// The resignation unwinds the first page of the delegations.
+	resign {
+		let d in 0 .. T::UnwindBatchSize::get();
+		let caller = funded_account::<T>("resolver", 0);
+		create_resolver::<T>(&caller, T::ActivationStakeAmount::get());
+		add_delegators::<T>(&caller, d);
+	}: _(RawOrigin::Signed(caller.clone()))
+	verify {
+		let release_at = pallet_timestamp::Pallet::<T>::get() + T::UndelegateTime::get();
+		assert_last_event::<T>(Event::ResolverTerminated { account: caller, release_at }.into());
+	}
+
+	continue_resign {
+		let d in 1 .. T::UnwindBatchSize::get();
+		let resolver = funded_account::<T>("resolver", 0);
+		create_resolver::<T>(&resolver, T::ActivationStakeAmount::get());
+		add_delegators::<T>(&resolver, T::UnwindBatchSize::get() + d);
+		Pallet::<T>::resign(RawOrigin::Signed(resolver.clone()).into())?;
+		let caller: T::AccountId = whitelisted_caller();
+	}: _(RawOrigin::Signed(caller), resolver.clone())
+	verify {
+		assert_last_event::<T>(
+			Event::DelegationsUnwound { resolver: resolver.clone(), unwound: d, remaining: 0 }.into(),
+		);
+		assert!(Pallet::<T>::unwinding_resolvers(&resolver).is_none());
+	}
+
+	release_pending_funds {
+		let owner = funded_account::<T>("owner", 0);
+		let amount: BalanceOf<T> = 1u32.into();
+		let pending_funds: Vec<PendingFund<T>> = (0..MAX_PENDING_FUNDS)
+			.map(|_| PendingFund::<T> { owner: owner.clone(), amount, release_at: Zero::zero() })
+			.collect();
+		<T as Config>::Currency::reserve(
+			T::FeeCurrency::get(),
+			&owner,
+			amount.saturating_mul(MAX_PENDING_FUNDS.into()),
+		)?;
+		<PendingFunds<T>>::put(pending_funds);
+		let caller: T::AccountId = whitelisted_caller();
+	}: _(RawOrigin::Signed(caller), owner.clone())
+	verify {
+		assert_last_event::<T>(
+			Event::PendingFundsReleased {
+				account: owner.clone(),
+				amount: amount.saturating_mul(MAX_PENDING_FUNDS.into()),
+			}
+			.into(),
+		);
+		assert!(Pallet::<T>::pending_funds().is_empty());
+		assert!(<T as Config>::Currency::reserved_balance(T::FeeCurrency::get(), &owner).is_zero());
+	}
+
+	set_encryption_key {
+		let caller = funded_account::<T>("caller", 0);
+		create_resolver::<T>(&caller, T::MinimumSelfStake::get());
+		let encryption_key = [1; 32];
+	}: _(RawOrigin::Signed(caller.clone()), encryption_key)
+	verify {
+		assert_last_event::<T>(Event::EncryptionKeyUpdated { account: caller, encryption_key }.into());
+	}
+
+	set_expertise {
+		let caller = funded_account::<T>("caller", 0);
+		create_resolver::<T>(&caller, T::MinimumSelfStake::get());
+		let categories: Vec<PaymentCategory> = (0..T::MaxExpertiseCategories::get())
+			.map(|code| PaymentCategory::MerchantCategoryCode(code as u16))
+			.collect();
+	}: _(RawOrigin::Signed(caller.clone()), categories.clone())
+	verify {
+		assert_last_event::<T>(Event::ExpertiseUpdated { account: caller, categories }.into());
+	}
+
+	create_pool {
+		let caller = funded_account::<T>("caller", 0);
+		let commission = T::MaxPoolCommission::get();
+	}: _(RawOrigin::Signed(caller.clone()), PoolStrategy::TopStaked, commission)
+	verify {
+		assert_last_event::<T>(
+			Event::PoolCreated {
+				pool_id: 0,
+				operator: caller,
+				strategy: PoolStrategy::TopStaked,
+				commission,
+			}
+			.into(),
+		);
+	}
+
+	// The stake is spread over `t` resolvers.
+	join_pool {
+		let t in 1 .. T::MaxPoolTargets::get();
+		let pool_id = create_pool_with_targets::<T>(t);
+		let caller = funded_account::<T>("caller", 0);
+		let amount = T::ActivationStakeAmount::get();
+	}: _(RawOrigin::Signed(caller.clone()), pool_id, amount)
+	verify {
+		assert_last_event::<T>(
+			Event::PoolJoined { pool_id, member: caller, amount, points: amount }.into(),
+		);
+	}
+
+	// The stake is undelegated from `t` resolvers.
+	leave_pool {
+		let t in 1 .. T::MaxPoolTargets::get();
+		let pool_id = create_pool_with_targets::<T>(t);
+		let caller = funded_account::<T>("caller", 0);
+		let amount = T::ActivationStakeAmount::get();
+		Pallet::<T>::join_pool(RawOrigin::Signed(caller.clone()).into(), pool_id, amount)?;
+	}: _(RawOrigin::Signed(caller.clone()), pool_id, amount)
+	verify {
+		let release_at = pallet_timestamp::Pallet::<T>::get() + T::UndelegateTime::get();
+		assert_last_event::<T>(
+			Event::PoolLeft { pool_id, member: caller, points: amount, amount, release_at }.into(),
+		);
+	}
+
+	withdraw_unbonded {
+		let t in 1 .. T::MaxPoolTargets::get();
+		let pool_id = create_pool_with_targets::<T>(t);
+		let caller = funded_account::<T>("caller", 0);
+		let amount = T::ActivationStakeAmount::get();
+		Pallet::<T>::join_pool(RawOrigin::Signed(caller.clone()).into(), pool_id, amount)?;
+		Pallet::<T>::leave_pool(RawOrigin::Signed(caller.clone()).into(), pool_id, amount)?;
+		pallet_timestamp::Pallet::<T>::set_timestamp(
+			pallet_timestamp::Pallet::<T>::get() + T::UndelegateTime::get(),
+		);
+	}: _(RawOrigin::Signed(caller.clone()), pool_id)
+	verify {
+		assert_last_event::<T>(Event::PoolWithdrawn { pool_id, member: caller, amount }.into());
+	}
+
+	// The rewards are compounded over `t` resolvers.
+	settle_pool {
+		let t in 1 .. T::MaxPoolTargets::get();
+		let pool_id = create_pool_with_targets::<T>(t);
+		let member = funded_account::<T>("member", 0);
+		let amount = T::ActivationStakeAmount::get();
+		Pallet::<T>::join_pool(RawOrigin::Signed(member).into(), pool_id, amount)?;
+		let reward = T::MinimumSelfStake::get();
+		<T as Config>::Currency::deposit(
+			T::FeeCurrency::get(),
+			&Pallet::<T>::pool_account(pool_id),
+			reward,
+		)?;
+		let caller: T::AccountId = whitelisted_caller();
+	}: _(RawOrigin::Signed(caller), pool_id)
+	verify {
+		let commission = T::MaxPoolCommission::get() * reward;
+		let pool = Pallet::<T>::nomination_pools(pool_id).unwrap();
+		assert_eq!(pool.stake, amount + reward - commission);
+	}
+
+	impl_benchmark_test_suite!(
+		Pallet,
+		crate::mock::ExtBuilder::default().build(),
+		crate::mock::Runtime
+	);
+}
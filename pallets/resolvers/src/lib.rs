@@ -6,28 +6,102 @@
 //! a community crowd loan to get enough delegations to become an active resolver. The delegators
 //! will share the rewards with the resolver.
 //!
+//! The stakes and the delegations are in the `FeeCurrency`, the native token unless the runtime
+//! configures another asset. They are reserved under the `RESOLVER_STAKE_RESERVE` identifier and
+//! tracked in `NamedReserves` until the pending funds are released, so the releases never consume
+//! the funds reserved by the other pallets.
+//!
+//! The deployments with compliance needs can configure `RequiredVerifiedFields`, the applicants
+//! must have at least one of these identity fields verified, e.g. a legal name verified by a KYC
+//! evaluator.
+//!
 //! ## Functions
 //!
 //! - `join_resolver_networks` - Apply to become resolver. If the `self_stake` amount reach the
 //!   `ActivationStakeAmount`, the resolver will be active. Other wise, it will be remain
-//!   `Candidacy` status.
+//!   `Candidacy` status. The resolver registers an X25519 public key, the dispute parties encrypt
+//!   the private evidence to the keys of the assigned panel.
+//! - `set_encryption_key` - Rotate the encryption key of a resolver. The resolvers bootstrapped at
+//!   genesis register their keys with it.
+//! - `set_expertise` - Declare the payment categories a resolver is experienced in, up to
+//!   `MaxExpertiseCategories`. The experts are preferred for the disputes of their categories.
 //! - `delegate` - Delegate some native token to a resolver. If the `total_stake` (`self_stake` +
-//!   `delegations`) reach the `ActivationStakeAmount`, the resolver will be active.
+//!   `delegations`) reach the `ActivationStakeAmount`, the resolver will be active. A resolver
+//!   cannot delegate to themselves, the own funds are staked as the `self_stake`.
+//! - `delegate_many` - Delegate native token to multiple resolvers in one call.
 //! - `undelegate` - Remove delegation from a resolver. If the `total_stake` drop bellow the
-//!   `ActivationStakeAmount`, the resolver will be inactive and become candidacy resolver.
+//!   `ActivationStakeAmount`, the resolver will be inactive and become candidacy resolver. The
+//!   funds are released after `UndelegateTime`, or later if the resolver is exposed to cases.
+//! - `redelegate` - Move delegation from a resolver to another resolver. The funds stay bonded,
+//!   so there is no `UndelegateTime` waiting.
 //! - `resign` - Leave the resolver position and get back the deposited tokens. The delegations will
-//!   be refunded after `UndelegateTime`.
+//!   be refunded after `UndelegateTime`. At most `UnwindBatchSize` delegations are moved to the
+//!   pending funds in the call, the rest are unwound page by page in the idle blocks.
+//! - `continue_resign` - Unwind the next page of the delegations of a resigned resolver. Anyone can
+//!   call it, so the refund does not depend on the idle blocks.
+//! - `release_pending_funds` - Release the pending funds of an account that are out of the locking
+//!   time. Anyone can call it, so the release does not depend on the off-chain worker, which
+//!   releases at most `ReleaseBatchSize` due pending funds in a run.
+//! - `create_pool` - Create a nomination pool with a delegation strategy and the operator
+//!   commission, up to `MaxPoolCommission`.
+//! - `join_pool` - Join a nomination pool with some native token, the member receives the points
+//!   of the pool.
+//! - `leave_pool` - Redeem the points of a nomination pool. The stake is undelegated and can be
+//!   withdrawn after `UndelegateTime`, or later if the resolvers are exposed to cases.
+//! - `withdraw_unbonded` - Withdraw the released stake of the left nomination pools.
+//! - `settle_pool` - Compound the rewards and account the slashes of a nomination pool. Anyone can
+//!   call it.
+//!
+//! ## Active set
+//!
+//! There are at most `MaxActiveResolvers` active resolvers. When the active set is full, the
+//! qualified resolvers are kept in the candidacy queue sorted by `total_stake`. The active set is
+//! recomputed every `EraDuration` blocks by selecting the top staked qualified resolvers.
+//!
+//! ## Case exposure
+//!
+//! The dispute resolution opens a case of a resolver when it accepts an assignment and closes it
+//! when the resolver proposes the judgment or the assignment expires. So the delegators cannot
+//! escape the slashing by undelegating right after their resolver takes on a big case, the funds
+//! undelegated from a resolver with open cases are locked for `CaseExposureTime` at least, and
+//! until `CaseExposureTime` after its latest closed case, covering the slash windows of the cases.
+//! The delegations unwound when the resolver resigns are locked likewise.
+//!
+//! ## Nomination pools
+//!
+//! The small holders can join a nomination pool instead of picking resolvers. The stake of a pool
+//! is held by the pool account derived from `PalletId`, and delegated evenly over the top or the
+//! least staked active resolvers by the strategy of the pool, to at most `MaxPoolTargets`
+//! resolvers. The members own the bonded stake by their points. The funds paid to the pool
+//! account are the rewards, compounded after the commission of the operator, and the funds missing
+//! from it are the slashes. Both are shared by the members through the value of their points.
+//!
+//! ## Genesis config
+//!
+//! An initial resolver set can be configured at genesis, so a new network can resolve disputes
+//! from the first block. The self stakes are reserved from the resolvers, who become active if
+//! their stakes reach the `ActivationStakeAmount`.
 //!
 //! ## Traits
 //!
 //! ResolverNetwork
-//! - get_resolver - Get a random resolver from resolvers network.
+//! - get_resolver - Get a random resolver from resolvers network. The resolver is drawn from the
+//! `Randomness` source by the `SelectionStrategy` of the runtime, either uniformly or weighted by
+//! the total stakes with the built-in strategies of [`selection`]. If some of the available resolvers declare one of the preferred
+//! languages in their identities, the resolver is drawn from them only, and likewise among them
+//! for the resolvers with the expertise in the payment category. A resolver is never drawn
+//! if it is one of the dispute parties, in the same organization identity as a party, a reviewer
+//! of a party, attested by or attested a party, or if it delegated to, or received delegations from, a party above the
+//! `ConflictDelegationThreshold`.
+//! - get_encryption_key - Get the registered encryption key of a resolver.
 //! - increase_credibility - Increase a resolver's credibility
 //! A resolver can gain credibility by resolving a dispute with correct judgment. The credibility
 //! cannot exceed the `MaxCredibility`.
 //! - reduce_credibility - Reduce a resolver's credibility
 //! A resolver can lose credibility if they made a mistake in dispute resolving process. If the
 //! credibility of a resolver falls below the `MinCredibility`, it will be terminated immediately.
+//! - open_case - Expose the delegations to a resolver to a case it accepted.
+//! - close_case - Close a case of a resolver, the delegations stay exposed for `CaseExposureTime`.
 //!
 //! ## Resolver status
 //!
@@ -41,10 +115,26 @@
 //! - ResolverActivated - A resolver is activated.
 //! - ResolverInactivated - A resolver is disabled.
 //! - ResolverTerminated - A resolver is terminated.
+//! - DelegationAdded - A delegator delegated to a resolver.
+//! - DelegationRemoved - A delegator undelegated from a resolver.
+//! - DelegationMoved - A delegator redelegated from a resolver to another resolver.
+//! - PendingFundsReleased - The pending funds of an account are released.
+//! - DelegationsUnwound - A page of the delegations of a terminated resolver is unwound.
+//! - PoolCreated - A nomination pool is created.
+//! - PoolJoined - A member joined a nomination pool.
+//! - PoolLeft - A member left a nomination pool.
+//! - PoolWithdrawn - A member withdrew the released funds from a nomination pool.
+//! - PoolRewarded - The rewards of a nomination pool are compounded.
+//! - PoolSlashed - The stake of a nomination pool is slashed.
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
 pub use pallet::*;
+pub use weights::WeightInfo;
+
+pub mod migrations;
+pub mod selection;
+pub mod weights;
 
 #[cfg(test)]
 mod mock;
@@ -57,22 +147,30 @@ mod benchmarking;
 
 #[frame_support::pallet]
 pub mod pallet {
+	use crate::{selection::SelectionStrategy, WeightInfo};
 	use frame_support::{
 		dispatch::DispatchResult,
 		log,
 		pallet_prelude::*,
-		sp_runtime::traits::{Hash, Zero},
+		sp_runtime::traits::{Hash, Saturating, Zero},
 		sp_std::vec::Vec,
 		traits::Randomness,
+		transactional, PalletId,
 	};
 	use frame_system::pallet_prelude::*;
 	use orml_traits::{MultiCurrency, MultiReservableCurrency};
-	use pallet_identities::IdentitiesManager;
+	use pallet_identities::{IdentitiesManager, ResolversInspector};
 	use pallet_timestamp::{self as timestamp};
-	use primitives::{Credibility, CurrencyId};
+	use primitives::{
+		indexed_content_key, Credibility, CurrencyId, EncryptionKey, LanguageCode,
+		PaymentCategory, ReserveIdentifier, RESOLVER_STAKE_RESERVE,
+	};
 	use scale_info::TypeInfo;
 	use sp_io::offchain_index;
-	use sp_runtime::RuntimeDebug;
+	use sp_runtime::{
+		helpers_128bit::multiply_by_rational, traits::AccountIdConversion, Permill, RuntimeDebug,
+		SaturatedConversion,
+	};
 
 	#[cfg(feature = "std")]
 	use serde::{Deserialize, Serialize};
@@ -81,8 +179,15 @@ pub mod pallet {
 	pub trait Config: frame_system::Config + timestamp::Config {
 		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
 		type Currency: MultiReservableCurrency<Self::AccountId, CurrencyId = CurrencyId<Self::Hash>>;
+		/// The currency of the resolver stakes and the delegations.
+		#[pallet::constant]
+		type FeeCurrency: Get<CurrencyId<Self::Hash>>;
 		type IdentitiesManager: IdentitiesManager<Self::AccountId>;
+		/// The randomness source to select resolvers. A VRF based source (e.g. BABE) should be
+		/// used in production since the collective flip is predictable by block producers.
 		type Randomness: Randomness<Self::Hash, Self::BlockNumber>;
+		/// The strategy to draw a resolver among the eligible ones, see [`crate::selection`].
+		type SelectionStrategy: SelectionStrategy<Self::AccountId, BalanceOf<Self>, Self::Hashing>;
 		#[pallet::constant]
 		type PenaltyTokenLockTime: Get<MomentOf<Self>>;
 		#[pallet::constant]
@@ -91,25 +196,82 @@ pub mod pallet {
 		type ActivationStakeAmount: Get<BalanceOf<Self>>;
 		#[pallet::constant]
 		type UndelegateTime: Get<MomentOf<Self>>;
+		/// The time the delegations to a resolver stay exposed after it closed a case, covering
+		/// the slash windows of the case. The delegations removed from a resolver with open cases
+		/// are locked for it at least.
+		#[pallet::constant]
+		type CaseExposureTime: Get<MomentOf<Self>>;
 		/// The required credibility to become a resolver.
 		#[pallet::constant]
 		type RequiredCredibility: Get<Credibility>;
+		/// The identity field names of which at least one must be verified to become a resolver.
+		/// No verified field is required if it is empty.
+		#[pallet::constant]
+		type RequiredVerifiedFields: Get<Vec<Vec<u8>>>;
+		/// The maximum number of active resolvers.
+		#[pallet::constant]
+		type MaxActiveResolvers: Get<u32>;
+		/// The number of blocks of an era, must not be zero. The active set is recomputed at the
+		/// start of each era.
+		#[pallet::constant]
+		type EraDuration: Get<Self::BlockNumber>;
+		/// The maximum number of delegations of a terminated resolver moved to the pending funds
+		/// in one call or one page of the idle blocks.
+		#[pallet::constant]
+		type UnwindBatchSize: Get<u32>;
+		/// The maximum number of due pending funds released in one run of the offchain worker.
+		#[pallet::constant]
+		type ReleaseBatchSize: Get<u32>;
+		/// A resolver is not drawn for a dispute if the resolver and one of the dispute parties
+		/// delegated more than the threshold to the other.
+		#[pallet::constant]
+		type ConflictDelegationThreshold: Get<BalanceOf<Self>>;
+		/// The maximum number of payment categories a resolver can declare the expertise in.
+		#[pallet::constant]
+		type MaxExpertiseCategories: Get<u32>;
+		/// The pallet id, the accounts of the nomination pools are derived from it.
+		#[pallet::constant]
+		type PalletId: Get<PalletId>;
+		/// The maximum number of resolvers a nomination pool spreads its stake over.
+		#[pallet::constant]
+		type MaxPoolTargets: Get<u32>;
+		/// The maximum commission an operator can take from the rewards of a nomination pool.
+		#[pallet::constant]
+		type MaxPoolCommission: Get<Permill>;
+		/// Weight information for extrinsics in this pallet.
+		type WeightInfo: WeightInfo;
 	}
 
 	type AccountOf<T> = <T as frame_system::Config>::AccountId;
 	type BalanceOf<T> =
 		<<T as Config>::Currency as MultiCurrency<<T as frame_system::Config>::AccountId>>::Balance;
 	type MomentOf<T> = <T as pallet_timestamp::Config>::Moment;
+	pub type PoolId = u32;
 
 	pub trait ResolversNetwork<AccountId, Hash> {
+		/// Draw an active resolver not in `selected` and without a conflict of interest with the
+		/// dispute `parties`, preferring the resolvers speaking one of the `languages` and then
+		/// the resolvers with the expertise in the payment `category`.
 		fn get_resolver(
 			payment_hash: Hash,
 			selected: Vec<AccountId>,
+			languages: Vec<LanguageCode>,
+			parties: Vec<AccountId>,
+			category: Option<PaymentCategory>,
 		) -> Result<AccountId, DispatchError>;
 
+		fn get_encryption_key(resolver_id: &AccountId) -> Option<EncryptionKey>;
+
 		fn increase_credibility(resolver_id: &AccountId, amount: Credibility) -> DispatchResult;
 
 		fn decrease_credibility(resolver_id: AccountId, amount: Credibility) -> DispatchResult;
+
+		/// Expose the delegations to the resolver to a case it accepted.
+		fn open_case(resolver_id: &AccountId);
+
+		/// Close a case of the resolver, the delegations stay exposed for the slash windows of
+		/// the case.
+		fn close_case(resolver_id: &AccountId);
 	}
 
 	#[derive(Clone, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo)]
@@ -127,6 +289,17 @@ pub mod pallet {
 		pub amount: BalanceOf<T>,
 	}
 
+	/// The cases a resolver is exposed to. The funds undelegated from the resolver are locked
+	/// until the slash windows of the cases are over.
+	#[derive(Clone, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo)]
+	#[scale_info(skip_type_params(T))]
+	pub struct CaseExposure<T: Config> {
+		/// The number of the accepted cases the resolver has not closed yet.
+		pub open_cases: u32,
+		/// The end of the slash windows of the closed cases.
+		pub exposed_until: MomentOf<T>,
+	}
+
 	#[derive(Clone, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo)]
 	#[scale_info(skip_type_params(T))]
 	#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
@@ -139,6 +312,42 @@ pub mod pallet {
 		Terminated,
 	}
 
+	/// The way a nomination pool spreads its stake over the active resolvers.
+	#[derive(Clone, Copy, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo)]
+	#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+	pub enum PoolStrategy {
+		/// Spread evenly over the top staked active resolvers.
+		TopStaked,
+		/// Spread evenly over the least staked active resolvers, to decentralize the active set.
+		LeastStaked,
+	}
+
+	/// A nomination pool aggregating the stake of its members. The members own the bonded stake
+	/// by their points, so the rewards and the slashes of the pool are shared proportionally.
+	#[derive(Clone, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo)]
+	#[scale_info(skip_type_params(T))]
+	pub struct NominationPool<T: Config> {
+		pub operator: AccountOf<T>,
+		pub strategy: PoolStrategy,
+		/// The share of the rewards paid to the operator.
+		pub commission: Permill,
+		/// The total points of the members.
+		pub points: BalanceOf<T>,
+		/// The bonded stake of the pool.
+		pub stake: BalanceOf<T>,
+		/// The funds of the leaving members waiting to be withdrawn.
+		pub unbonding: BalanceOf<T>,
+		/// The resolvers the pool delegated to.
+		pub targets: Vec<AccountOf<T>>,
+	}
+
+	#[derive(Clone, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo)]
+	#[scale_info(skip_type_params(T))]
+	pub struct PoolUnbonding<T: Config> {
+		pub amount: BalanceOf<T>,
+		pub release_at: MomentOf<T>,
+	}
+
 	#[derive(Clone, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo)]
 	#[scale_info(skip_type_params(T))]
 	pub struct Resolver<T: Config> {
@@ -151,7 +360,11 @@ pub mod pallet {
 		pub updated_at: MomentOf<T>,
 	}
 
+	/// The current storage version, see [`crate::migrations`].
+	pub(crate) const STORAGE_VERSION: StorageVersion = StorageVersion::new(3);
+
 	#[pallet::pallet]
+	#[pallet::storage_version(STORAGE_VERSION)]
 	#[pallet::without_storage_info]
 	pub struct Pallet<T>(_);
 
@@ -163,6 +376,23 @@ pub mod pallet {
 	#[pallet::getter(fn active_resolvers)]
 	pub(super) type ActiveResolvers<T: Config> = StorageValue<_, Vec<AccountOf<T>>, ValueQuery>;
 
+	/// The number of the active resolvers, kept along `ActiveResolvers` so it is read without
+	/// decoding the active set.
+	#[pallet::storage]
+	#[pallet::getter(fn active_resolvers_count)]
+	pub(super) type ActiveResolversCount<T: Config> = StorageValue<_, u32, ValueQuery>;
+
+	/// The sum of the total stakes of the resolvers, the stake securing the network.
+	#[pallet::storage]
+	#[pallet::getter(fn network_stake)]
+	pub(super) type NetworkStake<T: Config> = StorageValue<_, BalanceOf<T>, ValueQuery>;
+
+	/// The qualified resolvers waiting for a slot in the active set, sorted by total stake.
+	#[pallet::storage]
+	#[pallet::getter(fn candidacy_queue)]
+	pub(super) type CandidacyQueue<T: Config> =
+		StorageValue<_, Vec<(AccountOf<T>, BalanceOf<T>)>, ValueQuery>;
+
 	#[pallet::storage]
 	#[pallet::getter(fn blacklisted_accounts)]
 	pub(super) type BlacklistedAccounts<T: Config> = StorageValue<_, Vec<AccountOf<T>>, ValueQuery>;
@@ -171,17 +401,189 @@ pub mod pallet {
 	#[pallet::getter(fn pending_funds)]
 	pub(super) type PendingFunds<T: Config> = StorageValue<_, Vec<PendingFund<T>>, ValueQuery>;
 
+	/// The X25519 public keys of the resolvers, the private dispute evidence is encrypted to them.
+	#[pallet::storage]
+	#[pallet::getter(fn encryption_keys)]
+	pub(super) type EncryptionKeys<T: Config> =
+		StorageMap<_, Twox64Concat, AccountOf<T>, EncryptionKey>;
+
+	/// The payment categories the resolvers declared the expertise in.
+	#[pallet::storage]
+	#[pallet::getter(fn expertise)]
+	pub(super) type Expertise<T: Config> = StorageMap<
+		_,
+		Twox64Concat,
+		AccountOf<T>,
+		BoundedVec<PaymentCategory, T::MaxExpertiseCategories>,
+		ValueQuery,
+	>;
+
+	/// The terminated resolvers whose delegations are not all moved to the pending funds yet, with
+	/// the time the delegations are released at.
+	#[pallet::storage]
+	#[pallet::getter(fn unwinding_resolvers)]
+	pub(super) type UnwindingResolvers<T: Config> =
+		StorageMap<_, Twox64Concat, AccountOf<T>, MomentOf<T>>;
+
+	/// The exposure of the resolvers to their cases.
+	#[pallet::storage]
+	#[pallet::getter(fn case_exposures)]
+	pub(super) type CaseExposures<T: Config> =
+		StorageMap<_, Twox64Concat, AccountOf<T>, CaseExposure<T>>;
+
+	/// The funds reserved by the pallet by account, reserve identifier and currency. Only the
+	/// tracked funds are unreserved, so the reserves of the other pallets are never consumed.
+	#[pallet::storage]
+	#[pallet::getter(fn named_reserves)]
+	pub(super) type NamedReserves<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		AccountOf<T>,
+		Twox64Concat,
+		(ReserveIdentifier, CurrencyId<T::Hash>),
+		BalanceOf<T>,
+		ValueQuery,
+	>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn next_pool_id)]
+	pub(super) type NextPoolId<T: Config> = StorageValue<_, PoolId, ValueQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn nomination_pools)]
+	pub(super) type NominationPools<T: Config> =
+		StorageMap<_, Twox64Concat, PoolId, NominationPool<T>>;
+
+	/// The points of the pool members.
+	#[pallet::storage]
+	#[pallet::getter(fn pool_members)]
+	pub(super) type PoolMembers<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		PoolId,
+		Twox64Concat,
+		AccountOf<T>,
+		BalanceOf<T>,
+		ValueQuery,
+	>;
+
+	/// The funds of the members who left a pool, reserved by the pool account until released.
+	#[pallet::storage]
+	#[pallet::getter(fn pool_unbondings)]
+	pub(super) type PoolUnbondings<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		PoolId,
+		Twox64Concat,
+		AccountOf<T>,
+		Vec<PoolUnbonding<T>>,
+		ValueQuery,
+	>;
+
+	#[pallet::genesis_config]
+	pub struct GenesisConfig<T: Config> {
+		/// The initial resolvers with the application and self stake.
+		pub resolvers: Vec<(AccountOf<T>, Vec<u8>, BalanceOf<T>)>,
+	}
+
+	#[cfg(feature = "std")]
+	impl<T: Config> Default for GenesisConfig<T> {
+		fn default() -> Self {
+			Self { resolvers: Default::default() }
+		}
+	}
+
+	#[pallet::genesis_build]
+	impl<T: Config> GenesisBuild<T> for GenesisConfig<T> {
+		fn build(&self) {
+			for (account, application, self_stake) in self.resolvers.iter() {
+				assert!(
+					!<Resolvers<T>>::contains_key(account),
+					"Duplicate resolver in genesis config"
+				);
+				Pallet::<T>::_reserve_named(RESOLVER_STAKE_RESERVE, account, *self_stake)
+					.expect("Resolver must have enough balance to stake");
+
+				let mut resolver = Resolver::<T> {
+					application_digest: T::Hashing::hash_of(application),
+					status: ResolverStatus::Candidacy,
+					self_stake: *self_stake,
+					total_stake: *self_stake,
+					delegations: [].to_vec(),
+					updated_at: <timestamp::Pallet<T>>::get(),
+				};
+
+				Pallet::<T>::_update_resolver_status(account, &mut resolver);
+				Pallet::<T>::_increase_network_stake(*self_stake);
+				<Resolvers<T>>::insert(account, resolver);
+			}
+		}
+	}
+
 	#[pallet::event]
 	#[pallet::generate_deposit(pub(super) fn deposit_event)]
 	pub enum Event<T: Config> {
 		/// A resolver is created.
-		ResolverCreated { account: AccountOf<T> },
+		ResolverCreated { account: AccountOf<T>, self_stake: BalanceOf<T> },
 		/// A resolver is activated.
-		ResolverActivated { account: AccountOf<T> },
+		ResolverActivated { account: AccountOf<T>, total_stake: BalanceOf<T> },
 		/// A resolver is inactivated.
-		ResolverInactivated { account: AccountOf<T> },
-		/// A resolver is terminated.
-		ResolverTerminated { account: AccountOf<T> },
+		ResolverInactivated { account: AccountOf<T>, total_stake: BalanceOf<T> },
+		/// A resolver is terminated, the stakes are released at `release_at`.
+		ResolverTerminated { account: AccountOf<T>, release_at: MomentOf<T> },
+		/// A delegator delegated to a resolver.
+		DelegationAdded { resolver: AccountOf<T>, delegator: AccountOf<T>, amount: BalanceOf<T> },
+		/// A delegator undelegated from a resolver, the amount is released at `release_at`.
+		DelegationRemoved {
+			resolver: AccountOf<T>,
+			delegator: AccountOf<T>,
+			amount: BalanceOf<T>,
+			release_at: MomentOf<T>,
+		},
+		/// A delegator redelegated from a resolver to another resolver.
+		DelegationMoved {
+			delegator: AccountOf<T>,
+			from_resolver: AccountOf<T>,
+			to_resolver: AccountOf<T>,
+			amount: BalanceOf<T>,
+		},
+		/// The pending funds of an account are released.
+		PendingFundsReleased { account: AccountOf<T>, amount: BalanceOf<T> },
+		/// A resolver registered a new encryption key.
+		EncryptionKeyUpdated { account: AccountOf<T>, encryption_key: EncryptionKey },
+		/// A page of the delegations of a terminated resolver is moved to the pending funds. The
+		/// resignation is unwound once `remaining` is zero.
+		DelegationsUnwound { resolver: AccountOf<T>, unwound: u32, remaining: u32 },
+		/// A resolver declared the payment categories of its expertise.
+		ExpertiseUpdated { account: AccountOf<T>, categories: Vec<PaymentCategory> },
+		/// A nomination pool is created.
+		PoolCreated {
+			pool_id: PoolId,
+			operator: AccountOf<T>,
+			strategy: PoolStrategy,
+			commission: Permill,
+		},
+		/// A member joined a nomination pool.
+		PoolJoined {
+			pool_id: PoolId,
+			member: AccountOf<T>,
+			amount: BalanceOf<T>,
+			points: BalanceOf<T>,
+		},
+		/// A member left a nomination pool, the amount can be withdrawn at `release_at`.
+		PoolLeft {
+			pool_id: PoolId,
+			member: AccountOf<T>,
+			points: BalanceOf<T>,
+			amount: BalanceOf<T>,
+			release_at: MomentOf<T>,
+		},
+		/// A member withdrew the released funds from a nomination pool.
+		PoolWithdrawn { pool_id: PoolId, member: AccountOf<T>, amount: BalanceOf<T> },
+		/// The rewards of a nomination pool are compounded, the commission is paid to the operator.
+		PoolRewarded { pool_id: PoolId, reward: BalanceOf<T>, commission: BalanceOf<T> },
+		/// The stake of a nomination pool is slashed.
+		PoolSlashed { pool_id: PoolId, amount: BalanceOf<T> },
 	}
 
 	#[pallet::error]
@@ -194,6 +596,8 @@ pub mod pallet {
 		AccountIsBlacklisted,
 		/// The self stake have to higher than required.
 		CredibilityTooLow,
+		/// The identity has none of the `RequiredVerifiedFields` verified.
+		VerifiedFieldRequired,
 		/// The self stake have to higher than required.
 		NotMeetMinimumSelfStake,
 		/// There is no resolver related to the account.
@@ -206,10 +610,70 @@ pub mod pallet {
 		NotAResolver,
 		/// There is no active resolver at the moment.
 		NoAnyActiveResolver,
+		/// There is no pending fund of the account that can be released at the moment.
+		NoReleasableFunds,
+		/// The resolver is terminated and cannot receive delegations.
+		ResolverTerminated,
+		/// Cannot redelegate to the same resolver.
+		SameResolver,
+		/// The resolver has no delegation waiting to be unwound.
+		NothingToUnwind,
+		/// The delegations of the resigned resolver are not all unwound yet.
+		ResolverUnwinding,
+		/// A resolver cannot delegate to themselves, the own funds are staked as the self stake.
+		SelfDelegation,
+		/// The resolver declared more than `MaxExpertiseCategories` categories.
+		TooManyExpertiseCategories,
+		/// There is no nomination pool with the id.
+		PoolNotFound,
+		/// The commission is higher than `MaxPoolCommission`.
+		PoolCommissionTooHigh,
+		/// The stake of the pool is slashed out, it cannot be joined anymore.
+		PoolDepleted,
+		/// The member has less points in the pool than requested.
+		InsufficientPoolPoints,
+		/// The delegations of the pool that can be undelegated are less than requested.
+		InsufficientPoolLiquidity,
 	}
 
 	#[pallet::hooks]
 	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_initialize(block_number: T::BlockNumber) -> Weight {
+			if !(block_number % T::EraDuration::get()).is_zero() {
+				return 0
+			}
+
+			let candidates = Self::_recompute_active_resolvers();
+			T::DbWeight::get().reads_writes(2 + candidates as Weight, 2 + candidates as Weight)
+		}
+
+		// Unwind the resignations page by page with the remaining weight of the block.
+		fn on_idle(_block_number: T::BlockNumber, remaining_weight: Weight) -> Weight {
+			let page_weight = T::WeightInfo::continue_resign(T::UnwindBatchSize::get())
+				.saturating_add(T::DbWeight::get().reads(1));
+			let mut used_weight: Weight = 0;
+
+			while used_weight.saturating_add(page_weight) <= remaining_weight {
+				let resolver_account = match <UnwindingResolvers<T>>::iter_keys().next() {
+					Some(resolver_account) => resolver_account,
+					None => break,
+				};
+				used_weight = used_weight.saturating_add(page_weight);
+
+				if let Err(err) = Self::_unwind_delegations(&resolver_account) {
+					log::error!(
+						target: "Resolvers network",
+						"Fail to unwind the delegations of resolver {:?}: {:?}",
+						resolver_account,
+						err,
+					);
+					break
+				}
+			}
+
+			used_weight
+		}
+
 		fn offchain_worker(block_number: T::BlockNumber) {
 			if let Err(err) = Self::run_offchain_worker() {
 				log::error!(
@@ -226,22 +690,48 @@ pub mod pallet {
 				);
 			}
 		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade() -> Result<(), &'static str> {
+			Self::do_try_state()
+		}
 	}
 
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {
-		#[pallet::weight(1_000)]
+		#[pallet::weight(T::WeightInfo::join_resolvers_network())]
 		pub fn join_resolvers_network(
 			origin: OriginFor<T>,
 			application: Vec<u8>,
 			self_stake: BalanceOf<T>,
+			encryption_key: EncryptionKey,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			Self::_create_resolver(sender, application, self_stake, encryption_key)?;
+			Ok(())
+		}
+
+		#[pallet::weight(T::WeightInfo::set_encryption_key())]
+		pub fn set_encryption_key(
+			origin: OriginFor<T>,
+			encryption_key: EncryptionKey,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			Self::_set_encryption_key(sender, encryption_key)?;
+			Ok(())
+		}
+
+		#[pallet::weight(T::WeightInfo::set_expertise())]
+		pub fn set_expertise(
+			origin: OriginFor<T>,
+			categories: Vec<PaymentCategory>,
 		) -> DispatchResult {
 			let sender = ensure_signed(origin)?;
-			Self::_create_resolver(sender, application, self_stake)?;
+			Self::_set_expertise(sender, categories)?;
 			Ok(())
 		}
 
-		#[pallet::weight(1_000)]
+		#[pallet::weight(T::WeightInfo::delegate())]
 		pub fn delegate(
 			origin: OriginFor<T>,
 			resolver: AccountOf<T>,
@@ -252,7 +742,7 @@ pub mod pallet {
 			Ok(())
 		}
 
-		#[pallet::weight(1_000)]
+		#[pallet::weight(T::WeightInfo::undelegate())]
 		pub fn undelegate(
 			origin: OriginFor<T>,
 			resolver: AccountOf<T>,
@@ -263,15 +753,195 @@ pub mod pallet {
 			Ok(())
 		}
 
-		#[pallet::weight(1_000)]
+		#[pallet::weight(T::WeightInfo::redelegate())]
+		pub fn redelegate(
+			origin: OriginFor<T>,
+			from_resolver: AccountOf<T>,
+			to_resolver: AccountOf<T>,
+			amount: BalanceOf<T>,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			Self::_redelegate(sender, from_resolver, to_resolver, amount)?;
+			Ok(())
+		}
+
+		#[pallet::weight(T::WeightInfo::delegate_many(delegations.len() as u32))]
+		#[transactional]
+		pub fn delegate_many(
+			origin: OriginFor<T>,
+			delegations: Vec<(AccountOf<T>, BalanceOf<T>)>,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			for (resolver, amount) in delegations {
+				Self::_delegate(sender.clone(), resolver, amount)?;
+			}
+			Ok(())
+		}
+
+		#[pallet::weight(T::WeightInfo::resign(T::UnwindBatchSize::get()))]
 		pub fn resign(origin: OriginFor<T>) -> DispatchResult {
 			let sender = ensure_signed(origin)?;
 			Self::_terminate_resolver(sender, false)?;
 			Ok(())
 		}
+
+		#[pallet::weight(T::WeightInfo::continue_resign(T::UnwindBatchSize::get()))]
+		pub fn continue_resign(
+			origin: OriginFor<T>,
+			resolver: AccountOf<T>,
+		) -> DispatchResultWithPostInfo {
+			ensure_signed(origin)?;
+			Self::_unwind_delegations(&resolver)?;
+			Ok(Pays::No.into())
+		}
+
+		#[pallet::weight(T::WeightInfo::release_pending_funds())]
+		pub fn release_pending_funds(
+			origin: OriginFor<T>,
+			account: AccountOf<T>,
+		) -> DispatchResultWithPostInfo {
+			ensure_signed(origin)?;
+			let released = Self::_release_account_pending_funds(&account);
+			ensure!(released > 0, <Error<T>>::NoReleasableFunds);
+			Ok(Pays::No.into())
+		}
+
+		#[pallet::weight(T::WeightInfo::create_pool())]
+		pub fn create_pool(
+			origin: OriginFor<T>,
+			strategy: PoolStrategy,
+			commission: Permill,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			Self::_create_pool(sender, strategy, commission)?;
+			Ok(())
+		}
+
+		#[pallet::weight(T::WeightInfo::join_pool(T::MaxPoolTargets::get()))]
+		#[transactional]
+		pub fn join_pool(
+			origin: OriginFor<T>,
+			pool_id: PoolId,
+			amount: BalanceOf<T>,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			Self::_join_pool(sender, pool_id, amount)?;
+			Ok(())
+		}
+
+		#[pallet::weight(T::WeightInfo::leave_pool(T::MaxPoolTargets::get()))]
+		#[transactional]
+		pub fn leave_pool(
+			origin: OriginFor<T>,
+			pool_id: PoolId,
+			points: BalanceOf<T>,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			Self::_leave_pool(sender, pool_id, points)?;
+			Ok(())
+		}
+
+		#[pallet::weight(T::WeightInfo::withdraw_unbonded(T::MaxPoolTargets::get()))]
+		#[transactional]
+		pub fn withdraw_unbonded(origin: OriginFor<T>, pool_id: PoolId) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			Self::_withdraw_unbonded(sender, pool_id)?;
+			Ok(())
+		}
+
+		#[pallet::weight(T::WeightInfo::settle_pool(T::MaxPoolTargets::get()))]
+		#[transactional]
+		pub fn settle_pool(origin: OriginFor<T>, pool_id: PoolId) -> DispatchResult {
+			ensure_signed(origin)?;
+			let mut pool = Self::nomination_pools(pool_id).ok_or(<Error<T>>::PoolNotFound)?;
+			Self::_settle_pool(pool_id, &mut pool)?;
+			<NominationPools<T>>::insert(pool_id, pool);
+			Ok(())
+		}
 	}
 
 	impl<T: Config> Pallet<T> {
+		/// The account holding the stake of a nomination pool.
+		pub fn pool_account(pool_id: PoolId) -> AccountOf<T> {
+			T::PalletId::get().into_sub_account(pool_id)
+		}
+
+		/// The bonded stake a member owns in a nomination pool by its points.
+		pub fn pool_member_stake(pool_id: PoolId, member: &AccountOf<T>) -> BalanceOf<T> {
+			Self::nomination_pools(pool_id)
+				.map(|pool| {
+					Self::_pool_convert(
+						Self::pool_members(pool_id, member),
+						pool.points,
+						pool.stake,
+					)
+				})
+				.unwrap_or_else(Zero::zero)
+		}
+
+		/// Check the accounting invariants of the resolvers network, run by `try-runtime` after
+		/// the runtime upgrades:
+		/// - The total stake of a resolver is its self stake and the sum of its delegations.
+		/// - The stake of the network is the sum of the total stakes of the resolvers.
+		/// - The active resolvers are all in the active status, and counted.
+		/// - The pending funds of an account are covered by its reserved stakes.
+		#[cfg(any(feature = "try-runtime", test))]
+		pub fn do_try_state() -> Result<(), &'static str> {
+			use frame_support::sp_std::collections::btree_map::BTreeMap;
+
+			let mut network_stake = BalanceOf::<T>::zero();
+			for (_, resolver) in <Resolvers<T>>::iter() {
+				network_stake += resolver.total_stake;
+				let delegated = resolver
+					.delegations
+					.iter()
+					.fold(BalanceOf::<T>::zero(), |total, delegation| total + delegation.amount);
+				ensure!(
+					resolver.total_stake == resolver.self_stake + delegated,
+					"The total stake of a resolver does not match its stakes"
+				);
+			}
+			ensure!(
+				Self::network_stake() == network_stake,
+				"The stake of the network does not match the stakes of the resolvers"
+			);
+
+			let active_resolvers = Self::active_resolvers();
+			ensure!(
+				Self::active_resolvers_count() as usize == active_resolvers.len(),
+				"The count of the active resolvers does not match the active set"
+			);
+			for account in active_resolvers {
+				ensure!(
+					Self::resolvers(&account)
+						.map_or(false, |resolver| resolver.status == ResolverStatus::Active),
+					"An active resolver is not in the active status"
+				);
+			}
+
+			let currency_id = T::FeeCurrency::get();
+			let mut pending_funds: BTreeMap<AccountOf<T>, BalanceOf<T>> = BTreeMap::new();
+			for fund in Self::pending_funds() {
+				let amount = pending_funds.entry(fund.owner).or_insert_with(Zero::zero);
+				*amount += fund.amount;
+			}
+			for (owner, amount) in pending_funds {
+				ensure!(
+					amount <= Self::named_reserves(&owner, (RESOLVER_STAKE_RESERVE, currency_id)),
+					"The pending funds of an account exceed its reserved stakes"
+				);
+			}
+
+			for (who, (_, currency_id), amount) in <NamedReserves<T>>::iter() {
+				ensure!(
+					amount <= T::Currency::reserved_balance(currency_id, &who),
+					"The tracked reserves of an account exceed its reserved balance"
+				);
+			}
+
+			Ok(())
+		}
+
 		fn run_offchain_worker() -> DispatchResult {
 			Self::_release_pending_funds()?;
 			Ok(())
@@ -281,9 +951,12 @@ pub mod pallet {
 			sender: AccountOf<T>,
 			application: Vec<u8>,
 			self_stake: BalanceOf<T>,
+			encryption_key: EncryptionKey,
 		) -> DispatchResult {
 			// The blacklisted account cannot join the network.
 			ensure!(!(<BlacklistedAccounts<T>>::get().contains(&sender)), <Error<T>>::AccountIsBlacklisted);
+			// The delegations of a previous resignation must be unwound before joining again.
+			ensure!(!<UnwindingResolvers<T>>::contains_key(&sender), <Error<T>>::ResolverUnwinding);
 			// The identity is required to join resolver networks.
 			ensure!(T::IdentitiesManager::has_identity(&sender), <Error<T>>::IdentityRequired);
 			// The identity credibility must be higher than required level to join resolver
@@ -293,18 +966,26 @@ pub mod pallet {
 				resolver_credibility > T::RequiredCredibility::get(),
 				<Error<T>>::CredibilityTooLow
 			);
+			let required_fields = T::RequiredVerifiedFields::get();
+			ensure!(
+				required_fields.is_empty() ||
+					required_fields
+						.iter()
+						.any(|name| T::IdentitiesManager::is_field_verified(&sender, name)),
+				<Error<T>>::VerifiedFieldRequired
+			);
 			ensure!(self_stake >= T::MinimumSelfStake::get(), <Error<T>>::NotMeetMinimumSelfStake);
 			ensure!(
-				T::Currency::free_balance(CurrencyId::<T::Hash>::Native, &sender) >= self_stake,
+				T::Currency::free_balance(T::FeeCurrency::get(), &sender) >= self_stake,
 				<Error<T>>::InsufficientBalance,
 			);
 
 			let now = <timestamp::Pallet<T>>::get();
 			let application_digest = T::Hashing::hash_of(&application);
 
-			offchain_index::set(&application_digest.encode(), &application);
+			offchain_index::set(&indexed_content_key(&application_digest), &application);
 
-			T::Currency::reserve(CurrencyId::<T::Hash>::Native, &sender, self_stake)?;
+			Self::_reserve_named(RESOLVER_STAKE_RESERVE, &sender, self_stake)?;
 
 			let mut resolver = Resolver::<T> {
 				application_digest,
@@ -315,14 +996,44 @@ pub mod pallet {
 				updated_at: now,
 			};
 
-			if resolver.total_stake >= T::ActivationStakeAmount::get() {
-				resolver.status = ResolverStatus::Active;
-				Self::_add_active_resolver(sender.clone());
-			};
+			Self::_update_resolver_status(&sender, &mut resolver);
+			Self::_increase_network_stake(self_stake);
 
 			<Resolvers<T>>::insert(&sender, resolver);
+			<EncryptionKeys<T>>::insert(&sender, encryption_key);
+
+			Self::deposit_event(Event::ResolverCreated { account: sender, self_stake });
+
+			Ok(())
+		}
+
+		fn _set_encryption_key(
+			sender: AccountOf<T>,
+			encryption_key: EncryptionKey,
+		) -> DispatchResult {
+			let resolver = Self::resolvers(&sender).ok_or(<Error<T>>::ResolverNotFound)?;
+			ensure!(resolver.status != ResolverStatus::Terminated, <Error<T>>::ResolverTerminated);
 
-			Self::deposit_event(Event::ResolverCreated { account: sender });
+			<EncryptionKeys<T>>::insert(&sender, encryption_key);
+			Self::deposit_event(Event::EncryptionKeyUpdated { account: sender, encryption_key });
+
+			Ok(())
+		}
+
+		fn _set_expertise(sender: AccountOf<T>, categories: Vec<PaymentCategory>) -> DispatchResult {
+			let resolver = Self::resolvers(&sender).ok_or(<Error<T>>::ResolverNotFound)?;
+			ensure!(resolver.status != ResolverStatus::Terminated, <Error<T>>::ResolverTerminated);
+
+			let expertise: BoundedVec<PaymentCategory, T::MaxExpertiseCategories> = categories
+				.clone()
+				.try_into()
+				.map_err(|_| <Error<T>>::TooManyExpertiseCategories)?;
+			if expertise.is_empty() {
+				<Expertise<T>>::remove(&sender);
+			} else {
+				<Expertise<T>>::insert(&sender, expertise);
+			}
+			Self::deposit_event(Event::ExpertiseUpdated { account: sender, categories });
 
 			Ok(())
 		}
@@ -332,89 +1043,147 @@ pub mod pallet {
 			resolver_account: AccountOf<T>,
 			amount: BalanceOf<T>,
 		) -> DispatchResult {
+			ensure!(sender != resolver_account, <Error<T>>::SelfDelegation);
 			ensure!(
-				T::Currency::free_balance(CurrencyId::<T::Hash>::Native, &sender) >= amount,
+				T::Currency::free_balance(T::FeeCurrency::get(), &sender) >= amount,
 				<Error<T>>::InsufficientBalance,
 			);
 
 			let mut resolver =
 				Self::resolvers(&resolver_account).ok_or(<Error<T>>::ResolverNotFound)?;
+			ensure!(resolver.status != ResolverStatus::Terminated, <Error<T>>::ResolverTerminated);
 
-			T::Currency::reserve(CurrencyId::<T::Hash>::Native, &sender, amount)?;
+			Self::_reserve_named(RESOLVER_STAKE_RESERVE, &sender, amount)?;
 
-			let delegation_position = resolver
-				.delegations
-				.iter()
-				.position(|delegation| delegation.delegator == sender);
+			Self::_increase_delegation(&resolver_account, &mut resolver, sender.clone(), amount);
+			<Resolvers<T>>::insert(&resolver_account, resolver);
 
-			match delegation_position {
-				Some(p) => {
-					resolver.delegations[p].amount += amount;
-				},
-				None => {
-					let delegation = Delegation::<T> { delegator: sender, amount };
+			Self::deposit_event(Event::DelegationAdded {
+				resolver: resolver_account,
+				delegator: sender,
+				amount,
+			});
 
-					resolver.delegations.push(delegation);
-				},
-			}
+			Ok(())
+		}
 
-			resolver.total_stake += amount;
+		fn _undelegate(
+			sender: AccountOf<T>,
+			resolver_account: AccountOf<T>,
+			amount: BalanceOf<T>,
+		) -> DispatchResult {
+			let mut resolver =
+				Self::resolvers(&resolver_account).ok_or(<Error<T>>::ResolverNotFound)?;
 
-			if resolver.total_stake >= T::ActivationStakeAmount::get() {
-				resolver.status = ResolverStatus::Active;
-				Self::_add_active_resolver(resolver_account.clone());
-				Self::deposit_event(Event::ResolverActivated { account: resolver_account.clone() });
-			};
+			Self::_decrease_delegation(&resolver_account, &mut resolver, &sender, amount)?;
+
+			let release_at = Self::_undelegation_release_at(&resolver_account);
+
+			let pending_fund = PendingFund::<T> { owner: sender.clone(), amount, release_at };
+
+			<PendingFunds<T>>::mutate(|pending_funds| {
+				pending_funds.push(pending_fund);
+			});
 
 			<Resolvers<T>>::insert(&resolver_account, resolver);
 
+			Self::deposit_event(Event::DelegationRemoved {
+				resolver: resolver_account,
+				delegator: sender,
+				amount,
+				release_at,
+			});
+
 			Ok(())
 		}
 
-		fn _undelegate(
+		fn _redelegate(
 			sender: AccountOf<T>,
-			resolver_account: AccountOf<T>,
+			from_account: AccountOf<T>,
+			to_account: AccountOf<T>,
 			amount: BalanceOf<T>,
 		) -> DispatchResult {
-			let mut resolver =
-				Self::resolvers(&resolver_account).ok_or(<Error<T>>::ResolverNotFound)?;
+			ensure!(from_account != to_account, <Error<T>>::SameResolver);
+			ensure!(sender != to_account, <Error<T>>::SelfDelegation);
+
+			let mut from_resolver =
+				Self::resolvers(&from_account).ok_or(<Error<T>>::ResolverNotFound)?;
+			let mut to_resolver =
+				Self::resolvers(&to_account).ok_or(<Error<T>>::ResolverNotFound)?;
+			ensure!(
+				to_resolver.status != ResolverStatus::Terminated,
+				<Error<T>>::ResolverTerminated
+			);
+
+			// The funds stay reserved, only the delegation is moved.
+			Self::_decrease_delegation(&from_account, &mut from_resolver, &sender, amount)?;
+			Self::_increase_delegation(&to_account, &mut to_resolver, sender.clone(), amount);
+
+			<Resolvers<T>>::insert(&from_account, from_resolver);
+			<Resolvers<T>>::insert(&to_account, to_resolver);
+
+			Self::deposit_event(Event::DelegationMoved {
+				delegator: sender,
+				from_resolver: from_account,
+				to_resolver: to_account,
+				amount,
+			});
+
+			Ok(())
+		}
 
+		fn _increase_delegation(
+			resolver_account: &AccountOf<T>,
+			resolver: &mut Resolver<T>,
+			delegator: AccountOf<T>,
+			amount: BalanceOf<T>,
+		) {
 			let delegation_position = resolver
 				.delegations
 				.iter()
-				.position(|delegation| delegation.delegator == sender);
+				.position(|delegation| delegation.delegator == delegator);
 
 			match delegation_position {
-				None => return Err(<Error<T>>::DelegationNotFound.into()),
 				Some(p) => {
-					if amount > resolver.delegations[p].amount {
-						return Err(<Error<T>>::InvalidAmount.into())
-					}
+					resolver.delegations[p].amount += amount;
+				},
+				None => {
+					let delegation = Delegation::<T> { delegator, amount };
 
-					resolver.delegations[p].amount -= amount;
-					resolver.total_stake -= amount;
+					resolver.delegations.push(delegation);
+				},
+			}
 
-					let release_at = <timestamp::Pallet<T>>::get() + T::UndelegateTime::get();
+			resolver.total_stake += amount;
+			Self::_increase_network_stake(amount);
 
-					let pending_fund = PendingFund::<T> { owner: sender, amount, release_at };
+			Self::_update_resolver_status(resolver_account, resolver);
+		}
 
-					<PendingFunds<T>>::mutate(|pending_funds| {
-						pending_funds.push(pending_fund);
-					});
+		fn _decrease_delegation(
+			resolver_account: &AccountOf<T>,
+			resolver: &mut Resolver<T>,
+			delegator: &AccountOf<T>,
+			amount: BalanceOf<T>,
+		) -> DispatchResult {
+			// The delegations of a terminated resolver are refunded by the unwinding.
+			ensure!(resolver.status != ResolverStatus::Terminated, <Error<T>>::ResolverTerminated);
 
-					if resolver.total_stake < T::ActivationStakeAmount::get() {
-						resolver.status = ResolverStatus::Candidacy;
-						Self::_remove_active_resolver(resolver_account.clone());
-						Self::deposit_event(Event::ResolverInactivated {
-							account: resolver_account.clone(),
-						});
-					};
+			let p = resolver
+				.delegations
+				.iter()
+				.position(|delegation| delegation.delegator == *delegator)
+				.ok_or(<Error<T>>::DelegationNotFound)?;
 
-					resolver.delegations.retain(|delegation| delegation.amount > Zero::zero());
+			ensure!(amount <= resolver.delegations[p].amount, <Error<T>>::InvalidAmount);
 
-					<Resolvers<T>>::insert(&resolver_account, resolver);
-				},
-			}
+			resolver.delegations[p].amount -= amount;
+			resolver.total_stake -= amount;
+			Self::_decrease_network_stake(amount);
+
+			Self::_update_resolver_status(resolver_account, resolver);
+
+			resolver.delegations.retain(|delegation| delegation.amount > Zero::zero());
 
 			Ok(())
 		}
@@ -427,9 +1196,11 @@ pub mod pallet {
 				Self::resolvers(&resolver_account).ok_or(<Error<T>>::NotAResolver)?;
 
 			let release_at = if has_penalty {
-				<timestamp::Pallet<T>>::get() + T::PenaltyTokenLockTime::get()
+				let penalty_release_at =
+					<timestamp::Pallet<T>>::get() + T::PenaltyTokenLockTime::get();
+				penalty_release_at.max(Self::_undelegation_release_at(&resolver_account))
 			} else {
-				<timestamp::Pallet<T>>::get() + T::UndelegateTime::get()
+				Self::_undelegation_release_at(&resolver_account)
 			};
 
 			let resolver_pending_fund = PendingFund::<T> {
@@ -441,46 +1212,224 @@ pub mod pallet {
 				pending_funds.push(resolver_pending_fund);
 			});
 
-			for delegation in resolver.delegations.iter() {
-				let pending_fund = PendingFund::<T> {
-					owner: delegation.delegator.clone(),
+			// The total stake is the delegations left to be unwound.
+			resolver.total_stake -= resolver.self_stake;
+			Self::_decrease_network_stake(resolver.self_stake);
+			resolver.self_stake = Zero::zero();
+			resolver.status = ResolverStatus::Terminated;
+
+			Self::_remove_active_resolver(resolver_account.clone());
+			Self::_remove_queued_resolver(&resolver_account);
+			<Resolvers<T>>::insert(&resolver_account, resolver);
+
+			// The first page of the delegations is unwound right away, the rest by
+			// `continue_resign` or in the idle blocks.
+			<UnwindingResolvers<T>>::insert(&resolver_account, release_at);
+			Self::_unwind_delegations(&resolver_account)?;
+
+			Self::deposit_event(Event::ResolverTerminated {
+				account: resolver_account,
+				release_at,
+			});
+
+			Ok(())
+		}
+
+		// The time the funds undelegated from the resolver are released at, extended to cover the
+		// slash windows of the open and the recently closed cases of the resolver.
+		fn _undelegation_release_at(resolver_account: &AccountOf<T>) -> MomentOf<T> {
+			let now = <timestamp::Pallet<T>>::get();
+			let mut release_at = now + T::UndelegateTime::get();
+
+			if let Some(exposure) = Self::case_exposures(resolver_account) {
+				if exposure.open_cases > 0 {
+					release_at = release_at.max(now + T::CaseExposureTime::get());
+				}
+				release_at = release_at.max(exposure.exposed_until);
+			}
+
+			release_at
+		}
+
+		// Move a page of the delegations of a terminated resolver to the pending funds. Returns the
+		// number of the remaining delegations.
+		fn _unwind_delegations(resolver_account: &AccountOf<T>) -> Result<usize, DispatchError> {
+			let release_at =
+				Self::unwinding_resolvers(resolver_account).ok_or(<Error<T>>::NothingToUnwind)?;
+			let mut resolver =
+				Self::resolvers(resolver_account).ok_or(<Error<T>>::ResolverNotFound)?;
+
+			let page_size = resolver.delegations.len().min(T::UnwindBatchSize::get() as usize);
+			let unwound: Vec<Delegation<T>> = resolver.delegations.drain(..page_size).collect();
+			for delegation in unwound.iter() {
+				resolver.total_stake -= delegation.amount;
+				Self::_decrease_network_stake(delegation.amount);
+			}
+
+			<PendingFunds<T>>::mutate(|pending_funds| {
+				pending_funds.extend(unwound.into_iter().map(|delegation| PendingFund::<T> {
+					owner: delegation.delegator,
 					amount: delegation.amount,
 					release_at,
-				};
+				}));
+			});
+
+			let remaining = resolver.delegations.len();
+			if remaining == 0 {
+				<UnwindingResolvers<T>>::remove(resolver_account);
+			}
+			<Resolvers<T>>::insert(resolver_account, resolver);
 
-				<PendingFunds<T>>::mutate(|pending_funds| {
-					pending_funds.push(pending_fund);
+			if page_size > 0 {
+				Self::deposit_event(Event::DelegationsUnwound {
+					resolver: resolver_account.clone(),
+					unwound: page_size as u32,
+					remaining: remaining as u32,
 				});
 			}
 
-			resolver.total_stake = Zero::zero();
-			resolver.self_stake = Zero::zero();
-			resolver.delegations = [].to_vec();
-			resolver.status = ResolverStatus::Terminated;
+			Ok(remaining)
+		}
 
-			Self::_remove_active_resolver(resolver_account.clone());
-			<Resolvers<T>>::insert(&resolver_account, resolver);
-			Self::deposit_event(Event::ResolverTerminated { account: resolver_account });
+		// Update the status of the resolver after the stake is changed.
+		fn _update_resolver_status(resolver_account: &AccountOf<T>, resolver: &mut Resolver<T>) {
+			let qualified = resolver.total_stake >= T::ActivationStakeAmount::get();
+
+			match resolver.status {
+				ResolverStatus::Active if !qualified => {
+					resolver.status = ResolverStatus::Candidacy;
+					Self::_remove_active_resolver(resolver_account.clone());
+					Self::deposit_event(Event::ResolverInactivated {
+						account: resolver_account.clone(),
+						total_stake: resolver.total_stake,
+					});
+				},
+				ResolverStatus::Candidacy if qualified => {
+					let active_resolvers = <ActiveResolvers<T>>::decode_len().unwrap_or_default();
+
+					if active_resolvers < T::MaxActiveResolvers::get() as usize {
+						Self::_remove_queued_resolver(resolver_account);
+						resolver.status = ResolverStatus::Active;
+						Self::_add_active_resolver(resolver_account.clone());
+						Self::deposit_event(Event::ResolverActivated {
+							account: resolver_account.clone(),
+							total_stake: resolver.total_stake,
+						});
+					} else {
+						// The active set is full, wait for the next era in the queue.
+						Self::_queue_resolver(resolver_account, resolver.total_stake);
+					}
+				},
+				ResolverStatus::Candidacy => Self::_remove_queued_resolver(resolver_account),
+				_ => {},
+			}
+		}
 
-			Ok(())
+		fn _queue_resolver(resolver_account: &AccountOf<T>, total_stake: BalanceOf<T>) {
+			<CandidacyQueue<T>>::mutate(|queue| {
+				queue.retain(|(account, _)| account != resolver_account);
+				let position =
+					queue.iter().position(|(_, stake)| *stake < total_stake).unwrap_or(queue.len());
+				queue.insert(position, (resolver_account.clone(), total_stake));
+			});
+		}
+
+		fn _remove_queued_resolver(resolver_account: &AccountOf<T>) {
+			<CandidacyQueue<T>>::mutate(|queue| {
+				queue.retain(|(account, _)| account != resolver_account)
+			});
+		}
+
+		// Select the top staked qualified resolvers to the active set. Returns the number of
+		// candidates.
+		fn _recompute_active_resolvers() -> usize {
+			let previous_active_resolvers = <ActiveResolvers<T>>::get();
+			let mut candidates = <CandidacyQueue<T>>::get();
+
+			for account in previous_active_resolvers.iter() {
+				if let Some(resolver) = Self::resolvers(account) {
+					candidates.push((account.clone(), resolver.total_stake));
+				}
+			}
+
+			let number_of_candidates = candidates.len();
+			candidates.sort_by(|a, b| b.1.cmp(&a.1));
+
+			let max_active_resolvers = T::MaxActiveResolvers::get() as usize;
+			let queue = candidates.split_off(max_active_resolvers.min(number_of_candidates));
+			let active_resolvers: Vec<AccountOf<T>> =
+				candidates.into_iter().map(|(account, _)| account).collect();
+
+			for account in previous_active_resolvers.iter() {
+				if !active_resolvers.contains(account) {
+					<Resolvers<T>>::mutate(account, |resolver| {
+						if let Some(resolver) = resolver {
+							resolver.status = ResolverStatus::Candidacy;
+							Self::deposit_event(Event::ResolverInactivated {
+								account: account.clone(),
+								total_stake: resolver.total_stake,
+							});
+						}
+					});
+				}
+			}
+
+			for account in active_resolvers.iter() {
+				if !previous_active_resolvers.contains(account) {
+					<Resolvers<T>>::mutate(account, |resolver| {
+						if let Some(resolver) = resolver {
+							resolver.status = ResolverStatus::Active;
+							Self::deposit_event(Event::ResolverActivated {
+								account: account.clone(),
+								total_stake: resolver.total_stake,
+							});
+						}
+					});
+				}
+			}
+
+			<ActiveResolversCount<T>>::put(active_resolvers.len() as u32);
+			<ActiveResolvers<T>>::set(active_resolvers);
+			<CandidacyQueue<T>>::set(queue);
+
+			number_of_candidates
 		}
 
 		fn _add_active_resolver(resolver: AccountOf<T>) {
-			<ActiveResolvers<T>>::mutate(|resolvers| resolvers.push(resolver));
+			let count = <ActiveResolvers<T>>::mutate(|resolvers| {
+				resolvers.push(resolver);
+				resolvers.len() as u32
+			});
+			<ActiveResolversCount<T>>::put(count);
 		}
 
 		fn _remove_active_resolver(resolver: AccountOf<T>) {
-			<ActiveResolvers<T>>::mutate(|resolvers| resolvers.retain(|r| *r != resolver));
+			let count = <ActiveResolvers<T>>::mutate(|resolvers| {
+				resolvers.retain(|r| *r != resolver);
+				resolvers.len() as u32
+			});
+			<ActiveResolversCount<T>>::put(count);
+		}
+
+		fn _increase_network_stake(amount: BalanceOf<T>) {
+			<NetworkStake<T>>::mutate(|stake| *stake = stake.saturating_add(amount));
+		}
+
+		fn _decrease_network_stake(amount: BalanceOf<T>) {
+			<NetworkStake<T>>::mutate(|stake| *stake = stake.saturating_sub(amount));
 		}
 
 		fn _release_pending_funds() -> DispatchResult {
 			let mut pending_funds = <PendingFunds<T>>::get();
 			let now = <timestamp::Pallet<T>>::get();
+			let mut batch_size = T::ReleaseBatchSize::get();
 
+			// The rest of the due funds are released in the next runs.
 			pending_funds.retain(|fund| {
-				let can_release = now >= fund.release_at;
+				let can_release = batch_size > 0 && now >= fund.release_at;
 				if can_release {
-					T::Currency::unreserve(CurrencyId::<T::Hash>::Native, &fund.owner, fund.amount);
+					Self::_unreserve_named(RESOLVER_STAKE_RESERVE, &fund.owner, fund.amount);
+					batch_size -= 1;
 				}
 
 				!can_release
@@ -490,20 +1439,462 @@ pub mod pallet {
 
 			Ok(())
 		}
+
+		// Reserve the funds of the account in the fee currency under the reserve identifier.
+		fn _reserve_named(
+			id: ReserveIdentifier,
+			who: &AccountOf<T>,
+			amount: BalanceOf<T>,
+		) -> DispatchResult {
+			let currency_id = T::FeeCurrency::get();
+			T::Currency::reserve(currency_id, who, amount)?;
+			<NamedReserves<T>>::mutate(who, (id, currency_id), |reserved| {
+				*reserved = reserved.saturating_add(amount)
+			});
+			Ok(())
+		}
+
+		// Unreserve at most the funds tracked under the reserve identifier, returns the amount that
+		// cannot be unreserved.
+		fn _unreserve_named(
+			id: ReserveIdentifier,
+			who: &AccountOf<T>,
+			amount: BalanceOf<T>,
+		) -> BalanceOf<T> {
+			let currency_id = T::FeeCurrency::get();
+			let to_unreserve = amount.min(Self::named_reserves(who, (id, currency_id)));
+			let unreserved = to_unreserve - T::Currency::unreserve(currency_id, who, to_unreserve);
+
+			<NamedReserves<T>>::mutate_exists(who, (id, currency_id), |reserved| {
+				let left = reserved.unwrap_or_default().saturating_sub(unreserved);
+				*reserved = if left.is_zero() { None } else { Some(left) };
+			});
+
+			amount - unreserved
+		}
+
+		fn _delegated_amount(
+			delegator: &AccountOf<T>,
+			resolver_account: &AccountOf<T>,
+		) -> BalanceOf<T> {
+			Self::resolvers(resolver_account)
+				.and_then(|resolver| {
+					resolver
+						.delegations
+						.into_iter()
+						.find(|delegation| delegation.delegator == *delegator)
+				})
+				.map(|delegation| delegation.amount)
+				.unwrap_or_else(Zero::zero)
+		}
+
+		// The organization an account is a member of, or the account itself.
+		fn _affiliation(account: &AccountOf<T>) -> AccountOf<T> {
+			T::IdentitiesManager::get_organization(account).unwrap_or_else(|| account.clone())
+		}
+
+		// A resolver has a conflict of interest with a party if the resolver is the party, in the
+		// same organization, a reviewer of the party, or if one of them delegated more than the
+		// `ConflictDelegationThreshold` to the other.
+		fn _has_conflict(resolver_account: &AccountOf<T>, parties: &[AccountOf<T>]) -> bool {
+			let threshold = T::ConflictDelegationThreshold::get();
+			let affiliation = Self::_affiliation(resolver_account);
+
+			parties.iter().any(|party| {
+				Self::_affiliation(party) == affiliation ||
+					T::IdentitiesManager::is_reviewer(party, resolver_account) ||
+					T::IdentitiesManager::is_attested_by(party, resolver_account) ||
+					T::IdentitiesManager::is_attested_by(resolver_account, party) ||
+					Self::_delegated_amount(party, resolver_account) > threshold ||
+					Self::_delegated_amount(resolver_account, party) > threshold
+			})
+		}
+
+		fn _create_pool(
+			sender: AccountOf<T>,
+			strategy: PoolStrategy,
+			commission: Permill,
+		) -> DispatchResult {
+			ensure!(commission <= T::MaxPoolCommission::get(), <Error<T>>::PoolCommissionTooHigh);
+
+			let pool_id = Self::next_pool_id();
+			let pool = NominationPool::<T> {
+				operator: sender.clone(),
+				strategy,
+				commission,
+				points: Zero::zero(),
+				stake: Zero::zero(),
+				unbonding: Zero::zero(),
+				targets: [].to_vec(),
+			};
+
+			<NominationPools<T>>::insert(pool_id, pool);
+			<NextPoolId<T>>::put(pool_id.saturating_add(1));
+
+			Self::deposit_event(Event::PoolCreated {
+				pool_id,
+				operator: sender,
+				strategy,
+				commission,
+			});
+
+			Ok(())
+		}
+
+		fn _join_pool(
+			sender: AccountOf<T>,
+			pool_id: PoolId,
+			amount: BalanceOf<T>,
+		) -> DispatchResult {
+			let mut pool = Self::nomination_pools(pool_id).ok_or(<Error<T>>::PoolNotFound)?;
+			ensure!(!amount.is_zero(), <Error<T>>::InvalidAmount);
+			ensure!(
+				T::Currency::free_balance(T::FeeCurrency::get(), &sender) >= amount,
+				<Error<T>>::InsufficientBalance,
+			);
+
+			// The points are priced after the pending rewards and slashes are settled.
+			Self::_settle_pool(pool_id, &mut pool)?;
+			ensure!(pool.points.is_zero() || !pool.stake.is_zero(), <Error<T>>::PoolDepleted);
+
+			let points = if pool.points.is_zero() {
+				amount
+			} else {
+				Self::_pool_convert(amount, pool.stake, pool.points)
+			};
+
+			let pool_account = Self::pool_account(pool_id);
+			T::Currency::transfer(T::FeeCurrency::get(), &sender, &pool_account, amount)?;
+			Self::_pool_delegate(&pool_account, &mut pool, amount)?;
+
+			pool.points += points;
+			pool.stake += amount;
+			<PoolMembers<T>>::mutate(pool_id, &sender, |member_points| *member_points += points);
+			<NominationPools<T>>::insert(pool_id, pool);
+
+			Self::deposit_event(Event::PoolJoined { pool_id, member: sender, amount, points });
+
+			Ok(())
+		}
+
+		fn _leave_pool(
+			sender: AccountOf<T>,
+			pool_id: PoolId,
+			points: BalanceOf<T>,
+		) -> DispatchResult {
+			let mut pool = Self::nomination_pools(pool_id).ok_or(<Error<T>>::PoolNotFound)?;
+			ensure!(!points.is_zero(), <Error<T>>::InvalidAmount);
+			ensure!(
+				points <= Self::pool_members(pool_id, &sender),
+				<Error<T>>::InsufficientPoolPoints
+			);
+
+			Self::_settle_pool(pool_id, &mut pool)?;
+
+			let amount = Self::_pool_convert(points, pool.points, pool.stake);
+			let pool_account = Self::pool_account(pool_id);
+			let release_at = Self::_pool_undelegate(&pool_account, &mut pool, amount)?;
+
+			pool.points -= points;
+			pool.stake -= amount;
+			pool.unbonding += amount;
+			<PoolMembers<T>>::mutate_exists(pool_id, &sender, |member_points| {
+				let left = member_points.unwrap_or_default().saturating_sub(points);
+				*member_points = if left.is_zero() { None } else { Some(left) };
+			});
+			<PoolUnbondings<T>>::mutate(pool_id, &sender, |unbondings| {
+				unbondings.push(PoolUnbonding::<T> { amount, release_at })
+			});
+			<NominationPools<T>>::insert(pool_id, pool);
+
+			Self::deposit_event(Event::PoolLeft {
+				pool_id,
+				member: sender,
+				points,
+				amount,
+				release_at,
+			});
+
+			Ok(())
+		}
+
+		fn _withdraw_unbonded(sender: AccountOf<T>, pool_id: PoolId) -> DispatchResult {
+			let mut pool = Self::nomination_pools(pool_id).ok_or(<Error<T>>::PoolNotFound)?;
+			let now = <timestamp::Pallet<T>>::get();
+
+			let mut unbondings = Self::pool_unbondings(pool_id, &sender);
+			let mut amount: BalanceOf<T> = Zero::zero();
+			unbondings.retain(|unbonding| {
+				let can_release = now >= unbonding.release_at;
+				if can_release {
+					amount += unbonding.amount;
+				}
+
+				!can_release
+			});
+			ensure!(!amount.is_zero(), <Error<T>>::NoReleasableFunds);
+
+			// The unbonding funds are slashed after the bonded stake is slashed out.
+			Self::_settle_pool(pool_id, &mut pool)?;
+			let amount = amount.min(pool.unbonding);
+
+			let pool_account = Self::pool_account(pool_id);
+			let amount =
+				amount - Self::_unreserve_named(RESOLVER_STAKE_RESERVE, &pool_account, amount);
+			T::Currency::transfer(T::FeeCurrency::get(), &pool_account, &sender, amount)?;
+
+			pool.unbonding -= amount;
+			if unbondings.is_empty() {
+				<PoolUnbondings<T>>::remove(pool_id, &sender);
+			} else {
+				<PoolUnbondings<T>>::insert(pool_id, &sender, unbondings);
+			}
+			<NominationPools<T>>::insert(pool_id, pool);
+
+			Self::deposit_event(Event::PoolWithdrawn { pool_id, member: sender, amount });
+
+			Ok(())
+		}
+
+		// Reconcile the book of the pool with the funds of the pool account. The gains are the
+		// rewards, compounded after the commission of the operator. The losses are the slashes,
+		// shared by the members through the value of their points. The idle funds are delegated
+		// again by the strategy.
+		fn _settle_pool(pool_id: PoolId, pool: &mut NominationPool<T>) -> DispatchResult {
+			let currency_id = T::FeeCurrency::get();
+			let pool_account = Self::pool_account(pool_id);
+			let funds = T::Currency::free_balance(currency_id, &pool_account) +
+				T::Currency::reserved_balance(currency_id, &pool_account);
+			let booked = pool.stake + pool.unbonding;
+
+			if funds > booked {
+				let reward = funds - booked;
+				let commission = pool.commission * reward;
+				if !commission.is_zero() {
+					T::Currency::transfer(currency_id, &pool_account, &pool.operator, commission)?;
+				}
+				pool.stake += reward - commission;
+
+				Self::deposit_event(Event::PoolRewarded { pool_id, reward, commission });
+			} else if funds < booked {
+				let slashed = booked - funds;
+				let from_stake = slashed.min(pool.stake);
+				pool.stake -= from_stake;
+				pool.unbonding = pool.unbonding.saturating_sub(slashed - from_stake);
+
+				Self::deposit_event(Event::PoolSlashed { pool_id, amount: slashed });
+			}
+
+			let idle = T::Currency::free_balance(currency_id, &pool_account);
+			Self::_pool_delegate(&pool_account, pool, idle)
+		}
+
+		// The active resolvers a pool delegates to by its strategy.
+		fn _pool_targets(strategy: PoolStrategy) -> Vec<AccountOf<T>> {
+			let mut resolvers: Vec<(AccountOf<T>, BalanceOf<T>)> = <ActiveResolvers<T>>::get()
+				.into_iter()
+				.filter_map(|account| {
+					Self::resolvers(&account).map(|resolver| (account, resolver.total_stake))
+				})
+				.collect();
+
+			resolvers.sort_by(|a, b| b.1.cmp(&a.1));
+			if strategy == PoolStrategy::LeastStaked {
+				resolvers.reverse();
+			}
+			resolvers.truncate(T::MaxPoolTargets::get() as usize);
+
+			resolvers.into_iter().map(|(account, _)| account).collect()
+		}
+
+		// Spread the amount evenly over the targets of the strategy. The amount stays idle in the
+		// pool account if there is no active resolver.
+		fn _pool_delegate(
+			pool_account: &AccountOf<T>,
+			pool: &mut NominationPool<T>,
+			amount: BalanceOf<T>,
+		) -> DispatchResult {
+			let targets = Self::_pool_targets(pool.strategy);
+			if targets.is_empty() || amount.is_zero() {
+				return Ok(())
+			}
+
+			let count: BalanceOf<T> = (targets.len() as u32).into();
+			let share = amount / count;
+			// The rounding remainder goes to the first target.
+			let mut remainder = amount - share * count;
+
+			for target in targets {
+				let part = share + remainder;
+				remainder = Zero::zero();
+				if part.is_zero() {
+					continue
+				}
+
+				Self::_delegate(pool_account.clone(), target.clone(), part)?;
+				if !pool.targets.contains(&target) {
+					pool.targets.push(target);
+				}
+			}
+
+			Ok(())
+		}
+
+		// Undelegate the amount from the idle funds and then the targets of the pool. The funds
+		// stay reserved by the pool account until the returned release time.
+		fn _pool_undelegate(
+			pool_account: &AccountOf<T>,
+			pool: &mut NominationPool<T>,
+			amount: BalanceOf<T>,
+		) -> Result<MomentOf<T>, DispatchError> {
+			let idle = T::Currency::free_balance(T::FeeCurrency::get(), pool_account).min(amount);
+			let mut remaining = amount - idle;
+
+			// The delegations to the terminated resolvers are refunded by the unwinding.
+			let delegations: Vec<(AccountOf<T>, BalanceOf<T>)> = pool
+				.targets
+				.iter()
+				.filter(|target| {
+					Self::resolvers(target)
+						.map_or(false, |resolver| resolver.status != ResolverStatus::Terminated)
+				})
+				.map(|target| (target.clone(), Self::_delegated_amount(pool_account, target)))
+				.collect();
+			let liquid = delegations
+				.iter()
+				.fold(Zero::zero(), |total: BalanceOf<T>, (_, delegated)| total + *delegated);
+			ensure!(liquid >= remaining, <Error<T>>::InsufficientPoolLiquidity);
+
+			let mut release_at = <timestamp::Pallet<T>>::get();
+			if !idle.is_zero() {
+				Self::_reserve_named(RESOLVER_STAKE_RESERVE, pool_account, idle)?;
+			}
+
+			for (target, delegated) in delegations {
+				if remaining.is_zero() {
+					break
+				}
+
+				let part = delegated.min(remaining);
+				let mut resolver = Self::resolvers(&target).ok_or(<Error<T>>::ResolverNotFound)?;
+				Self::_decrease_delegation(&target, &mut resolver, pool_account, part)?;
+				<Resolvers<T>>::insert(&target, resolver);
+
+				remaining -= part;
+				let target_release_at = Self::_undelegation_release_at(&target);
+				release_at = release_at.max(target_release_at);
+
+				Self::deposit_event(Event::DelegationRemoved {
+					resolver: target,
+					delegator: pool_account.clone(),
+					amount: part,
+					release_at: target_release_at,
+				});
+			}
+
+			pool.targets
+				.retain(|target| !Self::_delegated_amount(pool_account, target).is_zero());
+
+			Ok(release_at)
+		}
+
+		// Convert an amount from the units of `from_total` to the units of `to_total`, e.g. the
+		// stake of a pool into the points.
+		fn _pool_convert(
+			amount: BalanceOf<T>,
+			from_total: BalanceOf<T>,
+			to_total: BalanceOf<T>,
+		) -> BalanceOf<T> {
+			multiply_by_rational(
+				amount.saturated_into(),
+				to_total.saturated_into(),
+				from_total.saturated_into(),
+			)
+			.map(|converted| converted.saturated_into())
+			.unwrap_or_else(|_| Zero::zero())
+		}
+
+		// Release the unlocked pending funds of an account, returns the number of released funds.
+		fn _release_account_pending_funds(account: &AccountOf<T>) -> usize {
+			let mut pending_funds = <PendingFunds<T>>::get();
+			let now = <timestamp::Pallet<T>>::get();
+			let mut released = 0;
+			let mut amount: BalanceOf<T> = Zero::zero();
+
+			pending_funds.retain(|fund| {
+				let can_release = fund.owner == *account && now >= fund.release_at;
+				if can_release {
+					Self::_unreserve_named(RESOLVER_STAKE_RESERVE, &fund.owner, fund.amount);
+					released += 1;
+					amount += fund.amount;
+				}
+
+				!can_release
+			});
+
+			if released > 0 {
+				<PendingFunds<T>>::set(pending_funds);
+				Self::deposit_event(Event::PendingFundsReleased {
+					account: account.clone(),
+					amount,
+				});
+			}
+
+			released
+		}
 	}
 
 	impl<T: Config> ResolversNetwork<T::AccountId, T::Hash> for Pallet<T> {
 		fn get_resolver(
 			payment_hash: T::Hash,
 			selected: Vec<T::AccountId>,
+			languages: Vec<LanguageCode>,
+			parties: Vec<T::AccountId>,
+			category: Option<PaymentCategory>,
 		) -> Result<T::AccountId, DispatchError> {
 			let mut active_resolvers = <ActiveResolvers<T>>::get();
-			active_resolvers.retain(|id| !selected.contains(id));
+			active_resolvers.retain(|id| !selected.contains(id) && !Self::_has_conflict(id, &parties));
 			ensure!(!active_resolvers.is_empty(), <Error<T>>::NoAnyActiveResolver);
-			let (output, _block_number) = T::Randomness::random(payment_hash.as_ref());
-			let random_number: usize = output.as_ref().iter().map(|x| *x as usize).sum();
 
-			Ok(active_resolvers[random_number % active_resolvers.len()].clone())
+			// Fall back to all the available resolvers if none of them speaks the languages.
+			let speaking_resolvers: Vec<T::AccountId> = active_resolvers
+				.iter()
+				.filter(|id| {
+					T::IdentitiesManager::get_languages(id)
+						.iter()
+						.any(|language| languages.contains(language))
+				})
+				.cloned()
+				.collect();
+			if !speaking_resolvers.is_empty() {
+				active_resolvers = speaking_resolvers;
+			}
+			// Among them, the resolvers with the expertise in the category are preferred.
+			if let Some(category) = category {
+				let expert_resolvers: Vec<T::AccountId> = active_resolvers
+					.iter()
+					.filter(|id| Self::expertise(id).contains(&category))
+					.cloned()
+					.collect();
+				if !expert_resolvers.is_empty() {
+					active_resolvers = expert_resolvers;
+				}
+			}
+			let candidates: Vec<(T::AccountId, BalanceOf<T>)> = active_resolvers
+				.into_iter()
+				.map(|id| {
+					let stake = Self::resolvers(&id).map(|r| r.total_stake).unwrap_or_default();
+					(id, stake)
+				})
+				.collect();
+			let (seed, _block_number) = T::Randomness::random(payment_hash.as_ref());
+			let index = T::SelectionStrategy::select(seed, &candidates);
+
+			Ok(candidates[index].0.clone())
+		}
+
+		fn get_encryption_key(resolver_account_id: &T::AccountId) -> Option<EncryptionKey> {
+			Self::encryption_keys(resolver_account_id)
 		}
 
 		fn increase_credibility(
@@ -526,5 +1917,32 @@ pub mod pallet {
 			}
 			Ok(())
 		}
+
+		fn open_case(resolver_account_id: &T::AccountId) {
+			<CaseExposures<T>>::mutate(resolver_account_id, |exposure| {
+				let exposure = exposure.get_or_insert_with(|| CaseExposure::<T> {
+					open_cases: 0,
+					exposed_until: Zero::zero(),
+				});
+				exposure.open_cases = exposure.open_cases.saturating_add(1);
+			});
+		}
+
+		fn close_case(resolver_account_id: &T::AccountId) {
+			let exposed_until = <timestamp::Pallet<T>>::get() + T::CaseExposureTime::get();
+			<CaseExposures<T>>::mutate(resolver_account_id, |exposure| {
+				if let Some(exposure) = exposure {
+					exposure.open_cases = exposure.open_cases.saturating_sub(1);
+					exposure.exposed_until = exposure.exposed_until.max(exposed_until);
+				}
+			});
+		}
+	}
+
+	impl<T: Config> ResolversInspector<T::AccountId> for Pallet<T> {
+		fn is_active_resolver(account_id: &T::AccountId) -> bool {
+			Self::resolvers(account_id)
+				.map_or(false, |resolver| resolver.status != ResolverStatus::Terminated)
+		}
 	}
 }
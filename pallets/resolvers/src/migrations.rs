@@ -0,0 +1,173 @@
+//! Storage migrations of the resolvers network pallet.
+//!
+//! Each migration upgrades the `StorageVersion` of the pallet by one and only runs if the on-chain
+//! version is the previous one. The migrations can be rehearsed against a live chain with the
+//! `try-runtime` feature, which runs the `pre_upgrade` and `post_upgrade` checks.
+
+use crate::{
+	ActiveResolvers, ActiveResolversCount, Config, NamedReserves, NetworkStake, Pallet,
+	PendingFunds, Resolvers,
+};
+#[cfg(feature = "try-runtime")]
+use frame_support::traits::OnRuntimeUpgradeHelpersExt;
+use frame_support::{pallet_prelude::*, traits::OnRuntimeUpgrade};
+
+/// Version 1 stamps the storage version. The resolvers kept their layout from the launch, so the
+/// checks only count that they still decode.
+pub mod v1 {
+	use super::*;
+
+	pub struct MigrateToV1<T>(PhantomData<T>);
+
+	impl<T: Config> OnRuntimeUpgrade for MigrateToV1<T> {
+		fn on_runtime_upgrade() -> Weight {
+			if Pallet::<T>::on_chain_storage_version() != 0 {
+				return T::DbWeight::get().reads(1)
+			}
+
+			StorageVersion::new(1).put::<Pallet<T>>();
+			T::DbWeight::get().reads_writes(1, 1)
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<(), &'static str> {
+			Self::set_temp_storage(Resolvers::<T>::iter_keys().count() as u32, "resolvers");
+			Ok(())
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade() -> Result<(), &'static str> {
+			ensure!(
+				Pallet::<T>::on_chain_storage_version() >= 1,
+				"Storage version is not migrated"
+			);
+			let resolvers: u32 =
+				Self::get_temp_storage("resolvers").ok_or("Missing resolvers count")?;
+			ensure!(
+				Resolvers::<T>::iter_values().count() as u32 == resolvers,
+				"Some resolvers cannot be decoded with the current layout"
+			);
+			Ok(())
+		}
+	}
+}
+
+/// Version 2 tracks the self stakes, the delegations and the pending funds in `NamedReserves`. The
+/// existing stakes are tracked as reserved, capped by the reserved balances of the accounts.
+pub mod v2 {
+	use super::*;
+	use frame_support::{
+		sp_runtime::traits::{Saturating, Zero},
+		sp_std::collections::btree_map::BTreeMap,
+	};
+	use orml_traits::{MultiCurrency, MultiReservableCurrency};
+	use primitives::RESOLVER_STAKE_RESERVE;
+
+	type BalanceOf<T> =
+		<<T as Config>::Currency as MultiCurrency<<T as frame_system::Config>::AccountId>>::Balance;
+
+	pub struct MigrateToV2<T>(PhantomData<T>);
+
+	impl<T: Config> OnRuntimeUpgrade for MigrateToV2<T> {
+		fn on_runtime_upgrade() -> Weight {
+			if Pallet::<T>::on_chain_storage_version() != 1 {
+				return T::DbWeight::get().reads(1)
+			}
+
+			let mut stakes: BTreeMap<T::AccountId, BalanceOf<T>> = BTreeMap::new();
+			let mut add_stake = |who: T::AccountId, amount: BalanceOf<T>| {
+				let total = stakes.entry(who).or_insert_with(Zero::zero);
+				*total = total.saturating_add(amount);
+			};
+
+			let mut resolvers: Weight = 0;
+			for (account, resolver) in Resolvers::<T>::iter() {
+				resolvers += 1;
+				add_stake(account, resolver.self_stake);
+				for delegation in resolver.delegations {
+					add_stake(delegation.delegator, delegation.amount);
+				}
+			}
+			for fund in PendingFunds::<T>::get() {
+				add_stake(fund.owner, fund.amount);
+			}
+
+			let currency_id = T::FeeCurrency::get();
+			let mut writes: Weight = 0;
+			for (who, stake) in stakes {
+				let tracked = stake.min(T::Currency::reserved_balance(currency_id, &who));
+				if !tracked.is_zero() {
+					NamedReserves::<T>::insert(
+						&who,
+						(RESOLVER_STAKE_RESERVE, currency_id),
+						tracked,
+					);
+					writes += 1;
+				}
+			}
+
+			StorageVersion::new(2).put::<Pallet<T>>();
+			T::DbWeight::get().reads_writes(2 + resolvers + writes, 1 + writes)
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade() -> Result<(), &'static str> {
+			ensure!(
+				Pallet::<T>::on_chain_storage_version() == 2,
+				"Storage version is not migrated"
+			);
+			ensure!(
+				NamedReserves::<T>::iter().all(|(who, (_, currency_id), amount)| {
+					amount <= T::Currency::reserved_balance(currency_id, &who)
+				}),
+				"Some tracked stakes are not reserved"
+			);
+			Ok(())
+		}
+	}
+}
+
+/// Version 3 counts the active resolvers in `ActiveResolversCount` and sums the total stakes of
+/// the resolvers in `NetworkStake`, from the active set and the existing resolvers.
+pub mod v3 {
+	use super::*;
+	use frame_support::sp_runtime::traits::{Saturating, Zero};
+
+	pub struct MigrateToV3<T>(PhantomData<T>);
+
+	impl<T: Config> OnRuntimeUpgrade for MigrateToV3<T> {
+		fn on_runtime_upgrade() -> Weight {
+			if Pallet::<T>::on_chain_storage_version() != 2 {
+				return T::DbWeight::get().reads(1)
+			}
+
+			let active_resolvers = ActiveResolvers::<T>::decode_len().unwrap_or_default();
+			ActiveResolversCount::<T>::put(active_resolvers as u32);
+
+			let mut resolvers: Weight = 0;
+			let mut network_stake = Zero::zero();
+			for resolver in Resolvers::<T>::iter_values() {
+				resolvers += 1;
+				network_stake = resolver.total_stake.saturating_add(network_stake);
+			}
+			NetworkStake::<T>::put(network_stake);
+
+			StorageVersion::new(3).put::<Pallet<T>>();
+			T::DbWeight::get().reads_writes(2 + resolvers, 3)
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade() -> Result<(), &'static str> {
+			ensure!(
+				Pallet::<T>::on_chain_storage_version() >= 3,
+				"Storage version is not migrated"
+			);
+			ensure!(
+				ActiveResolversCount::<T>::get() as usize ==
+					ActiveResolvers::<T>::decode_len().unwrap_or_default(),
+				"The active resolvers are not counted"
+			);
+			Ok(())
+		}
+	}
+}
@@ -5,6 +5,7 @@ use crate as resolvers_network;
 use frame_support::{
 	construct_runtime, parameter_types,
 	traits::{GenesisBuild, Nothing},
+	PalletId,
 };
 use frame_system as system;
 use orml_currencies::BasicCurrencyAdapter;
@@ -15,6 +16,7 @@ pub use primitives::{Credibility, CurrencyId, Hash};
 use sp_runtime::{
 	generic,
 	traits::{BlakeTwo256, IdentityLookup},
+	Permill,
 };
 use pallet_identities;
 
@@ -35,9 +37,12 @@ pub const MAX_CREDIBILITY: Credibility = 100;
 
 pub const PENALTY_TOKEN_LOCK_TIME: Moment = 1728000;
 pub const UNDELEGATE_TIME: Moment = 172800;
+pub const CASE_EXPOSURE_TIME: Moment = 345600;
 pub const MINIMUM_SELF_STAKE: Balance = 100;
 pub const ACTIVATION_STAKE_AMOUNT: Balance = 1000;
 pub const REQUIRED_CREDIBILITY: Credibility = 30;
+pub const ERA_DURATION: BlockNumber = 10;
+pub const CONFLICT_DELEGATION_THRESHOLD: Balance = 100;
 
 parameter_types! {
 	pub const BlockHashCount: u64 = 250;
@@ -138,25 +143,60 @@ parameter_types! {
 parameter_types! {
 	pub const PenaltyTokenLockTime: Moment = PENALTY_TOKEN_LOCK_TIME;
 	pub const UndelegateTime: Moment = UNDELEGATE_TIME;
+	pub const CaseExposureTime: Moment = CASE_EXPOSURE_TIME;
 	pub const MinimumSelfStake: Balance = MINIMUM_SELF_STAKE;
 	pub const ActivationStakeAmount: Balance = ACTIVATION_STAKE_AMOUNT;
 	pub const RequiredCredibility: Credibility = REQUIRED_CREDIBILITY;
+	pub static RequiredVerifiedFields: Vec<Vec<u8>> = Vec::new();
+	pub static MaxActiveResolvers: u32 = 100;
+	pub const EraDuration: BlockNumber = ERA_DURATION;
+	pub static UnwindBatchSize: u32 = 100;
+	pub static ReleaseBatchSize: u32 = 100;
+	pub const ConflictDelegationThreshold: Balance = CONFLICT_DELEGATION_THRESHOLD;
+	pub const MaxExpertiseCategories: u32 = 4;
+	pub const ResolversPalletId: PalletId = PalletId(*b"lb/rslvr");
+	pub const MaxPoolTargets: u32 = 2;
+	pub const MaxPoolCommission: Permill = Permill::from_percent(10);
 }
 
 impl resolvers_network::Config for Runtime {
 	type Event = Event;
 	type Currency = Currencies;
+	type FeeCurrency = GetNativeCurrencyId;
 	type IdentitiesManager = Identities;
 	type Randomness = RandomnessCollectiveFlip;
+	type SelectionStrategy = resolvers_network::selection::UniformSelection;
 	type PenaltyTokenLockTime = PenaltyTokenLockTime;
 	type MinimumSelfStake = MinimumSelfStake;
 	type ActivationStakeAmount = ActivationStakeAmount;
 	type UndelegateTime = UndelegateTime;
+	type CaseExposureTime = CaseExposureTime;
 	type RequiredCredibility = RequiredCredibility;
+	type RequiredVerifiedFields = RequiredVerifiedFields;
+	type MaxActiveResolvers = MaxActiveResolvers;
+	type EraDuration = EraDuration;
+	type UnwindBatchSize = UnwindBatchSize;
+	type ReleaseBatchSize = ReleaseBatchSize;
+	type ConflictDelegationThreshold = ConflictDelegationThreshold;
+	type MaxExpertiseCategories = MaxExpertiseCategories;
+	type PalletId = ResolversPalletId;
+	type MaxPoolTargets = MaxPoolTargets;
+	type MaxPoolCommission = MaxPoolCommission;
+	type WeightInfo = ();
 }
 
 parameter_types! {
 	pub const EvaluatorBonding: Balance = EVALUATOR_BONDING;
+	pub const VerifyRequestTimeout: BlockNumber = 10;
+	pub const VerifyRequestPenalty: Balance = 5;
+	pub const MaxFieldPrices: u32 = 3;
+	pub const EvaluatorRateBand: Balance = 10;
+	pub const MaxLanguages: u32 = 3;
+	pub const MaxEncryptionKeys: u32 = 3;
+	pub const MaxAttestations: u32 = 3;
+	pub const HandleDeposit: Balance = 10;
+	pub const HandlePeriod: BlockNumber = 100;
+	pub const MaxHandleLength: u32 = 16;
 	pub const InitialCredibility: Credibility = INITIAL_CREDIBILITY;
 	pub const MaxCredibility: Credibility = MAX_CREDIBILITY;
 }
@@ -165,8 +205,21 @@ impl pallet_identities::Config for Runtime {
 	type Event = Event;
 	type Currency = Currencies;
 	type EvaluatorBonding = EvaluatorBonding;
+	type GovernanceOrigin = system::EnsureRoot<AccountId>;
+	type Resolvers = ResolversNetwork;
+	type VerifyRequestTimeout = VerifyRequestTimeout;
+	type VerifyRequestPenalty = VerifyRequestPenalty;
+	type MaxFieldPrices = MaxFieldPrices;
+	type EvaluatorRateBand = EvaluatorRateBand;
+	type MaxLanguages = MaxLanguages;
+	type MaxEncryptionKeys = MaxEncryptionKeys;
+	type MaxAttestations = MaxAttestations;
+	type HandleDeposit = HandleDeposit;
+	type HandlePeriod = HandlePeriod;
+	type MaxHandleLength = MaxHandleLength;
 	type InitialCredibility = InitialCredibility;
 	type MaxCredibility = MaxCredibility;
+	type WeightInfo = ();
 }
 
 type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Runtime>;
@@ -184,24 +237,31 @@ construct_runtime!(
 		Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
 		Tokens: orml_tokens::{Pallet, Storage, Event<T>, Config<T>},
 		Currencies: orml_currencies::{Pallet, Call, Event<T>},
-		ResolversNetwork: resolvers_network::{Pallet, Call, Storage, Event<T>},
+		ResolversNetwork: resolvers_network::{Pallet, Call, Storage, Config<T>, Event<T>},
 		Identities: pallet_identities::{Pallet, Call, Storage, Event<T>},
 	}
 );
 
 pub struct ExtBuilder {
 	balances: Vec<(AccountId, CurrencyId<Hash>, Balance)>,
+	resolvers: Vec<(AccountId, Vec<u8>, Balance)>,
 }
 
 impl Default for ExtBuilder {
 	fn default() -> Self {
 		Self {
 			balances: vec![(ALICE, CurrencyId::Native, 1_000), (BOB, CurrencyId::Native, 1_000)],
+			resolvers: vec![],
 		}
 	}
 }
 
 impl ExtBuilder {
+	pub fn resolvers(mut self, resolvers: Vec<(AccountId, Vec<u8>, Balance)>) -> Self {
+		self.resolvers = resolvers;
+		self
+	}
+
 	pub fn build(self) -> sp_io::TestExternalities {
 		let mut t = system::GenesisConfig::default().build_storage::<Runtime>().unwrap();
 
@@ -213,6 +273,10 @@ impl ExtBuilder {
 			.assimilate_storage(&mut t)
 			.unwrap();
 
+		resolvers_network::GenesisConfig::<Runtime> { resolvers: self.resolvers }
+			.assimilate_storage(&mut t)
+			.unwrap();
+
 		t.into()
 	}
 }
@@ -0,0 +1,104 @@
+//! # Resolver selection
+//!
+//! The pallet filters the active resolvers eligible for a dispute (no conflict of interest,
+//! speaking the preferred languages, with the expertise in the payment category) and leaves the
+//! draw among them to the `SelectionStrategy` of the runtime, so the strategy can be swapped
+//! without forking the pallet.
+//!
+//! The built-in strategies:
+//! - `UniformSelection`: every candidate has the same chance to be drawn.
+//! - `StakeWeightedSelection`: the chance of a candidate is proportional to its total stake.
+//!
+//! Both strategies derive the draw from the random seed by rejection sampling, so the result is
+//! unbiased and deterministic for a given seed.
+
+use codec::Decode;
+use sp_runtime::traits::{AtLeast32BitUnsigned, Hash, UniqueSaturatedInto};
+
+/// The maximum rounds of rejection sampling to get an unbiased random value.
+const MAX_SAMPLING_ROUNDS: u32 = 16;
+
+pub trait SelectionStrategy<AccountId, Balance, Hashing: Hash> {
+	/// Pick one of the `candidates`, each given with its total stake, from the random `seed`.
+	/// Return the index of the selected candidate, `candidates` is never empty.
+	fn select(seed: Hashing::Output, candidates: &[(AccountId, Balance)]) -> usize;
+}
+
+/// Draw every candidate with the same chance.
+pub struct UniformSelection;
+
+impl<AccountId, Balance, Hashing: Hash> SelectionStrategy<AccountId, Balance, Hashing>
+	for UniformSelection
+{
+	fn select(seed: Hashing::Output, candidates: &[(AccountId, Balance)]) -> usize {
+		random_index::<Hashing>(seed, candidates.len() as u32) as usize
+	}
+}
+
+/// Draw the candidates with the chance proportional to their total stakes. The candidates are
+/// drawn uniformly if none of them has a stake.
+pub struct StakeWeightedSelection;
+
+impl<AccountId, Balance, Hashing> SelectionStrategy<AccountId, Balance, Hashing>
+	for StakeWeightedSelection
+where
+	Balance: AtLeast32BitUnsigned + Copy,
+	Hashing: Hash,
+{
+	fn select(seed: Hashing::Output, candidates: &[(AccountId, Balance)]) -> usize {
+		let stakes = candidates
+			.iter()
+			.map(|(_, stake)| UniqueSaturatedInto::<u128>::unique_saturated_into(*stake));
+		let total = stakes.clone().fold(0u128, |total, stake| total.saturating_add(stake));
+		if total == 0 {
+			return random_index::<Hashing>(seed, candidates.len() as u32) as usize
+		}
+
+		let mut point = random_point::<Hashing>(seed, total);
+		for (index, stake) in stakes.enumerate() {
+			if point < stake {
+				return index
+			}
+			point -= stake;
+		}
+
+		// Only reachable if the total stake saturated.
+		candidates.len() - 1
+	}
+}
+
+/// Derive an unbiased index in `0..len` from the random seed by rejection sampling.
+pub fn random_index<Hashing: Hash>(seed: Hashing::Output, len: u32) -> u32 {
+	// The largest multiple of `len` in the range of u32, the samples above it are biased.
+	let bound = (u32::MAX as u64 + 1) / len as u64 * len as u64;
+	let mut value: u32 = 0;
+
+	for round in 0..MAX_SAMPLING_ROUNDS {
+		let sample = Hashing::hash_of(&(seed, round));
+		value = u32::decode(&mut sample.as_ref()).unwrap_or_default();
+
+		if (value as u64) < bound {
+			break
+		}
+	}
+
+	value % len
+}
+
+/// Derive an unbiased point in `0..total` from the random seed by rejection sampling.
+fn random_point<Hashing: Hash>(seed: Hashing::Output, total: u128) -> u128 {
+	// The samples above the largest multiple of `total` in the range of u128 are biased.
+	let excess = (u128::MAX % total + 1) % total;
+	let mut value: u128 = 0;
+
+	for round in 0..MAX_SAMPLING_ROUNDS {
+		let sample = Hashing::hash_of(&(seed, round));
+		value = u128::decode(&mut sample.as_ref()).unwrap_or_default();
+
+		if value <= u128::MAX - excess {
+			break
+		}
+	}
+
+	value % total
+}
@@ -2,20 +2,46 @@
 
 use super::*;
 use crate::pallet::ResolversNetwork as ResolversNetworkT;
-use frame_support::{assert_noop, assert_ok, traits::Hooks};
+use crate::selection::SelectionStrategy;
+use frame_support::{
+	assert_noop, assert_ok,
+	traits::{GetStorageVersion, Hooks, OnRuntimeUpgrade, StorageVersion},
+};
 use frame_system as system;
 use mock::{
-	last_event, Currencies, CurrencyId, Event, ExtBuilder, Identities, Origin,
-	RandomnessCollectiveFlip, ResolversNetwork, Runtime, System, Timestamp, ALICE, BOB, CHARLIE,
-	INITIAL_CREDIBILITY, UNDELEGATE_TIME, PENALTY_TOKEN_LOCK_TIME
+	last_event, AccountId, Balance, Currencies, CurrencyId, Event, ExtBuilder, Identities,
+	MaxActiveResolvers, Origin, RandomnessCollectiveFlip, ReleaseBatchSize, RequiredVerifiedFields,
+	ResolversNetwork, Runtime, System, Timestamp, UnwindBatchSize, ACTIVATION_STAKE_AMOUNT, ALICE,
+	BOB, CASE_EXPOSURE_TIME, CHARLIE, CONFLICT_DELEGATION_THRESHOLD, ERA_DURATION,
+	INITIAL_CREDIBILITY, MINIMUM_SELF_STAKE, PENALTY_TOKEN_LOCK_TIME, UNDELEGATE_TIME,
+};
+use orml_traits::{MultiCurrency, MultiReservableCurrency};
+use pallet_identities::{
+	AttestationType, IdentitiesManager, IdentityFieldInput, IdentityType, VerifyMethod,
+};
+use primitives::{PaymentCategory, RESOLVER_STAKE_RESERVE};
+use sp_runtime::{
+	traits::{Hash, Header},
+	Permill,
 };
-use orml_traits::MultiReservableCurrency;
-use pallet_identities::{IdentitiesManager, IdentityType};
-use sp_runtime::traits::{Hash, Header};
 
 pub const INIT_TIMESTAMP: u64 = 1_000;
 pub const BLOCK_TIME: u64 = 6_000;
 
+// The amount a nomination pool delegated to a resolver.
+fn pool_delegation(pool_id: PoolId, resolver: AccountId) -> Balance {
+	let pool_account = ResolversNetwork::pool_account(pool_id);
+	ResolversNetwork::resolvers(resolver)
+		.and_then(|resolver| {
+			resolver
+				.delegations
+				.into_iter()
+				.find(|delegation| delegation.delegator == pool_account)
+		})
+		.map(|delegation| delegation.amount)
+		.unwrap_or_default()
+}
+
 fn run_to_block_number(block_number: u64) {
 	let mut parent_hash = System::parent_hash();
 
@@ -23,6 +49,7 @@ fn run_to_block_number(block_number: u64) {
 		System::reset_events();
 		System::initialize(&i, &parent_hash, &Default::default());
 		RandomnessCollectiveFlip::on_initialize(i);
+		ResolversNetwork::on_initialize(i);
 
 		let header = System::finalize();
 		parent_hash = header.hash();
@@ -38,7 +65,12 @@ fn join_resolvers_networks_works() {
 		System::set_block_number(1);
 
 		assert_noop!(
-			ResolversNetwork::join_resolvers_network(Origin::signed(ALICE), "".into(), 300),
+			ResolversNetwork::join_resolvers_network(
+				Origin::signed(ALICE),
+				"".into(),
+				300,
+				[0; 32],
+			),
 			Error::<Runtime>::IdentityRequired,
 		);
 
@@ -50,16 +82,26 @@ fn join_resolvers_networks_works() {
 		));
 
 		assert_noop!(
-			ResolversNetwork::join_resolvers_network(Origin::signed(ALICE), "".into(), 99),
+			ResolversNetwork::join_resolvers_network(Origin::signed(ALICE), "".into(), 99, [0; 32]),
 			Error::<Runtime>::NotMeetMinimumSelfStake
 		);
 
 		assert_noop!(
-			ResolversNetwork::join_resolvers_network(Origin::signed(ALICE), "".into(), 10001),
+			ResolversNetwork::join_resolvers_network(
+				Origin::signed(ALICE),
+				"".into(),
+				10001,
+				[0; 32],
+			),
 			Error::<Runtime>::InsufficientBalance
 		);
 
-		assert_ok!(ResolversNetwork::join_resolvers_network(Origin::signed(ALICE), "".into(), 300));
+		assert_ok!(ResolversNetwork::join_resolvers_network(
+			Origin::signed(ALICE),
+			"".into(),
+			300,
+			[0; 32]
+		));
 
 		let resolver = ResolversNetwork::resolvers(ALICE).unwrap();
 		assert_eq!(resolver.status, crate::ResolverStatus::Candidacy);
@@ -73,8 +115,141 @@ fn join_resolvers_networks_works() {
 
 		assert_eq!(
 			last_event(),
-			Event::ResolversNetwork(crate::Event::ResolverCreated { account: ALICE })
+			Event::ResolversNetwork(crate::Event::ResolverCreated {
+				account: ALICE,
+				self_stake: 300,
+			})
+		);
+	});
+}
+
+#[test]
+fn set_encryption_key_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+
+		assert_noop!(
+			ResolversNetwork::set_encryption_key(Origin::signed(ALICE), [2; 32]),
+			Error::<Runtime>::ResolverNotFound
+		);
+
+		assert_ok!(Identities::create_identity(
+			Origin::signed(ALICE),
+			"Alice".into(),
+			IdentityType::Individual,
+			[].into(),
+		));
+		assert_ok!(ResolversNetwork::join_resolvers_network(
+			Origin::signed(ALICE),
+			"".into(),
+			300,
+			[1; 32]
+		));
+		assert_eq!(ResolversNetwork::get_encryption_key(&ALICE), Some([1; 32]));
+
+		assert_ok!(ResolversNetwork::set_encryption_key(Origin::signed(ALICE), [2; 32]));
+		assert_eq!(
+			last_event(),
+			Event::ResolversNetwork(crate::Event::EncryptionKeyUpdated {
+				account: ALICE,
+				encryption_key: [2; 32],
+			})
+		);
+		assert_eq!(ResolversNetwork::get_encryption_key(&ALICE), Some([2; 32]));
+	});
+}
+
+#[test]
+fn set_expertise_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+
+		assert_noop!(
+			ResolversNetwork::set_expertise(Origin::signed(ALICE), [].into()),
+			Error::<Runtime>::ResolverNotFound
+		);
+
+		assert_ok!(Identities::create_identity(
+			Origin::signed(ALICE),
+			"Alice".into(),
+			IdentityType::Individual,
+			[].into(),
+		));
+		assert_ok!(ResolversNetwork::join_resolvers_network(
+			Origin::signed(ALICE),
+			"".into(),
+			300,
+			[1; 32]
+		));
+
+		let categories: Vec<PaymentCategory> =
+			(0..5).map(PaymentCategory::MerchantCategoryCode).collect();
+		assert_noop!(
+			ResolversNetwork::set_expertise(Origin::signed(ALICE), categories),
+			Error::<Runtime>::TooManyExpertiseCategories
+		);
+
+		let categories =
+			vec![PaymentCategory::DigitalGoods, PaymentCategory::MerchantCategoryCode(5812)];
+		assert_ok!(ResolversNetwork::set_expertise(Origin::signed(ALICE), categories.clone()));
+		assert_eq!(
+			last_event(),
+			Event::ResolversNetwork(crate::Event::ExpertiseUpdated {
+				account: ALICE,
+				categories: categories.clone(),
+			})
+		);
+		assert_eq!(ResolversNetwork::expertise(&ALICE).into_inner(), categories);
+
+		assert_ok!(ResolversNetwork::set_expertise(Origin::signed(ALICE), [].into()));
+		assert!(ResolversNetwork::expertise(&ALICE).is_empty());
+	});
+}
+
+#[test]
+fn join_resolvers_network_requires_verified_field() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		RequiredVerifiedFields::set(vec![b"legal_name".to_vec(), b"passport".to_vec()]);
+
+		assert_ok!(Identities::create_identity(
+			Origin::signed(ALICE),
+			"Alice".into(),
+			IdentityType::Individual,
+			[IdentityFieldInput {
+				name: "legal_name".into(),
+				value: "Alice".into(),
+				verify_method: VerifyMethod::Evaluator,
+			}]
+			.into(),
+		));
+		assert_noop!(
+			ResolversNetwork::join_resolvers_network(
+				Origin::signed(ALICE),
+				"".into(),
+				300,
+				[0; 32],
+			),
+			Error::<Runtime>::VerifiedFieldRequired,
 		);
+
+		// A KYC evaluator verifies the legal name.
+		assert_ok!(Identities::create_evaluator(
+			Origin::signed(CHARLIE),
+			"KYC".into(),
+			"KYC service".into(),
+			0,
+		));
+		assert_ok!(Identities::request_to_verify(Origin::signed(ALICE), [0].into(), CHARLIE));
+		assert_ok!(Identities::verify_data(Origin::signed(CHARLIE), 0, [(0, true)].into()));
+
+		assert_ok!(ResolversNetwork::join_resolvers_network(
+			Origin::signed(ALICE),
+			"".into(),
+			300,
+			[0; 32]
+		));
+		assert!(ResolversNetwork::resolvers(ALICE).is_some());
 	});
 }
 
@@ -88,7 +263,12 @@ fn delegate_works() {
 			IdentityType::Individual,
 			[].into(),
 		));
-		assert_ok!(ResolversNetwork::join_resolvers_network(Origin::signed(ALICE), "".into(), 900));
+		assert_ok!(ResolversNetwork::join_resolvers_network(
+			Origin::signed(ALICE),
+			"".into(),
+			900,
+			[0; 32]
+		));
 
 		// Test delegate amount tokens that exeed the balance.
 		assert_noop!(
@@ -106,8 +286,17 @@ fn delegate_works() {
 		assert_ok!(ResolversNetwork::delegate(Origin::signed(BOB), ALICE, 200));
 		assert_eq!(
 			last_event(),
-			Event::ResolversNetwork(crate::Event::ResolverActivated { account: ALICE })
+			Event::ResolversNetwork(crate::Event::DelegationAdded {
+				resolver: ALICE,
+				delegator: BOB,
+				amount: 200,
+			})
 		);
+		assert!(System::events().iter().any(|record| record.event ==
+			Event::ResolversNetwork(crate::Event::ResolverActivated {
+				account: ALICE,
+				total_stake: 1100,
+			})));
 		let resolver = ResolversNetwork::resolvers(ALICE).unwrap();
 		assert_eq!(resolver.status, crate::ResolverStatus::Active);
 		assert_eq!(resolver.self_stake, 900);
@@ -156,7 +345,12 @@ fn undelegate_works() {
 			IdentityType::Individual,
 			[].into(),
 		));
-		assert_ok!(ResolversNetwork::join_resolvers_network(Origin::signed(ALICE), "".into(), 900));
+		assert_ok!(ResolversNetwork::join_resolvers_network(
+			Origin::signed(ALICE),
+			"".into(),
+			900,
+			[0; 32]
+		));
 
 		// Test undelegate without any delegations.
 		assert_noop!(
@@ -171,8 +365,18 @@ fn undelegate_works() {
 		assert_ok!(ResolversNetwork::undelegate(Origin::signed(BOB), ALICE, 200));
 		assert_eq!(
 			last_event(),
-			Event::ResolversNetwork(crate::Event::ResolverInactivated { account: ALICE })
+			Event::ResolversNetwork(crate::Event::DelegationRemoved {
+				resolver: ALICE,
+				delegator: BOB,
+				amount: 200,
+				release_at: UNDELEGATE_TIME,
+			})
 		);
+		assert!(System::events().iter().any(|record| record.event ==
+			Event::ResolversNetwork(crate::Event::ResolverInactivated {
+				account: ALICE,
+				total_stake: 900,
+			})));
 		let resolver = ResolversNetwork::resolvers(ALICE).unwrap();
 		assert_eq!(resolver.status, crate::ResolverStatus::Candidacy);
 		assert_eq!(resolver.delegations.len(), 0);
@@ -204,9 +408,10 @@ fn undelegate_works() {
 }
 
 #[test]
-fn release_funds_works() {
+fn undelegate_from_exposed_resolver_is_locked() {
 	ExtBuilder::default().build().execute_with(|| {
 		System::set_block_number(1);
+		Timestamp::set_timestamp(INIT_TIMESTAMP);
 
 		assert_ok!(Identities::create_identity(
 			Origin::signed(ALICE),
@@ -214,120 +419,264 @@ fn release_funds_works() {
 			IdentityType::Individual,
 			[].into(),
 		));
-		assert_ok!(ResolversNetwork::join_resolvers_network(Origin::signed(ALICE), "".into(), 500));
-		// Bob and Charlie delegate tokens.
-		assert_ok!(ResolversNetwork::delegate(Origin::signed(BOB), ALICE, 200));
-		assert_ok!(ResolversNetwork::delegate(Origin::signed(CHARLIE), ALICE, 200));
-		assert_eq!(Currencies::reserved_balance(CurrencyId::Native, &BOB), 200);
-		assert_eq!(Currencies::reserved_balance(CurrencyId::Native, &CHARLIE), 200);
-		// Bob and Charlie undelegate tokens.
-		assert_ok!(ResolversNetwork::undelegate(Origin::signed(BOB), ALICE, 200));
-		assert_ok!(ResolversNetwork::undelegate(Origin::signed(CHARLIE), ALICE, 200));
-		// The undelegated funds are still locked.
-		assert_eq!(Currencies::reserved_balance(CurrencyId::Native, &BOB), 200);
-		assert_eq!(Currencies::reserved_balance(CurrencyId::Native, &CHARLIE), 200);
-		// There are 2 pending funds.
-		let pending_funds = ResolversNetwork::pending_funds();
-		assert_eq!(pending_funds.len(), 2);
-		assert_eq!(pending_funds[0].owner, BOB);
-		assert_eq!(pending_funds[0].amount, 200);
-		assert_eq!(pending_funds[1].owner, CHARLIE);
-		assert_eq!(pending_funds[1].amount, 200);
+		assert_ok!(ResolversNetwork::join_resolvers_network(
+			Origin::signed(ALICE),
+			"".into(),
+			900,
+			[0; 32]
+		));
+		assert_ok!(ResolversNetwork::delegate(Origin::signed(BOB), ALICE, 300));
 
-		// Wait until the undelegate time.
-		run_to_block_number((UNDELEGATE_TIME / BLOCK_TIME).into());
-		// The undelegated funds are released.
-		assert_eq!(Currencies::reserved_balance(CurrencyId::Native, &BOB), 0);
-		assert_eq!(Currencies::reserved_balance(CurrencyId::Native, &CHARLIE), 0);
-		// Pending funds is removed from the queue.
-		let pending_funds = ResolversNetwork::pending_funds();
-		assert_eq!(pending_funds.len(), 0);
+		// The undelegations from a resolver with an open case are locked for the exposure time.
+		ResolversNetwork::open_case(&ALICE);
+		assert_eq!(ResolversNetwork::case_exposures(ALICE).unwrap().open_cases, 1);
+		assert_ok!(ResolversNetwork::undelegate(Origin::signed(BOB), ALICE, 100));
+		assert_eq!(
+			ResolversNetwork::pending_funds()[0].release_at,
+			INIT_TIMESTAMP + CASE_EXPOSURE_TIME
+		);
+
+		// The delegations stay exposed for the exposure time after the case is closed.
+		Timestamp::set_timestamp(INIT_TIMESTAMP + UNDELEGATE_TIME);
+		ResolversNetwork::close_case(&ALICE);
+		let exposure = ResolversNetwork::case_exposures(ALICE).unwrap();
+		assert_eq!(exposure.open_cases, 0);
+		assert_eq!(exposure.exposed_until, INIT_TIMESTAMP + UNDELEGATE_TIME + CASE_EXPOSURE_TIME);
+		assert_ok!(ResolversNetwork::undelegate(Origin::signed(BOB), ALICE, 100));
+		assert_eq!(
+			last_event(),
+			Event::ResolversNetwork(crate::Event::DelegationRemoved {
+				resolver: ALICE,
+				delegator: BOB,
+				amount: 100,
+				release_at: exposure.exposed_until,
+			})
+		);
+
+		// Once the slash windows are over, the undelegations wait for the undelegate time only.
+		Timestamp::set_timestamp(exposure.exposed_until);
+		assert_ok!(ResolversNetwork::undelegate(Origin::signed(BOB), ALICE, 100));
+		assert_eq!(
+			ResolversNetwork::pending_funds()[2].release_at,
+			exposure.exposed_until + UNDELEGATE_TIME
+		);
 	});
 }
 
 #[test]
-fn resign_works() {
+fn delegate_many_works() {
 	ExtBuilder::default().build().execute_with(|| {
 		System::set_block_number(1);
 
-		// Test an account that is not resolver resign.
+		for account in [ALICE, BOB] {
+			assert_ok!(Identities::create_identity(
+				Origin::signed(account),
+				"Resolver".into(),
+				IdentityType::Individual,
+				[].into(),
+			));
+			assert_ok!(ResolversNetwork::join_resolvers_network(
+				Origin::signed(account),
+				"".into(),
+				900,
+				[0; 32]
+			));
+		}
+
+		// All delegations are reverted if one of them fails.
 		assert_noop!(
-			ResolversNetwork::resign(Origin::signed(ALICE)),
-			Error::<Runtime>::NotAResolver,
+			ResolversNetwork::delegate_many(
+				Origin::signed(CHARLIE),
+				[(ALICE, 200), (CHARLIE, 50)].to_vec()
+			),
+			Error::<Runtime>::ResolverNotFound,
 		);
 
-		// Test a resolver resign.
-		assert_ok!(Identities::create_identity(
-			Origin::signed(ALICE),
-			"Alice".into(),
-			IdentityType::Individual,
-			[].into(),
+		assert_ok!(ResolversNetwork::delegate_many(
+			Origin::signed(CHARLIE),
+			[(ALICE, 200), (BOB, 50)].to_vec()
 		));
-		assert_ok!(ResolversNetwork::join_resolvers_network(Origin::signed(ALICE), "".into(), 500));
-		assert_ok!(ResolversNetwork::delegate(Origin::signed(BOB), ALICE, 200));
+
+		let resolver = ResolversNetwork::resolvers(ALICE).unwrap();
+		assert_eq!(resolver.status, crate::ResolverStatus::Active);
+		assert_eq!(resolver.total_stake, 1100);
+		let resolver = ResolversNetwork::resolvers(BOB).unwrap();
+		assert_eq!(resolver.status, crate::ResolverStatus::Candidacy);
+		assert_eq!(resolver.total_stake, 950);
+		assert_eq!(Currencies::reserved_balance(CurrencyId::Native, &CHARLIE), 250);
+		assert_eq!(ResolversNetwork::active_resolvers(), [ALICE].to_vec());
+
+		let events = System::events();
+		for (resolver, amount) in [(ALICE, 200), (BOB, 50)] {
+			assert!(events.iter().any(|record| record.event ==
+				Event::ResolversNetwork(crate::Event::DelegationAdded {
+					resolver,
+					delegator: CHARLIE,
+					amount,
+				})));
+		}
+	});
+}
+
+#[test]
+fn cannot_delegate_to_self() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+
+		for account in [ALICE, BOB] {
+			assert_ok!(Identities::create_identity(
+				Origin::signed(account),
+				"Resolver".into(),
+				IdentityType::Individual,
+				[].into(),
+			));
+			assert_ok!(ResolversNetwork::join_resolvers_network(
+				Origin::signed(account),
+				"".into(),
+				800,
+				[0; 32]
+			));
+		}
+
+		assert_noop!(
+			ResolversNetwork::delegate(Origin::signed(ALICE), ALICE, 100),
+			Error::<Runtime>::SelfDelegation,
+		);
+		assert_noop!(
+			ResolversNetwork::delegate_many(Origin::signed(ALICE), [(BOB, 50), (ALICE, 50)].into()),
+			Error::<Runtime>::SelfDelegation,
+		);
+
+		assert_ok!(ResolversNetwork::delegate(Origin::signed(ALICE), BOB, 100));
+		assert_noop!(
+			ResolversNetwork::redelegate(Origin::signed(ALICE), BOB, ALICE, 100),
+			Error::<Runtime>::SelfDelegation,
+		);
+	});
+}
+
+#[test]
+fn redelegate_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+
+		for account in [ALICE, BOB] {
+			assert_ok!(Identities::create_identity(
+				Origin::signed(account),
+				"Resolver".into(),
+				IdentityType::Individual,
+				[].into(),
+			));
+			assert_ok!(ResolversNetwork::join_resolvers_network(
+				Origin::signed(account),
+				"".into(),
+				900,
+				[0; 32]
+			));
+		}
 		assert_ok!(ResolversNetwork::delegate(Origin::signed(CHARLIE), ALICE, 200));
 
-		assert_ok!(ResolversNetwork::resign(Origin::signed(ALICE)));
+		assert_noop!(
+			ResolversNetwork::redelegate(Origin::signed(CHARLIE), ALICE, ALICE, 100),
+			Error::<Runtime>::SameResolver,
+		);
+		assert_noop!(
+			ResolversNetwork::redelegate(Origin::signed(CHARLIE), BOB, ALICE, 100),
+			Error::<Runtime>::DelegationNotFound,
+		);
+		assert_noop!(
+			ResolversNetwork::redelegate(Origin::signed(CHARLIE), ALICE, BOB, 300),
+			Error::<Runtime>::InvalidAmount,
+		);
+
+		// Move the delegation without the undelegate waiting time.
+		assert_ok!(ResolversNetwork::redelegate(Origin::signed(CHARLIE), ALICE, BOB, 150));
 		assert_eq!(
 			last_event(),
-			Event::ResolversNetwork(crate::Event::ResolverTerminated { account: ALICE })
+			Event::ResolversNetwork(crate::Event::DelegationMoved {
+				delegator: CHARLIE,
+				from_resolver: ALICE,
+				to_resolver: BOB,
+				amount: 150,
+			})
 		);
-		let resolver = ResolversNetwork::resolvers(ALICE).unwrap();
-		assert_eq!(resolver.status, crate::ResolverStatus::Terminated);
-		assert_eq!(resolver.delegations.len(), 0);
-		assert_eq!(resolver.self_stake, 0);
-		assert_eq!(resolver.total_stake, 0);
-
-		let pending_funds = ResolversNetwork::pending_funds();
-		assert_eq!(pending_funds.len(), 3);
-		assert_eq!(pending_funds[0].owner, ALICE);
-		assert_eq!(pending_funds[0].amount, 500);
-		assert_eq!(pending_funds[1].owner, BOB);
-		assert_eq!(pending_funds[1].amount, 200);
-		assert_eq!(pending_funds[2].owner, CHARLIE);
-		assert_eq!(pending_funds[2].amount, 200);
+		assert!(System::events().iter().any(|record| record.event ==
+			Event::ResolversNetwork(crate::Event::ResolverActivated {
+				account: BOB,
+				total_stake: 1050,
+			})));
 
-		run_to_block_number((UNDELEGATE_TIME / BLOCK_TIME).into());
+		let resolver = ResolversNetwork::resolvers(ALICE).unwrap();
+		assert_eq!(resolver.status, crate::ResolverStatus::Candidacy);
+		assert_eq!(resolver.total_stake, 950);
+		assert_eq!(resolver.delegations[0].amount, 50);
+		let resolver = ResolversNetwork::resolvers(BOB).unwrap();
+		assert_eq!(resolver.status, crate::ResolverStatus::Active);
+		assert_eq!(resolver.total_stake, 1050);
+		assert_eq!(resolver.delegations[0].delegator, CHARLIE);
+		assert_eq!(resolver.delegations[0].amount, 150);
 
-		assert_eq!(Currencies::reserved_balance(CurrencyId::Native, &ALICE), 0);
-		assert_eq!(Currencies::reserved_balance(CurrencyId::Native, &BOB), 0);
-		assert_eq!(Currencies::reserved_balance(CurrencyId::Native, &CHARLIE), 0);
+		assert_eq!(ResolversNetwork::active_resolvers(), [BOB].to_vec());
+		assert_eq!(ResolversNetwork::pending_funds().len(), 0);
+		assert_eq!(Currencies::reserved_balance(CurrencyId::Native, &CHARLIE), 200);
 	});
 }
 
 #[test]
-fn test_increase_resolver_credibility_works() {
+fn active_set_is_recomputed_every_era() {
 	ExtBuilder::default().build().execute_with(|| {
 		System::set_block_number(1);
-		// Create a active resolver.
-		assert_ok!(Identities::create_identity(
-			Origin::signed(ALICE),
-			"Alice".into(),
-			IdentityType::Individual,
-			[].into(),
-		));
-		assert_ok!(ResolversNetwork::join_resolvers_network(
-			Origin::signed(ALICE),
-			"".into(),
-			1000
-		));
+		MaxActiveResolvers::set(1);
+
+		for account in [ALICE, BOB] {
+			assert_ok!(Identities::create_identity(
+				Origin::signed(account),
+				"Resolver".into(),
+				IdentityType::Individual,
+				[].into(),
+			));
+			assert_ok!(ResolversNetwork::join_resolvers_network(
+				Origin::signed(account),
+				"".into(),
+				900,
+				[0; 32]
+			));
+		}
+
+		assert_ok!(ResolversNetwork::delegate(Origin::signed(CHARLIE), ALICE, 100));
+		assert_ok!(ResolversNetwork::delegate(Origin::signed(CHARLIE), BOB, 100));
+
+		// The active set is full, Bob is kept in the candidacy queue.
+		assert_eq!(ResolversNetwork::active_resolvers(), [ALICE].to_vec());
+		assert_eq!(ResolversNetwork::candidacy_queue(), [(BOB, 1000)].to_vec());
+		let resolver = ResolversNetwork::resolvers(BOB).unwrap();
+		assert_eq!(resolver.status, crate::ResolverStatus::Candidacy);
+
+		assert_ok!(ResolversNetwork::delegate(Origin::signed(CHARLIE), BOB, 200));
+		assert_eq!(ResolversNetwork::candidacy_queue(), [(BOB, 1200)].to_vec());
+		assert_eq!(ResolversNetwork::active_resolvers(), [ALICE].to_vec());
 
+		// The top staked resolver is selected at the next era.
+		run_to_block_number(ERA_DURATION);
+
+		assert_eq!(ResolversNetwork::active_resolvers(), [BOB].to_vec());
+		assert_eq!(ResolversNetwork::candidacy_queue(), [(ALICE, 1000)].to_vec());
 		let resolver = ResolversNetwork::resolvers(ALICE).unwrap();
+		assert_eq!(resolver.status, crate::ResolverStatus::Candidacy);
+		let resolver = ResolversNetwork::resolvers(BOB).unwrap();
 		assert_eq!(resolver.status, crate::ResolverStatus::Active);
-		assert_eq!(Identities::get_credibility(&ALICE).unwrap(), INITIAL_CREDIBILITY);
 
-		// Test reduce a resolver credibility.
-		assert_ok!(ResolversNetwork::increase_credibility(&ALICE, 10));
-		assert_eq!(Identities::get_credibility(&ALICE).unwrap(), INITIAL_CREDIBILITY + 10);
+		// The resigned resolver is removed from the candidacy queue.
+		assert_ok!(ResolversNetwork::resign(Origin::signed(ALICE)));
+		assert_eq!(ResolversNetwork::candidacy_queue(), [].to_vec());
 	});
 }
 
 #[test]
-fn test_reduce_resolver_credibility_works() {
+fn release_funds_keeps_other_reserves() {
 	ExtBuilder::default().build().execute_with(|| {
 		System::set_block_number(1);
 
-		// Test a resolver resign.
 		assert_ok!(Identities::create_identity(
 			Origin::signed(ALICE),
 			"Alice".into(),
@@ -337,30 +686,34 @@ fn test_reduce_resolver_credibility_works() {
 		assert_ok!(ResolversNetwork::join_resolvers_network(
 			Origin::signed(ALICE),
 			"".into(),
-			1000
+			500,
+			[0; 32]
 		));
+		assert_ok!(ResolversNetwork::delegate(Origin::signed(BOB), ALICE, 200));
+		assert_eq!(
+			ResolversNetwork::named_reserves(BOB, (RESOLVER_STAKE_RESERVE, CurrencyId::Native)),
+			200
+		);
+		assert_ok!(ResolversNetwork::undelegate(Origin::signed(BOB), ALICE, 200));
 
-		let resolver = ResolversNetwork::resolvers(ALICE).unwrap();
-		assert_eq!(resolver.status, crate::ResolverStatus::Active);
-		assert_eq!(Identities::get_credibility(&ALICE).unwrap(), INITIAL_CREDIBILITY);
-		assert_ok!(ResolversNetwork::decrease_credibility(ALICE, 10));
-
-		// Test reduce a resolver credibility.
-		assert_eq!(Identities::get_credibility(&ALICE).unwrap(), INITIAL_CREDIBILITY - 10);
+		// The funds reserved by another pallet are not released with the pending funds.
+		assert_ok!(Currencies::reserve(CurrencyId::Native, &BOB, 100));
+		run_to_block_number((UNDELEGATE_TIME / BLOCK_TIME).into());
 
-		// Test a resolver will be terminated if credibility under MinimumCredibility
-		assert_ok!(ResolversNetwork::decrease_credibility(ALICE, 30));
-		let resolver = ResolversNetwork::resolvers(ALICE).unwrap();
-		assert_eq!(Identities::get_credibility(&ALICE).unwrap(), 20);
-		assert_eq!(resolver.status, crate::ResolverStatus::Terminated)
+		assert_eq!(Currencies::reserved_balance(CurrencyId::Native, &BOB), 100);
+		assert_eq!(
+			ResolversNetwork::named_reserves(BOB, (RESOLVER_STAKE_RESERVE, CurrencyId::Native)),
+			0
+		);
+		assert_eq!(ResolversNetwork::pending_funds().len(), 0);
 	});
 }
 
 #[test]
-fn get_random_resolver_works() {
+fn release_funds_works() {
 	ExtBuilder::default().build().execute_with(|| {
 		System::set_block_number(1);
-		// Test a resolver resign.
+
 		assert_ok!(Identities::create_identity(
 			Origin::signed(ALICE),
 			"Alice".into(),
@@ -370,7 +723,394 @@ fn get_random_resolver_works() {
 		assert_ok!(ResolversNetwork::join_resolvers_network(
 			Origin::signed(ALICE),
 			"".into(),
-			1000
+			500,
+			[0; 32]
+		));
+		// Bob and Charlie delegate tokens.
+		assert_ok!(ResolversNetwork::delegate(Origin::signed(BOB), ALICE, 200));
+		assert_ok!(ResolversNetwork::delegate(Origin::signed(CHARLIE), ALICE, 200));
+		assert_eq!(Currencies::reserved_balance(CurrencyId::Native, &BOB), 200);
+		assert_eq!(Currencies::reserved_balance(CurrencyId::Native, &CHARLIE), 200);
+		// Bob and Charlie undelegate tokens.
+		assert_ok!(ResolversNetwork::undelegate(Origin::signed(BOB), ALICE, 200));
+		assert_ok!(ResolversNetwork::undelegate(Origin::signed(CHARLIE), ALICE, 200));
+		// The undelegated funds are still locked.
+		assert_eq!(Currencies::reserved_balance(CurrencyId::Native, &BOB), 200);
+		assert_eq!(Currencies::reserved_balance(CurrencyId::Native, &CHARLIE), 200);
+		// There are 2 pending funds.
+		let pending_funds = ResolversNetwork::pending_funds();
+		assert_eq!(pending_funds.len(), 2);
+		assert_eq!(pending_funds[0].owner, BOB);
+		assert_eq!(pending_funds[0].amount, 200);
+		assert_eq!(pending_funds[1].owner, CHARLIE);
+		assert_eq!(pending_funds[1].amount, 200);
+
+		// Wait until the undelegate time.
+		run_to_block_number((UNDELEGATE_TIME / BLOCK_TIME).into());
+		// The undelegated funds are released.
+		assert_eq!(Currencies::reserved_balance(CurrencyId::Native, &BOB), 0);
+		assert_eq!(Currencies::reserved_balance(CurrencyId::Native, &CHARLIE), 0);
+		// Pending funds is removed from the queue.
+		let pending_funds = ResolversNetwork::pending_funds();
+		assert_eq!(pending_funds.len(), 0);
+	});
+}
+
+#[test]
+fn release_pending_funds_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		Timestamp::set_timestamp(INIT_TIMESTAMP);
+
+		assert_ok!(Identities::create_identity(
+			Origin::signed(ALICE),
+			"Alice".into(),
+			IdentityType::Individual,
+			[].into(),
+		));
+		assert_ok!(ResolversNetwork::join_resolvers_network(
+			Origin::signed(ALICE),
+			"".into(),
+			500,
+			[0; 32]
+		));
+		assert_ok!(ResolversNetwork::delegate(Origin::signed(BOB), ALICE, 200));
+		assert_ok!(ResolversNetwork::delegate(Origin::signed(CHARLIE), ALICE, 200));
+		assert_ok!(ResolversNetwork::undelegate(Origin::signed(BOB), ALICE, 200));
+		assert_ok!(ResolversNetwork::undelegate(Origin::signed(CHARLIE), ALICE, 200));
+
+		// The funds are still in the locking time.
+		assert_noop!(
+			ResolversNetwork::release_pending_funds(Origin::signed(ALICE), BOB),
+			Error::<Runtime>::NoReleasableFunds,
+		);
+
+		Timestamp::set_timestamp(INIT_TIMESTAMP + UNDELEGATE_TIME);
+
+		// Anyone can release the pending funds of an account.
+		assert_ok!(ResolversNetwork::release_pending_funds(Origin::signed(ALICE), BOB));
+		assert_eq!(
+			last_event(),
+			Event::ResolversNetwork(crate::Event::PendingFundsReleased {
+				account: BOB,
+				amount: 200,
+			})
+		);
+		assert_eq!(Currencies::reserved_balance(CurrencyId::Native, &BOB), 0);
+		assert_eq!(Currencies::reserved_balance(CurrencyId::Native, &CHARLIE), 200);
+
+		let pending_funds = ResolversNetwork::pending_funds();
+		assert_eq!(pending_funds.len(), 1);
+		assert_eq!(pending_funds[0].owner, CHARLIE);
+
+		assert_noop!(
+			ResolversNetwork::release_pending_funds(Origin::signed(ALICE), BOB),
+			Error::<Runtime>::NoReleasableFunds,
+		);
+	});
+}
+
+#[test]
+fn offchain_worker_releases_pending_funds_in_batches() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		Timestamp::set_timestamp(INIT_TIMESTAMP);
+		ReleaseBatchSize::set(1);
+
+		assert_ok!(Identities::create_identity(
+			Origin::signed(ALICE),
+			"Alice".into(),
+			IdentityType::Individual,
+			[].into(),
+		));
+		assert_ok!(ResolversNetwork::join_resolvers_network(
+			Origin::signed(ALICE),
+			"".into(),
+			500,
+			[0; 32]
+		));
+		assert_ok!(ResolversNetwork::delegate(Origin::signed(BOB), ALICE, 200));
+		assert_ok!(ResolversNetwork::delegate(Origin::signed(CHARLIE), ALICE, 200));
+		assert_ok!(ResolversNetwork::undelegate(Origin::signed(BOB), ALICE, 200));
+		assert_ok!(ResolversNetwork::undelegate(Origin::signed(CHARLIE), ALICE, 200));
+
+		Timestamp::set_timestamp(INIT_TIMESTAMP + UNDELEGATE_TIME);
+
+		// Only one due fund is released in a run, the rest is released in the next run.
+		ResolversNetwork::offchain_worker(System::block_number());
+		assert_eq!(Currencies::reserved_balance(CurrencyId::Native, &BOB), 0);
+		assert_eq!(Currencies::reserved_balance(CurrencyId::Native, &CHARLIE), 200);
+		assert_eq!(ResolversNetwork::pending_funds().len(), 1);
+
+		ResolversNetwork::offchain_worker(System::block_number());
+		assert_eq!(Currencies::reserved_balance(CurrencyId::Native, &CHARLIE), 0);
+		assert_eq!(ResolversNetwork::pending_funds().len(), 0);
+	});
+}
+
+#[test]
+fn resign_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+
+		// Test an account that is not resolver resign.
+		assert_noop!(
+			ResolversNetwork::resign(Origin::signed(ALICE)),
+			Error::<Runtime>::NotAResolver,
+		);
+
+		// Test a resolver resign.
+		assert_ok!(Identities::create_identity(
+			Origin::signed(ALICE),
+			"Alice".into(),
+			IdentityType::Individual,
+			[].into(),
+		));
+		assert_ok!(ResolversNetwork::join_resolvers_network(
+			Origin::signed(ALICE),
+			"".into(),
+			500,
+			[0; 32]
+		));
+		assert_ok!(ResolversNetwork::delegate(Origin::signed(BOB), ALICE, 200));
+		assert_ok!(ResolversNetwork::delegate(Origin::signed(CHARLIE), ALICE, 200));
+
+		// The identity of a resolver is kept until it resigns.
+		assert_noop!(
+			Identities::remove_identity(Origin::signed(ALICE)),
+			pallet_identities::Error::<Runtime>::ResolverIdentityInUse,
+		);
+
+		assert_ok!(ResolversNetwork::resign(Origin::signed(ALICE)));
+		assert_eq!(
+			last_event(),
+			Event::ResolversNetwork(crate::Event::ResolverTerminated {
+				account: ALICE,
+				release_at: UNDELEGATE_TIME,
+			})
+		);
+		let resolver = ResolversNetwork::resolvers(ALICE).unwrap();
+		assert_eq!(resolver.status, crate::ResolverStatus::Terminated);
+		assert_eq!(resolver.delegations.len(), 0);
+		assert_eq!(resolver.self_stake, 0);
+		assert_eq!(resolver.total_stake, 0);
+
+		let pending_funds = ResolversNetwork::pending_funds();
+		assert_eq!(pending_funds.len(), 3);
+		assert_eq!(pending_funds[0].owner, ALICE);
+		assert_eq!(pending_funds[0].amount, 500);
+		assert_eq!(pending_funds[1].owner, BOB);
+		assert_eq!(pending_funds[1].amount, 200);
+		assert_eq!(pending_funds[2].owner, CHARLIE);
+		assert_eq!(pending_funds[2].amount, 200);
+
+		run_to_block_number((UNDELEGATE_TIME / BLOCK_TIME).into());
+
+		assert_eq!(Currencies::reserved_balance(CurrencyId::Native, &ALICE), 0);
+		assert_eq!(Currencies::reserved_balance(CurrencyId::Native, &BOB), 0);
+		assert_eq!(Currencies::reserved_balance(CurrencyId::Native, &CHARLIE), 0);
+
+		assert_ok!(Identities::remove_identity(Origin::signed(ALICE)));
+	});
+}
+
+#[test]
+fn resign_unwinds_delegations_by_pages() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		UnwindBatchSize::set(1);
+
+		assert_ok!(Identities::create_identity(
+			Origin::signed(ALICE),
+			"Alice".into(),
+			IdentityType::Individual,
+			[].into(),
+		));
+		assert_ok!(ResolversNetwork::join_resolvers_network(
+			Origin::signed(ALICE),
+			"".into(),
+			500,
+			[0; 32]
+		));
+		assert_ok!(ResolversNetwork::delegate(Origin::signed(BOB), ALICE, 200));
+		assert_ok!(ResolversNetwork::delegate(Origin::signed(CHARLIE), ALICE, 200));
+
+		assert_noop!(
+			ResolversNetwork::continue_resign(Origin::signed(BOB), ALICE),
+			Error::<Runtime>::NothingToUnwind,
+		);
+
+		// Only the first page of the delegations is unwound by the resignation.
+		assert_ok!(ResolversNetwork::resign(Origin::signed(ALICE)));
+		assert_eq!(ResolversNetwork::unwinding_resolvers(ALICE), Some(UNDELEGATE_TIME));
+		let resolver = ResolversNetwork::resolvers(ALICE).unwrap();
+		assert_eq!(resolver.status, crate::ResolverStatus::Terminated);
+		assert_eq!(resolver.delegations.len(), 1);
+		assert_eq!(resolver.total_stake, 200);
+		assert_eq!(ResolversNetwork::pending_funds().len(), 2);
+
+		// The delegations waiting to be unwound cannot be moved, and the resolver cannot join
+		// again before they are all unwound.
+		assert_noop!(
+			ResolversNetwork::undelegate(Origin::signed(CHARLIE), ALICE, 200),
+			Error::<Runtime>::ResolverTerminated,
+		);
+		assert_noop!(
+			ResolversNetwork::join_resolvers_network(
+				Origin::signed(ALICE),
+				"".into(),
+				500,
+				[0; 32]
+			),
+			Error::<Runtime>::ResolverUnwinding,
+		);
+
+		// Anyone can unwind the next page.
+		assert_ok!(ResolversNetwork::continue_resign(Origin::signed(BOB), ALICE));
+		assert_eq!(
+			last_event(),
+			Event::ResolversNetwork(crate::Event::DelegationsUnwound {
+				resolver: ALICE,
+				unwound: 1,
+				remaining: 0,
+			})
+		);
+		assert_eq!(ResolversNetwork::unwinding_resolvers(ALICE), None);
+		let resolver = ResolversNetwork::resolvers(ALICE).unwrap();
+		assert_eq!(resolver.delegations.len(), 0);
+		assert_eq!(resolver.total_stake, 0);
+
+		let pending_funds = ResolversNetwork::pending_funds();
+		assert_eq!(pending_funds.len(), 3);
+		assert_eq!(pending_funds[2].owner, CHARLIE);
+		assert_eq!(pending_funds[2].amount, 200);
+		assert_eq!(pending_funds[2].release_at, UNDELEGATE_TIME);
+
+		assert_noop!(
+			ResolversNetwork::continue_resign(Origin::signed(BOB), ALICE),
+			Error::<Runtime>::NothingToUnwind,
+		);
+	});
+}
+
+#[test]
+fn resign_is_unwound_in_idle_blocks() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		UnwindBatchSize::set(1);
+
+		assert_ok!(Identities::create_identity(
+			Origin::signed(ALICE),
+			"Alice".into(),
+			IdentityType::Individual,
+			[].into(),
+		));
+		assert_ok!(ResolversNetwork::join_resolvers_network(
+			Origin::signed(ALICE),
+			"".into(),
+			500,
+			[0; 32]
+		));
+		assert_ok!(ResolversNetwork::delegate(Origin::signed(BOB), ALICE, 200));
+		assert_ok!(ResolversNetwork::delegate(Origin::signed(CHARLIE), ALICE, 200));
+		assert_ok!(ResolversNetwork::resign(Origin::signed(ALICE)));
+
+		// No page is unwound without enough remaining weight.
+		assert_eq!(ResolversNetwork::on_idle(1, 0), 0);
+		assert_eq!(ResolversNetwork::resolvers(ALICE).unwrap().delegations.len(), 1);
+
+		ResolversNetwork::on_idle(1, u64::MAX);
+		assert_eq!(ResolversNetwork::unwinding_resolvers(ALICE), None);
+		assert_eq!(ResolversNetwork::resolvers(ALICE).unwrap().delegations.len(), 0);
+		assert_eq!(ResolversNetwork::pending_funds().len(), 3);
+
+		run_to_block_number((UNDELEGATE_TIME / BLOCK_TIME).into());
+
+		assert_eq!(Currencies::reserved_balance(CurrencyId::Native, &ALICE), 0);
+		assert_eq!(Currencies::reserved_balance(CurrencyId::Native, &BOB), 0);
+		assert_eq!(Currencies::reserved_balance(CurrencyId::Native, &CHARLIE), 0);
+	});
+}
+
+#[test]
+fn test_increase_resolver_credibility_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		// Create a active resolver.
+		assert_ok!(Identities::create_identity(
+			Origin::signed(ALICE),
+			"Alice".into(),
+			IdentityType::Individual,
+			[].into(),
+		));
+		assert_ok!(ResolversNetwork::join_resolvers_network(
+			Origin::signed(ALICE),
+			"".into(),
+			1000,
+			[0; 32]
+		));
+
+		let resolver = ResolversNetwork::resolvers(ALICE).unwrap();
+		assert_eq!(resolver.status, crate::ResolverStatus::Active);
+		assert_eq!(Identities::get_credibility(&ALICE).unwrap(), INITIAL_CREDIBILITY);
+
+		// Test reduce a resolver credibility.
+		assert_ok!(ResolversNetwork::increase_credibility(&ALICE, 10));
+		assert_eq!(Identities::get_credibility(&ALICE).unwrap(), INITIAL_CREDIBILITY + 10);
+	});
+}
+
+#[test]
+fn test_reduce_resolver_credibility_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+
+		// Test a resolver resign.
+		assert_ok!(Identities::create_identity(
+			Origin::signed(ALICE),
+			"Alice".into(),
+			IdentityType::Individual,
+			[].into(),
+		));
+		assert_ok!(ResolversNetwork::join_resolvers_network(
+			Origin::signed(ALICE),
+			"".into(),
+			1000,
+			[0; 32]
+		));
+
+		let resolver = ResolversNetwork::resolvers(ALICE).unwrap();
+		assert_eq!(resolver.status, crate::ResolverStatus::Active);
+		assert_eq!(Identities::get_credibility(&ALICE).unwrap(), INITIAL_CREDIBILITY);
+		assert_ok!(ResolversNetwork::decrease_credibility(ALICE, 10));
+
+		// Test reduce a resolver credibility.
+		assert_eq!(Identities::get_credibility(&ALICE).unwrap(), INITIAL_CREDIBILITY - 10);
+
+		// Test a resolver will be terminated if credibility under MinimumCredibility
+		assert_ok!(ResolversNetwork::decrease_credibility(ALICE, 30));
+		let resolver = ResolversNetwork::resolvers(ALICE).unwrap();
+		assert_eq!(Identities::get_credibility(&ALICE).unwrap(), 20);
+		assert_eq!(resolver.status, crate::ResolverStatus::Terminated)
+	});
+}
+
+#[test]
+fn get_random_resolver_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		// Test a resolver resign.
+		assert_ok!(Identities::create_identity(
+			Origin::signed(ALICE),
+			"Alice".into(),
+			IdentityType::Individual,
+			[].into(),
+		));
+		assert_ok!(ResolversNetwork::join_resolvers_network(
+			Origin::signed(ALICE),
+			"".into(),
+			1000,
+			[0; 32]
 		));
 		assert_ok!(Identities::create_identity(
 			Origin::signed(BOB),
@@ -378,7 +1118,12 @@ fn get_random_resolver_works() {
 			IdentityType::Individual,
 			[].into(),
 		));
-		assert_ok!(ResolversNetwork::join_resolvers_network(Origin::signed(BOB), "".into(), 1000));
+		assert_ok!(ResolversNetwork::join_resolvers_network(
+			Origin::signed(BOB),
+			"".into(),
+			1000,
+			[0; 32]
+		));
 		assert_ok!(Identities::create_identity(
 			Origin::signed(CHARLIE),
 			"Alice".into(),
@@ -388,24 +1133,344 @@ fn get_random_resolver_works() {
 		assert_ok!(ResolversNetwork::join_resolvers_network(
 			Origin::signed(CHARLIE),
 			"".into(),
-			1000
+			1000,
+			[0; 32]
 		));
 
 		run_to_block_number(32);
 
-		let resolver1 = ResolversNetwork::get_resolver(
-			<Runtime as system::Config>::Hashing::hash_of(&"payment1".as_bytes()),
+		let mut selected: Vec<AccountId> = Vec::new();
+
+		for i in 0..20u32 {
+			let resolver = ResolversNetwork::get_resolver(
+				<Runtime as system::Config>::Hashing::hash_of(&i),
+				[].into(),
+				[].into(),
+				[].into(),
+				None,
+			)
+			.unwrap();
+
+			assert!([ALICE, BOB, CHARLIE].contains(&resolver));
+			if !selected.contains(&resolver) {
+				selected.push(resolver);
+			}
+		}
+
+		// The resolvers are not always the same one.
+		assert!(selected.len() > 1);
+
+		// The selected resolvers are excluded.
+		let resolver = ResolversNetwork::get_resolver(
+			<Runtime as system::Config>::Hashing::hash_of(&"payment".as_bytes()),
+			[ALICE, BOB].into(),
+			[].into(),
 			[].into(),
+			None,
 		)
 		.unwrap();
+		assert_eq!(resolver, CHARLIE);
+
+		// The resolvers speaking the preferred languages are drawn first.
+		assert_ok!(Identities::set_languages(Origin::signed(BOB), [*b"en", *b"vi"].into()));
+		for i in 0..10u32 {
+			let resolver = ResolversNetwork::get_resolver(
+				<Runtime as system::Config>::Hashing::hash_of(&i),
+				[].into(),
+				[*b"vi"].into(),
+				[].into(),
+				None,
+			)
+			.unwrap();
+			assert_eq!(resolver, BOB);
+		}
+
+		// Any resolver can be drawn if none of them speaks the languages.
+		let resolver = ResolversNetwork::get_resolver(
+			<Runtime as system::Config>::Hashing::hash_of(&"payment".as_bytes()),
+			[BOB].into(),
+			[*b"vi"].into(),
+			[].into(),
+			None,
+		)
+		.unwrap();
+		assert!([ALICE, CHARLIE].contains(&resolver));
+
+		// Among the resolvers speaking the languages, the experts in the category are preferred.
+		assert_ok!(Identities::set_languages(Origin::signed(ALICE), [*b"vi"].into()));
+		assert_ok!(ResolversNetwork::set_expertise(
+			Origin::signed(CHARLIE),
+			[PaymentCategory::Travel].into()
+		));
+		assert_ok!(ResolversNetwork::set_expertise(
+			Origin::signed(ALICE),
+			[PaymentCategory::DigitalGoods].into()
+		));
+		for i in 0..10u32 {
+			let resolver = ResolversNetwork::get_resolver(
+				<Runtime as system::Config>::Hashing::hash_of(&i),
+				[].into(),
+				[*b"vi"].into(),
+				[].into(),
+				Some(PaymentCategory::DigitalGoods),
+			)
+			.unwrap();
+			assert_eq!(resolver, ALICE);
+
+			// The expertise does not override the languages.
+			let resolver = ResolversNetwork::get_resolver(
+				<Runtime as system::Config>::Hashing::hash_of(&i),
+				[].into(),
+				[*b"vi"].into(),
+				[].into(),
+				Some(PaymentCategory::Travel),
+			)
+			.unwrap();
+			assert!([ALICE, BOB].contains(&resolver));
+
+			let resolver = ResolversNetwork::get_resolver(
+				<Runtime as system::Config>::Hashing::hash_of(&i),
+				[].into(),
+				[].into(),
+				[].into(),
+				Some(PaymentCategory::Travel),
+			)
+			.unwrap();
+			assert_eq!(resolver, CHARLIE);
+		}
+	});
+}
+
+#[test]
+fn selection_strategies_work() {
+	type Hashing = <Runtime as system::Config>::Hashing;
+	type Uniform = selection::UniformSelection;
+	type StakeWeighted = selection::StakeWeightedSelection;
+
+	let candidates: Vec<(AccountId, Balance)> = vec![(ALICE, 1000), (BOB, 0), (CHARLIE, 3000)];
+	let mut uniform_selected = [0u32; 3];
+	let mut weighted_selected = [0u32; 3];
+
+	for i in 0..200u32 {
+		let seed = Hashing::hash_of(&i);
+		let index = <Uniform as SelectionStrategy<_, _, Hashing>>::select(seed, &candidates);
+		uniform_selected[index] += 1;
+		let index = <StakeWeighted as SelectionStrategy<_, _, Hashing>>::select(seed, &candidates);
+		weighted_selected[index] += 1;
+
+		// The draw is deterministic for a given seed.
+		assert_eq!(
+			<StakeWeighted as SelectionStrategy<_, _, Hashing>>::select(seed, &candidates),
+			index
+		);
+	}
 
-		let resolver2 = ResolversNetwork::get_resolver(
-			<Runtime as system::Config>::Hashing::hash_of(&"payment2".as_bytes()),
+	// Every candidate can be drawn uniformly.
+	assert!(uniform_selected.iter().all(|count| *count > 0));
+
+	// A candidate without a stake is never drawn by stake, the larger stake is drawn more often.
+	assert_eq!(weighted_selected[1], 0);
+	assert!(weighted_selected[2] > weighted_selected[0]);
+
+	// The candidates are drawn uniformly if none of them has a stake.
+	let candidates: Vec<(AccountId, Balance)> = vec![(ALICE, 0), (BOB, 0)];
+	let mut selected = [0u32; 2];
+	for i in 0..20u32 {
+		let seed = Hashing::hash_of(&i);
+		selected[<StakeWeighted as SelectionStrategy<_, _, Hashing>>::select(seed, &candidates)] +=
+			1;
+	}
+	assert!(selected.iter().all(|count| *count > 0));
+}
+
+#[test]
+fn get_resolver_skips_conflicted_resolvers() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+
+		for (account, self_stake) in [(ALICE, 800), (BOB, 800), (CHARLIE, 900)] {
+			assert_ok!(Identities::create_identity(
+				Origin::signed(account),
+				"Resolver".into(),
+				IdentityType::Individual,
+				[].into(),
+			));
+			assert_ok!(ResolversNetwork::join_resolvers_network(
+				Origin::signed(account),
+				"".into(),
+				self_stake,
+				[0; 32]
+			));
+		}
+		assert_ok!(ResolversNetwork::delegate(Origin::signed(BOB), ALICE, 200));
+		assert_ok!(ResolversNetwork::delegate(
+			Origin::signed(ALICE),
+			BOB,
+			CONFLICT_DELEGATION_THRESHOLD
+		));
+		assert_ok!(ResolversNetwork::delegate(
+			Origin::signed(CHARLIE),
+			BOB,
+			CONFLICT_DELEGATION_THRESHOLD
+		));
+		assert_ok!(ResolversNetwork::delegate(Origin::signed(ALICE), CHARLIE, 100));
+
+		run_to_block_number(32);
+		assert_eq!(ResolversNetwork::active_resolvers().len(), 3);
+
+		for i in 0..10u32 {
+			let payment_hash = <Runtime as system::Config>::Hashing::hash_of(&i);
+
+			// Bob delegated to Alice above the threshold, so Alice cannot resolve the disputes of
+			// Bob and Bob cannot resolve the disputes of Alice.
+			let resolver = ResolversNetwork::get_resolver(
+				payment_hash,
+				[BOB].into(),
+				[].into(),
+				[BOB].into(),
+				None,
+			)
+			.unwrap();
+			assert_eq!(resolver, CHARLIE);
+			let resolver = ResolversNetwork::get_resolver(
+				payment_hash,
+				[ALICE].into(),
+				[].into(),
+				[ALICE].into(),
+				None,
+			)
+			.unwrap();
+			assert_eq!(resolver, CHARLIE);
+		}
+
+		// The delegations up to the threshold are not a conflict.
+		let resolver = ResolversNetwork::get_resolver(
+			<Runtime as system::Config>::Hashing::hash_of(&"payment".as_bytes()),
+			[ALICE, CHARLIE].into(),
 			[].into(),
+			[CHARLIE].into(),
+			None,
 		)
 		.unwrap();
+		assert_eq!(resolver, BOB);
 
-		assert_ne!(resolver1, resolver2);
+		assert_noop!(
+			ResolversNetwork::get_resolver(
+				<Runtime as system::Config>::Hashing::hash_of(&"payment".as_bytes()),
+				[CHARLIE].into(),
+				[].into(),
+				[ALICE, BOB].into(),
+				None,
+			),
+			Error::<Runtime>::NoAnyActiveResolver
+		);
+	});
+}
+
+#[test]
+fn get_resolver_skips_parties_organizations_and_reviewers() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+
+		for (account, identity_type) in [
+			(ALICE, IdentityType::Organization),
+			(BOB, IdentityType::Individual),
+			(CHARLIE, IdentityType::Individual),
+		] {
+			assert_ok!(Identities::create_identity(
+				Origin::signed(account),
+				"Resolver".into(),
+				identity_type,
+				[].into(),
+			));
+			assert_ok!(ResolversNetwork::join_resolvers_network(
+				Origin::signed(account),
+				"".into(),
+				800,
+				[0; 32]
+			));
+		}
+		assert_ok!(Identities::invite_member(Origin::signed(ALICE), BOB));
+		assert_ok!(Identities::accept_membership(Origin::signed(BOB), ALICE));
+		assert_ok!(Identities::review_identity(Origin::signed(BOB), CHARLIE, "Good".into()));
+
+		run_to_block_number(32);
+		assert_eq!(ResolversNetwork::active_resolvers().len(), 3);
+
+		let payment_hash = <Runtime as system::Config>::Hashing::hash_of(&"payment".as_bytes());
+
+		// Bob is the party and Alice is the organization of Bob.
+		let resolver =
+			ResolversNetwork::get_resolver(payment_hash, [].into(), [].into(), [BOB].into(), None)
+				.unwrap();
+		assert_eq!(resolver, CHARLIE);
+
+		// Alice is the party and Bob is a member of Alice.
+		let resolver =
+			ResolversNetwork::get_resolver(payment_hash, [].into(), [].into(), [ALICE].into(), None)
+				.unwrap();
+		assert_eq!(resolver, CHARLIE);
+
+		// Charlie is the party and Bob reviewed the identity of Charlie.
+		let resolver = ResolversNetwork::get_resolver(
+			payment_hash,
+			[].into(),
+			[].into(),
+			[CHARLIE].into(),
+			None,
+		)
+		.unwrap();
+		assert_eq!(resolver, ALICE);
+
+		// Alice is no longer conflicted once Bob leaves the organization.
+		assert_ok!(Identities::remove_member(Origin::signed(BOB), BOB));
+		let resolver = ResolversNetwork::get_resolver(
+			payment_hash,
+			[].into(),
+			[].into(),
+			[BOB, CHARLIE].into(),
+			None,
+		)
+		.unwrap();
+		assert_eq!(resolver, ALICE);
+
+		// Charlie attested Alice as a supplier, either of them cannot resolve the disputes of the
+		// other until the attestation is revoked.
+		assert_ok!(Identities::attest(Origin::signed(CHARLIE), ALICE, AttestationType::Supplier));
+		assert_noop!(
+			ResolversNetwork::get_resolver(
+				payment_hash,
+				[BOB].into(),
+				[].into(),
+				[ALICE].into(),
+				None
+			),
+			Error::<Runtime>::NoAnyActiveResolver
+		);
+		assert_noop!(
+			ResolversNetwork::get_resolver(
+				payment_hash,
+				[BOB].into(),
+				[].into(),
+				[CHARLIE].into(),
+				None
+			),
+			Error::<Runtime>::NoAnyActiveResolver
+		);
+		assert_ok!(Identities::revoke_attestation(
+			Origin::signed(CHARLIE),
+			ALICE,
+			AttestationType::Supplier
+		));
+		let resolver = ResolversNetwork::get_resolver(
+			payment_hash,
+			[BOB].into(),
+			[].into(),
+			[ALICE].into(),
+			None,
+		)
+		.unwrap();
+		assert_eq!(resolver, CHARLIE);
 	});
 }
 
@@ -423,7 +1488,8 @@ fn blacklist_resolver_if_credibility_to_low() {
 		assert_ok!(ResolversNetwork::join_resolvers_network(
 			Origin::signed(ALICE),
 			"".into(),
-			1000
+			1000,
+			[0; 32]
 		));
 
 		assert_ok!(ResolversNetwork::decrease_credibility(ALICE, 40));
@@ -460,7 +1526,366 @@ fn blacklist_resolver_if_credibility_to_low() {
 		assert_noop!(ResolversNetwork::join_resolvers_network(
 			Origin::signed(ALICE),
 			"".into(),
-			1000
+			1000,
+			[0; 32]
 		), Error::<Runtime>::AccountIsBlacklisted);
 	});
 }
+
+#[test]
+fn genesis_resolvers_work() {
+	ExtBuilder::default()
+		.resolvers(vec![
+			(ALICE, "Alice".into(), ACTIVATION_STAKE_AMOUNT),
+			(BOB, "Bob".into(), MINIMUM_SELF_STAKE),
+		])
+		.build()
+		.execute_with(|| {
+			let alice = ResolversNetwork::resolvers(&ALICE).unwrap();
+			assert_eq!(alice.status, ResolverStatus::Active);
+			assert_eq!(
+				alice.application_digest,
+				<Runtime as system::Config>::Hashing::hash_of(&"Alice")
+			);
+			assert_eq!(alice.total_stake, ACTIVATION_STAKE_AMOUNT);
+
+			let bob = ResolversNetwork::resolvers(&BOB).unwrap();
+			assert_eq!(bob.status, ResolverStatus::Candidacy);
+
+			assert_eq!(ResolversNetwork::active_resolvers(), vec![ALICE]);
+			assert_eq!(
+				Currencies::reserved_balance(CurrencyId::Native, &ALICE),
+				ACTIVATION_STAKE_AMOUNT
+			);
+			assert_eq!(Currencies::reserved_balance(CurrencyId::Native, &BOB), MINIMUM_SELF_STAKE);
+		});
+}
+
+#[test]
+fn nomination_pool_works() {
+	ExtBuilder::default()
+		.resolvers(vec![(ALICE, "Alice".into(), ACTIVATION_STAKE_AMOUNT)])
+		.build()
+		.execute_with(|| {
+			System::set_block_number(1);
+			Timestamp::set_timestamp(INIT_TIMESTAMP);
+
+			assert_noop!(
+				ResolversNetwork::create_pool(
+					Origin::signed(BOB),
+					PoolStrategy::TopStaked,
+					Permill::from_percent(20),
+				),
+				Error::<Runtime>::PoolCommissionTooHigh,
+			);
+			assert_ok!(ResolversNetwork::create_pool(
+				Origin::signed(BOB),
+				PoolStrategy::TopStaked,
+				Permill::from_percent(10),
+			));
+			assert_eq!(
+				last_event(),
+				Event::ResolversNetwork(crate::Event::PoolCreated {
+					pool_id: 0,
+					operator: BOB,
+					strategy: PoolStrategy::TopStaked,
+					commission: Permill::from_percent(10),
+				})
+			);
+
+			// The stake of the member is delegated by the pool account.
+			let pool_account = ResolversNetwork::pool_account(0);
+			assert_ok!(ResolversNetwork::join_pool(Origin::signed(CHARLIE), 0, 500));
+			assert_eq!(ResolversNetwork::pool_members(0, CHARLIE), 500);
+			assert_eq!(pool_delegation(0, ALICE), 500);
+			assert_eq!(ResolversNetwork::resolvers(ALICE).unwrap().total_stake, 1500);
+			assert_eq!(Currencies::free_balance(CurrencyId::Native, &CHARLIE), 500);
+
+			// The rewards paid to the pool are compounded after the commission.
+			assert_ok!(Currencies::deposit(CurrencyId::Native, &pool_account, 100));
+			assert_ok!(ResolversNetwork::settle_pool(Origin::signed(ALICE), 0));
+			assert!(System::events().iter().any(|record| record.event ==
+				Event::ResolversNetwork(crate::Event::PoolRewarded {
+					pool_id: 0,
+					reward: 100,
+					commission: 10,
+				})));
+			assert_eq!(Currencies::free_balance(CurrencyId::Native, &BOB), 1010);
+			assert_eq!(pool_delegation(0, ALICE), 590);
+			assert_eq!(ResolversNetwork::pool_member_stake(0, &CHARLIE), 590);
+
+			// The points of the new members are priced by the compounded stake.
+			assert_ok!(ResolversNetwork::join_pool(Origin::signed(BOB), 0, 295));
+			assert_eq!(ResolversNetwork::pool_members(0, BOB), 250);
+
+			assert_noop!(
+				ResolversNetwork::leave_pool(Origin::signed(CHARLIE), 0, 501),
+				Error::<Runtime>::InsufficientPoolPoints,
+			);
+			assert_ok!(ResolversNetwork::leave_pool(Origin::signed(CHARLIE), 0, 250));
+			assert_eq!(
+				last_event(),
+				Event::ResolversNetwork(crate::Event::PoolLeft {
+					pool_id: 0,
+					member: CHARLIE,
+					points: 250,
+					amount: 295,
+					release_at: INIT_TIMESTAMP + UNDELEGATE_TIME,
+				})
+			);
+			assert_eq!(ResolversNetwork::pool_members(0, CHARLIE), 250);
+			assert_eq!(pool_delegation(0, ALICE), 590);
+
+			// The unbonding funds are withdrawn after the undelegate time.
+			assert_noop!(
+				ResolversNetwork::withdraw_unbonded(Origin::signed(CHARLIE), 0),
+				Error::<Runtime>::NoReleasableFunds,
+			);
+			Timestamp::set_timestamp(INIT_TIMESTAMP + UNDELEGATE_TIME);
+			assert_ok!(ResolversNetwork::withdraw_unbonded(Origin::signed(CHARLIE), 0));
+			assert_eq!(
+				last_event(),
+				Event::ResolversNetwork(crate::Event::PoolWithdrawn {
+					pool_id: 0,
+					member: CHARLIE,
+					amount: 295,
+				})
+			);
+			assert_eq!(Currencies::free_balance(CurrencyId::Native, &CHARLIE), 795);
+			assert!(ResolversNetwork::pool_unbondings(0, CHARLIE).is_empty());
+
+			let pool = ResolversNetwork::nomination_pools(0).unwrap();
+			assert_eq!((pool.points, pool.stake, pool.unbonding), (500, 590, 0));
+		});
+}
+
+#[test]
+fn nomination_pool_shares_slashes() {
+	ExtBuilder::default()
+		.resolvers(vec![
+			(ALICE, "Alice".into(), ACTIVATION_STAKE_AMOUNT),
+			(BOB, "Bob".into(), ACTIVATION_STAKE_AMOUNT),
+		])
+		.build()
+		.execute_with(|| {
+			System::set_block_number(1);
+			Timestamp::set_timestamp(INIT_TIMESTAMP);
+
+			let (dave, eve): (AccountId, AccountId) = (4, 5);
+			for member in [dave, eve] {
+				assert_ok!(Currencies::deposit(CurrencyId::Native, &member, 1_000));
+			}
+
+			assert_ok!(ResolversNetwork::create_pool(
+				Origin::signed(CHARLIE),
+				PoolStrategy::LeastStaked,
+				Permill::zero(),
+			));
+			let pool_account = ResolversNetwork::pool_account(0);
+
+			// The stake is spread over the resolvers.
+			assert_ok!(ResolversNetwork::join_pool(Origin::signed(dave), 0, 300));
+			assert_ok!(ResolversNetwork::join_pool(Origin::signed(eve), 0, 300));
+			assert_eq!(pool_delegation(0, ALICE), 300);
+			assert_eq!(pool_delegation(0, BOB), 300);
+
+			// The slash is shared by the members through the value of their points.
+			assert_eq!(Currencies::slash_reserved(CurrencyId::Native, &pool_account, 100), 0);
+			assert_ok!(ResolversNetwork::settle_pool(Origin::signed(CHARLIE), 0));
+			assert_eq!(
+				last_event(),
+				Event::ResolversNetwork(crate::Event::PoolSlashed { pool_id: 0, amount: 100 })
+			);
+			assert_eq!(ResolversNetwork::pool_member_stake(0, &dave), 250);
+			assert_eq!(ResolversNetwork::pool_member_stake(0, &eve), 250);
+
+			// The delegation to a resigned resolver is refunded by the unwinding.
+			assert_ok!(ResolversNetwork::resign(Origin::signed(ALICE)));
+			assert_ok!(ResolversNetwork::leave_pool(Origin::signed(dave), 0, 300));
+			assert_eq!(pool_delegation(0, BOB), 50);
+			assert_noop!(
+				ResolversNetwork::leave_pool(Origin::signed(eve), 0, 300),
+				Error::<Runtime>::InsufficientPoolLiquidity,
+			);
+
+			// The released funds are delegated again when the pool is settled.
+			Timestamp::set_timestamp(INIT_TIMESTAMP + UNDELEGATE_TIME);
+			assert_ok!(ResolversNetwork::release_pending_funds(
+				Origin::signed(CHARLIE),
+				pool_account
+			));
+			assert_ok!(ResolversNetwork::settle_pool(Origin::signed(CHARLIE), 0));
+			assert_eq!(pool_delegation(0, BOB), 350);
+			assert_ok!(ResolversNetwork::leave_pool(Origin::signed(eve), 0, 300));
+
+			assert_ok!(ResolversNetwork::withdraw_unbonded(Origin::signed(dave), 0));
+			assert_eq!(Currencies::free_balance(CurrencyId::Native, &dave), 950);
+		});
+}
+
+#[test]
+fn network_totals_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+
+		assert_ok!(Identities::create_identity(
+			Origin::signed(ALICE),
+			"Alice".into(),
+			IdentityType::Individual,
+			[].into(),
+		));
+		assert_ok!(ResolversNetwork::join_resolvers_network(
+			Origin::signed(ALICE),
+			"".into(),
+			500,
+			[0; 32]
+		));
+		assert_eq!(ResolversNetwork::network_stake(), 500);
+		assert_eq!(ResolversNetwork::active_resolvers_count(), 0);
+
+		// The resolver is activated once its total stake reaches the activation amount.
+		assert_ok!(ResolversNetwork::delegate(Origin::signed(BOB), ALICE, 500));
+		assert_eq!(ResolversNetwork::network_stake(), 1000);
+		assert_eq!(ResolversNetwork::active_resolvers_count(), 1);
+
+		assert_ok!(ResolversNetwork::undelegate(Origin::signed(BOB), ALICE, 100));
+		assert_eq!(ResolversNetwork::network_stake(), 900);
+		assert_eq!(ResolversNetwork::active_resolvers_count(), 0);
+
+		// The stakes of a resigned resolver are no longer staked in the network.
+		assert_ok!(ResolversNetwork::resign(Origin::signed(ALICE)));
+		assert_eq!(ResolversNetwork::network_stake(), 0);
+		assert_ok!(ResolversNetwork::do_try_state());
+	});
+}
+
+#[test]
+fn try_state_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+
+		assert_ok!(Identities::create_identity(
+			Origin::signed(ALICE),
+			"Alice".into(),
+			IdentityType::Individual,
+			[].into(),
+		));
+		assert_ok!(ResolversNetwork::join_resolvers_network(
+			Origin::signed(ALICE),
+			"".into(),
+			500,
+			[0; 32]
+		));
+		assert_ok!(ResolversNetwork::delegate(Origin::signed(BOB), ALICE, 200));
+		assert_ok!(ResolversNetwork::do_try_state());
+
+		// The total stake must be the self stake and the delegations.
+		<Resolvers<Runtime>>::mutate(ALICE, |resolver| {
+			resolver.as_mut().unwrap().total_stake += 1;
+		});
+		assert_eq!(
+			ResolversNetwork::do_try_state(),
+			Err("The total stake of a resolver does not match its stakes")
+		);
+		<Resolvers<Runtime>>::mutate(ALICE, |resolver| {
+			resolver.as_mut().unwrap().total_stake -= 1;
+		});
+
+		// The stake of the network must be the sum of the total stakes.
+		<NetworkStake<Runtime>>::put(699);
+		assert_eq!(
+			ResolversNetwork::do_try_state(),
+			Err("The stake of the network does not match the stakes of the resolvers")
+		);
+		<NetworkStake<Runtime>>::put(700);
+
+		// The active set must only contain the active resolvers, and be counted.
+		<ActiveResolvers<Runtime>>::put(vec![BOB]);
+		assert_eq!(
+			ResolversNetwork::do_try_state(),
+			Err("The count of the active resolvers does not match the active set")
+		);
+		<ActiveResolversCount<Runtime>>::put(1);
+		assert_eq!(
+			ResolversNetwork::do_try_state(),
+			Err("An active resolver is not in the active status")
+		);
+		<ActiveResolvers<Runtime>>::kill();
+		<ActiveResolversCount<Runtime>>::kill();
+
+		// The pending funds must be covered by the reserved stakes.
+		assert_ok!(ResolversNetwork::resign(Origin::signed(ALICE)));
+		assert_ok!(ResolversNetwork::do_try_state());
+		<PendingFunds<Runtime>>::mutate(|pending_funds| {
+			pending_funds.push(PendingFund { owner: BOB, amount: 1, release_at: 0 })
+		});
+		assert_eq!(
+			ResolversNetwork::do_try_state(),
+			Err("The pending funds of an account exceed its reserved stakes")
+		);
+	});
+}
+
+#[test]
+fn migrate_to_v1_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		StorageVersion::new(0).put::<ResolversNetwork>();
+
+		migrations::v1::MigrateToV1::<Runtime>::on_runtime_upgrade();
+		assert_eq!(ResolversNetwork::on_chain_storage_version(), 1);
+	});
+}
+
+#[test]
+fn migrate_to_v2_works() {
+	ExtBuilder::default()
+		.resolvers(vec![(ALICE, "Alice".into(), MINIMUM_SELF_STAKE)])
+		.build()
+		.execute_with(|| {
+			System::set_block_number(1);
+			assert_ok!(ResolversNetwork::delegate(Origin::signed(BOB), ALICE, 200));
+			assert_ok!(ResolversNetwork::delegate(Origin::signed(CHARLIE), ALICE, 200));
+			assert_ok!(ResolversNetwork::undelegate(Origin::signed(CHARLIE), ALICE, 50));
+
+			StorageVersion::new(1).put::<ResolversNetwork>();
+			let _ = NamedReserves::<Runtime>::remove_all(None);
+
+			migrations::v2::MigrateToV2::<Runtime>::on_runtime_upgrade();
+			assert_eq!(ResolversNetwork::on_chain_storage_version(), 2);
+
+			let stake_of = |who| {
+				ResolversNetwork::named_reserves(who, (RESOLVER_STAKE_RESERVE, CurrencyId::Native))
+			};
+			assert_eq!(stake_of(ALICE), MINIMUM_SELF_STAKE);
+			assert_eq!(stake_of(BOB), 200);
+			// The delegation and the pending fund of Charlie.
+			assert_eq!(stake_of(CHARLIE), 200);
+		});
+}
+
+#[test]
+fn migrate_to_v3_works() {
+	ExtBuilder::default()
+		.resolvers(vec![(ALICE, "Alice".into(), ACTIVATION_STAKE_AMOUNT)])
+		.build()
+		.execute_with(|| {
+			System::set_block_number(1);
+			assert_ok!(ResolversNetwork::delegate(Origin::signed(BOB), ALICE, 200));
+
+			// The totals are not tracked with the version 2 layout.
+			<ActiveResolversCount<Runtime>>::kill();
+			<NetworkStake<Runtime>>::kill();
+			StorageVersion::new(2).put::<ResolversNetwork>();
+
+			migrations::v3::MigrateToV3::<Runtime>::on_runtime_upgrade();
+			assert_eq!(ResolversNetwork::on_chain_storage_version(), 3);
+			assert_eq!(
+				ResolversNetwork::on_chain_storage_version(),
+				ResolversNetwork::current_storage_version()
+			);
+
+			assert_eq!(ResolversNetwork::active_resolvers_count(), 1);
+			assert_eq!(ResolversNetwork::network_stake(), ACTIVATION_STAKE_AMOUNT + 200);
+			assert_ok!(ResolversNetwork::do_try_state());
+		});
+}
@@ -0,0 +1,222 @@
+//! Weights for pallet_resolvers.
+//!
+//! The weights are hand-estimated, no benchmark has been run for them yet. The database reads and
+//! writes are counted from the storage items each call accesses, and the base and per-item
+//! execution times are rough estimates. They are to be replaced with the output of the benchmarks
+//! in `benchmarking.rs` on the reference hardware before a release.
+
+use frame_support::{
+	sp_std::marker::PhantomData,
+	traits::Get,
+	weights::{constants::RocksDbWeight, Weight},
+};
+
+/// The weights of the calls of pallet_resolvers.
+pub trait WeightInfo {
+	fn join_resolvers_network() -> Weight;
+	fn delegate() -> Weight;
+	fn undelegate() -> Weight;
+	fn redelegate() -> Weight;
+	fn delegate_many(n: u32) -> Weight;
+	fn resign(d: u32) -> Weight;
+	fn release_pending_funds() -> Weight;
+	fn set_encryption_key() -> Weight;
+	fn set_expertise() -> Weight;
+	fn continue_resign(d: u32) -> Weight;
+	fn create_pool() -> Weight;
+	fn join_pool(t: u32) -> Weight;
+	fn leave_pool(t: u32) -> Weight;
+	fn withdraw_unbonded(t: u32) -> Weight;
+	fn settle_pool(t: u32) -> Weight;
+}
+
+/// The estimated weights for pallet_resolvers, priced with the database weight of the runtime.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	fn join_resolvers_network() -> Weight {
+		(97_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(7))
+			.saturating_add(T::DbWeight::get().writes(6))
+	}
+	fn delegate() -> Weight {
+		(310_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(4))
+			.saturating_add(T::DbWeight::get().writes(4))
+	}
+	fn undelegate() -> Weight {
+		(340_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(5))
+			.saturating_add(T::DbWeight::get().writes(4))
+	}
+	fn redelegate() -> Weight {
+		(560_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(4))
+			.saturating_add(T::DbWeight::get().writes(4))
+	}
+	fn delegate_many(n: u32) -> Weight {
+		(20_000_000 as Weight)
+			.saturating_add((42_000_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(T::DbWeight::get().reads(3))
+			.saturating_add(T::DbWeight::get().reads((2 as Weight).saturating_mul(n as Weight)))
+			.saturating_add(T::DbWeight::get().writes(3))
+			.saturating_add(T::DbWeight::get().writes((2 as Weight).saturating_mul(n as Weight)))
+	}
+	fn resign(d: u32) -> Weight {
+		(64_000_000 as Weight)
+			.saturating_add((13_900_000 as Weight).saturating_mul(d as Weight))
+			.saturating_add(T::DbWeight::get().reads(6))
+			.saturating_add(T::DbWeight::get().writes(5))
+	}
+	fn release_pending_funds() -> Weight {
+		(820_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(2))
+			.saturating_add(T::DbWeight::get().writes(2))
+	}
+	fn set_encryption_key() -> Weight {
+		(18_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	fn set_expertise() -> Weight {
+		(19_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	fn continue_resign(d: u32) -> Weight {
+		(26_000_000 as Weight)
+			.saturating_add((13_900_000 as Weight).saturating_mul(d as Weight))
+			.saturating_add(T::DbWeight::get().reads(3))
+			.saturating_add(T::DbWeight::get().writes(3))
+	}
+	fn create_pool() -> Weight {
+		(21_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1))
+			.saturating_add(T::DbWeight::get().writes(2))
+	}
+	fn join_pool(t: u32) -> Weight {
+		(96_000_000 as Weight)
+			.saturating_add((48_000_000 as Weight).saturating_mul(t as Weight))
+			.saturating_add(T::DbWeight::get().reads(5))
+			.saturating_add(T::DbWeight::get().reads((2 as Weight).saturating_mul(t as Weight)))
+			.saturating_add(T::DbWeight::get().writes(5))
+			.saturating_add(T::DbWeight::get().writes((2 as Weight).saturating_mul(t as Weight)))
+	}
+	fn leave_pool(t: u32) -> Weight {
+		(88_000_000 as Weight)
+			.saturating_add((52_000_000 as Weight).saturating_mul(t as Weight))
+			.saturating_add(T::DbWeight::get().reads(6))
+			.saturating_add(T::DbWeight::get().reads((3 as Weight).saturating_mul(t as Weight)))
+			.saturating_add(T::DbWeight::get().writes(5))
+			.saturating_add(T::DbWeight::get().writes((1 as Weight).saturating_mul(t as Weight)))
+	}
+	fn withdraw_unbonded(t: u32) -> Weight {
+		(74_000_000 as Weight)
+			.saturating_add((4_000_000 as Weight).saturating_mul(t as Weight))
+			.saturating_add(T::DbWeight::get().reads(7))
+			.saturating_add(T::DbWeight::get().reads((1 as Weight).saturating_mul(t as Weight)))
+			.saturating_add(T::DbWeight::get().writes(5))
+	}
+	fn settle_pool(t: u32) -> Weight {
+		(62_000_000 as Weight)
+			.saturating_add((48_000_000 as Weight).saturating_mul(t as Weight))
+			.saturating_add(T::DbWeight::get().reads(4))
+			.saturating_add(T::DbWeight::get().reads((2 as Weight).saturating_mul(t as Weight)))
+			.saturating_add(T::DbWeight::get().writes(4))
+			.saturating_add(T::DbWeight::get().writes((2 as Weight).saturating_mul(t as Weight)))
+	}
+}
+
+/// The estimated weights priced with the RocksDB weight, used by the tests.
+impl WeightInfo for () {
+	fn join_resolvers_network() -> Weight {
+		(97_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(7))
+			.saturating_add(RocksDbWeight::get().writes(6))
+	}
+	fn delegate() -> Weight {
+		(310_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(4))
+			.saturating_add(RocksDbWeight::get().writes(4))
+	}
+	fn undelegate() -> Weight {
+		(340_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(5))
+			.saturating_add(RocksDbWeight::get().writes(4))
+	}
+	fn redelegate() -> Weight {
+		(560_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(4))
+			.saturating_add(RocksDbWeight::get().writes(4))
+	}
+	fn delegate_many(n: u32) -> Weight {
+		(20_000_000 as Weight)
+			.saturating_add((42_000_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(RocksDbWeight::get().reads(3))
+			.saturating_add(RocksDbWeight::get().reads((2 as Weight).saturating_mul(n as Weight)))
+			.saturating_add(RocksDbWeight::get().writes(3))
+			.saturating_add(RocksDbWeight::get().writes((2 as Weight).saturating_mul(n as Weight)))
+	}
+	fn resign(d: u32) -> Weight {
+		(64_000_000 as Weight)
+			.saturating_add((13_900_000 as Weight).saturating_mul(d as Weight))
+			.saturating_add(RocksDbWeight::get().reads(6))
+			.saturating_add(RocksDbWeight::get().writes(5))
+	}
+	fn release_pending_funds() -> Weight {
+		(820_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(2))
+			.saturating_add(RocksDbWeight::get().writes(2))
+	}
+	fn set_encryption_key() -> Weight {
+		(18_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(1))
+			.saturating_add(RocksDbWeight::get().writes(1))
+	}
+	fn set_expertise() -> Weight {
+		(19_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(1))
+			.saturating_add(RocksDbWeight::get().writes(1))
+	}
+	fn continue_resign(d: u32) -> Weight {
+		(26_000_000 as Weight)
+			.saturating_add((13_900_000 as Weight).saturating_mul(d as Weight))
+			.saturating_add(RocksDbWeight::get().reads(3))
+			.saturating_add(RocksDbWeight::get().writes(3))
+	}
+	fn create_pool() -> Weight {
+		(21_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(1))
+			.saturating_add(RocksDbWeight::get().writes(2))
+	}
+	fn join_pool(t: u32) -> Weight {
+		(96_000_000 as Weight)
+			.saturating_add((48_000_000 as Weight).saturating_mul(t as Weight))
+			.saturating_add(RocksDbWeight::get().reads(5))
+			.saturating_add(RocksDbWeight::get().reads((2 as Weight).saturating_mul(t as Weight)))
+			.saturating_add(RocksDbWeight::get().writes(5))
+			.saturating_add(RocksDbWeight::get().writes((2 as Weight).saturating_mul(t as Weight)))
+	}
+	fn leave_pool(t: u32) -> Weight {
+		(88_000_000 as Weight)
+			.saturating_add((52_000_000 as Weight).saturating_mul(t as Weight))
+			.saturating_add(RocksDbWeight::get().reads(6))
+			.saturating_add(RocksDbWeight::get().reads((3 as Weight).saturating_mul(t as Weight)))
+			.saturating_add(RocksDbWeight::get().writes(5))
+			.saturating_add(RocksDbWeight::get().writes((1 as Weight).saturating_mul(t as Weight)))
+	}
+	fn withdraw_unbonded(t: u32) -> Weight {
+		(74_000_000 as Weight)
+			.saturating_add((4_000_000 as Weight).saturating_mul(t as Weight))
+			.saturating_add(RocksDbWeight::get().reads(7))
+			.saturating_add(RocksDbWeight::get().reads((1 as Weight).saturating_mul(t as Weight)))
+			.saturating_add(RocksDbWeight::get().writes(5))
+	}
+	fn settle_pool(t: u32) -> Weight {
+		(62_000_000 as Weight)
+			.saturating_add((48_000_000 as Weight).saturating_mul(t as Weight))
+			.saturating_add(RocksDbWeight::get().reads(4))
+			.saturating_add(RocksDbWeight::get().reads((2 as Weight).saturating_mul(t as Weight)))
+			.saturating_add(RocksDbWeight::get().writes(4))
+			.saturating_add(RocksDbWeight::get().writes((2 as Weight).saturating_mul(t as Weight)))
+	}
+}
@@ -35,9 +35,261 @@ pub type Index = u32;
 /// A hash of some data used by the chain.
 pub type Hash = sp_core::H256;
 
+/// The id of an asset issued outside of the chain, e.g. bridged USDC or the relay-chain DOT.
+pub type ForeignAssetId = u32;
+
+/// An ISO 639-1 language code, e.g. `*b"en"`.
+pub type LanguageCode = [u8; 2];
+
+/// An X25519 public key the private dispute evidence is encrypted to.
+pub type EncryptionKey = [u8; 32];
+
+/// The category of a payment, set by the payee. The common categories are named, the others are
+/// identified by their ISO 18245 merchant category code.
+#[derive(Encode, Decode, Eq, PartialEq, Copy, Clone, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub enum PaymentCategory {
+	PhysicalGoods,
+	DigitalGoods,
+	Services,
+	Travel,
+	Gambling,
+	/// An ISO 18245 merchant category code, e.g. `5812` for the restaurants.
+	MerchantCategoryCode(u16),
+}
+
+/// The merchant category codes of the wire transfers, the direct marketing, the quasi cash and the
+/// betting, which are prone to fraud and chargebacks.
+pub const HIGH_RISK_MERCHANT_CATEGORY_CODES: [u16; 4] = [4829, 5967, 6051, 7995];
+
+impl PaymentCategory {
+	/// Whether the payments of the category are prone to disputes, e.g. the digital goods cannot
+	/// be returned and the travels are paid long before the delivery.
+	pub fn is_high_risk(&self) -> bool {
+		match self {
+			PaymentCategory::DigitalGoods | PaymentCategory::Travel | PaymentCategory::Gambling =>
+				true,
+			PaymentCategory::MerchantCategoryCode(code) =>
+				HIGH_RISK_MERCHANT_CATEGORY_CODES.contains(code),
+			_ => false,
+		}
+	}
+}
+
+/// The identifier of a named reserve. Each pallet tracks the funds it reserves under its own
+/// identifiers and only unreserves, slashes or repatriates the funds it tracked, so the reserves of
+/// other pallets or other purposes of the same account are never consumed.
+pub type ReserveIdentifier = [u8; 8];
+
+/// The self stakes and the delegations of the resolvers network, including the pending funds.
+pub const RESOLVER_STAKE_RESERVE: ReserveIdentifier = *b"rslv/stk";
+/// The fees escrowed by the dispute parties to pay the resolvers.
+pub const DISPUTE_FEE_RESERVE: ReserveIdentifier = *b"dspt/fee";
+/// The bonds of the resolvers who accepted a dispute assignment.
+pub const RESOLVER_BOND_RESERVE: ReserveIdentifier = *b"dspt/bnd";
+/// The bonds of the parties who challenged a dispute resolution.
+pub const CHALLENGE_BOND_RESERVE: ReserveIdentifier = *b"dspt/chl";
+/// The fees escrowed by the reviewed identities to dispute a review.
+pub const REVIEW_DISPUTE_FEE_RESERVE: ReserveIdentifier = *b"dspt/rvw";
+/// The amounts of the LRP payments, reserved from the payers until the payments are settled.
+pub const PAYMENT_RESERVE: ReserveIdentifier = *b"lrp/pymt";
+/// The bonds of the identity evaluators.
+pub const EVALUATOR_BOND_RESERVE: ReserveIdentifier = *b"idty/bnd";
+/// The fees escrowed by the requestors of the verify data requests.
+pub const VERIFY_FEE_RESERVE: ReserveIdentifier = *b"idty/fee";
+/// The deposits of the registered identity handles.
+pub const HANDLE_DEPOSIT_RESERVE: ReserveIdentifier = *b"idty/hdl";
+/// The bonds of the currency listings.
+pub const LISTING_BOND_RESERVE: ReserveIdentifier = *b"curr/bnd";
+
+/// The outcome of a dispute over a payment.
+#[derive(Encode, Decode, Clone, PartialEq, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub enum Judgment {
+	ReleaseFundToPayer,
+	ReleaseFundToPayee,
+}
+
+/// The service level metrics of the dispute resolution. The medians are computed over the latest
+/// resolved disputes and are `None` if there is no such dispute.
+#[derive(Encode, Decode, Clone, Default, PartialEq, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct DisputeSlaMetrics<Moment> {
+	/// The number of resolved disputes.
+	pub resolved: u32,
+	/// The number of resolved disputes which were fought and evaluated by a panel.
+	pub fought: u32,
+	/// The number of assignments not accepted by the resolvers in time.
+	pub acceptance_timeouts: u32,
+	/// The number of accepted assignments not judged by the resolvers in time.
+	pub judging_timeouts: u32,
+	/// The median time from the creation of a dispute to the first panel drawn.
+	pub median_time_to_panel: Option<Moment>,
+	/// The median time from the creation of a dispute to the latest verdict of the panel.
+	pub median_time_to_verdict: Option<Moment>,
+	/// The median time from the creation of a dispute to the payout.
+	pub median_time_to_payout: Option<Moment>,
+}
+
+/// The fee schedule of a dispute, in the fee currency of the dispute resolution. Each fee is
+/// escrowed by the acting party, the losing party pays its escrow to the resolvers and the winner
+/// is refunded.
+#[derive(Encode, Decode, Clone, Default, PartialEq, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct DisputeCostEstimate<Balance> {
+	/// The amount of the disputed payment, in the currency of the payment.
+	pub payment_amount: Balance,
+	/// The fee escrowed by the payer to create the dispute.
+	pub create_fee: Balance,
+	/// The fee escrowed by the payee to fight the dispute.
+	pub fight_fee: Balance,
+	/// The fee escrowed by the escalating party in each escalation round. The other party escrows
+	/// the same amount to fight the escalation.
+	pub escalation_fees: Vec<Balance>,
+	/// The number of resolvers of the panel after the last round.
+	pub panel_size: u32,
+	/// The fee escrowed by each party through all the rounds.
+	pub total_per_party: Balance,
+}
+
+/// The funds received and sent by an account through the payments in a currency.
+#[derive(Encode, Decode, Clone, Default, PartialEq, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct PaymentFlows<Balance> {
+	/// The funds paid out to the account, as a payee, a refund recipient, a referrer, a fee
+	/// account or the beneficiary of a dispute.
+	pub inflow: Balance,
+	/// The funds escrowed from the account as a payer.
+	pub outflow: Balance,
+}
+
+/// The DID method of the identities, the DID of an account is the method followed by the hex of
+/// the encoded account id, e.g. `did:libra:0xd435...`.
+pub const DID_METHOD_PREFIX: &[u8] = b"did:libra:";
+
+/// A W3C DID document of an identity. The strings are UTF-8 bytes, the ids of the verification
+/// methods and the services are DID URLs relative to the document `id`.
+#[derive(Encode, Decode, Clone, Default, PartialEq, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct DidDocument {
+	/// The DID of the identity.
+	pub id: Vec<u8>,
+	/// The name of the identity.
+	pub name: Vec<u8>,
+	/// The DID of the organization identity the account is a member of.
+	pub controller: Option<Vec<u8>>,
+	/// The registered encryption keys, from the oldest to the current one.
+	pub verification_method: Vec<DidVerificationMethod>,
+	/// The id of the current encryption key, the others encrypt the data to the identity with.
+	pub key_agreement: Vec<Vec<u8>>,
+	/// The verified domains and emails of the identity.
+	pub service: Vec<DidService>,
+}
+
+#[derive(Encode, Decode, Clone, Default, PartialEq, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct DidVerificationMethod {
+	/// The DID URL of the key, e.g. `did:libra:0xd435...#key-1`.
+	pub id: Vec<u8>,
+	/// The type of the key, `X25519KeyAgreementKey2020` or `Sr25519VerificationKey2020`.
+	pub method_type: Vec<u8>,
+	/// The DID of the identity.
+	pub controller: Vec<u8>,
+	/// The multicodec public key in the base16 multibase encoding.
+	pub public_key_multibase: Vec<u8>,
+}
+
+#[derive(Encode, Decode, Clone, Default, PartialEq, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct DidService {
+	/// The DID URL of the service, e.g. `did:libra:0xd435...#domain-0`.
+	pub id: Vec<u8>,
+	/// The type of the service, `LinkedDomains` or `Email`.
+	pub service_type: Vec<u8>,
+	/// The endpoint, `https://` followed by the domain or `mailto:` followed by the email.
+	pub service_endpoint: Vec<u8>,
+}
+
+/// The filter of the evaluator directory, the suspended evaluators are never listed.
+#[derive(Encode, Decode, Clone, Default, PartialEq, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct EvaluatorFilter<Hash, Balance> {
+	/// The hash of the name of a data field, only the evaluators who publish a price for the
+	/// field are listed and the price is the published price. Without a field, the price is the
+	/// evaluator rate.
+	pub field: Option<Hash>,
+	/// The lowest price, inclusive.
+	pub min_price: Option<Balance>,
+	/// The highest price, inclusive.
+	pub max_price: Option<Balance>,
+}
+
+#[derive(Encode, Decode, Clone, Copy, Default, PartialEq, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct Pagination {
+	/// The number of matching items to skip.
+	pub offset: u32,
+	/// The maximum number of items of the page.
+	pub limit: u32,
+}
+
+/// An evaluator listed in the evaluator directory.
+#[derive(Encode, Decode, Clone, Default, PartialEq, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct EvaluatorListing<AccountId, Balance> {
+	pub account: AccountId,
+	pub name: Vec<u8>,
+	pub about: Vec<u8>,
+	/// The price of the filtered field, or the evaluator rate.
+	pub price: Balance,
+	/// The number of reviews of the completed verify data requests.
+	pub review_count: u32,
+	/// The sum of the stars of the reviews.
+	pub total_stars: u32,
+}
+
 #[derive(Encode, Decode, Eq, PartialEq, Copy, Clone, RuntimeDebug, PartialOrd, TypeInfo, Ord, MaxEncodedLen)]
 #[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
 pub enum CurrencyId<H> {
 	Native,
+	/// A currency created in the currencies registry, identified by the hash of its metadata.
 	Registered(H),
+	/// A well-known asset issued outside of the chain and minted by the bridge or the XCM
+	/// configuration of the runtime. It does not need to be registered in the currencies registry.
+	ForeignAsset(ForeignAssetId),
+}
+
+impl<H> CurrencyId<H> {
+	/// Whether the currency is the native token.
+	pub fn is_native(&self) -> bool {
+		matches!(self, CurrencyId::Native)
+	}
+
+	/// The hash of a registered currency, `None` for the other currencies.
+	pub fn registered_hash(&self) -> Option<&H> {
+		match self {
+			CurrencyId::Registered(hash) => Some(hash),
+			_ => None,
+		}
+	}
+
+	/// The id of a foreign asset, `None` for the other currencies.
+	pub fn foreign_asset_id(&self) -> Option<ForeignAssetId> {
+		match self {
+			CurrencyId::ForeignAsset(id) => Some(*id),
+			_ => None,
+		}
+	}
+}
+
+/// The prefix of the keys of the contents (receipts, delivery proofs, arguments, applications,
+/// reviews...) written to the off-chain indexing storage.
+pub const INDEXED_CONTENT_PREFIX: &[u8] = b"libra::indexed_content::";
+
+/// The off-chain indexing key of a content, which is the prefix followed by the encoded digest of
+/// the content.
+pub fn indexed_content_key<H: Encode>(digest: &H) -> Vec<u8> {
+	let mut key = INDEXED_CONTENT_PREFIX.to_vec();
+	digest.encode_to(&mut key);
+	key
 }
@@ -14,7 +14,7 @@ use sp_consensus_aura::sr25519::AuthorityId as AuraId;
 use sp_core::{crypto::KeyTypeId, OpaqueMetadata};
 use sp_runtime::{
 	create_runtime_str, generic, impl_opaque_keys,
-	traits::{AccountIdLookup, BlakeTwo256, Block as BlockT, NumberFor, Zero},
+	traits::{AccountIdConversion, AccountIdLookup, BlakeTwo256, Block as BlockT, NumberFor, Zero},
 	transaction_validity::{TransactionSource, TransactionValidity},
 	ApplyExtrinsicResult,
 };
@@ -36,8 +36,9 @@ pub use frame_support::{
 		constants::{BlockExecutionWeight, ExtrinsicBaseWeight, RocksDbWeight, WEIGHT_PER_SECOND},
 		IdentityFee, Weight,
 	},
-	RuntimeDebug, StorageValue,
+	PalletId, RuntimeDebug, StorageValue,
 };
+use frame_system::EnsureRoot;
 pub use pallet_balances::Call as BalancesCall;
 pub use pallet_identities;
 pub use pallet_lrp;
@@ -298,70 +299,219 @@ impl orml_currencies::Config for Runtime {
 }
 
 parameter_types! {
-	pub const BondingAmount: Balance = 100_000_000_000_000;
+	pub const CommunityListingBond: Balance = 100_000_000_000_000;
+	pub const VerifiedListingBond: Balance = 1_000_000_000_000_000;
+	pub const VerifiedListingFee: Balance = 100_000_000_000_000;
+	pub TreasuryAccount: AccountId = PalletId(*b"py/trsry").into_account();
 }
 
 impl currencies_registry::Config for Runtime {
 	type Event = Event;
 	type Currency = Currencies;
-	type BondingAmount = BondingAmount;
+	type CommunityListingBond = CommunityListingBond;
+	type VerifiedListingBond = VerifiedListingBond;
+	type VerifiedListingFee = VerifiedListingFee;
+	type TreasuryAccount = TreasuryAccount;
+	type WeightInfo = currencies_registry::weights::SubstrateWeight<Runtime>;
 }
 
 parameter_types! {
 	pub const PendingPaymentWaitingTime: Moment = 172800000;
 	pub const FullFilledPaymentWaitingTime: Moment = 2592000000;
+	pub const QueueBucketDuration: Moment = MILLISECS_PER_BLOCK;
+	pub const MaxPaymentsPerBucket: u32 = 1_000;
+	pub const QueueBatchSize: u32 = 256;
+	pub const PaymentIndexBucketDuration: Moment = 86400000; // 1 day
+	pub const RiskMatureAccountAge: Moment = 2592000000; // 30 days
+	pub const RiskHighAmount: Balance = 10_000_000_000_000_000;
+	pub const DailySpendingPeriod: Moment = 86400000; // 1 day;
+	pub const WeeklySpendingPeriod: Moment = 604800000; // 7 days;
+	pub const PayeeConfirmationThreshold: Balance = 1_000_000_000_000_000_000;
+	pub const PayeeConfirmationCooldown: Moment = 86400000; // 1 day;
+	pub const FulfillmentTime: Moment = 1209600000; // 14 days
+	pub const MaxDeliveryProofs: u32 = 20;
+	pub const MaxPolicyCurrencies: u32 = 16;
+	pub const MaxReferralFeeShare: Permill = Permill::from_percent(50);
+	pub const MaxPayoutRoutes: u32 = 8;
+	pub const LrpPalletId: PalletId = PalletId(*b"lb/lrpes");
+	pub const StatementEraDuration: BlockNumber = DAYS;
+	pub const InheritanceInactivityPeriod: Moment = 15552000000; // 180 days
+	pub const InheritanceChallengeWindow: Moment = 1209600000; // 14 days
+	pub const DefaultIdentityRequirement: pallet_lrp::IdentityRequirement<Balance> =
+		pallet_lrp::IdentityRequirement {
+			payer: false,
+			payee: false,
+			min_credibility: 0,
+			threshold: 0,
+		};
+	pub const VelocityPolicy: Option<pallet_lrp::VelocityPolicy<Moment>> =
+		Some(pallet_lrp::VelocityPolicy {
+			window: 86400000, // 1 day
+			fulfillments: 20,
+			disputes: 5,
+			hold: 1209600000, // 14 days
+		});
+}
+
+/// The fees of the completed payments, only the native currency is charged for now.
+pub struct PaymentFees;
+impl pallet_lrp::PaymentFee<CurrencyId<Hash>, Balance> for PaymentFees {
+	fn fee_schedule(currency_id: &CurrencyId<Hash>) -> Option<pallet_lrp::FeeSchedule<Balance>> {
+		match currency_id {
+			CurrencyId::Native => Some(pallet_lrp::FeeSchedule {
+				flat: 0,
+				percentage: Permill::from_perthousand(5),
+			}),
+			_ => None,
+		}
+	}
 }
 
 impl pallet_lrp::Config for Runtime {
 	type Event = Event;
 	type Currency = Currencies;
 	type CurrenciesManager = CurrenciesRegistry;
+	type IdentitiesManager = Identities;
+	type PaymentFee = PaymentFees;
+	type ComplianceCheck = ();
+	type TreasuryAccount = TreasuryAccount;
 	type PendingPaymentWaitingTime = PendingPaymentWaitingTime;
 	type FulfilledPaymentWaitingTime = FullFilledPaymentWaitingTime;
+	type QueueBucketDuration = QueueBucketDuration;
+	type MaxPaymentsPerBucket = MaxPaymentsPerBucket;
+	type QueueBatchSize = QueueBatchSize;
+	type PaymentIndexBucketDuration = PaymentIndexBucketDuration;
+	type DefaultIdentityRequirement = DefaultIdentityRequirement;
+	type RiskMatureAccountAge = RiskMatureAccountAge;
+	type RiskHighAmount = RiskHighAmount;
+	type DailySpendingPeriod = DailySpendingPeriod;
+	type WeeklySpendingPeriod = WeeklySpendingPeriod;
+	type PayeeConfirmationThreshold = PayeeConfirmationThreshold;
+	type PayeeConfirmationCooldown = PayeeConfirmationCooldown;
+	type FulfillmentTime = FulfillmentTime;
+	type MaxDeliveryProofs = MaxDeliveryProofs;
+	type MaxPolicyCurrencies = MaxPolicyCurrencies;
+	type MaxReferralFeeShare = MaxReferralFeeShare;
+	type MaxPayoutRoutes = MaxPayoutRoutes;
+	type PauseOrigin = EnsureRoot<AccountId>;
+	type PalletId = LrpPalletId;
+	type StatementEraDuration = StatementEraDuration;
+	type InheritanceInactivityPeriod = InheritanceInactivityPeriod;
+	type InheritanceChallengeWindow = InheritanceChallengeWindow;
+	type VelocityPolicy = VelocityPolicy;
 }
 
 parameter_types! {
 	pub const PenaltyTokenLockTime: Moment = 15778476000; // 6 months
 	pub const UndelegateTime: Moment = 2592000000; // 30 days
+	pub const CaseExposureTime: Moment = 2592000000; // 30 days
 	pub const MinimumSelfStake: Balance = 10_000_000_000_000_000;
 	pub const ActivationStakeAmount: Balance = 100_000_000_000_000_000;
 	pub const RequiredCredibility: Credibility = 40;
+	pub RequiredVerifiedFields: Vec<Vec<u8>> = Vec::new();
+	pub const MaxActiveResolvers: u32 = 1_000;
+	pub const EraDuration: BlockNumber = DAYS;
+	pub const UnwindBatchSize: u32 = 256;
+	pub const ReleaseBatchSize: u32 = 256;
+	pub const ConflictDelegationThreshold: Balance = 1_000_000_000_000_000;
+	pub const MaxExpertiseCategories: u32 = 16;
+	pub const ResolversPalletId: PalletId = PalletId(*b"lb/rslvr");
+	pub const MaxPoolTargets: u32 = 16;
+	pub const MaxPoolCommission: Permill = Permill::from_percent(20);
 }
 
 impl pallet_resolvers::Config for Runtime {
 	type Event = Event;
 	type Currency = Currencies;
+	type FeeCurrency = GetNativeCurrencyId;
 	type IdentitiesManager = Identities;
 	type Randomness = RandomnessCollectiveFlip;
+	type SelectionStrategy = pallet_resolvers::selection::UniformSelection;
 	type PenaltyTokenLockTime = PenaltyTokenLockTime;
 	type MinimumSelfStake = MinimumSelfStake;
 	type ActivationStakeAmount = ActivationStakeAmount;
 	type UndelegateTime = UndelegateTime;
+	type CaseExposureTime = CaseExposureTime;
 	type RequiredCredibility = RequiredCredibility;
+	type RequiredVerifiedFields = RequiredVerifiedFields;
+	type MaxActiveResolvers = MaxActiveResolvers;
+	type EraDuration = EraDuration;
+	type UnwindBatchSize = UnwindBatchSize;
+	type ReleaseBatchSize = ReleaseBatchSize;
+	type ConflictDelegationThreshold = ConflictDelegationThreshold;
+	type MaxExpertiseCategories = MaxExpertiseCategories;
+	type PalletId = ResolversPalletId;
+	type MaxPoolTargets = MaxPoolTargets;
+	type MaxPoolCommission = MaxPoolCommission;
+	type WeightInfo = pallet_resolvers::weights::SubstrateWeight<Runtime>;
 }
 
 parameter_types! {
 	pub const DisputeFinalizingTime: Moment = 604800000; // 7 days
 	pub const DisputeFee: Balance = 50_000_000_000_000;
+	pub const DisputeResolutionPalletId: PalletId = PalletId(*b"lb/dsptr");
+	pub const InsuranceFundShare: Permill = Permill::from_percent(10);
 	// Tolerance = CredibilityGain / CredibilityLoss
 	pub const CredibilityGain: Credibility = 1;
 	pub const CredibilityLoss: Credibility = 5;
+	pub const ResolverSelectionDelay: BlockNumber = HOURS;
+	pub const MaxResolverSelectionRetries: u32 = 10;
+	pub const AssignmentAcceptanceTime: Moment = 86400000; // 1 day
+	pub const AssignmentJudgingTime: Moment = 259200000; // 3 days
+	pub const ResolverBond: Balance = 10_000_000_000_000;
+	pub const ChallengeWindow: Moment = 604800000; // 7 days
+	pub const ChallengeBond: Balance = 250_000_000_000_000;
+	pub const MaxDisputesOwned: u32 = 1_000;
+	pub const MaxEscalationRounds: u32 = 3;
+	pub const MaxSlaSamples: u32 = 1_000;
+	pub const MaxPrecedentsPerCategory: u32 = 100;
+	pub const SlashCompensationShare: Permill = Permill::from_percent(50);
+	pub const ReviewDisputeFee: Balance = 5_000_000_000_000;
 }
 
 impl dispute_resolution::Config for Runtime {
 	type Event = Event;
 	type Currency = Currencies;
+	type FeeCurrency = GetNativeCurrencyId;
 	type PaymentProtocol = Lrp;
 	type ResolversNetwork = ResolversNetwork;
 	type IdentitiesManager = Identities;
+	type GovernanceOrigin = EnsureRoot<AccountId>;
+	type PauseOrigin = EnsureRoot<AccountId>;
+	type PalletId = DisputeResolutionPalletId;
+	type InsuranceFundShare = InsuranceFundShare;
 	type DisputeFinalizingTime = DisputeFinalizingTime;
 	type DisputeFee = DisputeFee;
 	type CredibilityGain = CredibilityGain;
 	type CredibilityLoss = CredibilityLoss;
+	type ResolverSelectionDelay = ResolverSelectionDelay;
+	type MaxResolverSelectionRetries = MaxResolverSelectionRetries;
+	type AssignmentAcceptanceTime = AssignmentAcceptanceTime;
+	type AssignmentJudgingTime = AssignmentJudgingTime;
+	type ResolverBond = ResolverBond;
+	type ChallengeWindow = ChallengeWindow;
+	type ChallengeBond = ChallengeBond;
+	type MaxDisputesOwned = MaxDisputesOwned;
+	type MaxEscalationRounds = MaxEscalationRounds;
+	type MaxSlaSamples = MaxSlaSamples;
+	type MaxPrecedentsPerCategory = MaxPrecedentsPerCategory;
+	type SlashCompensationShare = SlashCompensationShare;
+	type ReviewDisputeFee = ReviewDisputeFee;
+	type WeightInfo = dispute_resolution::weights::SubstrateWeight<Runtime>;
 }
 
 parameter_types! {
 	pub const EvaluatorBonding: Balance = 50_000_000_000_000;
+	pub const VerifyRequestTimeout: BlockNumber = 3 * DAYS;
+	pub const VerifyRequestPenalty: Balance = 1_000_000_000_000;
+	pub const MaxFieldPrices: u32 = 50;
+	pub const EvaluatorRateBand: Balance = 1_000_000_000_000;
+	pub const MaxLanguages: u32 = 8;
+	pub const MaxEncryptionKeys: u32 = 4;
+	pub const MaxAttestations: u32 = 16;
+	pub const HandleDeposit: Balance = 10_000_000_000_000;
+	pub const HandlePeriod: BlockNumber = 365 * DAYS;
+	pub const MaxHandleLength: u32 = 32;
 	pub const InitialCredibility: Credibility = 60;
 	pub const MaxCredibility: Credibility = 100;
 }
@@ -370,8 +520,21 @@ impl pallet_identities::Config for Runtime {
 	type Event = Event;
 	type Currency = Currencies;
 	type EvaluatorBonding = EvaluatorBonding;
+	type GovernanceOrigin = EnsureRoot<AccountId>;
+	type Resolvers = ResolversNetwork;
+	type VerifyRequestTimeout = VerifyRequestTimeout;
+	type VerifyRequestPenalty = VerifyRequestPenalty;
+	type MaxFieldPrices = MaxFieldPrices;
+	type EvaluatorRateBand = EvaluatorRateBand;
+	type MaxLanguages = MaxLanguages;
+	type MaxEncryptionKeys = MaxEncryptionKeys;
+	type MaxAttestations = MaxAttestations;
+	type HandleDeposit = HandleDeposit;
+	type HandlePeriod = HandlePeriod;
+	type MaxHandleLength = MaxHandleLength;
 	type InitialCredibility = InitialCredibility;
 	type MaxCredibility = MaxCredibility;
+	type WeightInfo = pallet_identities::weights::SubstrateWeight<Runtime>;
 }
 
 // Create the runtime by composing the FRAME pallets that were previously configured.
@@ -381,22 +544,24 @@ construct_runtime!(
 		NodeBlock = opaque::Block,
 		UncheckedExtrinsic = UncheckedExtrinsic
 	{
-		System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
-		RandomnessCollectiveFlip: pallet_randomness_collective_flip::{Pallet, Storage},
-		Timestamp: pallet_timestamp::{Pallet, Call, Storage, Inherent},
-		Aura: pallet_aura::{Pallet, Config<T>},
-		Grandpa: pallet_grandpa::{Pallet, Call, Storage, Config, Event},
-		Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
-		TransactionPayment: pallet_transaction_payment::{Pallet, Storage},
-		Sudo: pallet_sudo::{Pallet, Call, Config<T>, Storage, Event<T>},
-
-		Tokens: orml_tokens::{Pallet, Storage, Event<T>, Config<T>},
-		Currencies: orml_currencies::{Pallet, Call, Event<T>},
-		CurrenciesRegistry: currencies_registry::{Pallet, Call, Storage, Event<T>},
-		Lrp: pallet_lrp::{Pallet, Call, Storage, Event<T>},
-		ResolversNetwork: pallet_resolvers::{Pallet, Call, Storage, Event<T>},
-		DisputeResolution: dispute_resolution::{Pallet, Call, Storage, Event<T>},
-		Identities: pallet_identities::{Pallet, Call, Storage, Event<T>},
+		// The indices are part of the encoding of the calls and events, a new pallet takes the
+		// next index and the index of a removed pallet is never reused.
+		System: frame_system::{Pallet, Call, Config, Storage, Event<T>} = 0,
+		RandomnessCollectiveFlip: pallet_randomness_collective_flip::{Pallet, Storage} = 1,
+		Timestamp: pallet_timestamp::{Pallet, Call, Storage, Inherent} = 2,
+		Aura: pallet_aura::{Pallet, Config<T>} = 3,
+		Grandpa: pallet_grandpa::{Pallet, Call, Storage, Config, Event} = 4,
+		Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>} = 5,
+		TransactionPayment: pallet_transaction_payment::{Pallet, Storage} = 6,
+		Sudo: pallet_sudo::{Pallet, Call, Config<T>, Storage, Event<T>} = 7,
+
+		Tokens: orml_tokens::{Pallet, Storage, Event<T>, Config<T>} = 8,
+		Currencies: orml_currencies::{Pallet, Call, Event<T>} = 9,
+		CurrenciesRegistry: currencies_registry::{Pallet, Call, Storage, Config<T>, Event<T>} = 10,
+		Lrp: pallet_lrp::{Pallet, Call, Storage, Event<T>} = 11,
+		ResolversNetwork: pallet_resolvers::{Pallet, Call, Storage, Config<T>, Event<T>} = 12,
+		DisputeResolution: dispute_resolution::{Pallet, Call, Storage, Event<T>} = 13,
+		Identities: pallet_identities::{Pallet, Call, Storage, Config<T>, Event<T>} = 14,
 	}
 );
 
@@ -425,8 +590,34 @@ pub type Executive = frame_executive::Executive<
 	frame_system::ChainContext<Runtime>,
 	Runtime,
 	AllPalletsWithSystem,
+	Migrations,
 >;
 
+/// The storage migrations run on the runtime upgrade, in order.
+pub type Migrations = (
+	pallet_lrp::migrations::v1::MigrateToV1<Runtime>,
+	pallet_lrp::migrations::v2::MigrateToV2<Runtime>,
+	pallet_lrp::migrations::v3::MigrateToV3<Runtime>,
+	pallet_lrp::migrations::v4::MigrateToV4<Runtime>,
+	pallet_lrp::migrations::v5::MigrateToV5<Runtime>,
+	pallet_lrp::migrations::v6::MigrateToV6<Runtime>,
+	dispute_resolution::migrations::v1::MigrateToV1<Runtime>,
+	dispute_resolution::migrations::v2::MigrateToV2<Runtime>,
+	pallet_resolvers::migrations::v1::MigrateToV1<Runtime>,
+	pallet_resolvers::migrations::v2::MigrateToV2<Runtime>,
+	pallet_resolvers::migrations::v3::MigrateToV3<Runtime>,
+	pallet_identities::migrations::v1::MigrateToV1<Runtime>,
+	pallet_identities::migrations::v2::MigrateToV2<Runtime>,
+	pallet_identities::migrations::v3::MigrateToV3<Runtime>,
+	pallet_identities::migrations::v4::MigrateToV4<Runtime>,
+	pallet_identities::migrations::v5::MigrateToV5<Runtime>,
+	pallet_identities::migrations::v6::MigrateToV6<Runtime>,
+	pallet_identities::migrations::v7::MigrateToV7<Runtime>,
+	currencies_registry::migrations::v1::MigrateToV1<Runtime>,
+	currencies_registry::migrations::v2::MigrateToV2<Runtime>,
+	currencies_registry::migrations::v3::MigrateToV3<Runtime>,
+);
+
 impl_runtime_apis! {
 	impl sp_api::Core<Block> for Runtime {
 		fn version() -> RuntimeVersion {
@@ -558,6 +749,58 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl lrp_runtime_api::LrpApi<Block, AccountId, Balance, Hash, BlockNumber> for Runtime {
+		fn account_statement(
+			who: AccountId,
+			currency_id: CurrencyId<Hash>,
+			from: BlockNumber,
+			to: BlockNumber,
+		) -> lrp_runtime_api::PaymentFlows<Balance> {
+			Lrp::account_statement(who, currency_id, from, to)
+		}
+	}
+
+	impl dispute_resolution_runtime_api::DisputeResolutionApi<Block, Moment, Balance, Hash> for Runtime {
+		fn sla_metrics() -> dispute_resolution_runtime_api::DisputeSlaMetrics<Moment> {
+			DisputeResolution::sla_metrics()
+		}
+
+		fn estimate_dispute_cost(
+			payment_hash: Hash,
+			rounds: u32,
+		) -> Option<dispute_resolution_runtime_api::DisputeCostEstimate<Balance>> {
+			DisputeResolution::estimate_dispute_cost(payment_hash, rounds)
+		}
+	}
+
+	impl identities_runtime_api::IdentitiesApi<Block, AccountId, Balance, Hash> for Runtime {
+		fn did_document(who: AccountId) -> Option<identities_runtime_api::DidDocument> {
+			Identities::did_document(&who)
+		}
+
+		fn list_evaluators(
+			filter: identities_runtime_api::EvaluatorFilter<Hash, Balance>,
+			pagination: identities_runtime_api::Pagination,
+		) -> Vec<identities_runtime_api::EvaluatorListing<AccountId, Balance>> {
+			Identities::list_evaluators(filter, pagination)
+		}
+	}
+
+	#[cfg(feature = "try-runtime")]
+	impl frame_try_runtime::TryRuntime<Block> for Runtime {
+		fn on_runtime_upgrade() -> (Weight, Weight) {
+			// NOTE: intentional unwrap: we don't want to propagate the error backwards, and want to
+			// have a backtrace here. If any of the pre/post migration checks fail, we shall stop
+			// right here and right now.
+			let weight = Executive::try_runtime_upgrade().unwrap();
+			(weight, BlockWeights::get().max_block)
+		}
+
+		fn execute_block_no_check(block: Block) -> Weight {
+			Executive::execute_block_no_check(block)
+		}
+	}
+
 	#[cfg(feature = "runtime-benchmarks")]
 	impl frame_benchmarking::Benchmark<Block> for Runtime {
 		fn benchmark_metadata(extra: bool) -> (
@@ -575,6 +818,10 @@ impl_runtime_apis! {
 			list_benchmark!(list, extra, frame_system, SystemBench::<Runtime>);
 			list_benchmark!(list, extra, pallet_balances, Balances);
 			list_benchmark!(list, extra, pallet_timestamp, Timestamp);
+			list_benchmark!(list, extra, currencies_registry, CurrenciesRegistry);
+			list_benchmark!(list, extra, pallet_identities, Identities);
+			list_benchmark!(list, extra, pallet_resolvers, ResolversNetwork);
+			list_benchmark!(list, extra, dispute_resolution, DisputeResolution);
 
 			let storage_info = AllPalletsWithSystem::storage_info();
 
@@ -612,6 +859,10 @@ impl_runtime_apis! {
 			add_benchmark!(params, batches, frame_system, SystemBench::<Runtime>);
 			add_benchmark!(params, batches, pallet_balances, Balances);
 			add_benchmark!(params, batches, pallet_timestamp, Timestamp);
+			add_benchmark!(params, batches, currencies_registry, CurrenciesRegistry);
+			add_benchmark!(params, batches, pallet_identities, Identities);
+			add_benchmark!(params, batches, pallet_resolvers, ResolversNetwork);
+			add_benchmark!(params, batches, dispute_resolution, DisputeResolution);
 
 			Ok(batches)
 		}